@@ -6,8 +6,8 @@ static ALLOC: reth_cli_util::allocator::Allocator = reth_cli_util::allocator::ne
 use clap::Parser;
 use reth::{args::RessArgs, cli::Cli, ress::install_ress_subprotocol};
 use reth_ethereum_cli::chainspec::EthereumChainSpecParser;
-use reth_node_builder::NodeHandle;
-use reth_node_ethereum::EthereumNode;
+use reth_node_builder::{components::BasicPayloadServiceBuilder, NodeHandle};
+use reth_node_ethereum::{EthereumAddOns, EthereumConditionalPayloadBuilder, EthereumNode};
 use tracing::info;
 
 fn main() {
@@ -21,8 +21,16 @@ fn main() {
     if let Err(err) =
         Cli::<EthereumChainSpecParser, RessArgs>::parse().run(async move |builder, ress_args| {
             info!(target: "reth::cli", "Launching node");
-            let NodeHandle { node, node_exit_future } =
-                builder.node(EthereumNode::default()).launch_with_debug_capabilities().await?;
+            let NodeHandle { node, node_exit_future } = builder
+                .with_types::<EthereumNode>()
+                // skip conditional transactions (`--rpc.enable-tx-conditional`) whose conditions
+                // aren't met
+                .with_components(EthereumNode::components().payload(
+                    BasicPayloadServiceBuilder::new(EthereumConditionalPayloadBuilder::default()),
+                ))
+                .with_add_ons(EthereumAddOns::default())
+                .launch_with_debug_capabilities()
+                .await?;
 
             // Install ress subprotocol.
             if ress_args.enabled {
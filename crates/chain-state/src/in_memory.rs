@@ -6,18 +6,22 @@ use crate::{
 };
 use alloy_consensus::{transaction::TransactionMeta, BlockHeader};
 use alloy_eips::{BlockHashOrNumber, BlockNumHash};
-use alloy_primitives::{map::HashMap, BlockNumber, TxHash, B256};
+use alloy_primitives::{map::HashMap, Address, BlockNumber, TxHash, B256, U256};
 use parking_lot::RwLock;
 use reth_chainspec::ChainInfo;
 use reth_ethereum_primitives::EthPrimitives;
 use reth_execution_types::{Chain, ExecutionOutcome};
 use reth_metrics::{metrics::Gauge, Metrics};
 use reth_primitives_traits::{
-    BlockBody as _, IndexedTx, NodePrimitives, RecoveredBlock, SealedBlock, SealedHeader,
-    SignedTransaction,
+    Account, BlockBody as _, InMemorySize, IndexedTx, NodePrimitives, RecoveredBlock, SealedBlock,
+    SealedHeader, SignedTransaction,
 };
 use reth_storage_api::StateProviderBox;
-use reth_trie::{updates::TrieUpdates, HashedPostState};
+use reth_trie::{
+    updates::{StorageTrieUpdates, TrieUpdates},
+    BranchNodeCompact, HashedPostState, HashedStorage, Nibbles,
+};
+use revm_database::{states::StorageSlot, BundleAccount};
 use std::{collections::BTreeMap, sync::Arc, time::Instant};
 use tokio::sync::{broadcast, watch};
 
@@ -778,6 +782,63 @@ impl<N: NodePrimitives> ExecutedBlock<N> {
     pub fn block_number(&self) -> BlockNumber {
         self.recovered_block.header().number()
     }
+
+    /// Returns an estimate of the memory held by this block in bytes.
+    ///
+    /// This accounts for the block with its senders, the receipts, the bundle state, the hashed
+    /// post state and the trie updates. Reverts and the overhead of the hash maps are not
+    /// included, so the actual memory usage is higher.
+    pub fn estimated_size(&self) -> usize {
+        let block = self.recovered_block.header().size() +
+            self.recovered_block.body().size() +
+            self.recovered_block.senders().len() * size_of::<Address>();
+
+        let receipts = self.execution_output.receipts.iter().map(|r| r.size()).sum::<usize>();
+
+        let bundle = self
+            .execution_output
+            .bundle
+            .state
+            .values()
+            .map(|account| {
+                size_of::<(Address, BundleAccount)>() +
+                    account.storage.len() * size_of::<(U256, StorageSlot)>()
+            })
+            .sum::<usize>();
+
+        let hashed_state = self.hashed_state.accounts.len() * size_of::<(B256, Option<Account>)>() +
+            self.hashed_state
+                .storages
+                .values()
+                .map(|storage| {
+                    size_of::<(B256, HashedStorage)>() +
+                        storage.storage.len() * size_of::<(B256, U256)>()
+                })
+                .sum::<usize>();
+
+        let trie_nodes = |nodes: &HashMap<Nibbles, BranchNodeCompact>| {
+            nodes
+                .values()
+                .map(|node| {
+                    size_of::<(Nibbles, BranchNodeCompact)>() +
+                        node.hashes.len() * size_of::<B256>()
+                })
+                .sum::<usize>()
+        };
+        let trie_updates = trie_nodes(&self.trie_updates.account_nodes) +
+            self.trie_updates.removed_nodes.len() * size_of::<Nibbles>() +
+            self.trie_updates
+                .storage_tries
+                .values()
+                .map(|storage| {
+                    size_of::<(B256, StorageTrieUpdates)>() +
+                        trie_nodes(&storage.storage_nodes) +
+                        storage.removed_nodes.len() * size_of::<Nibbles>()
+                })
+                .sum::<usize>();
+
+        block + receipts + bundle + hashed_state + trie_updates
+    }
 }
 
 /// Non-empty chain of blocks.
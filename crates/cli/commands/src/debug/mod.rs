@@ -0,0 +1,50 @@
+//! Node debugging tools
+
+use crate::{launcher::Launcher, node::NoArgs};
+use clap::{Parser, Subcommand};
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_cli_runner::CliContext;
+use std::{fmt, sync::Arc};
+
+pub mod replay_engine;
+
+/// `reth debug` command
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser, Ext: clap::Args + fmt::Debug = NoArgs> {
+    #[command(subcommand)]
+    command: Subcommands<C, Ext>,
+}
+
+/// `reth debug` subcommands
+#[derive(Debug, Subcommand)]
+pub enum Subcommands<C: ChainSpecParser, Ext: clap::Args + fmt::Debug> {
+    /// Launch a node that replays engine API messages recorded with `--debug.engine-record`
+    #[command(name = "replay-engine")]
+    ReplayEngine(Box<replay_engine::Command<C, Ext>>),
+}
+
+impl<C, Ext> Command<C, Ext>
+where
+    C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>,
+    Ext: clap::Args + fmt::Debug,
+{
+    /// Execute `debug` command
+    pub async fn execute<L>(self, ctx: CliContext, launcher: L) -> eyre::Result<()>
+    where
+        L: Launcher<C, Ext>,
+    {
+        match self.command {
+            Subcommands::ReplayEngine(command) => command.execute(ctx, launcher).await,
+        }
+    }
+}
+
+impl<C: ChainSpecParser, Ext: clap::Args + fmt::Debug> Command<C, Ext> {
+    /// Returns the underlying chain being used to run this command
+    pub fn chain_spec(&self) -> Option<&Arc<C::ChainSpec>> {
+        match &self.command {
+            Subcommands::ReplayEngine(command) => command.chain_spec(),
+        }
+    }
+}
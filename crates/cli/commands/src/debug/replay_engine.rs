@@ -0,0 +1,82 @@
+//! Command that replays recorded engine API messages into a node.
+
+use crate::{
+    launcher::Launcher,
+    node::{NoArgs, NodeCommand},
+};
+use clap::Parser;
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_cli_runner::CliContext;
+use std::{fmt, path::PathBuf, sync::Arc};
+
+/// `reth debug replay-engine` command
+///
+/// Launches the node and feeds it the engine API messages recorded with `--debug.engine-record`
+/// instead of following a consensus client. To reproduce the recorded run, the node should be
+/// started from a datadir at the same state as the recording node was when the recording started.
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser, Ext: clap::Args + fmt::Debug = NoArgs> {
+    /// The path to the directory with the recorded engine API messages.
+    #[arg(long, value_name = "PATH")]
+    pub recording: PathBuf,
+
+    /// Replay the messages as fast as the engine processes them instead of preserving the
+    /// original delays between them.
+    #[arg(long)]
+    pub fast: bool,
+
+    /// The node to replay the messages into
+    #[command(flatten)]
+    pub node: NodeCommand<C, Ext>,
+}
+
+impl<C, Ext> Command<C, Ext>
+where
+    C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>,
+    Ext: clap::Args + fmt::Debug,
+{
+    /// Execute `debug replay-engine` command
+    pub async fn execute<L>(self, ctx: CliContext, launcher: L) -> eyre::Result<()>
+    where
+        L: Launcher<C, Ext>,
+    {
+        let Self { recording, fast, mut node } = self;
+        if !recording.is_dir() {
+            eyre::bail!("engine API recording not found at {}", recording.display())
+        }
+
+        node.debug.engine_replay = Some(recording);
+        node.debug.engine_replay_fast = fast;
+        node.execute(ctx, launcher).await
+    }
+}
+
+impl<C: ChainSpecParser, Ext: clap::Args + fmt::Debug> Command<C, Ext> {
+    /// Returns the underlying chain being used to run this command
+    pub fn chain_spec(&self) -> Option<&Arc<C::ChainSpec>> {
+        self.node.chain_spec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_ethereum_cli::chainspec::EthereumChainSpecParser;
+
+    #[test]
+    fn parse_replay_engine_args() {
+        let cmd = Command::<EthereumChainSpecParser>::try_parse_from([
+            "reth",
+            "--recording",
+            "/tmp/engine",
+            "--fast",
+            "--chain",
+            "sepolia",
+        ])
+        .unwrap();
+        assert_eq!(cmd.recording, PathBuf::from("/tmp/engine"));
+        assert!(cmd.fast);
+        assert_eq!(cmd.chain_spec().unwrap().chain().id(), 11155111);
+    }
+}
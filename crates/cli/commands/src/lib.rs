@@ -11,6 +11,7 @@
 pub mod common;
 pub mod config_cmd;
 pub mod db;
+pub mod debug;
 pub mod download;
 pub mod dump_genesis;
 pub mod export_era;
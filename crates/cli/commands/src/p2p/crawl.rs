@@ -0,0 +1,354 @@
+//! Network crawler subcommand of P2P Debugging tool.
+
+use alloy_primitives::{hex, B256, U256};
+use clap::Parser;
+use futures::SinkExt;
+use humantime::parse_duration;
+use reth_chainspec::{EthChainSpec, ForkFilter, ForkId, Hardforks, Head};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_cli_util::load_secret_key::rng_secret_key;
+use reth_discv4::{DiscoveryUpdate, Discv4, Discv4Config};
+use reth_discv5::{Config as Discv5Config, Discv5};
+use reth_ecies::stream::ECIESStream;
+use reth_eth_wire::{
+    DisconnectReason, EthMessage, EthNetworkPrimitives, HelloMessage, ProtocolMessage,
+    UnauthedP2PStream, UnifiedStatus,
+};
+use reth_network_peers::{pk2id, NodeRecord, PeerId};
+use secp256k1::{SecretKey, SECP256K1};
+use serde::Serialize;
+use std::{
+    collections::{HashSet, VecDeque},
+    io::{self, Write},
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{net::TcpStream, select, task::JoinSet, time::timeout};
+use tokio_stream::StreamExt;
+use tracing::{debug, info};
+
+/// Interval of the random discv4 lookups that walk the DHT.
+const LOOKUP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Interval of the crawl progress logs.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Crawl the network and write a census of the reachable nodes.
+///
+/// Walks the discovery DHT, dials every discovered node and records the client version,
+/// capabilities, fork ID and best block of the node, one JSON object per line.
+#[derive(Parser, Debug)]
+pub struct Command<C: ChainSpecParser> {
+    /// The chain to crawl.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = C::help_message(),
+        default_value = C::default_value(),
+        value_parser = C::parser()
+    )]
+    chain: Arc<C::ChainSpec>,
+
+    /// Listen address of the discovery service.
+    #[arg(long, default_value = "0.0.0.0:30305")]
+    pub addr: SocketAddr,
+
+    /// Also discover nodes via discv5.
+    #[arg(long)]
+    pub v5: bool,
+
+    /// Maximum number of nodes that are dialed concurrently.
+    #[arg(long, default_value_t = 32)]
+    pub concurrency: usize,
+
+    /// Timeout of the connection and handshake with a node.
+    #[arg(long, value_parser = parse_duration, default_value = "10s")]
+    pub dial_timeout: Duration,
+
+    /// Stop discovering nodes after this duration, e.g. `10m`.
+    ///
+    /// Crawls until interrupted if not set.
+    #[arg(long, value_parser = parse_duration)]
+    pub duration: Option<Duration>,
+
+    /// Stop after this many nodes have been dialed.
+    #[arg(long)]
+    pub max_nodes: Option<usize>,
+
+    /// Write the census to this path instead of stdout.
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+impl<C: ChainSpecParser> Command<C> {
+    /// Returns the chain that is crawled.
+    pub const fn chain_spec(&self) -> &Arc<C::ChainSpec> {
+        &self.chain
+    }
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + Hardforks>> Command<C> {
+    /// Execute `p2p crawl` command.
+    pub async fn execute(self) -> eyre::Result<()> {
+        let chain = &self.chain;
+        let sk = rng_secret_key();
+        let boot_nodes = chain.bootnodes().unwrap_or_default();
+
+        // advertise the genesis, like a node that just started syncing
+        let genesis = chain.genesis();
+        let head = Head {
+            hash: chain.genesis_hash(),
+            number: 0,
+            timestamp: genesis.timestamp,
+            difficulty: genesis.difficulty,
+            total_difficulty: genesis.difficulty,
+        };
+        let status = UnifiedStatus::spec_builder(&**chain, &head);
+
+        // fork IDs of the nodes are checked against the forks that are active now, block based
+        // forks are assumed to have passed
+        let fork_filter =
+            chain.fork_filter(Head { number: u64::MAX, timestamp: unix_timestamp(), ..head });
+
+        let local_enr = NodeRecord::from_secret_key(self.addr, &sk);
+        let config = Discv4Config::builder()
+            .add_boot_nodes(boot_nodes.clone())
+            .lookup_interval(LOOKUP_INTERVAL)
+            .build();
+        let (_discv4, mut discv4_service) = Discv4::bind(self.addr, local_enr, sk, config).await?;
+        let mut discv4_updates = discv4_service.update_stream();
+        discv4_service.spawn();
+        info!(target: "reth::cli", chain = %chain.chain(), "Started discv4 crawl at {}", self.addr);
+
+        let mut discv5 = None;
+        if self.v5 {
+            let config =
+                Discv5Config::builder(self.addr).add_unsigned_boot_nodes(boot_nodes).build();
+            let (discv5_node, updates, _) = Discv5::start(&sk, config).await?;
+            info!(target: "reth::cli", "Started discv5 crawl");
+            discv5 = Some((discv5_node, updates));
+        }
+
+        let mut writer: Box<dyn Write> = match &self.output {
+            Some(path) => Box::new(io::BufWriter::new(reth_fs_util::create_file(path)?)),
+            None => Box::new(io::stdout()),
+        };
+
+        let deadline = async {
+            match self.duration {
+                Some(duration) => tokio::time::sleep(duration).await,
+                None => futures::future::pending().await,
+            }
+        };
+        tokio::pin!(deadline);
+        let mut progress = tokio::time::interval(PROGRESS_INTERVAL);
+
+        let mut seen = HashSet::<PeerId>::new();
+        let mut queue = VecDeque::new();
+        let mut dials = JoinSet::new();
+        let mut dialed = 0usize;
+        let mut reachable = 0usize;
+        let mut stopped = false;
+
+        loop {
+            stopped |= self.max_nodes.is_some_and(|max| dialed >= max);
+            while !stopped && dials.len() < self.concurrency {
+                let Some(node) = queue.pop_front() else { break };
+                dials.spawn(crawl_node(node, sk, status, fork_filter.clone(), self.dial_timeout));
+                dialed += 1;
+                stopped |= self.max_nodes.is_some_and(|max| dialed >= max);
+            }
+            if stopped && dials.is_empty() {
+                break
+            }
+
+            select! {
+                update = discv4_updates.next(), if !stopped => match update {
+                    Some(DiscoveryUpdate::Added(node) | DiscoveryUpdate::DiscoveredAtCapacity(node)) => {
+                        if seen.insert(node.id) {
+                            queue.push_back(node);
+                        }
+                    }
+                    Some(_) => {}
+                    None => eyre::bail!("discv4 update stream ended"),
+                },
+                event = async {
+                    match &mut discv5 {
+                        Some((_, updates)) => updates.recv().await,
+                        None => futures::future::pending().await,
+                    }
+                }, if !stopped => {
+                    let Some(event) = event else { eyre::bail!("discv5 update stream ended") };
+                    if let Some(peer) = discv5.as_ref().and_then(|(node, _)| node.on_discv5_update(event)) &&
+                        seen.insert(peer.node_record.id)
+                    {
+                        queue.push_back(peer.node_record);
+                    }
+                },
+                Some(census) = dials.join_next() => {
+                    let census = census?;
+                    if census.error.is_none() {
+                        reachable += 1;
+                    }
+                    debug!(target: "reth::cli", id = %census.id, client = ?census.client_version, error = ?census.error, "Crawled node");
+                    serde_json::to_writer(&mut writer, &census)?;
+                    writeln!(writer)?;
+                    writer.flush()?;
+                },
+                _ = progress.tick() => {
+                    info!(target: "reth::cli", discovered = seen.len(), dialed, reachable, queued = queue.len(), "Crawling");
+                },
+                _ = &mut deadline, if !stopped => {
+                    stopped = true;
+                },
+            }
+        }
+
+        info!(target: "reth::cli", discovered = seen.len(), dialed, reachable, "Finished crawl");
+        Ok(())
+    }
+}
+
+/// The census entry of a dialed node.
+#[derive(Debug, Serialize)]
+pub struct NodeCensus {
+    /// The ID of the node.
+    pub id: PeerId,
+    /// The IP address of the node.
+    pub ip: IpAddr,
+    /// The `RLPx` port of the node.
+    pub tcp_port: u16,
+    /// Unix timestamp of the dial.
+    pub timestamp: u64,
+    /// The client version of the node's `Hello` message.
+    pub client_version: Option<String>,
+    /// The capabilities of the node's `Hello` message.
+    pub capabilities: Vec<String>,
+    /// The node's `Status` message.
+    pub status: Option<NodeStatus>,
+    /// Why the dial failed, if it did.
+    pub error: Option<String>,
+}
+
+impl NodeCensus {
+    fn new(node: &NodeRecord) -> Self {
+        Self {
+            id: node.id,
+            ip: node.address,
+            tcp_port: node.tcp_port,
+            timestamp: unix_timestamp(),
+            client_version: None,
+            capabilities: Vec::new(),
+            status: None,
+            error: None,
+        }
+    }
+}
+
+/// The `eth` status of a node.
+#[derive(Debug, Serialize)]
+pub struct NodeStatus {
+    /// The negotiated `eth` version.
+    pub eth_version: u8,
+    /// The network ID of the node.
+    pub network_id: u64,
+    /// The genesis hash of the node's chain.
+    pub genesis: B256,
+    /// The fork hash of the node's fork ID.
+    pub fork_hash: String,
+    /// The next fork of the node's fork ID, zero if no fork is scheduled.
+    pub fork_next: u64,
+    /// Whether the fork ID is compatible with the forks of the crawled chain that are active now.
+    pub fork_compatible: bool,
+    /// Hash of the node's best block.
+    pub best_hash: B256,
+    /// Number of the node's best block, only announced since eth/69.
+    pub best_number: Option<u64>,
+    /// Total difficulty of the node's chain, only announced before eth/69.
+    pub total_difficulty: Option<U256>,
+}
+
+impl NodeStatus {
+    fn new(status: UnifiedStatus, fork_filter: &ForkFilter) -> Self {
+        let ForkId { hash, next } = status.forkid;
+        Self {
+            eth_version: status.version.into(),
+            network_id: status.chain.id(),
+            genesis: status.genesis,
+            fork_hash: hex::encode_prefixed(hash.0),
+            fork_next: next,
+            fork_compatible: fork_filter.validate(status.forkid).is_ok(),
+            best_hash: status.blockhash,
+            best_number: status.latest_block,
+            total_difficulty: status.total_difficulty,
+        }
+    }
+}
+
+/// Dials the node and returns its census entry.
+async fn crawl_node(
+    node: NodeRecord,
+    key: SecretKey,
+    status: UnifiedStatus,
+    fork_filter: ForkFilter,
+    dial_timeout: Duration,
+) -> NodeCensus {
+    let mut census = NodeCensus::new(&node);
+    match timeout(dial_timeout, handshake(&node, key, status, &fork_filter, &mut census)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => census.error = Some(err.to_string()),
+        Err(_) => census.error = Some("handshake timed out".to_string()),
+    }
+    census
+}
+
+/// Performs the `RLPx` and `eth` handshakes with the node and records the node's messages.
+///
+/// Unlike the regular `eth` handshake, the node's status is recorded even if it's on another chain
+/// or fork.
+async fn handshake(
+    node: &NodeRecord,
+    key: SecretKey,
+    mut status: UnifiedStatus,
+    fork_filter: &ForkFilter,
+    census: &mut NodeCensus,
+) -> eyre::Result<()> {
+    let outgoing = TcpStream::connect((node.address, node.tcp_port)).await?;
+    let ecies_stream = ECIESStream::connect(outgoing, key, node.id).await?;
+
+    let hello = HelloMessage::builder(pk2id(&key.public_key(SECP256K1))).build();
+    let (mut p2p_stream, their_hello) =
+        UnauthedP2PStream::new(ecies_stream).handshake(hello).await?;
+    census.client_version = Some(their_hello.client_version);
+    census.capabilities = their_hello.capabilities.iter().map(ToString::to_string).collect();
+
+    let version = p2p_stream.shared_capabilities().eth_version()?;
+    status.set_eth_version(version);
+    p2p_stream
+        .send(
+            alloy_rlp::encode(ProtocolMessage::<EthNetworkPrimitives>::from(EthMessage::Status(
+                status.into_message(),
+            )))
+            .into(),
+        )
+        .await?;
+
+    let their_msg = p2p_stream.next().await.ok_or_else(|| eyre::eyre!("no status response"))??;
+    let msg =
+        ProtocolMessage::<EthNetworkPrimitives>::decode_message(version, &mut &their_msg[..])?;
+    let EthMessage::Status(their_status) = msg.message else {
+        eyre::bail!("expected status message, got {:?}", msg.message_type)
+    };
+    census.status = Some(NodeStatus::new(UnifiedStatus::from_message(their_status), fork_filter));
+
+    let _ = p2p_stream.disconnect(DisconnectReason::ClientQuitting).await;
+    Ok(())
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
@@ -0,0 +1,103 @@
+//! DNS discovery subcommand of P2P Debugging tool.
+
+use clap::Parser;
+use reth_cli_util::get_secret_key;
+use reth_dns_discovery::{
+    publish::{parse_zone_records, DnsTree},
+    tree::{LinkEntry, NodeEntry, TreeRootEntry},
+};
+use secp256k1::SecretKey;
+use std::path::PathBuf;
+use tracing::info;
+
+/// Build a signed EIP-1459 DNS tree of node records.
+///
+/// Prints the tree as zone file, or the changes to a previously published zone file.
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The domain the tree is published at.
+    #[arg(long, value_name = "DOMAIN")]
+    pub domain: String,
+
+    /// Secret key to sign the tree with.
+    ///
+    /// If no key exists at the path, a new random key will be generated and stored there.
+    #[arg(long, value_name = "PATH")]
+    pub signing_key: PathBuf,
+
+    /// File with the node records to publish, one `enr:` record per line.
+    #[arg(long, value_name = "FILE")]
+    pub nodes: PathBuf,
+
+    /// Links to other trees to include, in the `enrtree://<key>@<domain>` format.
+    #[arg(long = "link", value_name = "LINK")]
+    pub links: Vec<LinkEntry>,
+
+    /// The sequence number of the tree.
+    ///
+    /// Defaults to the sequence number of the previous zone file plus one, or 1.
+    #[arg(long)]
+    pub seq: Option<u64>,
+
+    /// TTL of the records in the zone file, in seconds.
+    #[arg(long, default_value_t = 3600)]
+    pub ttl: u32,
+
+    /// A previously published zone file.
+    #[arg(long, value_name = "FILE")]
+    pub previous: Option<PathBuf>,
+
+    /// Print the changes to the previous zone file instead of the zone file.
+    #[arg(long, requires = "previous")]
+    pub diff: bool,
+
+    /// Write the zone file to this path instead of stdout.
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+impl Command {
+    /// Execute `p2p dns-tree` command.
+    pub fn execute(self) -> eyre::Result<()> {
+        let key = get_secret_key(&self.signing_key)?;
+
+        let enrs = reth_fs_util::read_to_string(&self.nodes)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                line.parse::<NodeEntry<SecretKey>>()
+                    .map(|entry| entry.enr)
+                    .map_err(|err| eyre::eyre!("invalid node record {line}: {err}"))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        let previous = self
+            .previous
+            .as_ref()
+            .map(|path| reth_fs_util::read_to_string(path).map(|zone| parse_zone_records(&zone)))
+            .transpose()?
+            .unwrap_or_default();
+        let domain = self.domain.trim_end_matches('.');
+        let previous_seq = previous
+            .get(domain)
+            .and_then(|root| root.parse::<TreeRootEntry>().ok())
+            .map(|root| root.sequence_number);
+        let seq = self.seq.or(previous_seq.map(|seq| seq + 1)).unwrap_or(1);
+
+        let tree = DnsTree::new(seq, enrs, self.links, &key);
+        info!(target: "reth::cli", link = %tree.link(domain), seq, "Built DNS tree");
+
+        if self.diff {
+            print!("{}", tree.diff(domain, &previous));
+            return Ok(())
+        }
+
+        let zone = tree.to_zone_file(domain, self.ttl);
+        match self.output {
+            Some(path) => reth_fs_util::write(path, zone)?,
+            None => print!("{zone}"),
+        }
+        Ok(())
+    }
+}
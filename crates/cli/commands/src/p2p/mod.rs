@@ -18,6 +18,8 @@ use reth_node_core::{
 };
 
 pub mod bootnode;
+pub mod crawl;
+pub mod dns;
 pub mod rlpx;
 
 /// `reth p2p` command
@@ -85,6 +87,12 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + Hardforks + EthereumHardforks>
             Subcommands::Bootnode(command) => {
                 command.execute().await?;
             }
+            Subcommands::DnsTree(command) => {
+                command.execute()?;
+            }
+            Subcommands::Crawl(command) => {
+                command.execute().await?;
+            }
         }
 
         Ok(())
@@ -99,6 +107,8 @@ impl<C: ChainSpecParser> Command<C> {
             Subcommands::Body { args, .. } => Some(&args.chain),
             Subcommands::Rlpx(_) => None,
             Subcommands::Bootnode(_) => None,
+            Subcommands::DnsTree(_) => None,
+            Subcommands::Crawl(command) => Some(command.chain_spec()),
         }
     }
 }
@@ -126,6 +136,10 @@ pub enum Subcommands<C: ChainSpecParser> {
     Rlpx(rlpx::Command),
     /// Bootnode command
     Bootnode(bootnode::Command),
+    /// Build a signed DNS discovery tree of node records
+    DnsTree(dns::Command),
+    /// Crawl the network and write a census of the reachable nodes
+    Crawl(crawl::Command<C>),
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -227,4 +241,21 @@ mod tests {
         let _args: Command<EthereumChainSpecParser> =
             Command::parse_from(["reth", "body", "--chain", "mainnet", "1000"]);
     }
+
+    #[test]
+    fn parse_crawl_cmd() {
+        let args: Command<EthereumChainSpecParser> = Command::parse_from([
+            "reth",
+            "crawl",
+            "--chain",
+            "sepolia",
+            "--concurrency",
+            "64",
+            "--duration",
+            "10m",
+        ]);
+        let Subcommands::Crawl(command) = args.command else { panic!("expected crawl command") };
+        assert_eq!(command.concurrency, 64);
+        assert_eq!(command.duration, Some(std::time::Duration::from_secs(600)));
+    }
 }
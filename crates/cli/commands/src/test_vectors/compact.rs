@@ -18,8 +18,8 @@ use reth_codecs::alloy::{
 };
 use reth_db::{
     models::{
-        AccountBeforeTx, StaticFileBlockWithdrawals, StoredBlockBodyIndices, StoredBlockOmmers,
-        StoredBlockWithdrawals,
+        AccountBeforeTx, StaticFileBlockWithdrawals, StoredBlobSidecar, StoredBlockBodyIndices,
+        StoredBlockOmmers, StoredBlockWithdrawals,
     },
     ClientVersion,
 };
@@ -112,6 +112,7 @@ compact_types!(
         StoredBlockBodyIndices,
         StoredBlockWithdrawals,
         StaticFileBlockWithdrawals,
+        StoredBlobSidecar,
         // Manual implementations
         TransactionSigned,
         // Bytecode, // todo revm arbitrary
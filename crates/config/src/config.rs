@@ -469,6 +469,7 @@ impl PruneConfig {
                     account_history,
                     storage_history,
                     bodies_history,
+                    blob_sidecars,
                     merkle_changesets,
                     receipts_log_filter: (),
                 },
@@ -486,6 +487,7 @@ impl PruneConfig {
         self.segments.account_history = self.segments.account_history.or(account_history);
         self.segments.storage_history = self.segments.storage_history.or(storage_history);
         self.segments.bodies_history = self.segments.bodies_history.or(bodies_history);
+        self.segments.blob_sidecars = self.segments.blob_sidecars.or(blob_sidecars);
         // Merkle changesets is not optional, so we just replace it if provided
         self.segments.merkle_changesets = merkle_changesets;
     }
@@ -1004,6 +1006,7 @@ receipts = 'full'
                 account_history: None,
                 storage_history: Some(PruneMode::Before(5000)),
                 bodies_history: None,
+                blob_sidecars: None,
                 merkle_changesets: PruneMode::Before(0),
                 #[expect(deprecated)]
                 receipts_log_filter: (),
@@ -1019,6 +1022,7 @@ receipts = 'full'
                 account_history: Some(PruneMode::Distance(2000)),
                 storage_history: Some(PruneMode::Distance(3000)),
                 bodies_history: None,
+                blob_sidecars: Some(PruneMode::Distance(3200)),
                 merkle_changesets: PruneMode::Distance(10000),
                 #[expect(deprecated)]
                 receipts_log_filter: (),
@@ -1035,6 +1039,7 @@ receipts = 'full'
         assert_eq!(config1.segments.receipts, Some(PruneMode::Distance(1000)));
         assert_eq!(config1.segments.account_history, Some(PruneMode::Distance(2000)));
         assert_eq!(config1.segments.storage_history, Some(PruneMode::Before(5000)));
+        assert_eq!(config1.segments.blob_sidecars, Some(PruneMode::Distance(3200)));
         assert_eq!(config1.segments.merkle_changesets, PruneMode::Distance(10000));
     }
 
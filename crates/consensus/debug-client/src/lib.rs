@@ -1,8 +1,8 @@
 //! Debug consensus client.
 //!
 //! This is a worker that sends FCUs and new payloads by fetching recent blocks from an external
-//! provider like Etherscan or an RPC endpoint. This allows to quickly test the execution client
-//! without running a consensus node.
+//! provider like Etherscan, an RPC endpoint or local block files. This allows to quickly test the
+//! execution client without running a consensus node.
 
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
@@ -16,4 +16,4 @@ mod client;
 mod providers;
 
 pub use client::{BlockProvider, DebugConsensusClient};
-pub use providers::{EtherscanBlockProvider, RpcBlockProvider};
+pub use providers::{EtherscanBlockProvider, FileBlockProvider, RpcBlockProvider};
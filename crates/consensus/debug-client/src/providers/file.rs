@@ -0,0 +1,349 @@
+use crate::BlockProvider;
+use alloy_consensus::BlockHeader;
+use alloy_primitives::B256;
+use reth_primitives_traits::Block;
+use reth_tracing::tracing::{debug, error, info};
+use ringbuffer::{AllocRingBuffer, RingBuffer};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::mpsc::Sender;
+
+/// Block provider that reads blocks from an RLP encoded chain file, i.e. concatenated RLP encoded
+/// blocks as accepted by `reth import`.
+///
+/// ERA1 archives are not supported: they only contain pre-merge blocks, which can't be submitted
+/// to the engine API. Consensus layer ERA archives are not supported either, since they store SSZ
+/// encoded beacon blocks.
+///
+/// The offsets of the blocks are indexed once when the provider is created. Blocks are read from
+/// the file one at a time when they are requested, the file is never loaded into memory as a
+/// whole. The blocks in the file must form a contiguous range.
+#[derive(derive_more::Debug, Clone)]
+pub struct FileBlockProvider<B> {
+    path: PathBuf,
+    first_block_number: u64,
+    /// Byte offsets of the blocks in the file, in file order.
+    #[debug(skip)]
+    offsets: Arc<Vec<u64>>,
+    interval: Duration,
+    #[debug(skip)]
+    _block: PhantomData<B>,
+}
+
+impl<B: Block> FileBlockProvider<B> {
+    /// Creates a new file block provider for the chain file at the given path.
+    ///
+    /// This scans the file once to index the offsets of all blocks, and decodes the first block.
+    /// The scan does blocking I/O, so this should be called on a blocking thread.
+    pub fn new(path: impl Into<PathBuf>) -> eyre::Result<Self> {
+        let path = path.into();
+        if path.extension().is_some_and(|ext| ext == "era" || ext == "era1") {
+            eyre::bail!(
+                "ERA archives can't be submitted to the engine API, use an RLP encoded chain file instead: {}",
+                path.display()
+            )
+        }
+
+        let offsets = ChainFile::<B>::open(&path)?.index()?;
+        let first_offset =
+            *offsets.first().ok_or_else(|| eyre::eyre!("no blocks found in {}", path.display()))?;
+        let first_block = ChainFile::<B>::open(&path)?.read_block_at(first_offset)?;
+        let first_block_number = first_block.header().number();
+
+        info!(
+            target: "consensus::debug-client",
+            path = %path.display(),
+            first = first_block_number,
+            blocks = offsets.len(),
+            "Opened block file",
+        );
+
+        Ok(Self {
+            path,
+            first_block_number,
+            offsets: Arc::new(offsets),
+            interval: Duration::ZERO,
+            _block: PhantomData,
+        })
+    }
+
+    /// Sets the delay between two consecutive blocks.
+    ///
+    /// By default blocks are sent as fast as the consensus client consumes them.
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+}
+
+impl<B: Block + 'static> FileBlockProvider<B> {
+    /// Reads the block at the given position in the file on a blocking thread and checks that it
+    /// has the expected number.
+    async fn read_block(&self, index: usize) -> eyre::Result<B> {
+        let number = self.first_block_number + index as u64;
+        let offset = *self
+            .offsets
+            .get(index)
+            .ok_or_else(|| eyre::eyre!("block {number} not found in {}", self.path.display()))?;
+
+        let path = self.path.clone();
+        let block =
+            tokio::task::spawn_blocking(move || ChainFile::<B>::open(&path)?.read_block_at(offset))
+                .await??;
+
+        if block.header().number() != number {
+            eyre::bail!(
+                "blocks in {} are not contiguous: expected block {number}, found block {}",
+                self.path.display(),
+                block.header().number()
+            )
+        }
+
+        Ok(block)
+    }
+}
+
+impl<B: Block + 'static> BlockProvider for FileBlockProvider<B> {
+    type Block = B;
+
+    async fn subscribe_blocks(&self, tx: Sender<Self::Block>) {
+        for index in 0..self.offsets.len() {
+            let block = match self.read_block(index).await {
+                Ok(block) => block,
+                Err(err) => {
+                    error!(target: "consensus::debug-client", %err, path = %self.path.display(), "Failed to read block from file");
+                    return
+                }
+            };
+
+            if tx.send(block).await.is_err() {
+                // Channel closed.
+                return
+            }
+
+            if !self.interval.is_zero() {
+                tokio::time::sleep(self.interval).await;
+            }
+        }
+
+        debug!(
+            target: "consensus::debug-client",
+            path = %self.path.display(),
+            "Sent all blocks from file",
+        );
+    }
+
+    async fn get_block(&self, block_number: u64) -> eyre::Result<Self::Block> {
+        let index = block_number.checked_sub(self.first_block_number).ok_or_else(|| {
+            eyre::eyre!("block {block_number} not found in {}", self.path.display())
+        })?;
+        self.read_block(index as usize).await
+    }
+
+    async fn get_or_fetch_previous_block(
+        &self,
+        previous_block_hashes: &AllocRingBuffer<B256>,
+        current_block_number: u64,
+        offset: usize,
+    ) -> eyre::Result<B256> {
+        let stored_hash = previous_block_hashes
+            .len()
+            .checked_sub(offset)
+            .and_then(|index| previous_block_hashes.get(index));
+        if let Some(hash) = stored_hash {
+            return Ok(*hash);
+        }
+
+        // Blocks before the start of the file are unknown, so leave the safe and finalized blocks
+        // unset until the file has advanced far enough.
+        match current_block_number.checked_sub(offset as u64) {
+            Some(number) if number >= self.first_block_number => {
+                let block = self.get_block(number).await?;
+                Ok(block.header().hash_slow())
+            }
+            _ => Ok(B256::ZERO),
+        }
+    }
+}
+
+/// Reader for the blocks of an RLP encoded chain file.
+#[derive(Debug)]
+struct ChainFile<B> {
+    reader: BufReader<File>,
+    /// Length of the file in bytes.
+    len: u64,
+    /// Current position of the reader in the file.
+    position: u64,
+    _block: PhantomData<B>,
+}
+
+impl<B> ChainFile<B> {
+    /// Opens the chain file at the given path.
+    fn open(path: &Path) -> std::io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { reader: BufReader::new(file), len, position: 0, _block: PhantomData })
+    }
+
+    /// Reads the RLP list header of the next block into `buf` and returns the length of the
+    /// block's payload, or `None` at the end of the file.
+    ///
+    /// Returns an error if the payload doesn't fit into the rest of the file.
+    fn read_header(&mut self, buf: &mut Vec<u8>) -> eyre::Result<Option<u64>> {
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None)
+        }
+
+        let mut prefix = [0u8];
+        self.reader.read_exact(&mut prefix)?;
+        buf.push(prefix[0]);
+
+        let payload_length = match prefix[0] {
+            prefix @ 0xc0..=0xf7 => (prefix - 0xc0) as u64,
+            prefix @ 0xf8..=0xff => {
+                let mut length = [0u8; 8];
+                let length_of_length = (prefix - 0xf7) as usize;
+                self.reader.read_exact(&mut length[8 - length_of_length..])?;
+                buf.extend_from_slice(&length[8 - length_of_length..]);
+                u64::from_be_bytes(length)
+            }
+            _ => eyre::bail!("expected an RLP encoded block"),
+        };
+        self.position += buf.len() as u64;
+
+        let remaining = self.len.saturating_sub(self.position);
+        if payload_length > remaining {
+            eyre::bail!(
+                "block at offset {} is {payload_length} bytes long, but only {remaining} bytes are left in the file",
+                self.position - buf.len() as u64
+            )
+        }
+
+        Ok(Some(payload_length))
+    }
+
+    /// Returns the offsets of all blocks in the file, without decoding them.
+    fn index(mut self) -> eyre::Result<Vec<u64>> {
+        let mut offsets = Vec::new();
+        let mut buf = Vec::new();
+        loop {
+            let offset = self.position;
+            buf.clear();
+            let Some(payload_length) = self.read_header(&mut buf)? else { break };
+            offsets.push(offset);
+            self.reader.seek_relative(payload_length as i64)?;
+            self.position += payload_length;
+        }
+        Ok(offsets)
+    }
+
+    /// Reads and decodes the block at the given offset.
+    fn read_block_at(&mut self, offset: u64) -> eyre::Result<B>
+    where
+        B: Block,
+    {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.position = offset;
+
+        let mut buf = Vec::new();
+        let payload_length = self
+            .read_header(&mut buf)?
+            .ok_or_else(|| eyre::eyre!("no block found at offset {offset}"))?;
+
+        let header_length = buf.len();
+        buf.resize(header_length + payload_length as usize, 0);
+        self.reader.read_exact(&mut buf[header_length..])?;
+        self.position += payload_length;
+
+        Ok(alloy_rlp::decode_exact(&buf)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::Header;
+    use alloy_rlp::Encodable;
+    use reth_ethereum_primitives::Block as EthBlock;
+    use tokio::sync::mpsc;
+
+    fn block(number: u64) -> EthBlock {
+        EthBlock { header: Header { number, ..Default::default() }, body: Default::default() }
+    }
+
+    fn write_rlp_file(blocks: &[EthBlock]) -> tempfile::NamedTempFile {
+        let mut data = Vec::new();
+        for block in blocks {
+            block.encode(&mut data);
+        }
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), data).unwrap();
+        file
+    }
+
+    #[tokio::test]
+    async fn rlp_file_blocks() {
+        let blocks = (10..15).map(block).collect::<Vec<_>>();
+        let file = write_rlp_file(&blocks);
+
+        let provider = FileBlockProvider::<EthBlock>::new(file.path()).unwrap();
+        assert_eq!(provider.get_block(10).await.unwrap(), blocks[0]);
+        assert_eq!(provider.get_block(12).await.unwrap(), blocks[2]);
+        assert_eq!(provider.get_block(14).await.unwrap(), blocks[4]);
+        assert!(provider.get_block(9).await.is_err());
+        assert!(provider.get_block(15).await.is_err());
+
+        let (tx, mut rx) = mpsc::channel(blocks.len());
+        provider.subscribe_blocks(tx).await;
+        for expected in &blocks {
+            assert_eq!(&rx.recv().await.unwrap(), expected);
+        }
+        assert!(rx.recv().await.is_none());
+
+        // blocks before the start of the file are reported as unknown
+        let hashes = AllocRingBuffer::new(64);
+        assert_eq!(
+            provider.get_or_fetch_previous_block(&hashes, 14, 32).await.unwrap(),
+            B256::ZERO
+        );
+        assert_eq!(
+            provider.get_or_fetch_previous_block(&hashes, 14, 2).await.unwrap(),
+            blocks[2].header.hash_slow()
+        );
+    }
+
+    #[tokio::test]
+    async fn rlp_file_gap() {
+        let file = write_rlp_file(&[block(1), block(3)]);
+        let provider = FileBlockProvider::<EthBlock>::new(file.path()).unwrap();
+
+        // sending stops at the gap
+        let (tx, mut rx) = mpsc::channel(2);
+        provider.subscribe_blocks(tx).await;
+        assert_eq!(rx.recv().await.unwrap(), block(1));
+        assert!(rx.recv().await.is_none());
+
+        assert!(provider.get_block(2).await.is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        let empty = write_rlp_file(&[]);
+        assert!(FileBlockProvider::<EthBlock>::new(empty.path()).is_err());
+        assert!(FileBlockProvider::<EthBlock>::new("mainnet-00000-5ec1ffb8.era1").is_err());
+
+        // the length of the block exceeds the size of the file
+        let mut data = Vec::new();
+        block(1).encode(&mut data);
+        data.extend_from_slice(&[0xfb, 0xff, 0xff, 0xff, 0xff, 0xc0]);
+        let truncated = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(truncated.path(), data).unwrap();
+        assert!(FileBlockProvider::<EthBlock>::new(truncated.path()).is_err());
+    }
+}
@@ -1,5 +1,7 @@
 mod etherscan;
+mod file;
 mod rpc;
 
 pub use etherscan::EtherscanBlockProvider;
+pub use file::FileBlockProvider;
 pub use rpc::RpcBlockProvider;
@@ -8,7 +8,7 @@ use eyre::Ok;
 use futures_util::Future;
 use jsonrpsee::http_client::HttpClient;
 use reth_chainspec::EthereumHardforks;
-use reth_network_api::test_utils::PeersHandleProvider;
+use reth_network_api::PeersHandleProvider;
 use reth_node_api::{
     Block, BlockBody, BlockTy, EngineApiMessageVersion, FullNodeComponents, PayloadTypes,
     PrimitivesTy,
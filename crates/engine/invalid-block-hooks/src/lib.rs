@@ -1,5 +1,10 @@
 //! Invalid block hook implementations.
 
+mod opcode;
+mod prestate;
+mod trace;
 mod witness;
 
+pub use opcode::InvalidBlockOpcodeHook;
+pub use prestate::InvalidBlockPreStateHook;
 pub use witness::InvalidBlockWitnessHook;
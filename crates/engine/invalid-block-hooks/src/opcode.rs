@@ -0,0 +1,279 @@
+use crate::trace::{healthy_node_traces, save_diff, save_file, trace_block};
+use alloy_consensus::BlockHeader;
+use alloy_primitives::B256;
+use alloy_rpc_types_trace::geth::{
+    GethDebugTracingOptions, GethDefaultTracingOptions, TraceResult,
+};
+use reth_engine_primitives::InvalidBlockHook;
+use reth_evm::ConfigureEvm;
+use reth_primitives_traits::{NodePrimitives, RecoveredBlock, SealedHeader};
+use reth_provider::{BlockExecutionOutput, StateProviderFactory};
+use reth_tracing::tracing::warn;
+use reth_trie::updates::TrieUpdates;
+use revm_inspectors::tracing::TracingInspectorConfig;
+use std::path::PathBuf;
+
+/// Generates an opcode level trace (struct logs) of every transaction in the invalid block and
+/// saves one file per transaction, in the same format as the default tracer of
+/// `debug_traceBlock*`.
+#[derive(Debug)]
+pub struct InvalidBlockOpcodeHook<P, E> {
+    /// The provider to read the historical state and do the EVM execution.
+    provider: P,
+    /// The EVM configuration to use for the execution.
+    evm_config: E,
+    /// The directory to write the opcode traces to. Additionally, diff files will be written to
+    /// this directory in case of a mismatch with the healthy node.
+    output_directory: PathBuf,
+    /// The healthy node client to compare the opcode traces against.
+    healthy_node_client: Option<jsonrpsee::http_client::HttpClient>,
+}
+
+impl<P, E> InvalidBlockOpcodeHook<P, E> {
+    /// Creates a new opcode hook.
+    pub const fn new(
+        provider: P,
+        evm_config: E,
+        output_directory: PathBuf,
+        healthy_node_client: Option<jsonrpsee::http_client::HttpClient>,
+    ) -> Self {
+        Self { provider, evm_config, output_directory, healthy_node_client }
+    }
+}
+
+impl<P, E, N> InvalidBlockOpcodeHook<P, E>
+where
+    P: StateProviderFactory + Send + Sync + 'static,
+    E: ConfigureEvm<Primitives = N> + 'static,
+    N: NodePrimitives,
+{
+    /// Re-executes the block and returns the struct logs of every transaction.
+    fn re_execute_block(
+        &self,
+        parent_header: &SealedHeader<N::BlockHeader>,
+        block: &RecoveredBlock<N::Block>,
+        config: GethDefaultTracingOptions,
+    ) -> eyre::Result<Vec<TraceResult>> {
+        trace_block(
+            &self.provider,
+            &self.evm_config,
+            parent_header,
+            block,
+            TracingInspectorConfig::from_geth_config(&config),
+            |inspector, result_and_state, _| {
+                let result = &result_and_state.result;
+                let frame = inspector.geth_builder().geth_traces(
+                    result.gas_used(),
+                    result.output().cloned().unwrap_or_default(),
+                    config,
+                );
+                Ok(frame.into())
+            },
+        )
+    }
+
+    fn on_invalid_block(
+        &self,
+        parent_header: &SealedHeader<N::BlockHeader>,
+        block: &RecoveredBlock<N::Block>,
+    ) -> eyre::Result<()> {
+        let config = GethDefaultTracingOptions::default();
+        let traces = self.re_execute_block(parent_header, block, config)?;
+
+        let healthy_node_traces = self
+            .healthy_node_client
+            .as_ref()
+            .map(|client| {
+                let opts = GethDebugTracingOptions { config, ..Default::default() };
+                healthy_node_traces(client, block.number(), opts)
+            })
+            .transpose()?;
+
+        let block_prefix = format!("{}_{}", block.number(), block.hash());
+        self.save_traces(&block_prefix, &traces, healthy_node_traces.as_deref())
+    }
+
+    /// Saves the struct logs of every transaction, and their diff against the struct logs of the
+    /// healthy node if they mismatch.
+    fn save_traces(
+        &self,
+        block_prefix: &str,
+        traces: &[TraceResult],
+        healthy_node_traces: Option<&[TraceResult]>,
+    ) -> eyre::Result<()> {
+        for (index, trace) in traces.iter().enumerate() {
+            let tx_prefix = format!("{}.tx_{}", block_prefix, index);
+            let re_executed_path = save_file(
+                &self.output_directory,
+                format!("{}.opcodes.re_executed.json", tx_prefix),
+                trace,
+            )?;
+
+            let Some(healthy_trace) = healthy_node_traces.and_then(|t| t.get(index)) else {
+                continue
+            };
+
+            let healthy_path = save_file(
+                &self.output_directory,
+                format!("{}.opcodes.healthy.json", tx_prefix),
+                healthy_trace,
+            )?;
+
+            if trace != healthy_trace {
+                let diff_path = save_diff(
+                    &self.output_directory,
+                    format!("{}.opcodes.diff", tx_prefix),
+                    trace,
+                    healthy_trace,
+                )?;
+                warn!(
+                    target: "engine::invalid_block_hooks::opcode",
+                    tx_index = index,
+                    diff_path = %diff_path.display(),
+                    re_executed_path = %re_executed_path.display(),
+                    healthy_path = %healthy_path.display(),
+                    "Opcode trace mismatch against healthy node"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<P, E, N: NodePrimitives> InvalidBlockHook<N> for InvalidBlockOpcodeHook<P, E>
+where
+    P: StateProviderFactory + Send + Sync + 'static,
+    E: ConfigureEvm<Primitives = N> + 'static,
+{
+    fn on_invalid_block(
+        &self,
+        parent_header: &SealedHeader<N::BlockHeader>,
+        block: &RecoveredBlock<N::Block>,
+        _output: &BlockExecutionOutput<N::Receipt>,
+        _trie_updates: Option<(&TrieUpdates, B256)>,
+    ) {
+        if let Err(err) = self.on_invalid_block(parent_header, block) {
+            warn!(target: "engine::invalid_block_hooks::opcode", %err, "Failed to invoke hook");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{Header, TxLegacy};
+    use alloy_primitives::{Address, Bytes, TxKind, U256};
+    use alloy_rpc_types_trace::geth::{DefaultFrame, GethTrace};
+    use reth_chainspec::ChainSpec;
+    use reth_ethereum_primitives::{Block, BlockBody, EthPrimitives, Transaction};
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives_traits::SignedTransaction;
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_testing_utils::generators::{self, generate_key, sign_tx_with_key_pair};
+    use tempfile::TempDir;
+
+    fn hook(
+        output_directory: PathBuf,
+    ) -> (InvalidBlockOpcodeHook<MockEthProvider<EthPrimitives, ChainSpec>, EthEvmConfig>, Address)
+    {
+        let provider = MockEthProvider::<EthPrimitives, ChainSpec>::default();
+        // PUSH1 1, PUSH1 2, ADD, POP, STOP
+        let contract = Address::with_last_byte(0xaa);
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(Bytes::from_static(&[0x60, 0x01, 0x60, 0x02, 0x01, 0x50, 0x00])),
+        );
+        (
+            InvalidBlockOpcodeHook::new(provider, EthEvmConfig::mainnet(), output_directory, None),
+            contract,
+        )
+    }
+
+    fn read_json(path: PathBuf) -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_on_invalid_block_writes_opcode_files() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let output_directory = temp_dir.path().to_path_buf();
+        let (hook, contract) = hook(output_directory.clone());
+
+        let mut rng = generators::rng();
+        let key_pair = generate_key(&mut rng);
+        let transactions = (0..2)
+            .map(|nonce| {
+                sign_tx_with_key_pair(
+                    key_pair,
+                    Transaction::Legacy(TxLegacy {
+                        chain_id: Some(1),
+                        nonce,
+                        gas_price: 1,
+                        gas_limit: 100_000,
+                        to: TxKind::Call(contract),
+                        ..Default::default()
+                    }),
+                )
+            })
+            .collect::<Vec<_>>();
+        let sender = transactions[0].recover_signer().unwrap();
+        hook.provider.add_account(sender, ExtendedAccount::new(0, U256::from(1_000_000_000)));
+
+        let parent_header = generators::random_header(&mut rng, 1, None);
+        let block = RecoveredBlock::new_unhashed(
+            Block {
+                header: Header {
+                    parent_hash: parent_header.hash(),
+                    number: 2,
+                    gas_limit: 1_000_000,
+                    ..Default::default()
+                },
+                body: BlockBody { transactions, ..Default::default() },
+            },
+            vec![sender; 2],
+        );
+
+        hook.on_invalid_block(&parent_header, &block).unwrap();
+
+        let block_prefix = format!("{}_{}", block.number(), block.hash());
+        for index in 0..2 {
+            let path = output_directory
+                .join(format!("{block_prefix}.tx_{index}.opcodes.re_executed.json"));
+            let trace = read_json(path);
+            let struct_logs = trace["result"]["structLogs"].as_array().unwrap();
+            let ops = struct_logs.iter().map(|log| log["op"].as_str().unwrap()).collect::<Vec<_>>();
+            assert_eq!(ops, ["PUSH1", "PUSH1", "ADD", "POP", "STOP"]);
+        }
+        // no healthy node configured, so nothing to compare against
+        assert_eq!(std::fs::read_dir(&output_directory).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_save_traces_writes_diff_on_mismatch() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let output_directory = temp_dir.path().to_path_buf();
+        let (hook, _) = hook(output_directory.clone());
+
+        let trace = |gas| TraceResult::Success {
+            result: GethTrace::Default(DefaultFrame { gas, ..Default::default() }),
+            tx_hash: None,
+        };
+        let traces = [trace(21_000), trace(21_000)];
+        let healthy_node_traces = [trace(21_000), trace(42_000)];
+
+        hook.save_traces("1_0x01", &traces, Some(&healthy_node_traces)).unwrap();
+
+        for index in 0..2 {
+            let re_executed =
+                output_directory.join(format!("1_0x01.tx_{index}.opcodes.re_executed.json"));
+            let healthy = output_directory.join(format!("1_0x01.tx_{index}.opcodes.healthy.json"));
+            assert!(re_executed.exists() && healthy.exists());
+        }
+        assert!(!output_directory.join("1_0x01.tx_0.opcodes.diff").exists());
+        let diff =
+            std::fs::read_to_string(output_directory.join("1_0x01.tx_1.opcodes.diff")).unwrap();
+        assert!(diff.contains("42000"));
+    }
+}
@@ -0,0 +1,188 @@
+use crate::trace::{healthy_node_traces, save_diff, save_file, trace_block};
+use alloy_consensus::BlockHeader;
+use alloy_primitives::B256;
+use alloy_rpc_types_trace::geth::{
+    GethDebugBuiltInTracerType, GethDebugTracingOptions, PreStateConfig, TraceResult,
+};
+use reth_engine_primitives::InvalidBlockHook;
+use reth_evm::ConfigureEvm;
+use reth_primitives_traits::{NodePrimitives, RecoveredBlock, SealedHeader};
+use reth_provider::{BlockExecutionOutput, StateProviderFactory};
+use reth_tracing::tracing::warn;
+use reth_trie::updates::TrieUpdates;
+use revm_inspectors::tracing::TracingInspectorConfig;
+use std::path::PathBuf;
+
+/// Generates a prestate trace of every account and storage slot touched by the invalid block and
+/// saves it to a file, in the same format as the `prestateTracer` of `debug_traceBlock*`.
+#[derive(Debug)]
+pub struct InvalidBlockPreStateHook<P, E> {
+    /// The provider to read the historical state and do the EVM execution.
+    provider: P,
+    /// The EVM configuration to use for the execution.
+    evm_config: E,
+    /// The directory to write the prestate traces to. Additionally, diff files will be written to
+    /// this directory in case of a mismatch with the healthy node.
+    output_directory: PathBuf,
+    /// The healthy node client to compare the prestate traces against.
+    healthy_node_client: Option<jsonrpsee::http_client::HttpClient>,
+}
+
+impl<P, E> InvalidBlockPreStateHook<P, E> {
+    /// Creates a new prestate hook.
+    pub const fn new(
+        provider: P,
+        evm_config: E,
+        output_directory: PathBuf,
+        healthy_node_client: Option<jsonrpsee::http_client::HttpClient>,
+    ) -> Self {
+        Self { provider, evm_config, output_directory, healthy_node_client }
+    }
+}
+
+impl<P, E, N> InvalidBlockPreStateHook<P, E>
+where
+    P: StateProviderFactory + Send + Sync + 'static,
+    E: ConfigureEvm<Primitives = N> + 'static,
+    N: NodePrimitives,
+{
+    /// Re-executes the block and returns the prestate trace of every transaction.
+    fn re_execute_block(
+        &self,
+        parent_header: &SealedHeader<N::BlockHeader>,
+        block: &RecoveredBlock<N::Block>,
+        prestate_config: &PreStateConfig,
+    ) -> eyre::Result<Vec<TraceResult>> {
+        trace_block(
+            &self.provider,
+            &self.evm_config,
+            parent_header,
+            block,
+            TracingInspectorConfig::from_geth_prestate_config(prestate_config),
+            |inspector, result_and_state, db| {
+                let frame = inspector.geth_builder().geth_prestate_traces(
+                    result_and_state,
+                    prestate_config,
+                    db,
+                )?;
+                Ok(frame.into())
+            },
+        )
+    }
+
+    fn on_invalid_block(
+        &self,
+        parent_header: &SealedHeader<N::BlockHeader>,
+        block: &RecoveredBlock<N::Block>,
+    ) -> eyre::Result<()> {
+        let prestate_config = PreStateConfig::default();
+        let traces = self.re_execute_block(parent_header, block, &prestate_config)?;
+
+        let block_prefix = format!("{}_{}", block.number(), block.hash());
+        let re_executed_path = save_file(
+            &self.output_directory,
+            format!("{}.prestate.re_executed.json", block_prefix),
+            &traces,
+        )?;
+
+        if let Some(healthy_node_client) = &self.healthy_node_client {
+            let opts = GethDebugTracingOptions::default()
+                .with_tracer(GethDebugBuiltInTracerType::PreStateTracer.into())
+                .with_prestate_config(prestate_config);
+            let healthy_node_traces =
+                healthy_node_traces(healthy_node_client, block.number(), opts)?;
+
+            let healthy_path = save_file(
+                &self.output_directory,
+                format!("{}.prestate.healthy.json", block_prefix),
+                &healthy_node_traces,
+            )?;
+
+            if traces != healthy_node_traces {
+                let diff_path = save_diff(
+                    &self.output_directory,
+                    format!("{}.prestate.diff", block_prefix),
+                    &traces,
+                    &healthy_node_traces,
+                )?;
+                warn!(
+                    target: "engine::invalid_block_hooks::prestate",
+                    diff_path = %diff_path.display(),
+                    re_executed_path = %re_executed_path.display(),
+                    healthy_path = %healthy_path.display(),
+                    "Prestate trace mismatch against healthy node"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<P, E, N: NodePrimitives> InvalidBlockHook<N> for InvalidBlockPreStateHook<P, E>
+where
+    P: StateProviderFactory + Send + Sync + 'static,
+    E: ConfigureEvm<Primitives = N> + 'static,
+{
+    fn on_invalid_block(
+        &self,
+        parent_header: &SealedHeader<N::BlockHeader>,
+        block: &RecoveredBlock<N::Block>,
+        _output: &BlockExecutionOutput<N::Receipt>,
+        _trie_updates: Option<(&TrieUpdates, B256)>,
+    ) {
+        if let Err(err) = self.on_invalid_block(parent_header, block) {
+            warn!(target: "engine::invalid_block_hooks::prestate", %err, "Failed to invoke hook");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::ChainSpec;
+    use reth_ethereum_primitives::EthPrimitives;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_testing_utils::generators::{self, random_block, BlockParams};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_on_invalid_block_writes_prestate_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let output_directory = temp_dir.path().to_path_buf();
+
+        let provider = MockEthProvider::<EthPrimitives, ChainSpec>::default();
+        let hook = InvalidBlockPreStateHook::new(
+            provider,
+            EthEvmConfig::mainnet(),
+            output_directory.clone(),
+            None,
+        );
+
+        let mut rng = generators::rng();
+        let parent_header = generators::random_header(&mut rng, 1, None);
+        let block = random_block(
+            &mut rng,
+            2,
+            BlockParams {
+                parent: Some(parent_header.hash()),
+                tx_count: Some(0),
+                ..Default::default()
+            },
+        )
+        .try_recover()
+        .unwrap();
+
+        hook.on_invalid_block(&parent_header, &block).unwrap();
+
+        let path = output_directory.join(format!(
+            "{}_{}.prestate.re_executed.json",
+            block.number(),
+            block.hash()
+        ));
+        let traces: Vec<TraceResult> =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert!(traces.is_empty());
+    }
+}
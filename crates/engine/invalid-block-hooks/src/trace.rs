@@ -0,0 +1,127 @@
+//! Helpers shared by the tracer based invalid block hooks.
+
+use alloy_consensus::Transaction;
+use alloy_rpc_types_trace::geth::{GethDebugTracingOptions, GethTrace, TraceResult};
+use pretty_assertions::Comparison;
+use reth_evm::{execute::BlockExecutor, ConfigureEvm, Evm, HaltReasonFor};
+use reth_primitives_traits::{NodePrimitives, RecoveredBlock, SealedHeader, SignedTransaction};
+use reth_provider::{StateProvider, StateProviderFactory};
+use reth_revm::{database::StateProviderDatabase, db::State};
+use reth_rpc_api::DebugApiClient;
+use revm::context::result::ResultAndState;
+use revm_inspectors::tracing::{TracingInspector, TracingInspectorConfig};
+use serde::Serialize;
+use std::{
+    fmt::Debug,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The database the block is re-executed against: the state of the parent block.
+pub(crate) type TraceDb = State<StateProviderDatabase<Box<dyn StateProvider>>>;
+
+/// Re-executes the block on top of its parent state with a [`TracingInspector`] and builds one
+/// trace per transaction with `f`.
+///
+/// Every transaction is inspected before its state changes are committed, so `f` observes the
+/// database as it was right before the transaction. If a transaction fails to execute, an error
+/// result is recorded for it and the remaining transactions are skipped.
+pub(crate) fn trace_block<P, E, N, F>(
+    provider: &P,
+    evm_config: &E,
+    parent_header: &SealedHeader<N::BlockHeader>,
+    block: &RecoveredBlock<N::Block>,
+    config: TracingInspectorConfig,
+    mut f: F,
+) -> eyre::Result<Vec<TraceResult>>
+where
+    P: StateProviderFactory,
+    E: ConfigureEvm<Primitives = N>,
+    N: NodePrimitives,
+    F: FnMut(
+        &TracingInspector,
+        &ResultAndState<HaltReasonFor<E>>,
+        &TraceDb,
+    ) -> eyre::Result<GethTrace>,
+{
+    let mut db = State::builder()
+        .with_database(StateProviderDatabase::new(
+            provider.state_by_block_hash(parent_header.hash())?,
+        ))
+        .build();
+
+    let evm_env = evm_config.evm_env(block.header())?;
+    let evm =
+        evm_config.evm_with_env_and_inspector(&mut db, evm_env, TracingInspector::new(config));
+    let ctx = evm_config.context_for_block(block)?;
+    let mut executor = evm_config.create_executor(evm, ctx);
+    executor.apply_pre_execution_changes()?;
+
+    let mut results = Vec::with_capacity(block.transaction_count());
+    for tx in block.transactions_recovered() {
+        let tx_hash = Some(*tx.tx_hash());
+        let gas_limit = tx.gas_limit();
+
+        // discard everything recorded for system calls or previous transactions
+        executor.evm_mut().components_mut().1.fuse();
+
+        let result_and_state = match executor.execute_transaction_without_commit(tx) {
+            Ok(result_and_state) => result_and_state,
+            Err(err) => {
+                results.push(TraceResult::Error { error: err.to_string(), tx_hash });
+                break
+            }
+        };
+
+        let (db, inspector, _) = executor.evm_mut().components_mut();
+        inspector.set_transaction_gas_limit(gas_limit);
+        let result = f(inspector, &result_and_state, db)?;
+        results.push(TraceResult::Success { result, tx_hash });
+
+        if let Err(err) = executor.commit_transaction(result_and_state, tx) {
+            results.push(TraceResult::Error { error: err.to_string(), tx_hash });
+            break
+        }
+    }
+
+    Ok(results)
+}
+
+/// Fetches the traces of the block with the given number from the healthy node.
+pub(crate) fn healthy_node_traces(
+    client: &jsonrpsee::http_client::HttpClient,
+    block_number: u64,
+    opts: GethDebugTracingOptions,
+) -> eyre::Result<Vec<TraceResult>> {
+    Ok(futures::executor::block_on(async move {
+        DebugApiClient::<()>::debug_trace_block_by_number(client, block_number.into(), Some(opts))
+            .await
+    })?)
+}
+
+/// Serializes and saves a value to a JSON file in the output directory
+pub(crate) fn save_file<T: Serialize>(
+    output_directory: &Path,
+    filename: String,
+    value: &T,
+) -> eyre::Result<PathBuf> {
+    let path = output_directory.join(filename);
+    File::create(&path)?.write_all(serde_json::to_string(value)?.as_bytes())?;
+
+    Ok(path)
+}
+
+/// Compares two values and saves their diff to a file in the output directory
+pub(crate) fn save_diff<T: PartialEq + Debug>(
+    output_directory: &Path,
+    filename: String,
+    original: &T,
+    new: &T,
+) -> eyre::Result<PathBuf> {
+    let path = output_directory.join(filename);
+    let diff = Comparison::new(original, new);
+    File::create(&path)?.write_all(diff.to_string().as_bytes())?;
+
+    Ok(path)
+}
@@ -1,5 +1,7 @@
 //! Engine tree configuration.
 
+use core::time::Duration;
+
 /// Triggers persistence when the number of canonical blocks in memory exceeds this threshold.
 pub const DEFAULT_PERSISTENCE_THRESHOLD: u64 = 2;
 
@@ -44,6 +46,7 @@ const DEFAULT_BLOCK_BUFFER_LIMIT: u32 = 256;
 const DEFAULT_MAX_INVALID_HEADER_CACHE_LENGTH: u32 = 256;
 const DEFAULT_MAX_EXECUTE_BLOCK_BATCH_SIZE: usize = 4;
 const DEFAULT_CROSS_BLOCK_CACHE_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+const DEFAULT_EXECUTION_CACHE_SNAPSHOT_SIZE: u64 = 256 * 1024 * 1024;
 
 /// Determines if the host has enough parallelism to run the payload processor.
 ///
@@ -73,6 +76,19 @@ pub struct TreeConfig {
     ///
     /// Note: this should be less than or equal to `persistence_threshold`.
     memory_block_buffer_target: u64,
+    /// Estimated size in bytes of the executed blocks kept in memory above which persistence is
+    /// triggered.
+    ///
+    /// If set, this replaces `persistence_threshold` as the trigger, so that nodes with enough
+    /// memory keep more blocks in memory during bursts while nodes with little memory persist
+    /// early.
+    persistence_memory_threshold: Option<u64>,
+    /// Maximum time since the last persistence after which unpersisted canonical blocks are
+    /// persisted, regardless of how many there are.
+    persistence_max_age: Option<Duration>,
+    /// Whether to persist blocks as soon as they are finalized, including those within
+    /// `memory_block_buffer_target` of the canonical head.
+    persist_finalized: bool,
     /// Number of pending blocks that cannot be executed due to missing parent and
     /// are kept in cache.
     block_buffer_limit: u32,
@@ -107,6 +123,17 @@ pub struct TreeConfig {
     reserved_cpu_cores: usize,
     /// Whether to disable the precompile cache
     precompile_cache_disabled: bool,
+    /// Whether to save the cross-block execution cache and the precompile cache to disk on
+    /// shutdown and restore them on startup.
+    persist_execution_cache: bool,
+    /// Maximum size in bytes of the execution cache snapshot.
+    execution_cache_snapshot_size: u64,
+    /// Whether to speculatively execute the transactions of a block in parallel before
+    /// committing them in order.
+    ///
+    /// The block access list used to predict the state of each transaction is derived from the
+    /// block, unless the payload carries one, see `ExecutionPayload::block_access_list`.
+    parallel_execution: bool,
     /// Whether to use state root fallback for testing
     state_root_fallback: bool,
     /// Whether to always process payload attributes and begin a payload build process
@@ -127,6 +154,13 @@ pub struct TreeConfig {
     prewarm_max_concurrency: usize,
     /// Whether to unwind canonical header to ancestor during forkchoice updates.
     allow_unwind_canonical_header: bool,
+    /// Maximum number of canonical blocks a reorg may unwind.
+    ///
+    /// Deeper reorgs are refused until they are explicitly approved.
+    max_reorg_depth: Option<u64>,
+    /// Whether to shut down the engine instead of waiting for an approval when a reorg exceeds
+    /// `max_reorg_depth`.
+    halt_on_deep_reorg: bool,
     /// Number of storage proof worker threads.
     storage_worker_count: usize,
     /// Number of account proof worker threads.
@@ -138,6 +172,9 @@ impl Default for TreeConfig {
         Self {
             persistence_threshold: DEFAULT_PERSISTENCE_THRESHOLD,
             memory_block_buffer_target: DEFAULT_MEMORY_BLOCK_BUFFER_TARGET,
+            persistence_memory_threshold: None,
+            persistence_max_age: None,
+            persist_finalized: false,
             block_buffer_limit: DEFAULT_BLOCK_BUFFER_LIMIT,
             max_invalid_header_cache_length: DEFAULT_MAX_INVALID_HEADER_CACHE_LENGTH,
             max_execute_block_batch_size: DEFAULT_MAX_EXECUTE_BLOCK_BATCH_SIZE,
@@ -152,10 +189,15 @@ impl Default for TreeConfig {
             multiproof_chunk_size: DEFAULT_MULTIPROOF_TASK_CHUNK_SIZE,
             reserved_cpu_cores: DEFAULT_RESERVED_CPU_CORES,
             precompile_cache_disabled: false,
+            persist_execution_cache: false,
+            execution_cache_snapshot_size: DEFAULT_EXECUTION_CACHE_SNAPSHOT_SIZE,
+            parallel_execution: false,
             state_root_fallback: false,
             always_process_payload_attributes_on_canonical_head: false,
             prewarm_max_concurrency: DEFAULT_PREWARM_MAX_CONCURRENCY,
             allow_unwind_canonical_header: false,
+            max_reorg_depth: None,
+            halt_on_deep_reorg: false,
             storage_worker_count: default_storage_worker_count(),
             account_worker_count: default_account_worker_count(),
         }
@@ -192,6 +234,9 @@ impl TreeConfig {
         Self {
             persistence_threshold,
             memory_block_buffer_target,
+            persistence_memory_threshold: None,
+            persistence_max_age: None,
+            persist_finalized: false,
             block_buffer_limit,
             max_invalid_header_cache_length,
             max_execute_block_batch_size,
@@ -206,10 +251,15 @@ impl TreeConfig {
             multiproof_chunk_size,
             reserved_cpu_cores,
             precompile_cache_disabled,
+            persist_execution_cache: false,
+            execution_cache_snapshot_size: DEFAULT_EXECUTION_CACHE_SNAPSHOT_SIZE,
+            parallel_execution: false,
             state_root_fallback,
             always_process_payload_attributes_on_canonical_head,
             prewarm_max_concurrency,
             allow_unwind_canonical_header,
+            max_reorg_depth: None,
+            halt_on_deep_reorg: false,
             storage_worker_count,
             account_worker_count,
         }
@@ -225,6 +275,21 @@ impl TreeConfig {
         self.memory_block_buffer_target
     }
 
+    /// Return the estimated size in bytes of the in-memory blocks that triggers persistence.
+    pub const fn persistence_memory_threshold(&self) -> Option<u64> {
+        self.persistence_memory_threshold
+    }
+
+    /// Return the maximum time since the last persistence after which blocks are persisted.
+    pub const fn persistence_max_age(&self) -> Option<Duration> {
+        self.persistence_max_age
+    }
+
+    /// Return whether finalized blocks are persisted as soon as they are finalized.
+    pub const fn persist_finalized(&self) -> bool {
+        self.persist_finalized
+    }
+
     /// Return the block buffer limit.
     pub const fn block_buffer_limit(&self) -> u32 {
         self.block_buffer_limit
@@ -292,6 +357,21 @@ impl TreeConfig {
         self.precompile_cache_disabled
     }
 
+    /// Returns whether the execution cache is saved on shutdown and restored on startup.
+    pub const fn persist_execution_cache(&self) -> bool {
+        self.persist_execution_cache
+    }
+
+    /// Returns the maximum size of the execution cache snapshot in bytes.
+    pub const fn execution_cache_snapshot_size(&self) -> u64 {
+        self.execution_cache_snapshot_size
+    }
+
+    /// Returns whether transactions are speculatively executed in parallel.
+    pub const fn parallel_execution(&self) -> bool {
+        self.parallel_execution
+    }
+
     /// Returns whether to use state root fallback.
     pub const fn state_root_fallback(&self) -> bool {
         self.state_root_fallback
@@ -333,6 +413,27 @@ impl TreeConfig {
         self
     }
 
+    /// Setter for the estimated size in bytes of the in-memory blocks that triggers persistence.
+    pub const fn with_persistence_memory_threshold(
+        mut self,
+        persistence_memory_threshold: Option<u64>,
+    ) -> Self {
+        self.persistence_memory_threshold = persistence_memory_threshold;
+        self
+    }
+
+    /// Setter for the maximum time since the last persistence after which blocks are persisted.
+    pub const fn with_persistence_max_age(mut self, persistence_max_age: Option<Duration>) -> Self {
+        self.persistence_max_age = persistence_max_age;
+        self
+    }
+
+    /// Setter for whether finalized blocks are persisted as soon as they are finalized.
+    pub const fn with_persist_finalized(mut self, persist_finalized: bool) -> Self {
+        self.persist_finalized = persist_finalized;
+        self
+    }
+
     /// Setter for block buffer limit.
     pub const fn with_block_buffer_limit(mut self, block_buffer_limit: u32) -> Self {
         self.block_buffer_limit = block_buffer_limit;
@@ -433,6 +534,27 @@ impl TreeConfig {
         self
     }
 
+    /// Setter for whether the execution cache is saved on shutdown and restored on startup.
+    pub const fn with_persist_execution_cache(mut self, persist_execution_cache: bool) -> Self {
+        self.persist_execution_cache = persist_execution_cache;
+        self
+    }
+
+    /// Setter for the maximum size of the execution cache snapshot in bytes.
+    pub const fn with_execution_cache_snapshot_size(
+        mut self,
+        execution_cache_snapshot_size: u64,
+    ) -> Self {
+        self.execution_cache_snapshot_size = execution_cache_snapshot_size;
+        self
+    }
+
+    /// Setter for whether transactions are speculatively executed in parallel.
+    pub const fn with_parallel_execution(mut self, parallel_execution: bool) -> Self {
+        self.parallel_execution = parallel_execution;
+        self
+    }
+
     /// Setter for whether to use state root fallback, useful for testing.
     pub const fn with_state_root_fallback(mut self, state_root_fallback: bool) -> Self {
         self.state_root_fallback = state_root_fallback;
@@ -445,6 +567,28 @@ impl TreeConfig {
         self
     }
 
+    /// Return the maximum number of canonical blocks a reorg may unwind, if any.
+    pub const fn max_reorg_depth(&self) -> Option<u64> {
+        self.max_reorg_depth
+    }
+
+    /// Setter for the maximum number of canonical blocks a reorg may unwind.
+    pub const fn with_max_reorg_depth(mut self, max_reorg_depth: Option<u64>) -> Self {
+        self.max_reorg_depth = max_reorg_depth;
+        self
+    }
+
+    /// Returns true if the engine should shut down when a reorg exceeds the maximum reorg depth.
+    pub const fn halt_on_deep_reorg(&self) -> bool {
+        self.halt_on_deep_reorg
+    }
+
+    /// Setter for whether to shut down the engine when a reorg exceeds the maximum reorg depth.
+    pub const fn with_halt_on_deep_reorg(mut self, halt_on_deep_reorg: bool) -> Self {
+        self.halt_on_deep_reorg = halt_on_deep_reorg;
+        self
+    }
+
     /// Whether or not to use state root task
     pub const fn use_state_root_task(&self) -> bool {
         self.has_enough_parallelism && !self.legacy_state_root
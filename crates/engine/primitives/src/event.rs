@@ -1,7 +1,7 @@
 //! Events emitted by the beacon consensus engine.
 
-use crate::ForkchoiceStatus;
-use alloc::boxed::Box;
+use crate::{BlockValidationReport, ForkchoiceStatus};
+use alloc::{boxed::Box, vec::Vec};
 use alloy_consensus::BlockHeader;
 use alloy_eips::BlockNumHash;
 use alloy_primitives::B256;
@@ -35,6 +35,13 @@ pub enum ConsensusEngineEvent<N: NodePrimitives = EthPrimitives> {
     InvalidBlock(Box<SealedBlock<N::Block>>),
     /// The consensus engine is involved in live sync, and has specific progress
     LiveSyncProgress(ConsensusEngineLiveSyncProgress),
+    /// A block was validated and inserted into the tree, with the timing breakdown of the
+    /// validation.
+    BlockValidated(Box<BlockValidationReport>),
+    /// The canonical chain was reorged.
+    CanonicalChainReorged(Box<ChainReorg>),
+    /// A reorg deeper than the configured maximum reorg depth was refused.
+    DeepReorgRejected(Box<ChainReorg>),
 }
 
 impl<N: NodePrimitives> ConsensusEngineEvent<N> {
@@ -79,10 +86,42 @@ where
             Self::BlockReceived(num_hash) => {
                 write!(f, "BlockReceived({num_hash:?})")
             }
+            Self::BlockValidated(report) => {
+                write!(f, "BlockValidated({}, {})", report.number, report.hash)
+            }
+            Self::CanonicalChainReorged(reorg) => {
+                write!(f, "CanonicalChainReorged({:?}, depth: {})", reorg.fork_block, reorg.depth())
+            }
+            Self::DeepReorgRejected(reorg) => {
+                write!(f, "DeepReorgRejected({:?}, depth: {})", reorg.fork_block, reorg.depth())
+            }
         }
     }
 }
 
+/// The blocks that were removed from and added to the canonical chain by a reorg.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainReorg {
+    /// The last block both chains have in common.
+    pub fork_block: BlockNumHash,
+    /// The blocks of the old canonical chain above the fork block, in ascending order.
+    pub old: Vec<BlockNumHash>,
+    /// The blocks of the new canonical chain above the fork block, in ascending order.
+    pub new: Vec<BlockNumHash>,
+}
+
+impl ChainReorg {
+    /// Returns the number of canonical blocks unwound by the reorg.
+    pub const fn depth(&self) -> u64 {
+        self.old.len() as u64
+    }
+
+    /// Returns the new canonical head.
+    pub fn new_head(&self) -> Option<BlockNumHash> {
+        self.new.last().copied()
+    }
+}
+
 /// Progress of the consensus engine during live sync.
 #[derive(Clone, Debug)]
 pub enum ConsensusEngineLiveSyncProgress {
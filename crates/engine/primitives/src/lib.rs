@@ -39,6 +39,9 @@ pub use message::*;
 mod event;
 pub use event::*;
 
+mod report;
+pub use report::{BlockValidationReport, StateRootSource};
+
 mod invalid_block_hook;
 pub use invalid_block_hook::{InvalidBlockHook, InvalidBlockHooks, NoopInvalidBlockHook};
 
@@ -1,6 +1,7 @@
 use crate::{
     error::BeaconForkChoiceUpdateError, BeaconOnNewPayloadError, ExecutionPayload, ForkchoiceStatus,
 };
+use alloy_primitives::B256;
 use alloy_rpc_types_engine::{
     ForkChoiceUpdateResult, ForkchoiceState, ForkchoiceUpdateError, ForkchoiceUpdated, PayloadId,
     PayloadStatus, PayloadStatusEnum,
@@ -164,6 +165,18 @@ pub enum BeaconEngineMessage<Payload: PayloadTypes> {
         /// The sender for returning forkchoice updated result.
         tx: oneshot::Sender<RethResult<OnForkChoiceUpdated>>,
     },
+    /// Message with a candidate block, e.g. from a co-located block builder, that is executed
+    /// before the [`BeaconEngineMessage::NewPayload`] message for it arrives.
+    ExecuteCandidate {
+        /// The execution payload of the candidate block.
+        payload: Payload::ExecutionData,
+    },
+    /// Message approving a reorg to the given head that exceeds the configured maximum reorg
+    /// depth.
+    ApproveReorg {
+        /// Hash of the new head, or of any block of the new chain above the fork block.
+        head: B256,
+    },
 }
 
 impl<Payload: PayloadTypes> Display for BeaconEngineMessage<Payload> {
@@ -187,6 +200,16 @@ impl<Payload: PayloadTypes> Display for BeaconEngineMessage<Payload> {
                     payload_attrs.is_some()
                 )
             }
+            Self::ExecuteCandidate { payload } => {
+                write!(
+                    f,
+                    "ExecuteCandidate(parent: {}, number: {}, hash: {})",
+                    payload.parent_hash(),
+                    payload.block_number(),
+                    payload.block_hash()
+                )
+            }
+            Self::ApproveReorg { head } => write!(f, "ApproveReorg({head})"),
         }
     }
 }
@@ -223,6 +246,24 @@ where
         rx.await.map_err(|_| BeaconOnNewPayloadError::EngineUnavailable)?
     }
 
+    /// Submits a candidate block to be executed before the `newPayload` call for it arrives.
+    ///
+    /// The executed block is kept by the engine, so that validating the same block via
+    /// [`Self::new_payload`] later reuses the execution results instead of executing it again.
+    /// Candidates that fail validation are discarded without affecting the validation of the
+    /// payload.
+    pub fn execute_candidate(&self, payload: Payload::ExecutionData) {
+        let _ = self.to_engine.send(BeaconEngineMessage::ExecuteCandidate { payload });
+    }
+
+    /// Approves a reorg to the given head that exceeds the configured maximum reorg depth.
+    ///
+    /// The approval applies to the next reorg whose new chain contains the given block. Returns
+    /// `false` if the engine is no longer running.
+    pub fn approve_reorg(&self, head: B256) -> bool {
+        self.to_engine.send(BeaconEngineMessage::ApproveReorg { head }).is_ok()
+    }
+
     /// Sends a forkchoice update message to the beacon consensus engine and waits for a response.
     ///
     /// See also <https://github.com/ethereum/execution-apis/blob/3d627c95a4d3510a8187dd02e0250ecb4331d27e/src/engine/shanghai.md#engine_forkchoiceupdatedv2>
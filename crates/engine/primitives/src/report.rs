@@ -0,0 +1,121 @@
+//! Per-block validation reports.
+
+use alloy_primitives::B256;
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// The algorithm that produced the state root of a validated block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StateRootSource {
+    /// The state root was computed by the sparse trie task while the block was executed.
+    SparseTrie,
+    /// The state root was computed with the parallel state root algorithm after execution.
+    Parallel,
+    /// The state root was computed synchronously after execution.
+    #[default]
+    Synchronous,
+}
+
+/// Timing breakdown of the validation of a single block.
+///
+/// All durations are serialized as microseconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockValidationReport {
+    /// Number of the block.
+    pub number: u64,
+    /// Hash of the block.
+    pub hash: B256,
+    /// Gas used by the block.
+    pub gas_used: u64,
+    /// Number of transactions in the block.
+    pub transaction_count: u64,
+    /// Time from the start of the validation until the block was inserted into the tree.
+    #[serde(with = "duration_micros")]
+    pub total_duration: Duration,
+    /// Time spent executing the transactions of the block.
+    #[serde(with = "duration_micros")]
+    pub execution_duration: Duration,
+    /// Time spent speculatively executing the transactions in parallel before they were
+    /// committed in order. Zero unless parallel execution is enabled, and not part of
+    /// [`Self::execution_duration`].
+    #[serde(with = "duration_micros")]
+    pub speculative_execution_duration: Duration,
+    /// Time spent validating the execution output against the block, e.g. receipts root and gas
+    /// used, and hashing the post state.
+    #[serde(with = "duration_micros")]
+    pub post_execution_validation_duration: Duration,
+    /// Time from the end of the post execution validation until the state root was available.
+    #[serde(with = "duration_micros")]
+    pub state_root_duration: Duration,
+    /// Part of [`Self::state_root_duration`] spent waiting for outstanding multiproofs and sparse
+    /// trie updates. Zero unless the state root was computed by the sparse trie task.
+    #[serde(with = "duration_micros")]
+    pub multiproof_wait_duration: Duration,
+    /// Part of [`Self::state_root_duration`] spent computing the root of the sparse trie once all
+    /// proofs were revealed. Zero unless the state root was computed by the sparse trie task.
+    #[serde(with = "duration_micros")]
+    pub sparse_trie_root_duration: Duration,
+    /// The algorithm that produced the state root.
+    pub state_root_source: StateRootSource,
+    /// Number of state reads during execution that were served by the prewarmed execution cache.
+    pub cache_hits: u64,
+    /// Number of state reads during execution that missed the prewarmed execution cache.
+    pub cache_misses: u64,
+    /// Whether blocks were being persisted while the block was validated.
+    pub persistence_in_progress: bool,
+    /// Number of canonical blocks that were not persisted yet when the block was validated.
+    pub blocks_pending_persistence: u64,
+}
+
+impl BlockValidationReport {
+    /// Returns the share of state reads served by the prewarmed execution cache, or `None` if
+    /// execution did not read any state.
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        let total = self.cache_hits + self.cache_misses;
+        (total > 0).then(|| self.cache_hits as f64 / total as f64)
+    }
+}
+
+/// (De)serializes a [`Duration`] as a number of microseconds.
+mod duration_micros {
+    use core::time::Duration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        duration: &Duration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_micros() as u64)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_micros)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_roundtrip() {
+        let report = BlockValidationReport {
+            number: 1,
+            execution_duration: Duration::from_millis(12),
+            state_root_source: StateRootSource::SparseTrie,
+            cache_hits: 3,
+            cache_misses: 1,
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["executionDuration"], 12_000);
+        assert_eq!(json["stateRootSource"], "sparseTrie");
+        assert_eq!(serde_json::from_value::<BlockValidationReport>(json).unwrap(), report);
+        assert_eq!(report.cache_hit_rate(), Some(0.75));
+    }
+}
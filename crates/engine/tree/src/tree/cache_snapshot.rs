@@ -0,0 +1,735 @@
+//! Snapshots of the cross-block execution cache that are restored after a restart.
+//!
+//! After a restart the execution cache is empty and block execution is slow until the cache is
+//! warmed up again. A snapshot of the hottest cached accounts, storage slots and bytecodes and of
+//! the cached precompile results is written on shutdown and loaded on startup.
+//!
+//! The cached state is only correct for the block it was built for, and blocks that are only kept
+//! in memory are lost on shutdown. So a snapshot is only taken if the cache is at the last
+//! persisted block, and discarded unless the database is still at that block when the node starts.
+
+use crate::tree::{
+    cached_state::{
+        CachedStateMetrics, ExecutionCache as StateExecutionCache, ExecutionCacheBuilder,
+        SavedCache,
+    },
+    payload_processor::ExecutionCache,
+    precompile_cache::PrecompileCacheMap,
+};
+use alloy_eips::BlockNumHash;
+use alloy_primitives::{
+    map::HashSet, Address, Bytes, StorageKey, StorageValue, B256, KECCAK256_EMPTY, U256,
+};
+use reth_evm::{ConfigureEvm, SpecFor};
+use reth_fs_util::{self as fs, FsPathError};
+use reth_primitives_traits::{Account, Bytecode, HeaderTy};
+use reth_provider::{
+    BlockNumReader, DatabaseProviderFactory, HeaderProvider, ProviderError, ProviderResult,
+};
+use revm::precompile::PrecompileOutput;
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::PathBuf,
+};
+use tracing::{debug, info};
+
+/// Version of the snapshot encoding, bumped on every incompatible change.
+const SNAPSHOT_VERSION: u8 = 1;
+
+/// Encoded size of a cached account.
+const ACCOUNT_SIZE: u64 = 20 + 1 + 8 + 32 + 1 + 32;
+
+/// Encoded size of the storage of an account, without its slots.
+const STORAGE_SIZE: u64 = 20 + 8;
+
+/// Encoded size of a cached storage slot.
+const SLOT_SIZE: u64 = 32 + 1 + 32;
+
+/// Encoded size of a cached bytecode, without the code.
+const BYTECODE_SIZE: u64 = 32 + 1 + 8;
+
+/// Errors that can occur when saving or loading an [`ExecutionCacheSnapshot`].
+#[derive(Debug, thiserror::Error)]
+pub enum ExecutionCacheSnapshotError {
+    /// Failed to access the snapshot file.
+    #[error(transparent)]
+    Fs(#[from] FsPathError),
+    /// Failed to encode or decode the snapshot.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// Failed to read the block the snapshot belongs to.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// Failed to configure the EVM for the block the snapshot belongs to.
+    #[error("failed to configure EVM: {0}")]
+    Evm(Box<dyn core::error::Error + Send + Sync>),
+    /// The snapshot file is malformed.
+    #[error("corrupted execution cache snapshot: {0}")]
+    Corrupted(&'static str),
+}
+
+/// Handle to save the execution and precompile caches of a
+/// [`BasicEngineValidator`](crate::tree::BasicEngineValidator) to disk and to restore them.
+///
+/// The snapshot contains the state cached for the last persisted block, and the precompile results
+/// cached for the spec of that block.
+#[derive(Debug, Clone)]
+pub struct ExecutionCacheSnapshot<P, Evm>
+where
+    Evm: ConfigureEvm,
+{
+    /// The path of the snapshot file.
+    path: PathBuf,
+    /// Provider used to look up the block the snapshot belongs to.
+    provider: P,
+    /// EVM configuration used to determine the spec of the cached precompile results.
+    evm_config: Evm,
+    /// The cross-block cache of the payload processor.
+    execution_cache: ExecutionCache,
+    /// The precompile caches of the validator.
+    precompile_cache_map: PrecompileCacheMap<SpecFor<Evm>>,
+    /// Size in bytes of the restored cross-block cache.
+    cross_block_cache_size: u64,
+    /// Maximum size in bytes of the cached state that is saved.
+    max_size: u64,
+}
+
+impl<P, Evm> ExecutionCacheSnapshot<P, Evm>
+where
+    P: DatabaseProviderFactory<Provider: BlockNumReader>
+        + HeaderProvider<Header = HeaderTy<Evm::Primitives>>,
+    Evm: ConfigureEvm,
+{
+    /// Creates a new snapshot handle for the given caches.
+    pub(crate) const fn new(
+        path: PathBuf,
+        provider: P,
+        evm_config: Evm,
+        execution_cache: ExecutionCache,
+        precompile_cache_map: PrecompileCacheMap<SpecFor<Evm>>,
+        cross_block_cache_size: u64,
+        max_size: u64,
+    ) -> Self {
+        Self {
+            path,
+            provider,
+            evm_config,
+            execution_cache,
+            precompile_cache_map,
+            cross_block_cache_size,
+            max_size,
+        }
+    }
+
+    /// Writes the hottest cached state of the last persisted block to disk.
+    ///
+    /// The snapshot is skipped if the cache is at a block that is only kept in memory, because the
+    /// database is behind that block after a restart.
+    ///
+    /// This blocks on disk IO and should not be called on an async task. Returns the hash of the
+    /// block the snapshot was taken at, or `None` if there is nothing to save.
+    pub fn save(&self) -> Result<Option<B256>, ExecutionCacheSnapshotError> {
+        let Some(persisted) = self.persisted_block()? else { return Ok(None) };
+
+        // select the entries while holding the lock, so that the cache is not updated with the
+        // state of the next block in the meantime, but write them after releasing it
+        let Some(entries) = self.execution_cache.with_saved_cache(|saved_cache| {
+            let saved_cache = saved_cache?;
+            if saved_cache.executed_block_hash() != persisted.hash {
+                debug!(target: "engine::caching", cached = %saved_cache.executed_block_hash(), persisted = %persisted.hash, "Execution cache is not at the persisted block, skipping snapshot");
+                return None
+            }
+            Some(SnapshotEntries::select(saved_cache.cache(), self.max_size))
+        }) else {
+            return Ok(None)
+        };
+
+        let hash = persisted.hash;
+        let Some(header) = self.provider.header_by_number(persisted.number)? else {
+            debug!(target: "engine::caching", %hash, "Block of execution cache not found, skipping snapshot");
+            return Ok(None)
+        };
+        let spec = self.spec(&header)?;
+
+        fs::atomic_write_file(&self.path, |file| {
+            let mut writer = SnapshotWriter(BufWriter::new(file));
+            writer.write(hash, &entries, &self.precompile_cache_map, &spec)?;
+            writer.0.flush()
+        })?;
+
+        debug!(
+            target: "engine::caching",
+            %hash,
+            accounts = entries.accounts.len(),
+            storage = entries.storage.len(),
+            bytecodes = entries.bytecodes.len(),
+            "Saved execution cache snapshot"
+        );
+        Ok(Some(hash))
+    }
+
+    /// Restores the caches from the snapshot on disk, if it was taken at the last persisted block.
+    ///
+    /// The snapshot file is removed afterwards, so that it is never restored twice. Returns the
+    /// hash of the block the restored cache belongs to.
+    pub fn load(&self) -> Result<Option<B256>, ExecutionCacheSnapshotError> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(FsPathError::open(err, &self.path).into()),
+        };
+        let result = self.load_from(file);
+        fs::remove_file(&self.path)?;
+        result
+    }
+
+    fn load_from(&self, file: File) -> Result<Option<B256>, ExecutionCacheSnapshotError> {
+        let mut reader = SnapshotReader(BufReader::new(file));
+        if reader.u8()? != SNAPSHOT_VERSION {
+            return Err(ExecutionCacheSnapshotError::Corrupted("unsupported version"))
+        }
+        let hash = reader.b256()?;
+
+        let Some(persisted) = self.persisted_block()? else { return Ok(None) };
+        if persisted.hash != hash {
+            info!(target: "engine::caching", snapshot = %hash, persisted = %persisted.hash, "Discarding execution cache snapshot of a different block");
+            return Ok(None)
+        }
+        let Some(header) = self.provider.header_by_number(persisted.number)? else {
+            return Ok(None)
+        };
+        let spec = self.spec(&header)?;
+
+        let cache = ExecutionCacheBuilder::default().build_caches(self.cross_block_cache_size);
+        reader.read(&cache, &self.precompile_cache_map, &spec)?;
+
+        let saved_cache = SavedCache::new(hash, cache, CachedStateMetrics::zeroed());
+        saved_cache.update_metrics();
+        self.execution_cache.update_with_guard(|cached| {
+            // never replace a cache that was built by executing blocks
+            if cached.is_none() {
+                *cached = Some(saved_cache);
+            }
+        });
+
+        Ok(Some(hash))
+    }
+
+    /// Returns the last block that was written to the database.
+    ///
+    /// Blocks that are only kept in memory are ahead of this block.
+    fn persisted_block(&self) -> ProviderResult<Option<BlockNumHash>> {
+        let provider = self.provider.database_provider_ro()?;
+        let number = provider.best_block_number()?;
+        Ok(provider.block_hash(number)?.map(|hash| BlockNumHash::new(number, hash)))
+    }
+
+    /// Returns the spec the precompile results of the given block are cached for.
+    fn spec(
+        &self,
+        header: &HeaderTy<Evm::Primitives>,
+    ) -> Result<SpecFor<Evm>, ExecutionCacheSnapshotError> {
+        let evm_env = self
+            .evm_config
+            .evm_env(header)
+            .map_err(|err| ExecutionCacheSnapshotError::Evm(Box::new(err)))?;
+        Ok(evm_env.cfg_env.spec)
+    }
+}
+
+/// The cached state that is saved in a snapshot.
+#[derive(Debug, Default)]
+struct SnapshotEntries {
+    accounts: Vec<(Address, Option<Account>)>,
+    storage: Vec<(Address, Vec<(StorageKey, Option<StorageValue>)>)>,
+    bytecodes: Vec<(B256, Option<Bytecode>)>,
+}
+
+impl SnapshotEntries {
+    /// Selects the hottest entries of the cache that fit into the given encoded size.
+    ///
+    /// The size is split between storage, accounts and bytecodes in the same proportions as the
+    /// cross-block cache. Storage is taken from the accounts with the most storage cache hits
+    /// first, and the accounts and bytecodes of those contracts are preferred over the rest.
+    fn select(cache: &StateExecutionCache, max_size: u64) -> Self {
+        let mut entries = Self::default();
+
+        let mut budget = max_size * 8888 / 10000;
+        for (address, storage) in cache.cached_storage_by_hits() {
+            let slots = storage.slots();
+            let size = STORAGE_SIZE + slots.len() as u64 * SLOT_SIZE;
+            if size <= budget {
+                budget -= size;
+                entries.storage.push((address, slots));
+            }
+        }
+
+        let mut budget = max_size * 556 / 10000;
+        let mut addresses = HashSet::<Address>::default();
+        let hot_accounts = entries.storage.iter().filter_map(|(address, _)| {
+            cache.get_account(address).map(|account| (*address, account))
+        });
+        for (address, account) in hot_accounts.chain(cache.cached_accounts()) {
+            if budget < ACCOUNT_SIZE {
+                break
+            }
+            if addresses.insert(address) {
+                budget -= ACCOUNT_SIZE;
+                entries.accounts.push((address, account));
+            }
+        }
+
+        let mut budget = max_size * 556 / 10000;
+        let mut code_hashes = HashSet::<B256>::default();
+        let hot_bytecodes = entries
+            .accounts
+            .iter()
+            .filter_map(|(_, account)| account.and_then(|account| account.bytecode_hash))
+            .filter(|hash| *hash != KECCAK256_EMPTY)
+            .filter_map(|hash| cache.get_bytecode(&hash).map(|bytecode| (hash, bytecode)));
+        for (hash, bytecode) in hot_bytecodes.chain(cache.cached_bytecodes()) {
+            let size = BYTECODE_SIZE +
+                bytecode.as_ref().map_or(0, |bytecode| bytecode.original_bytes().len() as u64);
+            if size <= budget && code_hashes.insert(hash) {
+                budget -= size;
+                entries.bytecodes.push((hash, bytecode));
+            }
+        }
+
+        entries
+    }
+}
+
+/// Encodes the snapshot.
+///
+/// All integers are big-endian, and collections and byte strings are prefixed with their length.
+struct SnapshotWriter<W>(W);
+
+impl<W: Write> SnapshotWriter<W> {
+    fn write<S>(
+        &mut self,
+        hash: B256,
+        entries: &SnapshotEntries,
+        precompile_cache_map: &PrecompileCacheMap<S>,
+        spec: &S,
+    ) -> io::Result<()>
+    where
+        S: Eq + std::hash::Hash + std::fmt::Debug + Send + Sync + Clone + 'static,
+    {
+        self.u8(SNAPSHOT_VERSION)?;
+        self.0.write_all(hash.as_slice())?;
+
+        self.len(entries.accounts.len())?;
+        for (address, account) in &entries.accounts {
+            self.0.write_all(address.as_slice())?;
+            self.option(*account, |this, account| {
+                this.u64(account.nonce)?;
+                this.0.write_all(&account.balance.to_be_bytes::<32>())?;
+                this.option(account.bytecode_hash, |this, hash| this.0.write_all(hash.as_slice()))
+            })?;
+        }
+
+        self.len(entries.storage.len())?;
+        for (address, slots) in &entries.storage {
+            self.0.write_all(address.as_slice())?;
+            self.len(slots.len())?;
+            for (key, value) in slots {
+                self.0.write_all(key.as_slice())?;
+                self.option(*value, |this, value| this.0.write_all(&value.to_be_bytes::<32>()))?;
+            }
+        }
+
+        self.len(entries.bytecodes.len())?;
+        for (hash, bytecode) in &entries.bytecodes {
+            self.0.write_all(hash.as_slice())?;
+            self.option(bytecode.as_ref(), |this, bytecode| {
+                this.bytes(&bytecode.original_bytes())
+            })?;
+        }
+
+        // least recently used first, so that the recency order is kept when they are inserted
+        let precompile_results = precompile_cache_map.entries(spec);
+        self.len(precompile_results.len())?;
+        for (address, input, output) in precompile_results.into_iter().rev() {
+            self.0.write_all(address.as_slice())?;
+            self.bytes(&input)?;
+            self.u64(output.gas_used)?;
+            self.u8(u8::from(output.reverted))?;
+            self.bytes(&output.bytes)?;
+        }
+
+        Ok(())
+    }
+
+    fn u8(&mut self, value: u8) -> io::Result<()> {
+        self.0.write_all(&[value])
+    }
+
+    fn u64(&mut self, value: u64) -> io::Result<()> {
+        self.0.write_all(&value.to_be_bytes())
+    }
+
+    fn len(&mut self, len: usize) -> io::Result<()> {
+        self.u64(len as u64)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.len(bytes.len())?;
+        self.0.write_all(bytes)
+    }
+
+    fn option<T>(
+        &mut self,
+        value: Option<T>,
+        f: impl FnOnce(&mut Self, T) -> io::Result<()>,
+    ) -> io::Result<()> {
+        match value {
+            Some(value) => {
+                self.u8(1)?;
+                f(self, value)
+            }
+            None => self.u8(0),
+        }
+    }
+}
+
+/// Decodes a snapshot encoded by [`SnapshotWriter`].
+struct SnapshotReader<R>(R);
+
+impl<R: Read> SnapshotReader<R> {
+    fn read<S>(
+        &mut self,
+        cache: &StateExecutionCache,
+        precompile_cache_map: &PrecompileCacheMap<S>,
+        spec: &S,
+    ) -> Result<(), ExecutionCacheSnapshotError>
+    where
+        S: Eq + std::hash::Hash + std::fmt::Debug + Send + Sync + Clone + 'static,
+    {
+        for _ in 0..self.u64()? {
+            let address = self.address()?;
+            let account = self.option(|this| {
+                Ok(Account {
+                    nonce: this.u64()?,
+                    balance: U256::from_be_bytes(this.array()?),
+                    bytecode_hash: this.option(Self::b256)?,
+                })
+            })?;
+            cache.insert_account(address, account);
+        }
+
+        for _ in 0..self.u64()? {
+            let address = self.address()?;
+            let slots = (0..self.u64()?)
+                .map(|_| {
+                    let key = self.b256()?;
+                    let value = self.option(|this| Ok(U256::from_be_bytes(this.array()?)))?;
+                    Ok((key, value))
+                })
+                .collect::<Result<Vec<_>, ExecutionCacheSnapshotError>>()?;
+            cache.insert_storage_bulk(address, slots);
+        }
+
+        for _ in 0..self.u64()? {
+            let hash = self.b256()?;
+            let bytecode = self.option(|this| {
+                Bytecode::new_raw_checked(this.bytes()?)
+                    .map_err(|_| ExecutionCacheSnapshotError::Corrupted("invalid bytecode"))
+            })?;
+            cache.insert_bytecode(hash, bytecode);
+        }
+
+        for _ in 0..self.u64()? {
+            let address = self.address()?;
+            let input = self.bytes()?;
+            let output = PrecompileOutput {
+                gas_used: self.u64()?,
+                reverted: self.bool()?,
+                bytes: self.bytes()?,
+            };
+            precompile_cache_map.insert(address, spec.clone(), input, output);
+        }
+
+        Ok(())
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ExecutionCacheSnapshotError> {
+        let mut buf = [0u8; N];
+        self.0.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn u8(&mut self) -> Result<u8, ExecutionCacheSnapshotError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn bool(&mut self) -> Result<bool, ExecutionCacheSnapshotError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(ExecutionCacheSnapshotError::Corrupted("invalid flag")),
+        }
+    }
+
+    fn u64(&mut self) -> Result<u64, ExecutionCacheSnapshotError> {
+        Ok(u64::from_be_bytes(self.array()?))
+    }
+
+    fn b256(&mut self) -> Result<B256, ExecutionCacheSnapshotError> {
+        Ok(B256::from(self.array()?))
+    }
+
+    fn address(&mut self) -> Result<Address, ExecutionCacheSnapshotError> {
+        Ok(Address::from(self.array()?))
+    }
+
+    fn bytes(&mut self) -> Result<Bytes, ExecutionCacheSnapshotError> {
+        let len = self.u64()?;
+        let mut buf = Vec::new();
+        if (&mut self.0).take(len).read_to_end(&mut buf)? as u64 != len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into())
+        }
+        Ok(buf.into())
+    }
+
+    fn option<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, ExecutionCacheSnapshotError>,
+    ) -> Result<Option<T>, ExecutionCacheSnapshotError> {
+        self.bool()?.then(|| f(self)).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::Header;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives_traits::SealedHeader;
+    use reth_provider::{
+        test_utils::{create_test_provider_factory, MockNodeTypesWithDB},
+        ProviderFactory, StageCheckpointWriter, StaticFileProviderFactory, StaticFileSegment,
+    };
+    use reth_stages_api::{StageCheckpoint, StageId};
+
+    type TestProvider = ProviderFactory<MockNodeTypesWithDB>;
+
+    fn snapshot(
+        path: PathBuf,
+        provider: TestProvider,
+        max_size: u64,
+    ) -> ExecutionCacheSnapshot<TestProvider, EthEvmConfig> {
+        ExecutionCacheSnapshot::new(
+            path,
+            provider,
+            EthEvmConfig::mainnet(),
+            ExecutionCache::default(),
+            PrecompileCacheMap::default(),
+            1_000_000,
+            max_size,
+        )
+    }
+
+    /// Writes the headers of the given blocks and advances the database to the last one.
+    fn persist_blocks(
+        provider: &TestProvider,
+        numbers: impl IntoIterator<Item = u64>,
+    ) -> Vec<SealedHeader> {
+        let provider_rw = provider.database_provider_rw().unwrap();
+        let mut writer =
+            provider_rw.static_file_provider().latest_writer(StaticFileSegment::Headers).unwrap();
+        let headers = numbers
+            .into_iter()
+            .map(|number| SealedHeader::seal_slow(Header { number, ..Default::default() }))
+            .collect::<Vec<_>>();
+        for header in &headers {
+            writer.append_header(header.header(), &header.hash()).unwrap();
+        }
+        drop(writer);
+        let tip = headers.last().unwrap().number;
+        provider_rw.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(tip)).unwrap();
+        provider_rw.commit().unwrap();
+        headers
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("execution-cache.bin");
+
+        let provider = create_test_provider_factory();
+        let header = persist_blocks(&provider, 0..=1).pop().unwrap();
+
+        let account = Account {
+            nonce: 7,
+            balance: U256::from(100),
+            bytecode_hash: Some(B256::with_last_byte(1)),
+        };
+        let bytecode = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]));
+        let output =
+            PrecompileOutput { gas_used: 21, bytes: Bytes::from_static(b"out"), reverted: false };
+
+        let source = snapshot(path.clone(), provider.clone(), 1_000_000);
+        let spec = source.spec(header.header()).unwrap();
+        let cache = ExecutionCacheBuilder::default().build_caches(1_000_000);
+        cache.insert_account(Address::with_last_byte(1), Some(account));
+        cache.insert_account(Address::with_last_byte(2), None);
+        cache.insert_storage(
+            Address::with_last_byte(1),
+            B256::with_last_byte(1),
+            Some(U256::from(5)),
+        );
+        cache.insert_storage(Address::with_last_byte(1), B256::with_last_byte(2), None);
+        cache.insert_bytecode(B256::with_last_byte(1), Some(bytecode.clone()));
+        source.execution_cache.update_with_guard(|cached| {
+            *cached = Some(SavedCache::new(header.hash(), cache, CachedStateMetrics::zeroed()));
+        });
+        source.precompile_cache_map.insert(
+            Address::with_last_byte(9),
+            spec,
+            Bytes::from_static(b"in"),
+            output.clone(),
+        );
+        assert_eq!(source.save().unwrap(), Some(header.hash()));
+
+        let target = snapshot(path.clone(), provider, 1_000_000);
+        assert_eq!(target.load().unwrap(), Some(header.hash()));
+        assert!(!path.exists());
+
+        let restored = target.execution_cache.get_cache_for(header.hash()).unwrap();
+        let mut accounts = restored.cache().cached_accounts().collect::<Vec<_>>();
+        accounts.sort_by_key(|(address, _)| *address);
+        assert_eq!(
+            accounts,
+            vec![(Address::with_last_byte(1), Some(account)), (Address::with_last_byte(2), None)]
+        );
+        let mut slots = restored.cache().cached_storage_by_hits().pop().unwrap().1.slots();
+        slots.sort_by_key(|(key, _)| *key);
+        assert_eq!(
+            slots,
+            vec![(B256::with_last_byte(1), Some(U256::from(5))), (B256::with_last_byte(2), None)]
+        );
+        assert_eq!(
+            restored.cache().cached_bytecodes().collect::<Vec<_>>(),
+            vec![(B256::with_last_byte(1), Some(bytecode))]
+        );
+        assert_eq!(
+            target.precompile_cache_map.entries(&spec),
+            vec![(Address::with_last_byte(9), Bytes::from_static(b"in"), output)]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_of_other_block_is_discarded() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("execution-cache.bin");
+
+        let provider = create_test_provider_factory();
+        let header = persist_blocks(&provider, 0..=1).pop().unwrap();
+
+        let source = snapshot(path.clone(), provider.clone(), 1_000_000);
+        source.execution_cache.update_with_guard(|cached| {
+            let cache = ExecutionCacheBuilder::default().build_caches(1_000_000);
+            *cached = Some(SavedCache::new(header.hash(), cache, CachedStateMetrics::zeroed()));
+        });
+        assert_eq!(source.save().unwrap(), Some(header.hash()));
+
+        // the database advanced past the block the snapshot was taken at
+        persist_blocks(&provider, [2]);
+
+        let target = snapshot(path.clone(), provider, 1_000_000);
+        assert_eq!(target.load().unwrap(), None);
+        assert!(!path.exists());
+        assert!(target.execution_cache.get_cache_for(header.hash()).is_none());
+    }
+
+    #[test]
+    fn test_snapshot_of_in_memory_block_is_skipped() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("execution-cache.bin");
+
+        let provider = create_test_provider_factory();
+        let persisted = persist_blocks(&provider, 0..=1).pop().unwrap();
+
+        // the cache is at an executed block that isn't persisted yet, and is lost on shutdown
+        let executed = SealedHeader::seal_slow(Header {
+            number: 2,
+            parent_hash: persisted.hash(),
+            ..Default::default()
+        });
+        let source = snapshot(path.clone(), provider.clone(), 1_000_000);
+        source.execution_cache.update_with_guard(|cached| {
+            let cache = ExecutionCacheBuilder::default().build_caches(1_000_000);
+            cache.insert_account(Address::with_last_byte(1), Some(Account::default()));
+            *cached = Some(SavedCache::new(executed.hash(), cache, CachedStateMetrics::zeroed()));
+        });
+        assert_eq!(source.save().unwrap(), None);
+        assert!(!path.exists());
+
+        // after the restart, the database is at the persisted block and the cache is empty
+        let target = snapshot(path, provider, 1_000_000);
+        assert_eq!(target.load().unwrap(), None);
+        assert!(target.execution_cache.get_cache_for(persisted.hash()).is_none());
+        assert!(target.execution_cache.get_cache_for(executed.hash()).is_none());
+    }
+
+    #[test]
+    fn test_snapshot_keeps_hottest_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("execution-cache.bin");
+
+        let provider = create_test_provider_factory();
+        let header = persist_blocks(&provider, 0..=1).pop().unwrap();
+
+        let (hot, cold) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let code_hash = B256::with_last_byte(1);
+        let bytecode = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]));
+        let contract = Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(code_hash) };
+
+        let cache = ExecutionCacheBuilder::default().build_caches(1_000_000);
+        for address in [hot, cold] {
+            cache.insert_account(address, Some(contract));
+            cache.insert_storage_bulk(
+                address,
+                (0..100u8).map(|slot| (B256::with_last_byte(slot), Some(U256::from(slot)))),
+            );
+        }
+        for byte in 10..30 {
+            cache.insert_account(Address::with_last_byte(byte), Some(Account::default()));
+        }
+        cache.insert_bytecode(code_hash, Some(bytecode.clone()));
+        // only the storage of the hot contract is read
+        for slot in 0..10 {
+            cache.get_storage(&hot, &B256::with_last_byte(slot));
+        }
+
+        // room for the storage of a single contract and a few accounts
+        let max_size = 10_000;
+        let source = snapshot(path.clone(), provider.clone(), max_size);
+        source.execution_cache.update_with_guard(|cached| {
+            *cached = Some(SavedCache::new(header.hash(), cache, CachedStateMetrics::zeroed()));
+        });
+        assert_eq!(source.save().unwrap(), Some(header.hash()));
+        // the snapshot header and the collection lengths are not part of the budget
+        assert!(std::fs::metadata(&path).unwrap().len() <= max_size + 1 + 32 + 4 * 8);
+
+        let target = snapshot(path, provider, max_size);
+        assert_eq!(target.load().unwrap(), Some(header.hash()));
+        let restored = target.execution_cache.get_cache_for(header.hash()).unwrap();
+
+        let storage = restored.cache().cached_storage_by_hits();
+        assert_eq!(storage.len(), 1);
+        assert_eq!(storage[0].0, hot);
+        assert_eq!(storage[0].1.slots().len(), 100);
+
+        let accounts = restored.cache().cached_accounts().collect::<Vec<_>>();
+        assert_eq!(accounts.len(), 5);
+        assert!(accounts.contains(&(hot, Some(contract))));
+
+        assert_eq!(
+            restored.cache().cached_bytecodes().collect::<Vec<_>>(),
+            vec![(code_hash, Some(bytecode))]
+        );
+    }
+}
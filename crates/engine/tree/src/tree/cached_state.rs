@@ -15,7 +15,13 @@ use reth_trie::{
     MultiProofTargets, StorageMultiProof, StorageProof, TrieInput,
 };
 use revm_primitives::map::DefaultHashBuilder;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use tracing::{debug_span, instrument, trace};
 
 pub(crate) type Cache<K, V> =
@@ -31,6 +37,12 @@ pub(crate) struct CachedStateProvider<S> {
 
     /// Metrics for the cached state provider
     metrics: CachedStateMetrics,
+
+    /// Number of reads served by the caches through this provider
+    hits: AtomicU64,
+
+    /// Number of reads that missed the caches through this provider
+    misses: AtomicU64,
 }
 
 impl<S> CachedStateProvider<S>
@@ -44,7 +56,27 @@ where
         caches: ExecutionCache,
         metrics: CachedStateMetrics,
     ) -> Self {
-        Self { state_provider, caches, metrics }
+        Self { state_provider, caches, metrics, hits: AtomicU64::new(0), misses: AtomicU64::new(0) }
+    }
+}
+
+impl<S> CachedStateProvider<S> {
+    /// Returns the number of reads served by the caches and the number of reads that missed the
+    /// caches through this provider.
+    pub(crate) fn hits_and_misses(&self) -> (u64, u64) {
+        (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed))
+    }
+
+    /// Records a read that was served by the caches.
+    fn record_hit(&self, gauge: &Gauge) {
+        gauge.increment(1);
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a read that missed the caches.
+    fn record_miss(&self, gauge: &Gauge) {
+        gauge.increment(1);
+        self.misses.fetch_add(1, Ordering::Relaxed);
     }
 }
 
@@ -116,11 +148,11 @@ impl CachedStateMetrics {
 impl<S: AccountReader> AccountReader for CachedStateProvider<S> {
     fn basic_account(&self, address: &Address) -> ProviderResult<Option<Account>> {
         if let Some(res) = self.caches.account_cache.get(address) {
-            self.metrics.account_cache_hits.increment(1);
+            self.record_hit(&self.metrics.account_cache_hits);
             return Ok(res)
         }
 
-        self.metrics.account_cache_misses.increment(1);
+        self.record_miss(&self.metrics.account_cache_misses);
 
         let res = self.state_provider.basic_account(address)?;
         self.caches.account_cache.insert(*address, res);
@@ -147,17 +179,17 @@ impl<S: StateProvider> StateProvider for CachedStateProvider<S> {
     ) -> ProviderResult<Option<StorageValue>> {
         match self.caches.get_storage(&account, &storage_key) {
             SlotStatus::NotCached => {
-                self.metrics.storage_cache_misses.increment(1);
+                self.record_miss(&self.metrics.storage_cache_misses);
                 let final_res = self.state_provider.storage(account, storage_key)?;
                 self.caches.insert_storage(account, storage_key, final_res);
                 Ok(final_res)
             }
             SlotStatus::Empty => {
-                self.metrics.storage_cache_hits.increment(1);
+                self.record_hit(&self.metrics.storage_cache_hits);
                 Ok(None)
             }
             SlotStatus::Value(value) => {
-                self.metrics.storage_cache_hits.increment(1);
+                self.record_hit(&self.metrics.storage_cache_hits);
                 Ok(Some(value))
             }
         }
@@ -167,11 +199,11 @@ impl<S: StateProvider> StateProvider for CachedStateProvider<S> {
 impl<S: BytecodeReader> BytecodeReader for CachedStateProvider<S> {
     fn bytecode_by_hash(&self, code_hash: &B256) -> ProviderResult<Option<Bytecode>> {
         if let Some(res) = self.caches.code_cache.get(code_hash) {
-            self.metrics.code_cache_hits.increment(1);
+            self.record_hit(&self.metrics.code_cache_hits);
             return Ok(res)
         }
 
-        self.metrics.code_cache_misses.increment(1);
+        self.record_miss(&self.metrics.code_cache_misses);
 
         let final_res = self.state_provider.bytecode_by_hash(code_hash)?;
         self.caches.code_cache.insert(*code_hash, final_res.clone());
@@ -361,6 +393,48 @@ impl ExecutionCache {
         self.storage_cache.iter().map(|addr| addr.len()).sum()
     }
 
+    /// Returns an iterator over all cached accounts.
+    pub(crate) fn cached_accounts(&self) -> impl Iterator<Item = (Address, Option<Account>)> + '_ {
+        self.account_cache.iter().map(|entry| (*entry.key(), *entry.value()))
+    }
+
+    /// Returns the storage caches of all accounts, ordered by the number of cache hits, most hits
+    /// first.
+    pub(crate) fn cached_storage_by_hits(&self) -> Vec<(Address, Arc<AccountStorageCache>)> {
+        let mut storage = self
+            .storage_cache
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect::<Vec<_>>();
+        storage.sort_by_key(|(_, cache)| std::cmp::Reverse(cache.hits()));
+        storage
+    }
+
+    /// Returns an iterator over all cached bytecodes.
+    pub(crate) fn cached_bytecodes(&self) -> impl Iterator<Item = (B256, Option<Bytecode>)> + '_ {
+        self.code_cache.iter().map(|entry| (*entry.key(), entry.value().clone()))
+    }
+
+    /// Returns the cached account, if any.
+    pub(crate) fn get_account(&self, address: &Address) -> Option<Option<Account>> {
+        self.account_cache.get(address)
+    }
+
+    /// Returns the cached bytecode, if any.
+    pub(crate) fn get_bytecode(&self, code_hash: &B256) -> Option<Option<Bytecode>> {
+        self.code_cache.get(code_hash)
+    }
+
+    /// Insert account into the cache
+    pub(crate) fn insert_account(&self, address: Address, account: Option<Account>) {
+        self.account_cache.insert(address, account);
+    }
+
+    /// Insert bytecode into the cache
+    pub(crate) fn insert_bytecode(&self, code_hash: B256, bytecode: Option<Bytecode>) {
+        self.code_cache.insert(code_hash, bytecode);
+    }
+
     /// Inserts the post-execution state changes into the cache.
     ///
     /// This method is called after transaction execution to update the cache with
@@ -596,10 +670,13 @@ impl SavedCache {
 ///
 /// This represents the second level of the hierarchical storage cache.
 /// Each account gets its own `AccountStorageCache` to store accessed storage slots.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub(crate) struct AccountStorageCache {
     /// Map of storage keys to their cached values.
     slots: Cache<StorageKey, Option<StorageValue>>,
+    /// Number of reads served by this cache, used to pick the storage that is worth keeping
+    /// across restarts.
+    hits: AtomicU64,
 }
 
 impl AccountStorageCache {
@@ -607,6 +684,7 @@ impl AccountStorageCache {
     pub(crate) fn new(max_slots: u64) -> Self {
         Self {
             slots: CacheBuilder::new(max_slots).build_with_hasher(DefaultHashBuilder::default()),
+            hits: AtomicU64::new(0),
         }
     }
 
@@ -615,11 +693,18 @@ impl AccountStorageCache {
     /// - `Empty`: The slot is empty
     /// - `Value`: The slot has a specific value
     pub(crate) fn get_storage(&self, key: &StorageKey) -> SlotStatus {
-        match self.slots.get(key) {
-            None => SlotStatus::NotCached,
+        let status = match self.slots.get(key) {
+            None => return SlotStatus::NotCached,
             Some(None) => SlotStatus::Empty,
             Some(Some(value)) => SlotStatus::Value(value),
-        }
+        };
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        status
+    }
+
+    /// Returns the number of reads served by this cache.
+    pub(crate) fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
     }
 
     /// Insert a storage value
@@ -631,6 +716,11 @@ impl AccountStorageCache {
     pub(crate) fn len(&self) -> usize {
         self.slots.entry_count() as usize
     }
+
+    /// Returns all cached slots.
+    pub(crate) fn slots(&self) -> Vec<(StorageKey, Option<StorageValue>)> {
+        self.slots.iter().map(|entry| (*entry.key(), *entry.value())).collect()
+    }
 }
 
 impl Default for AccountStorageCache {
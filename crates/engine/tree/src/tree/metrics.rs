@@ -1,4 +1,7 @@
-use crate::tree::{error::InsertBlockFatalError, MeteredStateHook, TreeOutcome};
+use crate::tree::{
+    error::InsertBlockFatalError, payload_processor::parallel::SpeculativeExecution,
+    MeteredStateHook, TreeOutcome,
+};
 use alloy_consensus::transaction::TxHashRef;
 use alloy_evm::{
     block::{BlockExecutor, ExecutableTx},
@@ -59,11 +62,15 @@ impl EngineApiMetrics {
     ///
     /// This method updates metrics for execution time, gas usage, and the number
     /// of accounts, storage slots and bytecodes loaded and updated.
+    ///
+    /// If speculative results are given, they are committed instead of executing the transactions
+    /// wherever they are still valid.
     pub(crate) fn execute_metered<E, DB>(
         &self,
         executor: E,
         transactions: impl Iterator<Item = Result<impl ExecutableTx<E>, BlockExecutionError>>,
         state_hook: Box<dyn OnStateHook>,
+        mut speculative: Option<SpeculativeExecution<<E::Evm as Evm>::HaltReason>>,
     ) -> Result<BlockExecutionOutput<E::Receipt>, BlockExecutionError>
     where
         DB: alloy_evm::Database,
@@ -78,13 +85,17 @@ impl EngineApiMetrics {
 
         let f = || {
             executor.apply_pre_execution_changes()?;
-            for tx in transactions {
+            for (index, tx) in transactions.enumerate() {
                 let tx = tx?;
                 let span =
                     debug_span!(target: "engine::tree", "execute tx", tx_hash=?tx.tx().tx_hash());
                 let _enter = span.enter();
                 trace!(target: "engine::tree", "Executing transaction");
-                executor.execute_transaction(tx)?;
+                if let Some(speculative) = speculative.as_mut() {
+                    speculative.execute_transaction::<DB, _, _>(&mut executor, index, tx)?;
+                } else {
+                    executor.execute_transaction(tx)?;
+                }
             }
             executor.finish().map(|(evm, result)| (evm.into_db(), result))
         };
@@ -96,6 +107,15 @@ impl EngineApiMetrics {
             (gas_used, res)
         })?;
 
+        if let Some(speculative) = speculative {
+            self.block_validation
+                .speculative_transactions_committed_total
+                .increment(speculative.committed());
+            self.block_validation
+                .speculative_transactions_reexecuted_total
+                .increment(speculative.reexecuted());
+        }
+
         // merge transitions into bundle state
         db.borrow_mut().merge_transitions(BundleRetention::Reverts);
         let output = BlockExecutionOutput { result, state: db.borrow_mut().take_bundle() };
@@ -124,6 +144,8 @@ pub(crate) struct TreeMetrics {
     pub reorgs: Counter,
     /// The latest reorg depth
     pub latest_reorg_depth: Gauge,
+    /// The number of reorgs refused because they exceeded the maximum reorg depth
+    pub rejected_reorgs: Counter,
     /// The current safe block height (this is required by optimism)
     pub safe_block_height: Gauge,
     /// The current finalized block height (this is required by optimism)
@@ -144,12 +166,18 @@ pub(crate) struct EngineMetrics {
     pub(crate) executed_blocks: Gauge,
     /// How many already executed blocks were directly inserted into the tree.
     pub(crate) inserted_already_executed_blocks: Counter,
+    /// How many candidate blocks were executed before the `newPayload` call for them.
+    pub(crate) executed_candidate_blocks: Counter,
     /// The number of times the pipeline was run.
     pub(crate) pipeline_runs: Counter,
     /// Newly arriving block hash is not present in executed blocks cache storage
     pub(crate) executed_new_block_cache_miss: Counter,
     /// Histogram of persistence operation durations (in seconds)
     pub(crate) persistence_duration: Histogram,
+    /// How many canonical blocks the in-memory state is ahead of the last persisted block.
+    pub(crate) blocks_ahead_of_disk: Gauge,
+    /// Estimated size of all executed blocks kept in memory in bytes.
+    pub(crate) executed_blocks_estimated_size: Gauge,
     /// Tracks the how often we failed to deliver a newPayload response.
     ///
     /// This effectively tracks how often the message sender dropped the channel and indicates a CL
@@ -295,6 +323,12 @@ pub(crate) struct BlockValidationMetrics {
     pub(crate) post_execution_validation_duration: Histogram,
     /// Total duration of the new payload call
     pub(crate) total_duration: Histogram,
+    /// Duration of the speculative parallel execution of a block's transactions
+    pub(crate) speculative_execution_duration: Histogram,
+    /// Total number of speculatively executed transactions that were committed
+    pub(crate) speculative_transactions_committed_total: Counter,
+    /// Total number of speculatively executed transactions that had to be executed again
+    pub(crate) speculative_transactions_reexecuted_total: Counter,
 }
 
 impl BlockValidationMetrics {
@@ -478,6 +512,7 @@ mod tests {
             executor,
             input.clone_transactions_recovered().map(Ok::<_, BlockExecutionError>),
             state_hook,
+            None,
         );
 
         // Check if hook was called (it might not be if finish() fails early)
@@ -534,6 +569,7 @@ mod tests {
             executor,
             input.clone_transactions_recovered().map(Ok::<_, BlockExecutionError>),
             state_hook,
+            None,
         );
 
         let snapshot = snapshotter.snapshot().into_vec();
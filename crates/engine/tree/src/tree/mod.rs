@@ -18,8 +18,8 @@ use reth_chain_state::{
 };
 use reth_consensus::{Consensus, FullConsensus};
 use reth_engine_primitives::{
-    BeaconEngineMessage, BeaconOnNewPayloadError, ConsensusEngineEvent, ExecutionPayload,
-    ForkchoiceStateTracker, OnForkChoiceUpdated,
+    BeaconEngineMessage, BeaconOnNewPayloadError, ChainReorg, ConsensusEngineEvent,
+    ExecutionPayload, ForkchoiceStateTracker, OnForkChoiceUpdated,
 };
 use reth_errors::{ConsensusError, ProviderResult};
 use reth_evm::{ConfigureEvm, OnStateHook};
@@ -51,6 +51,7 @@ use tokio::sync::{
 use tracing::*;
 
 mod block_buffer;
+mod cache_snapshot;
 mod cached_state;
 pub mod error;
 mod instrumented_state;
@@ -68,10 +69,12 @@ mod trie_updates;
 
 use crate::tree::error::AdvancePersistenceError;
 pub use block_buffer::BlockBuffer;
+pub use cache_snapshot::{ExecutionCacheSnapshot, ExecutionCacheSnapshotError};
 pub use invalid_headers::InvalidHeaderCache;
 pub use payload_processor::*;
 pub use payload_validator::{BasicEngineValidator, EngineValidator};
 pub use persistence_state::PersistenceState;
+use persistence_state::PersistenceTrigger;
 pub use reth_engine_primitives::TreeConfig;
 
 pub mod state;
@@ -276,6 +279,14 @@ where
     engine_kind: EngineApiKind,
     /// The EVM configuration.
     evm_config: C,
+    /// Block of the new chain of a reorg exceeding the maximum reorg depth that was approved to be
+    /// applied.
+    approved_reorg: Option<B256>,
+    /// New head of the last reorg that was refused for exceeding the maximum reorg depth.
+    rejected_reorg: Option<B256>,
+    /// Whether a reorg exceeding the maximum reorg depth was refused and the engine should shut
+    /// down.
+    halted_on_deep_reorg: bool,
 }
 
 impl<N, P: Debug, T: PayloadTypes + Debug, V: Debug, C> std::fmt::Debug
@@ -300,6 +311,9 @@ where
             .field("metrics", &self.metrics)
             .field("engine_kind", &self.engine_kind)
             .field("evm_config", &self.evm_config)
+            .field("approved_reorg", &self.approved_reorg)
+            .field("rejected_reorg", &self.rejected_reorg)
+            .field("halted_on_deep_reorg", &self.halted_on_deep_reorg)
             .finish()
     }
 }
@@ -356,6 +370,9 @@ where
             incoming_tx,
             engine_kind,
             evm_config,
+            approved_reorg: None,
+            rejected_reorg: None,
+            halted_on_deep_reorg: false,
         }
     }
 
@@ -382,6 +399,7 @@ where
 
         let persistence_state = PersistenceState {
             last_persisted_block: BlockNumHash::new(best_block_number, header.hash()),
+            last_persisted_at: Some(Instant::now()),
             rx: None,
         };
 
@@ -429,6 +447,10 @@ where
                         error!(target: "engine::tree", %fatal, "insert block fatal error");
                         return
                     }
+                    if self.halted_on_deep_reorg {
+                        error!(target: "engine::tree", "Refused reorg deeper than the maximum reorg depth, shutting down");
+                        return
+                    }
                 }
                 Ok(None) => {
                     debug!(target: "engine::tree", "received no engine message for some time, while waiting for persistence task to complete");
@@ -574,6 +596,52 @@ where
         Ok(outcome)
     }
 
+    /// Executes a candidate block before the `newPayload` call for it arrives.
+    ///
+    /// A valid candidate is inserted into the tree like any other executed block, so that the
+    /// `newPayload` call for it finds the block already executed. Invalid candidates are discarded
+    /// without being marked as invalid and without invoking the
+    /// [`InvalidBlockHook`](reth_engine_primitives::InvalidBlockHook), the payload
+    /// is validated again when it arrives via `newPayload`.
+    fn on_candidate_payload(
+        &mut self,
+        payload: T::ExecutionData,
+    ) -> Result<(), InsertBlockFatalError> {
+        let num_hash = payload.num_hash();
+        if !self.backfill_sync_state.is_idle() ||
+            num_hash.number <= self.state.tree_state.canonical_block_number()
+        {
+            // outdated candidate or no state to execute it on
+            return Ok(())
+        }
+
+        // candidates are never buffered, they're only useful if they can be executed right away
+        if self.state_provider_builder(payload.parent_hash())?.is_none() {
+            debug!(target: "engine::tree", block=?num_hash, "Skipping candidate block with unknown parent");
+            return Ok(())
+        }
+
+        let start = Instant::now();
+        let inserted = self.insert_block_or_payload(
+            payload.block_with_parent(),
+            payload,
+            |validator, payload, ctx| validator.validate_payload(payload, ctx.with_candidate()),
+            |this, payload| Ok(this.payload_validator.ensure_well_formed_payload(payload)?),
+        );
+        match inserted {
+            Ok(InsertPayloadOk::Inserted(BlockStatus::Valid)) => {
+                self.metrics.engine.executed_candidate_blocks.increment(1);
+                debug!(target: "engine::tree", block=?num_hash, elapsed=?start.elapsed(), "Executed candidate block");
+            }
+            Ok(_) => {}
+            Err(error) => {
+                debug!(target: "engine::tree", block=?num_hash, %error, "Discarding invalid candidate block");
+            }
+        }
+
+        Ok(())
+    }
+
     /// Processes a payload during normal sync operation.
     ///
     /// Returns:
@@ -1144,7 +1212,9 @@ where
                 // transaction pool operate with the correct chain state after
                 // forkchoice update processing.
 
-                if self.config.unwind_canonical_header() {
+                if self.config.unwind_canonical_header() &&
+                    self.check_canonical_unwind_depth(&canonical_header)?
+                {
                     self.update_latest_block_to_canonical_ancestor(&canonical_header)?;
                 }
             }
@@ -1169,6 +1239,11 @@ where
 
         // Ensure we can apply a new chain update for the head block
         if let Some(chain_update) = self.on_new_head(state.head_block_hash)? {
+            if !self.check_reorg_depth(&chain_update) {
+                // the reorg was refused, keep the current canonical chain until it's approved
+                return Ok(Some(TreeOutcome::new(OnForkChoiceUpdated::syncing())));
+            }
+
             let tip = chain_update.tip().clone_sealed_header();
             self.on_canonical_chain_update(chain_update);
 
@@ -1328,13 +1403,28 @@ where
 
         if !self.persistence_state.in_progress() {
             if let Some(new_tip_num) = self.find_disk_reorg()? {
-                self.remove_blocks(new_tip_num)
-            } else if self.should_persist() {
+                if self.check_disk_reorg_depth(new_tip_num)? {
+                    self.remove_blocks(new_tip_num)
+                }
+            } else if let Some(trigger) = self.persistence_trigger() {
                 let blocks_to_persist = self.get_canonical_blocks_to_persist()?;
+                debug!(target: "engine::tree", ?trigger, count = blocks_to_persist.len(), "Triggered persistence");
                 self.persist_blocks(blocks_to_persist);
             }
         }
 
+        self.metrics.engine.blocks_ahead_of_disk.set(
+            self.state
+                .tree_state
+                .canonical_block_number()
+                .saturating_sub(self.persistence_state.last_persisted_block.number)
+                as f64,
+        );
+        self.metrics
+            .engine
+            .executed_blocks_estimated_size
+            .set(self.state.tree_state.estimated_size() as f64);
+
         Ok(())
     }
 
@@ -1455,6 +1545,12 @@ where
                                 // handle the event if any
                                 self.on_maybe_tree_event(maybe_event)?;
                             }
+                            BeaconEngineMessage::ExecuteCandidate { payload } => {
+                                self.on_candidate_payload(payload)?;
+                            }
+                            BeaconEngineMessage::ApproveReorg { head } => {
+                                self.on_approve_reorg(head)?;
+                            }
                         }
                     }
                 }
@@ -1619,7 +1715,9 @@ where
     ///
     /// This will update the tracked canonical in memory state and do the necessary housekeeping.
     fn make_canonical(&mut self, target: B256) -> ProviderResult<()> {
-        if let Some(chain_update) = self.on_new_head(target)? {
+        if let Some(chain_update) = self.on_new_head(target)? &&
+            self.check_reorg_depth(&chain_update)
+        {
             self.on_canonical_chain_update(chain_update);
         }
 
@@ -1687,15 +1785,62 @@ where
     /// Returns true if the canonical chain length minus the last persisted
     /// block is greater than or equal to the persistence threshold and
     /// backfill is not running.
-    pub const fn should_persist(&self) -> bool {
+    pub fn should_persist(&self) -> bool {
+        self.persistence_trigger().is_some()
+    }
+
+    /// Returns the reason to persist canonical blocks now, if there is any.
+    ///
+    /// Finalized blocks that are not persisted yet take precedence if
+    /// [`TreeConfig::persist_finalized`] is enabled. Otherwise, blocks are persisted once the
+    /// estimated size of the blocks in memory exceeds [`TreeConfig::persistence_memory_threshold`]
+    /// or, if there is no memory threshold, once the number of canonical blocks in memory exceeds
+    /// [`TreeConfig::persistence_threshold`]. Independently, blocks are persisted once the last
+    /// persistence is older than [`TreeConfig::persistence_max_age`].
+    fn persistence_trigger(&self) -> Option<PersistenceTrigger> {
         if !self.backfill_sync_state.is_idle() {
             // can't persist if backfill is running
-            return false
+            return None
+        }
+
+        let last_persisted_number = self.persistence_state.last_persisted_block.number;
+        let canonical_head_number = self.state.tree_state.canonical_block_number();
+
+        if self.config.persist_finalized() &&
+            self.canonical_in_memory_state.get_finalized_num_hash().is_some_and(|finalized| {
+                finalized.number > last_persisted_number &&
+                    finalized.number <= canonical_head_number
+            })
+        {
+            return Some(PersistenceTrigger::Finalized)
         }
 
-        let min_block = self.persistence_state.last_persisted_block.number;
-        self.state.tree_state.canonical_block_number().saturating_sub(min_block) >
+        if canonical_head_number.saturating_sub(self.config.memory_block_buffer_target()) <=
+            last_persisted_number
+        {
+            // nothing to persist outside of the memory block buffer
+            return None
+        }
+
+        if let Some(memory_threshold) = self.config.persistence_memory_threshold() {
+            if self.state.tree_state.estimated_size() as u64 > memory_threshold {
+                return Some(PersistenceTrigger::Memory)
+            }
+        } else if canonical_head_number.saturating_sub(last_persisted_number) >
             self.config.persistence_threshold()
+        {
+            return Some(PersistenceTrigger::Threshold)
+        }
+
+        if self.config.persistence_max_age().is_some_and(|max_age| {
+            self.persistence_state
+                .last_persisted_at
+                .is_some_and(|last_persisted_at| last_persisted_at.elapsed() >= max_age)
+        }) {
+            return Some(PersistenceTrigger::Age)
+        }
+
+        None
     }
 
     /// Returns a batch of consecutive canonical blocks to persist in the range
@@ -1713,9 +1858,14 @@ where
         let last_persisted_number = self.persistence_state.last_persisted_block.number;
         let canonical_head_number = self.state.tree_state.canonical_block_number();
 
-        // Persist only up to block buffer target
-        let target_number =
+        // Persist only up to block buffer target, unless finalized blocks are persisted eagerly
+        let mut target_number =
             canonical_head_number.saturating_sub(self.config.memory_block_buffer_target());
+        if self.config.persist_finalized() &&
+            let Some(finalized) = self.canonical_in_memory_state.get_finalized_num_hash()
+        {
+            target_number = target_number.max(finalized.number.min(canonical_head_number));
+        }
 
         debug!(
             target: "engine::tree",
@@ -1754,7 +1904,9 @@ where
         // If we have an on-disk reorg, we need to handle it first before touching the in-memory
         // state.
         if let Some(remove_above) = self.find_disk_reorg()? {
-            self.remove_blocks(remove_above);
+            if self.check_disk_reorg_depth(remove_above)? {
+                self.remove_blocks(remove_above);
+            }
             return Ok(())
         }
 
@@ -2234,9 +2386,13 @@ where
             trace!(target: "engine::tree", ?new_first, ?old_first, "Reorg detected, new and old first blocks");
 
             self.update_reorg_metrics(old.len());
+            self.emit_event(ConsensusEngineEvent::CanonicalChainReorged(Box::new(chain_reorg(
+                new, old,
+            ))));
             self.reinsert_reorged_blocks(new.clone());
             self.reinsert_reorged_blocks(old.clone());
         }
+        self.rejected_reorg = None;
 
         // update the tracked in-memory state with the new chain
         self.canonical_in_memory_state.update_chain(chain_update);
@@ -2255,6 +2411,116 @@ where
         ));
     }
 
+    /// Returns `true` if the given chain update doesn't exceed the maximum reorg depth, or was
+    /// approved to be applied.
+    fn check_reorg_depth(&mut self, chain_update: &NewCanonicalChain<N>) -> bool {
+        let NewCanonicalChain::Reorg { new, old } = chain_update else { return true };
+        !self.exceeds_max_reorg_depth(old.len() as u64) || self.on_deep_reorg(chain_reorg(new, old))
+    }
+
+    /// Returns `true` if unwinding the canonical chain to the given canonical ancestor doesn't
+    /// exceed the maximum reorg depth, or was approved to be applied.
+    fn check_canonical_unwind_depth(
+        &mut self,
+        ancestor: &SealedHeader<N::BlockHeader>,
+    ) -> ProviderResult<bool> {
+        let head = self.state.tree_state.current_canonical_head;
+        if !self.exceeds_max_reorg_depth(head.number.saturating_sub(ancestor.number())) {
+            return Ok(true)
+        }
+
+        let (fork_block, old) = self.canonical_blocks_above(head, ancestor.number())?;
+        Ok(self.on_deep_reorg(ChainReorg { fork_block, old, new: Vec::new() }))
+    }
+
+    /// Returns `true` if removing the persisted blocks above the given block number to resolve an
+    /// on-disk reorg doesn't exceed the maximum reorg depth, or was approved to be applied.
+    fn check_disk_reorg_depth(&mut self, remove_above: u64) -> ProviderResult<bool> {
+        let persisted = self.persistence_state.last_persisted_block;
+        if !self.exceeds_max_reorg_depth(persisted.number.saturating_sub(remove_above)) {
+            return Ok(true)
+        }
+
+        let (fork_block, old) = self.canonical_blocks_above(persisted, remove_above)?;
+        let (_, new) = self
+            .canonical_blocks_above(self.state.tree_state.current_canonical_head, remove_above)?;
+        Ok(self.on_deep_reorg(ChainReorg { fork_block, old, new }))
+    }
+
+    /// Returns `true` if unwinding the given number of canonical blocks exceeds the maximum reorg
+    /// depth.
+    fn exceeds_max_reorg_depth(&self, depth: u64) -> bool {
+        self.config.max_reorg_depth().is_some_and(|max_depth| depth > max_depth)
+    }
+
+    /// Walks back from `tip` and returns the block at the given number together with the blocks
+    /// above it, in ascending order.
+    fn canonical_blocks_above(
+        &self,
+        tip: BlockNumHash,
+        number: u64,
+    ) -> ProviderResult<(BlockNumHash, Vec<BlockNumHash>)> {
+        let mut blocks = Vec::new();
+        let mut block = tip;
+        while block.number > number {
+            blocks.push(block);
+            block = self
+                .sealed_header_by_hash(block.hash)?
+                .ok_or_else(|| ProviderError::HeaderNotFound(block.hash.into()))?
+                .parent_num_hash();
+        }
+        blocks.reverse();
+        Ok((block, blocks))
+    }
+
+    /// Handles a reorg that exceeds the maximum reorg depth and returns `true` if it was approved
+    /// to be applied.
+    ///
+    /// Refused reorgs are reported once per new head. If [`TreeConfig::halt_on_deep_reorg`] is
+    /// set, the engine shuts down after the current message.
+    fn on_deep_reorg(&mut self, reorg: ChainReorg) -> bool {
+        let max_depth = self.config.max_reorg_depth();
+        // unwinds without a new chain, e.g. to a canonical ancestor, end at the fork block
+        let new_head = reorg.new_head().unwrap_or(reorg.fork_block).hash;
+        if let Some(approved) = self.approved_reorg &&
+            (approved == new_head || reorg.new.iter().any(|block| block.hash == approved))
+        {
+            warn!(target: "engine::tree", depth = reorg.depth(), ?max_depth, fork_block = ?reorg.fork_block, new_head = ?reorg.new_head(), "Applying approved reorg deeper than the maximum reorg depth");
+            self.approved_reorg = None;
+            return true
+        }
+
+        if self.rejected_reorg != Some(new_head) {
+            error!(target: "engine::tree", depth = reorg.depth(), ?max_depth, fork_block = ?reorg.fork_block, new_head = ?reorg.new_head(), "Refusing reorg deeper than the maximum reorg depth");
+            self.rejected_reorg = Some(new_head);
+            self.metrics.tree.rejected_reorgs.increment(1);
+            self.emit_event(ConsensusEngineEvent::DeepReorgRejected(Box::new(reorg)));
+        }
+        self.halted_on_deep_reorg = self.config.halt_on_deep_reorg();
+
+        false
+    }
+
+    /// Approves a reorg whose new chain contains the given block, even if it exceeds the maximum
+    /// reorg depth.
+    ///
+    /// If the last refused reorg switches to a fork in the tree, it is applied right away.
+    /// Refused unwinds to a canonical ancestor and on-disk reorgs are applied on the next
+    /// forkchoice update or persistence run respectively.
+    fn on_approve_reorg(&mut self, head: B256) -> ProviderResult<()> {
+        info!(target: "engine::tree", %head, "Approved reorg");
+        self.approved_reorg = Some(head);
+
+        if let Some(rejected) = self.rejected_reorg &&
+            self.state.tree_state.executed_block_by_hash(rejected).is_some() &&
+            !self.state.tree_state.is_canonical(rejected)
+        {
+            self.make_canonical(rejected)?;
+        }
+
+        Ok(())
+    }
+
     /// This updates metrics based on the given reorg length.
     fn update_reorg_metrics(&self, old_chain_length: usize) {
         self.metrics.tree.reorgs.increment(1);
@@ -2520,6 +2786,19 @@ where
         };
         self.emit_event(EngineApiEvent::BeaconConsensus(engine_event));
 
+        if let Some(mut report) = self.payload_validator.take_validation_report() {
+            report.total_duration = elapsed;
+            report.persistence_in_progress = self.persistence_state.in_progress();
+            report.blocks_pending_persistence = self
+                .state
+                .tree_state
+                .canonical_block_number()
+                .saturating_sub(self.persistence_state.last_persisted_block.number);
+            self.emit_event(EngineApiEvent::BeaconConsensus(ConsensusEngineEvent::BlockValidated(
+                Box::new(report),
+            )));
+        }
+
         self.metrics
             .engine
             .block_insert_total_duration
@@ -2809,6 +3088,25 @@ where
     }
 }
 
+/// Returns the [`ChainReorg`] that replaces the `old` canonical blocks with the `new` ones.
+fn chain_reorg<N: NodePrimitives>(
+    new: &[ExecutedBlock<N>],
+    old: &[ExecutedBlock<N>],
+) -> ChainReorg {
+    let num_hashes = |blocks: &[ExecutedBlock<N>]| {
+        blocks.iter().map(|block| block.recovered_block().num_hash()).collect::<Vec<_>>()
+    };
+    let fork_block = new
+        .first()
+        .map(|block| {
+            let block = block.recovered_block();
+            BlockNumHash::new(block.number() - 1, block.parent_hash())
+        })
+        .unwrap_or_default();
+
+    ChainReorg { fork_block, old: num_hashes(old), new: num_hashes(new) }
+}
+
 /// Block inclusion can be valid, accepted, or invalid. Invalid blocks are returned as an error
 /// variant.
 ///
@@ -51,6 +51,7 @@ use tracing::{debug, debug_span, instrument, warn};
 mod configured_sparse_trie;
 pub mod executor;
 pub mod multiproof;
+pub mod parallel;
 pub mod prewarm;
 pub mod sparse_trie;
 
@@ -148,6 +149,16 @@ where
             prewarm_max_concurrency: config.prewarm_max_concurrency(),
         }
     }
+
+    /// Returns a handle to the execution cache that is shared across blocks.
+    pub(crate) fn execution_cache(&self) -> ExecutionCache {
+        self.execution_cache.clone()
+    }
+
+    /// Returns the cross-block cache size in bytes.
+    pub(crate) const fn cross_block_cache_size(&self) -> u64 {
+        self.cross_block_cache_size
+    }
 }
 
 impl<N, Evm> PayloadProcessor<Evm>
@@ -599,7 +610,7 @@ impl Drop for CacheTaskHandle {
 /// - Prepares data for state root proof computation
 /// - Runs concurrently but must not interfere with cache saves
 #[derive(Clone, Debug, Default)]
-struct ExecutionCache {
+pub(crate) struct ExecutionCache {
     /// Guarded cloneable cache identified by a block hash.
     inner: Arc<RwLock<Option<SavedCache>>>,
 }
@@ -626,6 +637,12 @@ impl ExecutionCache {
             .cloned()
     }
 
+    /// Calls the closure with the tracked cache while holding the lock, so that the cache is not
+    /// updated with the state of another block in the meantime.
+    pub(crate) fn with_saved_cache<R>(&self, f: impl FnOnce(Option<&SavedCache>) -> R) -> R {
+        f(self.inner.read().as_ref())
+    }
+
     /// Clears the tracked cache
     #[expect(unused)]
     pub(crate) fn clear(&self) {
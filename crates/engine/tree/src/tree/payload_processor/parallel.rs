@@ -0,0 +1,891 @@
+//! Parallel block execution driven by a block-level access list (EIP-7928).
+//!
+//! All transactions of a block are executed speculatively in parallel, each against the state the
+//! [`BlockAccessList`] predicts for its position in the block. The results are then committed in
+//! block order by the regular block executor: a speculative result is only committed if every value
+//! the transaction read matches the state left behind by the transactions before it, otherwise the
+//! transaction is executed again on top of the committed state.
+//!
+//! Since the committed state is always produced by the block executor, the outcome is identical to
+//! sequential execution no matter how good the prediction is. A wrong prediction only costs the
+//! time spent on the speculative execution.
+//!
+//! If the block doesn't carry an access list, one is derived by executing all transactions in
+//! parallel against the parent state first. This doubles the speculative work, so blocks that
+//! carry their access list are processed considerably faster.
+
+use crate::tree::precompile_cache::{CachedPrecompile, PrecompileCacheMap};
+use alloy_evm::{
+    block::{BlockExecutor, ExecutableTx},
+    env::BlockEnvironment,
+    Database,
+};
+use alloy_primitives::{map::HashMap, Address, Bytes, B256, U256};
+use alloy_rlp::{Decodable, Encodable, RlpDecodable, RlpEncodable};
+use core::borrow::BorrowMut;
+use rayon::prelude::*;
+use reth_errors::{BlockExecutionError, ProviderError};
+use reth_evm::{execute::ExecutableTxFor, ConfigureEvm, Evm, EvmEnvFor, HaltReasonFor, SpecFor};
+use reth_primitives_traits::SignedTransaction;
+use reth_provider::{AccountReader, BlockHashReader, BytecodeReader, StateProvider};
+use revm::{
+    context::result::ResultAndState,
+    database::State,
+    state::{AccountInfo, Bytecode},
+};
+use tracing::trace;
+
+/// Position of a change in a [`BlockAccessList`]: `0` for the system calls before the first
+/// transaction and `i + 1` for the `i`-th transaction of the block.
+pub type BlockAccessIndex = u64;
+
+/// The changes made to a single account within a block.
+///
+/// Every list holds the value after each change, ordered by [`BlockAccessIndex`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountChanges {
+    /// Values of the changed storage slots.
+    pub storage_changes: HashMap<U256, Vec<(BlockAccessIndex, U256)>>,
+    /// Balance changes.
+    pub balance_changes: Vec<(BlockAccessIndex, U256)>,
+    /// Nonce changes.
+    pub nonce_changes: Vec<(BlockAccessIndex, u64)>,
+    /// Code changes.
+    pub code_changes: Vec<(BlockAccessIndex, Bytecode)>,
+}
+
+/// The state changes made by a block, grouped by account (EIP-7928).
+///
+/// Applying all changes with an index lower than `i + 1` to the parent state yields the state the
+/// `i`-th transaction of the block is executed against.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockAccessList {
+    /// The changes made to each account.
+    pub accounts: HashMap<Address, AccountChanges>,
+}
+
+impl BlockAccessList {
+    /// Decodes the RLP encoding of an access list as defined by EIP-7928.
+    ///
+    /// Storage reads are not needed to predict the state and are skipped.
+    pub fn decode(mut buf: &[u8]) -> alloy_rlp::Result<Self> {
+        let encoded = Vec::<EncodedAccountChanges>::decode(&mut buf)?;
+        if !buf.is_empty() {
+            return Err(alloy_rlp::Error::UnexpectedLength)
+        }
+
+        let accounts = encoded
+            .into_iter()
+            .map(|account| {
+                let changes = AccountChanges {
+                    storage_changes: account
+                        .storage_changes
+                        .into_iter()
+                        .map(|slot| {
+                            let changes = slot
+                                .changes
+                                .into_iter()
+                                .map(|change| (change.block_access_index, change.new_value))
+                                .collect();
+                            (slot.slot, changes)
+                        })
+                        .collect(),
+                    balance_changes: account
+                        .balance_changes
+                        .into_iter()
+                        .map(|change| (change.block_access_index, change.post_balance))
+                        .collect(),
+                    nonce_changes: account
+                        .nonce_changes
+                        .into_iter()
+                        .map(|change| (change.block_access_index, change.new_nonce))
+                        .collect(),
+                    code_changes: account
+                        .code_changes
+                        .into_iter()
+                        .map(|change| {
+                            (change.block_access_index, Bytecode::new_raw(change.new_code))
+                        })
+                        .collect(),
+                };
+                (account.address, changes)
+            })
+            .collect();
+        Ok(Self { accounts })
+    }
+
+    /// Returns the RLP encoding of the access list as defined by EIP-7928, with accounts and
+    /// storage slots in ascending order.
+    pub fn encoded(&self) -> Bytes {
+        let mut accounts = self
+            .accounts
+            .iter()
+            .map(|(address, changes)| {
+                let mut storage_changes = changes
+                    .storage_changes
+                    .iter()
+                    .map(|(slot, changes)| EncodedSlotChanges {
+                        slot: *slot,
+                        changes: changes
+                            .iter()
+                            .map(|(index, value)| EncodedStorageChange {
+                                block_access_index: *index,
+                                new_value: *value,
+                            })
+                            .collect(),
+                    })
+                    .collect::<Vec<_>>();
+                storage_changes.sort_unstable_by_key(|slot| slot.slot);
+                EncodedAccountChanges {
+                    address: *address,
+                    storage_changes,
+                    storage_reads: Vec::new(),
+                    balance_changes: changes
+                        .balance_changes
+                        .iter()
+                        .map(|(index, balance)| EncodedBalanceChange {
+                            block_access_index: *index,
+                            post_balance: *balance,
+                        })
+                        .collect(),
+                    nonce_changes: changes
+                        .nonce_changes
+                        .iter()
+                        .map(|(index, nonce)| EncodedNonceChange {
+                            block_access_index: *index,
+                            new_nonce: *nonce,
+                        })
+                        .collect(),
+                    code_changes: changes
+                        .code_changes
+                        .iter()
+                        .map(|(index, code)| EncodedCodeChange {
+                            block_access_index: *index,
+                            new_code: code.original_bytes(),
+                        })
+                        .collect(),
+                }
+            })
+            .collect::<Vec<_>>();
+        accounts.sort_unstable_by_key(|account| account.address);
+
+        let mut buf = Vec::new();
+        accounts.encode(&mut buf);
+        buf.into()
+    }
+
+    /// Returns the account as seen at `index`, given the account in the parent state.
+    pub fn account_before(
+        &self,
+        address: Address,
+        index: BlockAccessIndex,
+        mut account: Option<AccountInfo>,
+    ) -> Option<AccountInfo> {
+        let Some(changes) = self.accounts.get(&address) else { return account };
+
+        let balance = last_before(&changes.balance_changes, index);
+        let nonce = last_before(&changes.nonce_changes, index);
+        let code = last_before(&changes.code_changes, index);
+        if balance.is_none() && nonce.is_none() && code.is_none() {
+            return account
+        }
+
+        let info = account.get_or_insert_default();
+        if let Some(balance) = balance {
+            info.balance = *balance;
+        }
+        if let Some(nonce) = nonce {
+            info.nonce = *nonce;
+        }
+        if let Some(code) = code {
+            info.code_hash = code.hash_slow();
+            info.code = Some(code.clone());
+        }
+        account
+    }
+
+    /// Returns the value of the storage slot as seen at `index`, or `None` if the slot wasn't
+    /// changed before `index`.
+    pub fn storage_before(
+        &self,
+        address: Address,
+        slot: U256,
+        index: BlockAccessIndex,
+    ) -> Option<U256> {
+        let changes = self.accounts.get(&address)?.storage_changes.get(&slot)?;
+        last_before(changes, index).copied()
+    }
+
+    /// Returns the code with the given hash if it was deployed in the block.
+    fn code_by_hash(&self, code_hash: B256) -> Option<Bytecode> {
+        self.accounts
+            .values()
+            .flat_map(|changes| changes.code_changes.iter())
+            .find(|(_, code)| code.hash_slow() == code_hash)
+            .map(|(_, code)| code.clone())
+    }
+
+    /// Derives an access list from the results of executing every transaction against the parent
+    /// state.
+    ///
+    /// Balance and nonce changes are applied as deltas on top of the changes of the transactions
+    /// before, so that independent changes to the same account, e.g. the fees paid to the
+    /// beneficiary, add up.
+    fn predict<H>(results: &[Option<SpeculativeResult<H>>]) -> Self {
+        let mut access_list = Self::default();
+        for (tx_index, result) in results.iter().enumerate() {
+            if let Some(result) = result {
+                access_list.record(tx_index as BlockAccessIndex + 1, result);
+            }
+        }
+        access_list
+    }
+
+    /// Records the changes of a single speculatively executed transaction.
+    fn record<H>(&mut self, index: BlockAccessIndex, result: &SpeculativeResult<H>) {
+        for (address, account) in &result.result.state {
+            if !account.is_touched() {
+                continue
+            }
+
+            let read = result.reads.accounts.get(address).cloned().flatten().unwrap_or_default();
+            let before =
+                self.account_before(*address, index, Some(read.clone())).unwrap_or_default();
+            let changes = self.accounts.entry(*address).or_default();
+
+            if account.info.balance != read.balance {
+                let balance = if account.info.balance > read.balance {
+                    before.balance.saturating_add(account.info.balance - read.balance)
+                } else {
+                    before.balance.saturating_sub(read.balance - account.info.balance)
+                };
+                changes.balance_changes.push((index, balance));
+            }
+            if account.info.nonce != read.nonce {
+                let nonce = if account.info.nonce > read.nonce {
+                    before.nonce.saturating_add(account.info.nonce - read.nonce)
+                } else {
+                    account.info.nonce
+                };
+                changes.nonce_changes.push((index, nonce));
+            }
+            if account.info.code_hash != read.code_hash &&
+                let Some(code) = &account.info.code
+            {
+                changes.code_changes.push((index, code.clone()));
+            }
+            for (slot, value) in &account.storage {
+                if value.is_changed() {
+                    changes
+                        .storage_changes
+                        .entry(*slot)
+                        .or_default()
+                        .push((index, value.present_value));
+                }
+            }
+        }
+    }
+}
+
+/// Wire format of the changes made to a single account (EIP-7928).
+#[derive(Debug, RlpEncodable, RlpDecodable)]
+struct EncodedAccountChanges {
+    address: Address,
+    storage_changes: Vec<EncodedSlotChanges>,
+    storage_reads: Vec<U256>,
+    balance_changes: Vec<EncodedBalanceChange>,
+    nonce_changes: Vec<EncodedNonceChange>,
+    code_changes: Vec<EncodedCodeChange>,
+}
+
+/// Wire format of the changes made to a single storage slot (EIP-7928).
+#[derive(Debug, RlpEncodable, RlpDecodable)]
+struct EncodedSlotChanges {
+    slot: U256,
+    changes: Vec<EncodedStorageChange>,
+}
+
+#[derive(Debug, RlpEncodable, RlpDecodable)]
+struct EncodedStorageChange {
+    block_access_index: BlockAccessIndex,
+    new_value: U256,
+}
+
+#[derive(Debug, RlpEncodable, RlpDecodable)]
+struct EncodedBalanceChange {
+    block_access_index: BlockAccessIndex,
+    post_balance: U256,
+}
+
+#[derive(Debug, RlpEncodable, RlpDecodable)]
+struct EncodedNonceChange {
+    block_access_index: BlockAccessIndex,
+    new_nonce: u64,
+}
+
+#[derive(Debug, RlpEncodable, RlpDecodable)]
+struct EncodedCodeChange {
+    block_access_index: BlockAccessIndex,
+    new_code: Bytes,
+}
+
+/// Returns the last value in `changes` with an index lower than `index`.
+fn last_before<T>(changes: &[(BlockAccessIndex, T)], index: BlockAccessIndex) -> Option<&T> {
+    changes[..changes.partition_point(|(change_index, _)| *change_index < index)]
+        .last()
+        .map(|(_, value)| value)
+}
+
+/// The values a speculatively executed transaction read.
+#[derive(Debug, Default)]
+struct RecordedReads {
+    /// Accounts that were read.
+    accounts: HashMap<Address, Option<AccountInfo>>,
+    /// Storage slots that were read.
+    storage: HashMap<(Address, U256), U256>,
+    /// Whether the transaction read code that isn't available, in which case the result can't be
+    /// used.
+    missing_code: bool,
+}
+
+impl RecordedReads {
+    /// Returns `true` if all recorded values match the values in the given database.
+    fn is_valid<DB: Database>(&self, db: &mut DB) -> bool {
+        for (address, expected) in &self.accounts {
+            let Ok(actual) = db.basic(*address) else { return false };
+            let matches = match (expected, &actual) {
+                (Some(expected), Some(actual)) => {
+                    expected.balance == actual.balance &&
+                        expected.nonce == actual.nonce &&
+                        expected.code_hash == actual.code_hash
+                }
+                (None, None) => true,
+                _ => false,
+            };
+            if !matches {
+                return false
+            }
+        }
+
+        self.storage
+            .iter()
+            .all(|((address, slot), expected)| db.storage(*address, *slot).ok() == Some(*expected))
+    }
+}
+
+/// The result of speculatively executing a transaction.
+#[derive(Debug)]
+pub(crate) struct SpeculativeResult<H> {
+    /// The execution result and the state changes.
+    result: ResultAndState<H>,
+    /// The values the transaction read.
+    reads: RecordedReads,
+}
+
+/// A [`Database`] that reads the state a [`BlockAccessList`] predicts for a transaction and records
+/// all values that were read.
+#[derive(Debug)]
+struct SpeculativeDatabase<'a, S> {
+    /// The state provider for the parent state.
+    state_provider: &'a S,
+    /// The access list applied on top of the parent state.
+    access_list: Option<&'a BlockAccessList>,
+    /// The position of the transaction in the access list.
+    index: BlockAccessIndex,
+    /// The values read by the transaction.
+    reads: RecordedReads,
+}
+
+impl<S: StateProvider> Database for SpeculativeDatabase<'_, S> {
+    type Error = ProviderError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let mut account = self.state_provider.basic_account(&address)?.map(Into::into);
+        if let Some(access_list) = self.access_list {
+            account = access_list.account_before(address, self.index, account);
+        }
+        self.reads.accounts.insert(address, account.clone());
+        Ok(account)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if let Some(code) = self.access_list.and_then(|list| list.code_by_hash(code_hash)) {
+            return Ok(code)
+        }
+        match self.state_provider.bytecode_by_hash(&code_hash)? {
+            Some(code) => Ok(code.0),
+            None => {
+                self.reads.missing_code = true;
+                Ok(Bytecode::default())
+            }
+        }
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value =
+            match self.access_list.and_then(|list| list.storage_before(address, index, self.index))
+            {
+                Some(value) => value,
+                None => self
+                    .state_provider
+                    .storage(address, B256::new(index.to_be_bytes()))?
+                    .unwrap_or_default(),
+            };
+        self.reads.storage.insert((address, index), value);
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        Ok(self.state_provider.block_hash(number)?.unwrap_or_default())
+    }
+}
+
+/// The speculative results of a block's transactions, committed in order by
+/// [`SpeculativeExecution::execute_transaction`].
+#[derive(Debug)]
+pub(crate) struct SpeculativeExecution<H> {
+    /// Speculative results by transaction index.
+    results: Vec<Option<SpeculativeResult<H>>>,
+    /// The gas limit of the block.
+    block_gas_limit: u64,
+    /// Gas used by the transactions committed so far.
+    gas_used: u64,
+    /// Number of transactions whose speculative result was committed.
+    committed: u64,
+    /// Number of transactions that were executed again.
+    reexecuted: u64,
+}
+
+impl<H> SpeculativeExecution<H> {
+    /// Returns the number of transactions whose speculative result was committed.
+    pub(crate) const fn committed(&self) -> u64 {
+        self.committed
+    }
+
+    /// Returns the number of transactions that were executed again.
+    pub(crate) const fn reexecuted(&self) -> u64 {
+        self.reexecuted
+    }
+
+    /// Commits the speculative result of the transaction at `index` if it's still valid on top of
+    /// the executor's state, otherwise executes the transaction.
+    ///
+    /// Returns the gas used by the transaction.
+    pub(crate) fn execute_transaction<DB, E, Tx>(
+        &mut self,
+        executor: &mut E,
+        index: usize,
+        tx: Tx,
+    ) -> Result<u64, BlockExecutionError>
+    where
+        DB: Database,
+        Tx: ExecutableTx<E>,
+        E: BlockExecutor<
+            Evm: Evm<DB: BorrowMut<State<DB>>, HaltReason = H>,
+            Transaction: SignedTransaction,
+        >,
+    {
+        let available_gas = self.block_gas_limit.saturating_sub(self.gas_used);
+        let speculative =
+            self.results.get_mut(index).and_then(Option::take).filter(|speculative| {
+                let db: &mut State<DB> = executor.evm_mut().db_mut().borrow_mut();
+                tx.tx().gas_limit() <= available_gas && speculative.reads.is_valid(db)
+            });
+
+        let gas_used = if let Some(speculative) = speculative {
+            self.committed += 1;
+            executor.commit_transaction(speculative.result, tx)?
+        } else {
+            trace!(target: "engine::tree::payload_processor::parallel", index, "No valid speculative result, executing transaction");
+            self.reexecuted += 1;
+            executor.execute_transaction(tx)?
+        };
+        self.gas_used += gas_used;
+        Ok(gas_used)
+    }
+}
+
+/// Speculatively executes the transactions of a block in parallel.
+///
+/// If no access list is given, one is derived from executing the transactions against the parent
+/// state first.
+pub(crate) fn execute_speculatively<Evm, S, Tx>(
+    evm_config: &Evm,
+    evm_env: &EvmEnvFor<Evm>,
+    state_provider: &S,
+    precompile_cache_map: Option<&PrecompileCacheMap<SpecFor<Evm>>>,
+    transactions: &[Tx],
+    access_list: Option<&BlockAccessList>,
+) -> SpeculativeExecution<HaltReasonFor<Evm>>
+where
+    Evm: ConfigureEvm,
+    S: StateProvider,
+    Tx: ExecutableTxFor<Evm> + Sync,
+{
+    let predicted;
+    let access_list = match access_list {
+        Some(access_list) => access_list,
+        None => {
+            let results = execute_all(
+                evm_config,
+                evm_env,
+                state_provider,
+                precompile_cache_map,
+                transactions,
+                None,
+            );
+            predicted = BlockAccessList::predict(&results);
+            &predicted
+        }
+    };
+
+    let results = execute_all(
+        evm_config,
+        evm_env,
+        state_provider,
+        precompile_cache_map,
+        transactions,
+        Some(access_list),
+    );
+
+    SpeculativeExecution {
+        results,
+        block_gas_limit: evm_env.block_env.gas_limit(),
+        gas_used: 0,
+        committed: 0,
+        reexecuted: 0,
+    }
+}
+
+/// Executes all transactions in parallel, each against the state the access list predicts for it,
+/// or against the parent state if no access list is given.
+fn execute_all<Evm, S, Tx>(
+    evm_config: &Evm,
+    evm_env: &EvmEnvFor<Evm>,
+    state_provider: &S,
+    precompile_cache_map: Option<&PrecompileCacheMap<SpecFor<Evm>>>,
+    transactions: &[Tx],
+    access_list: Option<&BlockAccessList>,
+) -> Vec<Option<SpeculativeResult<HaltReasonFor<Evm>>>>
+where
+    Evm: ConfigureEvm,
+    S: StateProvider,
+    Tx: ExecutableTxFor<Evm> + Sync,
+{
+    transactions
+        .par_iter()
+        .enumerate()
+        .map_init(
+            || {
+                let db = SpeculativeDatabase {
+                    state_provider,
+                    access_list,
+                    index: 0,
+                    reads: RecordedReads::default(),
+                };
+                let spec_id = *evm_env.spec_id();
+                let mut evm = evm_config.evm_with_env(db, evm_env.clone());
+                if let Some(precompile_cache_map) = precompile_cache_map {
+                    // Only cache pure precompiles to avoid issues with stateful precompiles
+                    evm.precompiles_mut().map_pure_precompiles(|address, precompile| {
+                        CachedPrecompile::wrap(
+                            precompile,
+                            precompile_cache_map.cache_for_address(*address),
+                            spec_id,
+                            None,
+                        )
+                    });
+                }
+                evm
+            },
+            |evm, (tx_index, tx)| {
+                evm.db_mut().index = tx_index as BlockAccessIndex + 1;
+                let result = evm.transact(tx);
+                let reads = core::mem::take(&mut evm.db_mut().reads);
+                let result = result.ok()?;
+                (!reads.missing_code).then_some(SpeculativeResult { result, reads })
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{constants::ETH_TO_WEI, Header, TxLegacy};
+    use alloy_primitives::{Bytes, Signature, TxKind};
+    use reth_chainspec::{ChainSpecBuilder, MAINNET};
+    use reth_ethereum_primitives::{Block, BlockBody, Receipt, Transaction, TransactionSigned};
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_execution_types::BlockExecutionResult;
+    use reth_primitives_traits::RecoveredBlock;
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_revm::{database::StateProviderDatabase, db::BundleState};
+    use revm::database::states::bundle_state::BundleRetention;
+    use std::sync::Arc;
+
+    /// Increments the value of storage slot 0.
+    const COUNTER_CODE: [u8; 10] = [0x60, 0x00, 0x54, 0x60, 0x01, 0x01, 0x60, 0x00, 0x55, 0x00];
+
+    const COUNTER: Address = Address::with_last_byte(0x20);
+    const RECIPIENT: Address = Address::with_last_byte(0x10);
+
+    fn provider() -> MockEthProvider {
+        let provider = MockEthProvider::default();
+        provider.extend_accounts((1..=4).map(|i| {
+            (Address::with_last_byte(i), ExtendedAccount::new(0, U256::from(ETH_TO_WEI)))
+        }));
+        provider.add_account(
+            COUNTER,
+            ExtendedAccount::new(1, U256::ZERO).with_bytecode(Bytes::from_static(&COUNTER_CODE)),
+        );
+        provider
+    }
+
+    fn evm_config() -> EthEvmConfig {
+        EthEvmConfig::new(Arc::new(ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build()))
+    }
+
+    fn call(nonce: u64, to: Address, value: u64) -> TransactionSigned {
+        TransactionSigned::new_unhashed(
+            Transaction::Legacy(TxLegacy {
+                nonce,
+                gas_price: 10,
+                gas_limit: 100_000,
+                to: TxKind::Call(to),
+                value: U256::from(value),
+                ..Default::default()
+            }),
+            Signature::test_signature(),
+        )
+    }
+
+    /// A block with independent transfers, transactions of the same sender and transactions
+    /// incrementing the same storage slot.
+    fn block() -> RecoveredBlock<Block> {
+        block_with([
+            (1, call(0, RECIPIENT, 1)),
+            (2, call(0, RECIPIENT, 2)),
+            (1, call(1, RECIPIENT, 3)),
+            (3, call(0, COUNTER, 0)),
+            (4, call(0, COUNTER, 0)),
+            (2, call(1, COUNTER, 0)),
+        ])
+    }
+
+    /// A block in which every transaction depends on the one before, because all of them
+    /// increment the same storage slot.
+    fn conflicting_block() -> RecoveredBlock<Block> {
+        block_with((0..8).map(|i| (i % 4 + 1, call(i as u64 / 4, COUNTER, 0))))
+    }
+
+    fn block_with(
+        transactions: impl IntoIterator<Item = (u8, TransactionSigned)>,
+    ) -> RecoveredBlock<Block> {
+        let (transactions, senders): (Vec<_>, Vec<_>) = transactions
+            .into_iter()
+            .map(|(sender, tx)| (tx, Address::with_last_byte(sender)))
+            .unzip();
+
+        RecoveredBlock::new_unhashed(
+            Block {
+                header: Header {
+                    number: 1,
+                    timestamp: 1,
+                    gas_limit: 30_000_000,
+                    base_fee_per_gas: Some(7),
+                    beneficiary: Address::with_last_byte(0x30),
+                    ..Default::default()
+                },
+                body: BlockBody {
+                    transactions,
+                    ommers: vec![],
+                    withdrawals: Some(Default::default()),
+                },
+            },
+            senders,
+        )
+    }
+
+    /// Builds the access list of the block by executing its transactions one after another.
+    fn sequential_access_list(
+        provider: &MockEthProvider,
+        block: &RecoveredBlock<Block>,
+    ) -> BlockAccessList {
+        let evm_config = evm_config();
+        let evm_env = evm_config.evm_env(block.header()).unwrap();
+        let mut access_list = BlockAccessList::default();
+        for (tx_index, tx) in block.transactions_recovered().collect::<Vec<_>>().iter().enumerate()
+        {
+            let index = tx_index as BlockAccessIndex + 1;
+            // the access list holds the changes of all transactions before, so the transaction
+            // reads the same state as in sequential execution
+            let (result, reads) = {
+                let db = SpeculativeDatabase {
+                    state_provider: provider,
+                    access_list: Some(&access_list),
+                    index,
+                    reads: RecordedReads::default(),
+                };
+                let mut evm = evm_config.evm_with_env(db, evm_env.clone());
+                let result = evm.transact(tx).unwrap();
+                (result, core::mem::take(&mut evm.db_mut().reads))
+            };
+            access_list.record(index, &SpeculativeResult { result, reads });
+        }
+        access_list
+    }
+
+    fn execute_sequential(
+        provider: &MockEthProvider,
+        block: &RecoveredBlock<Block>,
+    ) -> (BlockExecutionResult<Receipt>, BundleState) {
+        let evm_config = evm_config();
+        let mut db = State::builder()
+            .with_database(StateProviderDatabase::new(provider))
+            .with_bundle_update()
+            .build();
+        let result = evm_config
+            .executor_for_block(&mut db, block)
+            .unwrap()
+            .execute_block(block.transactions_recovered())
+            .unwrap();
+        db.merge_transitions(BundleRetention::Reverts);
+        (result, db.take_bundle())
+    }
+
+    fn execute_parallel(
+        provider: &MockEthProvider,
+        block: &RecoveredBlock<Block>,
+        access_list: Option<&BlockAccessList>,
+    ) -> (
+        BlockExecutionResult<Receipt>,
+        BundleState,
+        SpeculativeExecution<HaltReasonFor<EthEvmConfig>>,
+    ) {
+        let evm_config = evm_config();
+        let evm_env = evm_config.evm_env(block.header()).unwrap();
+        let transactions = block.transactions_recovered().collect::<Vec<_>>();
+        let mut speculative = execute_speculatively(
+            &evm_config,
+            &evm_env,
+            provider,
+            None,
+            &transactions,
+            access_list,
+        );
+
+        let mut db = State::builder()
+            .with_database(StateProviderDatabase::new(provider))
+            .with_bundle_update()
+            .build();
+        let mut executor = evm_config.executor_for_block(&mut db, block).unwrap();
+        executor.apply_pre_execution_changes().unwrap();
+        for (index, tx) in transactions.into_iter().enumerate() {
+            speculative.execute_transaction(&mut executor, index, tx).unwrap();
+        }
+        let (_, result) = executor.finish().unwrap();
+        db.merge_transitions(BundleRetention::Reverts);
+        (result, db.take_bundle(), speculative)
+    }
+
+    #[test]
+    fn test_parallel_execution_matches_sequential() {
+        let provider = provider();
+        let block = block();
+
+        let (expected_result, expected_state) = execute_sequential(&provider, &block);
+        let (result, state, speculative) = execute_parallel(&provider, &block, None);
+
+        assert_eq!(result, expected_result);
+        assert_eq!(state, expected_state);
+        assert_eq!(speculative.committed() + speculative.reexecuted(), 6);
+        assert!(speculative.committed() > 0);
+    }
+
+    #[test]
+    fn test_wrong_access_list_falls_back_to_sequential() {
+        let provider = provider();
+        let block = block();
+
+        // pretend the recipient and the counter slot are never changed
+        let mut access_list = BlockAccessList::default();
+        access_list.accounts.entry(RECIPIENT).or_default().balance_changes.push((0, U256::ZERO));
+        access_list
+            .accounts
+            .entry(COUNTER)
+            .or_default()
+            .storage_changes
+            .insert(U256::ZERO, vec![(0, U256::ZERO)]);
+
+        let (expected_result, expected_state) = execute_sequential(&provider, &block);
+        let (result, state, speculative) = execute_parallel(&provider, &block, Some(&access_list));
+
+        assert_eq!(result, expected_result);
+        assert_eq!(state, expected_state);
+        assert!(speculative.reexecuted() > 0);
+    }
+
+    #[test]
+    fn test_block_access_list_avoids_reexecution() {
+        let provider = provider();
+        let block = conflicting_block();
+        let (expected_result, expected_state) = execute_sequential(&provider, &block);
+
+        // deriving the access list mispredicts the counter, so transactions are executed again
+        let (result, state, speculative) = execute_parallel(&provider, &block, None);
+        assert_eq!(result, expected_result);
+        assert_eq!(state, expected_state);
+        assert!(speculative.reexecuted() > 0);
+
+        // with the access list of the block every speculative result is committed, so the commit
+        // loop doesn't execute any transaction and isn't slower than sequential execution
+        let access_list = sequential_access_list(&provider, &block);
+        let (result, state, speculative) = execute_parallel(&provider, &block, Some(&access_list));
+        assert_eq!(result, expected_result);
+        assert_eq!(state, expected_state);
+        assert_eq!(speculative.committed(), 8);
+        assert_eq!(speculative.reexecuted(), 0);
+    }
+
+    #[test]
+    fn test_block_access_list_rlp_roundtrip() {
+        let provider = provider();
+        let access_list = sequential_access_list(&provider, &block());
+        assert!(access_list.accounts.contains_key(&COUNTER));
+
+        let encoded = access_list.encoded();
+        assert_eq!(BlockAccessList::decode(&encoded).unwrap(), access_list);
+        // accounts are encoded in a canonical order
+        assert_eq!(BlockAccessList::decode(&encoded).unwrap().encoded(), encoded);
+
+        let mut trailing = encoded.to_vec();
+        trailing.push(0x80);
+        assert!(BlockAccessList::decode(&trailing).is_err());
+    }
+
+    #[test]
+    fn test_access_list_view() {
+        let address = Address::with_last_byte(1);
+        let mut access_list = BlockAccessList::default();
+        let changes = access_list.accounts.entry(address).or_default();
+        changes.balance_changes = vec![(1, U256::from(10)), (3, U256::from(30))];
+        changes.nonce_changes = vec![(2, 5)];
+        changes.storage_changes.insert(U256::ZERO, vec![(1, U256::from(1))]);
+
+        let parent = Some(AccountInfo { balance: U256::from(1), ..Default::default() });
+        assert_eq!(access_list.account_before(address, 0, parent.clone()), parent);
+        assert_eq!(access_list.account_before(address, 1, parent.clone()), parent);
+
+        let account = access_list.account_before(address, 3, parent).unwrap();
+        assert_eq!(account.balance, U256::from(10));
+        assert_eq!(account.nonce, 5);
+
+        let account = access_list.account_before(address, 4, None).unwrap();
+        assert_eq!(account.balance, U256::from(30));
+
+        assert_eq!(access_list.storage_before(address, U256::ZERO, 1), None);
+        assert_eq!(access_list.storage_before(address, U256::ZERO, 2), Some(U256::from(1)));
+        assert_eq!(access_list.storage_before(address, U256::from(1), 2), None);
+    }
+}
@@ -121,10 +121,11 @@ where
                 ParallelStateRootError::Other(format!("could not calculate state root: {e:?}"))
             })?;
 
-        self.metrics.sparse_trie_final_update_duration_histogram.record(start.elapsed());
+        let root_elapsed = start.elapsed();
+        self.metrics.sparse_trie_final_update_duration_histogram.record(root_elapsed);
         self.metrics.sparse_trie_total_duration_histogram.record(now.elapsed());
 
-        Ok(StateRootComputeOutcome { state_root, trie_updates })
+        Ok(StateRootComputeOutcome { state_root, trie_updates, root_elapsed })
     }
 }
 
@@ -136,6 +137,8 @@ pub struct StateRootComputeOutcome {
     pub state_root: B256,
     /// The trie updates.
     pub trie_updates: TrieUpdates,
+    /// Time spent computing the root once all proofs were revealed in the sparse trie.
+    pub root_elapsed: Duration,
 }
 
 /// Updates the sparse trie with the given proofs and state, and returns the elapsed time.
@@ -1,11 +1,16 @@
 //! Types and traits for validating blocks and payloads.
 
 use crate::tree::{
+    cache_snapshot::ExecutionCacheSnapshot,
     cached_state::CachedStateProvider,
     error::{InsertBlockError, InsertBlockErrorKind, InsertPayloadError},
     executor::WorkloadExecutor,
     instrumented_state::InstrumentedStateProvider,
-    payload_processor::{multiproof::MultiProofConfig, PayloadProcessor},
+    payload_processor::{
+        multiproof::MultiProofConfig,
+        parallel::{execute_speculatively, BlockAccessList},
+        PayloadProcessor,
+    },
     precompile_cache::{CachedPrecompile, CachedPrecompileMetrics, PrecompileCacheMap},
     sparse_trie::StateRootComputeOutcome,
     EngineApiMetrics, EngineApiTreeState, ExecutionEnv, PayloadHandle, StateProviderBuilder,
@@ -14,11 +19,12 @@ use crate::tree::{
 use alloy_consensus::transaction::Either;
 use alloy_eips::{eip1898::BlockWithParent, NumHash};
 use alloy_evm::Evm;
-use alloy_primitives::B256;
+use alloy_primitives::{Bytes, B256};
 use reth_chain_state::{CanonicalInMemoryState, ExecutedBlock};
 use reth_consensus::{ConsensusError, FullConsensus};
 use reth_engine_primitives::{
-    ConfigureEngineEvm, ExecutableTxIterator, ExecutionPayload, InvalidBlockHook, PayloadValidator,
+    BlockValidationReport, ConfigureEngineEvm, ExecutableTxIterator, ExecutionPayload,
+    InvalidBlockHook, PayloadValidator, StateRootSource,
 };
 use reth_errors::{BlockExecutionError, ProviderResult};
 use reth_evm::{
@@ -29,7 +35,7 @@ use reth_payload_primitives::{
     BuiltPayload, InvalidPayloadAttributesError, NewPayloadError, PayloadTypes,
 };
 use reth_primitives_traits::{
-    AlloyBlockHeader, BlockTy, GotExpected, NodePrimitives, RecoveredBlock, SealedHeader,
+    AlloyBlockHeader, BlockBody, BlockTy, GotExpected, NodePrimitives, RecoveredBlock, SealedHeader,
 };
 use reth_provider::{
     providers::OverlayStateProviderFactory, BlockExecutionOutput, BlockReader,
@@ -40,7 +46,12 @@ use reth_provider::{
 use reth_revm::db::State;
 use reth_trie::{updates::TrieUpdates, HashedPostState, TrieInput};
 use reth_trie_parallel::root::{ParallelStateRoot, ParallelStateRootError};
-use std::{collections::HashMap, sync::Arc, time::Instant};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tracing::{debug, debug_span, error, info, instrument, trace, warn};
 
 /// Context providing access to tree state during validation.
@@ -52,6 +63,8 @@ pub struct TreeCtx<'a, N: NodePrimitives> {
     state: &'a mut EngineApiTreeState<N>,
     /// Reference to the canonical in-memory state
     canonical_in_memory_state: &'a CanonicalInMemoryState<N>,
+    /// Whether the block is a candidate executed ahead of its `newPayload` call
+    is_candidate: bool,
 }
 
 impl<'a, N: NodePrimitives> std::fmt::Debug for TreeCtx<'a, N> {
@@ -59,6 +72,7 @@ impl<'a, N: NodePrimitives> std::fmt::Debug for TreeCtx<'a, N> {
         f.debug_struct("TreeCtx")
             .field("state", &"EngineApiTreeState")
             .field("canonical_in_memory_state", &self.canonical_in_memory_state)
+            .field("is_candidate", &self.is_candidate)
             .finish()
     }
 }
@@ -69,7 +83,20 @@ impl<'a, N: NodePrimitives> TreeCtx<'a, N> {
         state: &'a mut EngineApiTreeState<N>,
         canonical_in_memory_state: &'a CanonicalInMemoryState<N>,
     ) -> Self {
-        Self { state, canonical_in_memory_state }
+        Self { state, canonical_in_memory_state, is_candidate: false }
+    }
+
+    /// Marks the block as a candidate executed ahead of its `newPayload` call.
+    ///
+    /// Invalid candidates are discarded, so the [`InvalidBlockHook`] is not invoked for them.
+    pub const fn with_candidate(mut self) -> Self {
+        self.is_candidate = true;
+        self
+    }
+
+    /// Returns whether the block is a candidate executed ahead of its `newPayload` call
+    pub const fn is_candidate(&self) -> bool {
+        self.is_candidate
     }
 
     /// Returns a reference to the engine tree state
@@ -123,6 +150,8 @@ where
     validator: V,
     /// A cleared trie input, kept around to be reused so allocations can be minimized.
     trie_input: Option<TrieInput>,
+    /// Timing breakdown of the most recently validated block.
+    validation_report: Option<BlockValidationReport>,
 }
 
 impl<N, P, Evm, V> BasicEngineValidator<P, Evm, V>
@@ -167,9 +196,24 @@ where
             metrics: EngineApiMetrics::default(),
             validator,
             trie_input: Default::default(),
+            validation_report: None,
         }
     }
 
+    /// Returns a handle to save the execution and precompile caches of this validator to the given
+    /// path and to restore them from it.
+    pub fn execution_cache_snapshot(&self, path: PathBuf) -> ExecutionCacheSnapshot<P, Evm> {
+        ExecutionCacheSnapshot::new(
+            path,
+            self.provider.clone(),
+            self.evm_config.clone(),
+            self.payload_processor.execution_cache(),
+            self.precompile_cache_map.clone(),
+            self.payload_processor.cross_block_cache_size(),
+            self.config.execution_cache_snapshot_size(),
+        )
+    }
+
     /// Converts a [`BlockOrPayload`] to a recovered block.
     pub fn convert_to_block<T: PayloadTypes<BuiltPayload: BuiltPayload<Primitives = N>>>(
         &self,
@@ -334,6 +378,8 @@ where
             };
         }
 
+        self.validation_report = None;
+
         let parent_hash = input.parent_hash();
         let block_num_hash = input.num_hash();
 
@@ -400,7 +446,9 @@ where
         );
 
         // Execute the block and handle any execution errors
-        let output = match if self.config.state_provider_metrics() {
+        let execution_start = Instant::now();
+        let (output, speculative_execution_duration) = match if self.config.state_provider_metrics()
+        {
             let state_provider = InstrumentedStateProvider::from_state_provider(&state_provider);
             let result = self.execute_block(&state_provider, env, &input, &mut handle);
             state_provider.record_total_latency();
@@ -412,32 +460,46 @@ where
             Err(err) => return self.handle_execution_error(input, err, &parent_block),
         };
 
+        let execution_duration =
+            execution_start.elapsed().saturating_sub(speculative_execution_duration);
+        let (cache_hits, cache_misses) = state_provider.hits_and_misses();
+
         // after executing the block we can stop executing transactions
         handle.stop_prewarming_execution();
 
         let block = self.convert_to_block(input)?;
 
+        let post_execution_start = Instant::now();
         let hashed_state = ensure_ok_post_block!(
             self.validate_post_execution(&block, &parent_block, &output, &mut ctx),
             block
         );
+        let post_execution_validation_duration = post_execution_start.elapsed();
 
         debug!(target: "engine::tree::payload_validator", "Calculating block state root");
 
         let root_time = Instant::now();
 
         let mut maybe_state_root = None;
+        let mut state_root_source = StateRootSource::Synchronous;
+        let mut sparse_trie_root_duration = Duration::ZERO;
 
         match strategy {
             StateRootStrategy::StateRootTask => {
                 debug!(target: "engine::tree::payload_validator", "Using sparse trie state root algorithm");
                 match handle.state_root() {
-                    Ok(StateRootComputeOutcome { state_root, trie_updates }) => {
+                    Ok(StateRootComputeOutcome {
+                        state_root,
+                        trie_updates,
+                        root_elapsed: trie_root_elapsed,
+                    }) => {
                         let elapsed = root_time.elapsed();
                         info!(target: "engine::tree::payload_validator", ?state_root, ?elapsed, "State root task finished");
                         // we double check the state root here for good measure
                         if state_root == block.header().state_root() {
-                            maybe_state_root = Some((state_root, trie_updates, elapsed))
+                            maybe_state_root = Some((state_root, trie_updates, elapsed));
+                            state_root_source = StateRootSource::SparseTrie;
+                            sparse_trie_root_duration = trie_root_elapsed;
                         } else {
                             warn!(
                                 target: "engine::tree::payload_validator",
@@ -468,6 +530,7 @@ where
                             "Regular root task finished"
                         );
                         maybe_state_root = Some((result.0, result.1, elapsed));
+                        state_root_source = StateRootSource::Parallel;
                     }
                     Err(error) => {
                         debug!(target: "engine::tree::payload_validator", %error, "Parallel state root computation failed");
@@ -511,7 +574,7 @@ where
                 &block,
                 &output,
                 Some((&trie_output, state_root)),
-                ctx.state_mut(),
+                &mut ctx,
             );
             let block_state_root = block.header().state_root();
             return Err(InsertBlockError::new(
@@ -527,6 +590,28 @@ where
         // terminate prewarming task with good state output
         handle.terminate_caching(Some(&output.state));
 
+        let multiproof_wait_duration = if state_root_source == StateRootSource::SparseTrie {
+            root_elapsed.saturating_sub(sparse_trie_root_duration)
+        } else {
+            Duration::ZERO
+        };
+        self.validation_report = Some(BlockValidationReport {
+            number: block_num_hash.number,
+            hash: block_num_hash.hash,
+            gas_used: block.header().gas_used(),
+            transaction_count: block.body().transaction_count() as u64,
+            execution_duration,
+            speculative_execution_duration,
+            post_execution_validation_duration,
+            state_root_duration: root_elapsed,
+            multiproof_wait_duration,
+            sparse_trie_root_duration,
+            state_root_source,
+            cache_hits,
+            cache_misses,
+            ..Default::default()
+        });
+
         Ok(ExecutedBlock {
             recovered_block: Arc::new(block),
             execution_output: Arc::new(ExecutionOutcome::from((output, block_num_hash.number))),
@@ -568,14 +653,17 @@ where
     }
 
     /// Executes a block with the given state provider
+    ///
+    /// Returns the execution output and the time spent on speculative parallel execution before
+    /// the transactions were committed.
     #[instrument(level = "debug", target = "engine::tree::payload_validator", skip_all)]
     fn execute_block<S, Err, T>(
         &mut self,
         state_provider: S,
         env: ExecutionEnv<Evm>,
         input: &BlockOrPayload<T>,
-        handle: &mut PayloadHandle<impl ExecutableTxFor<Evm>, Err>,
-    ) -> Result<BlockExecutionOutput<N::Receipt>, InsertBlockErrorKind>
+        handle: &mut PayloadHandle<impl ExecutableTxFor<Evm> + Sync, Err>,
+    ) -> Result<(BlockExecutionOutput<N::Receipt>, Duration), InsertBlockErrorKind>
     where
         S: StateProvider,
         Err: core::error::Error + Send + Sync + 'static,
@@ -615,15 +703,50 @@ where
 
         let execution_start = Instant::now();
         let state_hook = Box::new(handle.state_hook());
-        let output = self.metrics.execute_metered(
-            executor,
-            handle.iter_transactions().map(|res| res.map_err(BlockExecutionError::other)),
-            state_hook,
-        )?;
+        let mut speculative_execution_duration = Duration::ZERO;
+        let output = if self.config.parallel_execution() {
+            let transactions = handle
+                .iter_transactions()
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(BlockExecutionError::other)?;
+            let access_list = input.block_access_list().and_then(|encoded| {
+                BlockAccessList::decode(encoded)
+                    .inspect_err(|err| {
+                        warn!(target: "engine::tree::payload_validator", %err, "Failed to decode block access list, deriving it instead");
+                    })
+                    .ok()
+            });
+            let speculative = execute_speculatively(
+                &self.evm_config,
+                &env.evm_env,
+                &state_provider,
+                (!self.config.precompile_cache_disabled()).then_some(&self.precompile_cache_map),
+                &transactions,
+                access_list.as_ref(),
+            );
+            speculative_execution_duration = execution_start.elapsed();
+            self.metrics
+                .block_validation
+                .speculative_execution_duration
+                .record(speculative_execution_duration.as_secs_f64());
+            self.metrics.execute_metered(
+                executor,
+                transactions.into_iter().map(Ok),
+                state_hook,
+                Some(speculative),
+            )?
+        } else {
+            self.metrics.execute_metered(
+                executor,
+                handle.iter_transactions().map(|res| res.map_err(BlockExecutionError::other)),
+                state_hook,
+                None,
+            )?
+        };
         let execution_finish = Instant::now();
         let execution_time = execution_finish.duration_since(execution_start);
-        debug!(target: "engine::tree::payload_validator", elapsed = ?execution_time, "Executed block");
-        Ok(output)
+        debug!(target: "engine::tree::payload_validator", elapsed = ?execution_time, speculative = ?speculative_execution_duration, "Executed block");
+        Ok((output, speculative_execution_duration))
     }
 
     /// Compute state root for the given hashed post state in parallel.
@@ -699,7 +822,7 @@ where
 
         if let Err(err) = self.consensus.validate_block_post_execution(block, output) {
             // call post-block hook
-            self.on_invalid_block(parent_block, block, output, None, ctx.state_mut());
+            self.on_invalid_block(parent_block, block, output, None, ctx);
             return Err(err.into())
         }
 
@@ -709,7 +832,7 @@ where
             self.validator.validate_block_post_execution_with_hashed_state(&hashed_state, block)
         {
             // call post-block hook
-            self.on_invalid_block(parent_block, block, output, None, ctx.state_mut());
+            self.on_invalid_block(parent_block, block, output, None, ctx);
             return Err(err.into())
         }
 
@@ -875,9 +998,13 @@ where
         block: &RecoveredBlock<N::Block>,
         output: &BlockExecutionOutput<N::Receipt>,
         trie_updates: Option<(&TrieUpdates, B256)>,
-        state: &mut EngineApiTreeState<N>,
+        ctx: &mut TreeCtx<'_, N>,
     ) {
-        if state.invalid_headers.get(&block.hash()).is_some() {
+        if ctx.is_candidate() {
+            // candidates are validated again when their `newPayload` call arrives
+            return
+        }
+        if ctx.state_mut().invalid_headers.get(&block.hash()).is_some() {
             // we already marked this block as invalid
             return
         }
@@ -993,6 +1120,14 @@ pub trait EngineValidator<
         block: RecoveredBlock<N::Block>,
         ctx: TreeCtx<'_, N>,
     ) -> ValidationOutcome<N>;
+
+    /// Takes the timing breakdown of the most recently validated block.
+    ///
+    /// Returns `None` if no block was validated successfully since the last call, or if the
+    /// validator does not collect validation reports.
+    fn take_validation_report(&mut self) -> Option<BlockValidationReport> {
+        None
+    }
 }
 
 impl<N, Types, P, Evm, V> EngineValidator<Types> for BasicEngineValidator<P, Evm, V>
@@ -1041,6 +1176,10 @@ where
     ) -> ValidationOutcome<N> {
         self.validate_block_with_state(BlockOrPayload::Block(block), ctx)
     }
+
+    fn take_validation_report(&mut self) -> Option<BlockValidationReport> {
+        self.validation_report.take()
+    }
 }
 
 /// Enum representing either block or payload being validated.
@@ -1085,6 +1224,16 @@ impl<T: PayloadTypes> BlockOrPayload<T> {
         }
     }
 
+    /// Returns the RLP encoded block-level access list (EIP-7928) carried by the block.
+    ///
+    /// Blocks downloaded from the network never carry an access list.
+    pub fn block_access_list(&self) -> Option<&Bytes> {
+        match self {
+            Self::Payload(payload) => payload.block_access_list(),
+            Self::Block(_) => None,
+        }
+    }
+
     /// Returns a string showing whether or not this is a block or payload.
     pub const fn type_name(&self) -> &'static str {
         match self {
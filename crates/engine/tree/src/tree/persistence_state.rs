@@ -33,6 +33,9 @@ pub struct PersistenceState {
     ///
     /// This tracks the chain height that is persisted on disk
     pub(crate) last_persisted_block: BlockNumHash,
+    /// The time the last persistence task finished, or the tree was created if no blocks were
+    /// persisted yet.
+    pub(crate) last_persisted_at: Option<Instant>,
     /// Receiver end of channel where the result of the persistence task will be
     /// sent when done. A None value means there's no persistence task in progress.
     pub(crate) rx:
@@ -80,11 +83,25 @@ impl PersistenceState {
     ) {
         trace!(target: "engine::tree", block= %last_persisted_block_number, hash=%last_persisted_block_hash, "updating persistence state");
         self.rx = None;
+        self.last_persisted_at = Some(Instant::now());
         self.last_persisted_block =
             BlockNumHash::new(last_persisted_block_number, last_persisted_block_hash);
     }
 }
 
+/// The reason canonical blocks are persisted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PersistenceTrigger {
+    /// The number of canonical blocks in memory exceeds the persistence threshold.
+    Threshold,
+    /// The estimated size of the blocks in memory exceeds the memory threshold.
+    Memory,
+    /// The last persistence is older than the maximum age.
+    Age,
+    /// The finalized block is not persisted yet.
+    Finalized,
+}
+
 /// The currently running persistence action.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum CurrentPersistenceAction {
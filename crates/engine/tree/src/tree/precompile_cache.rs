@@ -1,13 +1,13 @@
 //! Contains a precompile cache backed by `schnellru::LruMap` (LRU by length).
 
 use alloy_primitives::Bytes;
+use dashmap::DashMap;
 use parking_lot::Mutex;
 use reth_evm::precompiles::{DynPrecompile, Precompile, PrecompileInput};
 use revm::precompile::{PrecompileId, PrecompileOutput, PrecompileResult};
 use revm_primitives::Address;
 use schnellru::LruMap;
 use std::{
-    collections::HashMap,
     hash::{Hash, Hasher},
     sync::Arc,
 };
@@ -16,8 +16,11 @@ use std::{
 const MAX_CACHE_SIZE: u32 = 10_000;
 
 /// Stores caches for each precompile.
+///
+/// Clones share the same caches, including those of precompiles that are first called after the
+/// map was cloned.
 #[derive(Debug, Clone, Default)]
-pub struct PrecompileCacheMap<S>(HashMap<Address, PrecompileCache<S>>)
+pub struct PrecompileCacheMap<S>(Arc<DashMap<Address, PrecompileCache<S>>>)
 where
     S: Eq + Hash + std::fmt::Debug + Send + Sync + Clone;
 
@@ -25,9 +28,38 @@ impl<S> PrecompileCacheMap<S>
 where
     S: Eq + Hash + std::fmt::Debug + Send + Sync + Clone + 'static,
 {
-    pub(crate) fn cache_for_address(&mut self, address: Address) -> PrecompileCache<S> {
+    pub(crate) fn cache_for_address(&self, address: Address) -> PrecompileCache<S> {
         self.0.entry(address).or_default().clone()
     }
+
+    /// Returns the cached results of all precompiles for the given spec, most recently used
+    /// first.
+    pub(crate) fn entries(&self, spec_id: &S) -> Vec<(Address, Bytes, PrecompileOutput)> {
+        self.0
+            .iter()
+            .flat_map(|cache| {
+                let address = *cache.key();
+                cache
+                    .0
+                    .lock()
+                    .iter()
+                    .filter(|(key, _)| key.0 .0 == *spec_id)
+                    .map(|(key, entry)| (address, key.0 .1.clone(), entry.0.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Inserts the result of a precompile call for the given spec.
+    pub(crate) fn insert(
+        &self,
+        address: Address,
+        spec_id: S,
+        input: Bytes,
+        output: PrecompileOutput,
+    ) {
+        self.cache_for_address(address).insert(CacheKey::new(spec_id, input), CacheEntry(output));
+    }
 }
 
 /// Cache for precompiles, for each input stores the result.
@@ -306,7 +338,7 @@ mod tests {
         let address1 = Address::repeat_byte(1);
         let address2 = Address::repeat_byte(2);
 
-        let mut cache_map = PrecompileCacheMap::default();
+        let cache_map = PrecompileCacheMap::default();
 
         // create the first precompile with a specific output
         let precompile1: DynPrecompile = (PrecompileId::custom("custom"), {
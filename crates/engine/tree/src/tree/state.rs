@@ -38,6 +38,10 @@ pub struct TreeState<N: NodePrimitives = EthPrimitives> {
     pub(crate) current_canonical_head: BlockNumHash,
     /// The engine API variant of this handler
     pub(crate) engine_kind: EngineApiKind,
+    /// Sum of the estimated sizes of all executed blocks in bytes.
+    ///
+    /// See [`ExecutedBlock::estimated_size`].
+    pub(crate) estimated_size: usize,
 }
 
 impl<N: NodePrimitives> TreeState<N> {
@@ -49,6 +53,7 @@ impl<N: NodePrimitives> TreeState<N> {
             current_canonical_head,
             parent_to_child: HashMap::default(),
             engine_kind,
+            estimated_size: 0,
         }
     }
 
@@ -62,6 +67,11 @@ impl<N: NodePrimitives> TreeState<N> {
         self.blocks_by_hash.len()
     }
 
+    /// Returns the estimated memory held by all executed blocks in bytes.
+    pub(crate) const fn estimated_size(&self) -> usize {
+        self.estimated_size
+    }
+
     /// Returns the [`ExecutedBlock`] by hash.
     pub(crate) fn executed_block_by_hash(&self, hash: B256) -> Option<&ExecutedBlock<N>> {
         self.blocks_by_hash.get(&hash)
@@ -102,6 +112,7 @@ impl<N: NodePrimitives> TreeState<N> {
             return;
         }
 
+        self.estimated_size += executed.estimated_size();
         self.blocks_by_hash.insert(hash, executed.clone());
 
         self.blocks_by_number.entry(block_number).or_default().push(executed);
@@ -116,6 +127,7 @@ impl<N: NodePrimitives> TreeState<N> {
     /// The removed block and the block hashes of its children.
     fn remove_by_hash(&mut self, hash: B256) -> Option<(ExecutedBlock<N>, HashSet<B256>)> {
         let executed = self.blocks_by_hash.remove(&hash)?;
+        self.estimated_size = self.estimated_size.saturating_sub(executed.estimated_size());
 
         // Remove this block from collection of children of its parent block.
         let parent_entry = self.parent_to_child.entry(executed.recovered_block().parent_hash());
@@ -20,7 +20,9 @@ use alloy_rpc_types_engine::{
 use assert_matches::assert_matches;
 use reth_chain_state::{test_utils::TestBlockBuilder, BlockState};
 use reth_chainspec::{ChainSpec, HOLESKY, MAINNET};
-use reth_engine_primitives::{EngineApiValidator, ForkchoiceStatus, NoopInvalidBlockHook};
+use reth_engine_primitives::{
+    BlockValidationReport, EngineApiValidator, ForkchoiceStatus, NoopInvalidBlockHook,
+};
 use reth_ethereum_consensus::EthBeaconConsensus;
 use reth_ethereum_engine_primitives::EthEngineTypes;
 use reth_ethereum_primitives::{Block, EthPrimitives};
@@ -35,6 +37,7 @@ use std::{
         mpsc::{channel, Receiver, Sender},
         Arc,
     },
+    time::Duration,
 };
 use tokio::sync::oneshot;
 
@@ -86,6 +89,48 @@ impl EngineApiValidator<EthEngineTypes> for MockEngineValidator {
     }
 }
 
+/// Engine validator that returns blocks executed ahead of time, recording for every execution
+/// whether it was for a candidate block
+#[derive(Debug, Default)]
+struct PreExecutedValidator {
+    blocks: HashMap<B256, ExecutedBlock>,
+    executions: Arc<std::sync::Mutex<Vec<bool>>>,
+}
+
+impl EngineValidator<EthEngineTypes> for PreExecutedValidator {
+    fn validate_payload_attributes_against_header(
+        &self,
+        _attr: &alloy_rpc_types_engine::PayloadAttributes,
+        _header: &alloy_consensus::Header,
+    ) -> Result<(), reth_payload_primitives::InvalidPayloadAttributesError> {
+        unreachable!()
+    }
+
+    fn ensure_well_formed_payload(
+        &self,
+        payload: ExecutionData,
+    ) -> Result<RecoveredBlock<Block>, NewPayloadError> {
+        Ok(self.blocks[&payload.block_hash()].recovered_block().clone())
+    }
+
+    fn validate_payload(
+        &mut self,
+        payload: ExecutionData,
+        ctx: TreeCtx<'_, EthPrimitives>,
+    ) -> ValidationOutcome<EthPrimitives> {
+        self.executions.lock().unwrap().push(ctx.is_candidate());
+        Ok(self.blocks[&payload.block_hash()].clone())
+    }
+
+    fn validate_block(
+        &mut self,
+        _block: RecoveredBlock<Block>,
+        _ctx: TreeCtx<'_, EthPrimitives>,
+    ) -> ValidationOutcome<EthPrimitives> {
+        unreachable!()
+    }
+}
+
 /// This is a test channel that allows you to `release` any value that is in the channel.
 ///
 /// If nothing has been sent, then the next value will be immediately sent.
@@ -249,12 +294,14 @@ impl TestHarness {
             parent_hash = hash;
         }
 
+        let estimated_size = blocks.iter().map(|block| block.estimated_size()).sum();
         self.tree.state.tree_state = TreeState {
             blocks_by_hash,
             blocks_by_number,
             current_canonical_head: blocks.last().unwrap().recovered_block().num_hash(),
             parent_to_child,
             engine_kind: EngineApiKind::Ethereum,
+            estimated_size,
         };
 
         let last_executed_block = blocks.last().unwrap().clone();
@@ -272,6 +319,29 @@ impl TestHarness {
         self
     }
 
+    /// Replaces the engine validator of the tree, keeping its state
+    fn with_validator<V: EngineValidator<EthEngineTypes>>(
+        self,
+        validator: V,
+    ) -> EngineApiTreeHandler<EthPrimitives, MockEthProvider, EthEngineTypes, V, MockEvmConfig>
+    {
+        let tree = self.tree;
+        EngineApiTreeHandler::new(
+            tree.provider,
+            tree.consensus,
+            validator,
+            tree.outgoing,
+            tree.state,
+            tree.canonical_in_memory_state,
+            tree.persistence,
+            tree.persistence_state,
+            tree.payload_builder,
+            tree.config,
+            tree.engine_kind,
+            tree.evm_config,
+        )
+    }
+
     const fn with_backfill_state(mut self, state: BackfillSyncState) -> Self {
         self.tree.backfill_sync_state = state;
         self
@@ -436,6 +506,11 @@ impl ValidatorTestHarness {
         result
     }
 
+    /// Take the validation report of the last successfully validated block
+    fn take_validation_report(&mut self) -> Option<BlockValidationReport> {
+        EngineValidator::<EthEngineTypes>::take_validation_report(&mut self.validator)
+    }
+
     /// Get validation metrics for testing
     fn validation_call_count(&self) -> usize {
         self.metrics.total_calls()
@@ -866,6 +941,135 @@ fn test_tree_state_on_new_head_deep_fork() {
     }
 }
 
+/// Test that reorgs exceeding the maximum reorg depth are refused until they're approved
+#[test]
+fn test_reorg_exceeding_max_reorg_depth() {
+    reth_tracing::init_test_tracing();
+
+    let chain_spec = MAINNET.clone();
+    let mut test_block_builder = TestBlockBuilder::eth();
+    let blocks: Vec<_> = test_block_builder.get_executed_blocks(0..5).collect();
+    let mut test_harness = TestHarness::new(chain_spec).with_blocks(blocks.clone());
+    test_harness.tree.config = test_harness.tree.config.clone().with_max_reorg_depth(Some(2));
+
+    // a fork from block 1 unwinds 3 canonical blocks
+    let fork = test_block_builder.create_fork(blocks[1].recovered_block(), 4);
+    for block in &fork {
+        test_harness.tree.state.tree_state.insert_executed(ExecutedBlock {
+            recovered_block: Arc::new(block.clone()),
+            execution_output: Arc::new(ExecutionOutcome::default()),
+            hashed_state: Arc::new(HashedPostState::default()),
+            trie_updates: Arc::new(TrieUpdates::default()),
+        });
+    }
+    let canonical_head = blocks[4].recovered_block().num_hash();
+    let fork_head = fork.last().unwrap().num_hash();
+
+    let mut reorg_events = || {
+        let mut events = Vec::new();
+        while let Ok(event) = test_harness.from_tree_rx.try_recv() {
+            if let EngineApiEvent::BeaconConsensus(
+                event @ (ConsensusEngineEvent::CanonicalChainReorged(_) |
+                ConsensusEngineEvent::DeepReorgRejected(_)),
+            ) = event
+            {
+                events.push(event);
+            }
+        }
+        events
+    };
+
+    // the reorg is refused and reported once
+    test_harness.tree.make_canonical(fork_head.hash).unwrap();
+    test_harness.tree.make_canonical(fork_head.hash).unwrap();
+    assert_eq!(test_harness.tree.state.tree_state.current_canonical_head, canonical_head);
+    assert!(!test_harness.tree.halted_on_deep_reorg);
+    let events = reorg_events();
+    assert_eq!(events.len(), 1);
+    let ConsensusEngineEvent::DeepReorgRejected(reorg) = &events[0] else {
+        panic!("unexpected event: {:?}", events[0])
+    };
+    assert_eq!(reorg.fork_block, blocks[1].recovered_block().num_hash());
+    assert_eq!(reorg.depth(), 3);
+    assert_eq!(reorg.new_head(), Some(fork_head));
+
+    // approving a block of the new chain applies the refused reorg
+    test_harness
+        .tree
+        .on_engine_message(FromEngine::Request(
+            BeaconEngineMessage::ApproveReorg { head: fork[0].hash() }.into(),
+        ))
+        .unwrap();
+    assert_eq!(test_harness.tree.state.tree_state.current_canonical_head, fork_head);
+    assert!(test_harness.tree.approved_reorg.is_none());
+    let events = reorg_events();
+    assert_eq!(events.len(), 1);
+    let ConsensusEngineEvent::CanonicalChainReorged(reorg) = &events[0] else {
+        panic!("unexpected event: {:?}", events[0])
+    };
+    assert_eq!(reorg.old.len(), 3);
+    assert_eq!(reorg.new.len(), 4);
+
+    // without an approval, the engine shuts down if configured
+    test_harness.tree.config = test_harness.tree.config.clone().with_halt_on_deep_reorg(true);
+    test_harness.tree.make_canonical(canonical_head.hash).unwrap();
+    assert_eq!(test_harness.tree.state.tree_state.current_canonical_head, fork_head);
+    assert!(test_harness.tree.halted_on_deep_reorg);
+}
+
+/// Test that unwinds to a canonical ancestor and on-disk reorgs are subject to the maximum reorg
+/// depth
+#[test]
+fn test_unwind_exceeding_max_reorg_depth() {
+    reth_tracing::init_test_tracing();
+
+    let chain_spec = MAINNET.clone();
+    let mut test_block_builder = TestBlockBuilder::eth();
+    let blocks: Vec<_> = test_block_builder.get_executed_blocks(0..5).collect();
+    let mut test_harness = TestHarness::new(chain_spec).with_blocks(blocks.clone());
+    test_harness.tree.config = test_harness.tree.config.clone().with_max_reorg_depth(Some(2));
+    let ancestor = blocks[1].recovered_block().clone_sealed_header();
+
+    // unwinding 2 blocks is within the limit
+    assert!(test_harness
+        .tree
+        .check_canonical_unwind_depth(&blocks[2].recovered_block().clone_sealed_header())
+        .unwrap());
+
+    // unwinding 3 blocks is refused and reported
+    assert!(!test_harness.tree.check_canonical_unwind_depth(&ancestor).unwrap());
+    let mut rejected = Vec::new();
+    while let Ok(event) = test_harness.from_tree_rx.try_recv() {
+        if let EngineApiEvent::BeaconConsensus(ConsensusEngineEvent::DeepReorgRejected(reorg)) =
+            event
+        {
+            rejected.push(reorg);
+        }
+    }
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].fork_block, ancestor.num_hash());
+    assert_eq!(
+        rejected[0].old,
+        blocks[2..].iter().map(|block| block.recovered_block().num_hash()).collect::<Vec<_>>()
+    );
+    assert!(rejected[0].new.is_empty());
+
+    // removing 3 persisted blocks is refused as well
+    test_harness.tree.persistence_state.last_persisted_block =
+        blocks[4].recovered_block().num_hash();
+    assert!(!test_harness.tree.check_disk_reorg_depth(1).unwrap());
+    assert!(test_harness.tree.check_disk_reorg_depth(2).unwrap());
+
+    // approving the ancestor allows the unwind, without switching the canonical head right away
+    test_harness.tree.on_approve_reorg(ancestor.hash()).unwrap();
+    assert_eq!(
+        test_harness.tree.state.tree_state.current_canonical_head,
+        blocks[4].recovered_block().num_hash()
+    );
+    assert!(test_harness.tree.check_canonical_unwind_depth(&ancestor).unwrap());
+    assert!(test_harness.tree.approved_reorg.is_none());
+}
+
 #[tokio::test]
 async fn test_get_canonical_blocks_to_persist() {
     let chain_spec = MAINNET.clone();
@@ -926,6 +1130,63 @@ async fn test_get_canonical_blocks_to_persist() {
     );
 }
 
+#[tokio::test]
+async fn test_persistence_triggers() {
+    let chain_spec = MAINNET.clone();
+    let mut test_harness = TestHarness::new(chain_spec);
+    let mut test_block_builder = TestBlockBuilder::eth();
+
+    let blocks: Vec<_> = test_block_builder.get_executed_blocks(0..10).collect();
+    test_harness = test_harness.with_blocks(blocks.clone());
+    test_harness.tree.persistence_state.last_persisted_block = blocks[3].recovered_block.num_hash();
+
+    // 6 blocks in memory don't exceed the block threshold
+    test_harness.tree.config =
+        TreeConfig::default().with_persistence_threshold(6).with_memory_block_buffer_target(2);
+    assert_eq!(test_harness.tree.persistence_trigger(), None);
+
+    // the memory threshold replaces the block threshold
+    let estimated_size = test_harness.tree.state.tree_state.estimated_size() as u64;
+    assert!(estimated_size > 0);
+    test_harness.tree.config = TreeConfig::default()
+        .with_persistence_threshold(0)
+        .with_memory_block_buffer_target(2)
+        .with_persistence_memory_threshold(Some(estimated_size));
+    assert_eq!(test_harness.tree.persistence_trigger(), None);
+    test_harness.tree.config = test_harness
+        .tree
+        .config
+        .clone()
+        .with_persistence_memory_threshold(Some(estimated_size - 1));
+    assert_eq!(test_harness.tree.persistence_trigger(), Some(PersistenceTrigger::Memory));
+
+    // blocks are persisted once the last persistence is too old
+    test_harness.tree.config = TreeConfig::default()
+        .with_persistence_threshold(6)
+        .with_memory_block_buffer_target(2)
+        .with_persistence_max_age(Some(Duration::ZERO));
+    test_harness.tree.persistence_state.last_persisted_at = Some(Instant::now());
+    assert_eq!(test_harness.tree.persistence_trigger(), Some(PersistenceTrigger::Age));
+
+    // finalized blocks are persisted even within the memory block buffer
+    test_harness.tree.config = TreeConfig::default()
+        .with_persistence_threshold(6)
+        .with_memory_block_buffer_target(9)
+        .with_persist_finalized(true);
+    assert_eq!(test_harness.tree.persistence_trigger(), None);
+    test_harness
+        .tree
+        .canonical_in_memory_state
+        .set_finalized(blocks[5].recovered_block().clone_sealed_header());
+    assert_eq!(test_harness.tree.persistence_trigger(), Some(PersistenceTrigger::Finalized));
+
+    let blocks_to_persist = test_harness.tree.get_canonical_blocks_to_persist().unwrap();
+    assert_eq!(
+        blocks_to_persist.iter().map(|b| b.recovered_block().number).collect::<Vec<_>>(),
+        vec![4, 5]
+    );
+}
+
 #[tokio::test]
 async fn test_engine_tree_fcu_missing_head() {
     let chain_spec = MAINNET.clone();
@@ -1243,6 +1504,110 @@ fn test_on_new_payload_backfill_buffering() {
     );
 }
 
+/// Test that candidate blocks that can't be executed right away are neither buffered nor marked
+/// invalid, so that the `newPayload` call for them is handled as usual
+#[test]
+fn test_candidate_payload_with_unknown_parent() {
+    reth_tracing::init_test_tracing();
+
+    let s = include_str!("../../test-data/holesky/1.rlp");
+    let data = Bytes::from_str(s).unwrap();
+    let block = Block::decode(&mut data.as_ref()).unwrap();
+    let sealed = block.seal_slow();
+    let hash = sealed.hash();
+    let payload = ExecutionData {
+        payload: ExecutionPayloadV1::from_block_unchecked(hash, &sealed.into_block()).into(),
+        sidecar: ExecutionPayloadSidecar::none(),
+    };
+
+    let mut test_harness = TestHarness::new(HOLESKY.clone());
+
+    test_harness
+        .tree
+        .on_engine_message(FromEngine::Request(
+            BeaconEngineMessage::ExecuteCandidate { payload: payload.clone() }.into(),
+        ))
+        .unwrap();
+
+    assert!(test_harness.tree.state.buffer.block(&hash).is_none());
+    assert!(test_harness.tree.state.tree_state.sealed_header_by_hash(&hash).is_none());
+    assert!(test_harness.tree.state.invalid_headers.get(&hash).is_none());
+
+    let outcome = test_harness.tree.on_new_payload(payload).unwrap();
+    assert!(outcome.outcome.is_syncing());
+    assert!(test_harness.tree.state.buffer.block(&hash).is_some());
+}
+
+/// Test that candidate blocks are ignored during backfill sync
+#[test]
+fn test_candidate_payload_during_backfill() {
+    reth_tracing::init_test_tracing();
+
+    let s = include_str!("../../test-data/holesky/1.rlp");
+    let data = Bytes::from_str(s).unwrap();
+    let block = Block::decode(&mut data.as_ref()).unwrap();
+    let sealed = block.seal_slow();
+    let hash = sealed.hash();
+    let payload = ExecutionPayloadV1::from_block_unchecked(hash, &sealed.into_block());
+
+    let mut test_harness =
+        TestHarness::new(HOLESKY.clone()).with_backfill_state(BackfillSyncState::Active);
+
+    test_harness
+        .tree
+        .on_candidate_payload(ExecutionData {
+            payload: payload.into(),
+            sidecar: ExecutionPayloadSidecar::none(),
+        })
+        .unwrap();
+
+    assert!(test_harness.tree.state.buffer.block(&hash).is_none());
+    assert!(test_harness.tree.state.invalid_headers.get(&hash).is_none());
+}
+
+/// Test that the `newPayload` call for an executed candidate block is answered without executing
+/// the block again
+#[test]
+fn test_candidate_payload_is_not_executed_again() {
+    reth_tracing::init_test_tracing();
+
+    let mut test_block_builder = TestBlockBuilder::eth().with_chain_spec((*MAINNET).clone());
+    let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+    let candidate = blocks[2].clone();
+    let hash = candidate.recovered_block().hash();
+    let payload = ExecutionData {
+        payload: ExecutionPayloadV1::from_block_unchecked(
+            hash,
+            &candidate.recovered_block().clone_sealed_block().into_block(),
+        )
+        .into(),
+        sidecar: ExecutionPayloadSidecar::none(),
+    };
+
+    let validator = PreExecutedValidator {
+        blocks: HashMap::from_iter([(hash, candidate)]),
+        ..Default::default()
+    };
+    let executions = validator.executions.clone();
+    let mut tree = TestHarness::new(MAINNET.clone())
+        .with_blocks(blocks[..2].to_vec())
+        .with_validator(validator);
+
+    tree.on_engine_message(FromEngine::Request(
+        BeaconEngineMessage::ExecuteCandidate { payload: payload.clone() }.into(),
+    ))
+    .unwrap();
+
+    // the candidate is executed as such and inserted into the tree
+    assert_eq!(*executions.lock().unwrap(), vec![true]);
+    assert!(tree.state.tree_state.sealed_header_by_hash(&hash).is_some());
+
+    let outcome = tree.on_new_payload(payload).unwrap();
+    assert!(outcome.outcome.is_valid());
+    assert_eq!(outcome.outcome.latest_valid_hash, Some(hash));
+    assert_eq!(*executions.lock().unwrap(), vec![true]);
+}
+
 /// Test that captures the Engine-API rule where malformed payloads report latestValidHash = None
 #[test]
 fn test_on_new_payload_malformed_payload() {
@@ -1425,6 +1790,32 @@ fn test_validate_block_multiple_scenarios() {
     );
 }
 
+/// Test that a validation report is only available for successfully validated blocks
+#[test]
+fn test_validation_report_after_failed_validation() {
+    reth_tracing::init_test_tracing();
+
+    let mut test_harness = ValidatorTestHarness::new(MAINNET.clone());
+    let mut block_factory = TestBlockFactory::new(MAINNET.as_ref().clone());
+    let genesis_hash = MAINNET.genesis_hash();
+
+    let valid_block = block_factory.create_valid_block(genesis_hash);
+    let valid_block_hash = valid_block.hash();
+    let result = test_harness.validate_block_direct(valid_block);
+    let report = test_harness.take_validation_report();
+    if result.is_ok() {
+        assert_eq!(report.map(|report| report.hash), Some(valid_block_hash));
+    } else {
+        assert!(report.is_none());
+    }
+    // the report is taken only once
+    assert!(test_harness.take_validation_report().is_none());
+
+    let consensus_invalid = block_factory.create_invalid_consensus_block(genesis_hash);
+    assert!(test_harness.validate_block_direct(consensus_invalid).is_err());
+    assert!(test_harness.take_validation_report().is_none());
+}
+
 /// Test suite for the `check_invalid_ancestors` method
 #[cfg(test)]
 mod check_invalid_ancestors_tests {
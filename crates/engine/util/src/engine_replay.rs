@@ -0,0 +1,210 @@
+//! Replays engine API messages recorded by the [`EngineMessageStore`].
+
+use crate::engine_store::{EngineMessageStore, StoredEngineApiMessage};
+use reth_engine_primitives::{ConsensusEngineHandle, ExecutionPayload};
+use reth_payload_primitives::PayloadTypes;
+use std::{path::PathBuf, time::Duration};
+use tokio::time::{sleep_until, Instant};
+use tracing::*;
+
+/// Feeds engine API messages recorded by the [`EngineMessageStore`] into the consensus engine.
+///
+/// Messages are sent one by one in the order they were recorded and every response is awaited
+/// before the next message is sent, which makes the replay deterministic.
+#[derive(Debug)]
+pub struct EngineMessageReplay<T: PayloadTypes> {
+    /// The handle to the consensus engine the messages are sent to.
+    engine: ConsensusEngineHandle<T>,
+    /// The store the recorded messages are read from.
+    store: EngineMessageStore,
+    /// Whether to preserve the original delays between messages. If `false`, messages are sent
+    /// as fast as the engine processes them.
+    original_timing: bool,
+}
+
+impl<T: PayloadTypes> EngineMessageReplay<T> {
+    /// Creates a new replay of the messages recorded at the given path.
+    pub const fn new(
+        engine: ConsensusEngineHandle<T>,
+        path: PathBuf,
+        original_timing: bool,
+    ) -> Self {
+        Self { engine, store: EngineMessageStore::new(path), original_timing }
+    }
+
+    /// Replays all recorded messages and returns once the last response was received.
+    pub async fn run(self) -> eyre::Result<()> {
+        let start = Instant::now();
+        let mut first_received_at = None;
+        let (mut forkchoice_updates, mut new_payloads, mut invalid_payloads) = (0u64, 0u64, 0u64);
+
+        for message in self.store.engine_messages::<T>()? {
+            let (received_at, message) = message?;
+
+            if self.original_timing {
+                let first_received_at = *first_received_at.get_or_insert(received_at);
+                sleep_until(start + Duration::from_millis(received_at - first_received_at)).await;
+            }
+
+            match message {
+                StoredEngineApiMessage::ForkchoiceUpdated { state, payload_attrs, version } => {
+                    let response =
+                        self.engine.fork_choice_updated(state, payload_attrs, version).await?;
+                    forkchoice_updates += 1;
+                    debug!(target: "engine::replay", head = %state.head_block_hash, status = ?response.payload_status.status, "Replayed forkchoice update");
+                }
+                StoredEngineApiMessage::NewPayload { payload } => {
+                    let block_hash = payload.block_hash();
+                    let block_number = payload.block_number();
+                    let status = self.engine.new_payload(payload).await?;
+                    new_payloads += 1;
+                    if status.is_invalid() {
+                        invalid_payloads += 1;
+                        warn!(target: "engine::replay", %block_hash, block_number, ?status, "Replayed payload is invalid");
+                    } else {
+                        debug!(target: "engine::replay", %block_hash, block_number, ?status, "Replayed new payload");
+                    }
+                }
+            }
+        }
+
+        info!(
+            target: "engine::replay",
+            forkchoice_updates,
+            new_payloads,
+            invalid_payloads,
+            elapsed = ?start.elapsed(),
+            "Finished replaying engine API messages"
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, Bloom, Bytes, B256, U256};
+    use alloy_rpc_types_engine::{
+        ExecutionData, ExecutionPayloadSidecar, ExecutionPayloadV1, ForkchoiceState, PayloadStatus,
+        PayloadStatusEnum,
+    };
+    use reth_engine_primitives::{BeaconEngineMessage, OnForkChoiceUpdated};
+    use reth_ethereum_engine_primitives::EthEngineTypes;
+    use reth_payload_primitives::EngineApiMessageVersion;
+    use std::time::SystemTime;
+    use tokio::sync::{mpsc, oneshot};
+
+    fn forkchoice_updated(head: u8) -> BeaconEngineMessage<EthEngineTypes> {
+        BeaconEngineMessage::ForkchoiceUpdated {
+            state: ForkchoiceState {
+                head_block_hash: B256::repeat_byte(head),
+                ..Default::default()
+            },
+            payload_attrs: None,
+            version: EngineApiMessageVersion::default(),
+            tx: oneshot::channel().0,
+        }
+    }
+
+    fn new_payload(number: u64) -> BeaconEngineMessage<EthEngineTypes> {
+        let payload = ExecutionPayloadV1 {
+            parent_hash: B256::ZERO,
+            fee_recipient: Address::ZERO,
+            state_root: B256::ZERO,
+            receipts_root: B256::ZERO,
+            logs_bloom: Bloom::ZERO,
+            prev_randao: B256::ZERO,
+            block_number: number,
+            gas_limit: 30_000_000,
+            gas_used: 0,
+            timestamp: number * 12,
+            extra_data: Bytes::new(),
+            base_fee_per_gas: U256::from(7),
+            block_hash: B256::with_last_byte(number as u8),
+            transactions: Vec::new(),
+        };
+        BeaconEngineMessage::NewPayload {
+            payload: ExecutionData::new(payload.into(), ExecutionPayloadSidecar::none()),
+            tx: oneshot::channel().0,
+        }
+    }
+
+    /// Records a forkchoice update, a new payload 100ms later and another forkchoice update
+    /// 300ms after the first one.
+    fn record(path: PathBuf) {
+        let store = EngineMessageStore::new(path);
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        store.on_message(&forkchoice_updated(1), start).unwrap();
+        store.on_message(&new_payload(1), start + Duration::from_millis(100)).unwrap();
+        store.on_message(&forkchoice_updated(2), start + Duration::from_millis(300)).unwrap();
+    }
+
+    /// Replays the recording and returns every message received by the engine, with the time
+    /// since the replay started.
+    async fn replay(path: PathBuf, original_timing: bool) -> Vec<(String, Duration)> {
+        let (to_engine, mut from_replay) = mpsc::unbounded_channel();
+        let replay = EngineMessageReplay::<EthEngineTypes>::new(
+            ConsensusEngineHandle::new(to_engine),
+            path,
+            original_timing,
+        );
+
+        let start = Instant::now();
+        let engine = tokio::spawn(async move {
+            let mut received = Vec::new();
+            while let Some(message) = from_replay.recv().await {
+                match message {
+                    BeaconEngineMessage::ForkchoiceUpdated { state, tx, .. } => {
+                        received.push((format!("fcu {}", state.head_block_hash), start.elapsed()));
+                        let _ = tx.send(Ok(OnForkChoiceUpdated::syncing()));
+                    }
+                    BeaconEngineMessage::NewPayload { payload, tx } => {
+                        received.push((
+                            format!("new_payload {}", payload.block_number()),
+                            start.elapsed(),
+                        ));
+                        let _ = tx.send(Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing)));
+                    }
+                    message => panic!("unexpected message {message}"),
+                }
+            }
+            received
+        });
+
+        replay.run().await.unwrap();
+        engine.await.unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_with_original_timing() {
+        let dir = tempfile::tempdir().unwrap();
+        record(dir.path().to_path_buf());
+
+        let received = replay(dir.path().to_path_buf(), true).await;
+        assert_eq!(
+            received,
+            vec![
+                (format!("fcu {}", B256::repeat_byte(1)), Duration::ZERO),
+                ("new_payload 1".to_string(), Duration::from_millis(100)),
+                (format!("fcu {}", B256::repeat_byte(2)), Duration::from_millis(300)),
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_as_fast_as_possible() {
+        let dir = tempfile::tempdir().unwrap();
+        record(dir.path().to_path_buf());
+
+        let received = replay(dir.path().to_path_buf(), false).await;
+        assert_eq!(
+            received,
+            vec![
+                (format!("fcu {}", B256::repeat_byte(1)), Duration::ZERO),
+                ("new_payload 1".to_string(), Duration::ZERO),
+                (format!("fcu {}", B256::repeat_byte(2)), Duration::ZERO),
+            ]
+        );
+    }
+}
@@ -4,12 +4,13 @@ use alloy_rpc_types_engine::ForkchoiceState;
 use futures::{Stream, StreamExt};
 use reth_engine_primitives::{BeaconEngineMessage, ExecutionPayload};
 use reth_fs_util as fs;
-use reth_payload_primitives::PayloadTypes;
+use reth_payload_primitives::{EngineApiMessageVersion, PayloadTypes};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
     path::PathBuf,
     pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
     task::{ready, Context, Poll},
     time::SystemTime,
 };
@@ -25,6 +26,9 @@ pub enum StoredEngineApiMessage<T: PayloadTypes> {
         state: ForkchoiceState,
         /// The payload attributes sent in the persisted call, if any.
         payload_attrs: Option<T::PayloadAttributes>,
+        /// The engine API version of the persisted call.
+        #[serde(default)]
+        version: EngineApiMessageVersion,
     },
     /// The on-disk representation of an `engine_newPayload` method call.
     NewPayload {
@@ -35,10 +39,16 @@ pub enum StoredEngineApiMessage<T: PayloadTypes> {
 }
 
 /// This can read and write engine API messages in a specific directory.
+///
+/// Messages are stored as individual JSON files named
+/// `<received_at_millis>-<index>-<kind>-<block_hash>.json`, where `index` is a counter that
+/// preserves the order of messages received within the same millisecond.
 #[derive(Debug)]
 pub struct EngineMessageStore {
     /// The path to the directory that stores the engine API messages.
     path: PathBuf,
+    /// The index of the next stored message.
+    next_index: AtomicU64,
 }
 
 impl EngineMessageStore {
@@ -46,7 +56,7 @@ impl EngineMessageStore {
     ///
     /// The path is expected to be a directory, where individual message JSON files will be stored.
     pub const fn new(path: PathBuf) -> Self {
-        Self { path }
+        Self { path, next_index: AtomicU64::new(0) }
     }
 
     /// Stores the received [`BeaconEngineMessage`] to disk, appending the `received_at` time to the
@@ -61,24 +71,23 @@ impl EngineMessageStore {
     {
         fs::create_dir_all(&self.path)?; // ensure that store path had been created
         let timestamp = received_at.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis();
+        let index = self.next_index.fetch_add(1, Ordering::Relaxed);
         match msg {
-            BeaconEngineMessage::ForkchoiceUpdated {
-                state,
-                payload_attrs,
-                tx: _tx,
-                version: _version,
-            } => {
-                let filename = format!("{}-fcu-{}.json", timestamp, state.head_block_hash);
+            BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx: _tx, version } => {
+                let filename =
+                    format!("{}-{}-fcu-{}.json", timestamp, index, state.head_block_hash);
                 fs::write(
                     self.path.join(filename),
                     serde_json::to_vec(&StoredEngineApiMessage::<T>::ForkchoiceUpdated {
                         state: *state,
                         payload_attrs: payload_attrs.clone(),
+                        version: *version,
                     })?,
                 )?;
             }
             BeaconEngineMessage::NewPayload { payload, tx: _tx } => {
-                let filename = format!("{}-new_payload-{}.json", timestamp, payload.block_hash());
+                let filename =
+                    format!("{}-{}-new_payload-{}.json", timestamp, index, payload.block_hash());
                 fs::write(
                     self.path.join(filename),
                     serde_json::to_vec(&StoredEngineApiMessage::<T>::NewPayload {
@@ -86,19 +95,43 @@ impl EngineMessageStore {
                     })?,
                 )?;
             }
+            // candidates and reorg approvals don't originate from the consensus layer
+            BeaconEngineMessage::ExecuteCandidate { .. } |
+            BeaconEngineMessage::ApproveReorg { .. } => {}
         };
         Ok(())
     }
 
     /// Finds and iterates through any stored engine API message files, ordered by timestamp.
     pub fn engine_messages_iter(&self) -> eyre::Result<impl Iterator<Item = PathBuf>> {
-        let mut filenames_by_ts = BTreeMap::<u64, Vec<PathBuf>>::default();
+        Ok(self.engine_message_files()?.into_iter().map(|(_, path)| path))
+    }
+
+    /// Finds, reads and iterates through any stored engine API messages together with the unix
+    /// timestamp in milliseconds they were received at, ordered by timestamp.
+    pub fn engine_messages<T: PayloadTypes>(
+        &self,
+    ) -> eyre::Result<impl Iterator<Item = eyre::Result<(u64, StoredEngineApiMessage<T>)>>> {
+        Ok(self.engine_message_files()?.into_iter().map(|(timestamp, path)| {
+            let message = serde_json::from_slice(&fs::read(&path)?)?;
+            Ok((timestamp, message))
+        }))
+    }
+
+    /// Returns the paths of all stored engine API message files with their timestamps, in the
+    /// order the messages were received.
+    fn engine_message_files(&self) -> eyre::Result<Vec<(u64, PathBuf)>> {
+        let mut filenames_by_ts = BTreeMap::<(u64, u64), Vec<PathBuf>>::default();
         for entry in fs::read_dir(&self.path)? {
             let entry = entry?;
             let filename = entry.file_name();
             if let Some(filename) = filename.to_str().filter(|n| n.ends_with(".json")) {
-                if let Some(Ok(timestamp)) = filename.split('-').next().map(|n| n.parse::<u64>()) {
-                    filenames_by_ts.entry(timestamp).or_default().push(entry.path());
+                let mut parts = filename.split('-');
+                if let Some(Ok(timestamp)) = parts.next().map(|n| n.parse::<u64>()) {
+                    // files written by older versions have no index
+                    let index =
+                        parts.next().and_then(|n| n.parse::<u64>().ok()).unwrap_or_default();
+                    filenames_by_ts.entry((timestamp, index)).or_default().push(entry.path());
                     tracing::debug!(target: "engine::store", timestamp, filename, "Queued engine API message");
                 } else {
                     tracing::warn!(target: "engine::store", %filename, "Could not parse timestamp from filename")
@@ -107,7 +140,13 @@ impl EngineMessageStore {
                 tracing::warn!(target: "engine::store", ?filename, "Skipping non json file");
             }
         }
-        Ok(filenames_by_ts.into_iter().flat_map(|(_, paths)| paths))
+        Ok(filenames_by_ts
+            .into_iter()
+            .flat_map(|((timestamp, _), mut paths)| {
+                paths.sort_unstable();
+                paths.into_iter().map(move |path| (timestamp, path))
+            })
+            .collect())
     }
 }
 
@@ -0,0 +1,583 @@
+//! Stream wrapper that injects faults into the engine API message stream.
+
+use alloy_primitives::B256;
+use futures::{Stream, StreamExt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use reth_engine_primitives::{BeaconEngineMessage, ExecutionPayload as _};
+use reth_engine_tree::tree::EngineValidator;
+use reth_payload_primitives::{BuiltPayload, PayloadTypes};
+use reth_primitives_traits::{block::Block as _, NodePrimitives, SealedBlock};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+use tokio::{sync::oneshot, time::Sleep};
+use tracing::*;
+
+/// Configuration of the faults injected by [`EngineFaultInjector`].
+///
+/// Every fault is applied independently to each matching message with the configured percentage
+/// chance. All random decisions are drawn from a generator seeded with [`Self::seed`], so the same
+/// seed and the same incoming messages result in the same schedule of faults. If no seed is
+/// configured, a random one is picked and logged, so that the run can be reproduced.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EngineFaultConfig {
+    /// The seed of the random fault schedule.
+    pub seed: Option<u64>,
+    /// The chance in percent that the stream stalls before forwarding a message.
+    pub delay_percent: u8,
+    /// The maximum duration of a stall.
+    pub max_delay: Duration,
+    /// The chance in percent that a new payload is held back and forwarded after the next
+    /// message, or once [`Self::max_delay`] elapsed without another message.
+    pub reorder_percent: u8,
+    /// The chance in percent that a forkchoice update is sent twice.
+    pub duplicate_fcu_percent: u8,
+    /// The chance in percent that a copy of a new payload with a random state root is sent before
+    /// the original payload.
+    pub bad_state_root_percent: u8,
+    /// The chance in percent that a copy of a new payload with a random receipts root is sent
+    /// before the original payload.
+    pub bad_receipts_root_percent: u8,
+}
+
+impl EngineFaultConfig {
+    /// Returns `true` if any fault is configured to be injected.
+    pub const fn is_enabled(&self) -> bool {
+        self.delay_percent > 0 ||
+            self.reorder_percent > 0 ||
+            self.duplicate_fcu_percent > 0 ||
+            self.bad_state_root_percent > 0 ||
+            self.bad_receipts_root_percent > 0
+    }
+}
+
+/// Header field of a new payload that can be corrupted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CorruptedField {
+    StateRoot,
+    ReceiptsRoot,
+}
+
+impl CorruptedField {
+    /// Returns the name of the field in the JSON representation of the header.
+    const fn json_key(&self) -> &'static str {
+        match self {
+            Self::StateRoot => "stateRoot",
+            Self::ReceiptsRoot => "receiptsRoot",
+        }
+    }
+}
+
+/// Engine API stream wrapper that simulates an adversarial consensus client by delaying,
+/// reordering, duplicating and corrupting messages.
+///
+/// Corrupted payloads are sent in addition to the original ones and their responses are
+/// discarded, so the engine exercises the invalid block path while the consensus client driving
+/// the node keeps receiving responses for the messages it actually sent.
+#[derive(Debug)]
+#[pin_project::pin_project]
+pub struct EngineFaultInjector<S, T: PayloadTypes, Validator> {
+    /// Underlying stream
+    #[pin]
+    stream: S,
+    /// Payload validator used to decode payloads that are corrupted.
+    payload_validator: Validator,
+    /// The configured faults.
+    config: EngineFaultConfig,
+    /// The seeded source of randomness for the fault schedule.
+    rng: StdRng,
+    /// The stall that has to elapse before the next message is forwarded.
+    delay: Option<Pin<Box<Sleep>>>,
+    /// The messages ready to be forwarded.
+    queue: VecDeque<BeaconEngineMessage<T>>,
+    /// The new payload that is held back until the next message was forwarded, or until the
+    /// timer elapses.
+    ///
+    /// The consensus client usually waits for the response to a new payload before sending the
+    /// next message, so held back payloads are also released after a bounded delay.
+    held: Option<(BeaconEngineMessage<T>, Pin<Box<Sleep>>)>,
+}
+
+impl<S, T: PayloadTypes, Validator> EngineFaultInjector<S, T, Validator> {
+    /// Creates new [`EngineFaultInjector`] stream wrapper.
+    pub fn new(stream: S, payload_validator: Validator, config: EngineFaultConfig) -> Self {
+        let seed = config.seed.unwrap_or_else(rand::random);
+        info!(target: "engine::stream::faults", seed, ?config, "Injecting faults into engine messages");
+        Self {
+            stream,
+            payload_validator,
+            rng: StdRng::seed_from_u64(seed),
+            config,
+            delay: None,
+            queue: VecDeque::new(),
+            held: None,
+        }
+    }
+}
+
+impl<S, T, Validator> Stream for EngineFaultInjector<S, T, Validator>
+where
+    S: Stream<Item = BeaconEngineMessage<T>>,
+    T: PayloadTypes,
+    Validator: EngineValidator<T>,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(delay) = this.delay.as_mut() {
+                ready!(delay.as_mut().poll(cx));
+                *this.delay = None;
+            }
+
+            if let Some(msg) = this.queue.pop_front() {
+                return Poll::Ready(Some(msg))
+            }
+
+            let msg = match this.stream.poll_next_unpin(cx) {
+                Poll::Ready(Some(msg)) => msg,
+                Poll::Ready(None) => {
+                    // forward the held back payload before terminating
+                    return Poll::Ready(this.held.take().map(|(held, _)| held))
+                }
+                Poll::Pending => {
+                    if let Some((_, release)) = this.held.as_mut() &&
+                        release.as_mut().poll(cx).is_ready()
+                    {
+                        warn!(target: "engine::stream::faults", "Releasing held back payload that wasn't overtaken");
+                        return Poll::Ready(this.held.take().map(|(held, _)| held))
+                    }
+                    return Poll::Pending
+                }
+            };
+
+            if roll(this.rng, this.config.delay_percent) {
+                let delay = this.config.max_delay.mul_f64(this.rng.random::<f64>());
+                warn!(target: "engine::stream::faults", ?delay, "Delaying engine message");
+                *this.delay = Some(Box::pin(tokio::time::sleep(delay)));
+            }
+
+            match msg {
+                BeaconEngineMessage::NewPayload { payload, tx } => {
+                    for (field, percent) in [
+                        (CorruptedField::StateRoot, this.config.bad_state_root_percent),
+                        (CorruptedField::ReceiptsRoot, this.config.bad_receipts_root_percent),
+                    ] {
+                        if !roll(this.rng, percent) {
+                            continue
+                        }
+                        match corrupt_payload::<T, _>(
+                            this.payload_validator,
+                            payload.clone(),
+                            field,
+                            this.rng,
+                        ) {
+                            Ok(corrupted) => {
+                                warn!(
+                                    target: "engine::stream::faults",
+                                    block_number = payload.block_number(),
+                                    block_hash = %payload.block_hash(),
+                                    corrupted_hash = %corrupted.block_hash(),
+                                    ?field,
+                                    "Injecting corrupted payload"
+                                );
+                                // the response to the corrupted payload is discarded
+                                let (tx, _rx) = oneshot::channel();
+                                this.queue.push_back(BeaconEngineMessage::NewPayload {
+                                    payload: corrupted,
+                                    tx,
+                                });
+                            }
+                            Err(error) => {
+                                error!(target: "engine::stream::faults", %error, ?field, "Error corrupting payload");
+                            }
+                        }
+                    }
+
+                    if this.held.is_none() && roll(this.rng, this.config.reorder_percent) {
+                        warn!(target: "engine::stream::faults", block_number = payload.block_number(), block_hash = %payload.block_hash(), "Holding back payload");
+                        let release = Box::pin(tokio::time::sleep(this.config.max_delay));
+                        *this.held =
+                            Some((BeaconEngineMessage::NewPayload { payload, tx }, release));
+                        continue
+                    }
+                    this.queue.push_back(BeaconEngineMessage::NewPayload { payload, tx });
+                }
+                BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx, version } => {
+                    if roll(this.rng, this.config.duplicate_fcu_percent) {
+                        warn!(target: "engine::stream::faults", head = %state.head_block_hash, "Duplicating forkchoice update");
+                        // the response to the duplicate is discarded
+                        let (duplicate_tx, _rx) = oneshot::channel();
+                        this.queue.extend([
+                            BeaconEngineMessage::ForkchoiceUpdated {
+                                state,
+                                payload_attrs: payload_attrs.clone(),
+                                tx,
+                                version,
+                            },
+                            BeaconEngineMessage::ForkchoiceUpdated {
+                                state,
+                                payload_attrs,
+                                tx: duplicate_tx,
+                                version,
+                            },
+                        ]);
+                    } else {
+                        this.queue.push_back(BeaconEngineMessage::ForkchoiceUpdated {
+                            state,
+                            payload_attrs,
+                            tx,
+                            version,
+                        });
+                    }
+                }
+                msg @ (BeaconEngineMessage::ExecuteCandidate { .. } |
+                BeaconEngineMessage::ApproveReorg { .. }) => this.queue.push_back(msg),
+            }
+
+            // the held back payload is forwarded after the message that overtook it
+            if let Some((held, _)) = this.held.take() {
+                this.queue.push_back(held);
+            }
+        }
+    }
+}
+
+/// Returns `true` with the given chance in percent.
+fn roll(rng: &mut StdRng, percent: u8) -> bool {
+    percent > 0 && rng.random_range(0..100) < percent
+}
+
+/// Creates a copy of the payload with a random value in the given header field.
+///
+/// The block hash of the copy is recomputed, so the payload passes the well-formedness checks and
+/// is only rejected by the engine once the block has been executed.
+fn corrupt_payload<T, Validator>(
+    payload_validator: &Validator,
+    payload: T::ExecutionData,
+    field: CorruptedField,
+    rng: &mut StdRng,
+) -> eyre::Result<T::ExecutionData>
+where
+    T: PayloadTypes,
+    Validator: EngineValidator<T>,
+{
+    let block = payload_validator.ensure_well_formed_payload(payload)?.into_sealed_block().unseal();
+    let (header, body) = block.split();
+
+    let mut header_json = serde_json::to_value(&header)?;
+    let value = header_json
+        .get_mut(field.json_key())
+        .ok_or_else(|| eyre::eyre!("header has no {} field", field.json_key()))?;
+    *value = B256::from(rng.random::<[u8; 32]>()).to_string().into();
+    let header = serde_json::from_value(header_json)?;
+
+    let block =
+        <<T::BuiltPayload as BuiltPayload>::Primitives as NodePrimitives>::Block::new(header, body);
+    Ok(T::block_to_payload(SealedBlock::seal_slow(block)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::Header;
+    use alloy_rpc_types_engine::{
+        ExecutionData, ForkchoiceState, PayloadStatus, PayloadStatusEnum,
+    };
+    use reth_engine_primitives::{BeaconOnNewPayloadError, OnForkChoiceUpdated};
+    use reth_engine_tree::tree::payload_validator::{TreeCtx, ValidationOutcome};
+    use reth_errors::RethResult;
+    use reth_ethereum_engine_primitives::EthEngineTypes;
+    use reth_ethereum_primitives::{Block, EthPrimitives};
+    use reth_payload_primitives::{
+        EngineApiMessageVersion, InvalidPayloadAttributesError, NewPayloadError,
+    };
+    use reth_primitives_traits::RecoveredBlock;
+    use tokio::time::Instant;
+
+    /// Validator that only decodes payloads, which is all the fault injector needs.
+    #[derive(Debug)]
+    struct DecodingValidator;
+
+    impl EngineValidator<EthEngineTypes> for DecodingValidator {
+        fn validate_payload_attributes_against_header(
+            &self,
+            _attr: &<EthEngineTypes as PayloadTypes>::PayloadAttributes,
+            _header: &Header,
+        ) -> Result<(), InvalidPayloadAttributesError> {
+            unreachable!()
+        }
+
+        fn ensure_well_formed_payload(
+            &self,
+            payload: ExecutionData,
+        ) -> Result<RecoveredBlock<Block>, NewPayloadError> {
+            let block = payload.payload.try_into_block_with_sidecar(&payload.sidecar)?;
+            Ok(RecoveredBlock::new_unhashed(block, Vec::new()))
+        }
+
+        fn validate_payload(
+            &mut self,
+            _payload: ExecutionData,
+            _ctx: TreeCtx<'_, EthPrimitives>,
+        ) -> ValidationOutcome<EthPrimitives> {
+            unreachable!()
+        }
+
+        fn validate_block(
+            &mut self,
+            _block: RecoveredBlock<Block>,
+            _ctx: TreeCtx<'_, EthPrimitives>,
+        ) -> ValidationOutcome<EthPrimitives> {
+            unreachable!()
+        }
+    }
+
+    fn payload(number: u64) -> ExecutionData {
+        let header = Header {
+            number,
+            timestamp: number * 12,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(7),
+            state_root: B256::with_last_byte(1),
+            receipts_root: B256::with_last_byte(2),
+            ..Default::default()
+        };
+        EthEngineTypes::block_to_payload(SealedBlock::seal_slow(Block {
+            header,
+            body: Default::default(),
+        }))
+    }
+
+    fn new_payload(
+        number: u64,
+    ) -> (
+        BeaconEngineMessage<EthEngineTypes>,
+        oneshot::Receiver<Result<PayloadStatus, BeaconOnNewPayloadError>>,
+    ) {
+        let (tx, rx) = oneshot::channel();
+        (BeaconEngineMessage::NewPayload { payload: payload(number), tx }, rx)
+    }
+
+    fn forkchoice_updated(
+        head: u8,
+    ) -> (BeaconEngineMessage<EthEngineTypes>, oneshot::Receiver<RethResult<OnForkChoiceUpdated>>)
+    {
+        let (tx, rx) = oneshot::channel();
+        let state =
+            ForkchoiceState { head_block_hash: B256::repeat_byte(head), ..Default::default() };
+        let msg = BeaconEngineMessage::ForkchoiceUpdated {
+            state,
+            payload_attrs: None,
+            tx,
+            version: EngineApiMessageVersion::default(),
+        };
+        (msg, rx)
+    }
+
+    fn describe(msg: &BeaconEngineMessage<EthEngineTypes>) -> String {
+        match msg {
+            BeaconEngineMessage::NewPayload { payload, .. } => {
+                format!("new_payload {} {}", payload.block_number(), payload.block_hash())
+            }
+            BeaconEngineMessage::ForkchoiceUpdated { state, .. } => {
+                format!("fcu {}", state.head_block_hash)
+            }
+            msg => panic!("unexpected message {msg}"),
+        }
+    }
+
+    /// Runs the messages through the fault injector and returns the forwarded messages with the
+    /// time they were forwarded at.
+    async fn inject(
+        config: EngineFaultConfig,
+        messages: Vec<BeaconEngineMessage<EthEngineTypes>>,
+    ) -> Vec<(BeaconEngineMessage<EthEngineTypes>, Duration)> {
+        let start = Instant::now();
+        let mut injector =
+            EngineFaultInjector::new(futures::stream::iter(messages), DecodingValidator, config);
+        let mut forwarded = Vec::new();
+        while let Some(msg) = injector.next().await {
+            forwarded.push((msg, start.elapsed()));
+        }
+        forwarded
+    }
+
+    fn descriptions(forwarded: &[(BeaconEngineMessage<EthEngineTypes>, Duration)]) -> Vec<String> {
+        forwarded.iter().map(|(msg, _)| describe(msg)).collect()
+    }
+
+    fn all_faults(seed: u64) -> EngineFaultConfig {
+        EngineFaultConfig {
+            seed: Some(seed),
+            delay_percent: 50,
+            max_delay: Duration::from_secs(1),
+            reorder_percent: 50,
+            duplicate_fcu_percent: 50,
+            bad_state_root_percent: 50,
+            bad_receipts_root_percent: 50,
+        }
+    }
+
+    fn messages() -> Vec<BeaconEngineMessage<EthEngineTypes>> {
+        (1..=20).flat_map(|n| [new_payload(n).0, forkchoice_updated(n as u8).0]).collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn same_seed_same_schedule() {
+        let schedule = |forwarded: Vec<(BeaconEngineMessage<EthEngineTypes>, Duration)>| {
+            forwarded.iter().map(|(msg, at)| (describe(msg), *at)).collect::<Vec<_>>()
+        };
+
+        let first = schedule(inject(all_faults(42), messages()).await);
+        let second = schedule(inject(all_faults(42), messages()).await);
+        assert_eq!(first, second);
+        // corrupted payloads and duplicate forkchoice updates were injected
+        assert!(first.len() > messages().len());
+
+        let other_seed = schedule(inject(all_faults(43), messages()).await);
+        assert_ne!(first, other_seed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn no_faults_forwards_stream_unchanged() {
+        let config = EngineFaultConfig { seed: Some(1), ..Default::default() };
+        assert!(!config.is_enabled());
+
+        let expected = messages().iter().map(describe).collect::<Vec<_>>();
+        let forwarded = inject(config, messages()).await;
+        assert_eq!(descriptions(&forwarded), expected);
+        assert!(forwarded.iter().all(|(_, at)| at.is_zero()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn delays_messages() {
+        let config = EngineFaultConfig {
+            seed: Some(1),
+            delay_percent: 100,
+            max_delay: Duration::from_secs(1),
+            ..Default::default()
+        };
+        assert!(config.is_enabled());
+
+        let forwarded = inject(config, messages()).await;
+        assert_eq!(descriptions(&forwarded), messages().iter().map(describe).collect::<Vec<_>>());
+
+        let mut last = Duration::ZERO;
+        for (_, at) in forwarded {
+            assert!(at > last && at - last <= Duration::from_secs(1));
+            last = at;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reorders_payloads() {
+        let config =
+            EngineFaultConfig { seed: Some(1), reorder_percent: 100, ..Default::default() };
+        let forwarded = inject(
+            config,
+            vec![
+                new_payload(1).0,
+                forkchoice_updated(1).0,
+                new_payload(2).0,
+                forkchoice_updated(2).0,
+                new_payload(3).0,
+            ],
+        )
+        .await;
+
+        // every payload is overtaken by the next message, the last one is forwarded at the end
+        let expected = [
+            forkchoice_updated(1).0,
+            new_payload(1).0,
+            forkchoice_updated(2).0,
+            new_payload(2).0,
+            new_payload(3).0,
+        ];
+        assert_eq!(descriptions(&forwarded), expected.iter().map(describe).collect::<Vec<_>>());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn releases_held_payload_after_delay() {
+        let config = EngineFaultConfig {
+            seed: Some(1),
+            reorder_percent: 100,
+            max_delay: Duration::from_secs(2),
+            ..Default::default()
+        };
+        // the consensus client waits for the response before sending anything else
+        let stream = futures::stream::iter([new_payload(1).0]).chain(futures::stream::pending());
+        let mut injector = EngineFaultInjector::new(stream, DecodingValidator, config);
+
+        let start = Instant::now();
+        let msg = injector.next().await.unwrap();
+        assert_eq!(describe(&msg), describe(&new_payload(1).0));
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn duplicates_forkchoice_updates() {
+        let config =
+            EngineFaultConfig { seed: Some(1), duplicate_fcu_percent: 100, ..Default::default() };
+        let (fcu, rx) = forkchoice_updated(1);
+        let forwarded = inject(config, vec![fcu, new_payload(1).0]).await;
+
+        let expected = [forkchoice_updated(1).0, forkchoice_updated(1).0, new_payload(1).0];
+        assert_eq!(descriptions(&forwarded), expected.iter().map(describe).collect::<Vec<_>>());
+
+        // the response to the first forkchoice update is the one of the original message
+        let mut forwarded = forwarded.into_iter();
+        let Some((BeaconEngineMessage::ForkchoiceUpdated { tx, .. }, _)) = forwarded.next() else {
+            unreachable!()
+        };
+        tx.send(Ok(OnForkChoiceUpdated::syncing())).unwrap();
+        assert!(rx.await.unwrap().is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn corrupts_payload_roots() {
+        let config = EngineFaultConfig {
+            seed: Some(1),
+            bad_state_root_percent: 100,
+            bad_receipts_root_percent: 100,
+            ..Default::default()
+        };
+        let (msg, rx) = new_payload(1);
+        let forwarded = inject(config, vec![msg]).await;
+        assert_eq!(forwarded.len(), 3);
+
+        let original = payload(1);
+        let original_v1 = original.payload.as_v1().clone();
+        let mut forwarded = forwarded.into_iter().map(|(msg, _)| match msg {
+            BeaconEngineMessage::NewPayload { payload, tx } => (payload, tx),
+            msg => panic!("unexpected message {msg}"),
+        });
+
+        // a copy with a different state root is sent first
+        let (corrupted, _) = forwarded.next().unwrap();
+        let corrupted_v1 = corrupted.payload.as_v1();
+        assert_eq!(corrupted.block_number(), original.block_number());
+        assert_ne!(corrupted.block_hash(), original.block_hash());
+        assert_ne!(corrupted_v1.state_root, original_v1.state_root);
+        assert_eq!(corrupted_v1.receipts_root, original_v1.receipts_root);
+
+        // followed by a copy with a different receipts root
+        let (corrupted, _) = forwarded.next().unwrap();
+        let corrupted_v1 = corrupted.payload.as_v1();
+        assert_ne!(corrupted.block_hash(), original.block_hash());
+        assert_eq!(corrupted_v1.state_root, original_v1.state_root);
+        assert_ne!(corrupted_v1.receipts_root, original_v1.receipts_root);
+
+        // and the original payload, which still answers the consensus client
+        let (payload, tx) = forwarded.next().unwrap();
+        assert_eq!(payload.block_hash(), original.block_hash());
+        tx.send(Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing))).unwrap();
+        assert!(rx.await.unwrap().is_ok());
+    }
+}
@@ -17,6 +17,8 @@ use tokio_util::either::Either;
 pub mod engine_store;
 use engine_store::EngineStoreStream;
 
+pub mod engine_replay;
+
 pub mod skip_fcu;
 use skip_fcu::EngineSkipFcu;
 
@@ -26,10 +28,17 @@ use skip_new_payload::EngineSkipNewPayload;
 pub mod reorg;
 use reorg::EngineReorg;
 
+pub mod faults;
+use faults::{EngineFaultConfig, EngineFaultInjector};
+
 /// The result type for `maybe_reorg` method.
 type MaybeReorgResult<S, T, Provider, Evm, Validator, E> =
     Result<Either<EngineReorg<S, T, Provider, Evm, Validator>, S>, E>;
 
+/// The result type for `maybe_inject_faults` method.
+type MaybeInjectFaultsResult<S, T, Validator, E> =
+    Result<Either<EngineFaultInjector<S, T, Validator>, S>, E>;
+
 /// The collection of stream extensions for engine API message stream.
 pub trait EngineMessageStreamExt<T: PayloadTypes>: Stream<Item = BeaconEngineMessage<T>> {
     /// Skips the specified number of [`BeaconEngineMessage::ForkchoiceUpdated`] messages from the
@@ -161,6 +170,43 @@ pub trait EngineMessageStreamExt<T: PayloadTypes>: Stream<Item = BeaconEngineMes
             }
         }
     }
+
+    /// Injects the faults described by the config into the engine message stream.
+    fn inject_faults<Validator>(
+        self,
+        payload_validator: Validator,
+        config: EngineFaultConfig,
+    ) -> EngineFaultInjector<Self, T, Validator>
+    where
+        Self: Sized,
+    {
+        EngineFaultInjector::new(self, payload_validator, config)
+    }
+
+    /// If the config enables any fault, returns the stream that injects the configured faults.
+    /// Otherwise, returns `Self`.
+    ///
+    /// The `payload_validator_fn` closure is only called if faults are injected, allowing for
+    /// lazy initialization of the validator.
+    fn maybe_inject_faults<Validator, E, F, Fut>(
+        self,
+        payload_validator_fn: F,
+        config: EngineFaultConfig,
+    ) -> impl Future<Output = MaybeInjectFaultsResult<Self, T, Validator, E>> + Send
+    where
+        Self: Sized + Send,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Result<Validator, E>> + Send,
+    {
+        async move {
+            if config.is_enabled() {
+                let validator = payload_validator_fn().await?;
+                Ok(Either::Left(self.inject_faults(validator, config)))
+            } else {
+                Ok(Either::Right(self))
+            }
+        }
+    }
 }
 
 impl<T, S> EngineMessageStreamExt<T> for S
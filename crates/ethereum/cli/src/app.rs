@@ -210,6 +210,9 @@ where
         #[cfg(feature = "dev")]
         Commands::TestVectors(command) => runner.run_until_ctrl_c(command.execute()),
         Commands::ReExecute(command) => runner.run_until_ctrl_c(command.execute::<N>(components)),
+        Commands::Debug(command) => runner.run_command_until_exit(|ctx| {
+            command.execute(ctx, FnLauncher::new::<C, Ext>(launcher))
+        }),
     }
 }
 
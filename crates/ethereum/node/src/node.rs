@@ -1,6 +1,9 @@
 //! Ethereum Node types config.
 
-pub use crate::{payload::EthereumPayloadBuilder, EthereumEngineValidator};
+pub use crate::{
+    payload::{EthereumConditionalPayloadBuilder, EthereumPayloadBuilder},
+    EthereumEngineValidator,
+};
 use crate::{EthEngineTypes, EthEvmConfig};
 use alloy_eips::{eip7840::BlobParams, merge::EPOCH_SLOTS};
 use alloy_network::Ethereum;
@@ -35,12 +38,15 @@ use reth_node_builder::{
     BuilderContext, DebugNode, Node, NodeAdapter, PayloadBuilderConfig,
 };
 use reth_payload_primitives::PayloadTypes;
-use reth_provider::{providers::ProviderFactoryBuilder, EthStorage};
+use reth_provider::{providers::ProviderFactoryBuilder, CanonStateSubscriptions, EthStorage};
 use reth_rpc::{
-    eth::core::{EthApiFor, EthRpcConverterFor},
+    eth::{
+        core::{EthApiFor, EthRpcConverterFor},
+        EthConditional,
+    },
     ValidationApi,
 };
-use reth_rpc_api::servers::BlockSubmissionValidationApiServer;
+use reth_rpc_api::servers::{BlockSubmissionValidationApiServer, L2EthApiExtServer};
 use reth_rpc_builder::{config::RethRpcServerConfig, middleware::RethRpcMiddleware};
 use reth_rpc_eth_api::{
     helpers::{
@@ -53,8 +59,9 @@ use reth_rpc_eth_types::{error::FromEvmError, EthApiError};
 use reth_rpc_server_types::RethRpcModule;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, EthTransactionPool, PoolPooledTx, PoolTransaction,
-    TransactionPool, TransactionValidationTaskExecutor,
+    blobstore::DiskFileBlobStore, conditional::MaybeConditionalTransaction,
+    maintain::maintain_transaction_pool_conditional_future, EthTransactionPool, PoolPooledTx,
+    PoolTransaction, TransactionPool, TransactionValidationTaskExecutor,
 };
 use revm::context::TxEnv;
 use std::{marker::PhantomData, sync::Arc, time::SystemTime};
@@ -66,6 +73,9 @@ pub struct EthereumNode;
 
 impl EthereumNode {
     /// Returns a [`ComponentsBuilder`] configured for a regular Ethereum node.
+    ///
+    /// The payload builder doesn't check the conditionals of transactions, replace it with
+    /// [`EthereumConditionalPayloadBuilder`] if conditional transactions are accepted.
     pub fn components<Node>() -> ComponentsBuilder<
         Node,
         EthereumPoolBuilder,
@@ -275,6 +285,7 @@ where
             Payload: EngineTypes<ExecutionData = ExecutionData>,
         >,
         Evm: ConfigureEvm<NextBlockEnvCtx = NextBlockEnvAttributes>,
+        Pool: TransactionPool<Transaction: MaybeConditionalTransaction>,
     >,
     EthB: EthApiBuilder<N>,
     PVB: Send,
@@ -302,6 +313,12 @@ where
         let eth_config =
             EthConfigHandler::new(ctx.node.provider().clone(), ctx.node.evm_config().clone());
 
+        let tx_conditional = ctx
+            .config
+            .rpc
+            .rpc_enable_tx_conditional
+            .then(|| EthConditional::new(ctx.node.pool().clone(), ctx.node.provider().clone()));
+
         self.inner
             .launch_add_ons_with(ctx, move |container| {
                 container.modules.merge_if_module_configured(
@@ -313,6 +330,14 @@ where
                     .modules
                     .merge_if_module_configured(RethRpcModule::Eth, eth_config.into_rpc())?;
 
+                if let Some(tx_conditional) = tx_conditional {
+                    debug!(target: "reth::cli", "Installing conditional transaction rpc endpoint");
+                    container.modules.merge_if_module_configured(
+                        RethRpcModule::Eth,
+                        tx_conditional.into_rpc(),
+                    )?;
+                }
+
                 Ok(())
             })
             .await
@@ -502,6 +527,18 @@ where
         info!(target: "reth::cli", "Transaction pool initialized");
         debug!(target: "reth::cli", "Spawned txpool maintenance task");
 
+        if ctx.config().rpc.rpc_enable_tx_conditional {
+            let chain_events = ctx.provider().canonical_state_stream();
+            ctx.task_executor().spawn_critical(
+                "txpool conditional maintenance task",
+                maintain_transaction_pool_conditional_future(
+                    transaction_pool.clone(),
+                    chain_events,
+                ),
+            );
+            debug!(target: "reth::cli", "Spawned txpool conditional maintenance task");
+        }
+
         Ok(transaction_pool)
     }
 }
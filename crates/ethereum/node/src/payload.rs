@@ -11,7 +11,9 @@ use reth_node_api::{FullNodeTypes, NodeTypes, PrimitivesTy, TxTy};
 use reth_node_builder::{
     components::PayloadBuilderBuilder, BuilderContext, PayloadBuilderConfig, PayloadTypes,
 };
-use reth_transaction_pool::{PoolTransaction, TransactionPool};
+use reth_transaction_pool::{
+    conditional::MaybeConditionalTransaction, PoolTransaction, TransactionPool,
+};
 
 /// A basic ethereum payload service.
 #[derive(Clone, Default, Debug)]
@@ -44,15 +46,66 @@ where
         pool: Pool,
         evm_config: Evm,
     ) -> eyre::Result<Self::PayloadBuilder> {
-        let conf = ctx.payload_builder_config();
-        let chain = ctx.chain_spec().chain();
-        let gas_limit = conf.gas_limit_for(chain);
-
         Ok(reth_ethereum_payload_builder::EthereumPayloadBuilder::new(
             ctx.provider().clone(),
             pool,
             evm_config,
-            EthereumBuilderConfig::new().with_gas_limit(gas_limit),
+            builder_config(ctx),
         ))
     }
 }
+
+/// An ethereum payload service that skips transactions whose conditional is not met by the block
+/// that is being built.
+///
+/// Unlike [`EthereumPayloadBuilder`], this requires the pool's transactions to implement
+/// [`MaybeConditionalTransaction`].
+#[derive(Clone, Default, Debug)]
+#[non_exhaustive]
+pub struct EthereumConditionalPayloadBuilder;
+
+impl<Types, Node, Pool, Evm> PayloadBuilderBuilder<Node, Pool, Evm>
+    for EthereumConditionalPayloadBuilder
+where
+    Types: NodeTypes<ChainSpec: EthereumHardforks, Primitives = EthPrimitives>,
+    Node: FullNodeTypes<Types = Types>,
+    Pool: TransactionPool<
+            Transaction: PoolTransaction<Consensus = TxTy<Node::Types>>
+                             + MaybeConditionalTransaction,
+        > + Unpin
+        + 'static,
+    Evm: ConfigureEvm<
+            Primitives = PrimitivesTy<Types>,
+            NextBlockEnvCtx = reth_evm::NextBlockEnvAttributes,
+        > + 'static,
+    Types::Payload: PayloadTypes<
+        BuiltPayload = EthBuiltPayload,
+        PayloadAttributes = EthPayloadAttributes,
+        PayloadBuilderAttributes = EthPayloadBuilderAttributes,
+    >,
+{
+    type PayloadBuilder =
+        reth_ethereum_payload_builder::ConditionalEthereumPayloadBuilder<Pool, Node::Provider, Evm>;
+
+    async fn build_payload_builder(
+        self,
+        ctx: &BuilderContext<Node>,
+        pool: Pool,
+        evm_config: Evm,
+    ) -> eyre::Result<Self::PayloadBuilder> {
+        Ok(reth_ethereum_payload_builder::EthereumPayloadBuilder::new(
+            ctx.provider().clone(),
+            pool,
+            evm_config,
+            builder_config(ctx),
+        )
+        .with_conditional_transactions())
+    }
+}
+
+/// Returns the [`EthereumBuilderConfig`] for the node's payload builder.
+fn builder_config<Node: FullNodeTypes>(ctx: &BuilderContext<Node>) -> EthereumBuilderConfig {
+    let conf = ctx.payload_builder_config();
+    let chain = ctx.chain_spec().chain();
+    EthereumBuilderConfig::new().with_gas_limit(conf.gas_limit_for(chain))
+}
@@ -9,9 +9,10 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![allow(clippy::useless_let_if_seq)]
 
-use alloy_consensus::Transaction;
+use alloy_consensus::{conditional::BlockConditionalAttributes, Transaction};
 use alloy_primitives::U256;
 use alloy_rlp::Encodable;
+use alloy_rpc_types_eth::erc4337::{AccountStorage, TransactionConditional};
 use reth_basic_payload_builder::{
     is_better_payload, BuildArguments, BuildOutcome, MissingPayloadBehaviour, PayloadBuilder,
     PayloadConfig,
@@ -30,13 +31,15 @@ use reth_payload_builder_primitives::PayloadBuilderError;
 use reth_payload_primitives::PayloadBuilderAttributes;
 use reth_primitives_traits::transaction::error::InvalidTransactionError;
 use reth_revm::{database::StateProviderDatabase, db::State};
-use reth_storage_api::StateProviderFactory;
+use reth_storage_api::{StateProvider, StateProviderFactory};
 use reth_transaction_pool::{
+    conditional::{ConditionalNotMet, MaybeConditionalTransaction},
     error::{Eip4844PoolTransactionError, InvalidPoolTransactionError},
     BestTransactions, BestTransactionsAttributes, PoolTransaction, TransactionPool,
     ValidPoolTransaction,
 };
-use revm::context_interface::Block as _;
+use reth_trie_common::HashedStorage;
+use revm::{context_interface::Block as _, Database};
 use std::sync::Arc;
 use tracing::{debug, trace, warn};
 
@@ -73,6 +76,14 @@ impl<Pool, Client, EvmConfig> EthereumPayloadBuilder<Pool, Client, EvmConfig> {
     ) -> Self {
         Self { client, pool, evm_config, builder_config }
     }
+
+    /// Returns a builder that skips transactions whose [`TransactionConditional`] is not met by the
+    /// block that is being built.
+    pub const fn with_conditional_transactions(
+        self,
+    ) -> ConditionalEthereumPayloadBuilder<Pool, Client, EvmConfig> {
+        ConditionalEthereumPayloadBuilder { inner: self }
+    }
 }
 
 // Default implementation of [PayloadBuilder] for unit type
@@ -129,6 +140,58 @@ where
     }
 }
 
+/// Ethereum payload builder that skips transactions whose [`TransactionConditional`] is not met by
+/// the block that is being built.
+///
+/// Unlike [`EthereumPayloadBuilder`], this requires the pool's transactions to implement
+/// [`MaybeConditionalTransaction`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConditionalEthereumPayloadBuilder<Pool, Client, EvmConfig = EthEvmConfig> {
+    inner: EthereumPayloadBuilder<Pool, Client, EvmConfig>,
+}
+
+impl<Pool, Client, EvmConfig> PayloadBuilder
+    for ConditionalEthereumPayloadBuilder<Pool, Client, EvmConfig>
+where
+    EvmConfig: ConfigureEvm<Primitives = EthPrimitives, NextBlockEnvCtx = NextBlockEnvAttributes>,
+    Client: StateProviderFactory + ChainSpecProvider<ChainSpec: EthereumHardforks> + Clone,
+    Pool: TransactionPool<
+        Transaction: PoolTransaction<Consensus = TransactionSigned> + MaybeConditionalTransaction,
+    >,
+{
+    type Attributes = EthPayloadBuilderAttributes;
+    type BuiltPayload = EthBuiltPayload;
+
+    fn try_build(
+        &self,
+        args: BuildArguments<EthPayloadBuilderAttributes, EthBuiltPayload>,
+    ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError> {
+        let EthereumPayloadBuilder { client, pool, evm_config, builder_config } = &self.inner;
+        conditional_ethereum_payload(
+            evm_config.clone(),
+            client.clone(),
+            pool.clone(),
+            builder_config.clone(),
+            args,
+            |attributes| pool.best_transactions_with_attributes(attributes),
+        )
+    }
+
+    fn on_missing_payload(
+        &self,
+        args: BuildArguments<Self::Attributes, Self::BuiltPayload>,
+    ) -> MissingPayloadBehaviour<Self::BuiltPayload> {
+        self.inner.on_missing_payload(args)
+    }
+
+    fn build_empty_payload(
+        &self,
+        config: PayloadConfig<Self::Attributes>,
+    ) -> Result<EthBuiltPayload, PayloadBuilderError> {
+        self.inner.build_empty_payload(config)
+    }
+}
+
 /// Constructs an Ethereum transaction payload using the best transactions from the pool.
 ///
 /// Given build arguments including an Ethereum client, transaction pool,
@@ -143,6 +206,57 @@ pub fn default_ethereum_payload<EvmConfig, Client, Pool, F>(
     args: BuildArguments<EthPayloadBuilderAttributes, EthBuiltPayload>,
     best_txs: F,
 ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm<Primitives = EthPrimitives, NextBlockEnvCtx = NextBlockEnvAttributes>,
+    Client: StateProviderFactory + ChainSpecProvider<ChainSpec: EthereumHardforks>,
+    Pool: TransactionPool<Transaction: PoolTransaction<Consensus = TransactionSigned>>,
+    F: FnOnce(BestTransactionsAttributes) -> BestTransactionsIter<Pool>,
+{
+    build_payload(evm_config, client, pool, builder_config, args, best_txs, |_| None)
+}
+
+/// Constructs an Ethereum transaction payload like [`default_ethereum_payload`], but skips
+/// transactions whose [`TransactionConditional`] is not met by the block that is being built.
+#[inline]
+pub fn conditional_ethereum_payload<EvmConfig, Client, Pool, F>(
+    evm_config: EvmConfig,
+    client: Client,
+    pool: Pool,
+    builder_config: EthereumBuilderConfig,
+    args: BuildArguments<EthPayloadBuilderAttributes, EthBuiltPayload>,
+    best_txs: F,
+) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm<Primitives = EthPrimitives, NextBlockEnvCtx = NextBlockEnvAttributes>,
+    Client: StateProviderFactory + ChainSpecProvider<ChainSpec: EthereumHardforks>,
+    Pool: TransactionPool<
+        Transaction: PoolTransaction<Consensus = TransactionSigned> + MaybeConditionalTransaction,
+    >,
+    F: FnOnce(BestTransactionsAttributes) -> BestTransactionsIter<Pool>,
+{
+    build_payload(
+        evm_config,
+        client,
+        pool,
+        builder_config,
+        args,
+        best_txs,
+        <Pool::Transaction as MaybeConditionalTransaction>::conditional,
+    )
+}
+
+/// Builds the payload, `conditional` returns the [`TransactionConditional`] a pooled transaction
+/// was submitted with, if any.
+#[inline]
+fn build_payload<EvmConfig, Client, Pool, F>(
+    evm_config: EvmConfig,
+    client: Client,
+    pool: Pool,
+    builder_config: EthereumBuilderConfig,
+    args: BuildArguments<EthPayloadBuilderAttributes, EthBuiltPayload>,
+    best_txs: F,
+    conditional: fn(&Pool::Transaction) -> Option<&TransactionConditional>,
+) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
 where
     EvmConfig: ConfigureEvm<Primitives = EthPrimitives, NextBlockEnvCtx = NextBlockEnvAttributes>,
     Client: StateProviderFactory + ChainSpecProvider<ChainSpec: EthereumHardforks>,
@@ -202,6 +316,10 @@ where
         blob_params.as_ref().map(|params| params.max_blob_count).unwrap_or_default();
 
     let is_osaka = chain_spec.is_osaka_active_at_timestamp(attributes.timestamp);
+    let block_attr = BlockConditionalAttributes {
+        number: parent_header.number + 1,
+        timestamp: attributes.timestamp(),
+    };
 
     while let Some(pool_tx) = best_txs.next() {
         // ensure we still have capacity for this transaction
@@ -216,6 +334,24 @@ where
             continue
         }
 
+        // skip transactions whose conditional is not met by this block, this only skips the
+        // transaction for this payload, the pool removes it once the conditional expired
+        if let Some(conditional) = conditional(&pool_tx.transaction) &&
+            (!conditional.matches_block_attributes(&block_attr) ||
+                !known_accounts_match(
+                    &mut **builder.evm_mut().db_mut(),
+                    state_provider.as_ref(),
+                    conditional,
+                ))
+        {
+            trace!(target: "payload_builder", tx=?pool_tx.hash(), "skipping transaction with unmet conditional");
+            best_txs.mark_invalid(
+                &pool_tx,
+                InvalidPoolTransactionError::other(ConditionalNotMet::from(&block_attr)),
+            );
+            continue
+        }
+
         // check if the job was cancelled, if so we can exit early
         if cancel.is_cancelled() {
             return Ok(BuildOutcome::Cancelled)
@@ -372,3 +508,176 @@ where
 
     Ok(BuildOutcome::Better { payload, cached_reads })
 }
+
+/// Returns `true` if the known accounts of the given conditional match the state the transaction
+/// would be executed on.
+///
+/// Both storage slots and storage roots are checked against the state of the block that is being
+/// built, i.e. the parent state with the changes of the transactions included so far.
+fn known_accounts_match<DB: Database>(
+    db: &mut State<DB>,
+    parent_state: &dyn StateProvider,
+    conditional: &TransactionConditional,
+) -> bool {
+    for (address, storage) in &conditional.known_accounts {
+        match storage {
+            AccountStorage::Slots(slots) => {
+                for (slot, expected_value) in slots {
+                    let Ok(current) = db.storage(*address, U256::from_be_bytes(slot.0)) else {
+                        return false
+                    };
+                    if current != U256::from_be_bytes(expected_value.0) {
+                        return false
+                    }
+                }
+            }
+            AccountStorage::RootHash(expected_root) => {
+                // overlay the storage of the account as changed by the block on the parent state
+                let storage = db
+                    .cache
+                    .accounts
+                    .get(address)
+                    .map(|account| {
+                        HashedStorage::from_plain_storage(
+                            account.status,
+                            account.account.iter().flat_map(|account| &account.storage),
+                        )
+                    })
+                    .unwrap_or_default();
+                let Ok(actual_root) = parent_state.storage_root(*address, storage) else {
+                    return false
+                };
+                if *expected_root != actual_root {
+                    return false
+                }
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::Header;
+    use alloy_eips::eip1559::MIN_PROTOCOL_BASE_FEE;
+    use alloy_primitives::{Address, B256};
+    use alloy_rpc_types_engine::PayloadAttributes;
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_primitives_traits::{SealedHeader, SignedTransaction};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_transaction_pool::{
+        blobstore::InMemoryBlobStore,
+        test_utils::{OkValidator, TransactionBuilder},
+        CoinbaseTipOrdering, EthPooledTransaction, Pool, TransactionOrigin,
+    };
+
+    #[tokio::test]
+    async fn conditional_payload_skips_unmet_conditionals() {
+        let chain_spec = ChainSpecBuilder::mainnet().shanghai_activated().build();
+        let evm_config = EthEvmConfig::new(Arc::new(chain_spec.clone()));
+        let provider = MockEthProvider::default().with_chain_spec(chain_spec);
+        let pool = Pool::new(
+            OkValidator::<EthPooledTransaction>::default(),
+            CoinbaseTipOrdering::default(),
+            InMemoryBlobStore::default(),
+            Default::default(),
+        );
+
+        // a contract whose first storage slot is set to 1
+        let contract = Address::with_last_byte(0xaa);
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO).extend_storage([(B256::ZERO, U256::from(1))]),
+        );
+
+        let add_transaction = async |signer: u8, conditional: Option<TransactionConditional>| {
+            let tx = TransactionBuilder::default()
+                .signer(B256::with_last_byte(signer))
+                .to(Address::with_last_byte(0xbb))
+                .gas_limit(21_000)
+                .max_fee_per_gas(1_000_000_000)
+                .max_priority_fee_per_gas(1)
+                .into_eip1559();
+            let mut tx =
+                EthPooledTransaction::try_from_consensus(tx.try_into_recovered().unwrap()).unwrap();
+            provider.add_account(
+                tx.sender(),
+                ExtendedAccount::new(0, U256::from(1_000_000_000_000_000_000u128)),
+            );
+            if let Some(conditional) = conditional {
+                tx.set_conditional(conditional);
+            }
+            pool.add_transaction(TransactionOrigin::External, tx).await.unwrap().hash
+        };
+
+        let plain = add_transaction(1, None).await;
+        let met = add_transaction(
+            2,
+            Some(TransactionConditional { block_number_max: Some(10), ..Default::default() }),
+        )
+        .await;
+        add_transaction(
+            3,
+            Some(TransactionConditional { block_number_min: Some(2), ..Default::default() }),
+        )
+        .await;
+        add_transaction(
+            4,
+            Some(TransactionConditional {
+                known_accounts: [(
+                    contract,
+                    AccountStorage::Slots(
+                        [(B256::ZERO, B256::with_last_byte(2))].into_iter().collect(),
+                    ),
+                )]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            }),
+        )
+        .await;
+
+        let parent = Arc::new(SealedHeader::seal_slow(Header {
+            gas_limit: 30_000_000,
+            gas_used: 15_000_000,
+            base_fee_per_gas: Some(MIN_PROTOCOL_BASE_FEE),
+            ..Default::default()
+        }));
+        let attributes = EthPayloadBuilderAttributes::new(
+            parent.hash(),
+            PayloadAttributes {
+                timestamp: 12,
+                prev_randao: B256::ZERO,
+                suggested_fee_recipient: Address::ZERO,
+                withdrawals: Some(Vec::new()),
+                parent_beacon_block_root: None,
+            },
+        );
+        let args = BuildArguments::new(
+            Default::default(),
+            PayloadConfig::new(parent, attributes),
+            Default::default(),
+            None,
+        );
+
+        let BuildOutcome::Better { payload, .. } = conditional_ethereum_payload(
+            evm_config,
+            provider,
+            pool.clone(),
+            EthereumBuilderConfig::new(),
+            args,
+            |attributes| pool.best_transactions_with_attributes(attributes),
+        )
+        .unwrap() else {
+            panic!("expected a better payload")
+        };
+
+        // only the transactions without a conditional or with a met conditional are included
+        let included =
+            payload.block().body().transactions.iter().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(included.len(), 2);
+        assert!(included.contains(&plain));
+        assert!(included.contains(&met));
+    }
+}
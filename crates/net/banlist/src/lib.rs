@@ -10,7 +10,15 @@
 
 type PeerId = alloy_primitives::B512;
 
-use std::{collections::HashMap, net::IpAddr, time::Instant};
+#[cfg(feature = "serde")]
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::{
+    collections::HashMap,
+    fmt,
+    net::IpAddr,
+    str::FromStr,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 /// Determines whether or not the IP is globally routable.
 /// Should be replaced with [`IpAddr::is_global`](std::net::IpAddr::is_global) once it is stable.
@@ -25,12 +33,187 @@ pub const fn is_global(ip: &IpAddr) -> bool {
     }
 }
 
+/// A range of IP addresses in CIDR notation, e.g. `203.0.113.0/24`.
+///
+/// A single IP address parses as the range that only contains that address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(SerializeDisplay, DeserializeFromStr))]
+pub struct IpCidr {
+    /// The first address of the range.
+    addr: IpAddr,
+    /// The number of leading bits shared by all addresses of the range.
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// Creates the range of addresses that share the first `prefix_len` bits with the address.
+    ///
+    /// Returns `None` if the prefix is longer than the address.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Option<Self> {
+        let addr = match addr {
+            IpAddr::V4(addr) if prefix_len <= 32 => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(prefix_len)).unwrap_or(0);
+                IpAddr::from((u32::from(addr) & mask).to_be_bytes())
+            }
+            IpAddr::V6(addr) if prefix_len <= 128 => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(prefix_len)).unwrap_or(0);
+                IpAddr::from((u128::from(addr) & mask).to_be_bytes())
+            }
+            _ => return None,
+        };
+        Some(Self { addr, prefix_len })
+    }
+
+    /// Returns the first address of the range.
+    pub const fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Returns the prefix length of the range.
+    pub const fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns true if the address is in the range.
+    ///
+    /// IPv4-mapped IPv6 addresses are treated as IPv4 addresses.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        Self::new(ip.to_canonical(), self.prefix_len).is_some_and(|range| range == *self)
+    }
+}
+
+impl fmt::Display for IpCidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = InvalidBanTarget;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidBanTarget(s.to_string());
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix_len)) => {
+                (addr.parse().map_err(|_| invalid())?, prefix_len.parse().map_err(|_| invalid())?)
+            }
+            None => {
+                let addr: IpAddr = s.parse().map_err(|_| invalid())?;
+                (addr, if addr.is_ipv4() { 32 } else { 128 })
+            }
+        };
+        Self::new(addr, prefix_len).ok_or_else(invalid)
+    }
+}
+
+/// A peer, IP address or range of IP addresses that can be banned.
+///
+/// Parses from a peer ID or `enode` URL, an IP address, or a CIDR range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(SerializeDisplay, DeserializeFromStr))]
+pub enum BanTarget {
+    /// A peer ID.
+    Peer(PeerId),
+    /// A single IP address.
+    Ip(IpAddr),
+    /// A range of IP addresses.
+    Range(IpCidr),
+}
+
+impl BanTarget {
+    /// Returns true if the ban applies to the peer with the given ID and IP address.
+    pub fn matches(&self, peer_id: &PeerId, ip: &IpAddr) -> bool {
+        match self {
+            Self::Peer(peer) => peer == peer_id,
+            Self::Ip(addr) => *addr == ip.to_canonical(),
+            Self::Range(range) => range.contains(ip),
+        }
+    }
+
+    /// Returns false if the target is an IP address that is not globally routable, which is never
+    /// banned.
+    pub const fn is_bannable(&self) -> bool {
+        match self {
+            Self::Ip(ip) => is_global(ip),
+            Self::Peer(_) | Self::Range(_) => true,
+        }
+    }
+}
+
+impl fmt::Display for BanTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Peer(peer_id) => write!(f, "{}", alloy_primitives::hex::encode(peer_id)),
+            Self::Ip(ip) => write!(f, "{ip}"),
+            Self::Range(range) => write!(f, "{range}"),
+        }
+    }
+}
+
+impl FromStr for BanTarget {
+    type Err = InvalidBanTarget;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains('/') && !s.starts_with("enode://") {
+            return s.parse().map(Self::Range)
+        }
+        if let Ok(ip) = s.parse() {
+            return Ok(Self::Ip(ip))
+        }
+        let id =
+            s.strip_prefix("enode://").map_or(s, |enode| enode.split('@').next().unwrap_or(""));
+        id.parse().map(Self::Peer).map_err(|_| InvalidBanTarget(s.to_string()))
+    }
+}
+
+impl From<PeerId> for BanTarget {
+    fn from(peer_id: PeerId) -> Self {
+        Self::Peer(peer_id)
+    }
+}
+
+impl From<IpAddr> for BanTarget {
+    fn from(ip: IpAddr) -> Self {
+        Self::Ip(ip)
+    }
+}
+
+impl From<IpCidr> for BanTarget {
+    fn from(range: IpCidr) -> Self {
+        Self::Range(range)
+    }
+}
+
+/// Error returned when a string is not a valid [`BanTarget`] or [`IpCidr`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidBanTarget(pub String);
+
+impl fmt::Display for InvalidBanTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid peer ID, IP address or CIDR range: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidBanTarget {}
+
+/// A ban and its expiry as wall clock time, so it can be persisted or reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BanEntry {
+    /// The banned peer, IP address or range of IP addresses.
+    pub target: BanTarget,
+    /// Unix timestamp in seconds at which the ban expires, `None` if the ban is indefinite.
+    pub until: Option<u64>,
+}
+
 /// Stores peers that should be taken out of circulation either indefinitely or until a certain
 /// timestamp
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct BanList {
     /// A set of IPs whose packets get dropped instantly.
     banned_ips: HashMap<IpAddr, Option<Instant>>,
+    /// A set of IP ranges whose packets get dropped instantly.
+    banned_ranges: HashMap<IpCidr, Option<Instant>>,
     /// A set of [`PeerId`] whose packets get dropped instantly.
     banned_peers: HashMap<PeerId, Option<Instant>>,
 }
@@ -48,11 +231,11 @@ impl BanList {
     }
 
     /// Creates a new ban list that bans the given peers and ips with an optional timeout.
-    pub const fn new_with_timeout(
+    pub fn new_with_timeout(
         banned_peers: HashMap<PeerId, Option<Instant>>,
         banned_ips: HashMap<IpAddr, Option<Instant>>,
     ) -> Self {
-        Self { banned_ips, banned_peers }
+        Self { banned_ips, banned_ranges: HashMap::default(), banned_peers }
     }
 
     /// Removes all peers that are no longer banned.
@@ -85,12 +268,28 @@ impl BanList {
         evicted
     }
 
+    /// Removes all ip ranges that are no longer banned.
+    pub fn evict_ranges(&mut self, now: Instant) -> Vec<IpCidr> {
+        let mut evicted = Vec::new();
+        self.banned_ranges.retain(|range, until| {
+            if let Some(until) = until &&
+                now > *until
+            {
+                evicted.push(*range);
+                return false
+            }
+            true
+        });
+        evicted
+    }
+
     /// Removes all entries that should no longer be banned.
     ///
-    /// Returns the evicted entries.
+    /// Returns the evicted ip addresses and peers, expired ip ranges are removed as well.
     pub fn evict(&mut self, now: Instant) -> (Vec<IpAddr>, Vec<PeerId>) {
         let ips = self.evict_ips(now);
         let peers = self.evict_peers(now);
+        self.evict_ranges(now);
         (ips, peers)
     }
 
@@ -100,10 +299,11 @@ impl BanList {
         self.is_banned_peer(peer_id) || self.is_banned_ip(ip)
     }
 
-    /// checks the ban list to see if it contains the given ip
+    /// checks the ban list to see if it contains the given ip, or a range that contains it
     #[inline]
     pub fn is_banned_ip(&self, ip: &IpAddr) -> bool {
-        self.banned_ips.contains_key(ip)
+        self.banned_ips.contains_key(ip) ||
+            self.banned_ranges.keys().any(|range| range.contains(ip))
     }
 
     /// checks the ban list to see if it contains the given ip
@@ -122,6 +322,22 @@ impl BanList {
         self.banned_peers.remove(peer_id);
     }
 
+    /// Unbans the ip range
+    pub fn unban_range(&mut self, range: &IpCidr) {
+        self.banned_ranges.remove(range);
+    }
+
+    /// Unbans the peer, ip address or ip range.
+    ///
+    /// Returns true if the target was banned.
+    pub fn unban(&mut self, target: &BanTarget) -> bool {
+        match target {
+            BanTarget::Peer(peer_id) => self.banned_peers.remove(peer_id).is_some(),
+            BanTarget::Ip(ip) => self.banned_ips.remove(ip).is_some(),
+            BanTarget::Range(range) => self.banned_ranges.remove(range).is_some(),
+        }
+    }
+
     /// Bans the IP until the timestamp.
     ///
     /// This does not ban non-global IPs.
@@ -165,6 +381,60 @@ impl BanList {
             self.banned_ips.insert(ip, until);
         }
     }
+
+    /// Bans the ip range indefinitely or until the given timeout.
+    ///
+    /// If the range is already banned, the timeout will be updated to the new value.
+    pub fn ban_range_with(&mut self, range: IpCidr, until: Option<Instant>) {
+        self.banned_ranges.insert(range, until);
+    }
+
+    /// Bans the peer, ip address or ip range indefinitely or until the given timeout.
+    ///
+    /// This does not ban non-global IPs.
+    pub fn ban_with(&mut self, target: BanTarget, until: Option<Instant>) {
+        match target {
+            BanTarget::Peer(peer_id) => self.ban_peer_with(peer_id, until),
+            BanTarget::Ip(ip) => self.ban_ip_with(ip, until),
+            BanTarget::Range(range) => self.ban_range_with(range, until),
+        }
+    }
+
+    /// Returns all bans with their expiry as unix timestamp.
+    pub fn entries(&self) -> impl Iterator<Item = BanEntry> + '_ {
+        let now = Instant::now();
+        let unix_now = unix_now();
+        let peers =
+            self.banned_peers.iter().map(|(peer_id, until)| (BanTarget::Peer(*peer_id), until));
+        let ips = self.banned_ips.iter().map(|(ip, until)| (BanTarget::Ip(*ip), until));
+        let ranges =
+            self.banned_ranges.iter().map(|(range, until)| (BanTarget::Range(*range), until));
+        peers.chain(ips).chain(ranges).map(move |(target, until)| BanEntry {
+            target,
+            until: until.map(|until| (unix_now + until.saturating_duration_since(now)).as_secs()),
+        })
+    }
+
+    /// Adds the bans, skipping bans that already expired.
+    pub fn extend(&mut self, entries: impl IntoIterator<Item = BanEntry>) {
+        let now = Instant::now();
+        let unix_now = unix_now();
+        for BanEntry { target, until } in entries {
+            let until = match until {
+                Some(until) => match Duration::from_secs(until).checked_sub(unix_now) {
+                    Some(remaining) if !remaining.is_zero() => Some(now + remaining),
+                    _ => continue,
+                },
+                None => None,
+            };
+            self.ban_with(target, until);
+        }
+    }
+}
+
+/// Returns the time since the unix epoch.
+fn unix_now() -> Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -191,6 +461,85 @@ mod tests {
         assert!(!banlist.is_banned_ip(&ip));
     }
 
+    #[test]
+    fn non_global_ip_is_not_bannable() {
+        let local = BanTarget::Ip(IpAddr::from([127, 0, 0, 1]));
+        assert!(!local.is_bannable());
+        let mut banlist = BanList::default();
+        banlist.ban_with(local, None);
+        assert_eq!(banlist.entries().count(), 0);
+
+        assert!(BanTarget::Ip(IpAddr::from([1, 1, 1, 1])).is_bannable());
+        assert!(BanTarget::Range("127.0.0.0/8".parse().unwrap()).is_bannable());
+    }
+
+    #[test]
+    fn can_ban_ip_range() {
+        let range: IpCidr = "203.0.113.7/24".parse().unwrap();
+        assert_eq!(range.to_string(), "203.0.113.0/24");
+        assert!(range.contains(&IpAddr::from([203, 0, 113, 200])));
+        assert!(range.contains(&"::ffff:203.0.113.1".parse().unwrap()));
+        assert!(!range.contains(&IpAddr::from([203, 0, 114, 1])));
+
+        let mut banlist = BanList::default();
+        banlist.ban_range_with(range, None);
+        assert!(banlist.is_banned_ip(&IpAddr::from([203, 0, 113, 1])));
+        assert!(banlist.unban(&BanTarget::Range(range)));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([203, 0, 113, 1])));
+
+        let v6: IpCidr = "2001:db8::/32".parse().unwrap();
+        assert!(v6.contains(&"2001:db8:1::1".parse().unwrap()));
+        assert!(!v6.contains(&"2001:db9::1".parse().unwrap()));
+        assert!("10.0.0.0/33".parse::<IpCidr>().is_err());
+    }
+
+    #[test]
+    fn parse_ban_target() {
+        let peer = PeerId::new([0xab; 64]);
+        let hex = alloy_primitives::hex::encode(peer);
+        assert_eq!(hex.parse::<BanTarget>().unwrap(), BanTarget::Peer(peer));
+        assert_eq!(
+            format!("enode://{hex}@1.2.3.4:30303").parse::<BanTarget>().unwrap(),
+            BanTarget::Peer(peer)
+        );
+        assert_eq!("1.2.3.4".parse::<BanTarget>().unwrap(), BanTarget::Ip([1, 2, 3, 4].into()));
+        assert!(matches!("1.2.3.0/24".parse::<BanTarget>().unwrap(), BanTarget::Range(_)));
+        assert!("not a target".parse::<BanTarget>().is_err());
+
+        for target in [BanTarget::Peer(peer), BanTarget::Ip([1, 2, 3, 4].into())] {
+            assert_eq!(target.to_string().parse::<BanTarget>().unwrap(), target);
+        }
+    }
+
+    #[test]
+    fn entries_roundtrip() {
+        let peer = PeerId::new([1; 64]);
+        let range: IpCidr = "198.51.100.0/24".parse().unwrap();
+        let mut banlist = BanList::default();
+        banlist.ban_peer(peer);
+        banlist.ban_range_with(range, Some(Instant::now() + Duration::from_secs(3600)));
+
+        let mut restored = BanList::default();
+        let mut entries = banlist.entries().collect::<Vec<_>>();
+        // an expired ban is skipped
+        entries.push(BanEntry { target: BanTarget::Ip([1, 1, 1, 1].into()), until: Some(1) });
+        restored.extend(entries);
+
+        assert!(restored.is_banned_peer(&peer));
+        assert!(restored.is_banned_ip(&IpAddr::from([198, 51, 100, 1])));
+        assert!(!restored.is_banned_ip(&IpAddr::from([1, 1, 1, 1])));
+        assert_eq!(restored.entries().count(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_ban_entry() {
+        let entry = BanEntry { target: "1.2.3.0/24".parse().unwrap(), until: Some(100) };
+        let json = serde_json::to_string(&entry).unwrap();
+        assert_eq!(json, r#"{"target":"1.2.3.0/24","until":100}"#);
+        assert_eq!(serde_json::from_str::<BanEntry>(&json).unwrap(), entry);
+    }
+
     #[test]
     fn cannot_ban_non_global() {
         let mut ip = IpAddr::from([0, 0, 0, 0]);
@@ -42,6 +42,7 @@ use itertools::Itertools;
 use parking_lot::Mutex;
 use proto::{EnrRequest, EnrResponse};
 use reth_ethereum_forks::ForkId;
+use reth_net_banlist::BanTarget;
 use reth_network_peers::{pk2id, PeerId};
 use secp256k1::SecretKey;
 use std::{
@@ -167,6 +168,8 @@ type NodeRecordSender = OneshotSender<Vec<NodeRecord>>;
 pub struct Discv4 {
     /// The address of the udp socket
     local_addr: SocketAddr,
+    /// The address of the udp socket of the other address family, if running dual-stack.
+    secondary_addr: Option<SocketAddr>,
     /// channel to send commands over to the service
     to_service: mpsc::UnboundedSender<Discv4Command>,
     /// Tracks the local node record.
@@ -202,6 +205,7 @@ impl Discv4 {
             (IpAddr::from(std::net::Ipv4Addr::UNSPECIFIED), DEFAULT_DISCOVERY_PORT).into();
         Self {
             local_addr,
+            secondary_addr: None,
             to_service,
             node_record: Arc::new(Mutex::new(NodeRecord::new(
                 "127.0.0.1:3030".parse().unwrap(),
@@ -253,7 +257,54 @@ impl Discv4 {
         trace!(target: "discv4", ?local_addr,"opened UDP socket");
 
         let mut service =
-            Discv4Service::new(socket, local_addr, local_node_record, secret_key, config);
+            Discv4Service::new(socket, local_addr, None, local_node_record, secret_key, config);
+
+        // resolve the external address immediately
+        service.resolve_external_ip();
+
+        let discv4 = service.handle();
+        Ok((discv4, service))
+    }
+
+    /// Binds an IPv4 and an IPv6 `UdpSocket` and creates a service that runs discovery on both
+    /// address families.
+    ///
+    /// The `local_node_record` describes the endpoint of `local_address`. `secondary_address` must
+    /// be of the other address family; datagrams to peers of that family are sent from its
+    /// socket. If `secondary_address` is not unspecified, it is advertised together with
+    /// `secondary_tcp_port` as the endpoint of the other address family in the EIP-868 ENR.
+    /// Otherwise that endpoint is advertised once an external IP of that family is set, see
+    /// [`Discv4::set_external_ip_addr`].
+    pub async fn bind_dual_stack(
+        local_address: SocketAddr,
+        secondary_address: SocketAddr,
+        secondary_tcp_port: u16,
+        mut local_node_record: NodeRecord,
+        secret_key: SecretKey,
+        config: Discv4Config,
+    ) -> io::Result<(Self, Discv4Service)> {
+        if local_address.is_ipv4() == secondary_address.is_ipv4() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "dual-stack discovery requires an IPv4 and an IPv6 address",
+            ))
+        }
+
+        let socket = bind_single_stack_udp(local_address)?;
+        let local_addr = socket.local_addr()?;
+        local_node_record.udp_port = local_addr.port();
+        let secondary_socket = bind_single_stack_udp(secondary_address)?;
+        let secondary_addr = secondary_socket.local_addr()?;
+        trace!(target: "discv4", ?local_addr, ?secondary_addr, "opened dual-stack UDP sockets");
+
+        let mut service = Discv4Service::new(
+            socket,
+            local_addr,
+            Some((secondary_socket, secondary_tcp_port)),
+            local_node_record,
+            secret_key,
+            config,
+        );
 
         // resolve the external address immediately
         service.resolve_external_ip();
@@ -267,6 +318,12 @@ impl Discv4 {
         self.local_addr
     }
 
+    /// Returns the address of the UDP socket of the other address family, if the service runs
+    /// dual-stack, see [`Self::bind_dual_stack`].
+    pub const fn secondary_addr(&self) -> Option<SocketAddr> {
+        self.secondary_addr
+    }
+
     /// Returns the [`NodeRecord`] of the local node.
     ///
     /// This includes the currently tracked external IP address of the node.
@@ -370,6 +427,21 @@ impl Discv4 {
         self.send_to_service(cmd);
     }
 
+    /// Adds the peer, IP address or IP range to the ban list indefinitely or until the given
+    /// timestamp.
+    ///
+    /// This removes the nodes the ban applies to from the table and prevents any future inclusion.
+    pub fn ban_target(&self, target: BanTarget, until: Option<Instant>) {
+        let cmd = Discv4Command::BanTarget(target, until);
+        self.send_to_service(cmd);
+    }
+
+    /// Lifts the ban of the peer, IP address or IP range.
+    pub fn unban(&self, target: BanTarget) {
+        let cmd = Discv4Command::Unban(target);
+        self.send_to_service(cmd);
+    }
+
     /// Sets the tcp port
     ///
     /// This will update our [`NodeRecord`]'s tcp port.
@@ -378,6 +450,22 @@ impl Discv4 {
         self.send_to_service(cmd);
     }
 
+    /// Sets the udp port
+    ///
+    /// This will update our [`NodeRecord`]'s udp port, for example after the discovery port was
+    /// mapped to a different external port on the NAT gateway.
+    pub fn set_udp_port(&self, port: u16) {
+        let cmd = Discv4Command::SetUdpPort(port);
+        self.send_to_service(cmd);
+    }
+
+    /// Sets the external IP address of the node that is advertised to peers, for example after
+    /// the ports were mapped on the NAT gateway.
+    pub fn set_external_ip_addr(&self, ip: IpAddr) {
+        let cmd = Discv4Command::SetExternalIp(ip);
+        self.send_to_service(cmd);
+    }
+
     /// Sets the pair in the EIP-868 [`Enr`] of the node.
     ///
     /// If the key already exists, this will update it.
@@ -445,6 +533,10 @@ pub struct Discv4Service {
     shared_node_record: Arc<Mutex<NodeRecord>>,
     /// The secret key used to sign payloads
     secret_key: SecretKey,
+    /// Local address of the UDP socket of the other address family, if running dual-stack.
+    secondary_address: Option<SocketAddr>,
+    /// The `RLPx` port advertised for the other address family, if running dual-stack.
+    secondary_tcp_port: Option<u16>,
     /// The UDP socket for sending and receiving messages.
     _socket: Arc<UdpSocket>,
     /// The spawned UDP tasks.
@@ -507,9 +599,14 @@ pub struct Discv4Service {
 
 impl Discv4Service {
     /// Create a new instance for a bound [`UdpSocket`].
+    ///
+    /// If a secondary socket of the other address family is given, messages are received on both
+    /// sockets and the socket and the `RLPx` port of that family are advertised in the EIP-868
+    /// ENR.
     pub(crate) fn new(
         socket: UdpSocket,
         local_address: SocketAddr,
+        secondary_socket: Option<(UdpSocket, u16)>,
         local_node_record: NodeRecord,
         secret_key: SecretKey,
         config: Discv4Config,
@@ -519,11 +616,21 @@ impl Discv4Service {
         let (egress_tx, egress_rx) = mpsc::channel(config.udp_egress_message_buffer);
         let mut tasks = JoinSet::<()>::new();
 
+        let secondary_address =
+            secondary_socket.as_ref().and_then(|(socket, _)| socket.local_addr().ok());
+        let secondary_tcp_port = secondary_socket.as_ref().map(|(_, port)| *port);
+        let secondary_socket = secondary_socket.map(|(socket, _)| Arc::new(socket));
+
+        if let Some(secondary) = &secondary_socket {
+            let udp = Arc::clone(secondary);
+            tasks.spawn(receive_loop(udp, ingress_tx.clone(), local_node_record.id));
+        }
+
         let udp = Arc::clone(&socket);
         tasks.spawn(receive_loop(udp, ingress_tx, local_node_record.id));
 
         let udp = Arc::clone(&socket);
-        tasks.spawn(send_loop(udp, egress_rx));
+        tasks.spawn(send_loop(udp, secondary_socket, egress_rx));
 
         let kbuckets = KBucketsTable::new(
             NodeKey::from(&local_node_record).into(),
@@ -564,6 +671,17 @@ impl Discv4Service {
                 builder.udp6(local_node_record.udp_port);
                 builder.tcp6(local_node_record.tcp_port);
             }
+            if let (Some(addr), Some(tcp_port)) = (secondary_address, secondary_tcp_port) {
+                match addr.ip() {
+                    IpAddr::V4(ip) if !ip.is_unspecified() => {
+                        builder.ip4(ip).udp4(addr.port()).tcp4(tcp_port);
+                    }
+                    IpAddr::V6(ip) if !ip.is_unspecified() => {
+                        builder.ip6(ip).udp6(addr.port()).tcp6(tcp_port);
+                    }
+                    _ => {}
+                }
+            }
 
             for (key, val) in &config.additional_eip868_rlp_pairs {
                 builder.add_value_rlp(key, val.clone());
@@ -577,6 +695,8 @@ impl Discv4Service {
 
         Self {
             local_address,
+            secondary_address,
+            secondary_tcp_port,
             local_eip_868_enr,
             local_node_record,
             shared_node_record,
@@ -610,6 +730,7 @@ impl Discv4Service {
     pub fn handle(&self) -> Discv4 {
         Discv4 {
             local_addr: self.local_address,
+            secondary_addr: self.secondary_address,
             to_service: self.to_service.clone(),
             node_record: self.shared_node_record.clone(),
         }
@@ -636,7 +757,17 @@ impl Discv4Service {
 
     /// Sets the given ip address as the node's external IP in the node record announced in
     /// discovery
+    ///
+    /// If running dual-stack, an address of the other family than the node record is advertised
+    /// as the endpoint of the secondary socket in the EIP-868 ENR instead.
     pub fn set_external_ip_addr(&mut self, external_ip: IpAddr) {
+        if external_ip.is_ipv4() != self.local_node_record.address.is_ipv4() &&
+            self.secondary_address.is_some()
+        {
+            self.set_secondary_external_ip_addr(external_ip);
+            return
+        }
+
         if self.local_node_record.address != external_ip {
             debug!(target: "discv4", ?external_ip, "Updating external ip");
             self.local_node_record.address = external_ip;
@@ -647,6 +778,34 @@ impl Discv4Service {
         }
     }
 
+    /// Advertises the given ip address together with the ports of the secondary socket as the
+    /// endpoint of the other address family in the EIP-868 ENR.
+    fn set_secondary_external_ip_addr(&mut self, external_ip: IpAddr) {
+        let (Some(secondary_address), Some(tcp_port)) =
+            (self.secondary_address, self.secondary_tcp_port)
+        else {
+            return
+        };
+        let current_ip = if external_ip.is_ipv4() {
+            self.local_eip_868_enr.ip4().map(IpAddr::V4)
+        } else {
+            self.local_eip_868_enr.ip6().map(IpAddr::V6)
+        };
+        if current_ip == Some(external_ip) {
+            return
+        }
+
+        debug!(target: "discv4", ?external_ip, "Updating external ip of the secondary address family");
+        let udp_socket = SocketAddr::new(external_ip, secondary_address.port());
+        let _ = self.local_eip_868_enr.set_udp_socket(udp_socket, &self.secret_key);
+        let _ = if external_ip.is_ipv4() {
+            self.local_eip_868_enr.set_tcp4(tcp_port, &self.secret_key)
+        } else {
+            self.local_eip_868_enr.set_tcp6(tcp_port, &self.secret_key)
+        };
+        debug!(target: "discv4", enr=?self.local_eip_868_enr, "Updated local ENR");
+    }
+
     /// Returns the [`PeerId`] that identifies this node
     pub const fn local_peer_id(&self) -> &PeerId {
         &self.local_node_record.id
@@ -657,6 +816,11 @@ impl Discv4Service {
         self.local_address
     }
 
+    /// Returns the address of the UDP socket of the other address family, if running dual-stack.
+    pub const fn secondary_addr(&self) -> Option<SocketAddr> {
+        self.secondary_address
+    }
+
     /// Returns the ENR of this service.
     ///
     /// Note: this will include the external address if resolved.
@@ -865,6 +1029,22 @@ impl Discv4Service {
         self.config.ban_list.ban_peer_until(node_id, until);
     }
 
+    /// Adds the peer, IP address or IP range to the ban list indefinitely or until the given
+    /// timestamp, and removes the nodes the ban applies to from the table.
+    pub fn ban_target(&mut self, target: BanTarget, until: Option<Instant>) {
+        let banned = self
+            .kbuckets
+            .iter_ref()
+            .map(|entry| entry.node.value.record)
+            .filter(|record| target.matches(&record.id, &record.address))
+            .map(|record| record.id)
+            .collect::<Vec<_>>();
+        for node_id in banned {
+            self.remove_node(node_id);
+        }
+        self.config.ban_list.ban_with(target, until);
+    }
+
     /// Removes a `node_id` from the routing table.
     ///
     /// This allows applications, for whatever reason, to remove nodes from the local routing
@@ -1750,6 +1930,12 @@ impl Discv4Service {
                     Discv4Command::BanIp(ip) => {
                         self.ban_ip(ip);
                     }
+                    Discv4Command::BanTarget(target, until) => {
+                        self.ban_target(target, until);
+                    }
+                    Discv4Command::Unban(target) => {
+                        self.config.ban_list.unban(&target);
+                    }
                     Discv4Command::SetEIP868RLPPair { key, rlp } => {
                         debug!(target: "discv4", key=%String::from_utf8_lossy(&key), "Update EIP-868 extension pair");
 
@@ -1764,6 +1950,19 @@ impl Discv4Service {
                             let _ = self.local_eip_868_enr.set_tcp6(port, &self.secret_key);
                         }
                     }
+                    Discv4Command::SetUdpPort(port) => {
+                        debug!(target: "discv4", %port, "Update udp port");
+                        self.local_node_record.udp_port = port;
+                        if self.local_node_record.address.is_ipv4() {
+                            let _ = self.local_eip_868_enr.set_udp4(port, &self.secret_key);
+                        } else {
+                            let _ = self.local_eip_868_enr.set_udp6(port, &self.secret_key);
+                        }
+                        *self.shared_node_record.lock() = self.local_node_record;
+                    }
+                    Discv4Command::SetExternalIp(ip) => {
+                        self.set_external_ip_addr(ip);
+                    }
 
                     Discv4Command::Terminated => {
                         // terminate the service
@@ -1899,11 +2098,42 @@ pub enum Discv4Event {
     Terminated,
 }
 
-/// Continuously reads new messages from the channel and writes them to the socket
-pub(crate) async fn send_loop(udp: Arc<UdpSocket>, rx: EgressReceiver) {
+/// Binds a `UdpSocket` that only handles traffic of the address family of the given address, so
+/// that an IPv4 and an IPv6 socket can be bound to the same port.
+fn bind_single_stack_udp(addr: SocketAddr) -> io::Result<UdpSocket> {
+    let socket = socket2::Socket::new(
+        socket2::Domain::for_address(addr),
+        socket2::Type::DGRAM,
+        Some(socket2::Protocol::UDP),
+    )?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    UdpSocket::from_std(socket.into())
+}
+
+/// Continuously reads new messages from the channel and writes them to the socket.
+///
+/// If a secondary socket is given, messages to addresses of its address family are sent from the
+/// secondary socket.
+pub(crate) async fn send_loop(
+    udp: Arc<UdpSocket>,
+    secondary: Option<Arc<UdpSocket>>,
+    rx: EgressReceiver,
+) {
+    let secondary = secondary.and_then(|socket| {
+        let is_ipv6 = socket.local_addr().ok()?.is_ipv6();
+        Some((socket, is_ipv6))
+    });
     let mut stream = ReceiverStream::new(rx);
     while let Some((payload, to)) = stream.next().await {
-        match udp.send_to(&payload, to).await {
+        let socket = match &secondary {
+            Some((socket, is_ipv6)) if to.is_ipv6() == *is_ipv6 => socket,
+            _ => &udp,
+        };
+        match socket.send_to(&payload, to).await {
             Ok(size) => {
                 trace!(target: "discv4", ?to, ?size,"sent payload");
             }
@@ -2045,10 +2275,14 @@ impl Default for ReceiveCache {
 enum Discv4Command {
     Add(NodeRecord),
     SetTcpPort(u16),
+    SetUdpPort(u16),
+    SetExternalIp(IpAddr),
     SetEIP868RLPPair { key: Vec<u8>, rlp: Bytes },
     Ban(PeerId, IpAddr),
     BanPeer(PeerId),
     BanIp(IpAddr),
+    BanTarget(BanTarget, Option<Instant>),
+    Unban(BanTarget),
     Remove(PeerId),
     Lookup { node_id: Option<PeerId>, tx: Option<NodeRecordSender> },
     SetLookupInterval(Duration),
@@ -2434,6 +2668,97 @@ mod tests {
         assert_eq!(expected, decoded);
     }
 
+    #[tokio::test]
+    async fn test_dual_stack_enr() {
+        let (secret_key, _) = secp256k1::SECP256K1.generate_keypair(&mut rand_08::thread_rng());
+        let local_addr: SocketAddr = (Ipv4Addr::LOCALHOST, 0).into();
+        let local_enr = NodeRecord::from_secret_key(local_addr, &secret_key).with_tcp_port(30303);
+        let (discv4, service) = Discv4::bind_dual_stack(
+            local_addr,
+            (std::net::Ipv6Addr::LOCALHOST, 0).into(),
+            30304,
+            local_enr,
+            secret_key,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        let secondary_addr = discv4.secondary_addr().unwrap();
+        assert!(secondary_addr.is_ipv6());
+        let enr = &service.local_eip_868_enr;
+        assert_eq!(enr.ip4(), Some(Ipv4Addr::LOCALHOST));
+        assert_eq!(enr.tcp4(), Some(30303));
+        assert_eq!(enr.ip6(), Some(std::net::Ipv6Addr::LOCALHOST));
+        assert_eq!(enr.udp6(), Some(secondary_addr.port()));
+        assert_eq!(enr.tcp6(), Some(30304));
+
+        // both addresses must be of different families
+        let res = Discv4::bind_dual_stack(
+            local_addr,
+            local_addr,
+            30304,
+            local_enr,
+            secret_key,
+            Default::default(),
+        )
+        .await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dual_stack_enr_unspecified_secondary() {
+        let (secret_key, _) = secp256k1::SECP256K1.generate_keypair(&mut rand_08::thread_rng());
+        let local_addr: SocketAddr = (Ipv4Addr::LOCALHOST, 0).into();
+        let local_enr = NodeRecord::from_secret_key(local_addr, &secret_key).with_tcp_port(30303);
+        let (discv4, mut service) = Discv4::bind_dual_stack(
+            local_addr,
+            (std::net::Ipv6Addr::UNSPECIFIED, 0).into(),
+            30304,
+            local_enr,
+            secret_key,
+            Discv4Config::builder().external_ip_resolver(None).build(),
+        )
+        .await
+        .unwrap();
+
+        // the unspecified address is not advertised
+        assert_eq!(service.local_eip_868_enr.ip6(), None);
+
+        // an external address of the other family is advertised for the secondary socket
+        let external_ip: std::net::Ipv6Addr = "2001:db8::1".parse().unwrap();
+        service.set_external_ip_addr(external_ip.into());
+        let enr = &service.local_eip_868_enr;
+        assert_eq!(enr.ip4(), Some(Ipv4Addr::LOCALHOST));
+        assert_eq!(enr.tcp4(), Some(30303));
+        assert_eq!(enr.ip6(), Some(external_ip));
+        assert_eq!(enr.udp6(), Some(discv4.secondary_addr().unwrap().port()));
+        assert_eq!(enr.tcp6(), Some(30304));
+        assert_eq!(service.local_enr().address, IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        // an external address of the node record's family still updates the node record
+        let external_ip = Ipv4Addr::new(203, 0, 113, 1);
+        service.set_external_ip_addr(external_ip.into());
+        assert_eq!(service.local_eip_868_enr.ip4(), Some(external_ip));
+        assert_eq!(service.local_enr().address, IpAddr::V4(external_ip));
+    }
+
+    #[tokio::test]
+    async fn test_set_udp_port() {
+        let (discv4, mut service) = create_discv4().await;
+        discv4.set_udp_port(40404);
+
+        poll_fn(|cx| {
+            let _ = service.poll(cx);
+            Poll::Ready(())
+        })
+        .await;
+
+        assert_eq!(service.local_enr().udp_port, 40404);
+        assert_eq!(service.local_eip_868_enr.udp4(), Some(40404));
+        assert_eq!(discv4.node_record().udp_port, 40404);
+    }
+
     #[test]
     fn test_enr_forkid_entry_decode() {
         let raw: [u8; 8] = [0xc7, 0xc6, 0x84, 0xdc, 0xe9, 0x6c, 0x2d, 0x80];
@@ -2506,6 +2831,22 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_ban_range() {
+        let (_, mut service) = create_discv4().await;
+
+        let banned = NodeRecord::new("203.0.113.1:30303".parse().unwrap(), PeerId::random());
+        let other = NodeRecord::new("198.51.100.1:30303".parse().unwrap(), PeerId::random());
+        assert!(service.add_node(banned));
+        assert!(service.add_node(other));
+
+        let range = BanTarget::Range("203.0.113.0/24".parse().unwrap());
+        service.ban_target(range, None);
+        assert!(!service.contains_node(banned.id));
+        assert!(service.contains_node(other.id));
+        assert!(service.config.ban_list.is_banned_ip(&"203.0.113.9".parse().unwrap()));
+    }
+
     // Bootstraps with mainnet boot nodes
     #[tokio::test(flavor = "multi_thread")]
     #[ignore]
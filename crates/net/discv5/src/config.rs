@@ -72,6 +72,8 @@ pub struct ConfigBuilder {
     /// NOTE: IP address of `RLPx` socket overwrites IP address of same IP version in
     /// [`discv5::ListenConfig`].
     tcp_socket: SocketAddr,
+    /// `RLPx` TCP socket of the other IP version to advertise, if `RLPx` runs dual-stack.
+    secondary_tcp_socket: Option<SocketAddr>,
     /// List of `(key, rlp-encoded-value)` tuples that should be advertised in local node record
     /// (in addition to tcp port, udp port and fork).
     other_enr_kv_pairs: Vec<(&'static [u8], Bytes)>,
@@ -95,6 +97,7 @@ impl ConfigBuilder {
             bootstrap_nodes,
             fork,
             tcp_socket,
+            secondary_tcp_socket,
             other_enr_kv_pairs,
             lookup_interval,
             bootstrap_lookup_interval,
@@ -107,6 +110,7 @@ impl ConfigBuilder {
             bootstrap_nodes,
             fork: fork.map(|(key, fork_id)| (key, fork_id.fork_id)),
             tcp_socket,
+            secondary_tcp_socket,
             other_enr_kv_pairs,
             lookup_interval: Some(lookup_interval),
             bootstrap_lookup_interval: Some(bootstrap_lookup_interval),
@@ -177,6 +181,14 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets the tcp socket of the other IP version to advertise in the local
+    /// [`Enr`](discv5::enr::Enr), if `RLPx` listens on both IPv4 and IPv6. The IP address of this
+    /// socket will overwrite the discovery address of the same IP version, if one is configured.
+    pub const fn secondary_tcp_socket(mut self, socket: SocketAddr) -> Self {
+        self.secondary_tcp_socket = Some(socket);
+        self
+    }
+
     /// Adds an additional kv-pair to include in the local [`Enr`](discv5::enr::Enr). Takes the key
     /// to use for the kv-pair and the rlp encoded value.
     pub fn add_enr_kv_pair(mut self, key: &'static [u8], value: Bytes) -> Self {
@@ -221,6 +233,7 @@ impl ConfigBuilder {
             bootstrap_nodes,
             fork,
             tcp_socket,
+            secondary_tcp_socket,
             other_enr_kv_pairs,
             lookup_interval,
             bootstrap_lookup_interval,
@@ -234,6 +247,13 @@ impl ConfigBuilder {
 
         discv5_config.listen_config =
             amend_listen_config_wrt_rlpx(&discv5_config.listen_config, tcp_socket.ip());
+        // a secondary socket is only meaningful if it's of the other IP version
+        let secondary_tcp_socket =
+            secondary_tcp_socket.filter(|socket| socket.is_ipv4() != tcp_socket.is_ipv4());
+        if let Some(socket) = secondary_tcp_socket {
+            discv5_config.listen_config =
+                amend_listen_config_wrt_rlpx(&discv5_config.listen_config, socket.ip());
+        }
 
         let fork = fork.map(|(key, fork_id)| (key, fork_id.into()));
 
@@ -251,6 +271,7 @@ impl ConfigBuilder {
             bootstrap_nodes,
             fork,
             tcp_socket,
+            secondary_tcp_socket,
             other_enr_kv_pairs,
             lookup_interval,
             bootstrap_lookup_interval,
@@ -276,6 +297,8 @@ pub struct Config {
     /// NOTE: IP address of `RLPx` socket overwrites IP address of same IP version in
     /// [`discv5::ListenConfig`].
     pub(super) tcp_socket: SocketAddr,
+    /// `RLPx` TCP socket of the other IP version to advertise, if `RLPx` runs dual-stack.
+    pub(super) secondary_tcp_socket: Option<SocketAddr>,
     /// Additional kv-pairs (besides tcp port, udp port and fork) that should be advertised to
     /// peers by including in local node record.
     pub(super) other_enr_kv_pairs: Vec<(&'static [u8], Bytes)>,
@@ -300,6 +323,7 @@ impl Config {
             bootstrap_nodes: HashSet::default(),
             fork: None,
             tcp_socket: rlpx_tcp_socket,
+            secondary_tcp_socket: None,
             other_enr_kv_pairs: Vec::new(),
             lookup_interval: None,
             bootstrap_lookup_interval: None,
@@ -345,6 +369,11 @@ impl Config {
     pub const fn rlpx_socket(&self) -> &SocketAddr {
         &self.tcp_socket
     }
+
+    /// Returns the `RLPx` (TCP) socket of the other IP version, if `RLPx` runs dual-stack.
+    pub const fn secondary_rlpx_socket(&self) -> Option<&SocketAddr> {
+        self.secondary_tcp_socket.as_ref()
+    }
 }
 
 /// Returns the IPv4 discovery socket if one is configured.
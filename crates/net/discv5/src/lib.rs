@@ -145,6 +145,31 @@ impl Discv5 {
         self.discv5.ban_ip(ip, None);
     }
 
+    /// Returns the UDP port advertised in the local [`Enr`].
+    pub fn local_udp_port(&self) -> Option<u16> {
+        let enr = self.discv5.local_enr();
+        enr.udp4().or_else(|| enr.udp6())
+    }
+
+    /// Sets the external address advertised in the local [`Enr`], for example after the ports
+    /// were mapped on the NAT gateway.
+    ///
+    /// The `tcp_port` is the `RLPx` port, it's only updated if set.
+    pub fn set_external_addr(&self, ip: IpAddr, udp_port: u16, tcp_port: Option<u16>) {
+        let mut updated = self.discv5.update_local_enr_socket(SocketAddr::new(ip, udp_port), false);
+        if let Some(tcp_port) = tcp_port {
+            updated |= self.discv5.update_local_enr_socket(SocketAddr::new(ip, tcp_port), true);
+        }
+        if updated {
+            debug!(target: "net::discv5",
+                %ip,
+                udp_port,
+                ?tcp_port,
+                "updated external address in local enr"
+            );
+        }
+    }
+
     /// Returns the [`NodeRecord`] of the local node.
     ///
     /// This includes the currently tracked external IP address of the node.
@@ -353,7 +378,14 @@ impl Discv5 {
         let tcp_port = (match self.rlpx_ip_mode {
             IpMode::Ip4 => enr.tcp4(),
             IpMode::Ip6 => enr.tcp6(),
-            IpMode::DualStack => unimplemented!("dual-stack support not implemented for rlpx"),
+            // dial over the IP version of the socket the peer was discovered on
+            IpMode::DualStack => {
+                if address.is_ipv4() {
+                    enr.tcp4()
+                } else {
+                    enr.tcp6()
+                }
+            }
         })
         .unwrap_or(
             // tcp socket is missing from ENR, or is wrong IP version.
@@ -452,7 +484,17 @@ pub fn build_local_enr(
 ) -> (Enr<SecretKey>, NodeRecord, Option<&'static [u8]>, IpMode) {
     let mut builder = discv5::enr::Enr::builder();
 
-    let Config { discv5_config, fork, tcp_socket, other_enr_kv_pairs, .. } = config;
+    let Config {
+        discv5_config, fork, tcp_socket, secondary_tcp_socket, other_enr_kv_pairs, ..
+    } = config;
+
+    // the rlpx sockets per IP version, the primary socket is advertised for both if there is no
+    // socket for the other IP version
+    let (tcp4_port, tcp6_port) = match secondary_tcp_socket {
+        Some(secondary) if tcp_socket.is_ipv4() => (tcp_socket.port(), secondary.port()),
+        Some(secondary) => (secondary.port(), tcp_socket.port()),
+        None => (tcp_socket.port(), tcp_socket.port()),
+    };
 
     let socket = match discv5_config.listen_config {
         ListenConfig::Ipv4 { ip, port } => {
@@ -478,18 +520,27 @@ pub fn build_local_enr(
                 builder.ip4(ipv4);
             }
             builder.udp4(ipv4_port);
-            builder.tcp4(tcp_socket.port());
+            builder.tcp4(tcp4_port);
 
             if ipv6 != Ipv6Addr::UNSPECIFIED {
                 builder.ip6(ipv6);
             }
             builder.udp6(ipv6_port);
+            if secondary_tcp_socket.is_some() {
+                builder.tcp6(tcp6_port);
+            }
 
             (ipv6, ipv6_port).into()
         }
     };
 
-    let rlpx_ip_mode = if tcp_socket.is_ipv4() { IpMode::Ip4 } else { IpMode::Ip6 };
+    let rlpx_ip_mode = if secondary_tcp_socket.is_some() {
+        IpMode::DualStack
+    } else if tcp_socket.is_ipv4() {
+        IpMode::Ip4
+    } else {
+        IpMode::Ip6
+    };
 
     // identifies which network node is on
     let network_stack_id = fork.as_ref().map(|(network_stack_id, fork_value)| {
@@ -914,6 +965,32 @@ mod test {
         assert_eq!(TCP_PORT, enr.tcp4().unwrap()); // listen config is defaulting to ip mode ipv4
     }
 
+    #[test]
+    fn build_dual_stack_enr_from_config() {
+        let config = Config::builder((Ipv4Addr::UNSPECIFIED, 30303).into())
+            .secondary_tcp_socket((Ipv6Addr::UNSPECIFIED, 30304).into())
+            .build();
+
+        let sk = SecretKey::new(&mut thread_rng());
+        let (enr, _, _, ip_mode) = build_local_enr(&sk, &config);
+
+        assert_eq!(ip_mode, IpMode::DualStack);
+        assert_eq!(enr.tcp4(), Some(30303));
+        assert_eq!(enr.tcp6(), Some(30304));
+        assert!(enr.udp4().is_some());
+        assert!(enr.udp6().is_some());
+
+        // peers are dialed over the IP version they were discovered on
+        let mut discv5 = discv5_noop();
+        discv5.rlpx_ip_mode = ip_mode;
+        let peer_sk = CombinedKey::generate_secp256k1();
+        let peer_enr = Enr::builder().tcp4(1).tcp6(2).build(&peer_sk).unwrap();
+        let v4 = discv5.try_into_reachable(&peer_enr, (Ipv4Addr::LOCALHOST, 9).into()).unwrap();
+        assert_eq!(v4.tcp_port, 1);
+        let v6 = discv5.try_into_reachable(&peer_enr, (Ipv6Addr::LOCALHOST, 9).into()).unwrap();
+        assert_eq!(v6.tcp_port, 2);
+    }
+
     #[test]
     fn get_fork_id_with_different_network_stack_ids() {
         unsafe {
@@ -51,6 +51,7 @@ use tracing::{debug, trace};
 
 mod config;
 mod error;
+pub mod publish;
 mod query;
 pub mod resolver;
 mod sync;
@@ -0,0 +1,343 @@
+//! Support for publishing node lists as [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) trees.
+//!
+//! A [`DnsTree`] is built from a set of node records and links to other trees. The entries of the
+//! tree are published as TXT records: the signed root at the domain itself and all other entries
+//! at `<hash>.<domain>`, where the hash is the base32 encoded, truncated keccak256 hash of the
+//! entry's text.
+//!
+//! ```
+//! use reth_dns_discovery::publish::DnsTree;
+//! use secp256k1::SecretKey;
+//!
+//! let key = SecretKey::new(&mut secp256k1::rand::thread_rng());
+//! let tree = DnsTree::new(1, Vec::new(), Vec::new(), &key);
+//! let zone = tree.to_zone_file("nodes.example.org", 3600);
+//! assert!(zone.contains("enrtree-root:v1"));
+//! ```
+
+use crate::tree::{BranchEntry, LinkEntry, TreeRootEntry};
+use alloy_primitives::keccak256;
+use data_encoding::BASE32_NOPAD;
+use enr::{Enr, EnrKey};
+use secp256k1::{PublicKey, SecretKey};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// The maximum number of children of a branch entry.
+///
+/// This keeps branch records below 370 bytes, so they fit into a single DNS packet.
+const MAX_CHILDREN: usize = 370 / (HASH_LEN + 1);
+
+/// The length of the base32 encoded hash of an entry.
+const HASH_LEN: usize = 26;
+
+/// The maximum length of a single character string of a TXT record.
+const MAX_TXT_STRING_LEN: usize = 255;
+
+/// A signed tree of node records and links.
+#[derive(Debug, Clone)]
+pub struct DnsTree {
+    /// The signed root of the tree.
+    root: TreeRootEntry,
+    /// The key the root is signed with.
+    pubkey: PublicKey,
+    /// The text of all entries, except the root, by hash.
+    entries: BTreeMap<String, String>,
+}
+
+// === impl DnsTree ===
+
+impl DnsTree {
+    /// Builds the tree of the given node records and links, signed with the given key.
+    ///
+    /// Node records are sorted by node ID, so the tree doesn't depend on the order of the input.
+    pub fn new(
+        sequence_number: u64,
+        mut enrs: Vec<Enr<SecretKey>>,
+        links: Vec<LinkEntry>,
+        key: &SecretKey,
+    ) -> Self {
+        enrs.sort_unstable_by_key(|enr| enr.node_id().raw());
+        enrs.dedup_by_key(|enr| enr.node_id());
+
+        let mut entries = BTreeMap::new();
+        let enr_entries = enrs.into_iter().map(|enr| enr.to_base64()).collect();
+        let enr_root = build_subtree(enr_entries, &mut entries);
+        let link_entries = links.into_iter().map(|link| link.to_string()).collect();
+        let link_root = build_subtree(link_entries, &mut entries);
+
+        let mut root = TreeRootEntry {
+            enr_root: insert_entry(enr_root, &mut entries),
+            link_root: insert_entry(link_root, &mut entries),
+            sequence_number,
+            signature: Default::default(),
+        };
+        root.sign_recoverable(key);
+
+        Self { root, pubkey: key.public(), entries }
+    }
+
+    /// Returns the signed root of the tree.
+    pub const fn root(&self) -> &TreeRootEntry {
+        &self.root
+    }
+
+    /// Returns the link to the tree if it's published at the given domain.
+    pub fn link(&self, domain: &str) -> LinkEntry {
+        LinkEntry { domain: domain.trim_end_matches('.').to_string(), pubkey: self.pubkey }
+    }
+
+    /// Returns the TXT records of the tree published at the given domain, by name.
+    pub fn records(&self, domain: &str) -> BTreeMap<String, String> {
+        let domain = domain.trim_end_matches('.');
+        let mut records = BTreeMap::from([(domain.to_string(), self.root.to_string())]);
+        records.extend(
+            self.entries.iter().map(|(hash, entry)| (format!("{hash}.{domain}"), entry.clone())),
+        );
+        records
+    }
+
+    /// Returns the tree published at the given domain as zone file.
+    pub fn to_zone_file(&self, domain: &str, ttl: u32) -> String {
+        let mut zone = format!("; {}\n", self.link(domain));
+        for (name, value) in self.records(domain) {
+            zone.push_str(&format!("{name}.\t{ttl}\tIN\tTXT\t{}\n", quote_txt(&value)));
+        }
+        zone
+    }
+
+    /// Returns the changes needed to update the given records to the tree published at the given
+    /// domain.
+    pub fn diff(&self, domain: &str, existing: &BTreeMap<String, String>) -> RecordSetDiff {
+        let records = self.records(domain);
+        let mut diff = RecordSetDiff::default();
+        for (name, value) in &records {
+            match existing.get(name) {
+                None => {
+                    diff.created.insert(name.clone(), value.clone());
+                }
+                Some(existing) if existing != value => {
+                    diff.updated.insert(name.clone(), value.clone());
+                }
+                Some(_) => {}
+            }
+        }
+        diff.deleted.extend(existing.keys().filter(|name| !records.contains_key(*name)).cloned());
+        diff
+    }
+}
+
+/// The changes to a record set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordSetDiff {
+    /// Records to create, by name.
+    pub created: BTreeMap<String, String>,
+    /// Records whose value changed, by name.
+    pub updated: BTreeMap<String, String>,
+    /// Names of the records to delete.
+    pub deleted: BTreeSet<String>,
+}
+
+impl RecordSetDiff {
+    /// Returns `true` if there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+    }
+}
+
+impl fmt::Display for RecordSetDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.created {
+            writeln!(f, "+ {name} {}", quote_txt(value))?;
+        }
+        for (name, value) in &self.updated {
+            writeln!(f, "~ {name} {}", quote_txt(value))?;
+        }
+        for name in &self.deleted {
+            writeln!(f, "- {name}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the TXT records of a zone file, by name.
+///
+/// Other record types, comments and directives are ignored. Names are expected to be fully
+/// qualified, as written by [`DnsTree::to_zone_file`].
+pub fn parse_zone_records(zone: &str) -> BTreeMap<String, String> {
+    let mut records = BTreeMap::new();
+    for line in zone.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('$') {
+            continue
+        }
+        let Some((name, rest)) = line.split_once(char::is_whitespace) else { continue };
+        let Some((_, value)) = rest.split_once("TXT") else { continue };
+        // the value may be split into multiple quoted strings
+        let value = value.split('"').skip(1).step_by(2).collect::<String>();
+        records.insert(name.trim_end_matches('.').to_string(), value);
+    }
+    records
+}
+
+/// Builds the subtree of the given entries and returns the text of its root entry.
+///
+/// All other entries of the subtree are added to the entries.
+fn build_subtree(mut children: Vec<String>, entries: &mut BTreeMap<String, String>) -> String {
+    if children.len() == 1 {
+        return children.pop().expect("one child")
+    }
+    if children.len() <= MAX_CHILDREN {
+        let children = children.into_iter().map(|entry| insert_entry(entry, entries)).collect();
+        return BranchEntry { children }.to_string()
+    }
+
+    let subtrees =
+        children.chunks(MAX_CHILDREN).map(|chunk| build_subtree(chunk.to_vec(), entries)).collect();
+    build_subtree(subtrees, entries)
+}
+
+/// Adds the entry and returns its hash.
+fn insert_entry(entry: String, entries: &mut BTreeMap<String, String>) -> String {
+    let hash = entry_hash(&entry);
+    entries.insert(hash.clone(), entry);
+    hash
+}
+
+/// Returns the subdomain of the entry, the base32 encoded first 16 bytes of its keccak256 hash.
+fn entry_hash(entry: &str) -> String {
+    BASE32_NOPAD.encode(&keccak256(entry.as_bytes())[..16])
+}
+
+/// Quotes the value of a TXT record, splitting it into strings of at most 255 bytes.
+fn quote_txt(value: &str) -> String {
+    value
+        .as_bytes()
+        .chunks(MAX_TXT_STRING_LEN)
+        .map(|chunk| format!("\"{}\"", String::from_utf8_lossy(chunk)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DnsDiscoveryConfig, DnsDiscoveryEvent, DnsDiscoveryService, MapResolver};
+    use secp256k1::rand::thread_rng;
+    use std::{collections::HashSet, net::Ipv4Addr, num::NonZeroUsize, sync::Arc};
+    use tokio_stream::StreamExt;
+
+    fn enrs(num: u16) -> Vec<Enr<SecretKey>> {
+        (0..num)
+            .map(|i| {
+                let key = SecretKey::new(&mut thread_rng());
+                Enr::builder().ip4(Ipv4Addr::LOCALHOST).tcp4(30303 + i).build(&key).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn entry_hash_length() {
+        assert_eq!(entry_hash("enrtree-branch:").len(), HASH_LEN);
+        assert_eq!(MAX_CHILDREN, 13);
+    }
+
+    #[test]
+    fn build_tree() {
+        let key = SecretKey::new(&mut thread_rng());
+        let enrs = enrs(40);
+        let tree = DnsTree::new(7, enrs.clone(), Vec::new(), &key);
+
+        let root = tree.root();
+        assert_eq!(root.sequence_number, 7);
+        assert_eq!(root.signature.len(), 65);
+        assert!(root.verify::<SecretKey>(&key.public()));
+
+        // the tree doesn't depend on the order of the records
+        let mut reversed = enrs;
+        reversed.reverse();
+        assert_eq!(DnsTree::new(7, reversed, Vec::new(), &key).root().enr_root, root.enr_root);
+
+        // every branch fits into a single record
+        let records = tree.records("nodes.example.org");
+        for (name, value) in &records {
+            if let Some(children) = value.strip_prefix("enrtree-branch:") {
+                assert!(children.split(',').count() <= MAX_CHILDREN);
+            }
+            if name != "nodes.example.org" {
+                let hash = name.strip_suffix(".nodes.example.org").unwrap();
+                assert_eq!(entry_hash(value), hash);
+            }
+        }
+
+        // the empty link tree
+        assert_eq!(records[&format!("{}.nodes.example.org", root.link_root)], "enrtree-branch:");
+    }
+
+    #[test]
+    fn zone_file_roundtrip() {
+        let key = SecretKey::new(&mut thread_rng());
+        let tree = DnsTree::new(1, enrs(20), Vec::new(), &key);
+        let zone = tree.to_zone_file("nodes.example.org.", 300);
+        assert_eq!(parse_zone_records(&zone), tree.records("nodes.example.org"));
+    }
+
+    #[test]
+    fn diff_record_sets() {
+        let key = SecretKey::new(&mut thread_rng());
+        let mut enrs = enrs(3);
+        let tree = DnsTree::new(1, enrs.clone(), Vec::new(), &key);
+        let existing = tree.records("nodes.example.org");
+        assert!(tree.diff("nodes.example.org", &existing).is_empty());
+
+        enrs.pop();
+        let updated = DnsTree::new(2, enrs, Vec::new(), &key);
+        let diff = updated.diff("nodes.example.org", &existing);
+        assert_eq!(diff.updated.keys().collect::<Vec<_>>(), vec!["nodes.example.org"]);
+        // the removed record and the previous branch
+        assert_eq!(diff.deleted.len(), 2);
+        // the new branch
+        assert_eq!(diff.created.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn resolve_published_tree() {
+        reth_tracing::init_test_tracing();
+
+        let key = SecretKey::new(&mut thread_rng());
+        let enrs = enrs(30);
+
+        // a linked tree with a single node
+        let linked_key = SecretKey::new(&mut thread_rng());
+        let linked_enrs = self::enrs(1);
+        let linked = DnsTree::new(1, linked_enrs.clone(), Vec::new(), &linked_key);
+
+        let link = linked.link("linked.example.org");
+        let tree = DnsTree::new(1, enrs.clone(), vec![link], &key);
+
+        let resolver = MapResolver::default();
+        for (name, value) in tree
+            .records("nodes.example.org")
+            .into_iter()
+            .chain(linked.records("linked.example.org"))
+        {
+            resolver.insert(name, value);
+        }
+
+        let config = DnsDiscoveryConfig {
+            max_requests_per_sec: NonZeroUsize::new(1_000).unwrap(),
+            ..Default::default()
+        };
+        let mut service = DnsDiscoveryService::new(Arc::new(resolver), config);
+        service.sync_tree_with_link(tree.link("nodes.example.org"));
+
+        let mut expected =
+            enrs.iter().chain(&linked_enrs).map(|enr| enr.node_id()).collect::<HashSet<_>>();
+        while !expected.is_empty() {
+            let DnsDiscoveryEvent::Enr(enr) = service.next().await.unwrap();
+            assert!(expected.remove(&enr.node_id()), "unexpected record {enr}");
+        }
+    }
+}
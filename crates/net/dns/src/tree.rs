@@ -21,10 +21,10 @@ use crate::error::{
     ParseDnsEntryError::{FieldNotFound, UnknownEntry},
     ParseEntryResult,
 };
-use alloy_primitives::{hex, Bytes};
+use alloy_primitives::{hex, keccak256, Bytes};
 use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD};
 use enr::{Enr, EnrKey, EnrKeyUnambiguous, EnrPublicKey, Error as EnrError};
-use secp256k1::SecretKey;
+use secp256k1::{Message, SecretKey, SECP256K1};
 #[cfg(feature = "serde")]
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::{
@@ -141,6 +141,20 @@ impl TreeRootEntry {
         Ok(())
     }
 
+    /// Signs the content with the given key.
+    ///
+    /// Unlike [`TreeRootEntry::sign`], this produces the 65 byte signature including the recovery
+    /// ID, which is required by EIP-1459.
+    pub fn sign_recoverable(&mut self, key: &SecretKey) {
+        let hash = keccak256(self.content().as_bytes());
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&Message::from_digest(hash.0), key)
+            .serialize_compact();
+        let mut sig = signature.to_vec();
+        sig.push(i32::from(recovery_id) as u8);
+        self.signature = sig.into();
+    }
+
     /// Verify the signature of the record.
     #[must_use]
     pub fn verify<K: EnrKey>(&self, pubkey: &K::PublicKey) -> bool {
@@ -210,6 +224,11 @@ impl BranchEntry {
             Ok(hash.to_string())
         }
 
+        // an empty list has no children
+        if input.trim().is_empty() {
+            return Ok(Self { children: Vec::new() })
+        }
+
         let children =
             input.trim().split(',').map(ensure_valid_hash).collect::<ParseEntryResult<Vec<_>>>()?;
         Ok(Self { children })
@@ -392,6 +411,14 @@ mod tests {
             _ => unreachable!(),
         }
     }
+    #[test]
+    fn parse_empty_branch_entry() {
+        let s = "enrtree-branch:";
+        let entry: BranchEntry = s.parse().unwrap();
+        assert!(entry.children.is_empty());
+        assert_eq!(entry.to_string(), s);
+    }
+
     #[test]
     fn parse_branch_entry_base32() {
         let s = "enrtree-branch:YNEGZIWHOM7TOOSUATAPTM";
@@ -93,10 +93,123 @@ impl<T: TxReceipt> From<Receipts69<T>> for Receipts<T> {
     }
 }
 
+/// Eth/70 request for transaction receipts from the given block hashes, starting at the given
+/// receipt index of the first block.
+///
+/// This allows continuing a response that was cut off in the middle of a block, see
+/// [`Receipts70`].
+///
+/// Note: the fields are encoded without a list header, so that they are flattened into the
+/// [`RequestPair`](crate::message::RequestPair) as `[request-id, firstBlockReceiptIndex,
+/// [blockhash₁, ...]]`.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct GetReceipts70 {
+    /// The index of the first receipt to return for the first block.
+    pub first_block_receipt_index: u64,
+    /// The block hashes to request receipts for.
+    pub block_hashes: Vec<B256>,
+}
+
+impl alloy_rlp::Encodable for GetReceipts70 {
+    #[inline]
+    fn encode(&self, out: &mut dyn alloy_rlp::BufMut) {
+        self.first_block_receipt_index.encode(out);
+        self.block_hashes.encode(out);
+    }
+    #[inline]
+    fn length(&self) -> usize {
+        self.first_block_receipt_index.length() + self.block_hashes.length()
+    }
+}
+
+impl alloy_rlp::Decodable for GetReceipts70 {
+    #[inline]
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let first_block_receipt_index = alloy_rlp::Decodable::decode(buf)?;
+        let block_hashes = alloy_rlp::Decodable::decode(buf)?;
+        Ok(Self { first_block_receipt_index, block_hashes })
+    }
+}
+
+impl From<GetReceipts> for GetReceipts70 {
+    fn from(request: GetReceipts) -> Self {
+        Self { first_block_receipt_index: 0, block_hashes: request.0 }
+    }
+}
+
+/// Eth/70 receipt response type that can stop in the middle of a block.
+///
+/// Like [`Receipts69`] this doesn't include bloom filters. If `last_block_incomplete` is set, the
+/// receipts of the last block were cut off because of the response size limit, and the remaining
+/// receipts must be requested with [`GetReceipts70::first_block_receipt_index`] set to the number
+/// of receipts received for that block.
+///
+/// Note: the fields are encoded without a list header, so that they are flattened into the
+/// [`RequestPair`](crate::message::RequestPair) as `[request-id, lastBlockIncomplete,
+/// [[receipt₁, ...], ...]]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct Receipts70<T = Receipt> {
+    /// Whether the receipts of the last block are incomplete.
+    pub last_block_incomplete: bool,
+    /// Each receipt list corresponds to a block hash in the request.
+    pub receipts: Vec<Vec<T>>,
+}
+
+impl<T> Default for Receipts70<T> {
+    fn default() -> Self {
+        Self { last_block_incomplete: false, receipts: Vec::new() }
+    }
+}
+
+impl<T> Receipts70<T> {
+    /// Returns true if the response doesn't contain any receipt lists.
+    pub fn is_empty(&self) -> bool {
+        self.receipts.is_empty()
+    }
+
+    /// Converts this into the [`Receipts69`] response, dropping the completeness marker.
+    pub fn into_receipts69(self) -> Receipts69<T> {
+        Receipts69(self.receipts)
+    }
+}
+
+impl<T> From<Receipts69<T>> for Receipts70<T> {
+    fn from(receipts: Receipts69<T>) -> Self {
+        Self { last_block_incomplete: false, receipts: receipts.0 }
+    }
+}
+
+impl<T: RlpEncodableReceipt + alloy_rlp::Encodable> alloy_rlp::Encodable for Receipts70<T> {
+    #[inline]
+    fn encode(&self, out: &mut dyn alloy_rlp::BufMut) {
+        self.last_block_incomplete.encode(out);
+        self.receipts.encode(out);
+    }
+    #[inline]
+    fn length(&self) -> usize {
+        self.last_block_incomplete.length() + self.receipts.length()
+    }
+}
+
+impl<T: RlpDecodableReceipt + alloy_rlp::Decodable> alloy_rlp::Decodable for Receipts70<T> {
+    #[inline]
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let last_block_incomplete = alloy_rlp::Decodable::decode(buf)?;
+        let receipts = alloy_rlp::Decodable::decode(buf)?;
+        Ok(Self { last_block_incomplete, receipts })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{message::RequestPair, GetReceipts, Receipts};
+    use crate::{message::RequestPair, GetReceipts, GetReceipts70, Receipts, Receipts70};
     use alloy_consensus::TxType;
     use alloy_primitives::{hex, Log};
     use alloy_rlp::{Decodable, Encodable};
@@ -224,4 +337,51 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn roundtrip_get_receipts70_request_pair() {
+        let request = RequestPair {
+            request_id: 1111,
+            message: GetReceipts70 {
+                first_block_receipt_index: 3,
+                block_hashes: vec![hex!(
+                    "00000000000000000000000000000000000000000000000000000000deadc0de"
+                )
+                .into()],
+            },
+        };
+
+        let mut data = vec![];
+        request.encode(&mut data);
+        assert_eq!(data.len(), request.length());
+
+        // the fields are flattened into the request pair
+        assert_eq!(data[..5], hex!("e682045703"));
+        assert_eq!(RequestPair::<GetReceipts70>::decode(&mut &data[..]).unwrap(), request);
+    }
+
+    #[test]
+    fn roundtrip_receipts70_request_pair() {
+        let response = RequestPair {
+            request_id: 1111,
+            message: Receipts70 {
+                last_block_incomplete: true,
+                receipts: vec![vec![Receipt {
+                    tx_type: TxType::Eip1559,
+                    cumulative_gas_used: 21000,
+                    logs: vec![],
+                    success: true,
+                }]],
+            },
+        };
+
+        let mut data = vec![];
+        response.encode(&mut data);
+        assert_eq!(data.len(), response.length());
+        assert_eq!(data[4], 0x01);
+
+        let decoded = RequestPair::<Receipts70>::decode(&mut &data[..]).unwrap();
+        assert_eq!(decoded, response);
+        assert_eq!(decoded.message.into_receipts69().0.len(), 1);
+    }
 }
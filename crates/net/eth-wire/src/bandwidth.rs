@@ -0,0 +1,226 @@
+//! Bandwidth accounting of a [`P2PStream`](crate::P2PStream).
+//!
+//! Every frame that passes the stream is recorded with its (snappy compressed) size on the wire,
+//! broken down by the multiplexed message id and the direction of the traffic.
+
+use crate::{capability::SharedCapabilities, p2pstream::MAX_RESERVED_MESSAGE_ID};
+use reth_metrics::metrics::{counter, Counter, Label};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The label of the reserved `p2p` message id space.
+const P2P_PROTOCOL: &str = "p2p";
+
+/// The label of message ids that don't belong to any shared capability.
+const UNKNOWN_PROTOCOL: &str = "unknown";
+
+/// The direction of recorded traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficDirection {
+    /// Bytes received from the remote peer.
+    Ingress,
+    /// Bytes sent to the remote peer.
+    Egress,
+}
+
+impl TrafficDirection {
+    /// Returns the metrics label of the direction.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ingress => "ingress",
+            Self::Egress => "egress",
+        }
+    }
+}
+
+/// Snapshot of the traffic of a single message type in one direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Traffic {
+    /// Number of bytes on the wire.
+    pub bytes: u64,
+    /// Number of messages.
+    pub messages: u64,
+}
+
+/// Snapshot of the traffic of a single message type, see [`BandwidthMeter::traffic`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageTraffic<'a> {
+    /// The name of the protocol the message belongs to, `p2p` for reserved messages.
+    pub protocol: &'a str,
+    /// The id of the message relative to the message id offset of its protocol.
+    pub message_id: u8,
+    /// Traffic received from the peer.
+    pub ingress: Traffic,
+    /// Traffic sent to the peer.
+    pub egress: Traffic,
+}
+
+/// Atomic counters of a single message type in one direction.
+#[derive(Debug)]
+struct TrafficCounter {
+    bytes: AtomicU64,
+    messages: AtomicU64,
+    bytes_metric: Counter,
+    messages_metric: Counter,
+}
+
+impl TrafficCounter {
+    fn new(direction: TrafficDirection, protocol: &str, message_id: u8) -> Self {
+        let labels = vec![
+            Label::new("direction", direction.as_str()),
+            Label::new("protocol", protocol.to_string()),
+            Label::new("message_id", message_id.to_string()),
+        ];
+        Self {
+            bytes: AtomicU64::new(0),
+            messages: AtomicU64::new(0),
+            bytes_metric: counter!("p2pstream.bandwidth_bytes", labels.clone()),
+            messages_metric: counter!("p2pstream.bandwidth_messages", labels),
+        }
+    }
+
+    fn record(&self, bytes: usize) {
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.messages.fetch_add(1, Ordering::Relaxed);
+        self.bytes_metric.increment(bytes as u64);
+        self.messages_metric.increment(1);
+    }
+
+    fn snapshot(&self) -> Traffic {
+        Traffic {
+            bytes: self.bytes.load(Ordering::Relaxed),
+            messages: self.messages.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Counters of a single message type.
+#[derive(Debug)]
+struct MessageMeter {
+    protocol: String,
+    message_id: u8,
+    ingress: TrafficCounter,
+    egress: TrafficCounter,
+}
+
+impl MessageMeter {
+    fn new(protocol: &str, message_id: u8) -> Self {
+        Self {
+            protocol: protocol.to_string(),
+            message_id,
+            ingress: TrafficCounter::new(TrafficDirection::Ingress, protocol, message_id),
+            egress: TrafficCounter::new(TrafficDirection::Egress, protocol, message_id),
+        }
+    }
+
+    const fn counter(&self, direction: TrafficDirection) -> &TrafficCounter {
+        match direction {
+            TrafficDirection::Ingress => &self.ingress,
+            TrafficDirection::Egress => &self.egress,
+        }
+    }
+}
+
+/// Tracks the bytes a [`P2PStream`](crate::P2PStream) sends and receives per message type.
+///
+/// The meter is shared, so that the traffic of a session can be inspected while the stream is
+/// being driven by the session task. All recorded traffic is also exported as
+/// `p2pstream.bandwidth_bytes` and `p2pstream.bandwidth_messages` metrics, labeled by direction,
+/// protocol and relative message id.
+#[derive(Debug)]
+pub struct BandwidthMeter {
+    /// Meters indexed by the multiplexed message id, covering the reserved `p2p` id space and the
+    /// id space of all shared capabilities.
+    messages: Vec<MessageMeter>,
+    /// Meter of all message ids outside of the shared id space.
+    unknown: MessageMeter,
+}
+
+impl BandwidthMeter {
+    /// Creates a new meter for the message id space of the given shared capabilities.
+    pub fn new(shared_capabilities: &SharedCapabilities) -> Self {
+        let mut messages: Vec<_> =
+            (0..=MAX_RESERVED_MESSAGE_ID).map(|id| MessageMeter::new(P2P_PROTOCOL, id)).collect();
+        for cap in shared_capabilities.iter_caps() {
+            // capabilities are ordered by their offset, so the id space is filled without gaps
+            for id in 0..cap.num_messages() {
+                messages.push(MessageMeter::new(cap.name(), id));
+            }
+        }
+        Self { messages, unknown: MessageMeter::new(UNKNOWN_PROTOCOL, 0) }
+    }
+
+    /// Records a frame of `bytes` on the wire with the given multiplexed message id.
+    pub fn record(&self, direction: TrafficDirection, message_id: u8, bytes: usize) {
+        self.messages
+            .get(message_id as usize)
+            .unwrap_or(&self.unknown)
+            .counter(direction)
+            .record(bytes);
+    }
+
+    /// Returns the total traffic in the given direction.
+    pub fn total(&self, direction: TrafficDirection) -> Traffic {
+        self.messages.iter().chain(std::iter::once(&self.unknown)).fold(
+            Traffic::default(),
+            |acc, meter| {
+                let traffic = meter.counter(direction).snapshot();
+                Traffic {
+                    bytes: acc.bytes + traffic.bytes,
+                    messages: acc.messages + traffic.messages,
+                }
+            },
+        )
+    }
+
+    /// Returns the traffic of all message types that have been sent or received at least once.
+    pub fn traffic(&self) -> impl Iterator<Item = MessageTraffic<'_>> + '_ {
+        self.messages
+            .iter()
+            .chain(std::iter::once(&self.unknown))
+            .map(|meter| MessageTraffic {
+                protocol: &meter.protocol,
+                message_id: meter.message_id,
+                ingress: meter.ingress.snapshot(),
+                egress: meter.egress.snapshot(),
+            })
+            .filter(|traffic| traffic.ingress.messages > 0 || traffic.egress.messages > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{protocol::Protocol, Capability, EthVersion};
+
+    #[test]
+    fn records_by_protocol_and_message_id() {
+        let shared = SharedCapabilities::try_new(
+            vec![
+                Protocol::from(EthVersion::Eth68),
+                Protocol::new(Capability::new_static("aaa", 1), 2),
+            ],
+            vec![Capability::eth_68(), Capability::new_static("aaa", 1)],
+        )
+        .unwrap();
+        let meter = BandwidthMeter::new(&shared);
+
+        // `aaa` is ordered before `eth` and reserves the first two subprotocol ids
+        meter.record(TrafficDirection::Ingress, 0x10, 10);
+        meter.record(TrafficDirection::Egress, 0x12, 100);
+        meter.record(TrafficDirection::Egress, 0x12, 50);
+        meter.record(TrafficDirection::Ingress, 0x02, 3);
+        meter.record(TrafficDirection::Ingress, 0xff, 7);
+
+        let traffic: Vec<_> = meter.traffic().collect();
+        assert_eq!(traffic.len(), 4);
+        assert_eq!((traffic[0].protocol, traffic[0].message_id), ("p2p", 2));
+        assert_eq!((traffic[1].protocol, traffic[1].message_id), ("aaa", 0));
+        assert_eq!(traffic[1].ingress, Traffic { bytes: 10, messages: 1 });
+        assert_eq!((traffic[2].protocol, traffic[2].message_id), ("eth", 0));
+        assert_eq!(traffic[2].egress, Traffic { bytes: 150, messages: 2 });
+        assert_eq!(traffic[3].protocol, "unknown");
+
+        assert_eq!(meter.total(TrafficDirection::Ingress), Traffic { bytes: 20, messages: 3 });
+        assert_eq!(meter.total(TrafficDirection::Egress), Traffic { bytes: 150, messages: 2 });
+    }
+}
@@ -275,6 +275,20 @@ mod tests {
         assert!(has_eth69, "Default protocols should include Eth69");
     }
 
+    #[test]
+    fn test_default_protocols_exclude_eth70() {
+        // eth/70 is not final and must be enabled explicitly
+        let secret_key = SecretKey::new(&mut rand_08::thread_rng());
+        let id = pk2id(&secret_key.public_key(SECP256K1));
+        let hello = HelloMessageWithProtocols::builder(id).build();
+
+        let has_eth70 = hello
+            .protocols
+            .iter()
+            .any(|p| p.cap.name == "eth" && p.cap.version == EthVersion::Eth70 as usize);
+        assert!(!has_eth70, "Default protocols should not include Eth70");
+    }
+
     #[test]
     fn hello_message_id_prefix() {
         // ensure that the hello message id is prefixed
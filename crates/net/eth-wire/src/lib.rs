@@ -13,6 +13,7 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod bandwidth;
 pub mod capability;
 mod disconnect;
 pub mod errors;
@@ -36,6 +37,7 @@ pub use tokio_util::codec::{
 };
 
 pub use crate::{
+    bandwidth::BandwidthMeter,
     disconnect::CanDisconnect,
     ethstream::{EthStream, EthStreamInner, UnauthedEthStream, MAX_MESSAGE_SIZE},
     hello::{HelloMessage, HelloMessageBuilder, HelloMessageWithProtocols},
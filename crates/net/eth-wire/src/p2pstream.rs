@@ -1,4 +1,5 @@
 use crate::{
+    bandwidth::{BandwidthMeter, TrafficDirection},
     capability::SharedCapabilities,
     disconnect::CanDisconnect,
     errors::{P2PHandshakeError, P2PStreamError},
@@ -20,6 +21,7 @@ use std::{
     future::Future,
     io,
     pin::Pin,
+    sync::Arc,
     task::{ready, Context, Poll},
     time::Duration,
 };
@@ -245,6 +247,9 @@ pub struct P2PStream<S> {
     /// The supported capability for this stream.
     shared_capabilities: SharedCapabilities,
 
+    /// Records the bytes sent and received per message id.
+    bandwidth: Arc<BandwidthMeter>,
+
     /// Outgoing messages buffered for sending to the underlying stream.
     outgoing_messages: VecDeque<Bytes>,
 
@@ -267,6 +272,7 @@ impl<S> P2PStream<S> {
             encoder: snap::raw::Encoder::new(),
             decoder: snap::raw::Decoder::new(),
             pinger: Pinger::new(PING_INTERVAL, PING_TIMEOUT),
+            bandwidth: Arc::new(BandwidthMeter::new(&shared_capabilities)),
             shared_capabilities,
             outgoing_messages: VecDeque::new(),
             outgoing_message_buffer_capacity: MAX_P2P_CAPACITY,
@@ -296,6 +302,11 @@ impl<S> P2PStream<S> {
         &self.shared_capabilities
     }
 
+    /// Returns the [`BandwidthMeter`] that records the traffic of this stream.
+    pub const fn bandwidth(&self) -> &Arc<BandwidthMeter> {
+        &self.bandwidth
+    }
+
     /// Returns `true` if the stream has outgoing capacity.
     fn has_outgoing_capacity(&self) -> bool {
         self.outgoing_messages.len() < self.outgoing_message_buffer_capacity
@@ -411,6 +422,8 @@ where
                 return Poll::Ready(Some(Err(P2PStreamError::EmptyProtocolMessage)))
             }
 
+            this.bandwidth.record(TrafficDirection::Ingress, bytes[0], bytes.len());
+
             // first decode disconnect reasons, because they can be encoded in a variety of forms
             // over the wire, in both snappy compressed and uncompressed forms.
             //
@@ -626,6 +639,7 @@ where
                     let Some(message) = this.outgoing_messages.pop_front() else {
                         break Poll::Ready(Ok(()))
                     };
+                    this.bandwidth.record(TrafficDirection::Egress, message[0], message.len());
                     if let Err(err) = this.inner.as_mut().start_send(message) {
                         break Poll::Ready(Err(err.into()))
                     }
@@ -1,5 +1,8 @@
 //! Helpers for resolving the external IP.
 //!
+//! [`NatResolver::Upnp`] and [`NatResolver::NatPmp`] additionally map ports on the gateway, see
+//! [`PortMappingService`].
+//!
 //! ## Feature Flags
 //!
 //! - `serde` (default): Enable serde support
@@ -12,8 +15,15 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod mapping;
+pub mod natpmp;
 pub mod net_if;
+pub mod upnp;
 
+pub use mapping::{
+    MappedAddr, PortMapping, PortMappingConfig, PortMappingError, PortMappingHandle,
+    PortMappingProtocol, PortMappingService,
+};
 pub use net_if::{NetInterfaceError, DEFAULT_NET_IF_NAME};
 
 use std::{
@@ -27,7 +37,7 @@ use std::{
 };
 use tracing::debug;
 
-use crate::net_if::resolve_net_if_ip;
+use crate::{mapping::Gateway, net_if::resolve_net_if_ip};
 #[cfg(feature = "serde")]
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 
@@ -44,8 +54,10 @@ pub enum NatResolver {
     /// Resolve with any available resolver.
     #[default]
     Any,
-    /// Resolve external IP via `UPnP`.
+    /// Resolve external IP and map ports via `UPnP`.
     Upnp,
+    /// Resolve external IP and map ports via NAT-PMP or PCP.
+    NatPmp,
     /// Resolve external IP via a network request.
     PublicIp,
     /// Use the given [`IpAddr`]
@@ -69,6 +81,11 @@ impl NatResolver {
             _ => None,
         }
     }
+
+    /// Returns true if the resolver maps ports on the gateway.
+    pub const fn maps_ports(self) -> bool {
+        matches!(self, Self::Upnp | Self::NatPmp)
+    }
 }
 
 impl fmt::Display for NatResolver {
@@ -76,6 +93,7 @@ impl fmt::Display for NatResolver {
         match self {
             Self::Any => f.write_str("any"),
             Self::Upnp => f.write_str("upnp"),
+            Self::NatPmp => f.write_str("natpmp"),
             Self::PublicIp => f.write_str("publicip"),
             Self::ExternalIp(ip) => write!(f, "extip:{ip}"),
             Self::NetIf => f.write_str("netif"),
@@ -102,6 +120,7 @@ impl FromStr for NatResolver {
         let r = match s {
             "any" => Self::Any,
             "upnp" => Self::Upnp,
+            "natpmp" | "pcp" => Self::NatPmp,
             "none" => Self::None,
             "publicip" | "public-ip" => Self::PublicIp,
             "netif" => Self::NetIf,
@@ -202,7 +221,11 @@ pub async fn external_ip() -> Option<IpAddr> {
 /// Given a [`NatResolver`] attempts to produce an IP address (best effort).
 pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
     match resolver {
-        NatResolver::Any | NatResolver::Upnp | NatResolver::PublicIp => resolve_external_ip().await,
+        NatResolver::Any | NatResolver::PublicIp => resolve_external_ip().await,
+        NatResolver::Upnp | NatResolver::NatPmp => match resolve_gateway_ip(resolver).await {
+            Some(ip) => Some(ip),
+            None => resolve_external_ip().await,
+        },
         NatResolver::ExternalIp(ip) => Some(ip),
         NatResolver::NetIf => resolve_net_if_ip(DEFAULT_NET_IF_NAME)
             .inspect_err(|err| {
@@ -216,6 +239,22 @@ pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
     }
 }
 
+async fn resolve_gateway_ip(resolver: NatResolver) -> Option<IpAddr> {
+    let gateway = Gateway::discover(resolver, None).await;
+    let ip = match gateway {
+        Ok(gateway) => gateway.external_ip().await,
+        Err(err) => Err(err),
+    };
+    ip.inspect_err(|err| {
+        debug!(target: "net::nat",
+            %err,
+            %resolver,
+            "Failed to resolve external IP from gateway"
+        );
+    })
+    .ok()
+}
+
 async fn resolve_external_ip() -> Option<IpAddr> {
     let futures = EXTERNAL_IP_APIS.iter().copied().map(resolve_external_ip_url_res).map(Box::pin);
     futures_util::future::select_ok(futures)
@@ -271,6 +310,9 @@ mod tests {
     fn test_from_str() {
         assert_eq!(NatResolver::Any, "any".parse().unwrap());
         assert_eq!(NatResolver::None, "none".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp, "natpmp".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp, "pcp".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp.to_string(), "natpmp");
 
         let ip = NatResolver::ExternalIp(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
         let s = "extip:0.0.0.0";
@@ -0,0 +1,524 @@
+//! Port mappings on the NAT gateway.
+//!
+//! The [`PortMappingService`] maps the configured ports via `UPnP` or NAT-PMP/PCP, renews the
+//! mappings before their lease expires and removes them on shutdown. Every time the external
+//! address changes it is reported to the [`PortMappingHandle`], so it can be announced to peers.
+
+use crate::{natpmp::PmpGateway, upnp::IgdGateway, NatResolver};
+use std::{
+    fmt, io,
+    net::{IpAddr, SocketAddr},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    net::UdpSocket,
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+use tracing::{debug, info, warn};
+
+/// Default lease of a port mapping, mappings are renewed after half of it.
+pub const DEFAULT_PORT_MAPPING_LEASE: Duration = Duration::from_secs(20 * 60);
+
+/// How long to wait for a gateway to respond to discovery.
+const GATEWAY_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait before retrying after the ports couldn't be mapped.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long to wait for an in-flight refresh to finish on shutdown.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Errors that can occur while mapping ports on the gateway.
+#[derive(Debug, thiserror::Error)]
+pub enum PortMappingError {
+    /// Failed to communicate with the gateway.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// HTTP request to a `UPnP` gateway failed.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// No gateway was found.
+    #[error("no gateway found")]
+    NoGateway,
+    /// The gateway did not respond in time.
+    #[error("gateway did not respond")]
+    Timeout,
+    /// The gateway sent a response that couldn't be processed.
+    #[error("invalid gateway response: {0}")]
+    InvalidResponse(&'static str),
+    /// The `UPnP` gateway rejected the request.
+    #[error("UPnP error {code}: {description}")]
+    Upnp {
+        /// The `UPnP` error code.
+        code: u16,
+        /// The error description sent by the gateway.
+        description: String,
+    },
+    /// The NAT-PMP or PCP gateway rejected the request with the given result code.
+    #[error("NAT-PMP/PCP result code {0}")]
+    Pmp(u16),
+    /// The resolver doesn't map ports.
+    #[error("{0} does not support port mappings")]
+    Unsupported(NatResolver),
+}
+
+/// Transport protocol of a port mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PortMappingProtocol {
+    /// TCP, used by `RLPx`.
+    Tcp,
+    /// UDP, used by discovery.
+    Udp,
+}
+
+impl PortMappingProtocol {
+    /// Returns the IANA protocol number.
+    pub const fn iana_number(self) -> u8 {
+        match self {
+            Self::Tcp => 6,
+            Self::Udp => 17,
+        }
+    }
+}
+
+impl fmt::Display for PortMappingProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp => f.write_str("TCP"),
+            Self::Udp => f.write_str("UDP"),
+        }
+    }
+}
+
+/// A port mapped on the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortMapping {
+    /// Transport protocol of the mapping.
+    pub protocol: PortMappingProtocol,
+    /// The local port.
+    pub internal_port: u16,
+    /// The port on the gateway's external address.
+    pub external_port: u16,
+}
+
+/// The external address of the node after its ports were mapped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedAddr {
+    /// The external IP of the gateway.
+    pub ip: IpAddr,
+    /// All active mappings.
+    pub mappings: Vec<PortMapping>,
+}
+
+impl MappedAddr {
+    /// Returns the external port the given local port is mapped to.
+    pub fn external_port(&self, protocol: PortMappingProtocol, internal_port: u16) -> Option<u16> {
+        self.mappings
+            .iter()
+            .find(|mapping| mapping.protocol == protocol && mapping.internal_port == internal_port)
+            .map(|mapping| mapping.external_port)
+    }
+}
+
+/// Configuration of the [`PortMappingService`].
+#[derive(Debug, Clone)]
+pub struct PortMappingConfig {
+    /// The resolver used to map ports, either [`NatResolver::Upnp`] or [`NatResolver::NatPmp`].
+    resolver: NatResolver,
+    /// The local ports to map.
+    ports: Vec<(PortMappingProtocol, u16)>,
+    /// Lease requested for each mapping.
+    lease: Duration,
+    /// Description of the mappings, shown in the gateway's admin interface.
+    description: String,
+    /// Address of the gateway, discovered if not set.
+    ///
+    /// This is the SSDP address for `UPnP` and the NAT-PMP/PCP server address otherwise.
+    gateway: Option<SocketAddr>,
+}
+
+impl PortMappingConfig {
+    /// Creates a new config if the resolver maps ports on the gateway.
+    pub fn new(resolver: NatResolver) -> Option<Self> {
+        resolver.maps_ports().then(|| Self {
+            resolver,
+            ports: Vec::new(),
+            lease: DEFAULT_PORT_MAPPING_LEASE,
+            description: "reth".to_string(),
+            gateway: None,
+        })
+    }
+
+    /// Adds a local port to map.
+    pub fn with_port(mut self, protocol: PortMappingProtocol, port: u16) -> Self {
+        if port != 0 && !self.ports.contains(&(protocol, port)) {
+            self.ports.push((protocol, port));
+        }
+        self
+    }
+
+    /// Sets the lease requested for each mapping.
+    pub const fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Sets the description of the mappings.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Sets the gateway address instead of discovering it.
+    pub const fn with_gateway(mut self, gateway: SocketAddr) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    /// Returns the local ports to map.
+    pub fn ports(&self) -> &[(PortMappingProtocol, u16)] {
+        &self.ports
+    }
+}
+
+/// A gateway that supports port mappings.
+#[derive(Debug)]
+pub(crate) enum Gateway {
+    /// A `UPnP` Internet Gateway Device.
+    Igd(IgdGateway),
+    /// A NAT-PMP or PCP gateway.
+    Pmp(PmpGateway),
+}
+
+impl Gateway {
+    /// Discovers the gateway of the given resolver, or connects to the given address.
+    pub(crate) async fn discover(
+        resolver: NatResolver,
+        addr: Option<SocketAddr>,
+    ) -> Result<Self, PortMappingError> {
+        match (resolver, addr) {
+            (NatResolver::Upnp, Some(addr)) => {
+                Ok(Self::Igd(IgdGateway::search_at(addr, GATEWAY_DISCOVERY_TIMEOUT).await?))
+            }
+            (NatResolver::Upnp, None) => {
+                Ok(Self::Igd(IgdGateway::search(GATEWAY_DISCOVERY_TIMEOUT).await?))
+            }
+            (NatResolver::NatPmp, Some(addr)) => Ok(Self::Pmp(PmpGateway::new(addr).await?)),
+            (NatResolver::NatPmp, None) => Ok(Self::Pmp(PmpGateway::discover().await?)),
+            (resolver, _) => Err(PortMappingError::Unsupported(resolver)),
+        }
+    }
+
+    /// Returns the external IP address of the gateway.
+    pub(crate) async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        match self {
+            Self::Igd(gateway) => gateway.external_ip().await,
+            Self::Pmp(gateway) => gateway.external_ip().await,
+        }
+    }
+
+    /// Maps the local port to the same external port and returns the external port assigned by
+    /// the gateway.
+    async fn map_port(
+        &mut self,
+        protocol: PortMappingProtocol,
+        port: u16,
+        lease: Duration,
+        description: &str,
+    ) -> Result<u16, PortMappingError> {
+        match self {
+            Self::Igd(gateway) => {
+                gateway.add_port_mapping(protocol, port, port, lease, description).await?;
+                Ok(port)
+            }
+            Self::Pmp(gateway) => gateway.map_port(protocol, port, port, lease).await,
+        }
+    }
+
+    /// Removes the mapping.
+    async fn unmap_port(&mut self, mapping: PortMapping) -> Result<(), PortMappingError> {
+        match self {
+            Self::Igd(gateway) => {
+                gateway.remove_port_mapping(mapping.protocol, mapping.external_port).await
+            }
+            Self::Pmp(gateway) => {
+                gateway
+                    .map_port(mapping.protocol, mapping.internal_port, 0, Duration::ZERO)
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Service that keeps the configured ports mapped on the gateway.
+///
+/// Mappings are renewed after half of their lease. If the gateway stops responding it is
+/// discovered again. Once the [`PortMappingHandle`] requests a shutdown or is dropped, all
+/// mappings are removed and the service terminates.
+#[must_use = "Service does nothing unless polled"]
+pub struct PortMappingService {
+    config: PortMappingConfig,
+    /// The gateway the ports are mapped on.
+    gateway: Option<Gateway>,
+    /// The last reported external address.
+    mapped: Option<MappedAddr>,
+    /// All mappings created on the gateway, including those of an incomplete refresh.
+    active: Vec<PortMapping>,
+    /// Reports external address changes to the handle.
+    updates: mpsc::UnboundedSender<MappedAddr>,
+    /// Resolves once the handle requests a shutdown or is dropped.
+    shutdown: oneshot::Receiver<()>,
+}
+
+impl fmt::Debug for PortMappingService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PortMappingService")
+            .field("config", &self.config)
+            .field("gateway", &self.gateway)
+            .field("mapped", &self.mapped)
+            .field("active", &self.active)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PortMappingService {
+    /// Creates a new service and the handle to it.
+    pub fn new_pair(config: PortMappingConfig) -> (Self, PortMappingHandle) {
+        let (updates_tx, updates_rx) = mpsc::unbounded_channel();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let service = Self {
+            config,
+            gateway: None,
+            mapped: None,
+            active: Vec::new(),
+            updates: updates_tx,
+            shutdown: shutdown_rx,
+        };
+        (service, PortMappingHandle { updates: updates_rx, shutdown: Some(shutdown_tx) })
+    }
+
+    /// Spawns the service onto a new task.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::task::spawn(self.run())
+    }
+
+    /// Keeps the ports mapped until shutdown, then removes the mappings.
+    ///
+    /// A refresh that's in flight when the shutdown is requested is finished first, so no
+    /// mapping is left behind on the gateway.
+    pub async fn run(mut self) {
+        let mut shutdown = std::mem::replace(&mut self.shutdown, oneshot::channel().1);
+        loop {
+            let res = {
+                let mut refresh = std::pin::pin!(self.refresh());
+                let res = tokio::select! {
+                    res = &mut refresh => Some(res),
+                    _ = &mut shutdown => None,
+                };
+                if res.is_none() {
+                    let _ = tokio::time::timeout(SHUTDOWN_TIMEOUT, refresh).await;
+                }
+                res
+            };
+            let Some(res) = res else { break };
+
+            let next_refresh = match res {
+                Ok(()) => self.config.lease / 2,
+                Err(err) => {
+                    debug!(target: "net::nat", %err, resolver=%self.config.resolver, "Failed to map ports on gateway");
+                    self.gateway = None;
+                    RETRY_INTERVAL
+                }
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep(next_refresh) => {}
+                _ = &mut shutdown => break,
+            }
+        }
+
+        self.remove_mappings().await;
+    }
+
+    /// Maps all ports, discovering the gateway first if necessary, and reports the external
+    /// address if it changed.
+    async fn refresh(&mut self) -> Result<(), PortMappingError> {
+        if self.gateway.is_none() {
+            let gateway = Gateway::discover(self.config.resolver, self.config.gateway).await?;
+            debug!(target: "net::nat", ?gateway, "Discovered gateway");
+            self.gateway = Some(gateway);
+        }
+        let Some(gateway) = self.gateway.as_mut() else { return Err(PortMappingError::NoGateway) };
+
+        let mut mappings = Vec::with_capacity(self.config.ports.len());
+        for &(protocol, port) in &self.config.ports {
+            let external_port = gateway
+                .map_port(protocol, port, self.config.lease, &self.config.description)
+                .await?;
+            let mapping = PortMapping { protocol, internal_port: port, external_port };
+            self.active.retain(|active| {
+                active.protocol != protocol || active.internal_port != mapping.internal_port
+            });
+            self.active.push(mapping);
+            mappings.push(mapping);
+        }
+        let addr = MappedAddr { ip: gateway.external_ip().await?, mappings };
+
+        if self.mapped.as_ref() != Some(&addr) {
+            // private or carrier-grade NAT (100.64.0.0/10) addresses
+            if let IpAddr::V4(ip) = addr.ip &&
+                (ip.is_private() || (ip.octets()[0] == 100 && ip.octets()[1] & 0xc0 == 64))
+            {
+                warn!(target: "net::nat", %ip, "Gateway reports a non-public external address, the node is likely behind another NAT");
+            }
+            info!(target: "net::nat", ip=%addr.ip, mappings=?addr.mappings, "Mapped ports on gateway");
+            let _ = self.updates.send(addr.clone());
+            self.mapped = Some(addr);
+        }
+
+        Ok(())
+    }
+
+    /// Removes all active mappings from the gateway.
+    async fn remove_mappings(&mut self) {
+        self.mapped = None;
+        let Some(gateway) = self.gateway.as_mut() else { return };
+        for mapping in std::mem::take(&mut self.active) {
+            match gateway.unmap_port(mapping).await {
+                Ok(()) => debug!(target: "net::nat", ?mapping, "Removed port mapping"),
+                Err(err) => {
+                    debug!(target: "net::nat", ?mapping, %err, "Failed to remove port mapping")
+                }
+            }
+        }
+    }
+}
+
+/// Handle to a [`PortMappingService`].
+///
+/// Dropping the handle shuts the service down.
+#[derive(Debug)]
+pub struct PortMappingHandle {
+    /// External address updates from the service.
+    updates: mpsc::UnboundedReceiver<MappedAddr>,
+    /// Signals the service to remove the mappings and terminate.
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl PortMappingHandle {
+    /// Polls for the next external address reported by the service.
+    pub fn poll_mapped_addr(&mut self, cx: &mut Context<'_>) -> Poll<Option<MappedAddr>> {
+        self.updates.poll_recv(cx)
+    }
+
+    /// Returns the next external address reported by the service.
+    pub async fn next_mapped_addr(&mut self) -> Option<MappedAddr> {
+        self.updates.recv().await
+    }
+
+    /// Requests the service to remove all mappings and terminate.
+    pub fn shutdown(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+/// Returns the IP of the local interface used to reach `ip`.
+pub(crate) async fn local_ip_towards(ip: IpAddr) -> io::Result<IpAddr> {
+    let unspecified: IpAddr = if ip.is_ipv4() {
+        std::net::Ipv4Addr::UNSPECIFIED.into()
+    } else {
+        std::net::Ipv6Addr::UNSPECIFIED.into()
+    };
+    let socket = UdpSocket::bind((unspecified, 0)).await?;
+    // connecting a UDP socket only selects the route, nothing is sent
+    socket.connect((ip, 9)).await?;
+    Ok(socket.local_addr()?.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{natpmp::tests::FakePmpGateway, upnp::tests::FakeIgd};
+    use std::net::Ipv4Addr;
+
+    #[tokio::test]
+    async fn natpmp_service_maps_and_removes_ports() {
+        let fake = FakePmpGateway::spawn(Ipv4Addr::new(203, 0, 113, 7), false).await;
+        let config = PortMappingConfig::new(NatResolver::NatPmp)
+            .unwrap()
+            .with_port(PortMappingProtocol::Tcp, 30303)
+            .with_port(PortMappingProtocol::Udp, 30303)
+            .with_gateway(fake.addr);
+
+        let (service, mut handle) = PortMappingService::new_pair(config);
+        let service = service.spawn();
+
+        let addr = handle.next_mapped_addr().await.unwrap();
+        assert_eq!(addr.ip, Ipv4Addr::new(203, 0, 113, 7));
+        assert_eq!(addr.external_port(PortMappingProtocol::Tcp, 30303), Some(30303));
+        assert_eq!(addr.external_port(PortMappingProtocol::Udp, 30303), Some(30303));
+
+        handle.shutdown();
+        service.await.unwrap();
+        assert_eq!(*fake.lifetimes.lock().unwrap(), vec![1200, 1200, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn natpmp_service_finishes_refresh_on_shutdown() {
+        let fake = FakePmpGateway::spawn(Ipv4Addr::new(203, 0, 113, 7), false).await;
+        let config = PortMappingConfig::new(NatResolver::NatPmp)
+            .unwrap()
+            .with_port(PortMappingProtocol::Tcp, 30303)
+            .with_port(PortMappingProtocol::Udp, 30303)
+            .with_gateway(fake.addr);
+
+        // shutdown is requested while the first refresh is in flight
+        let (service, mut handle) = PortMappingService::new_pair(config);
+        handle.shutdown();
+        service.run().await;
+
+        // the refresh completed and all of its mappings were removed
+        assert_eq!(*fake.lifetimes.lock().unwrap(), vec![1200, 1200, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn upnp_service_maps_and_removes_ports() {
+        let igd = FakeIgd::spawn(Ipv4Addr::new(203, 0, 113, 7)).await;
+        let config = PortMappingConfig::new(NatResolver::Upnp)
+            .unwrap()
+            .with_port(PortMappingProtocol::Tcp, 30303)
+            .with_gateway(igd.ssdp_addr);
+
+        let (service, mut handle) = PortMappingService::new_pair(config);
+        let service = service.spawn();
+
+        let addr = handle.next_mapped_addr().await.unwrap();
+        assert_eq!(addr.ip, Ipv4Addr::new(203, 0, 113, 7));
+
+        // dropping the handle removes the mappings as well
+        drop(handle);
+        service.await.unwrap();
+        assert_eq!(
+            igd.actions.lock().unwrap().last().map(String::as_str),
+            Some("DeletePortMapping")
+        );
+    }
+
+    #[test]
+    fn config_only_for_mapping_resolvers() {
+        assert!(PortMappingConfig::new(NatResolver::Any).is_none());
+        assert!(PortMappingConfig::new(NatResolver::PublicIp).is_none());
+
+        let config = PortMappingConfig::new(NatResolver::Upnp)
+            .unwrap()
+            .with_port(PortMappingProtocol::Udp, 30303)
+            .with_port(PortMappingProtocol::Udp, 30303)
+            .with_port(PortMappingProtocol::Tcp, 0);
+        assert_eq!(config.ports(), &[(PortMappingProtocol::Udp, 30303)]);
+    }
+}
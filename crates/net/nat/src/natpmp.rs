@@ -0,0 +1,362 @@
+//! Minimal NAT-PMP ([RFC 6886](https://datatracker.ietf.org/doc/html/rfc6886)) and PCP
+//! ([RFC 6887](https://datatracker.ietf.org/doc/html/rfc6887)) client.
+//!
+//! PCP is tried first, gateways that only speak NAT-PMP reject it with an unsupported version
+//! response, after which NAT-PMP is used.
+
+use crate::mapping::{local_ip_towards, PortMappingError, PortMappingProtocol};
+use std::{
+    hash::{BuildHasher, RandomState},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+
+/// The port NAT-PMP and PCP servers listen on.
+pub const PMP_SERVER_PORT: u16 = 5351;
+
+/// NAT-PMP protocol version.
+const NATPMP_VERSION: u8 = 0;
+/// PCP protocol version.
+const PCP_VERSION: u8 = 2;
+/// NAT-PMP opcode to request the external address.
+const NATPMP_OPCODE_EXTERNAL_ADDRESS: u8 = 0;
+/// PCP opcode to request a mapping.
+const PCP_OPCODE_MAP: u8 = 1;
+/// Bit set on the opcode of responses.
+const RESPONSE_BIT: u8 = 0x80;
+/// Result code for an unsupported protocol version, the same in NAT-PMP and PCP.
+const UNSUPPORTED_VERSION: u16 = 1;
+
+/// Initial retransmission timeout, doubled on every attempt.
+const INITIAL_RETRANSMIT_TIMEOUT: Duration = Duration::from_millis(250);
+/// Number of attempts before a request is considered failed.
+const MAX_ATTEMPTS: u32 = 4;
+
+/// A gateway that supports NAT-PMP or PCP.
+#[derive(Debug, Clone)]
+pub struct PmpGateway {
+    /// Address of the NAT-PMP/PCP server.
+    addr: SocketAddr,
+    /// The IPv4 address of the local interface that faces the gateway.
+    local_ip: Ipv4Addr,
+    /// Nonce that identifies the PCP mappings of this client.
+    nonce: [u8; 12],
+    /// Whether the gateway speaks PCP, unknown until the first mapping.
+    pcp: Option<bool>,
+    /// The external address of the last PCP mapping.
+    pcp_external_ip: Option<IpAddr>,
+}
+
+impl PmpGateway {
+    /// Creates a client for the NAT-PMP/PCP server of the default gateway.
+    pub async fn discover() -> Result<Self, PortMappingError> {
+        let gateway = default_gateway().await.ok_or(PortMappingError::NoGateway)?;
+        Self::new(SocketAddr::new(gateway.into(), PMP_SERVER_PORT)).await
+    }
+
+    /// Creates a client for the NAT-PMP/PCP server at the given address.
+    pub async fn new(addr: SocketAddr) -> Result<Self, PortMappingError> {
+        let IpAddr::V4(local_ip) = local_ip_towards(addr.ip()).await? else {
+            return Err(PortMappingError::NoGateway)
+        };
+        let random = RandomState::new();
+        let mut nonce = [0u8; 12];
+        nonce[..8].copy_from_slice(&random.hash_one(addr).to_be_bytes());
+        nonce[8..].copy_from_slice(&random.hash_one(local_ip).to_be_bytes()[..4]);
+
+        Ok(Self { addr, local_ip, nonce, pcp: None, pcp_external_ip: None })
+    }
+
+    /// Returns the IP of the local interface that faces the gateway.
+    pub const fn local_ip(&self) -> Ipv4Addr {
+        self.local_ip
+    }
+
+    /// Returns the external IP address of the gateway.
+    pub async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        if self.pcp == Some(true) &&
+            let Some(ip) = self.pcp_external_ip
+        {
+            return Ok(ip)
+        }
+
+        let response = self.request(&[NATPMP_VERSION, NATPMP_OPCODE_EXTERNAL_ADDRESS]).await?;
+        let response = natpmp_response(&response, NATPMP_OPCODE_EXTERNAL_ADDRESS, 12)?;
+        Ok(IpAddr::V4(Ipv4Addr::new(response[8], response[9], response[10], response[11])))
+    }
+
+    /// Requests a mapping of `internal_port` for the given lifetime and returns the external port
+    /// assigned by the gateway, which may differ from the suggested `external_port`.
+    ///
+    /// A lifetime of zero removes the mapping.
+    pub async fn map_port(
+        &mut self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: Duration,
+    ) -> Result<u16, PortMappingError> {
+        let lifetime = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+
+        if self.pcp != Some(false) {
+            match self.pcp_map(protocol, internal_port, external_port, lifetime).await {
+                Ok(port) => {
+                    self.pcp = Some(true);
+                    return Ok(port)
+                }
+                Err(PortMappingError::Pmp(UNSUPPORTED_VERSION)) if self.pcp.is_none() => {
+                    self.pcp = Some(false);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        self.natpmp_map(protocol, internal_port, external_port, lifetime).await
+    }
+
+    /// Requests a mapping via NAT-PMP.
+    async fn natpmp_map(
+        &self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: u32,
+    ) -> Result<u16, PortMappingError> {
+        let opcode = match protocol {
+            PortMappingProtocol::Udp => 1,
+            PortMappingProtocol::Tcp => 2,
+        };
+        let mut request = Vec::with_capacity(12);
+        request.extend([NATPMP_VERSION, opcode, 0, 0]);
+        request.extend(internal_port.to_be_bytes());
+        request.extend(external_port.to_be_bytes());
+        request.extend(lifetime.to_be_bytes());
+
+        let response = self.request(&request).await?;
+        let response = natpmp_response(&response, opcode, 16)?;
+        Ok(u16::from_be_bytes([response[10], response[11]]))
+    }
+
+    /// Requests a mapping via the PCP `MAP` opcode.
+    async fn pcp_map(
+        &mut self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: u32,
+    ) -> Result<u16, PortMappingError> {
+        let mut request = Vec::with_capacity(60);
+        request.extend([PCP_VERSION, PCP_OPCODE_MAP, 0, 0]);
+        request.extend(lifetime.to_be_bytes());
+        request.extend(self.local_ip.to_ipv6_mapped().octets());
+        request.extend(self.nonce);
+        request.extend([protocol.iana_number(), 0, 0, 0]);
+        request.extend(internal_port.to_be_bytes());
+        request.extend(external_port.to_be_bytes());
+        request.extend(Ipv4Addr::UNSPECIFIED.to_ipv6_mapped().octets());
+
+        let response = self.request(&request).await?;
+        if response.len() < 4 {
+            return Err(PortMappingError::InvalidResponse("truncated PCP response"))
+        }
+        if response[0] != PCP_VERSION {
+            // NAT-PMP only gateways respond with their own version
+            return Err(PortMappingError::Pmp(UNSUPPORTED_VERSION))
+        }
+        if response[3] != 0 {
+            return Err(PortMappingError::Pmp(response[3].into()))
+        }
+        if response.len() < 60 || response[1] != PCP_OPCODE_MAP | RESPONSE_BIT {
+            return Err(PortMappingError::InvalidResponse("invalid PCP response"))
+        }
+        if response[24..36] != self.nonce {
+            return Err(PortMappingError::InvalidResponse("PCP nonce mismatch"))
+        }
+
+        let mut external_ip = [0u8; 16];
+        external_ip.copy_from_slice(&response[44..60]);
+        self.pcp_external_ip = Some(std::net::Ipv6Addr::from(external_ip).to_canonical());
+
+        Ok(u16::from_be_bytes([response[42], response[43]]))
+    }
+
+    /// Sends the request to the gateway, retransmitting with exponential backoff until a response
+    /// arrives.
+    async fn request(&self, request: &[u8]) -> Result<Vec<u8>, PortMappingError> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        socket.connect(self.addr).await?;
+
+        let mut buf = [0u8; 1100];
+        let mut timeout = INITIAL_RETRANSMIT_TIMEOUT;
+        for _ in 0..MAX_ATTEMPTS {
+            socket.send(request).await?;
+            if let Ok(len) = tokio::time::timeout(timeout, socket.recv(&mut buf)).await {
+                return Ok(buf[..len?].to_vec())
+            }
+            timeout *= 2;
+        }
+
+        Err(PortMappingError::Timeout)
+    }
+}
+
+/// Validates a NAT-PMP response to a request with the given opcode.
+fn natpmp_response(response: &[u8], opcode: u8, len: usize) -> Result<&[u8], PortMappingError> {
+    if response.len() < 4 || response[0] != NATPMP_VERSION || response[1] != opcode | RESPONSE_BIT {
+        return Err(PortMappingError::InvalidResponse("invalid NAT-PMP response"))
+    }
+    let result = u16::from_be_bytes([response[2], response[3]]);
+    if result != 0 {
+        return Err(PortMappingError::Pmp(result))
+    }
+    if response.len() < len {
+        return Err(PortMappingError::InvalidResponse("truncated NAT-PMP response"))
+    }
+    Ok(response)
+}
+
+/// Returns the IPv4 default gateway.
+///
+/// On Linux this is read from the routing table, otherwise the first address of the local
+/// interface's /24 network is assumed, which is what most consumer routers use.
+async fn default_gateway() -> Option<Ipv4Addr> {
+    #[cfg(target_os = "linux")]
+    if let Some(gateway) = std::fs::read_to_string("/proc/net/route")
+        .ok()
+        .and_then(|routes| parse_default_route(&routes))
+    {
+        return Some(gateway)
+    }
+
+    // connecting a UDP socket doesn't send anything, but selects the outbound interface
+    let IpAddr::V4(local_ip) = local_ip_towards(Ipv4Addr::new(1, 1, 1, 1).into()).await.ok()?
+    else {
+        return None
+    };
+    let [a, b, c, _] = local_ip.octets();
+    local_ip.is_private().then_some(Ipv4Addr::new(a, b, c, 1))
+}
+
+/// Returns the gateway of the default route in the `/proc/net/route` table.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_default_route(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let destination = fields.nth(1)?;
+        let gateway = fields.next()?;
+        if destination != "00000000" {
+            return None
+        }
+        // the address is printed as a native endian integer of the network order bytes
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        (gateway != 0).then(|| Ipv4Addr::from(gateway.to_ne_bytes()))
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A fake NAT-PMP gateway, optionally speaking PCP, that records all mapping lifetimes.
+    pub(crate) struct FakePmpGateway {
+        pub(crate) addr: SocketAddr,
+        pub(crate) lifetimes: Arc<Mutex<Vec<u32>>>,
+    }
+
+    impl FakePmpGateway {
+        pub(crate) async fn spawn(external_ip: Ipv4Addr, pcp: bool) -> Self {
+            let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+            let addr = socket.local_addr().unwrap();
+            let lifetimes = Arc::new(Mutex::new(Vec::new()));
+
+            let recorded = lifetimes.clone();
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1100];
+                while let Ok((len, from)) = socket.recv_from(&mut buf).await {
+                    let request = &buf[..len];
+                    let mut response = Vec::new();
+                    match (request[0], request[1]) {
+                        (PCP_VERSION, PCP_OPCODE_MAP) if pcp => {
+                            recorded
+                                .lock()
+                                .unwrap()
+                                .push(u32::from_be_bytes(request[4..8].try_into().unwrap()));
+                            response.extend([PCP_VERSION, PCP_OPCODE_MAP | RESPONSE_BIT, 0, 0]);
+                            response.extend(&request[4..8]);
+                            response.extend([0; 16]);
+                            // nonce, protocol, internal port and suggested external port
+                            response.extend(&request[24..44]);
+                            response.extend(external_ip.to_ipv6_mapped().octets());
+                        }
+                        (PCP_VERSION, _) => {
+                            response.extend([NATPMP_VERSION, request[1] | RESPONSE_BIT, 0, 1]);
+                        }
+                        (NATPMP_VERSION, NATPMP_OPCODE_EXTERNAL_ADDRESS) => {
+                            response.extend([NATPMP_VERSION, RESPONSE_BIT, 0, 0, 0, 0, 0, 0]);
+                            response.extend(external_ip.octets());
+                        }
+                        (NATPMP_VERSION, opcode) => {
+                            recorded
+                                .lock()
+                                .unwrap()
+                                .push(u32::from_be_bytes(request[8..12].try_into().unwrap()));
+                            response.extend([NATPMP_VERSION, opcode | RESPONSE_BIT, 0, 0]);
+                            response.extend([0; 4]);
+                            // internal port and suggested external port
+                            response.extend(&request[4..8]);
+                            response.extend(&request[8..12]);
+                        }
+                        _ => continue,
+                    }
+                    let _ = socket.send_to(&response, from).await;
+                }
+            });
+
+            Self { addr, lifetimes }
+        }
+    }
+
+    #[test]
+    fn parse_route_table() {
+        let routes =
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+                      eth0\t0000A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+                      eth0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0\n";
+        assert_eq!(parse_default_route(routes), Some(Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(parse_default_route("Iface\tDestination\tGateway\n"), None);
+    }
+
+    #[tokio::test]
+    async fn natpmp_fallback() {
+        let fake = FakePmpGateway::spawn(Ipv4Addr::new(203, 0, 113, 7), false).await;
+        let mut gateway = PmpGateway::new(fake.addr).await.unwrap();
+
+        let port = gateway
+            .map_port(PortMappingProtocol::Udp, 30303, 30303, Duration::from_secs(1200))
+            .await
+            .unwrap();
+        assert_eq!(port, 30303);
+        assert_eq!(gateway.pcp, Some(false));
+        assert_eq!(gateway.external_ip().await.unwrap(), Ipv4Addr::new(203, 0, 113, 7));
+
+        gateway.map_port(PortMappingProtocol::Udp, 30303, 0, Duration::ZERO).await.unwrap();
+        assert_eq!(*fake.lifetimes.lock().unwrap(), vec![1200, 0]);
+    }
+
+    #[tokio::test]
+    async fn pcp_mapping() {
+        let fake = FakePmpGateway::spawn(Ipv4Addr::new(203, 0, 113, 7), true).await;
+        let mut gateway = PmpGateway::new(fake.addr).await.unwrap();
+
+        let port = gateway
+            .map_port(PortMappingProtocol::Tcp, 30303, 30303, Duration::from_secs(1200))
+            .await
+            .unwrap();
+        assert_eq!(port, 30303);
+        assert_eq!(gateway.pcp, Some(true));
+        assert_eq!(gateway.external_ip().await.unwrap(), Ipv4Addr::new(203, 0, 113, 7));
+        assert_eq!(*fake.lifetimes.lock().unwrap(), vec![1200]);
+    }
+}
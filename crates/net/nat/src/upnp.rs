@@ -0,0 +1,408 @@
+//! Minimal `UPnP` Internet Gateway Device (IGD) client.
+//!
+//! Gateways are discovered via SSDP, the WAN connection service is looked up in the device
+//! description and port mappings are managed via SOAP actions on its control URL.
+
+use crate::mapping::{local_ip_towards, PortMappingError, PortMappingProtocol};
+use reqwest::Url;
+use std::{
+    fmt::Write,
+    net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+use tracing::debug;
+
+/// The SSDP multicast address `UPnP` devices listen on.
+pub const SSDP_MULTICAST_ADDR: SocketAddr =
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900));
+
+/// Device type searched for via SSDP.
+const IGD_DEVICE_TYPE: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+
+/// Services that provide the port mapping actions, in order of preference.
+const WAN_SERVICE_TYPES: &[&str] = &[
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+/// `UPnP` error code of gateways that only support permanent leases.
+const ONLY_PERMANENT_LEASES_SUPPORTED: u16 = 725;
+
+/// Timeout for HTTP requests to the gateway.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A `UPnP` Internet Gateway Device that supports port mappings.
+#[derive(Debug, Clone)]
+pub struct IgdGateway {
+    /// The control URL of the WAN connection service.
+    control_url: Url,
+    /// The WAN connection service type.
+    service_type: &'static str,
+    /// The IP of the local interface that faces the gateway.
+    local_ip: IpAddr,
+    /// HTTP client used for all requests to the gateway.
+    client: reqwest::Client,
+}
+
+impl IgdGateway {
+    /// Searches the local network for a gateway via SSDP multicast.
+    pub async fn search(timeout: Duration) -> Result<Self, PortMappingError> {
+        Self::search_at(SSDP_MULTICAST_ADDR, timeout).await
+    }
+
+    /// Sends the SSDP search request to the given address and returns the first gateway that
+    /// responds and supports port mappings.
+    pub async fn search_at(
+        ssdp_addr: SocketAddr,
+        timeout: Duration,
+    ) -> Result<Self, PortMappingError> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        let request = format!(
+            "M-SEARCH * HTTP/1.1\r\nHOST: {SSDP_MULTICAST_ADDR}\r\nST: {IGD_DEVICE_TYPE}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\n\r\n"
+        );
+        socket.send_to(request.as_bytes(), ssdp_addr).await?;
+
+        tokio::time::timeout(timeout, Self::recv_gateway(&socket))
+            .await
+            .map_err(|_| PortMappingError::NoGateway)?
+    }
+
+    /// Waits for the first SSDP response of a gateway that supports port mappings.
+    async fn recv_gateway(socket: &UdpSocket) -> Result<Self, PortMappingError> {
+        let mut buf = [0u8; 2048];
+        loop {
+            let (len, from) = socket.recv_from(&mut buf).await?;
+            let Some(location) = parse_ssdp_location(&buf[..len]) else { continue };
+            match Self::from_location(&location, from.ip()).await {
+                Ok(gateway) => return Ok(gateway),
+                Err(err) => {
+                    debug!(target: "net::nat", %from, %err, "Ignoring UPnP device");
+                }
+            }
+        }
+    }
+
+    /// Fetches the device description at `location` and looks up the WAN connection service.
+    async fn from_location(location: &str, gateway_ip: IpAddr) -> Result<Self, PortMappingError> {
+        let client = reqwest::Client::builder().timeout(HTTP_TIMEOUT).build()?;
+        let location: Url = location
+            .parse()
+            .map_err(|_| PortMappingError::InvalidResponse("invalid description location"))?;
+        let description =
+            client.get(location.clone()).send().await?.error_for_status()?.text().await?;
+
+        let (service_type, control_url) = parse_wan_service(&description)
+            .ok_or(PortMappingError::InvalidResponse("no WAN connection service"))?;
+        let control_url = location
+            .join(control_url)
+            .map_err(|_| PortMappingError::InvalidResponse("invalid control URL"))?;
+        let local_ip = local_ip_towards(gateway_ip).await?;
+
+        Ok(Self { control_url, service_type, local_ip, client })
+    }
+
+    /// Returns the IP of the local interface that faces the gateway.
+    pub const fn local_ip(&self) -> IpAddr {
+        self.local_ip
+    }
+
+    /// Returns the external IP address of the gateway.
+    pub async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        let response = self.soap("GetExternalIPAddress", &[]).await?;
+        xml_value(&response, "NewExternalIPAddress")
+            .and_then(|ip| ip.parse().ok())
+            .ok_or(PortMappingError::InvalidResponse("invalid external IP address"))
+    }
+
+    /// Maps `external_port` on the gateway to `internal_port` of this host.
+    ///
+    /// Falls back to a permanent mapping if the gateway doesn't support leases.
+    pub async fn add_port_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lease: Duration,
+        description: &str,
+    ) -> Result<(), PortMappingError> {
+        let args = |lease: u64| {
+            [
+                ("NewRemoteHost", String::new()),
+                ("NewExternalPort", external_port.to_string()),
+                ("NewProtocol", protocol.to_string()),
+                ("NewInternalPort", internal_port.to_string()),
+                ("NewInternalClient", self.local_ip.to_string()),
+                ("NewEnabled", "1".to_string()),
+                ("NewPortMappingDescription", description.to_string()),
+                ("NewLeaseDuration", lease.to_string()),
+            ]
+        };
+
+        match self.soap("AddPortMapping", &args(lease.as_secs())).await {
+            Err(PortMappingError::Upnp { code: ONLY_PERMANENT_LEASES_SUPPORTED, .. }) => {
+                debug!(target: "net::nat", %protocol, external_port, "Gateway only supports permanent port mappings");
+                self.soap("AddPortMapping", &args(0)).await?;
+            }
+            res => {
+                res?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the mapping of `external_port` from the gateway.
+    pub async fn remove_port_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        external_port: u16,
+    ) -> Result<(), PortMappingError> {
+        let args = [
+            ("NewRemoteHost", String::new()),
+            ("NewExternalPort", external_port.to_string()),
+            ("NewProtocol", protocol.to_string()),
+        ];
+        self.soap("DeletePortMapping", &args).await?;
+        Ok(())
+    }
+
+    /// Invokes the SOAP `action` on the WAN connection service and returns the response body.
+    async fn soap(
+        &self,
+        action: &str,
+        args: &[(&str, String)],
+    ) -> Result<String, PortMappingError> {
+        let mut body = format!(
+            r#"<?xml version="1.0"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/"><s:Body><u:{action} xmlns:u="{}">"#,
+            self.service_type
+        );
+        for (name, value) in args {
+            let _ = write!(body, "<{name}>{value}</{name}>");
+        }
+        let _ = write!(body, "</u:{action}></s:Body></s:Envelope>");
+
+        let response = self
+            .client
+            .post(self.control_url.clone())
+            .header("Content-Type", r#"text/xml; charset="utf-8""#)
+            .header("SOAPAction", format!(r#""{}#{action}""#, self.service_type))
+            .body(body)
+            .send()
+            .await?;
+        let status = response.status();
+        let text = response.text().await?;
+
+        if !status.is_success() {
+            let code = xml_value(&text, "errorCode")
+                .and_then(|code| code.parse().ok())
+                .unwrap_or_else(|| status.as_u16());
+            let description = xml_value(&text, "errorDescription").unwrap_or_default().to_string();
+            return Err(PortMappingError::Upnp { code, description })
+        }
+
+        Ok(text)
+    }
+}
+
+/// Returns the `LOCATION` header of an SSDP search response.
+fn parse_ssdp_location(response: &[u8]) -> Option<String> {
+    let response = std::str::from_utf8(response).ok()?;
+    let mut lines = response.lines();
+    if !lines.next()?.contains(" 200 ") {
+        return None
+    }
+    lines.find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("location").then(|| value.trim().to_string())
+    })
+}
+
+/// Returns the type and control URL of the preferred WAN connection service in the device
+/// description.
+fn parse_wan_service(description: &str) -> Option<(&'static str, &str)> {
+    let services = description
+        .split("<service>")
+        .skip(1)
+        .filter_map(|service| service.split("</service>").next())
+        .collect::<Vec<_>>();
+
+    WAN_SERVICE_TYPES.iter().find_map(|service_type| {
+        services
+            .iter()
+            .find(|service| xml_value(service, "serviceType") == Some(service_type))
+            .and_then(|service| xml_value(service, "controlURL"))
+            .map(|control_url| (*service_type, control_url))
+    })
+}
+
+/// Returns the trimmed text of the first `<tag>` element.
+fn xml_value<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{tag}>"))?;
+    Some(xml[start..end].trim())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:InternetGatewayDevice:1</deviceType>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>
+        <controlURL>/l3f</controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+        <controlURL>/ctl/IPConn</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+
+    /// A fake IGD that answers SSDP searches and SOAP requests, recording all SOAP actions.
+    pub(crate) struct FakeIgd {
+        pub(crate) ssdp_addr: SocketAddr,
+        pub(crate) actions: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FakeIgd {
+        pub(crate) async fn spawn(external_ip: Ipv4Addr) -> Self {
+            let http = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+            let http_addr = http.local_addr().unwrap();
+            let ssdp = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+            let ssdp_addr = ssdp.local_addr().unwrap();
+            let actions = Arc::new(Mutex::new(Vec::new()));
+
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                while let Ok((_, from)) = ssdp.recv_from(&mut buf).await {
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nST: {IGD_DEVICE_TYPE}\r\nLocation: http://{http_addr}/desc.xml\r\n\r\n"
+                    );
+                    let _ = ssdp.send_to(response.as_bytes(), from).await;
+                }
+            });
+
+            let recorded = actions.clone();
+            tokio::spawn(async move {
+                while let Ok((mut stream, _)) = http.accept().await {
+                    let request = read_http_request(&mut stream).await;
+                    let (status, body) = if request.starts_with("GET /desc.xml") {
+                        ("200 OK", DESCRIPTION.to_string())
+                    } else if let Some(action) = http_header(&request, "soapaction")
+                        .and_then(|action| action.trim_matches('"').split('#').nth(1))
+                    {
+                        recorded.lock().unwrap().push(action.to_string());
+                        match action {
+                            "GetExternalIPAddress" => (
+                                "200 OK",
+                                format!("<s:Envelope><s:Body><u:GetExternalIPAddressResponse><NewExternalIPAddress>{external_ip}</NewExternalIPAddress></u:GetExternalIPAddressResponse></s:Body></s:Envelope>"),
+                            ),
+                            "AddPortMapping" if !request.contains("<NewLeaseDuration>0<") => (
+                                "500 Internal Server Error",
+                                "<s:Envelope><s:Body><s:Fault><detail><UPnPError><errorCode>725</errorCode><errorDescription>OnlyPermanentLeasesSupported</errorDescription></UPnPError></detail></s:Fault></s:Body></s:Envelope>".to_string(),
+                            ),
+                            _ => ("200 OK", "<s:Envelope><s:Body/></s:Envelope>".to_string()),
+                        }
+                    } else {
+                        ("404 Not Found", String::new())
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                }
+            });
+
+            Self { ssdp_addr, actions }
+        }
+    }
+
+    /// Reads a full HTTP request from the stream.
+    async fn read_http_request(stream: &mut tokio::net::TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let len = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..len]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            let Some((headers, body)) = text.split_once("\r\n\r\n") else { continue };
+            let content_length = http_header(headers, "content-length")
+                .map(|len| len.parse::<usize>().unwrap())
+                .unwrap_or_default();
+            if len == 0 || body.len() >= content_length {
+                return text
+            }
+        }
+    }
+
+    /// Returns the value of the header with the given name.
+    fn http_header<'a>(headers: &'a str, name: &str) -> Option<&'a str> {
+        headers.lines().find_map(|line| {
+            let (header, value) = line.split_once(':')?;
+            header.eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+
+    #[test]
+    fn parse_search_response() {
+        let response = b"HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\nLOCATION: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+        assert_eq!(
+            parse_ssdp_location(response).as_deref(),
+            Some("http://192.168.1.1:5000/rootDesc.xml")
+        );
+        assert_eq!(parse_ssdp_location(b"NOTIFY * HTTP/1.1\r\nLOCATION: x\r\n\r\n"), None);
+    }
+
+    #[test]
+    fn parse_description() {
+        assert_eq!(
+            parse_wan_service(DESCRIPTION),
+            Some(("urn:schemas-upnp-org:service:WANIPConnection:1", "/ctl/IPConn"))
+        );
+        assert_eq!(parse_wan_service("<root></root>"), None);
+    }
+
+    #[tokio::test]
+    async fn fake_igd_port_mapping() {
+        let igd = FakeIgd::spawn(Ipv4Addr::new(203, 0, 113, 7)).await;
+
+        let gateway = IgdGateway::search_at(igd.ssdp_addr, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(gateway.local_ip(), IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        gateway
+            .add_port_mapping(
+                PortMappingProtocol::Tcp,
+                30303,
+                30303,
+                Duration::from_secs(1200),
+                "reth",
+            )
+            .await
+            .unwrap();
+        assert_eq!(gateway.external_ip().await.unwrap(), Ipv4Addr::new(203, 0, 113, 7));
+        gateway.remove_port_mapping(PortMappingProtocol::Tcp, 30303).await.unwrap();
+
+        assert_eq!(
+            *igd.actions.lock().unwrap(),
+            vec![
+                "AddPortMapping",
+                // retried with a permanent lease
+                "AddPortMapping",
+                "GetExternalIPAddress",
+                "DeletePortMapping"
+            ]
+        );
+    }
+}
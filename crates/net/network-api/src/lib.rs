@@ -19,13 +19,17 @@ pub mod error;
 pub mod events;
 /// Implementation of network traits for that does nothing.
 pub mod noop;
+pub mod peers;
 
 pub mod test_utils;
-use test_utils::PeersHandleProvider;
 
 pub use alloy_rpc_types_admin::EthProtocolInfo;
+pub use reth_net_banlist::{BanEntry, BanTarget, IpCidr};
 pub use reth_network_p2p::{BlockClient, HeadersClient};
-pub use reth_network_types::{PeerKind, Reputation, ReputationChangeKind};
+pub use reth_network_types::{
+    MessageBandwidthStats, PeerBandwidthStats, PeerKind, PeerScoreStats, Reputation,
+    ReputationChangeKind, ServedDataKind, ServedDataStats,
+};
 
 pub use downloaders::BlockDownloaderProvider;
 pub use error::NetworkError;
@@ -33,6 +37,7 @@ pub use events::{
     DiscoveredEvent, DiscoveryEvent, NetworkEvent, NetworkEventListenerProvider, PeerRequest,
     PeerRequestSender,
 };
+pub use peers::{PeerCommand, PeersHandle, PeersHandleProvider};
 
 use reth_eth_wire_types::{
     capability::Capabilities, Capability, DisconnectReason, EthVersion, NetworkPrimitives,
@@ -40,7 +45,12 @@ use reth_eth_wire_types::{
 };
 use reth_network_p2p::sync::NetworkSyncUpdater;
 use reth_network_peers::NodeRecord;
-use std::{future::Future, net::SocketAddr, sync::Arc, time::Instant};
+use std::{
+    future::Future,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// The `PeerId` type.
 pub type PeerId = alloy_primitives::B512;
@@ -215,6 +225,17 @@ pub trait Peers: PeersInfo {
         &self,
         peer_id: PeerId,
     ) -> impl Future<Output = Result<Option<Reputation>, NetworkError>> + Send;
+
+    /// Bans the peer, IP address or IP range indefinitely, or for the given duration.
+    ///
+    /// Connected peers the ban applies to are disconnected.
+    fn ban(&self, target: BanTarget, duration: Option<Duration>);
+
+    /// Lifts the ban of the peer, IP address or IP range.
+    fn unban(&self, target: BanTarget);
+
+    /// Returns all active bans.
+    fn get_bans(&self) -> impl Future<Output = Result<Vec<BanEntry>, NetworkError>> + Send;
 }
 
 /// Info about an active peer session.
@@ -244,6 +265,10 @@ pub struct PeerInfo {
     pub session_established: Instant,
     /// The peer's connection kind
     pub kind: PeerKind,
+    /// The quality of the data the peer served in this session.
+    pub score: PeerScoreStats,
+    /// The bytes exchanged with the peer in this session.
+    pub bandwidth: PeerBandwidthStats,
 }
 
 /// The direction of the connection.
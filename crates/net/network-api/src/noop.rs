@@ -4,12 +4,15 @@
 //! generic over it.
 
 use core::{fmt, marker::PhantomData};
-use std::net::{IpAddr, SocketAddr};
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
 
 use crate::{
     events::{NetworkPeersEvents, PeerEventStream},
-    test_utils::{PeersHandle, PeersHandleProvider},
-    BlockDownloaderProvider, DiscoveryEvent, NetworkError, NetworkEvent,
+    peers::{PeersHandle, PeersHandleProvider},
+    BanEntry, BanTarget, BlockDownloaderProvider, DiscoveryEvent, NetworkError, NetworkEvent,
     NetworkEventListenerProvider, NetworkInfo, NetworkStatus, PeerId, PeerInfo, PeerRequest, Peers,
     PeersInfo,
 };
@@ -167,6 +170,14 @@ where
     async fn reputation_by_id(&self, _peer_id: PeerId) -> Result<Option<Reputation>, NetworkError> {
         Ok(None)
     }
+
+    fn ban(&self, _target: BanTarget, _duration: Option<Duration>) {}
+
+    fn unban(&self, _target: BanTarget) {}
+
+    async fn get_bans(&self) -> Result<Vec<BanEntry>, NetworkError> {
+        Ok(vec![])
+    }
 }
 
 impl<Net> BlockDownloaderProvider for NoopNetwork<Net>
@@ -1,5 +1,5 @@
-//! Interaction with `reth_network::PeersManager`, for integration testing. Otherwise
-//! `reth_network::NetworkManager` manages `reth_network::PeersManager`.
+//! Interaction with `reth_network::PeersManager`, which is managed by
+//! `reth_network::NetworkManager`.
 
 use std::net::SocketAddr;
 
@@ -1,5 +1,3 @@
 //! API for integration testing network components.
 
-pub mod peers_manager;
-
-pub use peers_manager::{PeerCommand, PeersHandle, PeersHandleProvider};
+pub use crate::peers::{PeerCommand, PeersHandle, PeersHandleProvider};
@@ -23,13 +23,16 @@ pub use peers::reputation::{Reputation, ReputationChangeKind, ReputationChangeWe
 
 pub use backoff::BackoffKind;
 pub use peers::{
-    addr::PeerAddr,
+    addr::{IpVersions, PeerAddr},
     kind::PeerKind,
     reputation::{
         is_banned_reputation, is_connection_failed_reputation, ReputationChangeOutcome,
         DEFAULT_REPUTATION,
     },
+    score::{PeerScore, PeerScoreStats, ServedDataKind, ServedDataStats},
     state::PeerConnectionState,
-    ConnectionsConfig, Peer, PeersConfig,
+    ConnectionsConfig, Peer, PeerReputation, PeersConfig, PersistedPeerState,
+};
+pub use session::{
+    MessageBandwidthStats, PeerBandwidthStats, SessionLimits, SessionsConfig, UploadLimits,
 };
-pub use session::{SessionLimits, SessionsConfig};
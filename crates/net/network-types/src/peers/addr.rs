@@ -43,3 +43,49 @@ impl PeerAddr {
         Self::new(tcp, udp)
     }
 }
+
+/// The IP versions the local node can reach peers over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpVersions {
+    /// Only IPv4.
+    Ipv4,
+    /// Only IPv6.
+    Ipv6,
+    /// Both IPv4 and IPv6.
+    #[default]
+    DualStack,
+}
+
+impl IpVersions {
+    /// Returns the IP versions of the given local addresses, for example the addresses of the
+    /// `RLPx` listeners. Returns [`IpVersions::DualStack`] if there are no addresses.
+    ///
+    /// The unspecified IPv6 address is considered to cover both IP versions, since sockets bound to
+    /// it accept IPv4 traffic by default.
+    pub fn from_addrs(addrs: impl IntoIterator<Item = SocketAddr>) -> Self {
+        let (mut ipv4, mut ipv6) = (false, false);
+        for addr in addrs {
+            match addr.ip() {
+                IpAddr::V4(_) => ipv4 = true,
+                IpAddr::V6(ip) => {
+                    ipv6 = true;
+                    ipv4 |= ip.is_unspecified();
+                }
+            }
+        }
+        match (ipv4, ipv6) {
+            (true, false) => Self::Ipv4,
+            (false, true) => Self::Ipv6,
+            _ => Self::DualStack,
+        }
+    }
+
+    /// Returns `true` if the IP version of the given address is supported.
+    pub const fn supports(&self, addr: &SocketAddr) -> bool {
+        match self {
+            Self::Ipv4 => addr.is_ipv4(),
+            Self::Ipv6 => addr.is_ipv6(),
+            Self::DualStack => true,
+        }
+    }
+}
@@ -1,17 +1,17 @@
 //! Configuration for peering.
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     io::{self, ErrorKind},
     path::Path,
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use reth_net_banlist::BanList;
-use reth_network_peers::{NodeRecord, TrustedPeer};
+use reth_network_peers::{NodeRecord, PeerId, TrustedPeer};
 use tracing::info;
 
-use crate::{BackoffKind, ReputationChangeWeights};
+use crate::{BackoffKind, PersistedPeerState, Reputation, ReputationChangeWeights};
 
 /// Maximum number of available slots for outbound sessions.
 pub const DEFAULT_MAX_COUNT_PEERS_OUTBOUND: u32 = 100;
@@ -153,6 +153,10 @@ pub struct PeersConfig {
     /// Restrictions on `PeerIds` and Ips.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub ban_list: BanList,
+    /// Reputations of known peers that differ from the default reputation, restored from a
+    /// previous run.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub reputations: HashMap<PeerId, Reputation>,
     /// Restrictions on connections.
     pub connection_info: ConnectionsConfig,
     /// How to weigh reputation changes.
@@ -175,6 +179,7 @@ impl Default for PeersConfig {
             connection_info: Default::default(),
             reputation_weights: Default::default(),
             ban_list: Default::default(),
+            reputations: Default::default(),
             // Ban peers for 12h
             ban_duration: Duration::from_secs(60 * 60 * 12),
             backoff_durations: Default::default(),
@@ -301,6 +306,33 @@ impl PeersConfig {
         Ok(self.with_basic_nodes(nodes))
     }
 
+    /// Restores the bans and the decayed reputations of a previous run.
+    ///
+    /// See also [`PersistedPeerState::decayed_reputations`].
+    pub fn with_persisted_state(mut self, state: PersistedPeerState) -> Self {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        self.reputations.extend(state.decayed_reputations(now));
+        self.ban_list.extend(state.bans);
+        self
+    }
+
+    /// Read from file the bans and reputations of a previous run. Ignored if None.
+    #[cfg(feature = "serde")]
+    pub fn with_persisted_state_from_file(
+        self,
+        optional_file: Option<impl AsRef<Path>>,
+    ) -> Result<Self, io::Error> {
+        let Some(file_path) = optional_file else { return Ok(self) };
+        let reader = match std::fs::File::open(file_path.as_ref()) {
+            Ok(file) => io::BufReader::new(file),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(self),
+            Err(e) => Err(e)?,
+        };
+        info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved bans and reputations");
+        let state: PersistedPeerState = serde_json::from_reader(reader)?;
+        Ok(self.with_persisted_state(state))
+    }
+
     /// Returns settings for testing
     #[cfg(any(test, feature = "test-utils"))]
     pub fn test() -> Self {
@@ -1,18 +1,23 @@
 pub mod addr;
 pub mod config;
 pub mod kind;
+pub mod persisted;
 pub mod reputation;
+pub mod score;
 pub mod state;
 
 pub use config::{ConnectionsConfig, PeersConfig};
+pub use persisted::{PeerReputation, PersistedPeerState};
 pub use reputation::{Reputation, ReputationChange, ReputationChangeKind, ReputationChangeWeights};
+pub use score::{PeerScore, PeerScoreStats, ServedDataKind, ServedDataStats};
 
 use alloy_eip2124::ForkId;
+use std::sync::Arc;
 use tracing::trace;
 
 use crate::{
-    is_banned_reputation, PeerAddr, PeerConnectionState, PeerKind, ReputationChangeOutcome,
-    DEFAULT_REPUTATION,
+    is_banned_reputation, IpVersions, PeerAddr, PeerConnectionState, PeerKind,
+    ReputationChangeOutcome, DEFAULT_REPUTATION,
 };
 
 /// Tracks info about a single peer.
@@ -20,6 +25,9 @@ use crate::{
 pub struct Peer {
     /// Where to reach the peer.
     pub addr: PeerAddr,
+    /// Where to reach the peer over the other IP version, if the peer advertised addresses of
+    /// both IP versions.
+    pub secondary_addr: Option<PeerAddr>,
     /// Reputation of the peer.
     pub reputation: i32,
     /// The state of the connection, if any.
@@ -35,6 +43,8 @@ pub struct Peer {
     /// Counts number of times the peer was backed off due to a severe
     /// [`BackoffKind`](crate::BackoffKind).
     pub severe_backoff_counter: u8,
+    /// The quality of the data the peer served in its current or last session.
+    pub score: Option<Arc<PeerScore>>,
 }
 
 // === impl Peer ===
@@ -59,6 +69,7 @@ impl Peer {
     pub fn with_state(addr: PeerAddr, state: PeerConnectionState) -> Self {
         Self {
             addr,
+            secondary_addr: None,
             state,
             reputation: DEFAULT_REPUTATION,
             fork_id: None,
@@ -66,6 +77,7 @@ impl Peer {
             kind: Default::default(),
             backed_off: false,
             severe_backoff_counter: 0,
+            score: None,
         }
     }
 
@@ -74,6 +86,35 @@ impl Peer {
         Self { kind, ..Self::new(addr) }
     }
 
+    /// Updates the address of the peer.
+    ///
+    /// If the new address is of the other IP version, the current address is kept as
+    /// [`Self::secondary_addr`].
+    pub const fn set_addr(&mut self, addr: PeerAddr) {
+        if addr.tcp().is_ipv4() != self.addr.tcp().is_ipv4() {
+            self.secondary_addr = Some(self.addr);
+        }
+        self.addr = addr;
+    }
+
+    /// Makes the address of an IP version the local node can reach the address of the peer, if
+    /// the current address is of an unsupported IP version.
+    ///
+    /// Returns `true` if the addresses were swapped.
+    pub const fn prefer_ip_versions(&mut self, ip_versions: IpVersions) -> bool {
+        if ip_versions.supports(&self.addr.tcp()) {
+            return false
+        }
+        match self.secondary_addr {
+            Some(secondary) if ip_versions.supports(&secondary.tcp()) => {
+                self.secondary_addr = Some(self.addr);
+                self.addr = secondary;
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Resets the reputation of the peer to the default value. This always returns
     /// [`ReputationChangeOutcome::None`].
     pub const fn reset_reputation(&mut self) -> ReputationChangeOutcome {
@@ -134,6 +175,12 @@ impl Peer {
         matches!(self.kind, PeerKind::Trusted)
     }
 
+    /// Returns the share of requests the peer answered with data, see
+    /// [`PeerScore::usefulness`].
+    pub fn usefulness(&self) -> Option<f64> {
+        self.score.as_ref()?.usefulness()
+    }
+
     /// Returns whether this peer is static
     #[inline]
     pub const fn is_static(&self) -> bool {
@@ -0,0 +1,77 @@
+//! Peer state that is persisted across restarts.
+
+use crate::{Reputation, DEFAULT_REPUTATION};
+use reth_net_banlist::BanEntry;
+use reth_network_peers::PeerId;
+
+/// Bans and reputations of peers, persisted across restarts so misbehaving peers can't reconnect
+/// right after a restart.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct PersistedPeerState {
+    /// Unix timestamp in seconds at which the state was saved.
+    pub saved_at: u64,
+    /// The bans that were active when the state was saved.
+    pub bans: Vec<BanEntry>,
+    /// The peers whose reputation differs from the default reputation.
+    pub reputations: Vec<PeerReputation>,
+}
+
+impl PersistedPeerState {
+    /// Returns the reputations at the given unix timestamp.
+    ///
+    /// Reputations below the default recover by one point for every second since the state was
+    /// saved, which is the rate at which connected peers recover. Reputations that recovered to the
+    /// default are skipped.
+    pub fn decayed_reputations(&self, now: u64) -> impl Iterator<Item = (PeerId, Reputation)> + '_ {
+        let elapsed = Reputation::try_from(now.saturating_sub(self.saved_at)).unwrap_or(i32::MAX);
+        self.reputations.iter().filter_map(move |PeerReputation { peer_id, reputation }| {
+            let reputation = if *reputation < DEFAULT_REPUTATION {
+                reputation.saturating_add(elapsed).min(DEFAULT_REPUTATION)
+            } else {
+                *reputation
+            };
+            (reputation != DEFAULT_REPUTATION).then_some((*peer_id, reputation))
+        })
+    }
+}
+
+/// The reputation of a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PeerReputation {
+    /// The ID of the peer.
+    pub peer_id: PeerId,
+    /// The reputation of the peer.
+    pub reputation: Reputation,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peers::reputation::BANNED_REPUTATION;
+
+    #[test]
+    fn reputations_decay() {
+        let banned = PeerId::new([1; 64]);
+        let slashed = PeerId::new([2; 64]);
+        let state = PersistedPeerState {
+            saved_at: 1_000,
+            bans: Vec::new(),
+            reputations: vec![
+                PeerReputation { peer_id: banned, reputation: BANNED_REPUTATION },
+                PeerReputation { peer_id: slashed, reputation: -100 },
+            ],
+        };
+
+        let reputations = state.decayed_reputations(1_060).collect::<Vec<_>>();
+        assert_eq!(reputations, vec![(banned, BANNED_REPUTATION + 60), (slashed, -40)]);
+
+        let reputations = state.decayed_reputations(1_100).collect::<Vec<_>>();
+        assert_eq!(reputations, vec![(banned, BANNED_REPUTATION + 100)]);
+
+        // a clock that went backwards doesn't decay
+        let reputations = state.decayed_reputations(0).collect::<Vec<_>>();
+        assert_eq!(reputations.len(), 2);
+    }
+}
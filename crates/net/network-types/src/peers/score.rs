@@ -0,0 +1,235 @@
+//! Scoring of peers by the quality of the data they serve.
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// How much a new latency sample affects the moving average (X percent).
+const LATENCY_SAMPLE_IMPACT: f64 = 0.2;
+
+/// The minimum number of requests before the usefulness of a peer is rated, so a few empty
+/// responses right after connecting don't mark a peer as useless.
+pub const MIN_SCORED_REQUESTS: u64 = 32;
+
+/// The kinds of data served by peers that are scored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum ServedDataKind {
+    /// Block headers.
+    Headers,
+    /// Block bodies.
+    Bodies,
+    /// Receipts.
+    Receipts,
+    /// Pooled transactions.
+    PooledTransactions,
+}
+
+impl ServedDataKind {
+    /// All kinds of served data.
+    pub const ALL: [Self; 4] =
+        [Self::Headers, Self::Bodies, Self::Receipts, Self::PooledTransactions];
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Tracks the quality of the data a peer served in a session.
+///
+/// This is updated by the session that sends the requests and read by the components that choose
+/// which peers to request data from or to stay connected to.
+#[derive(Debug, Default)]
+pub struct PeerScore {
+    served: [ServedDataCounters; 4],
+}
+
+impl PeerScore {
+    /// Records a response of the given kind.
+    pub fn on_response(&self, kind: ServedDataKind, latency: Duration, bytes: usize, empty: bool) {
+        let counters = &self.served[kind.index()];
+        counters.requests.fetch_add(1, Ordering::Relaxed);
+        counters.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        if empty {
+            counters.empty_responses.fetch_add(1, Ordering::Relaxed);
+        }
+
+        // there's only one session updating the score, so this doesn't race
+        let sample = latency.as_millis() as u64;
+        let current = counters.latency_ms.load(Ordering::Relaxed);
+        let latency = if current == 0 {
+            sample.max(1)
+        } else {
+            (sample as f64)
+                .mul_add(LATENCY_SAMPLE_IMPACT, current as f64 * (1.0 - LATENCY_SAMPLE_IMPACT))
+                .max(1.0) as u64
+        };
+        counters.latency_ms.store(latency, Ordering::Relaxed);
+    }
+
+    /// Records a request of the given kind that timed out.
+    pub fn on_timeout(&self, kind: ServedDataKind) {
+        let counters = &self.served[kind.index()];
+        counters.requests.fetch_add(1, Ordering::Relaxed);
+        counters.timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the stats of the data of the given kind.
+    pub fn stats(&self, kind: ServedDataKind) -> ServedDataStats {
+        self.served[kind.index()].stats()
+    }
+
+    /// Returns the stats of all kinds of served data.
+    pub fn all_stats(&self) -> PeerScoreStats {
+        PeerScoreStats {
+            headers: self.stats(ServedDataKind::Headers),
+            bodies: self.stats(ServedDataKind::Bodies),
+            receipts: self.stats(ServedDataKind::Receipts),
+            pooled_transactions: self.stats(ServedDataKind::PooledTransactions),
+        }
+    }
+
+    /// Returns the expected time until the peer serves a non-empty response of the given kind, or
+    /// `None` if the peer didn't respond to a request of this kind yet.
+    pub fn expected_latency(&self, kind: ServedDataKind) -> Option<Duration> {
+        self.stats(kind).expected_latency()
+    }
+
+    /// Returns the share of all requests that the peer answered with data, or `None` if the peer
+    /// was sent less than [`MIN_SCORED_REQUESTS`] requests.
+    pub fn usefulness(&self) -> Option<f64> {
+        let (requests, useful) =
+            ServedDataKind::ALL.iter().fold((0, 0), |(requests, useful), kind| {
+                let stats = self.stats(*kind);
+                (requests + stats.requests, useful + stats.useful_responses())
+            });
+        (requests >= MIN_SCORED_REQUESTS).then(|| useful as f64 / requests as f64)
+    }
+}
+
+/// Counters of a single kind of served data.
+#[derive(Debug, Default)]
+struct ServedDataCounters {
+    requests: AtomicU64,
+    empty_responses: AtomicU64,
+    timeouts: AtomicU64,
+    bytes: AtomicU64,
+    /// Moving average of the latency in milliseconds, zero if there was no response yet.
+    latency_ms: AtomicU64,
+}
+
+impl ServedDataCounters {
+    fn stats(&self) -> ServedDataStats {
+        let latency_ms = self.latency_ms.load(Ordering::Relaxed);
+        ServedDataStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            empty_responses: self.empty_responses.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            latency_ms: (latency_ms != 0).then_some(latency_ms),
+        }
+    }
+}
+
+/// Snapshot of the data of one kind a peer served.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ServedDataStats {
+    /// Number of requests sent to the peer, including timed out requests.
+    pub requests: u64,
+    /// Number of responses without any data.
+    pub empty_responses: u64,
+    /// Number of requests the peer didn't respond to in time.
+    pub timeouts: u64,
+    /// Total size of the responses in bytes.
+    pub bytes: u64,
+    /// Moving average of the response latency in milliseconds, `None` if the peer didn't respond
+    /// yet.
+    pub latency_ms: Option<u64>,
+}
+
+impl ServedDataStats {
+    /// Returns the number of responses that contained data.
+    pub const fn useful_responses(&self) -> u64 {
+        self.requests.saturating_sub(self.empty_responses).saturating_sub(self.timeouts)
+    }
+
+    /// Returns the average latency scaled by the share of useful responses, which is the expected
+    /// time until the peer serves a non-empty response.
+    pub fn expected_latency(&self) -> Option<Duration> {
+        let latency = self.latency_ms?;
+        let expected = latency.saturating_mul(self.requests) / self.useful_responses().max(1);
+        Some(Duration::from_millis(expected))
+    }
+}
+
+/// Snapshot of the data of all kinds a peer served.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct PeerScoreStats {
+    /// Stats of served block headers.
+    pub headers: ServedDataStats,
+    /// Stats of served block bodies.
+    pub bodies: ServedDataStats,
+    /// Stats of served receipts.
+    pub receipts: ServedDataStats,
+    /// Stats of served pooled transactions.
+    pub pooled_transactions: ServedDataStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_latency_penalizes_empty_responses() {
+        let fast = PeerScore::default();
+        let slow = PeerScore::default();
+        assert_eq!(fast.expected_latency(ServedDataKind::Headers), None);
+
+        for _ in 0..4 {
+            fast.on_response(ServedDataKind::Headers, Duration::from_millis(100), 512, false);
+            slow.on_response(ServedDataKind::Headers, Duration::from_millis(300), 512, false);
+        }
+        assert!(
+            fast.expected_latency(ServedDataKind::Headers) <
+                slow.expected_latency(ServedDataKind::Headers)
+        );
+
+        // the fast peer turns useless
+        for _ in 0..12 {
+            fast.on_response(ServedDataKind::Headers, Duration::from_millis(100), 0, true);
+        }
+        fast.on_timeout(ServedDataKind::Headers);
+        assert!(
+            fast.expected_latency(ServedDataKind::Headers) >
+                slow.expected_latency(ServedDataKind::Headers)
+        );
+
+        let stats = fast.stats(ServedDataKind::Headers);
+        assert_eq!(stats.requests, 17);
+        assert_eq!(stats.empty_responses, 12);
+        assert_eq!(stats.timeouts, 1);
+        assert_eq!(stats.bytes, 2048);
+        assert_eq!(stats.useful_responses(), 4);
+        assert_eq!(fast.stats(ServedDataKind::Bodies), ServedDataStats::default());
+    }
+
+    #[test]
+    fn usefulness_requires_enough_requests() {
+        let score = PeerScore::default();
+        for _ in 0..MIN_SCORED_REQUESTS / 2 {
+            score.on_response(ServedDataKind::Bodies, Duration::from_millis(50), 1024, false);
+        }
+        assert_eq!(score.usefulness(), None);
+
+        for _ in 0..MIN_SCORED_REQUESTS / 2 {
+            score.on_response(ServedDataKind::Receipts, Duration::from_millis(50), 0, true);
+        }
+        assert_eq!(score.usefulness(), Some(0.5));
+    }
+}
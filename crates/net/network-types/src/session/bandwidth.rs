@@ -0,0 +1,65 @@
+//! Bandwidth accounting and upload limits of peer sessions.
+
+/// Caps on the bytes per second the node uploads to its peers.
+///
+/// By default, uploads are not limited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct UploadLimits {
+    /// Maximum bytes per second uploaded to all peers combined.
+    pub global: Option<u64>,
+    /// Maximum bytes per second uploaded to a single peer.
+    pub per_peer: Option<u64>,
+}
+
+impl UploadLimits {
+    /// Sets the maximum bytes per second uploaded to all peers combined.
+    pub const fn with_global(mut self, bytes_per_sec: u64) -> Self {
+        self.global = Some(bytes_per_sec);
+        self
+    }
+
+    /// Sets the maximum bytes per second uploaded to a single peer.
+    pub const fn with_per_peer(mut self, bytes_per_sec: u64) -> Self {
+        self.per_peer = Some(bytes_per_sec);
+        self
+    }
+
+    /// Returns `true` if any upload limit is configured.
+    pub const fn is_limited(&self) -> bool {
+        self.global.is_some() || self.per_peer.is_some()
+    }
+}
+
+/// Snapshot of the bytes exchanged with a peer in a session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct PeerBandwidthStats {
+    /// Total bytes received from the peer.
+    pub ingress_bytes: u64,
+    /// Total bytes sent to the peer.
+    pub egress_bytes: u64,
+    /// Traffic per message type, only includes message types that have been exchanged.
+    pub messages: Vec<MessageBandwidthStats>,
+}
+
+/// Snapshot of the bytes exchanged with a peer for a single message type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct MessageBandwidthStats {
+    /// The name of the protocol the message belongs to, `p2p` for reserved messages.
+    pub protocol: String,
+    /// The id of the message relative to the message id offset of its protocol.
+    pub message_id: u8,
+    /// Number of bytes received.
+    pub ingress_bytes: u64,
+    /// Number of messages received.
+    pub ingress_messages: u64,
+    /// Number of bytes sent.
+    pub egress_bytes: u64,
+    /// Number of messages sent.
+    pub egress_messages: u64,
+}
@@ -0,0 +1,157 @@
+//! Upload shaping of responses and transaction broadcasts.
+
+use parking_lot::Mutex;
+use reth_network_peers::PeerId;
+use reth_network_types::UploadLimits;
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+/// Enforces the configured [`UploadLimits`] on the bytes the node uploads to its peers.
+///
+/// Each limit is a token bucket that refills at the configured rate and allows bursts of one
+/// second worth of bytes. Uploads that exceed the available budget are paid back before the budget
+/// is available again, so the configured rate is a hard cap on average.
+///
+/// The limiter is shared by the [`EthRequestHandler`](crate::eth_requests::EthRequestHandler) and
+/// the [`TransactionsManager`](crate::transactions::TransactionsManager).
+#[derive(Debug, Clone, Default)]
+pub struct UploadLimiter {
+    /// The budgets, `None` if uploads are not limited.
+    inner: Option<Arc<Mutex<UploadBudgets>>>,
+}
+
+impl UploadLimiter {
+    /// Creates a new limiter that enforces the given limits.
+    pub fn new(limits: UploadLimits) -> Self {
+        let inner = limits.is_limited().then(|| {
+            Arc::new(Mutex::new(UploadBudgets {
+                limits,
+                global: UploadBudget::default(),
+                peers: HashMap::default(),
+            }))
+        });
+        Self { inner }
+    }
+
+    /// Returns `true` if uploads are limited.
+    pub const fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Returns the configured limits.
+    pub fn limits(&self) -> UploadLimits {
+        self.inner.as_ref().map(|inner| inner.lock().limits).unwrap_or_default()
+    }
+
+    /// Returns the number of bytes that can currently be uploaded to the peer, `None` if uploads
+    /// are not limited.
+    pub fn available(&self, peer_id: &PeerId) -> Option<usize> {
+        self.available_at(peer_id, Instant::now())
+    }
+
+    fn available_at(&self, peer_id: &PeerId, now: Instant) -> Option<usize> {
+        let mut budgets = self.inner.as_ref()?.lock();
+        let UploadBudgets { limits, global, peers } = &mut *budgets;
+        let global = limits.global.map(|rate| global.refill(now, rate));
+        let peer = limits.per_peer.map(|rate| peers.entry(*peer_id).or_default().refill(now, rate));
+        let available = match (global, peer) {
+            (Some(global), Some(peer)) => global.min(peer),
+            (global, peer) => global.or(peer)?,
+        };
+        Some(available.max(0) as usize)
+    }
+
+    /// Deducts the bytes uploaded to the peer from the global and the peer's budget.
+    pub fn consume(&self, peer_id: &PeerId, bytes: usize) {
+        let Some(inner) = self.inner.as_ref() else { return };
+        let mut budgets = inner.lock();
+        let UploadBudgets { limits, global, peers } = &mut *budgets;
+        if limits.global.is_some() {
+            global.consume(bytes);
+        }
+        if limits.per_peer.is_some() {
+            peers.entry(*peer_id).or_default().consume(bytes);
+        }
+    }
+
+    /// Removes the budget of a disconnected peer.
+    pub fn remove_peer(&self, peer_id: &PeerId) {
+        if let Some(inner) = self.inner.as_ref() {
+            inner.lock().peers.remove(peer_id);
+        }
+    }
+}
+
+/// The budgets of all limits.
+#[derive(Debug)]
+struct UploadBudgets {
+    limits: UploadLimits,
+    global: UploadBudget,
+    peers: HashMap<PeerId, UploadBudget>,
+}
+
+/// A token bucket of bytes.
+#[derive(Debug, Default)]
+struct UploadBudget {
+    /// Bytes that can currently be uploaded, negative if more bytes were uploaded than available.
+    available: i64,
+    /// When the budget was last refilled, `None` if it was never used.
+    last_refill: Option<Instant>,
+}
+
+impl UploadBudget {
+    /// Refills the budget according to the given rate and returns the available bytes.
+    fn refill(&mut self, now: Instant, bytes_per_second: u64) -> i64 {
+        let burst = bytes_per_second.min(i64::MAX as u64) as i64;
+        match self.last_refill {
+            // bytes uploaded before the first refill are deducted from the initial burst
+            None => self.available = burst.saturating_add(self.available),
+            Some(last) => {
+                let refill = (now.saturating_duration_since(last).as_secs_f64() *
+                    bytes_per_second as f64) as i64;
+                self.available = self.available.saturating_add(refill).min(burst);
+            }
+        }
+        self.last_refill = Some(now);
+        self.available
+    }
+
+    /// Deducts the given number of uploaded bytes.
+    fn consume(&mut self, bytes: usize) {
+        self.available = self.available.saturating_sub(bytes as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn unlimited_by_default() {
+        let limiter = UploadLimiter::default();
+        assert!(!limiter.is_enabled());
+        assert_eq!(limiter.available(&PeerId::random()), None);
+    }
+
+    #[test]
+    fn enforces_global_and_per_peer_limits() {
+        let limiter =
+            UploadLimiter::new(UploadLimits::default().with_global(1000).with_per_peer(600));
+        let (a, b) = (PeerId::random(), PeerId::random());
+        let now = Instant::now();
+
+        assert_eq!(limiter.available_at(&a, now), Some(600));
+        limiter.consume(&a, 500);
+        assert_eq!(limiter.available_at(&a, now), Some(100));
+        // the global budget is shared
+        assert_eq!(limiter.available_at(&b, now), Some(500));
+
+        // overshooting the budget is paid back before more bytes are available
+        limiter.consume(&b, 700);
+        assert_eq!(limiter.available_at(&b, now), Some(0));
+        let later = now + Duration::from_millis(100);
+        assert_eq!(limiter.available_at(&b, later), Some(0));
+        let much_later = now + Duration::from_secs(10);
+        assert_eq!(limiter.available_at(&b, much_later), Some(600));
+    }
+}
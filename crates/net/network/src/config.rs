@@ -13,16 +13,20 @@ use reth_discv5::NetworkStackId;
 use reth_dns_discovery::DnsDiscoveryConfig;
 use reth_eth_wire::{
     handshake::{EthHandshake, EthRlpxHandshake},
-    EthNetworkPrimitives, HelloMessage, HelloMessageWithProtocols, NetworkPrimitives,
+    EthNetworkPrimitives, EthVersion, HelloMessage, HelloMessageWithProtocols, NetworkPrimitives,
     UnifiedStatus,
 };
 use reth_ethereum_forks::{ForkFilter, Head};
 use reth_network_peers::{mainnet_nodes, pk2id, sepolia_nodes, PeerId, TrustedPeer};
-use reth_network_types::{PeersConfig, SessionsConfig};
+use reth_network_types::{PeersConfig, SessionsConfig, UploadLimits};
 use reth_storage_api::{noop::NoopProvider, BlockNumReader, BlockReader, HeaderProvider};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use secp256k1::SECP256K1;
-use std::{collections::HashSet, net::SocketAddr, sync::Arc};
+use std::{
+    collections::HashSet,
+    net::{Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
 
 // re-export for convenience
 use crate::{
@@ -52,12 +56,18 @@ pub struct NetworkConfig<C, N: NetworkPrimitives = EthNetworkPrimitives> {
     pub dns_discovery_config: Option<DnsDiscoveryConfig>,
     /// Address to use for discovery v4.
     pub discovery_v4_addr: SocketAddr,
+    /// Address of the other IP version to additionally run discovery v4 on, if the node runs
+    /// dual-stack.
+    pub discovery_v4_secondary_addr: Option<SocketAddr>,
     /// How to set up discovery.
     pub discovery_v4_config: Option<Discv4Config>,
     /// How to set up discovery version 5.
     pub discovery_v5_config: Option<reth_discv5::Config>,
     /// Address to listen for incoming connections
     pub listener_addr: SocketAddr,
+    /// Additional addresses to listen for incoming connections on, for example the IPv6 address
+    /// of a dual-stack node.
+    pub additional_listener_addrs: Vec<SocketAddr>,
     /// How to instantiate peer manager.
     pub peers_config: PeersConfig,
     /// How to configure the [`SessionManager`](crate::session::SessionManager).
@@ -85,6 +95,8 @@ pub struct NetworkConfig<C, N: NetworkPrimitives = EthNetworkPrimitives> {
     pub extra_protocols: RlpxSubProtocols,
     /// Whether to disable transaction gossip
     pub tx_gossip_disabled: bool,
+    /// Caps on the bytes per second uploaded in responses and transaction broadcasts.
+    pub upload_limits: UploadLimits,
     /// How to instantiate transactions manager.
     pub transactions_manager_config: TransactionsManagerConfig,
     /// The NAT resolver for external IP
@@ -146,6 +158,12 @@ impl<C, N: NetworkPrimitives> NetworkConfig<C, N> {
     pub const fn listener_addr(&self) -> &SocketAddr {
         &self.listener_addr
     }
+
+    /// Returns all addresses for incoming `RLPx` connections, starting with
+    /// [`Self::listener_addr`].
+    pub fn listener_addrs(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        std::iter::once(self.listener_addr).chain(self.additional_listener_addrs.iter().copied())
+    }
 }
 
 impl<C, N> NetworkConfig<C, N>
@@ -197,8 +215,12 @@ pub struct NetworkConfigBuilder<N: NetworkPrimitives = EthNetworkPrimitives> {
     boot_nodes: HashSet<TrustedPeer>,
     /// Address to use for discovery
     discovery_addr: Option<SocketAddr>,
+    /// Address of the other IP version to use for discovery
+    discovery_secondary_addr: Option<SocketAddr>,
     /// Listener for incoming connections
     listener_addr: Option<SocketAddr>,
+    /// Additional listeners for incoming connections
+    additional_listener_addrs: Vec<SocketAddr>,
     /// How to instantiate peer manager.
     peers_config: Option<PeersConfig>,
     /// How to configure the sessions manager
@@ -215,6 +237,10 @@ pub struct NetworkConfigBuilder<N: NetworkPrimitives = EthNetworkPrimitives> {
     head: Option<Head>,
     /// Whether tx gossip is disabled
     tx_gossip_disabled: bool,
+    /// Caps on the bytes per second uploaded in responses and transaction broadcasts.
+    upload_limits: UploadLimits,
+    /// Whether eth/70 is advertised in addition to the default eth versions.
+    eth70: bool,
     /// The block importer type
     block_import: Option<Box<dyn BlockImport<N::NewBlockPayload>>>,
     /// How to instantiate transactions manager.
@@ -255,7 +281,9 @@ impl<N: NetworkPrimitives> NetworkConfigBuilder<N> {
             discovery_v5_builder: None,
             boot_nodes: Default::default(),
             discovery_addr: None,
+            discovery_secondary_addr: None,
             listener_addr: None,
+            additional_listener_addrs: Vec::new(),
             peers_config: None,
             sessions_config: None,
             network_mode: Default::default(),
@@ -264,6 +292,8 @@ impl<N: NetworkPrimitives> NetworkConfigBuilder<N> {
             extra_protocols: Default::default(),
             head: None,
             tx_gossip_disabled: false,
+            upload_limits: Default::default(),
+            eth70: false,
             block_import: None,
             transactions_manager_config: Default::default(),
             nat: None,
@@ -398,6 +428,33 @@ impl<N: NetworkPrimitives> NetworkConfigBuilder<N> {
         self
     }
 
+    /// Adds a socket address the network will additionally listen on for incoming connections.
+    pub fn add_listener_addr(mut self, listener_addr: SocketAddr) -> Self {
+        self.additional_listener_addrs.push(listener_addr);
+        self
+    }
+
+    /// Sets the socket address of the other IP version the discovery v4 network will
+    /// additionally listen on.
+    ///
+    /// This must be an IPv6 address if the discovery address is an IPv4 address and vice versa.
+    pub const fn discovery_secondary_addr(mut self, discovery_addr: SocketAddr) -> Self {
+        self.discovery_secondary_addr = Some(discovery_addr);
+        self
+    }
+
+    /// Runs the network on both IPv4 and IPv6: listens for incoming connections and runs
+    /// discovery v4 on the given IPv6 address in addition to the IPv4 addresses.
+    ///
+    /// The IPv6 listener and discovery sockets use the ports of the listener and discovery
+    /// addresses configured so far.
+    pub fn dual_stack(mut self, ipv6: Ipv6Addr) -> Self {
+        let listener_port = self.listener_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS).port();
+        let discovery_port = self.discovery_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS).port();
+        self.discovery_secondary_addr = Some((ipv6, discovery_port).into());
+        self.add_listener_addr((ipv6, listener_port).into())
+    }
+
     /// Sets the port of the address the discovery network will listen on.
     ///
     /// By default, this is [`DEFAULT_DISCOVERY_PORT`](reth_discv4::DEFAULT_DISCOVERY_PORT)
@@ -554,6 +611,20 @@ impl<N: NetworkPrimitives> NetworkConfigBuilder<N> {
         self
     }
 
+    /// Sets the caps on the bytes per second uploaded in responses and transaction broadcasts.
+    pub const fn upload_limits(mut self, upload_limits: UploadLimits) -> Self {
+        self.upload_limits = upload_limits;
+        self
+    }
+
+    /// Sets whether eth/70 is advertised in addition to the eth versions of the hello message.
+    ///
+    /// eth/70 is not advertised by default, because its specification is not final yet.
+    pub const fn eth70(mut self, enabled: bool) -> Self {
+        self.eth70 = enabled;
+        self
+    }
+
     /// Sets the required block hashes for peer filtering.
     pub fn required_block_hashes(mut self, hashes: Vec<B256>) -> Self {
         self.required_block_hashes = hashes;
@@ -615,7 +686,9 @@ impl<N: NetworkPrimitives> NetworkConfigBuilder<N> {
             mut discovery_v5_builder,
             boot_nodes,
             discovery_addr,
+            discovery_secondary_addr,
             listener_addr,
+            additional_listener_addrs,
             peers_config,
             sessions_config,
             network_mode,
@@ -624,6 +697,8 @@ impl<N: NetworkPrimitives> NetworkConfigBuilder<N> {
             extra_protocols,
             head,
             tx_gossip_disabled,
+            upload_limits,
+            eth70,
             block_import,
             transactions_manager_config,
             nat,
@@ -640,20 +715,36 @@ impl<N: NetworkPrimitives> NetworkConfigBuilder<N> {
             total_difficulty: chain_spec.genesis().difficulty,
         });
 
+        let listener_addr = listener_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS);
+        // the listener of the other IP version, if listening dual-stack
+        let secondary_listener_addr = additional_listener_addrs
+            .iter()
+            .copied()
+            .find(|addr| addr.is_ipv4() != listener_addr.is_ipv4());
+
         discovery_v5_builder = discovery_v5_builder.map(|mut builder| {
             if let Some(network_stack_id) = NetworkStackId::id(&chain_spec) {
                 let fork_id = chain_spec.fork_id(&head);
                 builder = builder.fork(network_stack_id, fork_id)
             }
+            if let Some(addr) = secondary_listener_addr {
+                builder = builder.secondary_tcp_socket(addr)
+            }
 
             builder
         });
 
-        let listener_addr = listener_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS);
-
         let mut hello_message =
             hello_message.unwrap_or_else(|| HelloMessage::builder(peer_id).build());
         hello_message.port = listener_addr.port();
+        if eth70 &&
+            !hello_message
+                .protocols
+                .iter()
+                .any(|p| p.cap.name == "eth" && p.cap.version == EthVersion::Eth70 as usize)
+        {
+            hello_message.protocols.insert(0, EthVersion::Eth70.into());
+        }
 
         // set the status
         let mut status = UnifiedStatus::spec_builder(&chain_spec, &head);
@@ -685,7 +776,9 @@ impl<N: NetworkPrimitives> NetworkConfigBuilder<N> {
             discovery_v4_config: discovery_v4_builder.map(|builder| builder.build()),
             discovery_v5_config: discovery_v5_builder.map(|builder| builder.build()),
             discovery_v4_addr: discovery_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS),
+            discovery_v4_secondary_addr: discovery_secondary_addr,
             listener_addr,
+            additional_listener_addrs,
             peers_config: peers_config.unwrap_or_default(),
             sessions_config: sessions_config.unwrap_or_default(),
             chain_id,
@@ -697,6 +790,7 @@ impl<N: NetworkPrimitives> NetworkConfigBuilder<N> {
             extra_protocols,
             fork_filter,
             tx_gossip_disabled,
+            upload_limits,
             transactions_manager_config,
             nat,
             handshake,
@@ -740,6 +834,7 @@ mod tests {
     };
     use reth_discv5::build_local_enr;
     use reth_dns_discovery::tree::LinkEntry;
+    use reth_eth_wire::protocol::Protocol;
     use reth_storage_api::noop::NoopProvider;
     use std::{net::Ipv4Addr, sync::Arc};
 
@@ -760,6 +855,19 @@ mod tests {
         assert_eq!(bootstrap_nodes.len(), 1);
     }
 
+    #[test]
+    fn test_network_eth70_opt_in() {
+        let is_eth70 =
+            |p: &Protocol| p.cap.name == "eth" && p.cap.version == EthVersion::Eth70 as usize;
+
+        let config = builder().build(NoopProvider::default());
+        assert!(!config.hello_message.protocols.iter().any(is_eth70));
+
+        let config = builder().eth70(true).build(NoopProvider::default());
+        assert!(is_eth70(&config.hello_message.protocols[0]));
+        assert_eq!(config.hello_message.protocols.iter().filter(|p| is_eth70(p)).count(), 1);
+    }
+
     #[test]
     fn test_network_fork_filter_default() {
         let mut chain_spec = Arc::clone(&MAINNET);
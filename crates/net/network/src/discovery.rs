@@ -12,7 +12,11 @@ use reth_dns_discovery::{
     DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService, DnsNodeRecordUpdate, DnsResolver,
 };
 use reth_ethereum_forks::{EnrForkIdEntry, ForkId};
-use reth_network_api::{DiscoveredEvent, DiscoveryEvent};
+use reth_net_nat::{
+    MappedAddr, NatResolver, PortMappingConfig, PortMappingHandle, PortMappingProtocol,
+    PortMappingService,
+};
+use reth_network_api::{BanTarget, DiscoveredEvent, DiscoveryEvent};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_network_types::PeerAddr;
 use secp256k1::SecretKey;
@@ -22,10 +26,11 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{ready, Context, Poll},
+    time::Instant,
 };
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_stream::{wrappers::ReceiverStream, Stream};
-use tracing::trace;
+use tracing::{debug, trace};
 
 /// Default max capacity for cache of discovered peers.
 ///
@@ -64,6 +69,8 @@ pub struct Discovery {
     queued_events: VecDeque<DiscoveryEvent>,
     /// List of listeners subscribed to discovery events.
     discovery_listeners: Vec<mpsc::UnboundedSender<DiscoveryEvent>>,
+    /// Port mappings on the NAT gateway, if enabled.
+    port_mapping: Option<NatPortMapping>,
 }
 
 impl Discovery {
@@ -71,9 +78,16 @@ impl Discovery {
     ///
     /// This will spawn the [`reth_discv4::Discv4Service`] onto a new task and establish a listener
     /// channel to receive all discovered nodes.
+    ///
+    /// If a secondary discovery v4 address of the other IP version is given, discovery v4 runs
+    /// dual-stack and advertises the secondary `RLPx` address for that IP version, falling back to
+    /// the port of `tcp_addr`.
+    #[expect(clippy::too_many_arguments)]
     pub async fn new(
         tcp_addr: SocketAddr,
+        secondary_tcp_addr: Option<SocketAddr>,
         discovery_v4_addr: SocketAddr,
+        discovery_v4_secondary_addr: Option<SocketAddr>,
         sk: SecretKey,
         discv4_config: Option<Discv4Config>,
         discv5_config: Option<reth_discv5::Config>, // contains discv5 listen address
@@ -85,12 +99,24 @@ impl Discovery {
 
         let discv4_future = async {
             let Some(disc_config) = discv4_config else { return Ok((None, None, None)) };
-            let (discv4, mut discv4_service) =
-                Discv4::bind(discovery_v4_addr, local_enr, sk, disc_config).await.map_err(
-                    |err| {
-                        NetworkError::from_io_error(err, ServiceKind::Discovery(discovery_v4_addr))
-                    },
-                )?;
+            let bound = if let Some(secondary_addr) = discovery_v4_secondary_addr {
+                let secondary_tcp_port =
+                    secondary_tcp_addr.map_or(tcp_addr.port(), |addr| addr.port());
+                Discv4::bind_dual_stack(
+                    discovery_v4_addr,
+                    secondary_addr,
+                    secondary_tcp_port,
+                    local_enr,
+                    sk,
+                    disc_config,
+                )
+                .await
+            } else {
+                Discv4::bind(discovery_v4_addr, local_enr, sk, disc_config).await
+            };
+            let (discv4, mut discv4_service) = bound.map_err(|err| {
+                NetworkError::from_io_error(err, ServiceKind::Discovery(discovery_v4_addr))
+            })?;
             let discv4_updates = discv4_service.update_stream();
             // spawn the service
             let discv4_service = discv4_service.spawn();
@@ -134,9 +160,83 @@ impl Discovery {
             _dns_disc_service,
             _dns_discovery,
             dns_discovery_updates,
+            port_mapping: None,
         })
     }
 
+    /// Maps the `RLPx` port and the discovery ports on the NAT gateway, if the resolver supports
+    /// port mappings.
+    ///
+    /// The mapped external address is advertised via discv4 and discv5 once the gateway reports
+    /// it.
+    pub(crate) fn spawn_port_mapping(&mut self, resolver: NatResolver, tcp_port: u16) {
+        let Some(config) = PortMappingConfig::new(resolver) else { return };
+
+        let discv4_port = self.discv4.as_ref().map(|discv4| discv4.local_addr().port());
+        let discv5_port = self.discv5.as_ref().and_then(|discv5| discv5.local_udp_port());
+        let config = discv4_port
+            .into_iter()
+            .chain(discv5_port)
+            .fold(config.with_port(PortMappingProtocol::Tcp, tcp_port), |config, port| {
+                config.with_port(PortMappingProtocol::Udp, port)
+            });
+
+        let (service, handle) = PortMappingService::new_pair(config);
+        service.spawn();
+        self.port_mapping = Some(NatPortMapping { handle, tcp_port, discv4_port, discv5_port });
+    }
+
+    /// Removes the port mappings from the NAT gateway.
+    pub(crate) fn shutdown_port_mapping(&mut self) {
+        if let Some(port_mapping) = self.port_mapping.as_mut() {
+            port_mapping.handle.shutdown();
+        }
+    }
+
+    /// Advertises the external address mapped on the NAT gateway.
+    fn on_mapped_addr(&mut self, addr: MappedAddr) {
+        let Some(NatPortMapping { tcp_port, discv4_port, discv5_port, .. }) = self.port_mapping
+        else {
+            return
+        };
+        let external_tcp_port = addr.external_port(PortMappingProtocol::Tcp, tcp_port);
+        debug!(target: "net::discovery",
+            ip=%addr.ip,
+            ?external_tcp_port,
+            "advertising mapped external address"
+        );
+
+        self.local_enr.address = addr.ip;
+        if let Some(port) = external_tcp_port {
+            self.local_enr.tcp_port = port;
+        }
+
+        if let Some(discv4) = &self.discv4 {
+            discv4.set_external_ip_addr(addr.ip);
+            if let Some(port) = external_tcp_port {
+                discv4.set_tcp_port(port);
+            }
+        }
+        if let (Some(discv5), Some(port)) = (&self.discv5, discv5_port) {
+            let udp_port = addr.external_port(PortMappingProtocol::Udp, port).unwrap_or(port);
+            discv5.set_external_addr(addr.ip, udp_port, external_tcp_port);
+        }
+        if let Some(port) = discv4_port &&
+            let Some(external_port) = addr.external_port(PortMappingProtocol::Udp, port) &&
+            external_port != port
+        {
+            debug!(target: "net::discovery",
+                port,
+                external_port,
+                "advertising mapped external discv4 port"
+            );
+            self.local_enr.udp_port = external_port;
+            if let Some(discv4) = &self.discv4 {
+                discv4.set_udp_port(external_port);
+            }
+        }
+    }
+
     /// Registers a listener for receiving [`DiscoveryEvent`] updates.
     pub(crate) fn add_listener(&mut self, tx: mpsc::UnboundedSender<DiscoveryEvent>) {
         self.discovery_listeners.push(tx);
@@ -177,6 +277,21 @@ impl Discovery {
         }
     }
 
+    /// Bans the peer, [`IpAddr`] or IP range in the discv4 service indefinitely or until the
+    /// given timestamp.
+    pub(crate) fn ban_target(&self, target: BanTarget, until: Option<Instant>) {
+        if let Some(discv4) = &self.discv4 {
+            discv4.ban_target(target, until)
+        }
+    }
+
+    /// Lifts the ban of the peer, [`IpAddr`] or IP range in the discv4 service.
+    pub(crate) fn unban(&self, target: BanTarget) {
+        if let Some(discv4) = &self.discv4 {
+            discv4.unban(target)
+        }
+    }
+
     /// Returns a shared reference to the discv4.
     pub fn discv4(&self) -> Option<Discv4> {
         self.discv4.clone()
@@ -274,6 +389,13 @@ impl Discovery {
                 }
             }
 
+            // drain the external address updates of the port mapping
+            while let Some(Poll::Ready(Some(addr))) =
+                self.port_mapping.as_mut().map(|mapping| mapping.handle.poll_mapped_addr(cx))
+            {
+                self.on_mapped_addr(addr);
+            }
+
             // drain the dns update stream
             while let Some(Poll::Ready(Some(update))) =
                 self.dns_discovery_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
@@ -295,6 +417,19 @@ impl Discovery {
     }
 }
 
+/// Port mappings on the NAT gateway and the local ports they map.
+#[derive(Debug)]
+struct NatPortMapping {
+    /// Handle to the spawned [`PortMappingService`].
+    handle: PortMappingHandle,
+    /// The local `RLPx` port.
+    tcp_port: u16,
+    /// The local discv4 port.
+    discv4_port: Option<u16>,
+    /// The local discv5 port.
+    discv5_port: Option<u16>,
+}
+
 impl Stream for Discovery {
     type Item = DiscoveryEvent;
 
@@ -330,6 +465,7 @@ impl Discovery {
             dns_discovery_updates: None,
             _dns_disc_service: None,
             discovery_listeners: Default::default(),
+            port_mapping: None,
         }
     }
 }
@@ -346,7 +482,9 @@ mod tests {
         let discovery_addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0));
         let _discovery = Discovery::new(
             discovery_addr,
+            None,
             discovery_addr,
+            None,
             secret_key,
             Default::default(),
             None,
@@ -376,7 +514,9 @@ mod tests {
 
         Discovery::new(
             discv4_addr,
+            None,
             discv4_addr,
+            None,
             secret_key,
             Some(discv4_config),
             Some(discv5_config),
@@ -1,8 +1,8 @@
 //! Blocks/Headers management for the p2p network.
 
 use crate::{
-    budget::DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS, metered_poll_nested_stream_with_budget,
-    metrics::EthRequestHandlerMetrics,
+    bandwidth::UploadLimiter, budget::DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS,
+    metered_poll_nested_stream_with_budget, metrics::EthRequestHandlerMetrics,
 };
 use alloy_consensus::{BlockHeader, ReceiptWithBloom};
 use alloy_eips::BlockHashOrNumber;
@@ -10,9 +10,10 @@ use alloy_rlp::Encodable;
 use futures::StreamExt;
 use reth_eth_wire::{
     BlockBodies, BlockHeaders, EthNetworkPrimitives, GetBlockBodies, GetBlockHeaders, GetNodeData,
-    GetReceipts, HeadersDirection, NetworkPrimitives, NodeData, Receipts, Receipts69,
+    GetReceipts, GetReceipts70, HeadersDirection, NetworkPrimitives, NodeData, Receipts,
+    Receipts69, Receipts70,
 };
-use reth_network_api::test_utils::PeersHandle;
+use reth_network_api::PeersHandle;
 use reth_network_p2p::error::RequestResult;
 use reth_network_peers::PeerId;
 use reth_primitives_traits::Block;
@@ -63,6 +64,8 @@ pub struct EthRequestHandler<C, N: NetworkPrimitives = EthNetworkPrimitives> {
     incoming_requests: ReceiverStream<IncomingEthRequest<N>>,
     /// Metrics for the eth request handler.
     metrics: EthRequestHandlerMetrics,
+    /// Caps the size of responses to the configured upload limits.
+    upload_limiter: UploadLimiter,
 }
 
 // === impl EthRequestHandler ===
@@ -74,8 +77,36 @@ impl<C, N: NetworkPrimitives> EthRequestHandler<C, N> {
             peers,
             incoming_requests: ReceiverStream::new(incoming),
             metrics: Default::default(),
+            upload_limiter: Default::default(),
         }
     }
+
+    /// Sets the limiter that caps the size of responses to the configured upload limits.
+    pub fn with_upload_limiter(mut self, upload_limiter: UploadLimiter) -> Self {
+        self.upload_limiter = upload_limiter;
+        self
+    }
+
+    /// Returns the maximum size of a response to the peer: the soft response limit, capped by the
+    /// upload budget if uploads are limited.
+    fn response_limit(&self, peer_id: &PeerId) -> usize {
+        self.upload_limiter
+            .available(peer_id)
+            .map_or(SOFT_RESPONSE_LIMIT, |available| available.min(SOFT_RESPONSE_LIMIT))
+    }
+
+    /// Deducts the size of the response from the upload budget and sends it.
+    fn send_response<T: Encodable>(
+        &self,
+        peer_id: &PeerId,
+        response: oneshot::Sender<RequestResult<T>>,
+        message: T,
+    ) {
+        if self.upload_limiter.is_enabled() {
+            self.upload_limiter.consume(peer_id, message.length());
+        }
+        let _ = response.send(Ok(message));
+    }
 }
 
 impl<C, N> EthRequestHandler<C, N>
@@ -84,10 +115,20 @@ where
     C: BlockReader,
 {
     /// Returns the list of requested headers
-    fn get_headers_response(&self, request: GetBlockHeaders) -> Vec<C::Header> {
+    ///
+    /// The response is at most `response_limit` bytes, plus the last header that exceeds it.
+    fn get_headers_response(
+        &self,
+        request: GetBlockHeaders,
+        response_limit: usize,
+    ) -> Vec<C::Header> {
         let GetBlockHeaders { start_block, limit, skip, direction } = request;
 
         let mut headers = Vec::new();
+        if response_limit == 0 {
+            // the upload budget is exhausted
+            return headers
+        }
 
         let mut block: BlockHashOrNumber = match start_block {
             BlockHashOrNumber::Hash(start) => start.into(),
@@ -110,7 +151,7 @@ where
                 total_bytes += header.length();
                 headers.push(header);
 
-                if headers.len() >= MAX_HEADERS_SERVE || total_bytes > SOFT_RESPONSE_LIMIT {
+                if headers.len() >= MAX_HEADERS_SERVE || total_bytes > response_limit {
                     break
                 }
 
@@ -149,18 +190,18 @@ where
 
     fn on_headers_request(
         &self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetBlockHeaders,
         response: oneshot::Sender<RequestResult<BlockHeaders<C::Header>>>,
     ) {
         self.metrics.eth_headers_requests_received_total.increment(1);
-        let headers = self.get_headers_response(request);
-        let _ = response.send(Ok(BlockHeaders(headers)));
+        let headers = self.get_headers_response(request, self.response_limit(&peer_id));
+        self.send_response(&peer_id, response, BlockHeaders(headers));
     }
 
     fn on_bodies_request(
         &self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetBlockBodies,
         response: oneshot::Sender<RequestResult<BlockBodies<<C::Block as Block>::Body>>>,
     ) {
@@ -168,14 +209,16 @@ where
         let mut bodies = Vec::new();
 
         let mut total_bytes = 0;
+        let response_limit = self.response_limit(&peer_id);
 
-        for hash in request.0 {
+        // nothing is served if the upload budget is exhausted
+        for hash in request.0.into_iter().take_while(|_| response_limit > 0) {
             if let Some(block) = self.client.block_by_hash(hash).unwrap_or_default() {
                 let body = block.into_body();
                 total_bytes += body.length();
                 bodies.push(body);
 
-                if bodies.len() >= MAX_BODIES_SERVE || total_bytes > SOFT_RESPONSE_LIMIT {
+                if bodies.len() >= MAX_BODIES_SERVE || total_bytes > response_limit {
                     break
                 }
             } else {
@@ -183,42 +226,108 @@ where
             }
         }
 
-        let _ = response.send(Ok(BlockBodies(bodies)));
+        self.send_response(&peer_id, response, BlockBodies(bodies));
     }
 
     fn on_receipts_request(
         &self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetReceipts,
         response: oneshot::Sender<RequestResult<Receipts<C::Receipt>>>,
     ) {
         self.metrics.eth_receipts_requests_received_total.increment(1);
 
-        let receipts = self.get_receipts_response(request, |receipts_by_block| {
-            receipts_by_block.into_iter().map(ReceiptWithBloom::from).collect::<Vec<_>>()
-        });
+        let receipts = self.get_receipts_response(
+            request,
+            self.response_limit(&peer_id),
+            |receipts_by_block| {
+                receipts_by_block.into_iter().map(ReceiptWithBloom::from).collect::<Vec<_>>()
+            },
+        );
 
-        let _ = response.send(Ok(Receipts(receipts)));
+        self.send_response(&peer_id, response, Receipts(receipts));
     }
 
     fn on_receipts69_request(
         &self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetReceipts,
         response: oneshot::Sender<RequestResult<Receipts69<C::Receipt>>>,
     ) {
         self.metrics.eth_receipts_requests_received_total.increment(1);
 
-        let receipts = self.get_receipts_response(request, |receipts_by_block| {
-            // skip bloom filter for eth69
-            receipts_by_block
-        });
+        let receipts = self.get_receipts_response(
+            request,
+            self.response_limit(&peer_id),
+            |receipts_by_block| {
+                // skip bloom filter for eth69
+                receipts_by_block
+            },
+        );
 
-        let _ = response.send(Ok(Receipts69(receipts)));
+        self.send_response(&peer_id, response, Receipts69(receipts));
+    }
+
+    /// Serves an `eth/70` receipts request.
+    ///
+    /// Unlike earlier versions, the response can stop in the middle of a block if the soft
+    /// response limit is reached, in which case the block is marked as incomplete and the peer
+    /// requests the remaining receipts starting at the index of the first missing receipt.
+    fn on_receipts70_request(
+        &self,
+        peer_id: PeerId,
+        request: GetReceipts70,
+        response: oneshot::Sender<RequestResult<Receipts70<C::Receipt>>>,
+    ) {
+        self.metrics.eth_receipts_requests_received_total.increment(1);
+
+        let GetReceipts70 { first_block_receipt_index, block_hashes } = request;
+        let mut skip = first_block_receipt_index as usize;
+        let mut receipts = Vec::new();
+        let mut last_block_incomplete = false;
+        let mut total_bytes = 0;
+        let response_limit = self.response_limit(&peer_id);
+
+        // nothing is served if the upload budget is exhausted
+        'blocks: for hash in block_hashes.into_iter().take_while(|_| response_limit > 0) {
+            let Some(receipts_by_block) =
+                self.client.receipts_by_block(BlockHashOrNumber::Hash(hash)).unwrap_or_default()
+            else {
+                break
+            };
+
+            // only the first block can start at an index
+            let mut block_receipts = Vec::new();
+            for receipt in receipts_by_block.into_iter().skip(std::mem::take(&mut skip)) {
+                let receipt_bytes = receipt.length();
+                if total_bytes > 0 && total_bytes + receipt_bytes > response_limit {
+                    // serve the receipts that fit, the peer requests the rest
+                    if !block_receipts.is_empty() {
+                        receipts.push(block_receipts);
+                        last_block_incomplete = true;
+                    }
+                    break 'blocks
+                }
+                total_bytes += receipt_bytes;
+                block_receipts.push(receipt);
+            }
+            receipts.push(block_receipts);
+
+            if receipts.len() >= MAX_RECEIPTS_SERVE {
+                break
+            }
+        }
+
+        self.send_response(&peer_id, response, Receipts70 { last_block_incomplete, receipts });
     }
 
     #[inline]
-    fn get_receipts_response<T, F>(&self, request: GetReceipts, transform_fn: F) -> Vec<Vec<T>>
+    fn get_receipts_response<T, F>(
+        &self,
+        request: GetReceipts,
+        response_limit: usize,
+        transform_fn: F,
+    ) -> Vec<Vec<T>>
     where
         F: Fn(Vec<C::Receipt>) -> Vec<T>,
         T: Encodable,
@@ -226,7 +335,8 @@ where
         let mut receipts = Vec::new();
         let mut total_bytes = 0;
 
-        for hash in request.0 {
+        // nothing is served if the upload budget is exhausted
+        for hash in request.0.into_iter().take_while(|_| response_limit > 0) {
             if let Some(receipts_by_block) =
                 self.client.receipts_by_block(BlockHashOrNumber::Hash(hash)).unwrap_or_default()
             {
@@ -234,7 +344,7 @@ where
                 total_bytes += transformed_receipts.length();
                 receipts.push(transformed_receipts);
 
-                if receipts.len() >= MAX_RECEIPTS_SERVE || total_bytes > SOFT_RESPONSE_LIMIT {
+                if receipts.len() >= MAX_RECEIPTS_SERVE || total_bytes > response_limit {
                     break
                 }
             } else {
@@ -285,6 +395,9 @@ where
                     IncomingEthRequest::GetReceipts69 { peer_id, request, response } => {
                         this.on_receipts69_request(peer_id, request, response)
                     }
+                    IncomingEthRequest::GetReceipts70 { peer_id, request, response } => {
+                        this.on_receipts70_request(peer_id, request, response)
+                    }
                 }
             },
         );
@@ -359,4 +472,15 @@ pub enum IncomingEthRequest<N: NetworkPrimitives = EthNetworkPrimitives> {
         /// The channel sender for the response containing Receipts69.
         response: oneshot::Sender<RequestResult<Receipts69<N::Receipt>>>,
     },
+    /// Request Receipts from the peer, starting at a receipt index of the first block.
+    ///
+    /// The response should be sent through the channel.
+    GetReceipts70 {
+        /// The ID of the peer to request receipts from.
+        peer_id: PeerId,
+        /// The specific receipts requested.
+        request: GetReceipts70,
+        /// The channel sender for the response containing Receipts70.
+        response: oneshot::Sender<RequestResult<Receipts70<N::Receipt>>>,
+    },
 }
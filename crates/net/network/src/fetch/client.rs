@@ -4,13 +4,14 @@ use crate::{fetch::DownloadRequest, flattened_response::FlattenedResponse};
 use alloy_primitives::B256;
 use futures::{future, future::Either};
 use reth_eth_wire::{EthNetworkPrimitives, NetworkPrimitives};
-use reth_network_api::test_utils::PeersHandle;
+use reth_network_api::PeersHandle;
 use reth_network_p2p::{
     bodies::client::{BodiesClient, BodiesFut},
     download::DownloadClient,
     error::{PeerRequestResult, RequestError},
     headers::client::{HeadersClient, HeadersRequest},
     priority::Priority,
+    receipts::client::{ReceiptsClient, ReceiptsFut},
     BlockClient,
 };
 use reth_network_peers::PeerId;
@@ -102,6 +103,25 @@ impl<N: NetworkPrimitives> BodiesClient for FetchClient<N> {
     }
 }
 
+impl<N: NetworkPrimitives> ReceiptsClient for FetchClient<N> {
+    type Receipt = N::Receipt;
+    type Output = ReceiptsFut<N::Receipt>;
+
+    /// Sends a receipts request to an available peer.
+    fn get_receipts_with_priority(&self, request: Vec<B256>, priority: Priority) -> Self::Output {
+        let (response, rx) = oneshot::channel();
+        if self
+            .request_tx
+            .send(DownloadRequest::GetReceipts { request, response, priority })
+            .is_ok()
+        {
+            Box::pin(FlattenedResponse::from(rx))
+        } else {
+            Box::pin(future::err(RequestError::ChannelClosed))
+        }
+    }
+}
+
 impl<N: NetworkPrimitives> BlockClient for FetchClient<N> {
     type Block = N::Block;
 }
@@ -8,16 +8,17 @@ use crate::{message::BlockRequest, session::BlockRangeInfo};
 use alloy_primitives::B256;
 use futures::StreamExt;
 use reth_eth_wire::{
-    Capabilities, EthNetworkPrimitives, GetBlockBodies, GetBlockHeaders, NetworkPrimitives,
+    Capabilities, EthNetworkPrimitives, GetBlockBodies, GetBlockHeaders, GetReceipts70,
+    NetworkPrimitives, Receipts70,
 };
-use reth_network_api::test_utils::PeersHandle;
+use reth_network_api::PeersHandle;
 use reth_network_p2p::{
     error::{EthResponseValidator, PeerRequestResult, RequestError, RequestResult},
     headers::client::HeadersRequest,
     priority::Priority,
 };
 use reth_network_peers::PeerId;
-use reth_network_types::ReputationChangeKind;
+use reth_network_types::{PeerScore, ReputationChangeKind, ServedDataKind};
 use std::{
     collections::{HashMap, VecDeque},
     ops::RangeInclusive,
@@ -32,6 +33,7 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 
 type InflightHeadersRequest<H> = Request<HeadersRequest, PeerRequestResult<Vec<H>>>;
 type InflightBodiesRequest<B> = Request<(), PeerRequestResult<Vec<B>>>;
+type InflightReceiptsRequest<R> = Request<ReceiptsRequest<R>, PeerRequestResult<Vec<Vec<R>>>>;
 
 /// Manages data fetching operations.
 ///
@@ -45,6 +47,8 @@ pub struct StateFetcher<N: NetworkPrimitives = EthNetworkPrimitives> {
     inflight_headers_requests: HashMap<PeerId, InflightHeadersRequest<N::BlockHeader>>,
     /// Currently active [`GetBlockBodies`] requests
     inflight_bodies_requests: HashMap<PeerId, InflightBodiesRequest<N::BlockBody>>,
+    /// Currently active receipts requests
+    inflight_receipts_requests: HashMap<PeerId, InflightReceiptsRequest<N::Receipt>>,
     /// The list of _available_ peers for requests.
     peers: HashMap<PeerId, Peer>,
     /// The handle to the peers manager
@@ -67,6 +71,7 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
         Self {
             inflight_headers_requests: Default::default(),
             inflight_bodies_requests: Default::default(),
+            inflight_receipts_requests: Default::default(),
             peers: Default::default(),
             peers_handle,
             num_active_peers,
@@ -84,6 +89,7 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
         best_number: u64,
         capabilities: Arc<Capabilities>,
         timeout: Arc<AtomicU64>,
+        score: Arc<PeerScore>,
         range_info: Option<BlockRangeInfo>,
     ) {
         self.peers.insert(
@@ -94,6 +100,7 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
                 best_number,
                 capabilities,
                 timeout,
+                score,
                 last_response_likely_bad: false,
                 range_info,
             },
@@ -114,6 +121,9 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
         if let Some(req) = self.inflight_bodies_requests.remove(peer) {
             let _ = req.response.send(Err(RequestError::ConnectionDropped));
         }
+        if let Some(req) = self.inflight_receipts_requests.remove(peer) {
+            let _ = req.response.send(Err(RequestError::ConnectionDropped));
+        }
     }
 
     /// Updates the block information for the peer.
@@ -138,9 +148,13 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
     }
 
     /// Returns the _next_ idle peer that's ready to accept a request,
-    /// prioritizing those with the lowest timeout/latency and those that recently responded with
-    /// adequate data.
+    /// prioritizing those that serve the requested data the fastest and those that recently
+    /// responded with adequate data.
     fn next_best_peer(&self) -> Option<PeerId> {
+        let kind = self
+            .queued_requests
+            .front()
+            .map_or(ServedDataKind::Headers, DownloadRequest::served_data_kind);
         let mut idle = self.peers.iter().filter(|(_, peer)| peer.state.is_idle());
 
         let mut best_peer = idle.next()?;
@@ -152,8 +166,8 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
                 continue
             }
 
-            // replace best peer if this peer has better rtt
-            if maybe_better.1.timeout() < best_peer.1.timeout() &&
+            // replace best peer if this peer is expected to serve the data faster
+            if maybe_better.1.expected_latency(kind) < best_peer.1.expected_latency(kind) &&
                 !maybe_better.1.last_response_likely_bad
             {
                 best_peer = maybe_better;
@@ -245,6 +259,12 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
                 self.inflight_bodies_requests.insert(peer_id, inflight);
                 BlockRequest::GetBlockBodies(GetBlockBodies(request))
             }
+            DownloadRequest::GetReceipts { request, response, .. } => {
+                let inflight = Request { request: ReceiptsRequest::new(request), response };
+                let request = inflight.request.next_request();
+                self.inflight_receipts_requests.insert(peer_id, inflight);
+                BlockRequest::GetReceipts(request)
+            }
         }
     }
 
@@ -319,6 +339,57 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
         None
     }
 
+    /// Called on a receipts response from a peer.
+    ///
+    /// Responses of all `eth` versions are passed in the `eth/70` form. If the receipts of the last
+    /// block are incomplete, the remaining receipts are requested from the same peer before the
+    /// response is delegated.
+    pub(crate) fn on_receipts_response(
+        &mut self,
+        peer_id: PeerId,
+        res: RequestResult<Receipts70<N::Receipt>>,
+    ) -> Option<BlockResponseOutcome> {
+        let mut is_likely_bad_response = true;
+        let mut is_bad_message = false;
+
+        if let Some(mut inflight) = self.inflight_receipts_requests.remove(&peer_id) {
+            match res.and_then(|receipts| inflight.request.on_response(receipts)) {
+                Ok(true)
+                    if self.peers.get(&peer_id).is_some_and(|peer| !peer.state.is_closing()) =>
+                {
+                    // request the rest of the last block from the same peer
+                    let request = inflight.request.next_request();
+                    self.inflight_receipts_requests.insert(peer_id, inflight);
+                    return Some(BlockResponseOutcome::Request(
+                        peer_id,
+                        BlockRequest::GetReceipts(request),
+                    ))
+                }
+                Ok(_) => {
+                    let receipts = inflight.request.into_complete_receipts();
+                    is_likely_bad_response = receipts.is_empty();
+                    let _ = inflight.response.send(Ok((peer_id, receipts).into()));
+                }
+                Err(err) => {
+                    is_bad_message = err == RequestError::BadResponse;
+                    let _ = inflight.response.send(Err(err));
+                }
+            }
+        }
+
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            // update the peer's response state
+            peer.last_response_likely_bad = is_likely_bad_response;
+
+            if peer.state.on_request_finished() && !is_likely_bad_response {
+                return self.followup_request(peer_id)
+            }
+        }
+
+        is_bad_message
+            .then_some(BlockResponseOutcome::BadResponse(peer_id, ReputationChangeKind::BadMessage))
+    }
+
     /// Returns a new [`FetchClient`] that can send requests to this type.
     pub(crate) fn client(&self) -> FetchClient<N> {
         FetchClient {
@@ -350,6 +421,8 @@ struct Peer {
     capabilities: Arc<Capabilities>,
     /// Tracks the current timeout value we use for the peer.
     timeout: Arc<AtomicU64>,
+    /// Tracks the quality of the data the peer served.
+    score: Arc<PeerScore>,
     /// Tracks whether the peer has recently responded with a likely bad response.
     ///
     /// This is used to de-rank the peer if there are other peers available.
@@ -366,6 +439,16 @@ impl Peer {
     fn timeout(&self) -> u64 {
         self.timeout.load(Ordering::Relaxed)
     }
+
+    /// Returns the expected time in milliseconds until the peer serves data of the given kind.
+    ///
+    /// Falls back to the request timeout, which is derived from the round trip time, if the peer
+    /// didn't serve data of this kind yet.
+    fn expected_latency(&self, kind: ServedDataKind) -> u64 {
+        self.score
+            .expected_latency(kind)
+            .map_or_else(|| self.timeout(), |latency| latency.as_millis() as u64)
+    }
 }
 
 /// Tracks the state of an individual peer
@@ -377,6 +460,8 @@ enum PeerState {
     GetBlockHeaders,
     /// Peer is handling a `GetBlockBodies` request.
     GetBlockBodies,
+    /// Peer is handling a `GetReceipts` request.
+    GetReceipts,
     /// Peer session is about to close
     Closing,
 }
@@ -389,6 +474,11 @@ impl PeerState {
         matches!(self, Self::Idle)
     }
 
+    /// Returns true if the peer session is about to close.
+    const fn is_closing(&self) -> bool {
+        matches!(self, Self::Closing)
+    }
+
     /// Resets the state on a received response.
     ///
     /// If the state was already marked as `Closing` do nothing.
@@ -413,6 +503,76 @@ struct Request<Req, Resp> {
     response: oneshot::Sender<Resp>,
 }
 
+/// Tracks the receipts of a receipts request, which can be served across several responses with
+/// `eth/70`.
+#[derive(Debug)]
+struct ReceiptsRequest<R> {
+    /// The hashes of the requested blocks.
+    block_hashes: Vec<B256>,
+    /// The receipts received so far, one list per block.
+    receipts: Vec<Vec<R>>,
+    /// Whether the receipts of the last block are incomplete.
+    last_block_incomplete: bool,
+}
+
+impl<R> ReceiptsRequest<R> {
+    const fn new(block_hashes: Vec<B256>) -> Self {
+        Self { block_hashes, receipts: Vec::new(), last_block_incomplete: false }
+    }
+
+    /// Returns the request for the missing receipts.
+    ///
+    /// This is either the initial request or the rest of the incomplete last block.
+    fn next_request(&self) -> GetReceipts70 {
+        match self.receipts.last() {
+            Some(receipts) if self.last_block_incomplete => GetReceipts70 {
+                first_block_receipt_index: receipts.len() as u64,
+                block_hashes: vec![self.block_hashes[self.receipts.len() - 1]],
+            },
+            _ => GetReceipts70 {
+                first_block_receipt_index: 0,
+                block_hashes: self.block_hashes.clone(),
+            },
+        }
+    }
+
+    /// Adds the receipts of a response to the request sent by [`Self::next_request`].
+    ///
+    /// Returns `true` if the receipts of the last block are still incomplete, or an error if the
+    /// response doesn't match the request.
+    fn on_response(&mut self, response: Receipts70<R>) -> RequestResult<bool> {
+        let Receipts70 { last_block_incomplete, receipts } = response;
+
+        if self.last_block_incomplete {
+            // the response continues the last block and must make progress
+            let mut receipts = receipts.into_iter();
+            let Some(rest) = receipts.next() else { return Err(RequestError::BadResponse) };
+            if receipts.next().is_some() || (rest.is_empty() && last_block_incomplete) {
+                return Err(RequestError::BadResponse)
+            }
+            self.receipts.last_mut().expect("incomplete block").extend(rest);
+        } else {
+            if receipts.len() > self.block_hashes.len() ||
+                (receipts.is_empty() && last_block_incomplete)
+            {
+                return Err(RequestError::BadResponse)
+            }
+            self.receipts = receipts;
+        }
+
+        self.last_block_incomplete = last_block_incomplete;
+        Ok(last_block_incomplete)
+    }
+
+    /// Returns the receipts of the blocks that were received completely.
+    fn into_complete_receipts(mut self) -> Vec<Vec<R>> {
+        if self.last_block_incomplete {
+            self.receipts.pop();
+        }
+        self.receipts
+    }
+}
+
 /// Requests that can be sent to the Syncer from a [`FetchClient`]
 #[derive(Debug)]
 pub(crate) enum DownloadRequest<N: NetworkPrimitives> {
@@ -430,6 +590,12 @@ pub(crate) enum DownloadRequest<N: NetworkPrimitives> {
         #[allow(dead_code)]
         range_hint: Option<RangeInclusive<u64>>,
     },
+    /// Download the receipts of the requested blocks and send response through channel
+    GetReceipts {
+        request: Vec<B256>,
+        response: oneshot::Sender<PeerRequestResult<Vec<Vec<N::Receipt>>>>,
+        priority: Priority,
+    },
 }
 
 // === impl DownloadRequest ===
@@ -440,15 +606,25 @@ impl<N: NetworkPrimitives> DownloadRequest<N> {
         match self {
             Self::GetBlockHeaders { .. } => PeerState::GetBlockHeaders,
             Self::GetBlockBodies { .. } => PeerState::GetBlockBodies,
+            Self::GetReceipts { .. } => PeerState::GetReceipts,
+        }
+    }
+
+    /// Returns the kind of data this request downloads.
+    const fn served_data_kind(&self) -> ServedDataKind {
+        match self {
+            Self::GetBlockHeaders { .. } => ServedDataKind::Headers,
+            Self::GetBlockBodies { .. } => ServedDataKind::Bodies,
+            Self::GetReceipts { .. } => ServedDataKind::Receipts,
         }
     }
 
     /// Returns the requested priority of this request
     const fn get_priority(&self) -> &Priority {
         match self {
-            Self::GetBlockHeaders { priority, .. } | Self::GetBlockBodies { priority, .. } => {
-                priority
-            }
+            Self::GetBlockHeaders { priority, .. } |
+            Self::GetBlockBodies { priority, .. } |
+            Self::GetReceipts { priority, .. } => priority,
         }
     }
 
@@ -486,7 +662,7 @@ mod tests {
     use crate::{peers::PeersManager, PeersConfig};
     use alloy_consensus::Header;
     use alloy_primitives::B512;
-    use std::future::poll_fn;
+    use std::{future::poll_fn, time::Duration};
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_poll_fetcher() {
@@ -525,6 +701,7 @@ mod tests {
             1,
             Arc::clone(&capabilities),
             Arc::new(AtomicU64::new(1)),
+            Default::default(),
             None,
         );
         fetcher.new_active_peer(
@@ -533,6 +710,7 @@ mod tests {
             2,
             Arc::clone(&capabilities),
             Arc::new(AtomicU64::new(1)),
+            Default::default(),
             None,
         );
 
@@ -568,6 +746,7 @@ mod tests {
             1,
             Arc::clone(&capabilities),
             Arc::new(AtomicU64::new(30)),
+            Default::default(),
             None,
         );
         fetcher.new_active_peer(
@@ -576,6 +755,7 @@ mod tests {
             2,
             Arc::clone(&capabilities),
             Arc::clone(&peer2_timeout),
+            Default::default(),
             None,
         );
         fetcher.new_active_peer(
@@ -584,6 +764,7 @@ mod tests {
             3,
             Arc::clone(&capabilities),
             Arc::new(AtomicU64::new(50)),
+            Default::default(),
             None,
         );
 
@@ -597,6 +778,52 @@ mod tests {
         assert_eq!(fetcher.next_best_peer(), Some(peer2));
     }
 
+    #[tokio::test]
+    async fn test_peer_prioritization_by_score() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher =
+            StateFetcher::<EthNetworkPrimitives>::new(manager.handle(), Default::default());
+        let peer1 = B512::random();
+        let peer2 = B512::random();
+        let peer1_score = Arc::new(PeerScore::default());
+        let peer2_score = Arc::new(PeerScore::default());
+
+        let capabilities = Arc::new(Capabilities::from(vec![]));
+        for (peer, score) in [(peer1, &peer1_score), (peer2, &peer2_score)] {
+            fetcher.new_active_peer(
+                peer,
+                B256::random(),
+                1,
+                Arc::clone(&capabilities),
+                Arc::new(AtomicU64::new(1000)),
+                Arc::clone(score),
+                None,
+            );
+        }
+
+        // peer1 serves headers faster
+        peer1_score.on_response(ServedDataKind::Headers, Duration::from_millis(50), 1024, false);
+        peer2_score.on_response(ServedDataKind::Headers, Duration::from_millis(200), 1024, false);
+        assert_eq!(fetcher.next_best_peer(), Some(peer1));
+
+        // but responds with empty headers
+        for _ in 0..8 {
+            peer1_score.on_response(ServedDataKind::Headers, Duration::from_millis(50), 0, true);
+        }
+        assert_eq!(fetcher.next_best_peer(), Some(peer2));
+
+        // peer2 didn't serve bodies yet, so the faster peer1 is preferred for bodies
+        peer1_score.on_response(ServedDataKind::Bodies, Duration::from_millis(50), 1024, false);
+        let (tx, _rx) = oneshot::channel();
+        fetcher.queued_requests.push_back(DownloadRequest::GetBlockBodies {
+            request: vec![],
+            response: tx,
+            priority: Priority::default(),
+            range_hint: None,
+        });
+        assert_eq!(fetcher.next_best_peer(), Some(peer1));
+    }
+
     #[tokio::test]
     async fn test_on_block_headers_response() {
         let manager = PeersManager::new(PeersConfig::default());
@@ -655,6 +882,7 @@ mod tests {
             Default::default(),
             Arc::new(Capabilities::from(vec![])),
             Default::default(),
+            Default::default(),
             None,
         );
 
@@ -684,4 +912,83 @@ mod tests {
 
         assert!(fetcher.peers[&peer_id].state.is_idle());
     }
+
+    #[tokio::test]
+    async fn test_receipts_response_pagination() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher =
+            StateFetcher::<EthNetworkPrimitives>::new(manager.handle(), Default::default());
+        let peer_id = B512::random();
+        fetcher.new_active_peer(
+            peer_id,
+            Default::default(),
+            Default::default(),
+            Arc::new(Capabilities::from(vec![])),
+            Default::default(),
+            Default::default(),
+            None,
+        );
+
+        let receipt = |cumulative_gas_used| reth_ethereum_primitives::Receipt {
+            cumulative_gas_used,
+            ..Default::default()
+        };
+        let block_hashes = vec![B256::random(), B256::random()];
+        let (tx, rx) = oneshot::channel();
+        fetcher.peers.get_mut(&peer_id).unwrap().state = PeerState::GetReceipts;
+        fetcher.inflight_receipts_requests.insert(
+            peer_id,
+            Request { request: ReceiptsRequest::new(block_hashes.clone()), response: tx },
+        );
+
+        // the second block is cut off after the first receipt
+        let outcome = fetcher.on_receipts_response(
+            peer_id,
+            Ok(Receipts70 {
+                last_block_incomplete: true,
+                receipts: vec![vec![receipt(1)], vec![receipt(2)]],
+            }),
+        );
+        assert_eq!(
+            outcome,
+            Some(BlockResponseOutcome::Request(
+                peer_id,
+                BlockRequest::GetReceipts(GetReceipts70 {
+                    first_block_receipt_index: 1,
+                    block_hashes: vec![block_hashes[1]],
+                })
+            ))
+        );
+        assert!(!fetcher.peers[&peer_id].state.is_idle());
+
+        let outcome = fetcher.on_receipts_response(
+            peer_id,
+            Ok(Receipts70 { last_block_incomplete: false, receipts: vec![vec![receipt(3)]] }),
+        );
+        assert!(outcome.is_none());
+        assert!(fetcher.peers[&peer_id].state.is_idle());
+
+        let receipts = rx.await.unwrap().unwrap().1;
+        assert_eq!(receipts, vec![vec![receipt(1)], vec![receipt(2), receipt(3)]]);
+    }
+
+    #[test]
+    fn test_receipts_request_rejects_stalled_response() {
+        let mut request =
+            ReceiptsRequest::<reth_ethereum_primitives::Receipt>::new(vec![B256::random()]);
+        assert_eq!(
+            request.on_response(Receipts70 {
+                last_block_incomplete: true,
+                receipts: vec![vec![Default::default()]],
+            }),
+            Ok(true)
+        );
+
+        // a continuation without any receipts doesn't make progress
+        assert_eq!(
+            request.on_response(Receipts70 { last_block_incomplete: true, receipts: vec![vec![]] }),
+            Err(RequestError::BadResponse)
+        );
+        assert!(request.into_complete_receipts().is_empty());
+    }
 }
@@ -121,6 +121,7 @@
 /// Common helpers for network testing.
 pub mod test_utils;
 
+pub mod bandwidth;
 pub mod cache;
 pub mod config;
 pub mod error;
@@ -153,13 +154,14 @@ pub use reth_network_api::{
     NetworkEventListenerProvider, NetworkInfo, PeerRequest, PeerRequestSender, Peers, PeersInfo,
 };
 pub use reth_network_p2p::sync::{NetworkSyncUpdater, SyncState};
-pub use reth_network_types::{PeersConfig, SessionsConfig};
+pub use reth_network_types::{PeersConfig, SessionsConfig, UploadLimits};
 pub use session::{
     ActiveSessionHandle, ActiveSessionMessage, Direction, EthRlpxConnection, PeerInfo,
     PendingSessionEvent, PendingSessionHandle, PendingSessionHandshakeError, SessionCommand,
     SessionEvent, SessionId, SessionManager,
 };
 
+pub use bandwidth::UploadLimiter;
 pub use builder::NetworkBuilder;
 pub use config::{NetworkConfig, NetworkConfigBuilder};
 pub use discovery::Discovery;
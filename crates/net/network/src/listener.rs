@@ -11,14 +11,16 @@ use tokio::net::{TcpListener, TcpStream};
 
 /// A tcp connection listener.
 ///
-/// Listens for incoming connections.
+/// Listens for incoming connections on one or more local addresses.
 #[must_use = "Transport does nothing unless polled."]
 #[derive(Debug)]
 pub struct ConnectionListener {
-    /// Local address of the listener stream.
+    /// Local address of the primary listener stream.
     local_address: SocketAddr,
-    /// The active tcp listener for incoming connections.
-    incoming: TcpListenerStream,
+    /// The active tcp listeners for incoming connections, the primary listener comes first.
+    incoming: Vec<TcpListenerStream>,
+    /// Index of the listener that is polled first, rotated to ensure fairness.
+    next_listener: usize,
 }
 
 impl ConnectionListener {
@@ -29,32 +31,90 @@ impl ConnectionListener {
         Ok(Self::new(listener, local_addr))
     }
 
+    /// Creates a [`TcpListener`] for each of the given addresses. The first address is the primary
+    /// address of the listener, see [`Self::local_address`].
+    ///
+    /// If more than one address is given, IPv6 listeners only accept IPv6 connections, so that an
+    /// IPv4 and an IPv6 listener can share the same port.
+    pub async fn bind_all(addrs: &[SocketAddr]) -> io::Result<Self> {
+        let Some((primary, additional)) = addrs.split_first() else {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no listener address"))
+        };
+        if additional.is_empty() {
+            return Self::bind(*primary).await
+        }
+
+        let mut incoming = Vec::with_capacity(addrs.len());
+        for addr in addrs {
+            let inner = bind_single_stack_tcp(*addr)?;
+            let local_address = inner.local_addr()?;
+            incoming.push(TcpListenerStream { inner, local_address });
+        }
+        Ok(Self { local_address: incoming[0].local_address, incoming, next_listener: 0 })
+    }
+
     /// Creates a new connection listener stream.
-    pub(crate) const fn new(listener: TcpListener, local_address: SocketAddr) -> Self {
-        Self { local_address, incoming: TcpListenerStream { inner: listener } }
+    pub(crate) fn new(listener: TcpListener, local_address: SocketAddr) -> Self {
+        Self {
+            local_address,
+            incoming: vec![TcpListenerStream { inner: listener, local_address }],
+            next_listener: 0,
+        }
     }
 
     /// Polls the type to make progress.
     pub fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<ListenerEvent> {
         let this = self.get_mut();
-        match ready!(this.incoming.poll_next_unpin(cx)) {
-            Some(Ok((stream, remote_addr))) => {
-                if let Err(err) = stream.set_nodelay(true) {
-                    tracing::warn!(target: "net", "set nodelay failed: {:?}", err);
+        let num_listeners = this.incoming.len();
+        for offset in 0..num_listeners {
+            let idx = (this.next_listener + offset) % num_listeners;
+            let listener = &mut this.incoming[idx];
+            let Poll::Ready(next) = listener.poll_next_unpin(cx) else { continue };
+            this.next_listener = (idx + 1) % num_listeners;
+            return match next {
+                Some(Ok((stream, remote_addr))) => {
+                    if let Err(err) = stream.set_nodelay(true) {
+                        tracing::warn!(target: "net", "set nodelay failed: {:?}", err);
+                    }
+                    Poll::Ready(ListenerEvent::Incoming { stream, remote_addr })
                 }
-                Poll::Ready(ListenerEvent::Incoming { stream, remote_addr })
-            }
-            Some(Err(err)) => Poll::Ready(ListenerEvent::Error(err)),
-            None => {
-                Poll::Ready(ListenerEvent::ListenerClosed { local_address: this.local_address })
+                Some(Err(err)) => Poll::Ready(ListenerEvent::Error(err)),
+                None => Poll::Ready(ListenerEvent::ListenerClosed {
+                    local_address: listener.local_address,
+                }),
             }
         }
+        Poll::Pending
     }
 
-    /// Returns the socket address this listener listens on.
+    /// Returns the socket address the primary listener listens on.
     pub const fn local_address(&self) -> SocketAddr {
         self.local_address
     }
+
+    /// Returns the socket addresses of all listeners, starting with the primary address.
+    pub fn local_addresses(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        self.incoming.iter().map(|listener| listener.local_address)
+    }
+}
+
+/// Binds a [`TcpListener`] that only accepts connections of the address family of the given
+/// address.
+fn bind_single_stack_tcp(addr: SocketAddr) -> io::Result<TcpListener> {
+    let socket = socket2::Socket::new(
+        socket2::Domain::for_address(addr),
+        socket2::Type::STREAM,
+        Some(socket2::Protocol::TCP),
+    )?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    #[cfg(not(windows))]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+    TcpListener::from_std(socket.into())
 }
 
 /// Event type produced by the [`TcpListenerStream`].
@@ -85,6 +145,8 @@ pub enum ListenerEvent {
 struct TcpListenerStream {
     /// listener for incoming connections.
     inner: TcpListener,
+    /// Local address of the listener.
+    local_address: SocketAddr,
 }
 
 impl Stream for TcpListenerStream {
@@ -103,7 +165,7 @@ impl Stream for TcpListenerStream {
 mod tests {
     use super::*;
     use std::{
-        net::{Ipv4Addr, SocketAddrV4},
+        net::{Ipv4Addr, Ipv6Addr, SocketAddrV4},
         pin::pin,
     };
     use tokio::macros::support::poll_fn;
@@ -128,4 +190,36 @@ mod tests {
 
         let _ = TcpStream::connect(local_addr).await.unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dual_stack_listener() {
+        let ipv4 =
+            ConnectionListener::bind(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)))
+                .await
+                .unwrap();
+        let port = ipv4.local_address().port();
+        drop(ipv4);
+
+        // both listeners share the same port
+        let listener = ConnectionListener::bind_all(&[
+            (Ipv4Addr::LOCALHOST, port).into(),
+            (Ipv6Addr::LOCALHOST, port).into(),
+        ])
+        .await
+        .unwrap();
+        let addrs: Vec<_> = listener.local_addresses().collect();
+        assert_eq!(addrs.len(), 2);
+        assert_eq!(listener.local_address(), addrs[0]);
+
+        let mut listener = Box::pin(listener);
+        for addr in addrs {
+            let _stream = TcpStream::connect(addr).await.unwrap();
+            match poll_fn(|cx| listener.as_mut().poll(cx)).await {
+                ListenerEvent::Incoming { remote_addr, .. } => {
+                    assert_eq!(remote_addr.is_ipv6(), addr.is_ipv6())
+                }
+                _ => panic!("unexpected event"),
+            }
+        }
+    }
 }
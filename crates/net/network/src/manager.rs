@@ -16,6 +16,7 @@
 //! to the local node. Once a (tcp) connection is established, both peers start to authenticate a [RLPx session](https://github.com/ethereum/devp2p/blob/master/rlpx.md) via a handshake. If the handshake was successful, both peers announce their capabilities and are now ready to exchange sub-protocol messages via the `RLPx` session.
 
 use crate::{
+    bandwidth::UploadLimiter,
     budget::{DEFAULT_BUDGET_TRY_DRAIN_NETWORK_HANDLE_CHANNEL, DEFAULT_BUDGET_TRY_DRAIN_SWARM},
     config::NetworkConfig,
     discovery::Discovery,
@@ -48,7 +49,7 @@ use reth_network_api::{
     EthProtocolInfo, NetworkEvent, NetworkStatus, PeerInfo, PeerRequest,
 };
 use reth_network_peers::{NodeRecord, PeerId};
-use reth_network_types::ReputationChangeKind;
+use reth_network_types::{IpVersions, ReputationChangeKind};
 use reth_storage_api::BlockNumReader;
 use reth_tasks::shutdown::GracefulShutdown;
 use reth_tokio_util::EventSender;
@@ -232,9 +233,11 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
             client,
             secret_key,
             discovery_v4_addr,
+            discovery_v4_secondary_addr,
             mut discovery_v4_config,
             mut discovery_v5_config,
             listener_addr,
+            additional_listener_addrs,
             peers_config,
             sessions_config,
             chain_id,
@@ -248,21 +251,28 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
             dns_discovery_config,
             extra_protocols,
             tx_gossip_disabled,
+            upload_limits,
             transactions_manager_config: _,
             nat,
             handshake,
             required_block_hashes,
         } = config;
 
-        let peers_manager = PeersManager::new(peers_config);
+        let mut peers_manager = PeersManager::new(peers_config);
         let peers_handle = peers_manager.handle();
 
-        let incoming = ConnectionListener::bind(listener_addr).await.map_err(|err| {
+        let listener_addrs: Vec<_> =
+            std::iter::once(listener_addr).chain(additional_listener_addrs).collect();
+        let incoming = ConnectionListener::bind_all(&listener_addrs).await.map_err(|err| {
             NetworkError::from_io_error(err, ServiceKind::Listener(listener_addr))
         })?;
 
         // retrieve the tcp address of the socket
         let listener_addr = incoming.local_address();
+        // the tcp address of the other IP version, if listening dual-stack
+        let secondary_listener_addr =
+            incoming.local_addresses().find(|addr| addr.is_ipv4() != listener_addr.is_ipv4());
+        peers_manager.set_ip_versions(IpVersions::from_addrs(incoming.local_addresses()));
 
         // resolve boot nodes
         let resolved_boot_nodes =
@@ -281,15 +291,21 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
             discv5.extend_unsigned_boot_nodes(resolved_boot_nodes)
         }
 
-        let discovery = Discovery::new(
+        let mut discovery = Discovery::new(
             listener_addr,
+            secondary_listener_addr,
             discovery_v4_addr,
+            discovery_v4_secondary_addr,
             secret_key,
             discovery_v4_config,
             discovery_v5_config,
             dns_discovery_config,
         )
         .await?;
+        if let Some(nat) = nat {
+            // map the ports on the gateway, if supported by the resolver
+            discovery.spawn_port_mapping(nat, listener_addr.port());
+        }
         // need to retrieve the addr here since provided port could be `0`
         let local_peer_id = discovery.local_id();
         let discv4 = discovery.discv4();
@@ -331,6 +347,7 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
             network_mode,
             Arc::new(AtomicU64::new(chain_id)),
             tx_gossip_disabled,
+            UploadLimiter::new(upload_limits),
             discv4,
             discv5,
             event_sender.clone(),
@@ -441,6 +458,16 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
         Ok(())
     }
 
+    /// Collect the active bans and the peer reputations and write them to the given
+    /// `peer_state_file`, so they can be restored after a restart.
+    #[cfg(feature = "serde")]
+    pub fn write_peer_state_to_file(&self, peer_state_file: &Path) -> Result<(), FsPathError> {
+        let state = self.swarm.state().peers().persisted_state();
+        peer_state_file.parent().map(fs::create_dir_all).transpose()?;
+        reth_fs_util::write_json_file(peer_state_file, &state)?;
+        Ok(())
+    }
+
     /// Returns a new [`FetchClient`] that can be cloned and shared.
     ///
     /// The [`FetchClient`] is the entrypoint for sending requests to the network.
@@ -532,6 +559,13 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
                     response,
                 })
             }
+            PeerRequest::GetReceipts70 { request, response } => {
+                self.delegate_eth_request(IncomingEthRequest::GetReceipts70 {
+                    peer_id,
+                    request,
+                    response,
+                })
+            }
             PeerRequest::GetPooledTransactions { request, response } => {
                 self.notify_tx_manager(NetworkTransactionEvent::GetPooledTransactions {
                     peer_id,
@@ -704,6 +738,15 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
             NetworkHandleMessage::GetReputationById(peer_id, tx) => {
                 let _ = tx.send(self.swarm.state_mut().peers().get_reputation(&peer_id));
             }
+            NetworkHandleMessage::Ban(target, until) => {
+                self.swarm.state_mut().peers_mut().ban(target, until);
+            }
+            NetworkHandleMessage::Unban(target) => {
+                self.swarm.state_mut().peers_mut().unban(&target);
+            }
+            NetworkHandleMessage::GetBans(tx) => {
+                let _ = tx.send(self.swarm.state().peers().bans());
+            }
             NetworkHandleMessage::FetchClient(tx) => {
                 let _ = tx.send(self.fetch_client());
             }
@@ -776,6 +819,7 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
                 messages,
                 status,
                 direction,
+                score,
             } => {
                 let total_active = self.num_active_peers.fetch_add(1, Ordering::Relaxed) + 1;
                 self.metrics.connected_peers.set(total_active as f64);
@@ -801,6 +845,8 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
                     self.swarm.state_mut().peers_mut().on_active_outgoing_established(peer_id);
                 }
 
+                self.swarm.state_mut().peers_mut().on_session_score(peer_id, score);
+
                 self.update_active_connection_metrics();
 
                 let peer_kind = self
@@ -861,6 +907,7 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
                 };
                 self.metrics.closed_sessions.increment(1);
                 self.update_active_connection_metrics();
+                self.handle.upload_limiter().remove_peer(&peer_id);
 
                 if let Some(reason) = reason {
                     self.disconnect_metrics.increment(reason);
@@ -1084,6 +1131,8 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
         self.swarm.sessions_mut().disconnect_all(Some(DisconnectReason::ClientQuitting));
         // drop pending connections
         self.swarm.sessions_mut().disconnect_all_pending();
+        // remove the port mappings from the NAT gateway
+        self.swarm.state_mut().discovery_mut().shutdown_port_mapping();
     }
 }
 
@@ -1,5 +1,5 @@
 use crate::{
-    config::NetworkMode, message::PeerMessage, protocol::RlpxSubProtocol,
+    bandwidth::UploadLimiter, config::NetworkMode, message::PeerMessage, protocol::RlpxSubProtocol,
     swarm::NetworkConnectionState, transactions::TransactionsHandle, FetchClient,
 };
 use alloy_primitives::B256;
@@ -15,8 +15,8 @@ use reth_eth_wire::{
 use reth_ethereum_forks::Head;
 use reth_network_api::{
     events::{NetworkPeersEvents, PeerEvent, PeerEventStream},
-    test_utils::{PeersHandle, PeersHandleProvider},
-    BlockDownloaderProvider, DiscoveryEvent, NetworkError, NetworkEvent,
+    peers::{PeersHandle, PeersHandleProvider},
+    BanEntry, BanTarget, BlockDownloaderProvider, DiscoveryEvent, NetworkError, NetworkEvent,
     NetworkEventListenerProvider, NetworkInfo, NetworkStatus, PeerInfo, PeerRequest, Peers,
     PeersInfo,
 };
@@ -31,6 +31,7 @@ use std::{
         atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
+    time::{Duration, Instant},
 };
 use tokio::sync::{
     mpsc::{self, UnboundedSender},
@@ -62,6 +63,7 @@ impl<N: NetworkPrimitives> NetworkHandle<N> {
         network_mode: NetworkMode,
         chain_id: Arc<AtomicU64>,
         tx_gossip_disabled: bool,
+        upload_limiter: UploadLimiter,
         discv4: Option<Discv4>,
         discv5: Option<Discv5>,
         event_sender: EventSender<NetworkEvent<PeerRequest<N>>>,
@@ -79,6 +81,7 @@ impl<N: NetworkPrimitives> NetworkHandle<N> {
             initial_sync_done: Arc::new(AtomicBool::new(false)),
             chain_id,
             tx_gossip_disabled,
+            upload_limiter,
             discv4,
             discv5,
             event_sender,
@@ -186,6 +189,11 @@ impl<N: NetworkPrimitives> NetworkHandle<N> {
         self.inner.tx_gossip_disabled
     }
 
+    /// Returns the limiter that enforces the configured upload limits.
+    pub fn upload_limiter(&self) -> &UploadLimiter {
+        &self.inner.upload_limiter
+    }
+
     /// Returns the secret key used for authenticating sessions.
     pub fn secret_key(&self) -> &SecretKey {
         &self.inner.secret_key
@@ -356,6 +364,25 @@ impl<N: NetworkPrimitives> Peers for NetworkHandle<N> {
         let _ = self.manager().send(NetworkHandleMessage::GetReputationById(peer_id, tx));
         Ok(rx.await?)
     }
+
+    /// Sends a message to the [`NetworkManager`](crate::NetworkManager) to ban the peer, IP
+    /// address or IP range.
+    fn ban(&self, target: BanTarget, duration: Option<Duration>) {
+        let until = duration.map(|duration| Instant::now() + duration);
+        self.send_message(NetworkHandleMessage::Ban(target, until))
+    }
+
+    /// Sends a message to the [`NetworkManager`](crate::NetworkManager) to lift the ban of the
+    /// peer, IP address or IP range.
+    fn unban(&self, target: BanTarget) {
+        self.send_message(NetworkHandleMessage::Unban(target))
+    }
+
+    async fn get_bans(&self) -> Result<Vec<BanEntry>, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.manager().send(NetworkHandleMessage::GetBans(tx));
+        Ok(rx.await?)
+    }
 }
 
 impl<N: NetworkPrimitives> PeersHandleProvider for NetworkHandle<N> {
@@ -456,6 +483,8 @@ struct NetworkInner<N: NetworkPrimitives = EthNetworkPrimitives> {
     chain_id: Arc<AtomicU64>,
     /// Whether to disable transaction gossip
     tx_gossip_disabled: bool,
+    /// Enforces the configured upload limits.
+    upload_limiter: UploadLimiter,
     /// The instance of the discv4 service
     discv4: Option<Discv4>,
     /// The instance of the discv5 service
@@ -534,6 +563,12 @@ pub(crate) enum NetworkHandleMessage<N: NetworkPrimitives = EthNetworkPrimitives
     GetPeerInfosByPeerKind(PeerKind, oneshot::Sender<Vec<PeerInfo>>),
     /// Gets the reputation for a specific peer via a oneshot sender.
     GetReputationById(PeerId, oneshot::Sender<Option<Reputation>>),
+    /// Bans the peer, IP address or IP range indefinitely or until the given timeout.
+    Ban(BanTarget, Option<Instant>),
+    /// Lifts the ban of the peer, IP address or IP range.
+    Unban(BanTarget),
+    /// Gets all active bans via a oneshot sender.
+    GetBans(oneshot::Sender<Vec<BanEntry>>),
     /// Retrieves the `TransactionsHandle` via a oneshot sender.
     GetTransactionsHandle(oneshot::Sender<Option<TransactionsHandle<N>>>),
     /// Initiates a graceful shutdown of the network via a oneshot sender.
@@ -10,25 +10,27 @@ use futures::StreamExt;
 
 use reth_eth_wire::{errors::EthStreamError, DisconnectReason};
 use reth_ethereum_forks::ForkId;
-use reth_net_banlist::BanList;
-use reth_network_api::test_utils::{PeerCommand, PeersHandle};
+use reth_net_banlist::{BanEntry, BanList, BanTarget};
+use reth_network_api::{PeerCommand, PeersHandle};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_network_types::{
     is_connection_failed_reputation,
     peers::{
         config::PeerBackoffDurations,
-        reputation::{DEFAULT_REPUTATION, MAX_TRUSTED_PEER_REPUTATION_CHANGE},
+        reputation::{BANNED_REPUTATION, DEFAULT_REPUTATION, MAX_TRUSTED_PEER_REPUTATION_CHANGE},
     },
-    ConnectionsConfig, Peer, PeerAddr, PeerConnectionState, PeerKind, PeersConfig,
-    ReputationChangeKind, ReputationChangeOutcome, ReputationChangeWeights,
+    ConnectionsConfig, IpVersions, Peer, PeerAddr, PeerConnectionState, PeerKind, PeerReputation,
+    PeerScore, PeersConfig, PersistedPeerState, ReputationChangeKind, ReputationChangeOutcome,
+    ReputationChangeWeights,
 };
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     fmt::Display,
     io::{self},
     net::{IpAddr, SocketAddr},
+    sync::Arc,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
 use tokio::{
@@ -38,6 +40,10 @@ use tokio::{
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{trace, warn};
 
+/// Outbound peers that answered less than this share of requests with data are replaced when all
+/// outbound slots are occupied.
+const MIN_OUTBOUND_PEER_USEFULNESS: f64 = 0.25;
+
 /// Maintains the state of _all_ the peers known to the network.
 ///
 /// This is supposed to be owned by the network itself, but can be reached via the [`PeersHandle`].
@@ -90,6 +96,8 @@ pub struct PeersManager {
     net_connection_state: NetworkConnectionState,
     /// How long to temporarily ban ip on an incoming connection attempt.
     incoming_ip_throttle_duration: Duration,
+    /// The IP versions peers are preferably dialed over.
+    ip_versions: IpVersions,
 }
 
 impl PeersManager {
@@ -100,6 +108,7 @@ impl PeersManager {
             connection_info,
             reputation_weights,
             ban_list,
+            reputations,
             ban_duration,
             backoff_durations,
             trusted_nodes,
@@ -138,6 +147,13 @@ impl PeersManager {
             });
         }
 
+        for (peer_id, reputation) in reputations {
+            if let Some(peer) = peers.get_mut(&peer_id) {
+                // bans are restored via the ban list, which lifts them when they expire
+                peer.reputation = reputation.max(BANNED_REPUTATION);
+            }
+        }
+
         Self {
             peers,
             trusted_peer_ids,
@@ -161,9 +177,17 @@ impl PeersManager {
             max_backoff_count,
             net_connection_state: NetworkConnectionState::default(),
             incoming_ip_throttle_duration,
+            ip_versions: IpVersions::default(),
         }
     }
 
+    /// Sets the IP versions the local node can reach peers over.
+    ///
+    /// Peers that advertised addresses of both IP versions are dialed over a supported version.
+    pub(crate) const fn set_ip_versions(&mut self, ip_versions: IpVersions) {
+        self.ip_versions = ip_versions;
+    }
+
     /// Returns a new [`PeersHandle`] that can send commands to this type.
     pub(crate) fn handle(&self) -> PeersHandle {
         PeersHandle::new(self.manager_tx.clone())
@@ -423,6 +447,68 @@ impl PeersManager {
         self.queued_actions.push_back(PeerAction::UnBanPeer { peer_id });
     }
 
+    /// Bans the peer, IP address or IP range indefinitely or until the given timeout and
+    /// disconnects the connected peers the ban applies to.
+    pub(crate) fn ban(&mut self, target: BanTarget, until: Option<std::time::Instant>) {
+        self.ban_list.ban_with(target, until);
+        if let BanTarget::Peer(peer_id) = target {
+            self.queued_actions.push_back(PeerAction::BanPeer { peer_id });
+        }
+        self.queued_actions.push_back(PeerAction::DiscoveryBan { target, until });
+
+        for (peer_id, peer) in &self.peers {
+            let ip = peer.addr.tcp().ip();
+            if peer.state.is_connected() &&
+                target.matches(peer_id, &ip) &&
+                self.ban_list.is_banned(peer_id, &ip)
+            {
+                self.queued_actions.push_back(PeerAction::Disconnect {
+                    peer_id: *peer_id,
+                    reason: Some(DisconnectReason::DisconnectRequested),
+                });
+            }
+        }
+    }
+
+    /// Lifts the ban of the peer, IP address or IP range.
+    ///
+    /// Returns `true` if the target was banned.
+    pub(crate) fn unban(&mut self, target: &BanTarget) -> bool {
+        let unbanned = self.ban_list.unban(target);
+        if unbanned && let BanTarget::Peer(peer_id) = *target {
+            if let Some(peer) = self.peers.get_mut(&peer_id) {
+                peer.unban();
+            }
+            self.queued_actions.push_back(PeerAction::UnBanPeer { peer_id });
+        }
+        if unbanned {
+            self.queued_actions.push_back(PeerAction::DiscoveryUnban { target: *target });
+        }
+        unbanned
+    }
+
+    /// Returns all active bans.
+    pub(crate) fn bans(&self) -> Vec<BanEntry> {
+        self.ban_list.entries().collect()
+    }
+
+    /// Returns the bans and the reputations that should be restored after a restart.
+    pub(crate) fn persisted_state(&self) -> PersistedPeerState {
+        PersistedPeerState {
+            saved_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+            bans: self.bans(),
+            reputations: self
+                .peers
+                .iter()
+                .filter(|(_, peer)| peer.reputation != DEFAULT_REPUTATION)
+                .map(|(peer_id, peer)| PeerReputation {
+                    peer_id: *peer_id,
+                    reputation: peer.reputation,
+                })
+                .collect(),
+        }
+    }
+
     /// Tick function to update reputation of all connected peers.
     /// Peers are rewarded with reputation increases for the time they are connected since the last
     /// tick. This is to prevent peers from being disconnected eventually due to slashed
@@ -556,6 +642,14 @@ impl PeersManager {
         }
     }
 
+    /// Called when a session to a peer was established, with the score of the data the peer serves
+    /// in this session.
+    pub(crate) fn on_session_score(&mut self, peer_id: PeerId, score: Arc<PeerScore>) {
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.score = Some(score);
+        }
+    }
+
     /// Called when an _active_ session to a peer was forcefully dropped due to an error.
     ///
     /// Depending on whether the error is fatal, the peer will be removed from the peer set
@@ -758,7 +852,7 @@ impl PeersManager {
                 let peer = entry.get_mut();
                 peer.kind = kind;
                 peer.fork_id = fork_id.map(Box::new);
-                peer.addr = addr;
+                peer.set_addr(addr);
 
                 if peer.state.is_incoming() {
                     // now that we have an actual discovered address, for that peer and not just the
@@ -839,7 +933,7 @@ impl PeersManager {
                 let peer = entry.get_mut();
                 peer.kind = kind;
                 peer.fork_id = fork_id.map(Box::new);
-                peer.addr = addr;
+                peer.set_addr(addr);
 
                 if peer.state == PeerConnectionState::Idle {
                     // Try connecting again.
@@ -930,6 +1024,8 @@ impl PeersManager {
             return
         }
 
+        let ip_versions = self.ip_versions;
+
         // as long as there are slots available fill them with the best peers
         while self.connection_info.has_out_capacity() {
             let action = {
@@ -938,6 +1034,9 @@ impl PeersManager {
                     _ => break,
                 };
 
+                // dial over an IP version the peer advertised and we can reach
+                peer.prefer_ip_versions(ip_versions);
+
                 trace!(target: "net::peers", ?peer_id, addr=?peer.addr, "schedule outbound connection");
 
                 peer.state = PeerConnectionState::PendingOut;
@@ -948,6 +1047,48 @@ impl PeersManager {
 
             self.queued_actions.push_back(action);
         }
+
+        self.replace_useless_outbound_peer();
+    }
+
+    /// If all outbound slots are occupied, disconnects the outbound peer that served the least
+    /// useful data, so its slot is filled with another peer.
+    ///
+    /// Only peers that answered less than [`MIN_OUTBOUND_PEER_USEFULNESS`] of the requests with
+    /// data are replaced, one at a time, and only if there's another peer to connect to.
+    fn replace_useless_outbound_peer(&mut self) {
+        if self.connection_info.has_out_slot() ||
+            self.peers.values().any(|peer| peer.state == PeerConnectionState::DisconnectingOut) ||
+            self.best_unconnected().is_none()
+        {
+            return
+        }
+
+        let useless = self
+            .peers
+            .iter()
+            .filter(|(_, peer)| {
+                peer.state == PeerConnectionState::Out && !peer.is_trusted() && !peer.is_static()
+            })
+            .filter_map(|(peer_id, peer)| Some((*peer_id, peer.usefulness()?)))
+            .filter(|(_, usefulness)| *usefulness < MIN_OUTBOUND_PEER_USEFULNESS)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        if let Some((peer_id, usefulness)) = useless {
+            trace!(target: "net::peers", ?peer_id, usefulness, "replacing useless outbound peer");
+            if let Some(peer) = self.peers.get_mut(&peer_id) {
+                peer.state.disconnect();
+            }
+            // don't reconnect to the peer right away
+            self.backoff_peer_until(
+                peer_id,
+                std::time::Instant::now() + self.backoff_durations.medium,
+            );
+            self.queued_actions.push_back(PeerAction::Disconnect {
+                peer_id,
+                reason: Some(DisconnectReason::UselessPeer),
+            });
+        }
     }
 
     fn on_resolved_peer(&mut self, peer_id: PeerId, new_record: NodeRecord) {
@@ -1083,6 +1224,11 @@ impl ConnectionInfo {
             self.num_outbound < self.config.max_outbound
     }
 
+    /// Returns `true` if not all slots for active outbound connections are occupied.
+    const fn has_out_slot(&self) -> bool {
+        self.num_outbound < self.config.max_outbound
+    }
+
     ///  Returns `true` if there's still capacity to accept a new incoming connection.
     const fn has_in_capacity(&self) -> bool {
         self.num_inbound < self.config.max_inbound
@@ -1175,6 +1321,18 @@ pub enum PeerAction {
         /// The IP address.
         ip_addr: IpAddr,
     },
+    /// Ban the peer, IP address or IP range in discovery.
+    DiscoveryBan {
+        /// The banned peer, IP address or IP range.
+        target: BanTarget,
+        /// Until when the ban applies, indefinitely if `None`.
+        until: Option<std::time::Instant>,
+    },
+    /// Lift the ban of the peer, IP address or IP range in discovery.
+    DiscoveryUnban {
+        /// The peer, IP address or IP range that is no longer banned.
+        target: BanTarget,
+    },
     /// Ban the peer temporarily
     BanPeer {
         /// The peer ID.
@@ -1213,17 +1371,20 @@ mod tests {
         errors::{EthHandshakeError, EthStreamError, P2PHandshakeError, P2PStreamError},
         DisconnectReason,
     };
-    use reth_net_banlist::BanList;
+    use reth_net_banlist::{BanEntry, BanList, BanTarget};
     use reth_network_api::Direction;
-    use reth_network_peers::{PeerId, TrustedPeer};
+    use reth_network_peers::{NodeRecord, PeerId, TrustedPeer};
     use reth_network_types::{
-        peers::reputation::DEFAULT_REPUTATION, BackoffKind, Peer, ReputationChangeKind,
+        peers::reputation::DEFAULT_REPUTATION, BackoffKind, IpVersions, Peer, PeerScore,
+        ReputationChangeKind, ServedDataKind,
     };
     use std::{
+        collections::HashSet,
         future::{poll_fn, Future},
         io,
-        net::{IpAddr, Ipv4Addr, SocketAddr},
+        net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
         pin::Pin,
+        sync::Arc,
         task::{Context, Poll},
         time::Duration,
     };
@@ -1284,6 +1445,37 @@ mod tests {
         assert_eq!(record.udp_addr(), socket_addr);
     }
 
+    #[tokio::test]
+    async fn test_dial_supported_ip_version() {
+        let peer = PeerId::random();
+        let ipv4_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let ipv6_addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 8008);
+        let mut peers = PeersManager::default();
+        peers.set_ip_versions(IpVersions::Ipv4);
+
+        // the peer is rediscovered over IPv6, which we can't dial
+        peers.add_peer(peer, PeerAddr::from_tcp(ipv4_addr), None);
+        peers.add_peer(peer, PeerAddr::from_tcp(ipv6_addr), None);
+
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, remote_addr } => {
+                assert_eq!(peer_id, peer);
+                assert_eq!(remote_addr, ipv4_addr);
+            }
+            _ => unreachable!(),
+        }
+
+        let entry = peers.peers.get(&peer).unwrap();
+        assert_eq!(entry.addr.tcp(), ipv4_addr);
+        assert_eq!(entry.secondary_addr.unwrap().tcp(), ipv6_addr);
+    }
+
     #[tokio::test]
     async fn test_insert_udp() {
         let peer = PeerId::random();
@@ -2302,6 +2494,60 @@ mod tests {
         assert_eq!(peer_id, given_peer_id)
     }
 
+    #[tokio::test]
+    async fn test_ban_ip_range_disconnects() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)), 8008);
+        let mut peers = PeersManager::new(PeersConfig::test());
+        peers.add_peer(peer, PeerAddr::from_tcp(socket_addr), None);
+        peers.peers.get_mut(&peer).unwrap().state = PeerConnectionState::In;
+        peers.queued_actions.clear();
+
+        let range: BanTarget = "203.0.113.0/24".parse().unwrap();
+        peers.ban(range, None);
+        assert!(peers.ban_list.is_banned_ip(&socket_addr.ip()));
+        assert!(matches!(
+            peers.queued_actions.pop_front(),
+            Some(PeerAction::DiscoveryBan { target, until: None }) if target == range
+        ));
+        assert!(matches!(
+            peers.queued_actions.pop_front(),
+            Some(PeerAction::Disconnect { peer_id, .. }) if peer_id == peer
+        ));
+        assert_eq!(peers.bans(), vec![BanEntry { target: range, until: None }]);
+
+        assert!(peers.unban(&range));
+        assert!(!peers.unban(&range));
+        assert!(!peers.ban_list.is_banned_ip(&socket_addr.ip()));
+        assert!(matches!(
+            peers.queued_actions.pop_front(),
+            Some(PeerAction::DiscoveryUnban { target }) if target == range
+        ));
+        assert!(peers.queued_actions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_persisted_state_roundtrip() {
+        let slashed = PeerId::random();
+        let banned = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::new(PeersConfig::test());
+        peers.add_peer(slashed, PeerAddr::from_tcp(socket_addr), None);
+        peers.apply_reputation_change(&slashed, ReputationChangeKind::BadMessage);
+        let reputation = peers.get_reputation(&slashed).unwrap();
+        assert!(reputation < DEFAULT_REPUTATION);
+        peers.ban(BanTarget::Peer(banned), None);
+
+        let state = peers.persisted_state();
+        let basic_nodes = HashSet::from([NodeRecord::new(socket_addr, slashed)]);
+        let config = PeersConfig::test().with_basic_nodes(basic_nodes).with_persisted_state(state);
+        let restored = PeersManager::new(config);
+
+        assert!(restored.ban_list.is_banned_peer(&banned));
+        let restored_reputation = restored.get_reputation(&slashed).unwrap();
+        assert!(restored_reputation >= reputation && restored_reputation < DEFAULT_REPUTATION);
+    }
+
     #[test]
     fn test_connection_limits() {
         let mut info = ConnectionInfo::default();
@@ -2660,6 +2906,51 @@ mod tests {
         assert_eq!(peers.connection_info.num_outbound, 0);
     }
 
+    #[tokio::test]
+    async fn test_replace_useless_outbound_peer() {
+        let config = PeersConfig::test().with_max_outbound(1);
+        let mut peers = PeersManager::new(config);
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let useless = PeerId::random();
+        let other = PeerId::random();
+        peers.add_peer(useless, PeerAddr::from_tcp(socket_addr), None);
+        peers.queued_actions.clear();
+
+        peers.fill_outbound_slots();
+        assert!(matches!(
+            peers.queued_actions.pop_front(),
+            Some(PeerAction::Connect { peer_id, .. }) if peer_id == useless
+        ));
+        peers.on_active_outgoing_established(useless);
+        let score = Arc::new(PeerScore::default());
+        peers.on_session_score(useless, Arc::clone(&score));
+
+        // the only outbound peer is kept while there's no other peer
+        for _ in 0..64 {
+            score.on_response(ServedDataKind::Headers, Duration::from_millis(10), 0, true);
+        }
+        peers.fill_outbound_slots();
+        assert!(peers.queued_actions.is_empty());
+
+        peers.add_peer(other, PeerAddr::from_tcp(socket_addr), None);
+        peers.queued_actions.clear();
+        peers.fill_outbound_slots();
+        assert!(matches!(
+            peers.queued_actions.pop_front(),
+            Some(PeerAction::Disconnect { peer_id, reason: Some(DisconnectReason::UselessPeer) })
+                if peer_id == useless
+        ));
+        assert!(peers.queued_actions.is_empty());
+
+        // the replacement is dialed once the session is closed, not the useless peer
+        peers.on_active_session_gracefully_closed(useless);
+        peers.fill_outbound_slots();
+        assert!(matches!(
+            peers.queued_actions.pop_front(),
+            Some(PeerAction::Connect { peer_id, .. }) if peer_id == other
+        ));
+    }
+
     #[tokio::test]
     async fn test_max_concurrent_dials() {
         let config = PeersConfig::default();
@@ -21,6 +21,7 @@ use crate::{
 };
 use alloy_eips::merge::EPOCH_SLOTS;
 use alloy_primitives::Sealable;
+use alloy_rlp::Encodable;
 use futures::{stream::Fuse, SinkExt, StreamExt};
 use metrics::Gauge;
 use reth_eth_wire::{
@@ -33,7 +34,7 @@ use reth_metrics::common::mpsc::MeteredPollSender;
 use reth_network_api::PeerRequest;
 use reth_network_p2p::error::RequestError;
 use reth_network_peers::PeerId;
-use reth_network_types::session::config::INITIAL_REQUEST_TIMEOUT;
+use reth_network_types::{session::config::INITIAL_REQUEST_TIMEOUT, PeerScore, ServedDataKind};
 use reth_primitives_traits::Block;
 use rustc_hash::FxHashMap;
 use tokio::{
@@ -115,6 +116,8 @@ pub(crate) struct ActiveSession<N: NetworkPrimitives> {
     pub(crate) queued_outgoing: QueuedOutgoingMessages<N>,
     /// The maximum time we wait for a response from a peer.
     pub(crate) internal_request_timeout: Arc<AtomicU64>,
+    /// Tracks the quality of the responses of the peer.
+    pub(crate) score: Arc<PeerScore>,
     /// Interval when to check for timed out requests.
     pub(crate) internal_request_timeout_interval: Interval,
     /// If an [`ActiveSession`] does not receive a response at all within this duration then it is
@@ -187,15 +190,30 @@ impl<N: NetworkPrimitives> ActiveSession<N> {
         }
 
         /// Processes a response received from the peer
+        ///
+        /// The optional field is the list of the response that is checked for emptiness.
         macro_rules! on_response {
-            ($resp:ident, $item:ident) => {{
+            ($resp:ident, $item:ident) => {
+                on_response!($resp, $item, 0)
+            };
+            ($resp:ident, $item:ident, $data:tt) => {{
                 let RequestPair { request_id, message } = $resp;
                 if let Some(req) = self.inflight_requests.remove(&request_id) {
+                    let served_data_kind = req.served_data_kind();
                     match req.request {
                         RequestState::Waiting(PeerRequest::$item { response, .. }) => {
                             trace!(peer_id=?self.remote_peer_id, ?request_id, "received response from peer");
+                            let now = Instant::now();
+                            if let Some(kind) = served_data_kind {
+                                self.score.on_response(
+                                    kind,
+                                    now.saturating_duration_since(req.timestamp),
+                                    message.length(),
+                                    message.$data.is_empty(),
+                                );
+                            }
                             let _ = response.send(Ok(message));
-                            self.update_request_timeout(req.timestamp, Instant::now());
+                            self.update_request_timeout(req.timestamp, now);
                         }
                         RequestState::Waiting(request) => {
                             request.send_bad_response();
@@ -286,6 +304,12 @@ impl<N: NetworkPrimitives> ActiveSession<N> {
             EthMessage::Receipts69(resp) => {
                 on_response!(resp, GetReceipts69)
             }
+            EthMessage::GetReceipts70(req) => {
+                on_request!(req, Receipts70, GetReceipts70)
+            }
+            EthMessage::Receipts70(resp) => {
+                on_response!(resp, GetReceipts70, receipts)
+            }
             EthMessage::BlockRangeUpdate(msg) => {
                 // Validate that earliest <= latest according to the spec
                 if msg.earliest > msg.latest {
@@ -512,6 +536,9 @@ impl<N: NetworkPrimitives> ActiveSession<N> {
             if req.is_timed_out(now) {
                 if req.is_waiting() {
                     debug!(target: "net::session", ?id, remote_peer_id=?self.remote_peer_id, "timed out outgoing request");
+                    if let Some(kind) = req.served_data_kind() {
+                        self.score.on_timeout(kind);
+                    }
                     req.timeout();
                 } else if now - req.timestamp > self.protocol_breach_request_timeout {
                     return true
@@ -816,6 +843,14 @@ impl<N: NetworkPrimitives> InflightRequest<PeerRequest<N>> {
         matches!(self.request, RequestState::Waiting(_))
     }
 
+    /// Returns the kind of the requested data if we're still waiting for a response.
+    const fn served_data_kind(&self) -> Option<ServedDataKind> {
+        match &self.request {
+            RequestState::Waiting(request) => request.served_data_kind(),
+            RequestState::TimedOut => None,
+        }
+    }
+
     /// This will timeout the request by sending an error response to the internal channel
     fn timeout(&mut self) {
         let mut req = RequestState::TimedOut;
@@ -1073,6 +1108,7 @@ mod tests {
                         internal_request_timeout: Arc::new(AtomicU64::new(
                             INITIAL_REQUEST_TIMEOUT.as_millis() as u64,
                         )),
+                        score: Default::default(),
                         protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
                         terminate_message: None,
                         range_info: None,
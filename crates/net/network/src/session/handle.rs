@@ -7,12 +7,12 @@ use crate::{
 };
 use reth_ecies::ECIESError;
 use reth_eth_wire::{
-    errors::EthStreamError, Capabilities, DisconnectReason, EthVersion, NetworkPrimitives,
-    UnifiedStatus,
+    bandwidth::TrafficDirection, errors::EthStreamError, BandwidthMeter, Capabilities,
+    DisconnectReason, EthVersion, NetworkPrimitives, UnifiedStatus,
 };
 use reth_network_api::PeerInfo;
 use reth_network_peers::{NodeRecord, PeerId};
-use reth_network_types::PeerKind;
+use reth_network_types::{MessageBandwidthStats, PeerBandwidthStats, PeerKind, PeerScore};
 use std::{io, net::SocketAddr, sync::Arc, time::Instant};
 use tokio::sync::{
     mpsc::{self, error::SendError},
@@ -75,6 +75,10 @@ pub struct ActiveSessionHandle<N: NetworkPrimitives> {
     pub(crate) local_addr: Option<SocketAddr>,
     /// The Status message the peer sent for the `eth` handshake
     pub(crate) status: Arc<UnifiedStatus>,
+    /// Tracks the quality of the data the peer serves in this session.
+    pub(crate) score: Arc<PeerScore>,
+    /// Tracks the bytes exchanged with the peer in this session.
+    pub(crate) bandwidth: Arc<BandwidthMeter>,
 }
 
 // === impl ActiveSessionHandle ===
@@ -135,6 +139,31 @@ impl<N: NetworkPrimitives> ActiveSessionHandle<N> {
         self.remote_addr
     }
 
+    /// Returns the score of the data the peer served in this session.
+    pub fn score(&self) -> Arc<PeerScore> {
+        self.score.clone()
+    }
+
+    /// Returns the bytes exchanged with the peer in this session.
+    pub fn bandwidth(&self) -> PeerBandwidthStats {
+        PeerBandwidthStats {
+            ingress_bytes: self.bandwidth.total(TrafficDirection::Ingress).bytes,
+            egress_bytes: self.bandwidth.total(TrafficDirection::Egress).bytes,
+            messages: self
+                .bandwidth
+                .traffic()
+                .map(|traffic| MessageBandwidthStats {
+                    protocol: traffic.protocol.to_string(),
+                    message_id: traffic.message_id,
+                    ingress_bytes: traffic.ingress.bytes,
+                    ingress_messages: traffic.ingress.messages,
+                    egress_bytes: traffic.egress.bytes,
+                    egress_messages: traffic.egress.messages,
+                })
+                .collect(),
+        }
+    }
+
     /// Extracts the [`PeerInfo`] from the session handle.
     pub(crate) fn peer_info(&self, record: &NodeRecord, kind: PeerKind) -> PeerInfo {
         PeerInfo {
@@ -150,6 +179,8 @@ impl<N: NetworkPrimitives> ActiveSessionHandle<N> {
             status: self.status.clone(),
             session_established: self.established,
             kind,
+            score: self.score.all_stats(),
+            bandwidth: self.bandwidth(),
         }
     }
 }
@@ -27,7 +27,7 @@ use reth_ethereum_forks::{ForkFilter, ForkId, ForkTransition, Head};
 use reth_metrics::common::mpsc::MeteredPollSender;
 use reth_network_api::{PeerRequest, PeerRequestSender};
 use reth_network_peers::PeerId;
-use reth_network_types::SessionsConfig;
+use reth_network_types::{PeerScore, SessionsConfig};
 use reth_tasks::TaskSpawner;
 use rustc_hash::FxHashMap;
 use secp256k1::SecretKey;
@@ -534,6 +534,8 @@ impl<N: NetworkPrimitives> SessionManager<N> {
                 let timeout = Arc::new(AtomicU64::new(
                     self.initial_internal_request_timeout.as_millis() as u64,
                 ));
+                let score = Arc::new(PeerScore::default());
+                let bandwidth = Arc::clone(conn.inner().bandwidth());
 
                 // negotiated version
                 let version = conn.version();
@@ -566,6 +568,7 @@ impl<N: NetworkPrimitives> SessionManager<N> {
                         self.initial_internal_request_timeout,
                     ),
                     internal_request_timeout: Arc::clone(&timeout),
+                    score: Arc::clone(&score),
                     protocol_breach_request_timeout: self.protocol_breach_request_timeout,
                     terminate_message: None,
                     range_info: None,
@@ -589,6 +592,8 @@ impl<N: NetworkPrimitives> SessionManager<N> {
                     client_version: Arc::clone(&client_version),
                     remote_addr,
                     local_addr,
+                    score: Arc::clone(&score),
+                    bandwidth,
                 };
 
                 self.active_sessions.insert(peer_id, handle);
@@ -608,6 +613,7 @@ impl<N: NetworkPrimitives> SessionManager<N> {
                     messages,
                     direction,
                     timeout,
+                    score,
                     range_info: None,
                 })
             }
@@ -742,6 +748,8 @@ pub enum SessionEvent<N: NetworkPrimitives> {
         /// The maximum time that the session waits for a response from the peer before timing out
         /// the connection
         timeout: Arc<AtomicU64>,
+        /// Tracks the quality of the data the peer serves in this session.
+        score: Arc<PeerScore>,
         /// The range info for the peer.
         range_info: Option<BlockRangeInfo>,
     },
@@ -13,13 +13,13 @@ use alloy_consensus::BlockHeader;
 use alloy_primitives::B256;
 use rand::seq::SliceRandom;
 use reth_eth_wire::{
-    BlockHashNumber, Capabilities, DisconnectReason, EthNetworkPrimitives, NetworkPrimitives,
-    NewBlockHashes, NewBlockPayload, UnifiedStatus,
+    BlockHashNumber, Capabilities, DisconnectReason, EthNetworkPrimitives, EthVersion, GetReceipts,
+    NetworkPrimitives, NewBlockHashes, NewBlockPayload, Receipts70, UnifiedStatus,
 };
 use reth_ethereum_forks::ForkId;
 use reth_network_api::{DiscoveredEvent, DiscoveryEvent, PeerRequest, PeerRequestSender};
 use reth_network_peers::PeerId;
-use reth_network_types::{PeerAddr, PeerKind};
+use reth_network_types::{PeerAddr, PeerKind, PeerScore};
 use reth_primitives_traits::Block;
 use std::{
     collections::{HashMap, VecDeque},
@@ -150,6 +150,7 @@ impl<N: NetworkPrimitives> NetworkState<N> {
         status: Arc<UnifiedStatus>,
         request_tx: PeerRequestSender<PeerRequest<N>>,
         timeout: Arc<AtomicU64>,
+        score: Arc<PeerScore>,
         range_info: Option<BlockRangeInfo>,
     ) {
         debug_assert!(!self.active_peers.contains_key(&peer), "Already connected; not possible");
@@ -163,6 +164,7 @@ impl<N: NetworkPrimitives> NetworkState<N> {
             block_number,
             Arc::clone(&capabilities),
             timeout,
+            score,
             range_info,
         );
 
@@ -170,6 +172,7 @@ impl<N: NetworkPrimitives> NetworkState<N> {
             peer,
             ActivePeer {
                 best_hash: status.blockhash,
+                version: status.version,
                 capabilities,
                 request_tx,
                 pending_response: None,
@@ -358,6 +361,14 @@ impl<N: NetworkPrimitives> NetworkState<N> {
                 self.ban_discovery(peer_id, ip_addr)
             }
             PeerAction::DiscoveryBanIp { ip_addr } => self.ban_ip_discovery(ip_addr),
+            PeerAction::DiscoveryBan { target, until } => {
+                trace!(target: "net", %target, "Banning discovery");
+                self.discovery.ban_target(target, until)
+            }
+            PeerAction::DiscoveryUnban { target } => {
+                trace!(target: "net", %target, "Unbanning discovery");
+                self.discovery.unban(target)
+            }
             PeerAction::PeerAdded(peer_id) => {
                 self.queued_messages.push_back(StateAction::PeerAdded(peer_id))
             }
@@ -387,6 +398,30 @@ impl<N: NetworkPrimitives> NetworkState<N> {
                     let response = PeerResponse::BlockBodies { response: rx };
                     (request, response)
                 }
+                BlockRequest::GetReceipts(request) if peer.version >= EthVersion::Eth70 => {
+                    let (response, rx) = oneshot::channel();
+                    let request = PeerRequest::GetReceipts70 { request, response };
+                    let response = PeerResponse::Receipts70 { response: rx };
+                    (request, response)
+                }
+                BlockRequest::GetReceipts(request) if peer.version >= EthVersion::Eth69 => {
+                    let (response, rx) = oneshot::channel();
+                    let request = PeerRequest::GetReceipts69 {
+                        request: GetReceipts(request.block_hashes),
+                        response,
+                    };
+                    let response = PeerResponse::Receipts69 { response: rx };
+                    (request, response)
+                }
+                BlockRequest::GetReceipts(request) => {
+                    let (response, rx) = oneshot::channel();
+                    let request = PeerRequest::GetReceipts {
+                        request: GetReceipts(request.block_hashes),
+                        response,
+                    };
+                    let response = PeerResponse::Receipts { response: rx };
+                    (request, response)
+                }
             };
             let _ = peer.request_tx.to_session_tx.try_send(request);
             peer.pending_response = Some(response);
@@ -418,6 +453,24 @@ impl<N: NetworkPrimitives> NetworkState<N> {
             PeerResponseResult::BlockBodies(res) => {
                 self.state_fetcher.on_block_bodies_response(peer, res)
             }
+            PeerResponseResult::Receipts(res) => {
+                // receipts before eth/70 are always complete
+                let res = res.map(|receipts| Receipts70 {
+                    last_block_incomplete: false,
+                    receipts: receipts
+                        .into_iter()
+                        .map(|receipts| receipts.into_iter().map(|r| r.receipt).collect())
+                        .collect(),
+                });
+                self.state_fetcher.on_receipts_response(peer, res)
+            }
+            PeerResponseResult::Receipts69(res) => {
+                let res = res.map(|receipts| Receipts70 { last_block_incomplete: false, receipts });
+                self.state_fetcher.on_receipts_response(peer, res)
+            }
+            PeerResponseResult::Receipts70(res) => {
+                self.state_fetcher.on_receipts_response(peer, res)
+            }
             _ => None,
         };
 
@@ -514,6 +567,8 @@ impl<N: NetworkPrimitives> NetworkState<N> {
 pub(crate) struct ActivePeer<N: NetworkPrimitives> {
     /// Best block of the peer.
     pub(crate) best_hash: B256,
+    /// The negotiated `eth` version of the session.
+    pub(crate) version: EthVersion,
     /// The capabilities of the remote peer.
     #[expect(dead_code)]
     pub(crate) capabilities: Arc<Capabilities>,
@@ -622,6 +677,7 @@ mod tests {
             Arc::default(),
             peer_tx,
             Arc::new(AtomicU64::new(1)),
+            Default::default(),
             None,
         );
 
@@ -13,6 +13,7 @@ use reth_eth_wire::{
 };
 use reth_network_api::{PeerRequest, PeerRequestSender};
 use reth_network_peers::PeerId;
+use reth_network_types::PeerScore;
 use std::{
     io,
     net::SocketAddr,
@@ -122,6 +123,7 @@ impl<N: NetworkPrimitives> Swarm<N> {
                 messages,
                 direction,
                 timeout,
+                score,
                 range_info,
             } => {
                 self.state.on_session_activated(
@@ -130,6 +132,7 @@ impl<N: NetworkPrimitives> Swarm<N> {
                     status.clone(),
                     messages.clone(),
                     timeout,
+                    Arc::clone(&score),
                     range_info,
                 );
                 Some(SwarmEvent::SessionEstablished {
@@ -141,6 +144,7 @@ impl<N: NetworkPrimitives> Swarm<N> {
                     messages,
                     status,
                     direction,
+                    score,
                 })
             }
             SessionEvent::AlreadyConnected { peer_id, remote_addr, direction } => {
@@ -387,6 +391,8 @@ pub(crate) enum SwarmEvent<N: NetworkPrimitives = EthNetworkPrimitives> {
         messages: PeerRequestSender<PeerRequest<N>>,
         status: Arc<UnifiedStatus>,
         direction: Direction,
+        /// Tracks the quality of the data the peer serves in this session.
+        score: Arc<PeerScore>,
     },
     SessionClosed {
         peer_id: PeerId,
@@ -21,7 +21,7 @@ use reth_eth_wire::{
 use reth_ethereum_primitives::{PooledTransactionVariant, TransactionSigned};
 use reth_network_api::{
     events::{PeerEvent, SessionInfo},
-    test_utils::{PeersHandle, PeersHandleProvider},
+    peers::{PeersHandle, PeersHandleProvider},
     NetworkEvent, NetworkEventListenerProvider, NetworkInfo, Peers,
 };
 use reth_network_peers::PeerId;
@@ -467,7 +467,8 @@ where
         let (tx, rx) = channel(ETH_REQUEST_CHANNEL_CAPACITY);
         self.network.set_eth_request_handler(tx);
         let peers = self.network.peers_handle();
-        let request_handler = EthRequestHandler::new(self.client.clone(), peers, rx);
+        let request_handler = EthRequestHandler::new(self.client.clone(), peers, rx)
+            .with_upload_limiter(self.network.handle().upload_limiter().clone());
         self.request_handler = Some(request_handler);
     }
 
@@ -1,7 +1,7 @@
 use std::{fmt::Debug, marker::PhantomData, str::FromStr};
 
 use super::{
-    PeerMetadata, DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
+    gossip::PropagationCandidate, PeerMetadata, DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
     DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
     SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
 };
@@ -133,6 +133,42 @@ pub trait TransactionPropagationPolicy: Send + Sync + Unpin + 'static {
 
     /// A callback on the policy when a peer session is closed.
     fn on_session_closed<N: NetworkPrimitives>(&mut self, peer: &mut PeerMetadata<N>);
+
+    /// Returns the priority of a transaction that is about to be propagated.
+    ///
+    /// Transactions with a higher priority are propagated first, which makes them more likely to
+    /// be sent in full, because full broadcasts are capped in size. Transactions with the same
+    /// priority keep their original order.
+    ///
+    /// By default, all transactions have the same priority.
+    fn propagation_priority(&self, _candidate: &PropagationCandidate) -> u128 {
+        0
+    }
+
+    /// Returns the number of bytes of full transactions that can currently be sent to this peer.
+    ///
+    /// Transactions that don't fit into the budget are announced as hashes instead.
+    ///
+    /// By default, this is unlimited.
+    fn full_transactions_budget<N: NetworkPrimitives>(
+        &mut self,
+        _peer: &mut PeerMetadata<N>,
+    ) -> Option<usize> {
+        None
+    }
+
+    /// A callback on the policy when full transactions of the given total size were sent to the
+    /// peer.
+    fn on_full_transactions_sent<N: NetworkPrimitives>(
+        &mut self,
+        _peer: &mut PeerMetadata<N>,
+        _bytes: usize,
+    ) {
+    }
+
+    /// A callback on the policy when the peer announced or sent junk, e.g. a malformed
+    /// announcement or invalid transactions.
+    fn on_junk_received<N: NetworkPrimitives>(&mut self, _peer: &mut PeerMetadata<N>) {}
 }
 
 /// Determines which peers pending transactions are propagated to.
@@ -14,6 +14,13 @@ pub const SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE:
 /// Default is 128 KiB.
 pub const DEFAULT_SOFT_LIMIT_BYTE_SIZE_TRANSACTIONS_BROADCAST_MESSAGE: usize = 128 * 1024;
 
+/// Upper bound for the byte size of a single entry in a
+/// [`NewPooledTransactionHashes`](reth_eth_wire::NewPooledTransactionHashes) broadcast message,
+/// which consists of the hash, the transaction type and the transaction size.
+///
+/// Used to account announcements against the upload limits.
+pub const MAX_BYTE_SIZE_HASH_ANNOUNCEMENT: usize = 33 + 2 + 5;
+
 /* ================ REQUEST-RESPONSE ================ */
 
 /// Recommended soft limit for the number of hashes in a
@@ -0,0 +1,272 @@
+//! Fee and bandwidth aware transaction propagation.
+//!
+//! The default [`TransactionPropagationKind`] only decides _which_ peers receive transactions.
+//! [`FeeAwarePropagationPolicy`] additionally
+//!
+//!  - ranks transactions by their effective tip per byte, so that the most valuable transactions
+//!    are the ones sent in full when a broadcast message fills up,
+//!  - rate limits full transaction broadcasts per peer with a token bucket, transactions that
+//!    exceed the peer's budget are announced as hashes instead, and
+//!  - withholds transactions from peers that repeatedly announced or sent junk.
+
+use super::{config::TransactionPropagationKind, PeerMetadata, TransactionPropagationPolicy};
+use alloy_primitives::TxHash;
+use reth_eth_wire::NetworkPrimitives;
+use std::time::{Duration, Instant};
+
+/// Scale applied to the effective tip before dividing by the transaction size, so that the
+/// priority of small transactions doesn't collapse to zero.
+const TIP_PER_BYTE_SCALE: u128 = 1024;
+
+/// Default window after which junk received from a peer is forgotten.
+pub const DEFAULT_JUNK_DECAY: Duration = Duration::from_secs(60 * 10);
+
+/// A transaction that is about to be propagated, as seen by a [`TransactionPropagationPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropagationCandidate {
+    /// Hash of the transaction.
+    pub hash: TxHash,
+    /// Encoded size of the transaction in bytes.
+    pub size: usize,
+    /// Tip per gas the transaction pays at the pending block's base fee, if it pays the base fee.
+    pub effective_tip: Option<u128>,
+}
+
+/// Upload budget for full transaction broadcasts to a single peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PeerBandwidthLimit {
+    /// Bytes per second the budget is refilled with.
+    pub bytes_per_second: u64,
+    /// Maximum number of bytes that can be accumulated and sent in a single burst.
+    pub burst_bytes: u64,
+}
+
+impl PeerBandwidthLimit {
+    /// Creates a new limit with the given rate, allowing bursts of one second worth of bytes.
+    pub const fn per_second(bytes_per_second: u64) -> Self {
+        Self { bytes_per_second, burst_bytes: bytes_per_second }
+    }
+
+    /// Sets the burst size.
+    pub const fn with_burst(mut self, burst_bytes: u64) -> Self {
+        self.burst_bytes = burst_bytes;
+        self
+    }
+}
+
+/// Configuration for the [`FeeAwarePropagationPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeAwarePropagationConfig {
+    /// Which peers transactions are propagated to at all.
+    pub peers: TransactionPropagationKind,
+    /// Whether transactions are ranked by effective tip per byte before they are propagated.
+    pub rank_by_effective_tip: bool,
+    /// Upload budget for full transaction broadcasts per peer, unlimited if `None`.
+    pub peer_bandwidth: Option<PeerBandwidthLimit>,
+    /// Number of junk announcements after which no more transactions are propagated to a peer,
+    /// disabled if `None`.
+    pub max_junk_announcements: Option<u32>,
+    /// Window after which junk received from a peer is forgotten.
+    pub junk_decay: Duration,
+}
+
+impl Default for FeeAwarePropagationConfig {
+    fn default() -> Self {
+        Self {
+            peers: TransactionPropagationKind::All,
+            rank_by_effective_tip: false,
+            peer_bandwidth: None,
+            max_junk_announcements: None,
+            junk_decay: DEFAULT_JUNK_DECAY,
+        }
+    }
+}
+
+/// A [`TransactionPropagationPolicy`] that ranks transactions by fee, rate limits broadcasts per
+/// peer and withholds transactions from peers that send junk.
+///
+/// With the default config this behaves exactly like the configured
+/// [`TransactionPropagationKind`].
+#[derive(Debug, Clone, Default)]
+pub struct FeeAwarePropagationPolicy {
+    config: FeeAwarePropagationConfig,
+}
+
+impl FeeAwarePropagationPolicy {
+    /// Creates a new policy with the given config.
+    pub const fn new(config: FeeAwarePropagationConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the config of this policy.
+    pub const fn config(&self) -> &FeeAwarePropagationConfig {
+        &self.config
+    }
+}
+
+impl From<TransactionPropagationKind> for FeeAwarePropagationPolicy {
+    fn from(peers: TransactionPropagationKind) -> Self {
+        Self::new(FeeAwarePropagationConfig { peers, ..Default::default() })
+    }
+}
+
+impl TransactionPropagationPolicy for FeeAwarePropagationPolicy {
+    fn can_propagate<N: NetworkPrimitives>(&self, peer: &mut PeerMetadata<N>) -> bool {
+        if !self.config.peers.can_propagate(peer) {
+            return false
+        }
+        if let Some(max_junk) = self.config.max_junk_announcements &&
+            peer.gossip_state().junk_count(Instant::now(), self.config.junk_decay) >= max_junk
+        {
+            return false
+        }
+        true
+    }
+
+    fn on_session_established<N: NetworkPrimitives>(&mut self, _peer: &mut PeerMetadata<N>) {}
+
+    fn on_session_closed<N: NetworkPrimitives>(&mut self, _peer: &mut PeerMetadata<N>) {}
+
+    fn propagation_priority(&self, candidate: &PropagationCandidate) -> u128 {
+        if !self.config.rank_by_effective_tip {
+            return 0
+        }
+        candidate.effective_tip.unwrap_or_default().saturating_mul(TIP_PER_BYTE_SCALE) /
+            candidate.size.max(1) as u128
+    }
+
+    fn full_transactions_budget<N: NetworkPrimitives>(
+        &mut self,
+        peer: &mut PeerMetadata<N>,
+    ) -> Option<usize> {
+        let limit = self.config.peer_bandwidth?;
+        Some(peer.gossip_state_mut().available_bytes(Instant::now(), &limit))
+    }
+
+    fn on_full_transactions_sent<N: NetworkPrimitives>(
+        &mut self,
+        peer: &mut PeerMetadata<N>,
+        bytes: usize,
+    ) {
+        if self.config.peer_bandwidth.is_some() {
+            peer.gossip_state_mut().consume_bytes(bytes);
+        }
+    }
+
+    fn on_junk_received<N: NetworkPrimitives>(&mut self, peer: &mut PeerMetadata<N>) {
+        if self.config.max_junk_announcements.is_some() {
+            peer.gossip_state_mut().record_junk(Instant::now(), self.config.junk_decay);
+        }
+    }
+}
+
+/// Per peer state used by [`TransactionPropagationPolicy`]s for gossip decisions.
+#[derive(Debug, Clone, Default)]
+pub struct PeerGossipState {
+    /// Bytes that can currently be sent to the peer in full transaction broadcasts.
+    available_bytes: u64,
+    /// When the byte budget was last refilled, `None` if it was never used.
+    last_refill: Option<Instant>,
+    /// Number of junk announcements received from the peer within the current window.
+    junk_count: u32,
+    /// When junk was last received from the peer.
+    last_junk: Option<Instant>,
+}
+
+impl PeerGossipState {
+    /// Refills the byte budget according to the given limit and returns the number of bytes that
+    /// can currently be sent.
+    pub fn available_bytes(&mut self, now: Instant, limit: &PeerBandwidthLimit) -> usize {
+        match self.last_refill {
+            None => self.available_bytes = limit.burst_bytes,
+            Some(last) => {
+                let refill = (now.saturating_duration_since(last).as_secs_f64() *
+                    limit.bytes_per_second as f64) as u64;
+                self.available_bytes =
+                    self.available_bytes.saturating_add(refill).min(limit.burst_bytes);
+            }
+        }
+        self.last_refill = Some(now);
+        self.available_bytes as usize
+    }
+
+    /// Deducts the given number of sent bytes from the budget.
+    pub const fn consume_bytes(&mut self, bytes: usize) {
+        self.available_bytes = self.available_bytes.saturating_sub(bytes as u64);
+    }
+
+    /// Records that the peer announced or sent junk.
+    pub fn record_junk(&mut self, now: Instant, decay: Duration) {
+        self.junk_count = self.junk_count(now, decay).saturating_add(1);
+        self.last_junk = Some(now);
+    }
+
+    /// Returns the number of junk announcements within the current window.
+    pub fn junk_count(&self, now: Instant, decay: Duration) -> u32 {
+        match self.last_junk {
+            Some(last) if now.saturating_duration_since(last) < decay => self.junk_count,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bandwidth_budget_refills() {
+        let limit = PeerBandwidthLimit::per_second(1000).with_burst(2000);
+        let mut state = PeerGossipState::default();
+        let now = Instant::now();
+
+        assert_eq!(state.available_bytes(now, &limit), 2000);
+        state.consume_bytes(1800);
+        assert_eq!(state.available_bytes(now, &limit), 200);
+
+        let later = now + Duration::from_millis(500);
+        assert_eq!(state.available_bytes(later, &limit), 700);
+
+        let much_later = later + Duration::from_secs(10);
+        assert_eq!(state.available_bytes(much_later, &limit), 2000);
+    }
+
+    #[test]
+    fn junk_decays() {
+        let decay = Duration::from_secs(60);
+        let mut state = PeerGossipState::default();
+        let now = Instant::now();
+
+        state.record_junk(now, decay);
+        state.record_junk(now, decay);
+        assert_eq!(state.junk_count(now, decay), 2);
+        assert_eq!(state.junk_count(now + decay, decay), 0);
+
+        state.record_junk(now + decay, decay);
+        assert_eq!(state.junk_count(now + decay, decay), 1);
+    }
+
+    #[test]
+    fn ranks_by_tip_per_byte() {
+        let policy = FeeAwarePropagationPolicy::new(FeeAwarePropagationConfig {
+            rank_by_effective_tip: true,
+            ..Default::default()
+        });
+        let cheap = PropagationCandidate { hash: TxHash::ZERO, size: 100, effective_tip: Some(1) };
+        let valuable =
+            PropagationCandidate { hash: TxHash::ZERO, size: 100, effective_tip: Some(10) };
+        let large =
+            PropagationCandidate { hash: TxHash::ZERO, size: 10_000, effective_tip: Some(10) };
+        let underpriced =
+            PropagationCandidate { hash: TxHash::ZERO, size: 100, effective_tip: None };
+
+        assert!(policy.propagation_priority(&valuable) > policy.propagation_priority(&cheap));
+        assert!(policy.propagation_priority(&cheap) > policy.propagation_priority(&large));
+        assert_eq!(policy.propagation_priority(&underpriced), 0);
+
+        let unranked = FeeAwarePropagationPolicy::default();
+        assert_eq!(unranked.propagation_priority(&valuable), 0);
+    }
+}
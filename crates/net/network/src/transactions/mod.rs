@@ -8,6 +8,8 @@ pub mod config;
 pub mod constants;
 /// Component responsible for fetching transactions from [`NewPooledTransactionHashes`].
 pub mod fetcher;
+/// Fee and bandwidth aware transaction propagation.
+pub mod gossip;
 /// Defines the [`TransactionPolicies`] trait for aggregating transaction-related policies.
 pub mod policy;
 
@@ -20,6 +22,7 @@ pub use config::{
     AnnouncementFilteringPolicy, TransactionFetcherConfig, TransactionPropagationMode,
     TransactionPropagationPolicy, TransactionsManagerConfig,
 };
+use gossip::{PeerGossipState, PropagationCandidate};
 use policy::{NetworkPolicies, TransactionPolicies};
 
 pub(crate) use fetcher::{FetchEvent, TransactionFetcher};
@@ -38,7 +41,11 @@ use crate::{
     NetworkHandle, TxTypesCounter,
 };
 use alloy_primitives::{TxHash, B256};
-use constants::SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE;
+use alloy_rlp::Encodable;
+use constants::{
+    MAX_BYTE_SIZE_HASH_ANNOUNCEMENT,
+    SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE,
+};
 use futures::{stream::FuturesUnordered, Future, StreamExt};
 use reth_eth_wire::{
     DedupPayload, EthNetworkPrimitives, EthVersion, GetPooledTransactions, HandleMempoolData,
@@ -461,6 +468,13 @@ impl<Pool: TransactionPool, N: NetworkPrimitives, PBundle: TransactionPolicies>
         self.network.reputation_change(peer_id, ReputationChangeKind::AlreadySeenTransaction);
     }
 
+    /// Notifies the propagation policy that the peer announced or sent junk.
+    fn on_junk_received(&mut self, peer_id: PeerId) {
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            self.policies.propagation_policy_mut().on_junk_received(peer);
+        }
+    }
+
     /// Clear the transaction
     fn on_good_import(&mut self, hash: TxHash) {
         self.transactions_by_peers.remove(&hash);
@@ -501,6 +515,7 @@ impl<Pool: TransactionPool, N: NetworkPrimitives, PBundle: TransactionPolicies>
         if let Some(peers) = peers {
             for peer_id in peers {
                 self.report_peer_bad_transactions(peer_id);
+                self.on_junk_received(peer_id);
             }
         }
         self.metrics.bad_imports.increment(1);
@@ -632,6 +647,7 @@ impl<Pool: TransactionPool, N: NetworkPrimitives, PBundle: TransactionPolicies>
         // 1. filter out spam
         if msg.is_empty() {
             self.report_peer(peer_id, ReputationChangeKind::BadAnnouncement);
+            self.on_junk_received(peer_id);
             return;
         }
 
@@ -640,6 +656,7 @@ impl<Pool: TransactionPool, N: NetworkPrimitives, PBundle: TransactionPolicies>
 
         if partially_valid_msg.len() != original_len {
             self.report_peer(peer_id, ReputationChangeKind::BadAnnouncement);
+            self.on_junk_received(peer_id);
         }
 
         // 2. filter out transactions pending import to pool
@@ -726,6 +743,7 @@ impl<Pool: TransactionPool, N: NetworkPrimitives, PBundle: TransactionPolicies>
 
         if should_report_peer {
             self.report_peer(peer_id, ReputationChangeKind::BadAnnouncement);
+            self.on_junk_received(peer_id);
         }
 
         let mut valid_announcement_data =
@@ -877,13 +895,25 @@ where
     ) -> Option<PropagatedTransactions> {
         trace!(target: "net::tx", ?peer_id, "Propagating transactions to peer");
 
+        let mut to_propagate: Vec<_> =
+            self.pool.get_all(txs).into_iter().map(PropagateTransaction::pool_tx).collect();
+        let base_fee = self.pool.block_info().pending_basefee;
+        sort_by_propagation_priority(self.policies.propagation_policy(), &mut to_propagate, |tx| {
+            tx.propagation_candidate(base_fee)
+        });
+
         let peer = self.peers.get_mut(&peer_id)?;
         let mut propagated = PropagatedTransactions::default();
 
         // filter all transactions unknown to the peer
         let mut full_transactions = FullTransactionsBuilder::new(peer.version);
-
-        let to_propagate = self.pool.get_all(txs).into_iter().map(PropagateTransaction::pool_tx);
+        let policy_budget = self.policies.propagation_policy_mut().full_transactions_budget(peer);
+        if let Some(budget) = full_transactions_budget(
+            policy_budget,
+            self.network.upload_limiter().available(&peer_id),
+        ) {
+            full_transactions = full_transactions.with_budget(budget);
+        }
 
         if propagation_mode.is_forced() {
             // skip cache check if forced
@@ -914,17 +944,25 @@ where
                 peer.seen_transactions.insert(hash);
             }
 
+            self.network
+                .upload_limiter()
+                .consume(&peer_id, new_pooled_hashes.len() * MAX_BYTE_SIZE_HASH_ANNOUNCEMENT);
+
             // send hashes of transactions
             self.network.send_transactions_hashes(peer_id, new_pooled_hashes);
         }
 
         // send full transactions, if any
         if let Some(new_full_transactions) = full {
+            let mut full_bytes = 0;
             for tx in &new_full_transactions {
                 propagated.0.entry(*tx.tx_hash()).or_default().push(PropagateKind::Full(peer_id));
                 // mark transaction as seen by peer
                 peer.seen_transactions.insert(*tx.tx_hash());
+                full_bytes += tx.length();
             }
+            self.policies.propagation_policy_mut().on_full_transactions_sent(peer, full_bytes);
+            self.network.upload_limiter().consume(&peer_id, full_bytes);
 
             // send full transactions
             self.network.send_transactions(peer_id, new_full_transactions);
@@ -1012,7 +1050,7 @@ where
     /// Note: EIP-4844 are disallowed from being broadcast in full and are only ever sent as hashes, see also <https://eips.ethereum.org/EIPS/eip-4844#networking>.
     fn propagate_transactions(
         &mut self,
-        to_propagate: Vec<PropagateTransaction<N::BroadcastedTransaction>>,
+        mut to_propagate: Vec<PropagateTransaction<N::BroadcastedTransaction>>,
         propagation_mode: PropagationMode,
     ) -> PropagatedTransactions {
         let mut propagated = PropagatedTransactions::default();
@@ -1020,6 +1058,12 @@ where
             return propagated
         }
 
+        // rank the transactions, so the most valuable ones are preferred for full broadcasts
+        let base_fee = self.pool.block_info().pending_basefee;
+        sort_by_propagation_priority(self.policies.propagation_policy(), &mut to_propagate, |tx| {
+            tx.propagation_candidate(base_fee)
+        });
+
         // send full transactions to a set of the connected peers based on the configured mode
         let max_num_full = self.config.propagation_mode.full_peer_count(self.peers.len());
 
@@ -1029,11 +1073,25 @@ where
                 // skip peers we should not propagate to
                 continue
             }
+            let upload_budget = self.network.upload_limiter().available(peer_id);
+            if upload_budget == Some(0) {
+                // the upload limit is reached, the peer learns about the transactions from others
+                continue
+            }
             // determine whether to send full tx objects or hashes.
             let mut builder = if peer_idx > max_num_full {
                 PropagateTransactionsBuilder::pooled(peer.version)
             } else {
-                PropagateTransactionsBuilder::full(peer.version)
+                let policy_budget =
+                    self.policies.propagation_policy_mut().full_transactions_budget(peer);
+                match full_transactions_budget(policy_budget, upload_budget) {
+                    // the peer's upload budget is exhausted, only announce hashes
+                    Some(0) => PropagateTransactionsBuilder::pooled(peer.version),
+                    Some(budget) => {
+                        PropagateTransactionsBuilder::full_with_budget(peer.version, budget)
+                    }
+                    None => PropagateTransactionsBuilder::full(peer.version),
+                }
             };
 
             if propagation_mode.is_forced() {
@@ -1073,12 +1131,17 @@ where
 
                 trace!(target: "net::tx", ?peer_id, num_txs=?new_pooled_hashes.len(), "Propagating tx hashes to peer");
 
+                self.network
+                    .upload_limiter()
+                    .consume(peer_id, new_pooled_hashes.len() * MAX_BYTE_SIZE_HASH_ANNOUNCEMENT);
+
                 // send hashes of transactions
                 self.network.send_transactions_hashes(*peer_id, new_pooled_hashes);
             }
 
             // send full transactions, if any
             if let Some(new_full_transactions) = full {
+                let mut full_bytes = 0;
                 for tx in &new_full_transactions {
                     propagated
                         .0
@@ -1087,7 +1150,10 @@ where
                         .push(PropagateKind::Full(*peer_id));
                     // mark transaction as seen by peer
                     peer.seen_transactions.insert(*tx.tx_hash());
+                    full_bytes += tx.length();
                 }
+                self.policies.propagation_policy_mut().on_full_transactions_sent(peer, full_bytes);
+                self.network.upload_limiter().consume(peer_id, full_bytes);
 
                 trace!(target: "net::tx", ?peer_id, num_txs=?new_full_transactions.len(), "Propagating full transactions to peer");
 
@@ -1132,11 +1198,19 @@ where
                 let _ = response.send(Ok(PooledTransactions::default()));
                 return
             }
+            let mut response_limit =
+                self.transaction_fetcher.info.soft_limit_byte_size_pooled_transactions_response;
+            if let Some(upload_budget) = self.network.upload_limiter().available(&peer_id) {
+                if upload_budget == 0 {
+                    // the upload limit is reached
+                    let _ = response.send(Ok(PooledTransactions::default()));
+                    return
+                }
+                response_limit = response_limit.min(upload_budget);
+            }
             let transactions = self.pool.get_pooled_transaction_elements(
                 request.0,
-                GetPooledTransactionLimit::ResponseSizeSoftLimit(
-                    self.transaction_fetcher.info.soft_limit_byte_size_pooled_transactions_response,
-                ),
+                GetPooledTransactionLimit::ResponseSizeSoftLimit(response_limit),
             );
             trace!(target: "net::tx::propagation", sent_txs=?transactions.iter().map(|tx| tx.tx_hash()), "Sending requested transactions to peer");
 
@@ -1145,6 +1219,9 @@ where
             peer.seen_transactions.extend(transactions.iter().map(|tx| *tx.tx_hash()));
 
             let resp = PooledTransactions(transactions);
+            if self.network.upload_limiter().is_enabled() {
+                self.network.upload_limiter().consume(&peer_id, resp.length());
+            }
             let _ = response.send(Ok(resp));
         }
     }
@@ -1230,13 +1307,21 @@ where
         }
 
         // Get transactions to broadcast
-        let pooled_txs = self.pool.pooled_transactions_max(
+        let mut pooled_txs = self.pool.pooled_transactions_max(
             SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE,
         );
         if pooled_txs.is_empty() {
             trace!(target: "net::tx", ?peer_id, "No transactions in the pool to broadcast");
             return;
         }
+        let base_fee = self.pool.block_info().pending_basefee;
+        sort_by_propagation_priority(self.policies.propagation_policy(), &mut pooled_txs, |tx| {
+            PropagationCandidate {
+                hash: *tx.hash(),
+                size: tx.encoded_length(),
+                effective_tip: tx.effective_tip_per_gas(base_fee),
+            }
+        });
 
         // Build and send transaction hashes message
         let mut msg_builder = PooledTransactionsHashesBuilder::new(version);
@@ -1688,6 +1773,18 @@ impl<T: SignedTransaction> PropagateTransaction<T> {
         Self { size, transaction: Arc::new(transaction) }
     }
 
+    /// Returns the candidate the propagation policy ranks this transaction by.
+    fn propagation_candidate(&self, base_fee: u64) -> PropagationCandidate {
+        PropagationCandidate {
+            hash: *self.tx_hash(),
+            size: self.size,
+            effective_tip: alloy_consensus::Transaction::effective_tip_per_gas(
+                &*self.transaction,
+                base_fee,
+            ),
+        }
+    }
+
     /// Create a new instance from a pooled transaction
     fn pool_tx<P>(tx: Arc<ValidPoolTransaction<P>>) -> Self
     where
@@ -1723,6 +1820,12 @@ impl<T> PropagateTransactionsBuilder<T> {
         Self::Full(FullTransactionsBuilder::new(version))
     }
 
+    /// Create a builder that sends at most `budget` bytes of transactions in full and records
+    /// transactions that don't fit.
+    fn full_with_budget(version: EthVersion, budget: usize) -> Self {
+        Self::Full(FullTransactionsBuilder::new(version).with_budget(budget))
+    }
+
     /// Returns true if no transactions are recorded.
     fn is_empty(&self) -> bool {
         match self {
@@ -1775,6 +1878,8 @@ struct PropagateTransactions<T> {
 struct FullTransactionsBuilder<T> {
     /// The soft limit to enforce for a single broadcast message of full transactions.
     total_size: usize,
+    /// Hard limit for the total size of full transactions, e.g. the peer's upload budget.
+    budget: Option<usize>,
     /// All transactions to be broadcasted.
     transactions: Vec<Arc<T>>,
     /// Transactions that didn't fit into the broadcast message
@@ -1786,11 +1891,20 @@ impl<T> FullTransactionsBuilder<T> {
     fn new(version: EthVersion) -> Self {
         Self {
             total_size: 0,
+            budget: None,
             pooled: PooledTransactionsHashesBuilder::new(version),
             transactions: vec![],
         }
     }
 
+    /// Sets a hard limit for the total size of full transactions.
+    ///
+    /// Unlike the soft limit, a single transaction that exceeds the budget is never sent in full.
+    const fn with_budget(mut self, budget: usize) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
     /// Returns whether or not any transactions are in the [`FullTransactionsBuilder`].
     fn is_empty(&self) -> bool {
         self.transactions.is_empty() && self.pooled.is_empty()
@@ -1836,8 +1950,9 @@ impl<T: SignedTransaction> FullTransactionsBuilder<T> {
         }
 
         let new_size = self.total_size + transaction.size;
-        if new_size > DEFAULT_SOFT_LIMIT_BYTE_SIZE_TRANSACTIONS_BROADCAST_MESSAGE &&
-            self.total_size > 0
+        if (new_size > DEFAULT_SOFT_LIMIT_BYTE_SIZE_TRANSACTIONS_BROADCAST_MESSAGE &&
+            self.total_size > 0) ||
+            self.budget.is_some_and(|budget| new_size > budget)
         {
             // transaction does not fit into the message
             self.pooled.push(transaction);
@@ -1849,6 +1964,24 @@ impl<T: SignedTransaction> FullTransactionsBuilder<T> {
     }
 }
 
+/// Sorts the transactions by the priority the propagation policy assigns to them, highest first.
+fn sort_by_propagation_priority<T>(
+    policy: &impl TransactionPropagationPolicy,
+    txs: &mut [T],
+    candidate: impl Fn(&T) -> PropagationCandidate,
+) {
+    txs.sort_by_cached_key(|tx| std::cmp::Reverse(policy.propagation_priority(&candidate(tx))));
+}
+
+/// Returns the number of bytes of full transactions that can be sent to a peer, given the budget
+/// of the propagation policy and the upload limit of the peer.
+fn full_transactions_budget(policy: Option<usize>, upload: Option<usize>) -> Option<usize> {
+    match (policy, upload) {
+        (Some(policy), Some(upload)) => Some(policy.min(upload)),
+        (policy, upload) => policy.or(upload),
+    }
+}
+
 /// A helper type to create the pooled transactions message based on the negotiated version of the
 /// session with the peer
 #[derive(Debug, Clone)]
@@ -1905,7 +2038,9 @@ impl PooledTransactionsHashesBuilder {
     fn new(version: EthVersion) -> Self {
         match version {
             EthVersion::Eth66 | EthVersion::Eth67 => Self::Eth66(Default::default()),
-            EthVersion::Eth68 | EthVersion::Eth69 => Self::Eth68(Default::default()),
+            EthVersion::Eth68 | EthVersion::Eth69 | EthVersion::Eth70 => {
+                Self::Eth68(Default::default())
+            }
         }
     }
 
@@ -1949,6 +2084,8 @@ pub struct PeerMetadata<N: NetworkPrimitives = EthNetworkPrimitives> {
     client_version: Arc<str>,
     /// The kind of peer.
     peer_kind: PeerKind,
+    /// State tracked by the propagation policy.
+    gossip_state: PeerGossipState,
 }
 
 impl<N: NetworkPrimitives> PeerMetadata<N> {
@@ -1966,6 +2103,7 @@ impl<N: NetworkPrimitives> PeerMetadata<N> {
             version,
             client_version,
             peer_kind,
+            gossip_state: PeerGossipState::default(),
         }
     }
 
@@ -1993,6 +2131,16 @@ impl<N: NetworkPrimitives> PeerMetadata<N> {
     pub const fn peer_kind(&self) -> PeerKind {
         self.peer_kind
     }
+
+    /// Returns the state tracked by the propagation policy for this peer.
+    pub const fn gossip_state(&self) -> &PeerGossipState {
+        &self.gossip_state
+    }
+
+    /// Returns a mutable reference to the state tracked by the propagation policy for this peer.
+    pub const fn gossip_state_mut(&mut self) -> &mut PeerGossipState {
+        &mut self.gossip_state
+    }
 }
 
 /// Commands to send to the [`TransactionsManager`]
@@ -2790,6 +2938,37 @@ mod tests {
         assert_eq!(txs.len(), 1);
     }
 
+    #[test]
+    fn test_transaction_builder_budget() {
+        let mut factory = MockTransactionFactory::default();
+        let txs = (0..3)
+            .map(|_| {
+                let mut tx = factory.create_eip1559();
+                tx.transaction.set_size(100);
+                PropagateTransaction::pool_tx(Arc::new(tx))
+            })
+            .collect::<Vec<_>>();
+
+        // only two transactions fit into the budget, the third one is announced as hash
+        let mut builder =
+            FullTransactionsBuilder::<TransactionSigned>::new(EthVersion::Eth68).with_budget(250);
+        builder.extend(txs.clone());
+        let PropagateTransactions { pooled, full } = builder.build();
+        let full = full.unwrap();
+        assert_eq!(full.len(), 2);
+        let pooled = pooled.unwrap();
+        assert_eq!(pooled.len(), 1);
+        assert_eq!(pooled.iter_hashes().next(), Some(txs[2].tx_hash()));
+
+        // an exhausted budget announces all transactions as hashes
+        let mut builder =
+            FullTransactionsBuilder::<TransactionSigned>::new(EthVersion::Eth68).with_budget(0);
+        builder.extend(txs);
+        let PropagateTransactions { pooled, full } = builder.build();
+        assert!(full.is_none());
+        assert_eq!(pooled.unwrap().len(), 3);
+    }
+
     #[test]
     fn test_transaction_builder_eip4844() {
         let mut builder =
@@ -13,6 +13,7 @@ use reth_network_api::{NetworkInfo, Peers};
 use reth_network_p2p::{
     bodies::client::BodiesClient,
     headers::client::{HeadersClient, HeadersRequest},
+    receipts::client::ReceiptsClient,
 };
 use reth_provider::test_utils::MockEthProvider;
 use reth_transaction_pool::test_utils::{TestPool, TransactionGenerator};
@@ -526,3 +527,77 @@ async fn test_eth69_get_receipts() {
         assert_eq!(receipts_response.0[0][1].cumulative_gas_used, 42000);
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_eth70_get_receipts() {
+    reth_tracing::init_test_tracing();
+    let mut rng = rand::rng();
+    let mock_provider = Arc::new(MockEthProvider::default());
+
+    let mut net: Testnet<Arc<MockEthProvider>, TestPool> = Testnet::default();
+
+    // Create peers with ETH70 protocol
+    let p0 = PeerConfig::with_protocols(mock_provider.clone(), Some(EthVersion::Eth70.into()));
+    net.add_peer_with_config(p0).await.unwrap();
+
+    let p1 = PeerConfig::with_protocols(mock_provider.clone(), Some(EthVersion::Eth70.into()));
+    net.add_peer_with_config(p1).await.unwrap();
+
+    // install request handlers
+    net.for_each_mut(|peer| peer.install_request_handler());
+
+    let handle0 = net.peers()[0].handle();
+    let mut events0 = NetworkEventStream::new(handle0.event_listener());
+
+    let handle1 = net.peers()[1].handle();
+
+    let _handle = net.spawn();
+
+    let fetch0 = handle0.fetch_client().await.unwrap();
+
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+
+    // Wait for the session to be established
+    let connected = events0.next_session_established().await.unwrap();
+    assert_eq!(connected, *handle1.peer_id());
+
+    let block_hash = rng.random();
+    let header = Header { number: 1, ..Default::default() };
+    let receipts = vec![
+        reth_ethereum_primitives::Receipt {
+            cumulative_gas_used: 21000,
+            success: true,
+            ..Default::default()
+        },
+        reth_ethereum_primitives::Receipt {
+            cumulative_gas_used: 42000,
+            success: false,
+            ..Default::default()
+        },
+    ];
+    mock_provider.add_header(block_hash, header.clone());
+    mock_provider.add_receipts(header.number, receipts.clone());
+
+    // request the receipts of the block starting at the second receipt
+    let (tx, rx) = oneshot::channel();
+    handle0.send_request(
+        *handle1.peer_id(),
+        reth_network::PeerRequest::GetReceipts70 {
+            request: reth_eth_wire::GetReceipts70 {
+                first_block_receipt_index: 1,
+                block_hashes: vec![block_hash],
+            },
+            response: tx,
+        },
+    );
+
+    let receipts_response = rx.await.unwrap().unwrap();
+    assert!(!receipts_response.last_block_incomplete);
+    assert_eq!(receipts_response.receipts.len(), 1);
+    assert_eq!(receipts_response.receipts[0].len(), 1);
+    assert_eq!(receipts_response.receipts[0][0].cumulative_gas_used, 42000);
+
+    // the fetch client requests the receipts with the negotiated version
+    let res = fetch0.get_receipts(vec![block_hash]).await.unwrap();
+    assert_eq!(res.1, vec![receipts]);
+}
@@ -39,6 +39,9 @@ pub mod error;
 /// Priority enum for `BlockHeader` and `BlockBody` requests
 pub mod priority;
 
+/// Traits for implementing P2P receipt clients.
+pub mod receipts;
+
 /// Syncing related traits.
 pub mod sync;
 
@@ -51,6 +54,7 @@ pub mod test_utils;
 
 pub use bodies::client::BodiesClient;
 pub use headers::client::HeadersClient;
+pub use receipts::client::ReceiptsClient;
 use reth_primitives_traits::Block;
 
 /// Helper trait that unifies network behaviour needed for fetching entire blocks.
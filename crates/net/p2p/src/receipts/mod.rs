@@ -0,0 +1,2 @@
+/// Traits for receipt clients.
+pub mod client;
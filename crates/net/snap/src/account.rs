@@ -0,0 +1,88 @@
+//! The slim account format of the `snap/1` protocol.
+
+use alloy_primitives::{Bytes, B256, KECCAK_EMPTY, U256};
+use alloy_rlp::{Decodable, Encodable, Header};
+use reth_primitives_traits::Account;
+use reth_trie::{TrieAccount, EMPTY_ROOT_HASH};
+
+/// Returns the account in the slim format of the `snap/1` protocol.
+///
+/// This is the RLP encoded trie account, except that an empty storage root and the empty code hash
+/// are encoded as empty strings.
+pub fn slim_account_body(account: &Account, storage_root: B256) -> Bytes {
+    let storage_root: &[u8] =
+        if storage_root == EMPTY_ROOT_HASH { &[] } else { storage_root.as_slice() };
+    let code_hash = account.get_bytecode_hash();
+    let code_hash: &[u8] = if code_hash == KECCAK_EMPTY { &[] } else { code_hash.as_slice() };
+
+    let payload_length = account.nonce.length() +
+        account.balance.length() +
+        storage_root.length() +
+        code_hash.length();
+    let mut buf = Vec::with_capacity(payload_length + alloy_rlp::length_of_length(payload_length));
+    Header { list: true, payload_length }.encode(&mut buf);
+    account.nonce.encode(&mut buf);
+    account.balance.encode(&mut buf);
+    storage_root.encode(&mut buf);
+    code_hash.encode(&mut buf);
+    buf.into()
+}
+
+/// Decodes an account in the slim format of the `snap/1` protocol.
+pub fn decode_slim_account(mut body: &[u8]) -> alloy_rlp::Result<TrieAccount> {
+    let buf = &mut body;
+    let header = Header::decode(buf)?;
+    if !header.list {
+        return Err(alloy_rlp::Error::UnexpectedString)
+    }
+    let started_len = buf.len();
+
+    let nonce = u64::decode(buf)?;
+    let balance = U256::decode(buf)?;
+    let storage_root = decode_optional_hash(buf)?.unwrap_or(EMPTY_ROOT_HASH);
+    let code_hash = decode_optional_hash(buf)?.unwrap_or(KECCAK_EMPTY);
+
+    if started_len - buf.len() != header.payload_length {
+        return Err(alloy_rlp::Error::ListLengthMismatch {
+            expected: header.payload_length,
+            got: started_len - buf.len(),
+        })
+    }
+
+    Ok(TrieAccount { nonce, balance, storage_root, code_hash })
+}
+
+/// Decodes a hash that is encoded as empty string if it's the default.
+fn decode_optional_hash(buf: &mut &[u8]) -> alloy_rlp::Result<Option<B256>> {
+    let bytes = Header::decode_bytes(buf, false)?;
+    match bytes.len() {
+        0 => Ok(None),
+        32 => Ok(Some(B256::from_slice(bytes))),
+        _ => Err(alloy_rlp::Error::UnexpectedLength),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slim_account_encoding() {
+        let account = Account { nonce: 1, balance: U256::from(2), bytecode_hash: None };
+        let body = slim_account_body(&account, EMPTY_ROOT_HASH);
+        assert_eq!(&body[..], &[0xc4, 0x01, 0x02, 0x80, 0x80]);
+        let decoded = decode_slim_account(&body).unwrap();
+        assert_eq!(decoded, account.into_trie_account(EMPTY_ROOT_HASH));
+
+        let code_hash = B256::repeat_byte(0x11);
+        let storage_root = B256::repeat_byte(0x22);
+        let account = Account { bytecode_hash: Some(code_hash), ..account };
+        let body = slim_account_body(&account, storage_root);
+        let full = TrieAccount::decode(&mut &body[..]).unwrap();
+        assert_eq!(full.storage_root, storage_root);
+        assert_eq!(full.code_hash, code_hash);
+        assert_eq!(decode_slim_account(&body).unwrap(), full);
+
+        assert!(decode_slim_account(&[0xc3, 0x01, 0x02, 0x80]).is_err());
+    }
+}
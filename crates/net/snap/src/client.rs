@@ -0,0 +1,222 @@
+//! A [`SnapClient`] that sends requests to the peers connected via the `snap/1` sub-protocol.
+
+use crate::server::SnapRequest;
+use parking_lot::RwLock;
+use reth_eth_wire_types::snap::{
+    GetAccountRangeMessage, GetByteCodesMessage, GetStorageRangesMessage, GetTrieNodesMessage,
+};
+use reth_network_api::{test_utils::PeersHandle, PeerId};
+use reth_network_p2p::{
+    download::DownloadClient,
+    error::{PeerRequestResult, RequestError, RequestResult},
+    priority::Priority,
+    snap::client::{SnapClient, SnapResponse},
+};
+use reth_network_peers::WithPeerId;
+use reth_network_types::ReputationChangeKind;
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::{sleep, Sleep},
+};
+
+/// Default timeout for a single `snap/1` request.
+pub const DEFAULT_SNAP_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A request that is sent to a peer.
+#[derive(Debug)]
+pub(crate) struct OutgoingSnapRequest {
+    /// The request, its ID is assigned by the connection.
+    pub(crate) request: SnapRequest,
+    /// The channel sender for the response.
+    pub(crate) response: oneshot::Sender<RequestResult<SnapResponse>>,
+}
+
+/// The connected peers that support `snap/1`.
+///
+/// Connections register themselves once the protocol was negotiated and are removed when the
+/// connection closes.
+#[derive(Debug, Clone, Default)]
+pub struct SnapPeers {
+    peers: Arc<RwLock<HashMap<PeerId, mpsc::UnboundedSender<OutgoingSnapRequest>>>>,
+    /// Used to rotate requests across peers.
+    next_peer: Arc<AtomicUsize>,
+}
+
+impl SnapPeers {
+    /// Returns the number of connected peers.
+    pub fn len(&self) -> usize {
+        self.peers.read().len()
+    }
+
+    /// Returns `true` if no peer is connected.
+    pub fn is_empty(&self) -> bool {
+        self.peers.read().is_empty()
+    }
+
+    /// Returns the IDs of the connected peers.
+    pub fn peer_ids(&self) -> Vec<PeerId> {
+        self.peers.read().keys().copied().collect()
+    }
+
+    /// Registers the connection to the peer.
+    pub(crate) fn insert(&self, peer_id: PeerId, tx: mpsc::UnboundedSender<OutgoingSnapRequest>) {
+        self.peers.write().insert(peer_id, tx);
+    }
+
+    /// Removes the connection to the peer, unless it was replaced by a new connection.
+    pub(crate) fn remove(&self, peer_id: &PeerId, tx: &mpsc::UnboundedSender<OutgoingSnapRequest>) {
+        let mut peers = self.peers.write();
+        if peers.get(peer_id).is_some_and(|current| current.same_channel(tx)) {
+            peers.remove(peer_id);
+        }
+    }
+
+    /// Sends the request to the next peer, returns the peer it was sent to.
+    fn send(&self, request: SnapRequest) -> Result<(PeerId, SnapResponseReceiver), RequestError> {
+        let peers = self.peers.read();
+        if peers.is_empty() {
+            return Err(RequestError::UnsupportedCapability)
+        }
+
+        let idx = self.next_peer.fetch_add(1, Ordering::Relaxed) % peers.len();
+        let (peer_id, tx) = peers.iter().nth(idx).expect("index in bounds");
+        let (response, rx) = oneshot::channel();
+        tx.send(OutgoingSnapRequest { request, response })
+            .map_err(|_| RequestError::ConnectionDropped)?;
+        Ok((*peer_id, rx))
+    }
+}
+
+type SnapResponseReceiver = oneshot::Receiver<RequestResult<SnapResponse>>;
+
+/// Sends `snap/1` requests to the connected [`SnapPeers`].
+///
+/// Requests are distributed across peers in turn. Request IDs are assigned by the connections, so
+/// the IDs of the request messages are ignored.
+#[derive(Debug, Clone)]
+pub struct SnapNetworkClient {
+    peers: SnapPeers,
+    /// Used to penalize peers that sent bad responses.
+    peers_handle: PeersHandle,
+    request_timeout: Duration,
+}
+
+impl SnapNetworkClient {
+    /// Creates a new client for the given peers.
+    pub const fn new(peers: SnapPeers, peers_handle: PeersHandle) -> Self {
+        Self { peers, peers_handle, request_timeout: DEFAULT_SNAP_REQUEST_TIMEOUT }
+    }
+
+    /// Sets the timeout for a single request.
+    pub const fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Returns the connected peers.
+    pub const fn peers(&self) -> &SnapPeers {
+        &self.peers
+    }
+
+    fn request(&self, request: SnapRequest) -> SnapResponseFuture {
+        let state = self.peers.send(request);
+        SnapResponseFuture { state, timeout: Box::pin(sleep(self.request_timeout)) }
+    }
+}
+
+impl DownloadClient for SnapNetworkClient {
+    fn report_bad_message(&self, peer_id: PeerId) {
+        self.peers_handle.reputation_change(peer_id, ReputationChangeKind::BadMessage);
+    }
+
+    fn num_connected_peers(&self) -> usize {
+        self.peers.len()
+    }
+}
+
+impl SnapClient for SnapNetworkClient {
+    type Output = SnapResponseFuture;
+
+    fn get_account_range_with_priority(
+        &self,
+        request: GetAccountRangeMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.request(SnapRequest::GetAccountRange(request))
+    }
+
+    fn get_storage_ranges(&self, request: GetStorageRangesMessage) -> Self::Output {
+        self.get_storage_ranges_with_priority(request, Priority::Normal)
+    }
+
+    fn get_storage_ranges_with_priority(
+        &self,
+        request: GetStorageRangesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.request(SnapRequest::GetStorageRanges(request))
+    }
+
+    fn get_byte_codes(&self, request: GetByteCodesMessage) -> Self::Output {
+        self.get_byte_codes_with_priority(request, Priority::Normal)
+    }
+
+    fn get_byte_codes_with_priority(
+        &self,
+        request: GetByteCodesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.request(SnapRequest::GetByteCodes(request))
+    }
+
+    fn get_trie_nodes(&self, request: GetTrieNodesMessage) -> Self::Output {
+        self.get_trie_nodes_with_priority(request, Priority::Normal)
+    }
+
+    fn get_trie_nodes_with_priority(
+        &self,
+        request: GetTrieNodesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.request(SnapRequest::GetTrieNodes(request))
+    }
+}
+
+/// The response of a peer to a request sent by the [`SnapNetworkClient`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct SnapResponseFuture {
+    state: Result<(PeerId, SnapResponseReceiver), RequestError>,
+    timeout: Pin<Box<Sleep>>,
+}
+
+impl Future for SnapResponseFuture {
+    type Output = PeerRequestResult<SnapResponse>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let (peer_id, rx) = match &mut this.state {
+            Ok(state) => state,
+            Err(err) => return Poll::Ready(Err(err.clone())),
+        };
+
+        if let Poll::Ready(response) = Pin::new(rx).poll(cx) {
+            let response = response.map_err(|_| RequestError::ConnectionDropped)?;
+            return Poll::Ready(response.map(|response| WithPeerId::new(*peer_id, response)))
+        }
+
+        ready!(this.timeout.as_mut().poll(cx));
+        Poll::Ready(Err(RequestError::Timeout))
+    }
+}
@@ -0,0 +1,32 @@
+//! Support for the `snap/1` state sync protocol.
+//!
+//! The [`SnapRequestHandler`] answers the requests of peers from the hashed state and trie tables
+//! of the latest persisted block. Peers negotiate the protocol via the [`SnapProtocolHandler`],
+//! which must be added to the network as `RLPx` sub-protocol alongside `eth`.
+//!
+//! The protocol handler also tracks the connected [`SnapPeers`], which the [`SnapNetworkClient`]
+//! sends requests to. The [`SnapSyncStage`] uses it to download the state of a pivot block instead
+//! of executing all blocks.
+//!
+//! See also <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+pub mod account;
+pub mod client;
+mod metrics;
+pub mod protocol;
+pub mod server;
+pub mod sync;
+
+pub use account::{decode_slim_account, slim_account_body};
+pub use client::{SnapNetworkClient, SnapPeers};
+pub use protocol::{snap_capability, snap_protocol, SnapProtocolHandler};
+pub use server::{IncomingSnapRequest, SnapRequest, SnapRequestHandler};
+pub use sync::{SnapSyncConfig, SnapSyncEvent, SnapSyncStage, SnapSyncer};
@@ -0,0 +1,52 @@
+use reth_metrics::{
+    metrics::{Counter, Gauge, Histogram},
+    Metrics,
+};
+
+/// Metrics for the [`SnapRequestHandler`](crate::SnapRequestHandler).
+#[derive(Metrics)]
+#[metrics(scope = "network.snap")]
+pub(crate) struct SnapServerMetrics {
+    /// Number of received `GetAccountRange` requests
+    pub(crate) account_range_requests_total: Counter,
+    /// Number of received `GetStorageRanges` requests
+    pub(crate) storage_ranges_requests_total: Counter,
+    /// Number of received `GetByteCodes` requests
+    pub(crate) byte_codes_requests_total: Counter,
+    /// Number of received `GetTrieNodes` requests
+    pub(crate) trie_nodes_requests_total: Counter,
+    /// Number of requests for a state root that isn't available
+    pub(crate) unavailable_root_total: Counter,
+    /// Number of requests served from the state of a block below the latest persisted block
+    pub(crate) historical_root_total: Counter,
+    /// Number of requests that failed with a database error
+    pub(crate) failed_requests_total: Counter,
+    /// Size of the served responses in bytes
+    pub(crate) response_bytes: Histogram,
+    /// Time spent serving a single request
+    pub(crate) serve_duration_seconds: Histogram,
+}
+
+/// Metrics for the [`SnapSyncer`](crate::SnapSyncer).
+#[derive(Metrics)]
+#[metrics(scope = "sync.snap")]
+pub(crate) struct SnapSyncMetrics {
+    /// Number of sent requests
+    pub(crate) requests_total: Counter,
+    /// Number of requests that failed or timed out
+    pub(crate) failed_requests_total: Counter,
+    /// Number of responses that didn't contain the requested state
+    pub(crate) empty_responses_total: Counter,
+    /// Number of responses that failed verification
+    pub(crate) invalid_responses_total: Counter,
+    /// Number of in-flight requests
+    pub(crate) inflight_requests: Gauge,
+    /// Number of downloaded accounts
+    pub(crate) accounts_total: Counter,
+    /// Number of downloaded storage slots
+    pub(crate) storage_slots_total: Counter,
+    /// Number of downloaded bytecodes
+    pub(crate) bytecodes_total: Counter,
+    /// Number of downloaded trie nodes
+    pub(crate) trie_nodes_total: Counter,
+}
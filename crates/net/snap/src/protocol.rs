@@ -0,0 +1,287 @@
+//! The `snap/1` `RLPx` sub-protocol.
+//!
+//! Requests received on the protocol connection are forwarded to the
+//! [`SnapRequestHandler`](crate::SnapRequestHandler) and its responses are sent back to the peer.
+//! Established connections are registered in the [`SnapPeers`], through which the
+//! [`SnapNetworkClient`](crate::SnapNetworkClient) sends its own requests.
+
+use crate::{
+    client::{OutgoingSnapRequest, SnapPeers},
+    server::{response_message, IncomingSnapRequest, SnapRequest},
+};
+use alloy_primitives::bytes::BytesMut;
+use futures::{stream::FuturesUnordered, Stream, StreamExt};
+use reth_eth_wire::{
+    capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol, Capability,
+};
+use reth_eth_wire_types::snap::SnapProtocolMessage;
+use reth_network::protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler};
+use reth_network_api::{Direction, PeerId};
+use reth_network_p2p::{error::RequestResult, snap::client::SnapResponse};
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::{debug, trace};
+
+/// Number of messages of the `snap/1` protocol.
+pub const SNAP_PROTOCOL_MESSAGE_COUNT: u8 = 8;
+
+/// Maximum number of requests of a single peer that are served concurrently.
+///
+/// Additional requests are dropped until responses were sent.
+pub const MAX_CONCURRENT_SNAP_REQUESTS_PER_PEER: usize = 8;
+
+/// Returns the `snap/1` capability.
+pub const fn snap_capability() -> Capability {
+    Capability::new_static("snap", 1)
+}
+
+/// Returns the `snap/1` protocol.
+pub const fn snap_protocol() -> Protocol {
+    Protocol::new(snap_capability(), SNAP_PROTOCOL_MESSAGE_COUNT)
+}
+
+/// Announces the `snap/1` protocol to peers, serves their requests and sends our own requests.
+///
+/// Requests of peers are ignored if the handler was created without a request channel.
+#[derive(Debug, Clone, Default)]
+pub struct SnapProtocolHandler {
+    /// Sends incoming requests to the [`SnapRequestHandler`](crate::SnapRequestHandler).
+    requests: Option<mpsc::Sender<IncomingSnapRequest>>,
+    /// The established connections.
+    peers: SnapPeers,
+}
+
+impl SnapProtocolHandler {
+    /// Creates a new handler that forwards requests to the given channel.
+    pub fn new(requests: mpsc::Sender<IncomingSnapRequest>) -> Self {
+        Self { requests: Some(requests), peers: Default::default() }
+    }
+
+    /// Sets the registry of the established connections.
+    pub fn with_peers(mut self, peers: SnapPeers) -> Self {
+        self.peers = peers;
+        self
+    }
+
+    /// Returns the established connections.
+    pub const fn peers(&self) -> &SnapPeers {
+        &self.peers
+    }
+
+    fn connection_handler(&self) -> SnapConnectionHandler {
+        SnapConnectionHandler { requests: self.requests.clone(), peers: self.peers.clone() }
+    }
+}
+
+impl ProtocolHandler for SnapProtocolHandler {
+    type ConnectionHandler = SnapConnectionHandler;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+}
+
+/// Negotiates the `snap/1` protocol for a single connection.
+#[derive(Debug)]
+pub struct SnapConnectionHandler {
+    requests: Option<mpsc::Sender<IncomingSnapRequest>>,
+    peers: SnapPeers,
+}
+
+impl ConnectionHandler for SnapConnectionHandler {
+    type Connection = SnapConnection;
+
+    fn protocol(&self) -> Protocol {
+        snap_protocol()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        let (commands_tx, commands) = mpsc::unbounded_channel();
+        self.peers.insert(peer_id, commands_tx.clone());
+        SnapConnection {
+            peer_id,
+            conn,
+            requests: self.requests,
+            pending_responses: FuturesUnordered::new(),
+            peers: self.peers,
+            commands_tx,
+            commands: UnboundedReceiverStream::new(commands),
+            inflight_requests: HashMap::default(),
+            next_request_id: 0,
+        }
+    }
+}
+
+/// A `snap/1` connection to a peer.
+///
+/// Yields the encoded responses to the peer's requests and our own requests, the connection is
+/// closed once the peer sends a message that can't be decoded.
+#[must_use = "Connection does nothing unless polled"]
+pub struct SnapConnection {
+    peer_id: PeerId,
+    conn: ProtocolConnection,
+    requests: Option<mpsc::Sender<IncomingSnapRequest>>,
+    pending_responses: FuturesUnordered<oneshot::Receiver<SnapResponse>>,
+    /// The registry this connection is registered in.
+    peers: SnapPeers,
+    /// Identifies this connection in the registry.
+    commands_tx: mpsc::UnboundedSender<OutgoingSnapRequest>,
+    /// Our own requests to send to the peer.
+    commands: UnboundedReceiverStream<OutgoingSnapRequest>,
+    /// Our requests that await a response, by request ID.
+    inflight_requests: HashMap<u64, oneshot::Sender<RequestResult<SnapResponse>>>,
+    next_request_id: u64,
+}
+
+impl fmt::Debug for SnapConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnapConnection")
+            .field("peer_id", &self.peer_id)
+            .field("pending_responses", &self.pending_responses.len())
+            .field("inflight_requests", &self.inflight_requests.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SnapConnection {
+    /// Forwards the request to the request handler.
+    fn on_request(&mut self, request: SnapRequest) {
+        let Some(requests) = &self.requests else {
+            trace!(target: "net::snap", peer_id=%self.peer_id, "Ignoring snap request, not serving");
+            return
+        };
+        if self.pending_responses.len() >= MAX_CONCURRENT_SNAP_REQUESTS_PER_PEER {
+            debug!(target: "net::snap", peer_id=%self.peer_id, "Dropping snap request, too many pending requests");
+            return
+        }
+
+        let (tx, rx) = oneshot::channel();
+        let request = IncomingSnapRequest { peer_id: self.peer_id, request, response: tx };
+        if requests.try_send(request).is_ok() {
+            self.pending_responses.push(rx);
+        } else {
+            debug!(target: "net::snap", peer_id=%self.peer_id, "Dropping snap request, request handler is busy");
+        }
+    }
+
+    /// Assigns an ID to our request and returns the message to send.
+    fn on_outgoing_request(&mut self, request: OutgoingSnapRequest) -> SnapProtocolMessage {
+        let OutgoingSnapRequest { mut request, response } = request;
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        request.set_request_id(request_id);
+        self.inflight_requests.insert(request_id, response);
+        request.into()
+    }
+
+    /// Delivers the response to our request.
+    fn on_response(&mut self, response: SnapResponse) {
+        let request_id = response_request_id(&response);
+        if let Some(tx) = self.inflight_requests.remove(&request_id) {
+            let _ = tx.send(Ok(response));
+        } else {
+            trace!(target: "net::snap", peer_id=%self.peer_id, request_id, "Ignoring unsolicited snap response");
+        }
+    }
+}
+
+impl Drop for SnapConnection {
+    fn drop(&mut self) {
+        self.peers.remove(&self.peer_id, &self.commands_tx);
+    }
+}
+
+impl Stream for SnapConnection {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Poll::Ready(Some(response)) = this.pending_responses.poll_next_unpin(cx) {
+                let Ok(response) = response else {
+                    // the request handler dropped the request
+                    continue
+                };
+                let message = response_message(response);
+                return Poll::Ready(Some(BytesMut::from(&message.encode()[..])))
+            }
+
+            if let Poll::Ready(Some(request)) = this.commands.poll_next_unpin(cx) {
+                let message = this.on_outgoing_request(request);
+                return Poll::Ready(Some(BytesMut::from(&message.encode()[..])))
+            }
+
+            let Poll::Ready(msg) = this.conn.poll_next_unpin(cx) else { return Poll::Pending };
+            let Some(msg) = msg else { return Poll::Ready(None) };
+
+            let Some((&id, mut body)) = msg.split_first() else { return Poll::Ready(None) };
+            let message = match SnapProtocolMessage::decode(id, &mut body) {
+                Ok(message) => message,
+                Err(err) => {
+                    debug!(target: "net::snap", peer_id=%this.peer_id, %err, "Failed to decode snap message");
+                    return Poll::Ready(None)
+                }
+            };
+
+            match SnapRequest::try_from(message) {
+                Ok(request) => this.on_request(request),
+                Err(message) => {
+                    if let Some(response) = response_from_message(message) {
+                        this.on_response(response)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the response if the message is a response.
+fn response_from_message(message: SnapProtocolMessage) -> Option<SnapResponse> {
+    match message {
+        SnapProtocolMessage::AccountRange(msg) => Some(SnapResponse::AccountRange(msg)),
+        SnapProtocolMessage::StorageRanges(msg) => Some(SnapResponse::StorageRanges(msg)),
+        SnapProtocolMessage::ByteCodes(msg) => Some(SnapResponse::ByteCodes(msg)),
+        SnapProtocolMessage::TrieNodes(msg) => Some(SnapResponse::TrieNodes(msg)),
+        _ => None,
+    }
+}
+
+/// Returns the ID of the request the response belongs to.
+const fn response_request_id(response: &SnapResponse) -> u64 {
+    match response {
+        SnapResponse::AccountRange(msg) => msg.request_id,
+        SnapResponse::StorageRanges(msg) => msg.request_id,
+        SnapResponse::ByteCodes(msg) => msg.request_id,
+        SnapResponse::TrieNodes(msg) => msg.request_id,
+    }
+}
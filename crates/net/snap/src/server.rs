@@ -0,0 +1,1141 @@
+//! Serves `snap/1` requests from the hashed state and trie tables.
+//!
+//! The states of the [`MAX_SERVED_STATE_ROOTS`] latest persisted blocks are served, older states
+//! are recovered by reverting the latest state with the changesets of the blocks above them.
+//! Requests for any other state root are answered with empty responses, as mandated by the
+//! protocol for unavailable state.
+
+use crate::{
+    account::slim_account_body, metrics::SnapServerMetrics, protocol::SnapProtocolHandler,
+};
+use alloy_consensus::BlockHeader;
+use alloy_primitives::{map::B256Set, BlockNumber, Bytes, B256, KECCAK_EMPTY};
+use alloy_rlp::Encodable;
+use futures::StreamExt;
+use parking_lot::Mutex;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    tables,
+    transaction::DbTx,
+    DatabaseError,
+};
+use reth_eth_wire_types::snap::{
+    AccountData, AccountRangeMessage, ByteCodesMessage, GetAccountRangeMessage,
+    GetByteCodesMessage, GetStorageRangesMessage, GetTrieNodesMessage, SnapProtocolMessage,
+    StorageData, StorageRangesMessage, TrieNodesMessage, TriePath,
+};
+use reth_network_api::PeerId;
+use reth_network_p2p::snap::client::SnapResponse;
+use reth_storage_api::{BlockNumReader, DBProvider, DatabaseProviderFactory, HeaderProvider};
+use reth_storage_errors::provider::ProviderResult;
+use reth_trie::{
+    hashed_cursor::{HashedCursor, HashedCursorFactory, HashedPostStateCursorFactory},
+    proof::{Proof, StorageProof},
+    HashedPostState, HashedPostStateSorted, KeccakKeyHasher, MultiProof, MultiProofTargets,
+    Nibbles, StorageMultiProof, StorageRoot, StoredNibblesSubKey, TriePrefixSetsMut,
+    EMPTY_ROOT_HASH,
+};
+use reth_trie_db::{
+    DatabaseHashedCursorFactory, DatabaseHashedPostState, DatabaseStorageRoot,
+    DatabaseTrieCursorFactory,
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, trace};
+
+// Limits: <https://github.com/ethereum/go-ethereum/blob/master/eth/protocols/snap/handler.go>
+
+/// Maximum size of replies to snap requests: 2MB
+///
+/// Requests asking for more bytes are capped at this limit.
+pub const SNAP_SOFT_RESPONSE_LIMIT: u64 = 2 * 1024 * 1024;
+
+/// Maximum number of bytecodes to serve.
+///
+/// Used to limit lookups.
+pub const MAX_CODE_LOOKUPS: usize = 1024;
+
+/// Maximum number of trie nodes to serve.
+///
+/// Used to limit lookups.
+pub const MAX_TRIE_NODE_LOOKUPS: usize = 1024;
+
+/// Number of the latest persisted blocks whose state is served.
+///
+/// Peers expect the state of the recent blocks to be available, so that they can pick a pivot
+/// below the tip. The changesets of these blocks are never pruned.
+pub const MAX_SERVED_STATE_ROOTS: u64 = 128;
+
+/// Capacity of the channel of incoming snap requests.
+pub const SNAP_REQUESTS_CHANNEL_CAPACITY: usize = 256;
+
+/// Maximum number of requests to serve before yielding to the executor.
+const MAX_REQUESTS_PER_POLL: usize = 16;
+
+/// A `snap/1` request of a peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapRequest {
+    /// Request for a range of accounts.
+    GetAccountRange(GetAccountRangeMessage),
+    /// Request for the storage slots of accounts.
+    GetStorageRanges(GetStorageRangesMessage),
+    /// Request for contract bytecodes.
+    GetByteCodes(GetByteCodesMessage),
+    /// Request for trie nodes.
+    GetTrieNodes(GetTrieNodesMessage),
+}
+
+impl SnapRequest {
+    /// Returns the ID of the request.
+    pub const fn request_id(&self) -> u64 {
+        match self {
+            Self::GetAccountRange(msg) => msg.request_id,
+            Self::GetStorageRanges(msg) => msg.request_id,
+            Self::GetByteCodes(msg) => msg.request_id,
+            Self::GetTrieNodes(msg) => msg.request_id,
+        }
+    }
+
+    /// Sets the ID of the request.
+    pub const fn set_request_id(&mut self, request_id: u64) {
+        match self {
+            Self::GetAccountRange(msg) => msg.request_id = request_id,
+            Self::GetStorageRanges(msg) => msg.request_id = request_id,
+            Self::GetByteCodes(msg) => msg.request_id = request_id,
+            Self::GetTrieNodes(msg) => msg.request_id = request_id,
+        }
+    }
+}
+
+impl From<SnapRequest> for SnapProtocolMessage {
+    fn from(request: SnapRequest) -> Self {
+        match request {
+            SnapRequest::GetAccountRange(msg) => Self::GetAccountRange(msg),
+            SnapRequest::GetStorageRanges(msg) => Self::GetStorageRanges(msg),
+            SnapRequest::GetByteCodes(msg) => Self::GetByteCodes(msg),
+            SnapRequest::GetTrieNodes(msg) => Self::GetTrieNodes(msg),
+        }
+    }
+}
+
+impl TryFrom<SnapProtocolMessage> for SnapRequest {
+    type Error = SnapProtocolMessage;
+
+    fn try_from(message: SnapProtocolMessage) -> Result<Self, Self::Error> {
+        match message {
+            SnapProtocolMessage::GetAccountRange(msg) => Ok(Self::GetAccountRange(msg)),
+            SnapProtocolMessage::GetStorageRanges(msg) => Ok(Self::GetStorageRanges(msg)),
+            SnapProtocolMessage::GetByteCodes(msg) => Ok(Self::GetByteCodes(msg)),
+            SnapProtocolMessage::GetTrieNodes(msg) => Ok(Self::GetTrieNodes(msg)),
+            message => Err(message),
+        }
+    }
+}
+
+/// Converts the response into the message sent to the peer.
+pub(crate) fn response_message(response: SnapResponse) -> SnapProtocolMessage {
+    match response {
+        SnapResponse::AccountRange(msg) => SnapProtocolMessage::AccountRange(msg),
+        SnapResponse::StorageRanges(msg) => SnapProtocolMessage::StorageRanges(msg),
+        SnapResponse::ByteCodes(msg) => SnapProtocolMessage::ByteCodes(msg),
+        SnapResponse::TrieNodes(msg) => SnapProtocolMessage::TrieNodes(msg),
+    }
+}
+
+/// Returns the length of the RLP encoded response.
+fn response_length(response: &SnapResponse) -> usize {
+    match response {
+        SnapResponse::AccountRange(msg) => msg.length(),
+        SnapResponse::StorageRanges(msg) => msg.length(),
+        SnapResponse::ByteCodes(msg) => msg.length(),
+        SnapResponse::TrieNodes(msg) => msg.length(),
+    }
+}
+
+/// A `snap/1` request delegated by a peer's connection.
+#[derive(Debug)]
+pub struct IncomingSnapRequest {
+    /// The peer that sent the request.
+    pub peer_id: PeerId,
+    /// The request.
+    pub request: SnapRequest,
+    /// The channel sender for the response.
+    pub response: oneshot::Sender<SnapResponse>,
+}
+
+/// Serves `snap/1` requests of peers.
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[derive(Debug)]
+#[must_use = "Handler does nothing unless polled."]
+pub struct SnapRequestHandler<F> {
+    /// Provides access to the database.
+    factory: F,
+    /// Incoming requests from the peer connections.
+    incoming_requests: ReceiverStream<IncomingSnapRequest>,
+    /// Upper bound of the bytes served in a single response.
+    soft_response_limit: u64,
+    /// The reverts to the most recently requested state root.
+    reverts: Mutex<Option<CachedReverts>>,
+    /// Metrics for the snap request handler.
+    metrics: SnapServerMetrics,
+}
+
+impl<F> SnapRequestHandler<F> {
+    /// Creates a new handler that serves the requests of the given channel.
+    pub fn new(factory: F, incoming: mpsc::Receiver<IncomingSnapRequest>) -> Self {
+        Self {
+            factory,
+            incoming_requests: ReceiverStream::new(incoming),
+            soft_response_limit: SNAP_SOFT_RESPONSE_LIMIT,
+            reverts: Default::default(),
+            metrics: Default::default(),
+        }
+    }
+
+    /// Creates a new handler and the [`SnapProtocolHandler`] that delegates requests to it.
+    ///
+    /// The protocol handler must be added to the network as `RLPx` sub-protocol.
+    pub fn new_pair(factory: F) -> (Self, SnapProtocolHandler) {
+        let (tx, rx) = mpsc::channel(SNAP_REQUESTS_CHANNEL_CAPACITY);
+        (Self::new(factory, rx), SnapProtocolHandler::new(tx))
+    }
+
+    /// Sets the upper bound of the bytes served in a single response.
+    ///
+    /// Peers may request less, but never more than this.
+    pub const fn with_soft_response_limit(mut self, limit: u64) -> Self {
+        self.soft_response_limit = limit;
+        self
+    }
+}
+
+impl<F> SnapRequestHandler<F>
+where
+    F: DatabaseProviderFactory<Provider: BlockNumReader + HeaderProvider>,
+{
+    /// Serves the request.
+    ///
+    /// Requests that can't be served are answered with an empty response.
+    pub fn on_request(&self, request: SnapRequest) -> SnapResponse {
+        let request_id = request.request_id();
+        let start = Instant::now();
+
+        let response = self.try_serve(&request).unwrap_or_else(|err| {
+            debug!(target: "net::snap", %err, request_id, "Failed to serve snap request");
+            self.metrics.failed_requests_total.increment(1);
+            None
+        });
+        let response = response.unwrap_or_else(|| empty_response(&request));
+
+        self.metrics.serve_duration_seconds.record(start.elapsed());
+        self.metrics.response_bytes.record(response_length(&response) as f64);
+        response
+    }
+
+    /// Serves the request, returns `None` if the requested state root is unavailable.
+    fn try_serve(&self, request: &SnapRequest) -> ProviderResult<Option<SnapResponse>> {
+        let provider = self.factory.database_provider_ro()?;
+
+        let root_hash = match request {
+            SnapRequest::GetAccountRange(request) => {
+                self.metrics.account_range_requests_total.increment(1);
+                request.root_hash
+            }
+            SnapRequest::GetStorageRanges(request) => {
+                self.metrics.storage_ranges_requests_total.increment(1);
+                request.root_hash
+            }
+            SnapRequest::GetTrieNodes(request) => {
+                self.metrics.trie_nodes_requests_total.increment(1);
+                request.root_hash
+            }
+            SnapRequest::GetByteCodes(request) => {
+                // bytecodes are addressed by hash and don't depend on the state root
+                self.metrics.byte_codes_requests_total.increment(1);
+                let response = byte_codes(provider.tx_ref(), request, self.soft_response_limit)?;
+                return Ok(Some(SnapResponse::ByteCodes(response)))
+            }
+        };
+
+        let Some(reverts) = self.state_reverts(&provider, root_hash)? else {
+            trace!(target: "net::snap", %root_hash, "Requested state root unavailable");
+            self.metrics.unavailable_root_total.increment(1);
+            return Ok(None)
+        };
+
+        let state = ServedState::new(provider.tx_ref(), reverts);
+        let limit = self.soft_response_limit;
+        let response = match request {
+            SnapRequest::GetAccountRange(request) => {
+                SnapResponse::AccountRange(account_range(&state, request, limit)?)
+            }
+            SnapRequest::GetStorageRanges(request) => {
+                SnapResponse::StorageRanges(storage_ranges(&state, request, limit)?)
+            }
+            SnapRequest::GetTrieNodes(request) => {
+                SnapResponse::TrieNodes(trie_nodes(&state, request, limit)?)
+            }
+            SnapRequest::GetByteCodes(_) => unreachable!("served above"),
+        };
+        Ok(Some(response))
+    }
+
+    /// Returns the reverts from the latest persisted state to the state with the given root.
+    ///
+    /// Returns `None` if the root isn't the state root of one of the [`MAX_SERVED_STATE_ROOTS`]
+    /// latest persisted blocks.
+    fn state_reverts(
+        &self,
+        provider: &F::Provider,
+        root_hash: B256,
+    ) -> ProviderResult<Option<Arc<StateReverts>>> {
+        let best_number = provider.best_block_number()?;
+        let cached = self.reverts.lock().as_ref().and_then(|cached| {
+            (cached.root_hash == root_hash && cached.best_number == best_number)
+                .then(|| (cached.number, cached.reverts.clone()))
+        });
+
+        let (number, reverts) = match cached {
+            Some(cached) => cached,
+            None => {
+                let range = best_number.saturating_sub(MAX_SERVED_STATE_ROOTS - 1)..=best_number;
+                let Some(number) = provider
+                    .headers_range(range)?
+                    .into_iter()
+                    .rev()
+                    .find(|header| header.state_root() == root_hash)
+                    .map(|header| header.number())
+                else {
+                    return Ok(None)
+                };
+
+                let reverts = if number == best_number {
+                    Arc::default()
+                } else {
+                    Arc::new(StateReverts::new(provider.tx_ref(), number)?)
+                };
+                *self.reverts.lock() = Some(CachedReverts {
+                    root_hash,
+                    best_number,
+                    number,
+                    reverts: reverts.clone(),
+                });
+                (number, reverts)
+            }
+        };
+
+        if number != best_number {
+            self.metrics.historical_root_total.increment(1);
+        }
+        Ok(Some(reverts))
+    }
+}
+
+/// The reverts to a requested state root, valid as long as the latest persisted block doesn't
+/// change.
+#[derive(Debug)]
+struct CachedReverts {
+    /// The requested state root.
+    root_hash: B256,
+    /// The latest persisted block the reverts apply to.
+    best_number: BlockNumber,
+    /// The block with the requested state root.
+    number: BlockNumber,
+    /// The reverts from the latest persisted state to the requested state.
+    reverts: Arc<StateReverts>,
+}
+
+/// The reverts from the latest persisted state to the state of a recent block.
+///
+/// The stored trie nodes on the paths of reverted keys are outdated, the prefix sets make sure
+/// they are recomputed from the reverted hashed state.
+#[derive(Debug, Default)]
+struct StateReverts {
+    /// The hashed state before the blocks above the recent block.
+    state: HashedPostStateSorted,
+    /// The prefixes of the reverted keys.
+    prefix_sets: TriePrefixSetsMut,
+}
+
+impl StateReverts {
+    /// Collects the reverts of all blocks above the given block.
+    fn new<TX: DbTx>(tx: &TX, number: BlockNumber) -> Result<Self, DatabaseError> {
+        let reverts = HashedPostState::from_reverts::<KeccakKeyHasher>(tx, number + 1..)?;
+        Ok(Self { prefix_sets: reverts.construct_prefix_sets(), state: reverts.into_sorted() })
+    }
+}
+
+/// The state requests are served from: the latest persisted state, overlaid with the reverts to
+/// the requested state.
+#[derive(Debug)]
+pub(crate) struct ServedState<'a, TX> {
+    /// The database transaction.
+    tx: &'a TX,
+    /// The reverts, empty if the latest state is served.
+    reverts: Arc<StateReverts>,
+}
+
+impl<'a, TX: DbTx> ServedState<'a, TX> {
+    /// Creates the state with the given reverts.
+    const fn new(tx: &'a TX, reverts: Arc<StateReverts>) -> Self {
+        Self { tx, reverts }
+    }
+
+    /// Returns the latest persisted state.
+    pub(crate) fn latest(tx: &'a TX) -> Self {
+        Self::new(tx, Arc::default())
+    }
+
+    /// Returns the factory of hashed cursors over the served state.
+    fn hashed_cursor_factory(
+        &self,
+    ) -> HashedPostStateCursorFactory<DatabaseHashedCursorFactory<&'a TX>, &HashedPostStateSorted>
+    {
+        HashedPostStateCursorFactory::new(
+            DatabaseHashedCursorFactory::new(self.tx),
+            &self.reverts.state,
+        )
+    }
+
+    /// Returns the multiproof of the targets in the served state.
+    fn multiproof(&self, targets: MultiProofTargets) -> ProviderResult<MultiProof> {
+        Ok(Proof::new(DatabaseTrieCursorFactory::new(self.tx), self.hashed_cursor_factory())
+            .with_prefix_sets_mut(self.reverts.prefix_sets.clone())
+            .multiproof(targets)?)
+    }
+
+    /// Returns the multiproof of the slots in the served storage of the account.
+    fn storage_multiproof(
+        &self,
+        hashed_address: B256,
+        slots: B256Set,
+    ) -> ProviderResult<StorageMultiProof> {
+        let prefix_set = self
+            .reverts
+            .prefix_sets
+            .storage_prefix_sets
+            .get(&hashed_address)
+            .cloned()
+            .unwrap_or_default();
+        Ok(StorageProof::new_hashed(
+            DatabaseTrieCursorFactory::new(self.tx),
+            self.hashed_cursor_factory(),
+            hashed_address,
+        )
+        .with_prefix_set_mut(prefix_set)
+        .storage_multiproof(slots)?)
+    }
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<F> Future for SnapRequestHandler<F>
+where
+    F: DatabaseProviderFactory<Provider: BlockNumReader + HeaderProvider> + Unpin,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        for _ in 0..MAX_REQUESTS_PER_POLL {
+            match this.incoming_requests.poll_next_unpin(cx) {
+                Poll::Ready(Some(IncomingSnapRequest { peer_id, request, response })) => {
+                    trace!(target: "net::snap", %peer_id, request_id=request.request_id(), "Serving snap request");
+                    let _ = response.send(this.on_request(request));
+                }
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        // make sure we're woken up again
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Returns the empty response to the request, sent if the state is unavailable.
+fn empty_response(request: &SnapRequest) -> SnapResponse {
+    let request_id = request.request_id();
+    match request {
+        SnapRequest::GetAccountRange(_) => SnapResponse::AccountRange(AccountRangeMessage {
+            request_id,
+            accounts: Vec::new(),
+            proof: Vec::new(),
+        }),
+        SnapRequest::GetStorageRanges(_) => SnapResponse::StorageRanges(StorageRangesMessage {
+            request_id,
+            slots: Vec::new(),
+            proof: Vec::new(),
+        }),
+        SnapRequest::GetByteCodes(_) => {
+            SnapResponse::ByteCodes(ByteCodesMessage { request_id, codes: Vec::new() })
+        }
+        SnapRequest::GetTrieNodes(_) => {
+            SnapResponse::TrieNodes(TrieNodesMessage { request_id, nodes: Vec::new() })
+        }
+    }
+}
+
+/// Returns the consecutive accounts starting at the requested hash, and the proof of the first
+/// requested and the last returned account.
+fn account_range<TX: DbTx>(
+    state: &ServedState<'_, TX>,
+    request: &GetAccountRangeMessage,
+    limit: u64,
+) -> ProviderResult<AccountRangeMessage> {
+    let limit = request.response_bytes.min(limit);
+    let hashed_cursor_factory = state.hashed_cursor_factory();
+    let mut cursor = hashed_cursor_factory.hashed_account_cursor()?;
+    let mut storage_trie_cursor = state.tx.cursor_dup_read::<tables::StoragesTrie>()?;
+    let mut storage_cursor = state.tx.cursor_dup_read::<tables::HashedStorages>()?;
+
+    let mut accounts = Vec::new();
+    let mut size = 0u64;
+    let mut entry = cursor.seek(request.starting_hash)?;
+    while let Some((hash, account)) = entry {
+        let storage_root =
+            storage_root(state, &mut storage_trie_cursor, &mut storage_cursor, hash)?;
+        let body = slim_account_body(&account, storage_root);
+
+        size += (B256::len_bytes() + body.len()) as u64;
+        accounts.push(AccountData { hash, body });
+
+        if hash >= request.limit_hash || size >= limit {
+            break
+        }
+        entry = cursor.next()?;
+    }
+
+    let targets = MultiProofTargets::from_iter(
+        core::iter::once(request.starting_hash)
+            .chain(accounts.last().map(|account| account.hash))
+            .map(|hash| (hash, B256Set::default())),
+    );
+    let proof = state.multiproof(targets)?;
+    let proof =
+        proof.account_subtree.into_nodes_sorted().into_iter().map(|(_, node)| node).collect();
+
+    Ok(AccountRangeMessage { request_id: request.request_id, accounts, proof })
+}
+
+/// Returns the storage root of the account.
+///
+/// The root is read from the root node of the storage trie. Only reverted storage and storage
+/// tries that are too small for their root node to be stored are computed from the hashed storage.
+pub(crate) fn storage_root<TX: DbTx>(
+    state: &ServedState<'_, TX>,
+    storage_trie_cursor: &mut impl DbDupCursorRO<tables::StoragesTrie>,
+    storage_cursor: &mut impl DbDupCursorRO<tables::HashedStorages>,
+    hashed_address: B256,
+) -> ProviderResult<B256> {
+    if state.reverts.state.storages.contains_key(&hashed_address) {
+        return Ok(state.storage_multiproof(hashed_address, B256Set::default())?.root)
+    }
+
+    let root = storage_trie_cursor
+        .seek_by_key_subkey(hashed_address, StoredNibblesSubKey(Nibbles::default()))?
+        .filter(|entry| entry.nibbles.0.is_empty())
+        .and_then(|entry| entry.node.root_hash);
+    if let Some(root) = root {
+        return Ok(root)
+    }
+
+    if storage_cursor.seek_exact(hashed_address)?.is_none() {
+        return Ok(EMPTY_ROOT_HASH)
+    }
+    Ok(StorageRoot::from_tx_hashed(state.tx, hashed_address).root().map_err(DatabaseError::from)?)
+}
+
+/// Returns the storage slots of the requested accounts.
+///
+/// The starting and limit hash only apply to the first account. The response is only proven if it
+/// doesn't cover the entire storage of the last account, in which case it ends with that account.
+fn storage_ranges<TX: DbTx>(
+    state: &ServedState<'_, TX>,
+    request: &GetStorageRangesMessage,
+    limit: u64,
+) -> ProviderResult<StorageRangesMessage> {
+    let limit = request.response_bytes.min(limit);
+    let hashed_cursor_factory = state.hashed_cursor_factory();
+
+    let mut slots = Vec::new();
+    let mut proof = Vec::new();
+    let mut size = 0u64;
+    for (idx, &hashed_address) in request.account_hashes.iter().enumerate() {
+        if size >= limit {
+            break
+        }
+
+        let (origin, limit_hash) = if idx == 0 {
+            let limit_hash = if request.limit_hash.is_zero() {
+                B256::repeat_byte(0xff)
+            } else {
+                request.limit_hash
+            };
+            (request.starting_hash, limit_hash)
+        } else {
+            (B256::ZERO, B256::repeat_byte(0xff))
+        };
+
+        let mut cursor = hashed_cursor_factory.hashed_storage_cursor(hashed_address)?;
+        let mut storage = Vec::new();
+        let mut aborted = false;
+        let mut entry = cursor.seek(origin)?;
+        while let Some((hash, value)) = entry {
+            if size >= limit {
+                aborted = true;
+                break
+            }
+
+            let data = Bytes::from(alloy_rlp::encode(value));
+            size += (B256::len_bytes() + data.len()) as u64;
+            storage.push(StorageData { hash, data });
+
+            if hash >= limit_hash {
+                break
+            }
+            entry = cursor.next()?;
+        }
+
+        let last = storage.last().map(|slot| slot.hash);
+        if !storage.is_empty() {
+            slots.push(storage);
+        }
+
+        if !origin.is_zero() || (aborted && last.is_some()) {
+            let targets = core::iter::once(origin).chain(last).collect();
+            let storage_proof = state.storage_multiproof(hashed_address, targets)?;
+            proof = storage_proof
+                .subtree
+                .into_nodes_sorted()
+                .into_iter()
+                .map(|(_, node)| node)
+                .collect();
+            // a proof terminates the response
+            break
+        }
+    }
+
+    Ok(StorageRangesMessage { request_id: request.request_id, slots, proof })
+}
+
+/// Returns the requested bytecodes, unknown bytecodes are skipped.
+fn byte_codes<TX: DbTx>(
+    tx: &TX,
+    request: &GetByteCodesMessage,
+    limit: u64,
+) -> ProviderResult<ByteCodesMessage> {
+    let limit = request.response_bytes.min(limit);
+
+    let mut codes = Vec::new();
+    let mut size = 0u64;
+    for &hash in request.hashes.iter().take(MAX_CODE_LOOKUPS) {
+        let code = if hash == KECCAK_EMPTY {
+            Bytes::new()
+        } else if let Some(code) = tx.get::<tables::Bytecodes>(hash)? {
+            code.original_bytes()
+        } else {
+            continue
+        };
+
+        size += code.len() as u64;
+        codes.push(code);
+        if size >= limit {
+            break
+        }
+    }
+
+    Ok(ByteCodesMessage { request_id: request.request_id, codes })
+}
+
+/// Returns the requested account and storage trie nodes.
+///
+/// Nodes that don't exist are returned empty, the response ends at the first account that
+/// doesn't exist.
+fn trie_nodes<TX: DbTx>(
+    state: &ServedState<'_, TX>,
+    request: &GetTrieNodesMessage,
+    limit: u64,
+) -> ProviderResult<TrieNodesMessage> {
+    let limit = request.response_bytes.min(limit);
+    let hashed_cursor_factory = state.hashed_cursor_factory();
+
+    let mut nodes = Vec::new();
+    let mut size = 0u64;
+    let mut lookups = 0;
+    'paths: for TriePath { account_path, slot_paths } in &request.paths {
+        if slot_paths.is_empty() {
+            let Some(path) = decode_compact_path(account_path) else { break };
+            let node = account_trie_node(state, path)?.unwrap_or_default();
+            size += node.len() as u64;
+            nodes.push(node);
+            lookups += 1;
+        } else {
+            // the account is addressed by its full hash if storage nodes are requested
+            if account_path.len() != B256::len_bytes() {
+                break
+            }
+            let hashed_address = B256::from_slice(account_path);
+            // the overlay cursors only move forward, so every lookup gets a fresh one
+            let account = hashed_cursor_factory.hashed_account_cursor()?.seek(hashed_address)?;
+            if account.is_none_or(|(hash, _)| hash != hashed_address) {
+                break
+            }
+
+            for slot_path in slot_paths {
+                let Some(path) = decode_compact_path(slot_path) else { break 'paths };
+                let node = storage_trie_node(state, hashed_address, path)?.unwrap_or_default();
+                size += node.len() as u64;
+                nodes.push(node);
+                lookups += 1;
+
+                if size >= limit || lookups >= MAX_TRIE_NODE_LOOKUPS {
+                    break 'paths
+                }
+            }
+        }
+
+        if size >= limit || lookups >= MAX_TRIE_NODE_LOOKUPS {
+            break
+        }
+    }
+
+    Ok(TrieNodesMessage { request_id: request.request_id, nodes })
+}
+
+/// Returns the RLP encoded node at the given path of the account trie.
+pub(crate) fn account_trie_node<TX: DbTx>(
+    state: &ServedState<'_, TX>,
+    path: Nibbles,
+) -> ProviderResult<Option<Bytes>> {
+    // the proof of any key below the path contains the node at the path, if it exists
+    let targets = MultiProofTargets::from_iter([(padded_key(&path), B256Set::default())]);
+    let proof = state.multiproof(targets)?;
+    Ok(proof.account_subtree.get(&path).cloned())
+}
+
+/// Returns the RLP encoded node at the given path of the account's storage trie.
+pub(crate) fn storage_trie_node<TX: DbTx>(
+    state: &ServedState<'_, TX>,
+    hashed_address: B256,
+    path: Nibbles,
+) -> ProviderResult<Option<Bytes>> {
+    let proof =
+        state.storage_multiproof(hashed_address, B256Set::from_iter([padded_key(&path)]))?;
+    Ok(proof.subtree.get(&path).cloned())
+}
+
+/// Returns the key of the path, padded with zero nibbles.
+pub(crate) fn padded_key(path: &Nibbles) -> B256 {
+    let mut key = B256::ZERO;
+    for (idx, nibble) in path.to_vec().into_iter().enumerate() {
+        key[idx / 2] |= if idx % 2 == 0 { nibble << 4 } else { nibble };
+    }
+    key
+}
+
+/// Decodes a hex-prefix encoded trie path.
+///
+/// See also <https://ethereum.org/en/developers/docs/data-structures-and-encoding/patricia-merkle-trie/#specification>
+pub fn decode_compact_path(path: &[u8]) -> Option<Nibbles> {
+    let (&first, rest) = path.split_first()?;
+    // the flag nibble encodes whether the path is a leaf path and of odd length
+    let flag = first >> 4;
+    if flag > 3 {
+        return None
+    }
+
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    } else if first & 0x0f != 0 {
+        return None
+    }
+    for byte in rest {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+
+    (nibbles.len() <= B256::len_bytes() * 2).then(|| Nibbles::from_nibbles(nibbles))
+}
+
+/// Encodes the path in the compact encoding used by `GetTrieNodes` requests.
+pub fn encode_compact_path(path: &Nibbles) -> Bytes {
+    let nibbles = path.to_vec();
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        encoded.push(0x10 | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        encoded.push(0x00);
+        &nibbles[..]
+    };
+    encoded.extend(rest.chunks_exact(2).map(|pair| pair[0] << 4 | pair[1]));
+    encoded.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::Header;
+    use alloy_primitives::{keccak256, Address, U256};
+    use reth_db_api::{
+        models::{AccountBeforeTx, BlockNumberAddress},
+        transaction::DbTxMut,
+    };
+    use reth_primitives_traits::{Account, Bytecode, StorageEntry};
+    use reth_provider::{
+        providers::ProviderNodeTypes, test_utils::create_test_provider_factory, ProviderFactory,
+        StageCheckpointWriter, StaticFileProviderFactory, StaticFileSegment, TrieWriter,
+    };
+    use reth_stages_api::{StageCheckpoint, StageId};
+    use reth_trie::{HashBuilder, StateRoot};
+    use reth_trie_db::DatabaseStateRoot;
+
+    #[test]
+    fn decode_compact_paths() {
+        assert_eq!(decode_compact_path(&[0x00]), Some(Nibbles::default()));
+        assert_eq!(decode_compact_path(&[0x11]), Some(Nibbles::from_nibbles([1])));
+        assert_eq!(decode_compact_path(&[0x00, 0x12]), Some(Nibbles::from_nibbles([1, 2])));
+        assert_eq!(decode_compact_path(&[0x31, 0x23]), Some(Nibbles::from_nibbles([1, 2, 3])));
+        assert_eq!(decode_compact_path(&[0x01]), None);
+        assert_eq!(decode_compact_path(&[0x40]), None);
+        assert_eq!(decode_compact_path(&[]), None);
+
+        for nibbles in [vec![], vec![1], vec![1, 2], vec![1, 2, 3]] {
+            let path = Nibbles::from_nibbles(nibbles);
+            assert_eq!(decode_compact_path(&encode_compact_path(&path)), Some(path));
+        }
+    }
+
+    /// Inserts hashed state for the given number of accounts, every third with storage, and
+    /// returns the state root.
+    fn insert_state<N: ProviderNodeTypes>(factory: &ProviderFactory<N>, num_accounts: u8) -> B256 {
+        let provider = factory.database_provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        for i in 0..num_accounts {
+            let hashed_address = keccak256([i]);
+            let account = Account {
+                nonce: i.into(),
+                balance: U256::from(i),
+                bytecode_hash: (i % 2 == 0).then(|| keccak256([i, i])),
+            };
+            tx.put::<tables::HashedAccounts>(hashed_address, account).unwrap();
+            if i % 3 == 0 {
+                for slot in 1..=4u8 {
+                    tx.put::<tables::HashedStorages>(
+                        hashed_address,
+                        StorageEntry { key: keccak256([slot]), value: U256::from(slot) },
+                    )
+                    .unwrap();
+                }
+            }
+            if let Some(hash) = account.bytecode_hash {
+                tx.put::<tables::Bytecodes>(hash, Bytecode::new_raw(Bytes::from(vec![i, i])))
+                    .unwrap();
+            }
+        }
+        let (root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        provider.write_trie_updates(updates).unwrap();
+        provider.commit().unwrap();
+        root
+    }
+
+    #[test]
+    fn serve_account_range_with_proof() {
+        let factory = create_test_provider_factory();
+        let root = insert_state(&factory, 32);
+        let provider = factory.database_provider_ro().unwrap();
+        let tx = provider.tx_ref();
+        let state = ServedState::latest(tx);
+
+        let request = GetAccountRangeMessage {
+            request_id: 1,
+            root_hash: root,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: 500,
+        };
+        let response = account_range(&state, &request, SNAP_SOFT_RESPONSE_LIMIT).unwrap();
+        assert!(!response.accounts.is_empty() && response.accounts.len() < 32);
+        assert!(response.accounts.windows(2).all(|w| w[0].hash < w[1].hash));
+
+        // the proof contains the proof of the last returned account
+        let last = response.accounts.last().unwrap();
+        let nodes =
+            Proof::new(DatabaseTrieCursorFactory::new(tx), DatabaseHashedCursorFactory::new(tx))
+                .multiproof(MultiProofTargets::from_iter([(last.hash, B256Set::default())]))
+                .unwrap()
+                .account_subtree;
+        for (path, node) in nodes.into_nodes_sorted() {
+            assert!(response.proof.contains(&node), "missing proof node at {path:?}");
+        }
+
+        // an unavailable range is answered with the proof of absence
+        let request = GetAccountRangeMessage { starting_hash: B256::repeat_byte(0xff), ..request };
+        let response = account_range(&state, &request, SNAP_SOFT_RESPONSE_LIMIT).unwrap();
+        assert!(response.accounts.is_empty());
+        assert!(!response.proof.is_empty());
+    }
+
+    #[test]
+    fn storage_root_from_trie_tables() {
+        let factory = create_test_provider_factory();
+
+        // an account with enough slots for the root node of its storage trie to be stored
+        let large_storage = keccak256([0xffu8]);
+        let provider = factory.database_provider_rw().unwrap();
+        provider.tx_ref().put::<tables::HashedAccounts>(large_storage, Account::default()).unwrap();
+        for slot in 0..=255u8 {
+            provider
+                .tx_ref()
+                .put::<tables::HashedStorages>(
+                    large_storage,
+                    StorageEntry {
+                        key: keccak256([slot]),
+                        value: U256::from(slot) + U256::from(1),
+                    },
+                )
+                .unwrap();
+        }
+        provider.commit().unwrap();
+        insert_state(&factory, 8);
+
+        let provider = factory.database_provider_ro().unwrap();
+        let tx = provider.tx_ref();
+        let stored_root = tx
+            .cursor_dup_read::<tables::StoragesTrie>()
+            .unwrap()
+            .seek_by_key_subkey(large_storage, StoredNibblesSubKey(Nibbles::default()))
+            .unwrap()
+            .and_then(|entry| entry.node.root_hash);
+        assert!(stored_root.is_some());
+
+        let state = ServedState::latest(tx);
+        let mut storage_trie_cursor = tx.cursor_dup_read::<tables::StoragesTrie>().unwrap();
+        let mut storage_cursor = tx.cursor_dup_read::<tables::HashedStorages>().unwrap();
+        // accounts 0, 3 and 6 have small storage tries, the others no storage
+        for hashed_address in (0..8u8).map(|i| keccak256([i])).chain([large_storage]) {
+            let root =
+                storage_root(&state, &mut storage_trie_cursor, &mut storage_cursor, hashed_address)
+                    .unwrap();
+            assert_eq!(root, StorageRoot::from_tx_hashed(tx, hashed_address).root().unwrap());
+        }
+    }
+
+    #[test]
+    fn serve_storage_ranges_and_codes() {
+        let factory = create_test_provider_factory();
+        let root = insert_state(&factory, 8);
+        let provider = factory.database_provider_ro().unwrap();
+        let tx = provider.tx_ref();
+        let state = ServedState::latest(tx);
+
+        // accounts 0, 3 and 6 have storage, 1 doesn't
+        let request = GetStorageRangesMessage {
+            request_id: 2,
+            root_hash: root,
+            account_hashes: [0u8, 1, 3].map(|i| keccak256([i])).to_vec(),
+            starting_hash: B256::ZERO,
+            limit_hash: B256::ZERO,
+            response_bytes: SNAP_SOFT_RESPONSE_LIMIT,
+        };
+        let response = storage_ranges(&state, &request, SNAP_SOFT_RESPONSE_LIMIT).unwrap();
+        assert_eq!(response.slots.len(), 2);
+        assert!(response.slots.iter().all(|slots| slots.len() == 4));
+        assert!(response.proof.is_empty());
+
+        // a partial range is proven and terminates the response
+        let request = GetStorageRangesMessage { response_bytes: 1, ..request };
+        let response = storage_ranges(&state, &request, SNAP_SOFT_RESPONSE_LIMIT).unwrap();
+        assert_eq!(response.slots.len(), 1);
+        assert_eq!(response.slots[0].len(), 1);
+        assert!(!response.proof.is_empty());
+
+        let request = GetByteCodesMessage {
+            request_id: 3,
+            hashes: vec![keccak256([2u8, 2]), B256::repeat_byte(0x42), KECCAK_EMPTY],
+            response_bytes: SNAP_SOFT_RESPONSE_LIMIT,
+        };
+        let response = byte_codes(tx, &request, SNAP_SOFT_RESPONSE_LIMIT).unwrap();
+        assert_eq!(response.codes, vec![Bytes::from(vec![2u8, 2]), Bytes::new()]);
+    }
+
+    #[test]
+    fn serve_trie_nodes() {
+        let factory = create_test_provider_factory();
+        let root = insert_state(&factory, 64);
+        let provider = factory.database_provider_ro().unwrap();
+        let tx = provider.tx_ref();
+        let state = ServedState::latest(tx);
+
+        let storage_account = keccak256([0u8]);
+        let request = GetTrieNodesMessage {
+            request_id: 4,
+            root_hash: root,
+            paths: vec![
+                TriePath { account_path: Bytes::from_static(&[0x00]), slot_paths: vec![] },
+                TriePath {
+                    account_path: Bytes::copy_from_slice(storage_account.as_slice()),
+                    slot_paths: vec![Bytes::from_static(&[0x00])],
+                },
+            ],
+            response_bytes: SNAP_SOFT_RESPONSE_LIMIT,
+        };
+        let response = trie_nodes(&state, &request, SNAP_SOFT_RESPONSE_LIMIT).unwrap();
+        assert_eq!(response.nodes.len(), 2);
+        // the root nodes hash to the state root and the storage root
+        assert_eq!(keccak256(&response.nodes[0]), root);
+        let storage_root = StorageRoot::from_tx_hashed(tx, storage_account).root().unwrap();
+        assert_eq!(keccak256(&response.nodes[1]), storage_root);
+    }
+
+    /// Recomputes the state root from the hashed state and writes the trie tables.
+    fn write_state_root<P: DBProvider<Tx: DbTxMut> + TrieWriter>(provider: &P) -> B256 {
+        provider.tx_ref().clear::<tables::AccountsTrie>().unwrap();
+        provider.tx_ref().clear::<tables::StoragesTrie>().unwrap();
+        let (root, updates) = StateRoot::from_tx(provider.tx_ref()).root_with_updates().unwrap();
+        provider.write_trie_updates(updates).unwrap();
+        root
+    }
+
+    #[test]
+    fn serve_recent_state_roots() {
+        let factory = create_test_provider_factory();
+        insert_state(&factory, 16);
+
+        let address = Address::with_last_byte(0xaa);
+        let created = Address::with_last_byte(0xbb);
+        let hashed_address = keccak256(address);
+        let (slot, new_slot) = (B256::with_last_byte(1), B256::with_last_byte(2));
+        let account = Account { nonce: 1, ..Default::default() };
+
+        // block 1 adds an account with a single slot
+        let provider = factory.database_provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        let entry = StorageEntry { key: keccak256(slot), value: U256::from(1) };
+        tx.put::<tables::HashedAccounts>(hashed_address, account).unwrap();
+        tx.put::<tables::HashedStorages>(hashed_address, entry).unwrap();
+        let parent_root = write_state_root(&provider);
+
+        // block 2 updates the account and its slot, adds a slot and creates another account
+        tx.put::<tables::HashedAccounts>(hashed_address, Account { nonce: 2, ..account }).unwrap();
+        tx.put::<tables::HashedAccounts>(
+            keccak256(created),
+            Account { balance: U256::from(5), ..Default::default() },
+        )
+        .unwrap();
+        tx.delete::<tables::HashedStorages>(hashed_address, Some(entry)).unwrap();
+        for (slot, value) in [(slot, 2u8), (new_slot, 3)] {
+            tx.put::<tables::HashedStorages>(
+                hashed_address,
+                StorageEntry { key: keccak256(slot), value: U256::from(value) },
+            )
+            .unwrap();
+        }
+        for (address, info) in [(address, Some(account)), (created, None)] {
+            tx.put::<tables::AccountChangeSets>(2, AccountBeforeTx { address, info }).unwrap();
+        }
+        for (key, value) in [(slot, U256::from(1)), (new_slot, U256::ZERO)] {
+            tx.put::<tables::StorageChangeSets>(
+                BlockNumberAddress((2, address)),
+                StorageEntry { key, value },
+            )
+            .unwrap();
+        }
+        let root = write_state_root(&provider);
+
+        let mut writer =
+            provider.static_file_provider().latest_writer(StaticFileSegment::Headers).unwrap();
+        for (number, state_root) in [B256::ZERO, parent_root, root].into_iter().enumerate() {
+            let header = Header { number: number as u64, state_root, ..Default::default() };
+            writer.append_header(&header, &header.hash_slow()).unwrap();
+        }
+        drop(writer);
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(2)).unwrap();
+        provider.commit().unwrap();
+
+        let (handler, _) = SnapRequestHandler::new_pair(factory);
+        for (root_hash, num_accounts) in [(root, 18), (parent_root, 17)] {
+            let request = GetAccountRangeMessage {
+                request_id: 1,
+                root_hash,
+                starting_hash: B256::ZERO,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: SNAP_SOFT_RESPONSE_LIMIT,
+            };
+            let SnapResponse::AccountRange(response) =
+                handler.on_request(SnapRequest::GetAccountRange(request))
+            else {
+                panic!("unexpected response")
+            };
+            assert_eq!(response.accounts.len(), num_accounts);
+
+            // the full range of accounts hashes to the requested root
+            let mut hash_builder = HashBuilder::default();
+            for account in &response.accounts {
+                hash_builder.add_leaf(Nibbles::unpack(account.hash), &account.body);
+            }
+            assert_eq!(hash_builder.root(), root_hash);
+            assert_eq!(keccak256(&response.proof[0]), root_hash);
+
+            let request = GetTrieNodesMessage {
+                request_id: 2,
+                root_hash,
+                paths: vec![TriePath {
+                    account_path: Bytes::from_static(&[0x00]),
+                    slot_paths: vec![],
+                }],
+                response_bytes: SNAP_SOFT_RESPONSE_LIMIT,
+            };
+            let SnapResponse::TrieNodes(response) =
+                handler.on_request(SnapRequest::GetTrieNodes(request))
+            else {
+                panic!("unexpected response")
+            };
+            assert_eq!(keccak256(&response.nodes[0]), root_hash);
+        }
+
+        // the storage is served as of the parent block
+        let request = GetStorageRangesMessage {
+            request_id: 3,
+            root_hash: parent_root,
+            account_hashes: vec![hashed_address],
+            starting_hash: B256::ZERO,
+            limit_hash: B256::ZERO,
+            response_bytes: SNAP_SOFT_RESPONSE_LIMIT,
+        };
+        let SnapResponse::StorageRanges(response) =
+            handler.on_request(SnapRequest::GetStorageRanges(request.clone()))
+        else {
+            panic!("unexpected response")
+        };
+        assert_eq!(
+            response.slots,
+            vec![vec![StorageData {
+                hash: keccak256(slot),
+                data: Bytes::from(alloy_rlp::encode(U256::from(1)))
+            }]]
+        );
+
+        // unknown roots are unavailable
+        let request = GetStorageRangesMessage { root_hash: B256::repeat_byte(0x42), ..request };
+        let SnapResponse::StorageRanges(response) =
+            handler.on_request(SnapRequest::GetStorageRanges(request))
+        else {
+            panic!("unexpected response")
+        };
+        assert!(response.slots.is_empty() && response.proof.is_empty());
+    }
+}
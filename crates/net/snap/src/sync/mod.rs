@@ -0,0 +1,762 @@
+//! Snap sync: downloads the state of a pivot block from `snap/1` peers.
+//!
+//! The [`SnapSyncer`] downloads the account ranges, the storage of the accounts and their
+//! bytecodes, and verifies all responses against the state root of the pivot block. Because peers
+//! only serve recent state, the pivot moves while the download is in progress. The resulting state
+//! is therefore inconsistent and must be healed by downloading the trie nodes that differ from the
+//! latest pivot, see [`SnapSyncStage`].
+
+use crate::{
+    account::decode_slim_account,
+    metrics::SnapSyncMetrics,
+    server::encode_compact_path,
+    sync::proof::{verify_range_proof, InvalidSnapResponse},
+};
+use alloy_primitives::{keccak256, map::B256Set, Bytes, B256, U256};
+use alloy_rlp::Decodable;
+use futures::{stream::FuturesUnordered, Stream, StreamExt};
+use reth_eth_wire_types::snap::{
+    GetAccountRangeMessage, GetByteCodesMessage, GetStorageRangesMessage, GetTrieNodesMessage,
+    TriePath,
+};
+use reth_network_p2p::{
+    error::PeerRequestResult,
+    snap::client::{SnapClient, SnapResponse},
+};
+use reth_primitives_traits::StorageEntry;
+use reth_trie::{Nibbles, TrieAccount};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{sleep, Sleep};
+use tracing::{debug, trace};
+
+mod proof;
+pub use proof::*;
+
+mod stage;
+pub use stage::*;
+
+/// The largest possible key, used as limit of range requests.
+const MAX_HASH: B256 = B256::repeat_byte(0xff);
+
+/// Configuration of the [`SnapSyncer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapSyncConfig {
+    /// Maximum number of concurrent requests.
+    pub max_concurrent_requests: usize,
+    /// Soft limit of the response size requested from peers.
+    pub response_bytes: u64,
+    /// Maximum number of accounts per storage ranges request.
+    pub max_storage_accounts_per_request: usize,
+    /// Maximum number of bytecodes per request.
+    pub max_codes_per_request: usize,
+    /// Maximum number of trie nodes per request.
+    pub max_trie_nodes_per_request: usize,
+    /// Number of consecutive empty responses after which the pivot is considered unavailable.
+    pub max_empty_responses: usize,
+    /// Delay before requests are retried after a failed request.
+    pub retry_delay: Duration,
+}
+
+impl Default for SnapSyncConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 16,
+            response_bytes: 512 * 1024,
+            max_storage_accounts_per_request: 64,
+            max_codes_per_request: 64,
+            max_trie_nodes_per_request: 128,
+            max_empty_responses: 32,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Verified state downloaded by the [`SnapSyncer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapSyncEvent {
+    /// A range of accounts, starting at the origin.
+    Accounts {
+        /// The requested origin of the range.
+        origin: B256,
+        /// The accounts by hashed address, in ascending order.
+        accounts: Vec<(B256, TrieAccount)>,
+        /// The start of the next range, `None` once all accounts were downloaded.
+        next: Option<B256>,
+    },
+    /// A range of storage slots of an account, starting at the origin.
+    Storage {
+        /// The hashed address of the account.
+        hashed_address: B256,
+        /// The requested origin of the range.
+        origin: B256,
+        /// The slots by hashed slot, in ascending order.
+        slots: Vec<StorageEntry>,
+        /// Whether these are the last slots of the account.
+        complete: bool,
+    },
+    /// Bytecodes by hash.
+    ByteCodes(Vec<(B256, Bytes)>),
+    /// Nodes of the account trie by path, matching the requested hashes.
+    AccountTrieNodes(Vec<(Nibbles, Bytes)>),
+    /// The state of the pivot is no longer served by peers, a newer pivot is required.
+    PivotUnavailable,
+}
+
+/// The storage of an account to download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StorageTask {
+    hashed_address: B256,
+    storage_root: B256,
+    origin: B256,
+}
+
+/// A unit of work that is requested from a peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SnapTask {
+    Accounts { origin: B256 },
+    Storages(Vec<StorageTask>),
+    ByteCodes(Vec<B256>),
+    AccountTrieNodes(Vec<(Nibbles, B256)>),
+}
+
+/// Downloads and verifies state from `snap/1` peers.
+///
+/// Work is added via the `sync_*` functions and the verified state is yielded as
+/// [`SnapSyncEvent`]s. The syncer is idle once all work is done.
+#[must_use = "Stream does nothing unless polled"]
+pub struct SnapSyncer<C: SnapClient> {
+    client: C,
+    config: SnapSyncConfig,
+    /// The state root of the pivot.
+    root: B256,
+    /// The origin of the next account range to request.
+    next_accounts: Option<B256>,
+    pending_storages: VecDeque<StorageTask>,
+    pending_codes: VecDeque<B256>,
+    pending_nodes: VecDeque<(Nibbles, B256)>,
+    /// Failed tasks that are retried first.
+    retries: VecDeque<SnapTask>,
+    inflight: FuturesUnordered<InflightRequest<C::Output>>,
+    /// Delays requests after failures.
+    backoff: Option<Pin<Box<Sleep>>>,
+    /// Number of consecutive empty responses for the current root.
+    empty_responses: usize,
+    queued_events: VecDeque<SnapSyncEvent>,
+    metrics: SnapSyncMetrics,
+}
+
+impl<C: SnapClient> std::fmt::Debug for SnapSyncer<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapSyncer")
+            .field("config", &self.config)
+            .field("root", &self.root)
+            .field("next_accounts", &self.next_accounts)
+            .field("pending_storages", &self.pending_storages.len())
+            .field("pending_codes", &self.pending_codes.len())
+            .field("pending_nodes", &self.pending_nodes.len())
+            .field("inflight", &self.inflight.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: SnapClient> SnapSyncer<C> {
+    /// Creates a new syncer that sends requests via the given client.
+    pub fn new(client: C, config: SnapSyncConfig) -> Self {
+        Self {
+            client,
+            config,
+            root: B256::ZERO,
+            next_accounts: None,
+            pending_storages: Default::default(),
+            pending_codes: Default::default(),
+            pending_nodes: Default::default(),
+            retries: Default::default(),
+            inflight: Default::default(),
+            backoff: None,
+            empty_responses: 0,
+            queued_events: Default::default(),
+            metrics: Default::default(),
+        }
+    }
+
+    /// Returns the state root of the current pivot.
+    pub const fn root(&self) -> B256 {
+        self.root
+    }
+
+    /// Sets the state root of the pivot.
+    ///
+    /// Pending work is continued against the new root, responses to requests for the previous
+    /// root are discarded.
+    pub fn set_root(&mut self, root: B256) {
+        if self.root != root {
+            debug!(target: "sync::snap", previous=%self.root, %root, "Updated pivot state root");
+            self.root = root;
+            self.empty_responses = 0;
+            self.queued_events.retain(|event| *event != SnapSyncEvent::PivotUnavailable);
+        }
+    }
+
+    /// Downloads the accounts, starting at the origin.
+    pub const fn sync_accounts(&mut self, origin: B256) {
+        self.next_accounts = Some(origin);
+    }
+
+    /// Downloads the entire storage of the account.
+    pub fn sync_storage(&mut self, hashed_address: B256, storage_root: B256) {
+        self.pending_storages.push_back(StorageTask {
+            hashed_address,
+            storage_root,
+            origin: B256::ZERO,
+        });
+    }
+
+    /// Downloads the bytecodes.
+    pub fn sync_codes(&mut self, hashes: impl IntoIterator<Item = B256>) {
+        self.pending_codes.extend(hashes);
+    }
+
+    /// Downloads the nodes of the account trie at the given paths, with the given hashes.
+    pub fn sync_account_trie_nodes(&mut self, nodes: impl IntoIterator<Item = (Nibbles, B256)>) {
+        self.pending_nodes.extend(nodes);
+    }
+
+    /// Returns `true` if there's no pending work.
+    pub fn is_idle(&self) -> bool {
+        self.next_accounts.is_none() &&
+            self.pending_storages.is_empty() &&
+            self.pending_codes.is_empty() &&
+            self.pending_nodes.is_empty() &&
+            self.retries.is_empty() &&
+            self.inflight.is_empty() &&
+            self.queued_events.is_empty()
+    }
+
+    /// Returns the next task to request.
+    fn next_task(&mut self) -> Option<SnapTask> {
+        if let Some(task) = self.retries.pop_front() {
+            return Some(task)
+        }
+        if let Some(origin) = self.next_accounts.take() {
+            return Some(SnapTask::Accounts { origin })
+        }
+        if let Some(first) = self.pending_storages.pop_front() {
+            // ranges that don't start at the origin may be large and are requested on their own
+            let mut tasks = vec![first];
+            if first.origin.is_zero() {
+                while tasks.len() < self.config.max_storage_accounts_per_request {
+                    match self.pending_storages.front() {
+                        Some(task) if task.origin.is_zero() => {
+                            tasks.extend(self.pending_storages.pop_front())
+                        }
+                        _ => break,
+                    }
+                }
+            }
+            return Some(SnapTask::Storages(tasks))
+        }
+        if !self.pending_codes.is_empty() {
+            let count = self.pending_codes.len().min(self.config.max_codes_per_request);
+            return Some(SnapTask::ByteCodes(self.pending_codes.drain(..count).collect()))
+        }
+        if !self.pending_nodes.is_empty() {
+            let count = self.pending_nodes.len().min(self.config.max_trie_nodes_per_request);
+            return Some(SnapTask::AccountTrieNodes(self.pending_nodes.drain(..count).collect()))
+        }
+        None
+    }
+
+    /// Sends the request for the task.
+    fn send(&mut self, task: SnapTask) {
+        let root_hash = self.root;
+        let response_bytes = self.config.response_bytes;
+        let fut = match &task {
+            SnapTask::Accounts { origin } => {
+                self.client.get_account_range(GetAccountRangeMessage {
+                    request_id: 0,
+                    root_hash,
+                    starting_hash: *origin,
+                    limit_hash: MAX_HASH,
+                    response_bytes,
+                })
+            }
+            SnapTask::Storages(tasks) => self.client.get_storage_ranges(GetStorageRangesMessage {
+                request_id: 0,
+                root_hash,
+                account_hashes: tasks.iter().map(|task| task.hashed_address).collect(),
+                starting_hash: tasks[0].origin,
+                limit_hash: MAX_HASH,
+                response_bytes,
+            }),
+            SnapTask::ByteCodes(hashes) => self.client.get_byte_codes(GetByteCodesMessage {
+                request_id: 0,
+                hashes: hashes.clone(),
+                response_bytes,
+            }),
+            SnapTask::AccountTrieNodes(nodes) => self.client.get_trie_nodes(GetTrieNodesMessage {
+                request_id: 0,
+                root_hash,
+                paths: nodes
+                    .iter()
+                    .map(|(path, _)| TriePath {
+                        account_path: encode_compact_path(path),
+                        slot_paths: Vec::new(),
+                    })
+                    .collect(),
+                response_bytes,
+            }),
+        };
+        self.metrics.requests_total.increment(1);
+        self.inflight.push(InflightRequest { task: Some(task), root: root_hash, fut });
+    }
+
+    /// Retries the task after a delay.
+    fn retry(&mut self, task: SnapTask) {
+        self.retries.push_back(task);
+        if self.backoff.is_none() {
+            self.backoff = Some(Box::pin(sleep(self.config.retry_delay)));
+        }
+    }
+
+    fn on_response(
+        &mut self,
+        task: SnapTask,
+        root: B256,
+        response: PeerRequestResult<SnapResponse>,
+    ) {
+        let (peer_id, response) = match response {
+            Ok(response) => response.split(),
+            Err(err) => {
+                trace!(target: "sync::snap", %err, "Snap request failed");
+                self.metrics.failed_requests_total.increment(1);
+                self.retry(task);
+                return
+            }
+        };
+
+        if root != self.root {
+            // the pivot moved while the request was in flight
+            self.retries.push_back(task);
+            return
+        }
+
+        match self.on_peer_response(task.clone(), response) {
+            Ok(Some(event)) => {
+                self.empty_responses = 0;
+                self.queued_events.push_back(event);
+            }
+            Ok(None) => {
+                trace!(target: "sync::snap", %peer_id, "Empty snap response");
+                self.metrics.empty_responses_total.increment(1);
+                self.empty_responses += 1;
+                self.retry(task);
+                if self.empty_responses == self.config.max_empty_responses {
+                    debug!(target: "sync::snap", root=%self.root, "Pivot state unavailable");
+                    self.queued_events.push_back(SnapSyncEvent::PivotUnavailable);
+                }
+            }
+            Err(err) => {
+                debug!(target: "sync::snap", %peer_id, %err, "Invalid snap response");
+                self.metrics.invalid_responses_total.increment(1);
+                self.client.report_bad_message(peer_id);
+                self.retry(task);
+            }
+        }
+    }
+
+    /// Verifies the response to the task.
+    ///
+    /// Returns `None` if the peer doesn't serve the requested state. Work that wasn't covered by
+    /// the response is queued again.
+    fn on_peer_response(
+        &mut self,
+        task: SnapTask,
+        response: SnapResponse,
+    ) -> Result<Option<SnapSyncEvent>, InvalidSnapResponse> {
+        match (task, response) {
+            (SnapTask::Accounts { origin }, SnapResponse::AccountRange(msg)) => {
+                if msg.accounts.is_empty() && msg.proof.is_empty() {
+                    return Ok(None)
+                }
+
+                let accounts = msg
+                    .accounts
+                    .iter()
+                    .map(|account| Ok((account.hash, decode_slim_account(&account.body)?)))
+                    .collect::<Result<Vec<_>, InvalidSnapResponse>>()?;
+                let leaves = accounts
+                    .iter()
+                    .map(|(hash, account)| (*hash, alloy_rlp::encode(account)))
+                    .collect::<Vec<_>>();
+                verify_range_proof(self.root, origin, &leaves, &msg.proof)?;
+
+                // a range without proof is the entire trie
+                let next = if msg.proof.is_empty() {
+                    None
+                } else {
+                    accounts.last().and_then(|(hash, _)| next_hash(*hash))
+                };
+                self.next_accounts = next;
+                self.metrics.accounts_total.increment(accounts.len() as u64);
+
+                Ok(Some(SnapSyncEvent::Accounts { origin, accounts, next }))
+            }
+            (SnapTask::Storages(tasks), SnapResponse::StorageRanges(msg)) => {
+                if msg.slots.is_empty() && msg.proof.is_empty() {
+                    return Ok(None)
+                }
+                if msg.slots.len() > tasks.len() {
+                    return Err(InvalidSnapResponse::UnorderedKeys)
+                }
+
+                // only the last range may be partial, an empty range with proof proves that the
+                // storage of the first account has no slots after the origin
+                let last = msg.slots.len().max(1) - 1;
+                let empty = Vec::new();
+                let mut event = None;
+                for (idx, task) in tasks.iter().enumerate() {
+                    let Some(slots) = msg.slots.get(idx).or((idx == last).then_some(&empty)) else {
+                        // not covered by the response
+                        self.pending_storages.push_front(*task);
+                        continue
+                    };
+
+                    let proof = if idx == last { &msg.proof[..] } else { &[] };
+                    let leaves =
+                        slots.iter().map(|slot| (slot.hash, &slot.data[..])).collect::<Vec<_>>();
+                    verify_range_proof(task.storage_root, task.origin, &leaves, proof)?;
+                    let slots = slots
+                        .iter()
+                        .map(|slot| {
+                            Ok(StorageEntry {
+                                key: slot.hash,
+                                value: U256::decode(&mut &slot.data[..])?,
+                            })
+                        })
+                        .collect::<Result<Vec<_>, InvalidSnapResponse>>()?;
+
+                    let next = slots.last().and_then(|slot| next_hash(slot.key));
+                    let complete = proof.is_empty() || next.is_none();
+                    if let Some(origin) = next.filter(|_| !complete) {
+                        self.pending_storages.push_front(StorageTask { origin, ..*task });
+                    }
+
+                    self.metrics.storage_slots_total.increment(slots.len() as u64);
+                    let storage = SnapSyncEvent::Storage {
+                        hashed_address: task.hashed_address,
+                        origin: task.origin,
+                        slots,
+                        complete,
+                    };
+                    // all but one event are queued directly
+                    if let Some(previous) = event.replace(storage) {
+                        self.queued_events.push_back(previous);
+                    }
+                }
+                Ok(event)
+            }
+            (SnapTask::ByteCodes(hashes), SnapResponse::ByteCodes(msg)) => {
+                if msg.codes.is_empty() {
+                    return Ok(None)
+                }
+
+                let mut missing = hashes.iter().copied().collect::<B256Set>();
+                let mut codes = Vec::with_capacity(msg.codes.len());
+                for code in msg.codes {
+                    let hash = keccak256(&code);
+                    if !missing.remove(&hash) {
+                        return Err(InvalidSnapResponse::NodeHashMismatch(hash))
+                    }
+                    codes.push((hash, code));
+                }
+                self.pending_codes.extend(hashes.into_iter().filter(|hash| missing.contains(hash)));
+                self.metrics.bytecodes_total.increment(codes.len() as u64);
+
+                Ok(Some(SnapSyncEvent::ByteCodes(codes)))
+            }
+            (SnapTask::AccountTrieNodes(requested), SnapResponse::TrieNodes(msg)) => {
+                if msg.nodes.is_empty() {
+                    return Ok(None)
+                }
+                if msg.nodes.len() > requested.len() {
+                    return Err(InvalidSnapResponse::UnorderedKeys)
+                }
+
+                let mut nodes = Vec::with_capacity(msg.nodes.len());
+                for ((path, hash), node) in requested.iter().zip(msg.nodes) {
+                    if keccak256(&node) != *hash {
+                        return Err(InvalidSnapResponse::NodeHashMismatch(*hash))
+                    }
+                    nodes.push((*path, node));
+                }
+                // nodes that weren't served are requested again
+                for node in requested[nodes.len()..].iter().rev() {
+                    self.pending_nodes.push_front(*node);
+                }
+                self.metrics.trie_nodes_total.increment(nodes.len() as u64);
+
+                Ok(Some(SnapSyncEvent::AccountTrieNodes(nodes)))
+            }
+            (_, response) => {
+                trace!(target: "sync::snap", ?response, "Unexpected snap response");
+                Err(InvalidSnapResponse::UnexpectedResponse)
+            }
+        }
+    }
+}
+
+impl<C: SnapClient> Stream for SnapSyncer<C> {
+    type Item = SnapSyncEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(event) = this.queued_events.pop_front() {
+                return Poll::Ready(Some(event))
+            }
+
+            if let Some(backoff) = &mut this.backoff {
+                if backoff.as_mut().poll(cx).is_ready() {
+                    this.backoff = None;
+                }
+            }
+            if this.backoff.is_none() {
+                while this.inflight.len() < this.config.max_concurrent_requests {
+                    let Some(task) = this.next_task() else { break };
+                    this.send(task);
+                }
+            }
+            this.metrics.inflight_requests.set(this.inflight.len() as f64);
+
+            match this.inflight.poll_next_unpin(cx) {
+                Poll::Ready(Some((task, root, response))) => this.on_response(task, root, response),
+                Poll::Ready(None) | Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// A request that is sent to a peer, yields the task with the response.
+struct InflightRequest<F> {
+    task: Option<SnapTask>,
+    /// The state root the request was sent for.
+    root: B256,
+    fut: F,
+}
+
+impl<F> Future for InflightRequest<F>
+where
+    F: Future<Output = PeerRequestResult<SnapResponse>> + Unpin,
+{
+    type Output = (SnapTask, B256, PeerRequestResult<SnapResponse>);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let response = futures::ready!(Pin::new(&mut this.fut).poll(cx));
+        Poll::Ready((this.task.take().expect("polled after completion"), this.root, response))
+    }
+}
+
+/// Returns the hash that follows the given hash, `None` if it's the largest hash.
+fn next_hash(hash: B256) -> Option<B256> {
+    U256::from_be_bytes(hash.0)
+        .checked_add(U256::from(1))
+        .map(|next| B256::from(next.to_be_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{account::slim_account_body, server::SnapRequest};
+    use alloy_primitives::map::B256Map;
+    use futures::future::{ready, Ready};
+    use parking_lot::Mutex;
+    use reth_eth_wire_types::snap::{AccountData, AccountRangeMessage, ByteCodesMessage};
+    use reth_network_p2p::{download::DownloadClient, priority::Priority};
+    use reth_network_peers::{PeerId, WithPeerId};
+    use reth_primitives_traits::Account;
+    use reth_trie::{HashBuilder, EMPTY_ROOT_HASH};
+    use std::sync::Arc;
+
+    /// Serves requests from a fixed set of accounts and codes.
+    #[derive(Debug, Clone, Default)]
+    struct TestClient {
+        accounts: Vec<(B256, Account)>,
+        codes: B256Map<Bytes>,
+        requests: Arc<Mutex<Vec<SnapRequest>>>,
+        bad_peers: Arc<Mutex<Vec<PeerId>>>,
+    }
+
+    impl TestClient {
+        fn respond(&self, request: SnapRequest) -> Ready<PeerRequestResult<SnapResponse>> {
+            self.requests.lock().push(request.clone());
+            let res = match request {
+                SnapRequest::GetAccountRange(req) => {
+                    // serves the entire trie without proof
+                    let accounts = self
+                        .accounts
+                        .iter()
+                        .map(|(hash, account)| AccountData {
+                            hash: *hash,
+                            body: slim_account_body(account, EMPTY_ROOT_HASH),
+                        })
+                        .collect();
+                    SnapResponse::AccountRange(AccountRangeMessage {
+                        request_id: req.request_id,
+                        accounts,
+                        proof: Vec::new(),
+                    })
+                }
+                SnapRequest::GetByteCodes(req) => SnapResponse::ByteCodes(ByteCodesMessage {
+                    request_id: req.request_id,
+                    codes: req
+                        .hashes
+                        .iter()
+                        .filter_map(|hash| self.codes.get(hash).cloned())
+                        .collect(),
+                }),
+                _ => unimplemented!(),
+            };
+            ready(Ok(WithPeerId::new(PeerId::ZERO, res)))
+        }
+    }
+
+    impl DownloadClient for TestClient {
+        fn report_bad_message(&self, peer_id: PeerId) {
+            self.bad_peers.lock().push(peer_id);
+        }
+
+        fn num_connected_peers(&self) -> usize {
+            1
+        }
+    }
+
+    impl SnapClient for TestClient {
+        type Output = Ready<PeerRequestResult<SnapResponse>>;
+
+        fn get_account_range_with_priority(
+            &self,
+            request: GetAccountRangeMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            self.respond(SnapRequest::GetAccountRange(request))
+        }
+
+        fn get_storage_ranges(&self, request: GetStorageRangesMessage) -> Self::Output {
+            self.respond(SnapRequest::GetStorageRanges(request))
+        }
+
+        fn get_storage_ranges_with_priority(
+            &self,
+            request: GetStorageRangesMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            self.respond(SnapRequest::GetStorageRanges(request))
+        }
+
+        fn get_byte_codes(&self, request: GetByteCodesMessage) -> Self::Output {
+            self.respond(SnapRequest::GetByteCodes(request))
+        }
+
+        fn get_byte_codes_with_priority(
+            &self,
+            request: GetByteCodesMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            self.respond(SnapRequest::GetByteCodes(request))
+        }
+
+        fn get_trie_nodes(&self, request: GetTrieNodesMessage) -> Self::Output {
+            self.respond(SnapRequest::GetTrieNodes(request))
+        }
+
+        fn get_trie_nodes_with_priority(
+            &self,
+            request: GetTrieNodesMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            self.respond(SnapRequest::GetTrieNodes(request))
+        }
+    }
+
+    fn test_accounts() -> (Vec<(B256, Account)>, B256) {
+        let mut accounts = (0..8u8)
+            .map(|i| {
+                (
+                    keccak256([i]),
+                    Account { nonce: i.into(), balance: U256::from(i), bytecode_hash: None },
+                )
+            })
+            .collect::<Vec<_>>();
+        accounts.sort_unstable_by_key(|(hash, _)| *hash);
+        let mut hash_builder = HashBuilder::default();
+        for (hash, account) in &accounts {
+            hash_builder.add_leaf(
+                Nibbles::unpack(hash),
+                &alloy_rlp::encode(account.into_trie_account(EMPTY_ROOT_HASH)),
+            );
+        }
+        (accounts, hash_builder.root())
+    }
+
+    #[tokio::test]
+    async fn sync_entire_account_trie() {
+        let (accounts, root) = test_accounts();
+        let client = TestClient { accounts: accounts.clone(), ..Default::default() };
+        let mut syncer = SnapSyncer::new(client, SnapSyncConfig::default());
+        syncer.set_root(root);
+        syncer.sync_accounts(B256::ZERO);
+        assert!(!syncer.is_idle());
+
+        let event = syncer.next().await.unwrap();
+        let SnapSyncEvent::Accounts { origin, accounts: synced, next } = event else {
+            panic!("unexpected event {event:?}")
+        };
+        assert_eq!(origin, B256::ZERO);
+        assert_eq!(next, None);
+        assert_eq!(synced.len(), accounts.len());
+        assert!(syncer.is_idle());
+    }
+
+    #[tokio::test]
+    async fn reject_invalid_account_range() {
+        let (accounts, root) = test_accounts();
+        // the range is missing an account
+        let client = TestClient { accounts: accounts[1..].to_vec(), ..Default::default() };
+        let mut syncer = SnapSyncer::new(client.clone(), SnapSyncConfig::default());
+        syncer.set_root(root);
+        syncer.sync_accounts(B256::ZERO);
+
+        let poll = futures::poll!(syncer.next());
+        assert!(poll.is_pending());
+        assert_eq!(*client.bad_peers.lock(), vec![PeerId::ZERO]);
+        assert!(!syncer.is_idle());
+    }
+
+    #[tokio::test]
+    async fn sync_codes() {
+        let code = Bytes::from_static(&[0x60, 0x00]);
+        let hash = keccak256(&code);
+        let missing = B256::repeat_byte(0x42);
+        let client =
+            TestClient { codes: B256Map::from_iter([(hash, code.clone())]), ..Default::default() };
+        let mut syncer = SnapSyncer::new(
+            client.clone(),
+            SnapSyncConfig { retry_delay: Duration::from_secs(3600), ..Default::default() },
+        );
+        syncer.sync_codes([hash, missing]);
+
+        assert_eq!(syncer.next().await.unwrap(), SnapSyncEvent::ByteCodes(vec![(hash, code)]));
+        // the missing code is requested again
+        assert!(futures::poll!(syncer.next()).is_pending());
+        assert_eq!(client.requests.lock().len(), 2);
+        assert!(!syncer.is_idle());
+    }
+}
@@ -0,0 +1,210 @@
+//! Verification of `snap/1` range proofs.
+
+use alloy_primitives::{keccak256, map::B256Map, Bytes, B256};
+use alloy_rlp::Decodable;
+use reth_trie::{HashBuilder, Nibbles, RlpNode, TrieNode};
+
+/// A response that doesn't match the requested state.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidSnapResponse {
+    /// The keys are not in ascending order or below the requested origin.
+    #[error("keys are not sorted or below the requested origin")]
+    UnorderedKeys,
+    /// A range without proof is not the entire trie.
+    #[error("unproven range doesn't match the root, expected {expected}, got {got}")]
+    RootMismatch {
+        /// The requested root.
+        expected: B256,
+        /// The root of the returned range.
+        got: B256,
+    },
+    /// A proof node required to verify the boundaries of the range is missing.
+    #[error("missing proof node {0}")]
+    MissingProofNode(B256),
+    /// The proof doesn't prove the boundaries of the range.
+    #[error("invalid proof of the range boundary {0}")]
+    InvalidBoundary(B256),
+    /// A trie node or bytecode doesn't match the requested hash.
+    #[error("response doesn't match the requested hash {0}")]
+    NodeHashMismatch(B256),
+    /// The response doesn't match the type of the request.
+    #[error("unexpected response type")]
+    UnexpectedResponse,
+    /// An account or node can't be decoded.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+}
+
+/// Verifies that the leaves are a consecutive range of the trie with the given root, starting at
+/// the origin.
+///
+/// A range without proof must be the entire trie. Otherwise the proof must prove the origin and the
+/// last leaf. Whether leaves are missing within the range can't be detected from the proof, missing
+/// leaves are detected by the root check after the download and fixed by healing the trie.
+pub fn verify_range_proof<V: AsRef<[u8]>>(
+    root: B256,
+    origin: B256,
+    leaves: &[(B256, V)],
+    proof: &[Bytes],
+) -> Result<(), InvalidSnapResponse> {
+    let ordered = leaves.windows(2).all(|pair| pair[0].0 < pair[1].0);
+    if !ordered || leaves.first().is_some_and(|(key, _)| *key < origin) {
+        return Err(InvalidSnapResponse::UnorderedKeys)
+    }
+
+    if proof.is_empty() {
+        let mut hash_builder = HashBuilder::default();
+        for (key, value) in leaves {
+            hash_builder.add_leaf(Nibbles::unpack(key), value.as_ref());
+        }
+        let got = hash_builder.root();
+        if got != root {
+            return Err(InvalidSnapResponse::RootMismatch { expected: root, got })
+        }
+        return Ok(())
+    }
+
+    let nodes = proof.iter().map(|node| (keccak256(node), node)).collect::<B256Map<_>>();
+
+    let origin_value = proven_value(root, origin, &nodes)?;
+    let expected =
+        leaves.first().filter(|(key, _)| *key == origin).map(|(_, value)| value.as_ref());
+    if origin_value.as_deref() != expected {
+        return Err(InvalidSnapResponse::InvalidBoundary(origin))
+    }
+
+    if let Some((key, value)) = leaves.last() {
+        if proven_value(root, *key, &nodes)?.as_deref() != Some(value.as_ref()) {
+            return Err(InvalidSnapResponse::InvalidBoundary(*key))
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the value of the key in the trie with the given root, or `None` if the proof proves
+/// that the key doesn't exist.
+fn proven_value(
+    root: B256,
+    key: B256,
+    nodes: &B256Map<&Bytes>,
+) -> Result<Option<Vec<u8>>, InvalidSnapResponse> {
+    let path = Nibbles::unpack(key);
+    let mut encoded = nodes.get(&root).ok_or(InvalidSnapResponse::MissingProofNode(root))?.to_vec();
+    let mut depth = 0;
+
+    loop {
+        match TrieNode::decode(&mut &encoded[..])? {
+            TrieNode::EmptyRoot => return Ok(None),
+            TrieNode::Branch(branch) => {
+                if depth >= path.len() {
+                    return Err(InvalidSnapResponse::InvalidBoundary(key))
+                }
+                let nibble = path.get_unchecked(depth);
+                if !branch.state_mask.is_bit_set(nibble) {
+                    return Ok(None)
+                }
+                let idx = (0..nibble).filter(|&i| branch.state_mask.is_bit_set(i)).count();
+                encoded = resolve(&branch.stack[idx], nodes)?;
+                depth += 1;
+            }
+            TrieNode::Extension(extension) => {
+                if !path.slice(depth..).starts_with(&extension.key) {
+                    return Ok(None)
+                }
+                depth += extension.key.len();
+                encoded = resolve(&extension.child, nodes)?;
+            }
+            TrieNode::Leaf(leaf) => {
+                return Ok((path.slice(depth..) == leaf.key).then_some(leaf.value))
+            }
+        }
+    }
+}
+
+/// Returns the encoded node the reference points to.
+fn resolve(node: &RlpNode, nodes: &B256Map<&Bytes>) -> Result<Vec<u8>, InvalidSnapResponse> {
+    match node.as_hash() {
+        Some(hash) => {
+            Ok(nodes.get(&hash).ok_or(InvalidSnapResponse::MissingProofNode(hash))?.to_vec())
+        }
+        // nodes shorter than 32 bytes are embedded in their parent
+        None => Ok(node.to_vec()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use reth_trie_common::proof::ProofRetainer;
+
+    /// Returns the root of the leaves and the proof of the given keys.
+    fn trie_with_proof(leaves: &[(B256, Vec<u8>)], targets: &[B256]) -> (B256, Vec<Bytes>) {
+        let retainer = ProofRetainer::from_iter(targets.iter().map(|key| Nibbles::unpack(key)));
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+        for (key, value) in leaves {
+            hash_builder.add_leaf(Nibbles::unpack(key), value);
+        }
+        let root = hash_builder.root();
+        let proof = hash_builder
+            .take_proof_nodes()
+            .into_nodes_sorted()
+            .into_iter()
+            .map(|(_, node)| node)
+            .collect();
+        (root, proof)
+    }
+
+    fn leaves(count: u8) -> Vec<(B256, Vec<u8>)> {
+        let mut leaves = (0..count)
+            .map(|i| (keccak256([i]), alloy_rlp::encode(U256::from(i) + U256::from(1))))
+            .collect::<Vec<_>>();
+        leaves.sort_unstable_by_key(|(key, _)| *key);
+        leaves
+    }
+
+    #[test]
+    fn verify_entire_trie() {
+        let leaves = leaves(16);
+        let (root, _) = trie_with_proof(&leaves, &[]);
+        verify_range_proof(root, B256::ZERO, &leaves, &[]).unwrap();
+
+        assert!(matches!(
+            verify_range_proof(root, B256::ZERO, &leaves[1..], &[]),
+            Err(InvalidSnapResponse::RootMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn verify_partial_range() {
+        let leaves = leaves(64);
+        let range = &leaves[10..20];
+        let origin = range[0].0;
+        let last = range[range.len() - 1].0;
+        let (root, proof) = trie_with_proof(&leaves, &[origin, last]);
+        verify_range_proof(root, origin, range, &proof).unwrap();
+
+        // the proof of an absent origin
+        let absent = B256::from(U256::from_be_bytes(origin.0) - U256::from(1));
+        let (root, proof) = trie_with_proof(&leaves, &[absent, last]);
+        verify_range_proof(root, absent, range, &proof).unwrap();
+
+        // a tampered boundary is rejected
+        let mut tampered = range.to_vec();
+        tampered.last_mut().unwrap().1 = vec![0x01];
+        assert!(verify_range_proof(root, absent, &tampered, &proof).is_err());
+
+        // unordered leaves are rejected
+        let mut unordered = range.to_vec();
+        unordered.swap(0, 1);
+        assert!(matches!(
+            verify_range_proof(root, absent, &unordered, &proof),
+            Err(InvalidSnapResponse::UnorderedKeys)
+        ));
+
+        // a proof without the last leaf is rejected
+        let (_, proof) = trie_with_proof(&leaves, &[absent]);
+        assert!(verify_range_proof(root, absent, range, &proof).is_err());
+    }
+}
@@ -0,0 +1,910 @@
+//! The snap sync stage.
+
+use crate::{
+    server::{account_trie_node, padded_key, storage_root, ServedState},
+    sync::{SnapSyncEvent, SnapSyncer},
+};
+use alloy_consensus::BlockHeader;
+use alloy_primitives::{keccak256, map::B256Set, BlockNumber, Bytes, B256, KECCAK_EMPTY};
+use alloy_rlp::Decodable;
+use futures::StreamExt;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_network_p2p::snap::client::SnapClient;
+use reth_primitives_traits::{Account, Bytecode};
+use reth_prune_types::{PruneCheckpoint, PruneMode, PruneSegment};
+use reth_stages_api::{
+    ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId, UnwindInput, UnwindOutput,
+};
+use reth_storage_api::{
+    DBProvider, HeaderProvider, PruneCheckpointWriter, StageCheckpointReader,
+    StageCheckpointWriter, TrieWriter,
+};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use reth_trie::{Nibbles, StateRoot, TrieAccount, TrieNode, TriePrefixSetsMut, EMPTY_ROOT_HASH};
+use reth_trie_db::DatabaseStateRoot;
+use std::task::{ready, Context, Poll};
+use tracing::{debug, info, warn};
+
+/// The ID of the [`SnapSyncStage`].
+pub const SNAP_SYNC_STAGE_ID: StageId = StageId::Other("SnapSync");
+
+/// The default number of events that are buffered before they are written.
+pub const DEFAULT_SNAP_SYNC_COMMIT_THRESHOLD: usize = 512;
+
+/// The stages whose work is replaced by snap sync, their checkpoints are set to the pivot once the
+/// state was downloaded.
+const SKIPPED_STAGES: [StageId; 8] = [
+    StageId::Execution,
+    StageId::MerkleUnwind,
+    StageId::AccountHashing,
+    StageId::StorageHashing,
+    StageId::MerkleExecute,
+    StageId::MerkleChangeSets,
+    StageId::IndexAccountHistory,
+    StageId::IndexStorageHistory,
+];
+
+/// The snap sync stage downloads the state of the target block from `snap/1` peers instead of
+/// executing all blocks.
+///
+/// The target block is used as pivot. Its hashed state is downloaded via the [`SnapSyncer`] and
+/// written to the hashed state tables, after which the tries are built. Because the pivot moves
+/// while the download is in progress, the state is inconsistent and the account trie is healed:
+/// trie nodes that differ from the pivot are downloaded top-down until the state root matches.
+/// Storage tries that don't match are downloaded again as a whole. The tries are built once, after
+/// that only the paths written while healing are recomputed.
+///
+/// Once the state root matches, the checkpoints of the execution, hashing, merkle and history
+/// stages are set to the pivot, so the pipeline continues with regular execution from there.
+/// Receipts, changesets and history before the pivot are not available, so snap sync requires
+/// receipts pruning to be configured, and the stage can't be unwound below the pivot.
+///
+/// The stage is a no-op once blocks were executed. If peers stop serving the state of the pivot,
+/// the stage finishes without progress, so the next pipeline run continues with a newer pivot.
+/// Downloaded accounts are tracked in the stage checkpoint progress, so the download is resumed
+/// after a restart. Once the state is synced, the progress holds the pivot.
+#[derive(Debug)]
+pub struct SnapSyncStage<C: SnapClient> {
+    syncer: SnapSyncer<C>,
+    /// The pivot block and its state root.
+    pivot: Option<(BlockNumber, B256)>,
+    /// Verified state that is written on the next execution.
+    buffer: Vec<SnapSyncEvent>,
+    /// Prefixes of the state written since the tries were built, `None` if the tries have to be
+    /// built from scratch.
+    prefix_sets: Option<TriePrefixSetsMut>,
+    commit_threshold: usize,
+}
+
+impl<C: SnapClient> SnapSyncStage<C> {
+    /// Creates a new stage that downloads state via the given syncer.
+    pub const fn new(syncer: SnapSyncer<C>) -> Self {
+        Self {
+            syncer,
+            pivot: None,
+            buffer: Vec::new(),
+            prefix_sets: None,
+            commit_threshold: DEFAULT_SNAP_SYNC_COMMIT_THRESHOLD,
+        }
+    }
+
+    /// Sets the number of events that are buffered before they are written.
+    pub const fn with_commit_threshold(mut self, commit_threshold: usize) -> Self {
+        self.commit_threshold = commit_threshold;
+        self
+    }
+
+    /// Returns `true` if the stage has no work for the given input.
+    fn is_skipped<Provider: StageCheckpointReader>(
+        provider: &Provider,
+        input: &ExecInput,
+    ) -> ProviderResult<bool> {
+        let executed = provider
+            .get_stage_checkpoint(StageId::Execution)?
+            .is_some_and(|checkpoint| checkpoint.block_number > 0);
+        Ok(input.target_reached() || executed)
+    }
+
+    /// Writes the verified state, queueing any state that is still missing.
+    fn write_event<Provider>(
+        &mut self,
+        provider: &Provider,
+        event: SnapSyncEvent,
+    ) -> Result<(), StageError>
+    where
+        Provider: DBProvider<Tx: DbTxMut> + StageCheckpointWriter,
+    {
+        let tx = provider.tx_ref();
+        match event {
+            SnapSyncEvent::Accounts { accounts, next, .. } => {
+                let mut cursor = tx.cursor_write::<tables::HashedAccounts>()?;
+                let mut codes = B256Set::default();
+                for (hashed_address, account) in accounts {
+                    self.on_account(tx, hashed_address, account, &mut codes)?;
+                    cursor.upsert(hashed_address, &into_account(account))?;
+                }
+                self.syncer.sync_codes(codes);
+
+                // an empty progress marks the account download as complete
+                let progress = next.map(|next| next.to_vec()).unwrap_or_default();
+                provider.save_stage_checkpoint_progress(SNAP_SYNC_STAGE_ID, progress)?;
+            }
+            SnapSyncEvent::Storage { hashed_address, origin, slots, .. } => {
+                let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+                if origin.is_zero() {
+                    wipe_storage(tx, hashed_address)?;
+                }
+                self.mark_storage(hashed_address, slots.iter().map(|entry| entry.key));
+                for entry in slots {
+                    if cursor
+                        .seek_by_key_subkey(hashed_address, entry.key)?
+                        .is_some_and(|existing| existing.key == entry.key)
+                    {
+                        cursor.delete_current()?;
+                    }
+                    if !entry.value.is_zero() {
+                        cursor.upsert(hashed_address, &entry)?;
+                    }
+                }
+            }
+            SnapSyncEvent::ByteCodes(codes) => {
+                for (hash, code) in codes {
+                    tx.put::<tables::Bytecodes>(hash, Bytecode::new_raw(code))?;
+                }
+            }
+            SnapSyncEvent::AccountTrieNodes(nodes) => {
+                for (path, node) in nodes {
+                    self.heal_node(tx, path, node)?;
+                }
+            }
+            SnapSyncEvent::PivotUnavailable => {}
+        }
+        Ok(())
+    }
+
+    /// Queues the storage and bytecode of the downloaded account if they are missing locally, and
+    /// marks the account as written.
+    fn on_account<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        hashed_address: B256,
+        account: TrieAccount,
+        codes: &mut B256Set,
+    ) -> ProviderResult<()> {
+        self.mark_account(hashed_address);
+        if account.storage_root == EMPTY_ROOT_HASH {
+            wipe_storage(tx, hashed_address)?;
+        } else if self.local_storage_root(tx, hashed_address)? != Some(account.storage_root) {
+            self.syncer.sync_storage(hashed_address, account.storage_root);
+        }
+        if account.code_hash != KECCAK_EMPTY &&
+            tx.get::<tables::Bytecodes>(account.code_hash)?.is_none()
+        {
+            codes.insert(account.code_hash);
+        }
+        Ok(())
+    }
+
+    /// Returns the root of the local storage of the account, `None` if it's unknown.
+    ///
+    /// The root is only known once the tries were built, and only if the storage wasn't written
+    /// since.
+    fn local_storage_root<TX: DbTx>(
+        &self,
+        tx: &TX,
+        hashed_address: B256,
+    ) -> ProviderResult<Option<B256>> {
+        let Some(prefix_sets) = &self.prefix_sets else { return Ok(None) };
+        if prefix_sets.storage_prefix_sets.contains_key(&hashed_address) ||
+            tx.get::<tables::HashedAccounts>(hashed_address)?.is_none()
+        {
+            return Ok(None)
+        }
+
+        let mut storage_trie_cursor = tx.cursor_dup_read::<tables::StoragesTrie>()?;
+        let mut storage_cursor = tx.cursor_dup_read::<tables::HashedStorages>()?;
+        storage_root(
+            &ServedState::latest(tx),
+            &mut storage_trie_cursor,
+            &mut storage_cursor,
+            hashed_address,
+        )
+        .map(Some)
+    }
+
+    /// Marks the account as written since the tries were built.
+    fn mark_account(&mut self, hashed_address: B256) {
+        if let Some(prefix_sets) = &mut self.prefix_sets {
+            prefix_sets.account_prefix_set.insert(Nibbles::unpack(hashed_address));
+        }
+    }
+
+    /// Marks the storage slots of the account as written since the tries were built.
+    fn mark_storage(&mut self, hashed_address: B256, slots: impl IntoIterator<Item = B256>) {
+        if let Some(prefix_sets) = &mut self.prefix_sets {
+            prefix_sets.account_prefix_set.insert(Nibbles::unpack(hashed_address));
+            prefix_sets
+                .storage_prefix_sets
+                .entry(hashed_address)
+                .or_default()
+                .extend_keys(slots.into_iter().map(Nibbles::unpack));
+        }
+    }
+
+    /// Replaces the local state below the path with the state of the downloaded node, queueing
+    /// the children that differ from the local trie.
+    ///
+    /// Malformed nodes are requested again.
+    fn heal_node<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        path: Nibbles,
+        node: Bytes,
+    ) -> Result<(), StageError> {
+        let actions = match heal_actions(path, &node) {
+            Ok(actions) => actions,
+            Err(err) => {
+                debug!(target: "sync::stages::snap", ?path, %err, "Requesting malformed trie node again");
+                self.syncer.sync_account_trie_nodes([(path, keccak256(&node))]);
+                return Ok(())
+            }
+        };
+
+        for action in actions {
+            match action {
+                HealAction::Delete { path, keep } => {
+                    let deleted = delete_accounts(tx, &path, |key| {
+                        keep.is_none_or(|keep| !key.starts_with(&keep))
+                    })?;
+                    for hashed_address in deleted {
+                        self.mark_account(hashed_address);
+                    }
+                }
+                HealAction::Queue { path, hash } => self.queue_node(tx, path, hash)?,
+                HealAction::Account { hashed_address, account } => {
+                    let mut codes = B256Set::default();
+                    self.on_account(tx, hashed_address, account, &mut codes)?;
+                    self.syncer.sync_codes(codes);
+                    tx.put::<tables::HashedAccounts>(hashed_address, into_account(account))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Queues the download of the node, unless the local node at the path matches.
+    fn queue_node<TX: DbTx>(&mut self, tx: &TX, path: Nibbles, hash: B256) -> ProviderResult<()> {
+        if account_trie_node(&ServedState::latest(tx), path)?
+            .is_none_or(|local| keccak256(local) != hash)
+        {
+            self.syncer.sync_account_trie_nodes([(path, hash)]);
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered state, and finalizes the sync once the state matches the pivot.
+    fn write_and_verify<Provider>(
+        &mut self,
+        provider: &Provider,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError>
+    where
+        Provider:
+            DBProvider<Tx: DbTxMut> + StageCheckpointWriter + PruneCheckpointWriter + TrieWriter,
+    {
+        let target = input.target();
+        let mut pivot_unavailable = false;
+        for event in std::mem::take(&mut self.buffer) {
+            pivot_unavailable |= event == SnapSyncEvent::PivotUnavailable;
+            self.write_event(provider, event)?;
+        }
+        if pivot_unavailable {
+            warn!(target: "sync::stages::snap", pivot = target, "Peers no longer serve the state of the pivot, waiting for a newer pivot");
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        if !self.syncer.is_idle() {
+            return Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+        }
+
+        let (_, root) = self.pivot.expect("pivot is set");
+        if !self.verify(provider, root)? {
+            return Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+        }
+
+        self.finalize(provider, target)?;
+        info!(target: "sync::stages::snap", pivot = target, %root, "Snap sync finished");
+        Ok(ExecOutput::done(StageCheckpoint::new(target)))
+    }
+
+    /// Updates the tries and returns `true` if the state matches the pivot.
+    ///
+    /// Otherwise, healing of the trie or the download of missing bytecodes is queued.
+    fn verify<Provider>(&mut self, provider: &Provider, root: B256) -> Result<bool, StageError>
+    where
+        Provider: DBProvider<Tx: DbTxMut> + TrieWriter,
+    {
+        let tx = provider.tx_ref();
+        let state_root = match self.prefix_sets.take() {
+            // only the paths written since the tries were built are recomputed
+            Some(prefix_sets) => StateRoot::from_tx(tx).with_prefix_sets(prefix_sets.freeze()),
+            None => {
+                // writes of previous runs aren't tracked, so the tries are built from scratch
+                tx.clear::<tables::AccountsTrie>()?;
+                tx.clear::<tables::StoragesTrie>()?;
+                // bytecodes aren't covered by the state root, and pending downloads of previous
+                // runs are lost
+                self.sync_missing_codes(tx)?;
+                StateRoot::from_tx(tx)
+            }
+        };
+        let (state_root, updates) =
+            state_root.root_with_updates().map_err(|err| StageError::Fatal(Box::new(err)))?;
+        provider.write_trie_updates(updates)?;
+        self.prefix_sets = Some(TriePrefixSetsMut::default());
+
+        if state_root != root {
+            debug!(target: "sync::stages::snap", %state_root, %root, "Healing state trie");
+            self.syncer.sync_account_trie_nodes([(Nibbles::default(), root)]);
+            return Ok(false)
+        }
+
+        // missing bytecodes are downloaded before the sync finishes
+        Ok(self.syncer.is_idle())
+    }
+
+    /// Queues the download of the bytecodes of all accounts that are missing locally.
+    fn sync_missing_codes<TX: DbTx>(&mut self, tx: &TX) -> ProviderResult<()> {
+        let mut codes = B256Set::default();
+        for entry in tx.cursor_read::<tables::HashedAccounts>()?.walk(None)? {
+            let (_, account) = entry?;
+            if let Some(hash) = account.bytecode_hash.filter(|hash| *hash != KECCAK_EMPTY) &&
+                !codes.contains(&hash) &&
+                tx.get::<tables::Bytecodes>(hash)?.is_none()
+            {
+                codes.insert(hash);
+            }
+        }
+        if !codes.is_empty() {
+            debug!(target: "sync::stages::snap", codes = codes.len(), "Downloading missing bytecodes");
+            self.syncer.sync_codes(codes);
+        }
+        Ok(())
+    }
+
+    /// Marks the state of the pivot as complete, so the pipeline continues after the pivot.
+    fn finalize<Provider>(&self, provider: &Provider, pivot: BlockNumber) -> Result<(), StageError>
+    where
+        Provider: DBProvider + StageCheckpointWriter + PruneCheckpointWriter,
+    {
+        for stage in SKIPPED_STAGES {
+            provider.save_stage_checkpoint(stage, StageCheckpoint::new(pivot))?;
+        }
+
+        // receipts, changesets and history before the pivot don't exist
+        let prune_modes = provider.prune_modes_ref();
+        let fallback = PruneMode::Before(pivot + 1);
+        for (segment, mode) in [
+            (PruneSegment::Receipts, prune_modes.receipts),
+            (PruneSegment::AccountHistory, prune_modes.account_history),
+            (PruneSegment::StorageHistory, prune_modes.storage_history),
+        ] {
+            provider.save_prune_checkpoint(
+                segment,
+                PruneCheckpoint {
+                    block_number: Some(pivot),
+                    tx_number: None,
+                    prune_mode: mode.unwrap_or(fallback),
+                },
+            )?;
+        }
+
+        // the state can't be unwound below the pivot
+        provider
+            .save_stage_checkpoint_progress(SNAP_SYNC_STAGE_ID, pivot.to_be_bytes().to_vec())?;
+        Ok(())
+    }
+}
+
+impl<C, Provider> Stage<Provider> for SnapSyncStage<C>
+where
+    C: SnapClient + 'static,
+    Provider: DBProvider<Tx: DbTxMut>
+        + HeaderProvider
+        + StageCheckpointReader
+        + StageCheckpointWriter
+        + PruneCheckpointWriter
+        + TrieWriter,
+{
+    fn id(&self) -> StageId {
+        SNAP_SYNC_STAGE_ID
+    }
+
+    fn poll_execute_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+        input: ExecInput,
+    ) -> Poll<Result<(), StageError>> {
+        // the pivot is set up on execution
+        if input.target_reached() || self.pivot.is_none_or(|(pivot, _)| pivot != input.target()) {
+            return Poll::Ready(Ok(()))
+        }
+
+        while self.buffer.len() < self.commit_threshold {
+            if self.syncer.is_idle() {
+                break
+            }
+            match ready!(self.syncer.poll_next_unpin(cx)) {
+                Some(SnapSyncEvent::PivotUnavailable) => {
+                    self.buffer.push(SnapSyncEvent::PivotUnavailable);
+                    break
+                }
+                Some(event) => self.buffer.push(event),
+                None => break,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn execute(&mut self, provider: &Provider, input: ExecInput) -> Result<ExecOutput, StageError> {
+        if Self::is_skipped(provider, &input)? {
+            self.buffer.clear();
+            return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+        }
+
+        if !provider.prune_modes_ref().has_receipts_pruning() {
+            return Err(StageError::Fatal(
+                "snap sync requires receipts pruning to be configured".into(),
+            ))
+        }
+
+        let target = input.target();
+        if self.pivot.is_none_or(|(pivot, _)| pivot != target) {
+            let header = provider
+                .header_by_number(target)?
+                .ok_or(ProviderError::HeaderNotFound(target.into()))?;
+            let root = header.state_root();
+
+            if self.pivot.is_none() {
+                match provider.get_stage_checkpoint_progress(SNAP_SYNC_STAGE_ID)? {
+                    Some(progress) if progress.is_empty() => {}
+                    Some(progress) if progress.len() == B256::len_bytes() => {
+                        self.syncer.sync_accounts(B256::from_slice(&progress))
+                    }
+                    _ => self.syncer.sync_accounts(B256::ZERO),
+                }
+            }
+            info!(target: "sync::stages::snap", pivot = target, %root, "Snap syncing state");
+            self.syncer.set_root(root);
+            self.pivot = Some((target, root));
+            return Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+        }
+
+        let output = self.write_and_verify(provider, input);
+        if output.is_err() {
+            // the writes are discarded, so the tries can't be updated incrementally
+            self.prefix_sets = None;
+        }
+        output
+    }
+
+    fn unwind(
+        &mut self,
+        provider: &Provider,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        // the state after the pivot is unwound by the hashing and merkle stages, but there are no
+        // changesets to revert the state of the pivot
+        if let Some(pivot) = synced_pivot(provider)? &&
+            input.unwind_to < pivot
+        {
+            return Err(StageError::Fatal(
+                format!("unwinding below the snap sync pivot {pivot} is unsupported").into(),
+            ))
+        }
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
+    }
+}
+
+/// Returns the pivot whose state was synced, `None` if snap sync didn't finish.
+fn synced_pivot<Provider: StageCheckpointReader>(
+    provider: &Provider,
+) -> ProviderResult<Option<BlockNumber>> {
+    Ok(provider
+        .get_stage_checkpoint_progress(SNAP_SYNC_STAGE_ID)?
+        .and_then(|progress| <[u8; 8]>::try_from(progress).ok())
+        .map(BlockNumber::from_be_bytes))
+}
+
+/// A change of the local state, derived from a downloaded node of the account trie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HealAction {
+    /// Deletes the accounts below the path, except the ones below the kept path.
+    Delete { path: Nibbles, keep: Option<Nibbles> },
+    /// Downloads the node at the path, unless the local node has the same hash.
+    Queue { path: Nibbles, hash: B256 },
+    /// Writes the account.
+    Account { hashed_address: B256, account: TrieAccount },
+}
+
+/// Returns the changes that replace the local state below the path with the state of the
+/// downloaded node.
+///
+/// The node and the nodes embedded in it are decoded entirely before any change is applied, so a
+/// malformed node leaves the local state untouched.
+fn heal_actions(path: Nibbles, node: &[u8]) -> alloy_rlp::Result<Vec<HealAction>> {
+    const MAX_PATH_LEN: usize = B256::len_bytes() * 2;
+
+    let mut actions = Vec::new();
+    let mut nodes = vec![(path, node.to_vec())];
+    while let Some((path, node)) = nodes.pop() {
+        match TrieNode::decode(&mut &node[..])? {
+            TrieNode::EmptyRoot => actions.push(HealAction::Delete { path, keep: None }),
+            TrieNode::Branch(branch) => {
+                if path.len() >= MAX_PATH_LEN {
+                    return Err(alloy_rlp::Error::Custom("branch node below a full path"))
+                }
+                let mut children = branch.stack.iter();
+                for nibble in 0..16u8 {
+                    let mut child_path = path;
+                    child_path.push_unchecked(nibble);
+                    if !branch.state_mask.is_bit_set(nibble) {
+                        actions.push(HealAction::Delete { path: child_path, keep: None });
+                        continue
+                    }
+                    let child = children
+                        .next()
+                        .ok_or(alloy_rlp::Error::Custom("branch node is missing a child"))?;
+                    match child.as_hash() {
+                        Some(hash) => actions.push(HealAction::Queue { path: child_path, hash }),
+                        // nodes shorter than 32 bytes are embedded in their parent
+                        None => nodes.push((child_path, child.to_vec())),
+                    }
+                }
+            }
+            TrieNode::Extension(extension) => {
+                if path.len() + extension.key.len() >= MAX_PATH_LEN {
+                    return Err(alloy_rlp::Error::Custom("extension node exceeds a full path"))
+                }
+                let mut child_path = path;
+                child_path.extend(&extension.key);
+                actions.push(HealAction::Delete { path, keep: Some(child_path) });
+                match extension.child.as_hash() {
+                    Some(hash) => actions.push(HealAction::Queue { path: child_path, hash }),
+                    None => nodes.push((child_path, extension.child.to_vec())),
+                }
+            }
+            TrieNode::Leaf(leaf) => {
+                if path.len() + leaf.key.len() != MAX_PATH_LEN {
+                    return Err(alloy_rlp::Error::Custom("leaf node doesn't end at a full path"))
+                }
+                let mut key = path;
+                key.extend(&leaf.key);
+                let account = TrieAccount::decode(&mut &leaf.value[..])?;
+                actions.push(HealAction::Delete { path, keep: Some(key) });
+                actions.push(HealAction::Account { hashed_address: padded_key(&key), account });
+            }
+        }
+    }
+    Ok(actions)
+}
+
+/// Converts the trie account to the account stored in the hashed state.
+fn into_account(account: TrieAccount) -> Account {
+    Account {
+        nonce: account.nonce,
+        balance: account.balance,
+        bytecode_hash: (account.code_hash != KECCAK_EMPTY).then_some(account.code_hash),
+    }
+}
+
+/// Deletes the storage of the account and its storage trie.
+fn wipe_storage<TX: DbTxMut + DbTx>(tx: &TX, hashed_address: B256) -> ProviderResult<()> {
+    let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+    if cursor.seek_exact(hashed_address)?.is_some() {
+        cursor.delete_current_duplicates()?;
+    }
+    let mut cursor = tx.cursor_dup_write::<tables::StoragesTrie>()?;
+    if cursor.seek_exact(hashed_address)?.is_some() {
+        cursor.delete_current_duplicates()?;
+    }
+    Ok(())
+}
+
+/// Deletes the accounts below the path that match the filter, including their storage.
+///
+/// Returns the hashed addresses of the deleted accounts.
+fn delete_accounts<TX: DbTxMut + DbTx>(
+    tx: &TX,
+    path: &Nibbles,
+    filter: impl Fn(&Nibbles) -> bool,
+) -> ProviderResult<Vec<B256>> {
+    let mut deleted = Vec::new();
+    let mut cursor = tx.cursor_write::<tables::HashedAccounts>()?;
+    let mut entry = cursor.seek(padded_key(path))?;
+    while let Some((hashed_address, _)) = entry {
+        let key = Nibbles::unpack(hashed_address);
+        if !key.starts_with(path) {
+            break
+        }
+        if filter(&key) {
+            cursor.delete_current()?;
+            wipe_storage(tx, hashed_address)?;
+            deleted.push(hashed_address);
+        }
+        entry = cursor.next()?;
+    }
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        server::{SnapRequest, SnapRequestHandler},
+        sync::SnapSyncConfig,
+    };
+    use alloy_consensus::Header;
+    use alloy_primitives::U256;
+    use futures::future::{poll_fn, ready, Ready};
+    use parking_lot::Mutex;
+    use reth_db_api::table::Table;
+    use reth_eth_wire_types::snap::{
+        GetAccountRangeMessage, GetByteCodesMessage, GetStorageRangesMessage, GetTrieNodesMessage,
+    };
+    use reth_network_p2p::{
+        download::DownloadClient, error::PeerRequestResult, priority::Priority,
+        snap::client::SnapResponse,
+    };
+    use reth_network_peers::{PeerId, WithPeerId};
+    use reth_primitives_traits::StorageEntry;
+    use reth_provider::{
+        test_utils::{create_test_provider_factory, MockNodeTypesWithDB},
+        ProviderFactory, StaticFileProviderFactory, StaticFileSegment,
+    };
+    use reth_prune_types::PruneModes;
+    use reth_storage_api::DatabaseProviderFactory;
+    use reth_trie::LeafNode;
+    use std::collections::VecDeque;
+
+    type TestFactory = ProviderFactory<MockNodeTypesWithDB>;
+    type TestProvider = <TestFactory as DatabaseProviderFactory>::ProviderRW;
+
+    /// Serves requests from the state of another node.
+    #[derive(Debug)]
+    struct HandlerClient(Mutex<SnapRequestHandler<TestFactory>>);
+
+    impl HandlerClient {
+        fn new(factory: TestFactory) -> Self {
+            Self(Mutex::new(SnapRequestHandler::new_pair(factory).0))
+        }
+
+        fn respond(&self, request: SnapRequest) -> Ready<PeerRequestResult<SnapResponse>> {
+            ready(Ok(WithPeerId::new(PeerId::ZERO, self.0.lock().on_request(request))))
+        }
+    }
+
+    impl DownloadClient for HandlerClient {
+        fn report_bad_message(&self, peer_id: PeerId) {
+            panic!("served state was rejected: {peer_id}")
+        }
+
+        fn num_connected_peers(&self) -> usize {
+            1
+        }
+    }
+
+    impl SnapClient for HandlerClient {
+        type Output = Ready<PeerRequestResult<SnapResponse>>;
+
+        fn get_account_range_with_priority(
+            &self,
+            request: GetAccountRangeMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            self.respond(SnapRequest::GetAccountRange(request))
+        }
+
+        fn get_storage_ranges(&self, request: GetStorageRangesMessage) -> Self::Output {
+            self.respond(SnapRequest::GetStorageRanges(request))
+        }
+
+        fn get_storage_ranges_with_priority(
+            &self,
+            request: GetStorageRangesMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            self.respond(SnapRequest::GetStorageRanges(request))
+        }
+
+        fn get_byte_codes(&self, request: GetByteCodesMessage) -> Self::Output {
+            self.respond(SnapRequest::GetByteCodes(request))
+        }
+
+        fn get_byte_codes_with_priority(
+            &self,
+            request: GetByteCodesMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            self.respond(SnapRequest::GetByteCodes(request))
+        }
+
+        fn get_trie_nodes(&self, request: GetTrieNodesMessage) -> Self::Output {
+            self.respond(SnapRequest::GetTrieNodes(request))
+        }
+
+        fn get_trie_nodes_with_priority(
+            &self,
+            request: GetTrieNodesMessage,
+            _priority: Priority,
+        ) -> Self::Output {
+            self.respond(SnapRequest::GetTrieNodes(request))
+        }
+    }
+
+    /// Inserts the account with the given number of slots, every other account has a bytecode.
+    fn insert_account<TX: DbTxMut>(tx: &TX, i: u8, num_slots: u8) {
+        let bytecode_hash = (i % 2 == 0).then(|| keccak256([i, i]));
+        let account = Account { nonce: i.into(), balance: U256::from(i), bytecode_hash };
+        tx.put::<tables::HashedAccounts>(keccak256([i]), account).unwrap();
+        for slot in 0..num_slots {
+            let entry =
+                StorageEntry { key: keccak256([slot]), value: U256::from(slot) + U256::from(1) };
+            tx.put::<tables::HashedStorages>(keccak256([i]), entry).unwrap();
+        }
+        if let Some(hash) = bytecode_hash {
+            tx.put::<tables::Bytecodes>(hash, Bytecode::new_raw(Bytes::from(vec![i, i]))).unwrap();
+        }
+    }
+
+    /// Inserts the genesis header and the header of the pivot with the given state root.
+    fn insert_headers(factory: &TestFactory, state_root: B256) {
+        let provider = factory.database_provider_rw().unwrap();
+        let mut writer =
+            provider.static_file_provider().latest_writer(StaticFileSegment::Headers).unwrap();
+        for (number, state_root) in [(0, B256::ZERO), (1, state_root)] {
+            let header = Header { number, state_root, ..Default::default() };
+            writer.append_header(&header, &header.hash_slow()).unwrap();
+        }
+        drop(writer);
+        provider.commit().unwrap();
+    }
+
+    /// Returns all entries of the table.
+    fn entries<T: Table, TX: DbTx>(tx: &TX) -> Vec<(T::Key, T::Value)> {
+        tx.cursor_read::<T>().unwrap().walk(None).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn delete_accounts_below_path() {
+        let factory = create_test_provider_factory();
+        let provider = factory.database_provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        let keys = [B256::repeat_byte(0x11), B256::repeat_byte(0x12), B256::repeat_byte(0x21)];
+        for key in keys {
+            tx.put::<tables::HashedAccounts>(key, Account::default()).unwrap();
+            tx.put::<tables::HashedStorages>(key, StorageEntry { key, value: U256::from(1) })
+                .unwrap();
+        }
+
+        let keep = Nibbles::unpack(keys[0]);
+        delete_accounts(tx, &Nibbles::from_nibbles([1]), |key| *key != keep).unwrap();
+
+        assert!(tx.get::<tables::HashedAccounts>(keys[0]).unwrap().is_some());
+        assert!(tx.get::<tables::HashedAccounts>(keys[1]).unwrap().is_none());
+        assert!(tx.get::<tables::HashedAccounts>(keys[2]).unwrap().is_some());
+        let mut storages = tx.cursor_dup_read::<tables::HashedStorages>().unwrap();
+        assert!(storages.seek_exact(keys[1]).unwrap().is_none());
+        assert!(storages.seek_exact(keys[2]).unwrap().is_some());
+    }
+
+    #[test]
+    fn trie_account_conversion() {
+        let account = Account { nonce: 1, balance: U256::from(2), bytecode_hash: None };
+        assert_eq!(into_account(account.into_trie_account(EMPTY_ROOT_HASH)), account);
+        let account = Account { bytecode_hash: Some(B256::repeat_byte(0x11)), ..account };
+        assert_eq!(into_account(account.into_trie_account(EMPTY_ROOT_HASH)), account);
+    }
+
+    #[tokio::test]
+    async fn sync_state_from_peer() {
+        // the served state, with storage tries that are large enough to be stored
+        let source = create_test_provider_factory();
+        let provider = source.database_provider_rw().unwrap();
+        for i in 0..64u8 {
+            insert_account(provider.tx_ref(), i, if i % 3 == 0 { 4 } else { 0 });
+        }
+        insert_account(provider.tx_ref(), 64, 200);
+        let (root, updates) = StateRoot::from_tx(provider.tx_ref()).root_with_updates().unwrap();
+        provider.write_trie_updates(updates).unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(1)).unwrap();
+        provider.commit().unwrap();
+        insert_headers(&source, root);
+
+        // local state that diverges from the served state and has to be healed
+        let factory = create_test_provider_factory()
+            .with_prune_modes(PruneModes { receipts: Some(PruneMode::Full), ..Default::default() });
+        let provider = factory.database_provider_rw().unwrap();
+        insert_account(provider.tx_ref(), 1, 2);
+        insert_account(provider.tx_ref(), 3, 8);
+        insert_account(provider.tx_ref(), 201, 2);
+        provider.commit().unwrap();
+        insert_headers(&factory, root);
+
+        let syncer = SnapSyncer::new(HandlerClient::new(source.clone()), SnapSyncConfig::default());
+        let mut stage = SnapSyncStage::new(syncer);
+        let input = ExecInput { target: Some(1), checkpoint: None };
+        let mut done = false;
+        for _ in 0..32 {
+            poll_fn(|cx| Stage::<TestProvider>::poll_execute_ready(&mut stage, cx, input))
+                .await
+                .unwrap();
+            let provider = factory.database_provider_rw().unwrap();
+            done = stage.execute(&provider, input).unwrap().done;
+            provider.commit().unwrap();
+            if done {
+                break
+            }
+        }
+        assert!(done);
+
+        let provider = factory.database_provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        let source_provider = source.database_provider_ro().unwrap();
+        let source_tx = source_provider.tx_ref();
+        assert_eq!(StateRoot::from_tx(tx).root().unwrap(), root);
+        assert_eq!(
+            entries::<tables::HashedAccounts, _>(tx),
+            entries::<tables::HashedAccounts, _>(source_tx)
+        );
+        assert_eq!(
+            entries::<tables::HashedStorages, _>(tx),
+            entries::<tables::HashedStorages, _>(source_tx)
+        );
+        assert_eq!(
+            entries::<tables::AccountsTrie, _>(tx),
+            entries::<tables::AccountsTrie, _>(source_tx)
+        );
+        assert_eq!(
+            entries::<tables::StoragesTrie, _>(tx),
+            entries::<tables::StoragesTrie, _>(source_tx)
+        );
+        assert_eq!(entries::<tables::Bytecodes, _>(tx), entries::<tables::Bytecodes, _>(source_tx));
+        assert_eq!(
+            provider.get_stage_checkpoint(StageId::Execution).unwrap(),
+            Some(StageCheckpoint::new(1))
+        );
+
+        // the state of the pivot can't be unwound
+        let unwind =
+            UnwindInput { checkpoint: StageCheckpoint::new(1), unwind_to: 0, bad_block: None };
+        assert!(stage.unwind(&provider, unwind).is_err());
+        assert!(stage.unwind(&provider, UnwindInput { unwind_to: 1, ..unwind }).is_ok());
+    }
+
+    #[test]
+    fn request_malformed_trie_node_again() {
+        let factory = create_test_provider_factory();
+        let syncer =
+            SnapSyncer::new(HandlerClient::new(factory.clone()), SnapSyncConfig::default());
+        let mut stage = SnapSyncStage::new(syncer);
+        let provider = factory.database_provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        let hashed_address = B256::repeat_byte(0x11);
+        tx.put::<tables::HashedAccounts>(hashed_address, Account::default()).unwrap();
+
+        // a leaf that doesn't end at a full path
+        let path = Nibbles::from_nibbles([1]);
+        let leaf = LeafNode::new(Nibbles::from_nibbles([1]), vec![0x80]);
+        let node = Bytes::from(alloy_rlp::encode(TrieNode::Leaf(leaf)));
+        stage.heal_node(tx, path, node.clone()).unwrap();
+
+        // the local state is untouched
+        assert!(tx.get::<tables::HashedAccounts>(hashed_address).unwrap().is_some());
+        assert_eq!(stage.syncer.pending_nodes, VecDeque::from([(path, keccak256(&node))]));
+    }
+}
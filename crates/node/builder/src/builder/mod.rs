@@ -33,6 +33,7 @@ use reth_provider::{
     providers::{BlockchainProvider, NodeTypesForProvider},
     ChainSpecProvider, FullProvider,
 };
+use reth_snap::{SnapPeers, SnapProtocolHandler, SnapRequestHandler};
 use reth_tasks::TaskExecutor;
 use reth_transaction_pool::{PoolConfig, PoolTransaction, TransactionPool};
 use secp256k1::SecretKey;
@@ -711,6 +712,8 @@ pub struct BuilderContext<Node: FullNodeTypes> {
     pub(crate) executor: TaskExecutor,
     /// Config container
     pub(crate) config_container: WithConfigs<<Node::Types as NodeTypes>::ChainSpec>,
+    /// The connected `snap/1` peers, shared with the snap sync stage.
+    pub(crate) snap_peers: Option<SnapPeers>,
 }
 
 impl<Node: FullNodeTypes> BuilderContext<Node> {
@@ -721,7 +724,13 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         executor: TaskExecutor,
         config_container: WithConfigs<<Node::Types as NodeTypes>::ChainSpec>,
     ) -> Self {
-        Self { head, provider, executor, config_container }
+        Self { head, provider, executor, config_container, snap_peers: None }
+    }
+
+    /// Sets the registry of the connected `snap/1` peers.
+    pub fn with_snap_peers(mut self, snap_peers: SnapPeers) -> Self {
+        self.snap_peers = Some(snap_peers);
+        self
     }
 
     /// Returns the configured provider to interact with the blockchain.
@@ -805,7 +814,7 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
             builder,
             pool,
             self.config().network.transactions_manager_config(),
-            self.config().network.tx_propagation_policy,
+            self.config().network.transactions_propagation_policy(),
         )
     }
 
@@ -817,7 +826,7 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     /// connected to that network.
     pub fn start_network_with<Pool, N, Policy>(
         &self,
-        builder: NetworkBuilder<(), (), N>,
+        mut builder: NetworkBuilder<(), (), N>,
         pool: Pool,
         tx_config: TransactionsManagerConfig,
         propagation_policy: Policy,
@@ -834,6 +843,23 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         Node::Provider: BlockReaderFor<N>,
         Policy: TransactionPropagationPolicy + Debug,
     {
+        let snap_peers = self.snap_peers.clone().unwrap_or_default();
+        if self.config().network.snap_serve {
+            let (snap, protocol) = SnapRequestHandler::new_pair(self.provider().clone());
+            let snap = match self.config().network.snap_response_soft_limit {
+                Some(limit) => snap.with_soft_response_limit(limit),
+                None => snap,
+            };
+            builder.network_mut().add_rlpx_sub_protocol(protocol.with_peers(snap_peers));
+            // serving snap requests walks the database and computes proofs
+            self.executor.spawn_critical_blocking("p2p snap request handler", snap);
+        } else if self.config().network.snap_sync {
+            // only send requests to peers
+            builder
+                .network_mut()
+                .add_rlpx_sub_protocol(SnapProtocolHandler::default().with_peers(snap_peers));
+        }
+
         let (handle, network, txpool, eth) = builder
             .transactions_with_policy(pool, tx_config, propagation_policy)
             .request_handler(self.provider().clone())
@@ -844,6 +870,8 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
 
         let default_peers_path = self.config().datadir().known_peers();
         let known_peers_file = self.config().network.persistent_peers_file(default_peers_path);
+        let peer_state_file =
+            self.config().network.persistent_peers_file(self.config().datadir().peer_state());
         self.executor.spawn_critical_with_graceful_shutdown_signal(
             "p2p network task",
             |shutdown| {
@@ -860,6 +888,16 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
                             }
                         }
                     }
+                    if let Some(peer_state_file) = peer_state_file {
+                        match network.write_peer_state_to_file(peer_state_file.as_path()) {
+                            Ok(_) => {
+                                info!(target: "reth::cli", peer_state_file=?peer_state_file, "Wrote bans and peer reputations to file");
+                            }
+                            Err(err) => {
+                                warn!(target: "reth::cli", %err, "Failed to write bans and peer reputations to file");
+                            }
+                        }
+                    }
                 }))
             },
         );
@@ -923,6 +961,7 @@ impl<Node: FullNodeTypes<Types: NodeTypes<ChainSpec: Hardforks>>> BuilderContext
                 self.config().chain.clone(),
                 secret_key,
                 default_peers_path,
+                self.config().datadir().peer_state(),
             )
             .with_task_executor(Box::new(self.executor.clone()))
             .set_head(self.head);
@@ -1,15 +1,28 @@
 //! Pool component for the node builder.
 
 use crate::{BuilderContext, FullNodeTypes};
-use alloy_primitives::Address;
-use reth_chain_state::CanonStateSubscriptions;
+use alloy_consensus::{BlockHeader, Transaction};
+use alloy_eips::{
+    eip4844::kzg_to_versioned_hash,
+    eip7594::{BlobTransactionSidecarVariant, CELLS_PER_EXT_BLOB},
+};
+use alloy_primitives::{Address, BlockNumber, Bytes, FixedBytes, TxHash, B256};
+use futures::StreamExt;
+use reth_chain_state::{CanonStateNotification, CanonStateSubscriptions};
 use reth_chainspec::EthereumHardforks;
+use reth_db_api::{models::StoredBlobSidecar, transaction::DbTx};
 use reth_node_api::{NodeTypes, TxTy};
+use reth_primitives_traits::{BlockBody, NodePrimitives, SignedTransaction};
+use reth_provider::{BlobSidecarWriter, DBProvider, DatabaseProviderFactory};
 use reth_transaction_pool::{
     blobstore::DiskFileBlobStore, CoinbaseTipOrdering, PoolConfig, PoolTransaction, SubPoolLimit,
     TransactionPool, TransactionValidationTaskExecutor, TransactionValidator,
 };
-use std::{collections::HashSet, future::Future};
+use std::{
+    collections::{BTreeMap, HashSet},
+    future::Future,
+};
+use tracing::warn;
 
 /// A type that knows how to build the transaction pool.
 pub trait PoolBuilder<Node: FullNodeTypes>: Send {
@@ -254,6 +267,201 @@ where
     Ok(())
 }
 
+/// Spawn the blob retention task if blob retention is enabled.
+///
+/// The task copies the sidecars of blob transactions included in canonical blocks from the blob
+/// store into the database, where they're kept until the blob sidecars prune segment removes them.
+/// Sidecars of blob transactions that never entered the pool can't be retained.
+///
+/// Sidecars are written in batches of [`BlobSidecarBatch::MAX_BLOCKS`] blocks, so the task doesn't
+/// compete with the engine for the database write lock on every block. Pending sidecars are
+/// written on reorgs and on shutdown.
+fn spawn_blob_retention_task<Node, Pool>(ctx: &BuilderContext<Node>, pool: Pool) -> eyre::Result<()>
+where
+    Node: FullNodeTypes,
+    Pool: TransactionPool + Clone + 'static,
+{
+    if ctx.config().txpool.blob_retention_epochs.is_none() {
+        return Ok(())
+    }
+
+    let mut chain_events = ctx.provider().canonical_state_stream();
+    let provider = ctx.provider().clone();
+
+    ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+        "blob retention task",
+        |shutdown| async move {
+            let mut shutdown = std::pin::pin!(shutdown);
+            let mut batch = BlobSidecarBatch::default();
+
+            let guard = loop {
+                tokio::select! {
+                    guard = &mut shutdown => break Some(guard),
+                    event = chain_events.next() => {
+                        let Some(event) = event else { break None };
+                        if let Err(err) = batch.on_canon_state_notification(&pool, &event) {
+                            warn!(
+                                target: "txpool::blob_retention",
+                                %err,
+                                "Failed to collect blob sidecars"
+                            );
+                        }
+                        if batch.is_due() {
+                            write_blob_sidecars(&provider, std::mem::take(&mut batch)).await;
+                        }
+                    }
+                }
+            };
+
+            write_blob_sidecars(&provider, batch).await;
+            drop(guard);
+        },
+    );
+
+    Ok(())
+}
+
+/// Writes the batch to the database on a blocking thread.
+async fn write_blob_sidecars<Provider>(provider: &Provider, batch: BlobSidecarBatch)
+where
+    Provider: DatabaseProviderFactory<ProviderRW: BlobSidecarWriter> + Clone + 'static,
+{
+    let provider = provider.clone();
+    match tokio::task::spawn_blocking(move || batch.write(&provider)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(err)) => {
+            warn!(target: "txpool::blob_retention", %err, "Failed to retain blob sidecars")
+        }
+        Err(err) => {
+            warn!(target: "txpool::blob_retention", %err, "Blob sidecars write task failed")
+        }
+    }
+}
+
+/// Blob sidecars of canonical blocks that are not yet written to the database.
+#[derive(Debug, Default)]
+struct BlobSidecarBatch {
+    /// First block whose retained sidecars must be removed, because it was reorged out.
+    remove_from: Option<BlockNumber>,
+    /// Sidecars of blocks that include blob transactions, keyed by block number.
+    blocks: BTreeMap<BlockNumber, Vec<(B256, StoredBlobSidecar)>>,
+}
+
+impl BlobSidecarBatch {
+    /// Number of blocks with blob sidecars after which the batch is written.
+    const MAX_BLOCKS: usize = 8;
+
+    /// Collects the sidecars of the blob transactions in the committed chain from the pool.
+    ///
+    /// On reorgs, pending sidecars of reorged blocks are dropped, and previously written sidecars
+    /// are scheduled for removal if the reverted chain included blob transactions.
+    fn on_canon_state_notification<Pool, N>(
+        &mut self,
+        pool: &Pool,
+        notification: &CanonStateNotification<N>,
+    ) -> eyre::Result<()>
+    where
+        Pool: TransactionPool,
+        N: NodePrimitives,
+    {
+        if let Some(reverted) = notification.reverted() {
+            let first = reverted.first().number();
+            self.blocks.retain(|number, _| *number < first);
+            if reverted.blocks_iter().any(|block| !blob_transactions(block.body()).is_empty()) {
+                self.remove_from = Some(self.remove_from.map_or(first, |from| from.min(first)));
+            }
+        }
+
+        for block in notification.committed().blocks_iter() {
+            let blob_transactions = blob_transactions(block.body());
+            if blob_transactions.is_empty() {
+                continue
+            }
+
+            let sidecars = pool
+                .get_all_blobs(blob_transactions)?
+                .into_iter()
+                .flat_map(|(tx_hash, sidecar)| stored_blob_sidecars(tx_hash, &sidecar))
+                .collect::<Vec<_>>();
+            if !sidecars.is_empty() {
+                self.blocks.insert(block.number(), sidecars);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the batch should be written, because it's full or includes a reorg.
+    fn is_due(&self) -> bool {
+        self.remove_from.is_some() || self.blocks.len() >= Self::MAX_BLOCKS
+    }
+
+    /// Writes the batch to the database.
+    ///
+    /// Returns `false` without opening a database transaction if there's nothing to write.
+    fn write<Provider>(self, provider: &Provider) -> eyre::Result<bool>
+    where
+        Provider: DatabaseProviderFactory<ProviderRW: BlobSidecarWriter>,
+    {
+        if self.remove_from.is_none() && self.blocks.is_empty() {
+            return Ok(false)
+        }
+
+        let provider_rw = provider.database_provider_rw()?;
+        if let Some(number) = self.remove_from {
+            provider_rw.remove_blob_sidecars_from(number)?;
+        }
+        for (number, sidecars) in self.blocks {
+            provider_rw.insert_blob_sidecars(number, sidecars)?;
+        }
+
+        // Only the database transaction is committed, the blob sidecars don't touch static files.
+        provider_rw.into_tx().commit()?;
+
+        Ok(true)
+    }
+}
+
+/// Returns the hashes of all blob transactions in the block body.
+fn blob_transactions<B: BlockBody>(body: &B) -> Vec<TxHash> {
+    body.transactions_iter()
+        .filter(|tx| tx.blob_versioned_hashes().is_some())
+        .map(|tx| *tx.tx_hash())
+        .collect()
+}
+
+/// Splits a blob transaction sidecar into its blobs, keyed by versioned hash.
+fn stored_blob_sidecars(
+    tx_hash: TxHash,
+    sidecar: &BlobTransactionSidecarVariant,
+) -> Vec<(B256, StoredBlobSidecar)> {
+    let stored = |commitment: FixedBytes<48>, proofs: Vec<FixedBytes<48>>, blob: &[u8]| {
+        (
+            kzg_to_versioned_hash(commitment.as_slice()),
+            StoredBlobSidecar { tx_hash, commitment, proofs, blob: Bytes::copy_from_slice(blob) },
+        )
+    };
+
+    match sidecar {
+        BlobTransactionSidecarVariant::Eip4844(sidecar) => sidecar
+            .blobs
+            .iter()
+            .zip(&sidecar.commitments)
+            .zip(&sidecar.proofs)
+            .map(|((blob, commitment), proof)| stored(*commitment, vec![*proof], blob.as_slice()))
+            .collect(),
+        BlobTransactionSidecarVariant::Eip7594(sidecar) => sidecar
+            .blobs
+            .iter()
+            .zip(&sidecar.commitments)
+            .zip(sidecar.cell_proofs.chunks(CELLS_PER_EXT_BLOB))
+            .map(|((blob, commitment), proofs)| {
+                stored(*commitment, proofs.to_vec(), blob.as_slice())
+            })
+            .collect(),
+    }
+}
+
 /// Spawn all maintenance tasks for a transaction pool (backup + main maintenance).
 pub fn spawn_maintenance_tasks<Node, Pool>(
     ctx: &BuilderContext<Node>,
@@ -266,6 +474,7 @@ where
     Pool::Transaction: PoolTransaction<Consensus = TxTy<Node::Types>>,
 {
     spawn_local_backup_task(ctx, pool.clone())?;
+    spawn_blob_retention_task(ctx, pool.clone())?;
     spawn_pool_maintenance_task(ctx, pool, pool_config)?;
     Ok(())
 }
@@ -279,7 +488,128 @@ impl<Node: FullNodeTypes, V: std::fmt::Debug> std::fmt::Debug for TxPoolBuilder<
 #[cfg(test)]
 mod tests {
     use super::*;
-    use reth_transaction_pool::PoolConfig;
+    use alloy_consensus::{Header, TxEip4844};
+    use alloy_eips::eip4844::{BlobTransactionSidecar, Bytes48};
+    use alloy_primitives::Signature;
+    use reth_ethereum_primitives::{Block, BlockBody, Transaction, TransactionSigned};
+    use reth_primitives_traits::RecoveredBlock;
+    use reth_provider::{test_utils::create_test_provider_factory, BlobSidecarReader, Chain};
+    use reth_transaction_pool::{
+        blobstore::BlobStore,
+        test_utils::{testing_pool, TestPool},
+        PoolConfig,
+    };
+    use std::sync::Arc;
+
+    /// Creates a block with one blob transaction per commitment byte, and adds their sidecars to
+    /// the blob store of the pool.
+    fn block_with_blobs(pool: &TestPool, number: u64, commitments: &[u8]) -> RecoveredBlock<Block> {
+        let transactions = commitments
+            .iter()
+            .map(|&byte| {
+                let commitment = Bytes48::repeat_byte(byte);
+                let tx = TransactionSigned::new_unhashed(
+                    Transaction::Eip4844(TxEip4844 {
+                        blob_versioned_hashes: vec![kzg_to_versioned_hash(commitment.as_slice())],
+                        ..Default::default()
+                    }),
+                    Signature::test_signature(),
+                );
+                let sidecar = BlobTransactionSidecar {
+                    blobs: vec![Default::default()],
+                    commitments: vec![commitment],
+                    proofs: vec![Default::default()],
+                };
+                pool.blob_store()
+                    .insert(*tx.tx_hash(), BlobTransactionSidecarVariant::Eip4844(sidecar))
+                    .unwrap();
+                tx
+            })
+            .collect::<Vec<_>>();
+        let senders = vec![Address::ZERO; transactions.len()];
+
+        RecoveredBlock::new_unhashed(
+            Block {
+                header: Header { number, ..Default::default() },
+                body: BlockBody { transactions, ..Default::default() },
+            },
+            senders,
+        )
+    }
+
+    fn chain(blocks: impl IntoIterator<Item = RecoveredBlock<Block>>) -> Arc<Chain> {
+        Arc::new(Chain::new(blocks, Default::default(), None))
+    }
+
+    #[test]
+    fn test_blob_sidecar_batch_without_blobs() {
+        let factory = create_test_provider_factory();
+        let pool = testing_pool();
+
+        let mut batch = BlobSidecarBatch::default();
+        let notification =
+            CanonStateNotification::Commit { new: chain([block_with_blobs(&pool, 1, &[])]) };
+        batch.on_canon_state_notification(&pool, &notification).unwrap();
+
+        assert!(!batch.is_due());
+        // nothing to write, so no database transaction is opened
+        assert!(!batch.write(&factory).unwrap());
+        assert!(factory.provider().unwrap().blob_sidecars(1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_blob_sidecar_batch_reorg() {
+        let factory = create_test_provider_factory();
+        let pool = testing_pool();
+
+        let mut batch = BlobSidecarBatch::default();
+        let notification = CanonStateNotification::Commit {
+            new: chain([block_with_blobs(&pool, 1, &[1]), block_with_blobs(&pool, 2, &[2, 3])]),
+        };
+        batch.on_canon_state_notification(&pool, &notification).unwrap();
+        assert!(!batch.is_due());
+        assert!(batch.write(&factory).unwrap());
+
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.blob_sidecars(1).unwrap().len(), 1);
+        assert_eq!(provider.blob_sidecars(2).unwrap().len(), 2);
+        drop(provider);
+
+        // block 2 is replaced by a block without blob transactions
+        let mut batch = BlobSidecarBatch::default();
+        let notification = CanonStateNotification::Reorg {
+            old: chain([block_with_blobs(&pool, 2, &[2, 3])]),
+            new: chain([block_with_blobs(&pool, 2, &[])]),
+        };
+        batch.on_canon_state_notification(&pool, &notification).unwrap();
+        assert_eq!(batch.remove_from, Some(2));
+        assert!(batch.is_due());
+        assert!(batch.write(&factory).unwrap());
+
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.blob_sidecars(1).unwrap().len(), 1);
+        assert!(provider.blob_sidecars(2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_blob_sidecar_batch_drops_pending_reorged_blocks() {
+        let pool = testing_pool();
+
+        let mut batch = BlobSidecarBatch::default();
+        let notification = CanonStateNotification::Commit {
+            new: chain([block_with_blobs(&pool, 1, &[1]), block_with_blobs(&pool, 2, &[2])]),
+        };
+        batch.on_canon_state_notification(&pool, &notification).unwrap();
+
+        let notification = CanonStateNotification::Reorg {
+            old: chain([block_with_blobs(&pool, 2, &[2])]),
+            new: chain([block_with_blobs(&pool, 2, &[4])]),
+        };
+        batch.on_canon_state_notification(&pool, &notification).unwrap();
+
+        assert_eq!(batch.blocks.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(batch.blocks[&2][0].1.commitment, Bytes48::repeat_byte(4));
+    }
 
     #[test]
     fn test_pool_builder_config_overrides_apply() {
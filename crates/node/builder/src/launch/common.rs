@@ -72,6 +72,7 @@ use reth_provider::{
 use reth_prune::{PruneModes, PrunerBuilder};
 use reth_rpc_builder::config::RethRpcServerConfig;
 use reth_rpc_layer::JwtSecret;
+use reth_snap::SnapPeers;
 use reth_stages::{
     sets::DefaultStages, stages::EraImportSource, MetricEvent, PipelineBuilder, PipelineTarget,
     StageId,
@@ -425,11 +426,18 @@ impl<R, ChainSpec: EthChainSpec> LaunchContextWith<Attached<WithConfigs<ChainSpe
     }
 
     /// Returns an initialized [`PrunerBuilder`] based on the configured [`PruneConfig`]
+    ///
+    /// The blob sidecars segment is only registered with the pruner, so enabling blob retention
+    /// doesn't change the persisted prune configuration of the node.
     pub fn pruner_builder(&self) -> PrunerBuilder
     where
         ChainSpec: reth_chainspec::EthereumHardforks,
     {
-        PrunerBuilder::new(self.prune_config())
+        let mut prune_config = self.prune_config();
+        if let Some(mode) = self.node_config().txpool.blob_retention_prune_mode() {
+            prune_config.segments.blob_sidecars = Some(mode);
+        }
+        PrunerBuilder::new(prune_config)
     }
 
     /// Loads the JWT secret for the engine API
@@ -755,12 +763,14 @@ where
         // fetch the head block from the database
         let head = self.lookup_head()?;
 
+        let snap_peers = SnapPeers::default();
         let builder_ctx = BuilderContext::new(
             head,
             self.blockchain_db().clone(),
             self.task_executor().clone(),
             self.configs().clone(),
-        );
+        )
+        .with_snap_peers(snap_peers.clone());
 
         debug!(target: "reth::cli", "creating components");
         let components = components_builder.build_components(&builder_ctx).await?;
@@ -783,6 +793,7 @@ where
             },
             node_adapter,
             head,
+            snap_peers,
         };
 
         let ctx = LaunchContextWith {
@@ -835,6 +846,11 @@ where
         self.right().head
     }
 
+    /// Returns the connected `snap/1` peers.
+    pub const fn snap_peers(&self) -> &SnapPeers {
+        &self.right().snap_peers
+    }
+
     /// Returns the configured `NodeAdapter`.
     pub const fn node_adapter(&self) -> &NodeAdapter<T, CB::Components> {
         &self.right().node_adapter
@@ -1129,6 +1145,7 @@ where
     db_provider_container: WithMeteredProvider<NodeTypesWithDBAdapter<T::Types, T::DB>>,
     node_adapter: NodeAdapter<T, CB::Components>,
     head: Head,
+    snap_peers: SnapPeers,
 }
 
 #[cfg(test)]
@@ -1186,4 +1203,19 @@ mod tests {
             assert_eq!(reth_config, loaded_config);
         })
     }
+
+    #[test]
+    fn test_blob_retention_keeps_archive_prune_config() {
+        with_tempdir("blob-retention-prune-test", |config_path| {
+            let mut reth_config = Config::default();
+            let mut node_config = NodeConfig::test();
+            node_config.txpool.blob_retention_epochs = Some(4096);
+
+            assert!(node_config.prune_config().is_none());
+
+            LaunchContext::save_pruning_config(&mut reth_config, &node_config, config_path)
+                .unwrap();
+            assert!(reth_config.prune.is_default());
+        })
+    }
 }
@@ -4,8 +4,11 @@ use alloy_consensus::transaction::Either;
 use alloy_provider::network::AnyNetwork;
 use jsonrpsee::core::{DeserializeOwned, Serialize};
 use reth_chainspec::EthChainSpec;
-use reth_consensus_debug_client::{DebugConsensusClient, EtherscanBlockProvider, RpcBlockProvider};
+use reth_consensus_debug_client::{
+    DebugConsensusClient, EtherscanBlockProvider, FileBlockProvider, RpcBlockProvider,
+};
 use reth_engine_local::LocalMiner;
+use reth_engine_util::engine_replay::EngineMessageReplay;
 use reth_node_api::{
     BlockTy, FullNodeComponents, PayloadAttrTy, PayloadAttributesBuilder, PayloadTypes,
 };
@@ -14,7 +17,7 @@ use std::{
     pin::Pin,
     sync::Arc,
 };
-use tracing::info;
+use tracing::{error, info};
 
 /// [`Node`] extension with support for debugging utilities.
 ///
@@ -28,6 +31,9 @@ use tracing::info;
 ///   blocks to the local engine.
 /// - **RPC Consensus Client**: Connect to an external RPC endpoint to fetch blocks and submit them
 ///   to the local engine to follow the chain.
+/// - **File Consensus Client**: Read blocks from a local RLP chain file and submit them to the
+///   local engine.
+/// - **Engine API Replay**: Feed previously recorded engine API messages into the local engine.
 ///
 /// See [`DebugNodeLauncher`] for the launcher that enables these features.
 ///
@@ -81,7 +87,7 @@ pub trait DebugNode<N: FullNodeComponents>: Node<N> {
 /// Node launcher with support for launching various debugging utilities.
 ///
 /// This launcher wraps an existing launcher and adds debugging capabilities when
-/// certain debug flags are enabled. It provides two main debugging features:
+/// certain debug flags are enabled. It provides the following debugging features:
 ///
 /// ## RPC Consensus Client
 ///
@@ -99,6 +105,22 @@ pub trait DebugNode<N: FullNodeComponents>: Node<N> {
 /// - Submit them to the local engine
 /// - Requires `ETHERSCAN_API_KEY` environment variable
 /// - Falls back to default Etherscan URL for the chain if URL not provided
+///
+/// ## File Consensus Client
+///
+/// When `--debug.block-file <PATH>` is provided, the launcher will:
+/// - Read the blocks one at a time from an RLP chain file of post-merge blocks
+/// - Submit them to the local engine as new payloads followed by forkchoice updates
+/// - Send them either as fast as the engine processes them or paced by
+///   `--debug.block-file-interval`
+/// - Useful for offline benchmarks of the live engine path on historical blocks
+///
+/// ## Engine API Replay
+///
+/// When `--debug.engine-replay <PATH>` is provided, the launcher will:
+/// - Read the engine API messages recorded with `--debug.engine-record`
+/// - Submit them to the local engine in the order they were received, either preserving the
+///   original delays between them or as fast as possible with `--debug.engine-replay-fast`
 #[derive(Debug, Clone)]
 pub struct DebugNodeLauncher<L = EngineNodeLauncher> {
     inner: L,
@@ -215,6 +237,41 @@ where
             });
         }
 
+        if let Some(path) = config.debug.block_file.clone() {
+            info!(target: "reth::cli", path = %path.display(), "Using block file as consensus client");
+
+            // indexing the file does blocking I/O
+            let mut block_provider = tokio::task::spawn_blocking(move || {
+                FileBlockProvider::<BlockTy<N::Types>>::new(path)
+            })
+            .await??;
+            if let Some(interval) = config.debug.block_file_interval {
+                block_provider = block_provider.with_interval(interval);
+            }
+            let file_consensus_client = DebugConsensusClient::new(
+                handle.node.add_ons_handle.beacon_engine_handle.clone(),
+                Arc::new(block_provider),
+            );
+            handle.node.task_executor.spawn_critical("file consensus client", async move {
+                file_consensus_client.run().await
+            });
+        }
+
+        if let Some(path) = config.debug.engine_replay.clone() {
+            info!(target: "reth::cli", path = %path.display(), "Replaying recorded engine API messages");
+
+            let replay = EngineMessageReplay::new(
+                handle.node.add_ons_handle.beacon_engine_handle.clone(),
+                path,
+                !config.debug.engine_replay_fast,
+            );
+            handle.node.task_executor.spawn_critical("engine replay", async move {
+                if let Err(err) = replay.run().await {
+                    error!(target: "reth::cli", %err, "Failed to replay engine API messages");
+                }
+            });
+        }
+
         if config.dev.dev {
             info!(target: "reth::cli", "Using local payload attributes builder for dev mode");
 
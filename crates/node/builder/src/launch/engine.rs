@@ -16,10 +16,10 @@ use reth_engine_tree::{
     engine::{EngineApiRequest, EngineRequestHandler},
     tree::TreeConfig,
 };
-use reth_engine_util::EngineMessageStreamExt;
+use reth_engine_util::{faults::EngineFaultConfig, EngineMessageStreamExt};
 use reth_exex::ExExManagerHandle;
 use reth_network::{types::BlockRangeUpdate, NetworkSyncUpdater, SyncState};
-use reth_network_api::BlockDownloaderProvider;
+use reth_network_api::{BlockDownloaderProvider, PeersHandleProvider};
 use reth_node_api::{
     BuiltPayload, ConsensusEngineHandle, FullNodeTypes, NodeTypes, NodeTypesWithDBAdapter,
 };
@@ -33,6 +33,7 @@ use reth_provider::{
     providers::{BlockchainProvider, NodeTypesForProvider},
     BlockNumReader,
 };
+use reth_snap::{SnapNetworkClient, SnapSyncConfig, SnapSyncStage, SnapSyncer};
 use reth_tasks::TaskExecutor;
 use reth_tokio_util::EventSender;
 use reth_tracing::tracing::{debug, error, info};
@@ -138,6 +139,20 @@ impl EngineNodeLauncher {
 
         let consensus = Arc::new(ctx.components().consensus().clone());
 
+        let snap_sync = if node_config.network.snap_sync {
+            if !ctx.prune_config().segments.has_receipts_pruning() {
+                eyre::bail!("snap sync requires receipts pruning to be configured");
+            }
+            let client = SnapNetworkClient::new(
+                ctx.snap_peers().clone(),
+                network_handle.peers_handle().clone(),
+            );
+            info!(target: "reth::cli", "Snap sync enabled");
+            Some(SnapSyncStage::new(SnapSyncer::new(client, SnapSyncConfig::default())))
+        } else {
+            None
+        };
+
         let pipeline = build_networked_pipeline(
             &ctx.toml_config().stages,
             network_client.clone(),
@@ -151,6 +166,7 @@ impl EngineNodeLauncher {
             ctx.components().evm_config().clone(),
             maybe_exex_manager_handle.clone().unwrap_or_else(ExExManagerHandle::empty),
             ctx.era_import_source(),
+            snap_sync,
         )?;
 
         // The new engine writes directly to static files. This ensures that they're up to the tip.
@@ -189,18 +205,35 @@ impl EngineNodeLauncher {
             .build_tree_validator(&add_ons_ctx, engine_tree_config.clone())
             .await?;
 
-        // Create the consensus engine stream with optional reorg
+        // Validators of the debug stream wrappers only decode payloads, so they must not restore or
+        // overwrite the execution cache snapshot of the engine validator
+        let debug_tree_config = engine_tree_config.clone().with_persist_execution_cache(false);
+
+        // Create the consensus engine stream with optional reorg and fault injection
         let consensus_engine_stream = UnboundedReceiverStream::from(consensus_engine_rx)
             .maybe_skip_fcu(node_config.debug.skip_fcu)
             .maybe_skip_new_payload(node_config.debug.skip_new_payload)
             .maybe_reorg(
                 ctx.blockchain_db().clone(),
                 ctx.components().evm_config().clone(),
-                || validator_builder.build_tree_validator(&add_ons_ctx, engine_tree_config.clone()),
+                || validator_builder.build_tree_validator(&add_ons_ctx, debug_tree_config.clone()),
                 node_config.debug.reorg_frequency,
                 node_config.debug.reorg_depth,
             )
             .await?
+            .maybe_inject_faults(
+                || validator_builder.build_tree_validator(&add_ons_ctx, debug_tree_config.clone()),
+                EngineFaultConfig {
+                    seed: node_config.debug.fault_seed,
+                    delay_percent: node_config.debug.fault_delay,
+                    max_delay: node_config.debug.fault_max_delay,
+                    reorder_percent: node_config.debug.fault_reorder,
+                    duplicate_fcu_percent: node_config.debug.fault_duplicate_fcu,
+                    bad_state_root_percent: node_config.debug.fault_bad_state_root,
+                    bad_receipts_root_percent: node_config.debug.fault_bad_receipts_root,
+                },
+            )
+            .await?
             // Store messages _after_ skipping so that `replay-engine` command
             // would replay only the messages that were observed by the engine
             // during this run.
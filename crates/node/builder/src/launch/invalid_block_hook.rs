@@ -55,6 +55,8 @@ where
 /// This function constructs the appropriate [`InvalidBlockHook`] based on the debug
 /// configuration in the node config. It supports:
 /// - Witness hooks for capturing block witness data
+/// - Prestate hooks for capturing the accounts and storage slots touched by the block
+/// - Opcode hooks for capturing the struct logs of every transaction in the block
 /// - Healthy node verification via RPC
 ///
 /// # Arguments
@@ -81,7 +83,9 @@ where
     E: reth_evm::ConfigureEvm<Primitives = N> + Clone + 'static,
 {
     use reth_engine_primitives::{InvalidBlockHooks, NoopInvalidBlockHook};
-    use reth_invalid_block_hooks::InvalidBlockWitnessHook;
+    use reth_invalid_block_hooks::{
+        InvalidBlockOpcodeHook, InvalidBlockPreStateHook, InvalidBlockWitnessHook,
+    };
 
     let Some(ref hook) = config.debug.invalid_block_hook else {
         return Ok(Box::new(NoopInvalidBlockHook::default()))
@@ -104,12 +108,21 @@ where
                     output_directory,
                     healthy_node_rpc_client.clone(),
                 )),
-                InvalidBlockHookType::PreState | InvalidBlockHookType::Opcode => {
-                    eyre::bail!("invalid block hook {hook:?} is not implemented yet")
-                }
+                InvalidBlockHookType::PreState => Box::new(InvalidBlockPreStateHook::new(
+                    provider.clone(),
+                    evm_config.clone(),
+                    output_directory,
+                    healthy_node_rpc_client.clone(),
+                )),
+                InvalidBlockHookType::Opcode => Box::new(InvalidBlockOpcodeHook::new(
+                    provider.clone(),
+                    evm_config.clone(),
+                    output_directory,
+                    healthy_node_rpc_client.clone(),
+                )),
             } as Box<dyn InvalidBlockHook<_>>)
         })
-        .collect::<Result<_, _>>()?;
+        .collect::<eyre::Result<_>>()?;
 
     Ok(Box::new(InvalidBlockHooks(hooks)))
 }
@@ -23,23 +23,34 @@ use reth_node_core::{
     version::{version_metadata, CLIENT_CODE},
 };
 use reth_payload_builder::{PayloadBuilderHandle, PayloadStore};
-use reth_rpc::eth::{core::EthRpcConverterFor, DevSigner, EthApiTypes, FullEthApiServer};
-use reth_rpc_api::{eth::helpers::EthTransactions, IntoEngineApiRpcModule};
+use reth_rpc::{
+    eth::{core::EthRpcConverterFor, DevSigner, EthApiTypes, FullEthApiServer},
+    AdminReorgApi, ValidationReportsApi,
+};
+use reth_rpc_api::{
+    eth::helpers::EthTransactions, AdminReorgApiServer, IntoEngineApiRpcModule,
+    RethValidationReportsApiServer,
+};
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerHandle},
     config::RethRpcServerConfig,
-    RpcModuleBuilder, RpcRegistryInner, RpcServerConfig, RpcServerHandle, TransportRpcModules,
+    RethRpcModule, RpcModuleBuilder, RpcRegistryInner, RpcServerConfig, RpcServerHandle,
+    TransportRpcModules,
 };
 use reth_rpc_engine_api::{capabilities::EngineCapabilities, EngineApi};
 use reth_rpc_eth_types::{cache::cache_new_blocks_task, EthConfig, EthStateCache};
 use reth_tokio_util::EventSender;
-use reth_tracing::tracing::{debug, info};
+use reth_tracing::tracing::{debug, info, warn};
 use std::{
     fmt::{self, Debug},
     future::Future,
     ops::{Deref, DerefMut},
+    time::Duration,
 };
 
+/// How long graceful shutdown waits for the execution cache snapshot to be written.
+const EXECUTION_CACHE_SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Contains the handles to the spawned RPC servers.
 ///
 /// This can be used to access the endpoints of the servers.
@@ -995,6 +1006,18 @@ where
             registry.eth_api().signers().write().extend(signers);
         }
 
+        if let Some(capacity) = config.rpc.rpc_validation_reports {
+            let validation_reports = ValidationReportsApi::new(capacity);
+            validation_reports.spawn_listener(engine_events.new_listener(), node.task_executor());
+            modules
+                .merge_if_module_configured(RethRpcModule::Reth, validation_reports.into_rpc())?;
+        }
+
+        if config.engine.max_reorg_depth.is_some() {
+            let reorg_api = AdminReorgApi::new(beacon_engine_handle.clone());
+            modules.merge_if_module_configured(RethRpcModule::Admin, reorg_api.into_rpc())?;
+        }
+
         let mut registry = RpcRegistry { registry };
         let ctx = RpcContext {
             node: node.clone(),
@@ -1308,14 +1331,55 @@ where
         let validator = self.payload_validator_builder.build(ctx).await?;
         let data_dir = ctx.config.datadir.clone().resolve_datadir(ctx.config.chain.chain());
         let invalid_block_hook = ctx.create_invalid_block_hook(&data_dir).await?;
-        Ok(BasicEngineValidator::new(
+        let persist_execution_cache = tree_config.persist_execution_cache();
+        let engine_validator = BasicEngineValidator::new(
             ctx.node.provider().clone(),
             std::sync::Arc::new(ctx.node.consensus().clone()),
             ctx.node.evm_config().clone(),
             validator,
             tree_config,
             invalid_block_hook,
-        ))
+        );
+
+        if persist_execution_cache {
+            let snapshot =
+                engine_validator.execution_cache_snapshot(data_dir.execution_cache_snapshot());
+            match snapshot.load() {
+                Ok(Some(hash)) => {
+                    info!(target: "reth::cli", %hash, "Restored execution cache snapshot")
+                }
+                Ok(None) => {}
+                Err(err) => warn!(target: "reth::cli", %err, "Failed to restore execution cache"),
+            }
+
+            ctx.node.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                "execution cache snapshot task",
+                |shutdown| async move {
+                    let graceful_guard = shutdown.await;
+                    // writing the snapshot blocks on disk IO, and a slow disk must not hold up
+                    // the shutdown of the node
+                    let save = tokio::task::spawn_blocking(move || snapshot.save());
+                    match tokio::time::timeout(EXECUTION_CACHE_SNAPSHOT_TIMEOUT, save).await {
+                        Ok(Ok(Ok(Some(hash)))) => {
+                            info!(target: "reth::cli", %hash, "Saved execution cache snapshot")
+                        }
+                        Ok(Ok(Ok(None))) => {}
+                        Ok(Ok(Err(err))) => {
+                            warn!(target: "reth::cli", %err, "Failed to save execution cache")
+                        }
+                        Ok(Err(err)) => {
+                            warn!(target: "reth::cli", %err, "Failed to save execution cache")
+                        }
+                        Err(_) => {
+                            warn!(target: "reth::cli", timeout = ?EXECUTION_CACHE_SNAPSHOT_TIMEOUT, "Timed out saving execution cache")
+                        }
+                    }
+                    drop(graceful_guard)
+                },
+            );
+        }
+
+        Ok(engine_validator)
     }
 }
 
@@ -5,7 +5,8 @@ use clap::{
     builder::{PossibleValue, TypedValueParser},
     Arg, Args, Command,
 };
-use std::{collections::HashSet, ffi::OsStr, fmt, path::PathBuf, str::FromStr};
+use humantime::parse_duration;
+use std::{collections::HashSet, ffi::OsStr, fmt, path::PathBuf, str::FromStr, time::Duration};
 use strum::{AsRefStr, EnumIter, IntoStaticStr, ParseError, VariantArray, VariantNames};
 
 /// Parameters for debugging purposes
@@ -50,6 +51,31 @@ pub struct DebugArgs {
     )]
     pub rpc_consensus_url: Option<String>,
 
+    /// Runs a fake consensus client using blocks read from a local file.
+    /// Accepts an RLP encoded chain file of post-merge blocks, as exported for `reth import`. The
+    /// blocks are sent to the engine as new payloads followed by forkchoice updates.
+    #[arg(
+        long = "debug.block-file",
+        help_heading = "Debug",
+        value_name = "PATH",
+        conflicts_with_all = ["tip", "etherscan", "rpc_consensus_url", "engine_replay"]
+    )]
+    pub block_file: Option<PathBuf>,
+
+    /// The delay between two consecutive blocks sent with `--debug.block-file`.
+    /// By default, the next block is sent as soon as the previous one was processed.
+    ///
+    /// Parses strings using [`humantime::parse_duration`]
+    /// --debug.block-file-interval 12s
+    #[arg(
+        long = "debug.block-file-interval",
+        help_heading = "Debug",
+        value_parser = parse_duration,
+        requires = "block_file",
+        verbatim_doc_comment
+    )]
+    pub block_file_interval: Option<Duration>,
+
     /// If provided, the engine will skip `n` consecutive FCUs.
     #[arg(long = "debug.skip-fcu", help_heading = "Debug")]
     pub skip_fcu: Option<usize>,
@@ -66,12 +92,109 @@ pub struct DebugArgs {
     #[arg(long = "debug.reorg-depth", requires = "reorg_frequency", help_heading = "Debug")]
     pub reorg_depth: Option<usize>,
 
-    /// The path to store engine API messages at.
-    /// If specified, all of the intercepted engine API messages
-    /// will be written to specified location.
-    #[arg(long = "debug.engine-api-store", help_heading = "Debug", value_name = "PATH")]
+    /// The seed of the randomized fault schedule. Runs with the same seed and the same
+    /// consensus client messages inject the same faults.
+    ///
+    /// If not provided, a random seed is picked and logged on startup.
+    #[arg(long = "debug.fault-seed", help_heading = "Debug", value_name = "SEED")]
+    pub fault_seed: Option<u64>,
+
+    /// The chance in percent that the engine message stream stalls before delivering a message.
+    #[arg(
+        long = "debug.fault-delay",
+        help_heading = "Debug",
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        default_value_t = 0
+    )]
+    pub fault_delay: u8,
+
+    /// The maximum duration of a stall injected with `--debug.fault-delay`, and of a payload held
+    /// back with `--debug.fault-reorder`.
+    ///
+    /// Parses strings using [`humantime::parse_duration`]
+    /// --debug.fault-max-delay 2s
+    #[arg(
+        long = "debug.fault-max-delay",
+        help_heading = "Debug",
+        value_parser = parse_duration,
+        default_value = "1s",
+        verbatim_doc_comment
+    )]
+    pub fault_max_delay: Duration,
+
+    /// The chance in percent that a new payload is delivered after the message that follows it, or
+    /// after `--debug.fault-max-delay` if no other message arrives in time.
+    #[arg(
+        long = "debug.fault-reorder",
+        help_heading = "Debug",
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        default_value_t = 0
+    )]
+    pub fault_reorder: u8,
+
+    /// The chance in percent that a forkchoice update is delivered twice.
+    #[arg(
+        long = "debug.fault-duplicate-fcu",
+        help_heading = "Debug",
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        default_value_t = 0
+    )]
+    pub fault_duplicate_fcu: u8,
+
+    /// The chance in percent that a copy of a new payload with a random state root is delivered
+    /// before the original payload.
+    #[arg(
+        long = "debug.fault-bad-state-root",
+        help_heading = "Debug",
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        default_value_t = 0
+    )]
+    pub fault_bad_state_root: u8,
+
+    /// The chance in percent that a copy of a new payload with a random receipts root is
+    /// delivered before the original payload.
+    #[arg(
+        long = "debug.fault-bad-receipts-root",
+        help_heading = "Debug",
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        default_value_t = 0
+    )]
+    pub fault_bad_receipts_root: u8,
+
+    /// The path to record engine API messages at.
+    /// If specified, all of the forkchoice updates and new payloads received by the engine
+    /// will be written to specified location together with the time they were received at.
+    ///
+    /// The recording can be replayed with `reth debug replay-engine`.
+    #[arg(
+        long = "debug.engine-record",
+        alias = "debug.engine-api-store",
+        help_heading = "Debug",
+        value_name = "PATH"
+    )]
     pub engine_api_store: Option<PathBuf>,
 
+    /// The path to replay recorded engine API messages from.
+    /// If specified, the messages recorded with `--debug.engine-record` are fed into the engine
+    /// in the order they were received.
+    #[arg(
+        long = "debug.engine-replay",
+        help_heading = "Debug",
+        value_name = "PATH",
+        conflicts_with_all = ["tip", "etherscan", "rpc_consensus_url"]
+    )]
+    pub engine_replay: Option<PathBuf>,
+
+    /// Replay the recorded engine API messages as fast as possible instead of preserving the
+    /// original delays between them.
+    #[arg(long = "debug.engine-replay-fast", help_heading = "Debug", requires = "engine_replay")]
+    pub engine_replay_fast: bool,
+
     /// Determines which type of invalid block hook to install
     ///
     /// Example: `witness,prestate`
@@ -118,11 +241,22 @@ impl Default for DebugArgs {
             max_block: None,
             etherscan: None,
             rpc_consensus_url: None,
+            block_file: None,
+            block_file_interval: None,
             skip_fcu: None,
             skip_new_payload: None,
             reorg_frequency: None,
             reorg_depth: None,
+            fault_seed: None,
+            fault_delay: 0,
+            fault_max_delay: Duration::from_secs(1),
+            fault_reorder: 0,
+            fault_duplicate_fcu: 0,
+            fault_bad_state_root: 0,
+            fault_bad_receipts_root: 0,
             engine_api_store: None,
+            engine_replay: None,
+            engine_replay_fast: false,
             invalid_block_hook: Some(InvalidBlockSelection::default()),
             healthy_node_rpc_url: None,
             ethstats: None,
@@ -410,4 +544,102 @@ mod tests {
         .args;
         assert_eq!(args, expected_args);
     }
+
+    #[test]
+    fn test_parse_engine_record_replay_args() {
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--debug.engine-record",
+            "/tmp/engine",
+        ])
+        .args;
+        assert_eq!(args.engine_api_store, Some(PathBuf::from("/tmp/engine")));
+
+        // the previous name of the flag is still accepted
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--debug.engine-api-store",
+            "/tmp/engine",
+        ])
+        .args;
+        assert_eq!(args.engine_api_store, Some(PathBuf::from("/tmp/engine")));
+
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--debug.engine-replay",
+            "/tmp/engine",
+            "--debug.engine-replay-fast",
+        ])
+        .args;
+        assert_eq!(args.engine_replay, Some(PathBuf::from("/tmp/engine")));
+        assert!(args.engine_replay_fast);
+
+        assert!(CommandParser::<DebugArgs>::try_parse_from(["reth", "--debug.engine-replay-fast"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_block_file_args() {
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--debug.block-file",
+            "/tmp/chain.rlp",
+            "--debug.block-file-interval",
+            "12s",
+        ])
+        .args;
+        assert_eq!(args.block_file, Some(PathBuf::from("/tmp/chain.rlp")));
+        assert_eq!(args.block_file_interval, Some(Duration::from_secs(12)));
+
+        assert!(CommandParser::<DebugArgs>::try_parse_from([
+            "reth",
+            "--debug.block-file-interval",
+            "12s"
+        ])
+        .is_err());
+        assert!(CommandParser::<DebugArgs>::try_parse_from([
+            "reth",
+            "--debug.block-file",
+            "/tmp/chain.rlp",
+            "--debug.engine-replay",
+            "/tmp/engine",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_fault_args() {
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--debug.fault-seed",
+            "42",
+            "--debug.fault-delay",
+            "10",
+            "--debug.fault-max-delay",
+            "500ms",
+            "--debug.fault-reorder",
+            "5",
+            "--debug.fault-duplicate-fcu",
+            "20",
+            "--debug.fault-bad-state-root",
+            "1",
+            "--debug.fault-bad-receipts-root",
+            "2",
+        ])
+        .args;
+        let expected_args = DebugArgs {
+            fault_seed: Some(42),
+            fault_delay: 10,
+            fault_max_delay: Duration::from_millis(500),
+            fault_reorder: 5,
+            fault_duplicate_fcu: 20,
+            fault_bad_state_root: 1,
+            fault_bad_receipts_root: 2,
+            ..Default::default()
+        };
+        assert_eq!(args, expected_args);
+
+        assert!(CommandParser::<DebugArgs>::try_parse_from(["reth", "--debug.fault-delay", "101"])
+            .is_err());
+    }
 }
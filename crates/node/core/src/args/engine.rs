@@ -1,11 +1,13 @@
 //! clap [Args](clap::Args) for engine purposes
 
 use clap::Args;
+use humantime::parse_duration;
 use reth_engine_primitives::{TreeConfig, DEFAULT_MULTIPROOF_TASK_CHUNK_SIZE};
+use std::time::Duration;
 
 use crate::node_config::{
-    DEFAULT_CROSS_BLOCK_CACHE_SIZE_MB, DEFAULT_MEMORY_BLOCK_BUFFER_TARGET,
-    DEFAULT_PERSISTENCE_THRESHOLD, DEFAULT_RESERVED_CPU_CORES,
+    DEFAULT_CROSS_BLOCK_CACHE_SIZE_MB, DEFAULT_EXECUTION_CACHE_SNAPSHOT_SIZE_MB,
+    DEFAULT_MEMORY_BLOCK_BUFFER_TARGET, DEFAULT_PERSISTENCE_THRESHOLD, DEFAULT_RESERVED_CPU_CORES,
 };
 
 /// Parameters for configuring the engine driver.
@@ -20,6 +22,24 @@ pub struct EngineArgs {
     #[arg(long = "engine.memory-block-buffer-target", default_value_t = DEFAULT_MEMORY_BLOCK_BUFFER_TARGET)]
     pub memory_block_buffer_target: u64,
 
+    /// Configure the estimated size in megabytes of the executed blocks kept in memory above
+    /// which blocks are persisted.
+    ///
+    /// If set, this replaces `--engine.persistence-threshold` as the trigger for persistence, so
+    /// the number of blocks kept in memory is bounded by their size instead of their count.
+    #[arg(long = "engine.persistence-memory-threshold", value_name = "MB")]
+    pub persistence_memory_threshold: Option<u64>,
+
+    /// Configure the maximum time since the last persistence after which unpersisted canonical
+    /// blocks are persisted, regardless of how many there are.
+    #[arg(long = "engine.persistence-max-age", value_parser = parse_duration)]
+    pub persistence_max_age: Option<Duration>,
+
+    /// Persist blocks as soon as they are finalized, even if they are within
+    /// `--engine.memory-block-buffer-target` of the canonical head.
+    #[arg(long = "engine.persist-finalized", default_value = "false")]
+    pub persist_finalized: bool,
+
     /// Enable legacy state root
     #[arg(long = "engine.legacy-state-root", default_value = "false")]
     pub legacy_state_root_task_enabled: bool,
@@ -85,6 +105,32 @@ pub struct EngineArgs {
     #[arg(long = "engine.disable-precompile-cache", default_value = "false")]
     pub precompile_cache_disabled: bool,
 
+    /// Save the cross-block execution cache and the precompile cache to the data directory on
+    /// shutdown and restore them on startup.
+    ///
+    /// The snapshot is only taken if the cache is at the last persisted block, and only restored
+    /// if the database is still at that block, otherwise it is discarded.
+    #[arg(long = "engine.persist-execution-cache", default_value = "false")]
+    pub persist_execution_cache: bool,
+
+    /// Configure the maximum size of the execution cache snapshot in megabytes.
+    ///
+    /// The storage of the contracts with the most cache hits is saved first.
+    #[arg(long = "engine.execution-cache-snapshot-size", default_value_t = DEFAULT_EXECUTION_CACHE_SNAPSHOT_SIZE_MB)]
+    pub execution_cache_snapshot_size: u64,
+
+    /// Speculatively execute the transactions of a block in parallel before committing them in
+    /// order (experimental).
+    ///
+    /// The state each transaction executes against is predicted with a block-level access list
+    /// (EIP-7928). Transactions whose predicted state turns out to be wrong are executed again
+    /// sequentially.
+    ///
+    /// None of the supported payload types carry an access list yet, so it is always derived by
+    /// executing the block speculatively first, which adds two speculative passes per block.
+    #[arg(long = "engine.parallel-execution", default_value = "false")]
+    pub parallel_execution: bool,
+
     /// Enable state root fallback, useful for testing
     #[arg(long = "engine.state-root-fallback", default_value = "false")]
     pub state_root_fallback: bool,
@@ -105,6 +151,18 @@ pub struct EngineArgs {
     #[arg(long = "engine.allow-unwind-canonical-header", default_value = "false")]
     pub allow_unwind_canonical_header: bool,
 
+    /// Maximum number of canonical blocks a reorg may unwind.
+    ///
+    /// Deeper reorgs are refused and the forkchoice update is answered with `SYNCING` until the
+    /// new head is approved with `admin_approveReorg`.
+    #[arg(long = "engine.max-reorg-depth", value_name = "BLOCKS")]
+    pub max_reorg_depth: Option<u64>,
+
+    /// Shut down the node instead of waiting for an approval when a reorg exceeds
+    /// `--engine.max-reorg-depth`.
+    #[arg(long = "engine.halt-on-deep-reorg", requires = "max_reorg_depth")]
+    pub halt_on_deep_reorg: bool,
+
     /// Configure the number of storage proof workers in the Tokio blocking pool.
     /// If not specified, defaults to 2x available parallelism, clamped between 2 and 64.
     #[arg(long = "engine.storage-worker-count")]
@@ -122,6 +180,9 @@ impl Default for EngineArgs {
         Self {
             persistence_threshold: DEFAULT_PERSISTENCE_THRESHOLD,
             memory_block_buffer_target: DEFAULT_MEMORY_BLOCK_BUFFER_TARGET,
+            persistence_memory_threshold: None,
+            persistence_max_age: None,
+            persist_finalized: false,
             legacy_state_root_task_enabled: false,
             state_root_task_compare_updates: false,
             caching_and_prewarming_enabled: true,
@@ -136,9 +197,14 @@ impl Default for EngineArgs {
             reserved_cpu_cores: DEFAULT_RESERVED_CPU_CORES,
             precompile_cache_enabled: true,
             precompile_cache_disabled: false,
+            persist_execution_cache: false,
+            execution_cache_snapshot_size: DEFAULT_EXECUTION_CACHE_SNAPSHOT_SIZE_MB,
+            parallel_execution: false,
             state_root_fallback: false,
             always_process_payload_attributes_on_canonical_head: false,
             allow_unwind_canonical_header: false,
+            max_reorg_depth: None,
+            halt_on_deep_reorg: false,
             storage_worker_count: None,
             account_worker_count: None,
         }
@@ -151,6 +217,11 @@ impl EngineArgs {
         let mut config = TreeConfig::default()
             .with_persistence_threshold(self.persistence_threshold)
             .with_memory_block_buffer_target(self.memory_block_buffer_target)
+            .with_persistence_memory_threshold(
+                self.persistence_memory_threshold.map(|threshold| threshold * 1024 * 1024),
+            )
+            .with_persistence_max_age(self.persistence_max_age)
+            .with_persist_finalized(self.persist_finalized)
             .with_legacy_state_root(self.legacy_state_root_task_enabled)
             .without_prewarming(self.prewarming_disabled)
             .with_disable_parallel_sparse_trie(self.parallel_sparse_trie_disabled)
@@ -161,11 +232,16 @@ impl EngineArgs {
             .with_multiproof_chunk_size(self.multiproof_chunk_size)
             .with_reserved_cpu_cores(self.reserved_cpu_cores)
             .without_precompile_cache(self.precompile_cache_disabled)
+            .with_persist_execution_cache(self.persist_execution_cache)
+            .with_execution_cache_snapshot_size(self.execution_cache_snapshot_size * 1024 * 1024)
+            .with_parallel_execution(self.parallel_execution)
             .with_state_root_fallback(self.state_root_fallback)
             .with_always_process_payload_attributes_on_canonical_head(
                 self.always_process_payload_attributes_on_canonical_head,
             )
-            .with_unwind_canonical_header(self.allow_unwind_canonical_header);
+            .with_unwind_canonical_header(self.allow_unwind_canonical_header)
+            .with_max_reorg_depth(self.max_reorg_depth)
+            .with_halt_on_deep_reorg(self.halt_on_deep_reorg);
 
         if let Some(count) = self.storage_worker_count {
             config = config.with_storage_worker_count(count);
@@ -197,4 +273,77 @@ mod tests {
         let args = CommandParser::<EngineArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn test_parse_persistence_strategy_args() {
+        let args = CommandParser::<EngineArgs>::parse_from([
+            "reth",
+            "--engine.persistence-memory-threshold",
+            "4096",
+            "--engine.persistence-max-age",
+            "5m",
+            "--engine.persist-finalized",
+        ])
+        .args;
+        assert_eq!(args.persistence_memory_threshold, Some(4096));
+        assert_eq!(args.persistence_max_age, Some(Duration::from_secs(300)));
+        assert!(args.persist_finalized);
+
+        let config = args.tree_config();
+        assert_eq!(config.persistence_memory_threshold(), Some(4096 * 1024 * 1024));
+        assert_eq!(config.persistence_max_age(), Some(Duration::from_secs(300)));
+        assert!(config.persist_finalized());
+    }
+
+    #[test]
+    fn test_parse_persist_execution_cache() {
+        let args =
+            CommandParser::<EngineArgs>::parse_from(["reth", "--engine.persist-execution-cache"])
+                .args;
+        assert!(args.persist_execution_cache);
+        assert!(args.tree_config().persist_execution_cache());
+        assert_eq!(args.tree_config().execution_cache_snapshot_size(), 256 * 1024 * 1024);
+
+        let args = CommandParser::<EngineArgs>::parse_from([
+            "reth",
+            "--engine.persist-execution-cache",
+            "--engine.execution-cache-snapshot-size",
+            "64",
+        ])
+        .args;
+        assert_eq!(args.execution_cache_snapshot_size, 64);
+        assert_eq!(args.tree_config().execution_cache_snapshot_size(), 64 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_parse_parallel_execution() {
+        let args =
+            CommandParser::<EngineArgs>::parse_from(["reth", "--engine.parallel-execution"]).args;
+        assert!(args.parallel_execution);
+        assert!(args.tree_config().parallel_execution());
+    }
+
+    #[test]
+    fn test_parse_reorg_protection_args() {
+        let args = CommandParser::<EngineArgs>::parse_from([
+            "reth",
+            "--engine.max-reorg-depth",
+            "16",
+            "--engine.halt-on-deep-reorg",
+        ])
+        .args;
+        assert_eq!(args.max_reorg_depth, Some(16));
+        assert!(args.halt_on_deep_reorg);
+
+        let config = args.tree_config();
+        assert_eq!(config.max_reorg_depth(), Some(16));
+        assert!(config.halt_on_deep_reorg());
+
+        // halting requires a maximum reorg depth
+        assert!(CommandParser::<EngineArgs>::try_parse_from([
+            "reth",
+            "--engine.halt-on-deep-reorg"
+        ])
+        .is_err());
+    }
 }
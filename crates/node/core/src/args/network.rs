@@ -29,11 +29,13 @@ use reth_network::{
                 DEFAULT_MAX_COUNT_PENDING_POOL_IMPORTS, DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
             },
         },
+        gossip::{FeeAwarePropagationConfig, FeeAwarePropagationPolicy, PeerBandwidthLimit},
         TransactionFetcherConfig, TransactionPropagationMode, TransactionsManagerConfig,
         DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
         SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
     },
     HelloMessageWithProtocols, NetworkConfigBuilder, NetworkPrimitives, SessionsConfig,
+    UploadLimits,
 };
 use reth_network_peers::{mainnet_nodes, TrustedPeer};
 use secp256k1::SecretKey;
@@ -84,11 +86,11 @@ pub struct NetworkArgs {
     #[arg(long, value_name = "PATH")]
     pub p2p_secret_key: Option<PathBuf>,
 
-    /// Do not persist peers.
+    /// Do not persist peers, bans and peer reputations.
     #[arg(long, verbatim_doc_comment)]
     pub no_persist_peers: bool,
 
-    /// NAT resolution method (any|none|upnp|publicip|extip:\<IP\>)
+    /// NAT resolution method (any|none|upnp|natpmp|publicip|extip:\<IP\>)
     #[arg(long, default_value = "any")]
     pub nat: NatResolver,
 
@@ -100,6 +102,11 @@ pub struct NetworkArgs {
     #[arg(long = "port", value_name = "PORT", default_value_t = DEFAULT_DISCOVERY_PORT)]
     pub port: u16,
 
+    /// Additional IPv6 network listening address, enables dual-stack networking on `--port`.
+    /// Ignored if `--addr` is an IPv6 address.
+    #[arg(long = "addr.ipv6", value_name = "ADDR_IPV6")]
+    pub addr_ipv6: Option<Ipv6Addr>,
+
     /// Maximum number of outbound peers. default: 100
     #[arg(long)]
     pub max_outbound_peers: Option<usize>,
@@ -180,6 +187,27 @@ pub struct NetworkArgs {
     )]
     pub propagation_mode: TransactionPropagationMode,
 
+    /// Rank transactions by effective tip per byte before propagating them.
+    ///
+    /// The most valuable transactions are then preferred when transactions are sent to peers in
+    /// full.
+    #[arg(long = "tx-propagation-rank-by-tip")]
+    pub tx_propagation_rank_by_tip: bool,
+
+    /// Maximum upload rate for full transaction broadcasts to a single peer.
+    ///
+    /// Transactions that exceed a peer's budget are announced as hashes instead. Unlimited if not
+    /// set.
+    #[arg(long = "tx-propagation-peer-bandwidth", value_name = "BYTES_PER_SEC")]
+    pub tx_propagation_peer_bandwidth: Option<u64>,
+
+    /// Stop propagating transactions to a peer once it announced or sent junk this many times
+    /// within ten minutes.
+    ///
+    /// Disabled if not set.
+    #[arg(long = "tx-propagation-max-junk", value_name = "COUNT")]
+    pub tx_propagation_max_junk: Option<u32>,
+
     /// Comma separated list of required block hashes.
     /// Peers that don't have these blocks will be filtered out.
     #[arg(long = "required-block-hashes", value_delimiter = ',')]
@@ -188,6 +216,43 @@ pub struct NetworkArgs {
     /// Optional network ID to override the chain specification's network ID for P2P connections
     #[arg(long)]
     pub network_id: Option<u64>,
+
+    /// Maximum upload rate to all peers combined.
+    ///
+    /// Applies to responses to block, receipt and transaction requests and to transaction
+    /// broadcasts. Unlimited if not set.
+    #[arg(long = "upload-limit", value_name = "BYTES_PER_SEC")]
+    pub upload_limit: Option<u64>,
+
+    /// Maximum upload rate to a single peer.
+    ///
+    /// Applies to responses to block, receipt and transaction requests and to transaction
+    /// broadcasts. Unlimited if not set.
+    #[arg(long = "upload-limit.per-peer", value_name = "BYTES_PER_SEC")]
+    pub upload_limit_per_peer: Option<u64>,
+
+    /// Serve the `snap/1` protocol to peers from the state of the latest persisted block.
+    #[arg(long = "snap.serve")]
+    pub snap_serve: bool,
+
+    /// Max byte size of a single `snap/1` response.
+    ///
+    /// Peers may request less. Default is 2 MiB.
+    #[arg(long = "snap.response-soft-limit", value_name = "BYTES", requires = "snap_serve")]
+    pub snap_response_soft_limit: Option<u64>,
+
+    /// Download the state of the sync target from `snap/1` peers instead of executing all blocks.
+    ///
+    /// Receipts and history before the downloaded state are not available, so this requires
+    /// receipts pruning to be configured.
+    #[arg(long = "snap.sync")]
+    pub snap_sync: bool,
+
+    /// Advertise the `eth/70` protocol, which serves partial block receipts.
+    ///
+    /// The specification of `eth/70` is not final yet, so it's disabled by default.
+    #[arg(long = "eth70.experimental")]
+    pub eth70: bool,
 }
 
 impl NetworkArgs {
@@ -233,11 +298,25 @@ impl NetworkArgs {
         }
     }
 
+    /// Returns the [`FeeAwarePropagationPolicy`] configured by the transaction propagation
+    /// arguments.
+    pub fn transactions_propagation_policy(&self) -> FeeAwarePropagationPolicy {
+        FeeAwarePropagationPolicy::new(FeeAwarePropagationConfig {
+            peers: self.tx_propagation_policy,
+            rank_by_effective_tip: self.tx_propagation_rank_by_tip,
+            peer_bandwidth: self.tx_propagation_peer_bandwidth.map(PeerBandwidthLimit::per_second),
+            max_junk_announcements: self.tx_propagation_max_junk,
+            ..Default::default()
+        })
+    }
+
     /// Build a [`NetworkConfigBuilder`] from a [`Config`] and a [`EthChainSpec`], in addition to
     /// the values in this option struct.
     ///
     /// The `default_peers_file` will be used as the default location to store the persistent peers
-    /// file if `no_persist_peers` is false, and there is no provided `peers_file`.
+    /// file if `no_persist_peers` is false, and there is no provided `peers_file`. The bans and
+    /// peer reputations of a previous run are restored from the `peer_state_file` unless
+    /// `no_persist_peers` is set.
     ///
     /// Configured Bootnodes are prioritized, if unset, the chain spec bootnodes are used
     /// Priority order for bootnodes configuration:
@@ -250,6 +329,7 @@ impl NetworkArgs {
         chain_spec: impl EthChainSpec,
         secret_key: SecretKey,
         default_peers_file: PathBuf,
+        peer_state_file: PathBuf,
     ) -> NetworkConfigBuilder<N> {
         let addr = self.resolved_addr();
         let chain_bootnodes = self
@@ -263,6 +343,16 @@ impl NetworkArgs {
             .clone()
             .with_max_inbound_opt(self.max_inbound_peers)
             .with_max_outbound_opt(self.max_outbound_peers);
+        let peers_config = match peers_config
+            .clone()
+            .with_persisted_state_from_file(self.persistent_peers_file(peer_state_file))
+        {
+            Ok(peers_config) => peers_config,
+            Err(err) => {
+                error!(target: "reth::cli", %err, "Failed to load saved bans and reputations");
+                peers_config
+            }
+        };
 
         // Configure basic network stack
         NetworkConfigBuilder::<N>::new(secret_key)
@@ -299,7 +389,24 @@ impl NetworkArgs {
                 // set discovery port based on instance number
                 self.discovery.port,
             ))
+            // listen and run discovery v4 on IPv6 as well, if configured
+            .apply(|mut builder| {
+                if let Some(ip) = self.addr_ipv6.filter(|_| addr.is_ipv4()) {
+                    builder = builder.add_listener_addr((ip, self.port).into());
+                }
+                if let Some(ip) = self.discovery.addr_ipv6.or(self.addr_ipv6) &&
+                    self.discovery.addr.is_ipv4()
+                {
+                    builder = builder.discovery_secondary_addr((ip, self.discovery.port).into());
+                }
+                builder
+            })
             .disable_tx_gossip(self.disable_tx_gossip)
+            .upload_limits(UploadLimits {
+                global: self.upload_limit,
+                per_peer: self.upload_limit_per_peer,
+            })
+            .eth70(self.eth70)
             .required_block_hashes(self.required_block_hashes.clone())
             .network_id(self.network_id)
     }
@@ -361,6 +468,7 @@ impl Default for NetworkArgs {
             nat: NatResolver::Any,
             addr: DEFAULT_DISCOVERY_ADDR,
             port: DEFAULT_DISCOVERY_PORT,
+            addr_ipv6: None,
             max_outbound_peers: None,
             max_inbound_peers: None,
             max_concurrent_tx_requests: DEFAULT_MAX_COUNT_CONCURRENT_REQUESTS,
@@ -375,8 +483,17 @@ impl Default for NetworkArgs {
             tx_propagation_policy: TransactionPropagationKind::default(),
             disable_tx_gossip: false,
             propagation_mode: TransactionPropagationMode::Sqrt,
+            tx_propagation_rank_by_tip: false,
+            tx_propagation_peer_bandwidth: None,
+            tx_propagation_max_junk: None,
             required_block_hashes: vec![],
             network_id: None,
+            upload_limit: None,
+            upload_limit_per_peer: None,
+            snap_serve: false,
+            snap_response_soft_limit: None,
+            snap_sync: false,
+            eth70: false,
         }
     }
 }
@@ -412,6 +529,15 @@ pub struct DiscoveryArgs {
     #[arg(id = "discovery.port", long = "discovery.port", value_name = "DISCOVERY_PORT", default_value_t = DEFAULT_DISCOVERY_PORT)]
     pub port: u16,
 
+    /// The UDP IPv6 address to additionally use for devp2p peer discovery version 4 on
+    /// `--discovery.port`. Defaults to `--addr.ipv6`. Ignored if `--discovery.addr` is IPv6.
+    #[arg(
+        id = "discovery.addr.ipv6",
+        long = "discovery.addr.ipv6",
+        value_name = "DISCOVERY_ADDR_IPV6"
+    )]
+    pub addr_ipv6: Option<Ipv6Addr>,
+
     /// The UDP IPv4 address to use for devp2p peer discovery version 5. Overwritten by `RLPx`
     /// address, if it's also IPv4.
     #[arg(id = "discovery.v5.addr", long = "discovery.v5.addr", value_name = "DISCOVERY_V5_ADDR", default_value = None)]
@@ -565,6 +691,7 @@ impl Default for DiscoveryArgs {
             disable_nat: false,
             addr: DEFAULT_DISCOVERY_ADDR,
             port: DEFAULT_DISCOVERY_PORT,
+            addr_ipv6: None,
             discv5_addr: None,
             discv5_addr_ipv6: None,
             discv5_port: DEFAULT_DISCOVERY_V5_PORT,
@@ -657,6 +784,47 @@ mod tests {
         assert!(args.disable_tx_gossip);
     }
 
+    #[test]
+    fn parse_upload_limit_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--upload-limit",
+            "10000000",
+            "--upload-limit.per-peer",
+            "500000",
+        ])
+        .args;
+        assert_eq!(args.upload_limit, Some(10_000_000));
+        assert_eq!(args.upload_limit_per_peer, Some(500_000));
+    }
+
+    #[test]
+    fn parse_eth70_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth"]).args;
+        assert!(!args.eth70);
+
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth", "--eth70.experimental"]).args;
+        assert!(args.eth70);
+    }
+
+    #[test]
+    fn parse_dual_stack_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth", "--addr.ipv6", "::"]).args;
+        assert_eq!(args.addr_ipv6, Some(Ipv6Addr::UNSPECIFIED));
+        assert_eq!(args.discovery.addr_ipv6, None);
+
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--addr.ipv6",
+            "::1",
+            "--discovery.addr.ipv6",
+            "::",
+        ])
+        .args;
+        assert_eq!(args.addr_ipv6, Some(Ipv6Addr::LOCALHOST));
+        assert_eq!(args.discovery.addr_ipv6, Some(Ipv6Addr::UNSPECIFIED));
+    }
+
     #[test]
     fn network_args_default_sanity_test() {
         let default_args = NetworkArgs::default();
@@ -135,6 +135,7 @@ impl PruningArgs {
                         .ethereum_fork_activation(EthereumHardfork::Paris)
                         .block_number()
                         .map(PruneMode::Before),
+                    blob_sidecars: None,
                     merkle_changesets: PruneMode::Distance(MINIMUM_PRUNING_DISTANCE),
                     #[expect(deprecated)]
                     receipts_log_filter: (),
@@ -262,6 +262,19 @@ pub struct RpcServerArgs {
         value_parser = parse_duration_from_secs_or_ms,
     )]
     pub rpc_send_raw_transaction_sync_timeout: Duration,
+
+    /// Enable `eth_sendRawTransactionConditional` for transactions with ERC-4337 conditionals.
+    ///
+    /// Conditional transactions are not gossiped and are dropped from the pool once their
+    /// conditions can no longer be met.
+    #[arg(long = "rpc.enable-tx-conditional")]
+    pub rpc_enable_tx_conditional: bool,
+
+    /// Number of recent block validation reports to retain for `reth_getValidationReports`.
+    ///
+    /// The method is only available if this is set and the `reth` namespace is enabled.
+    #[arg(long = "rpc.validation-reports", value_name = "COUNT")]
+    pub rpc_validation_reports: Option<usize>,
 }
 
 impl RpcServerArgs {
@@ -430,6 +443,8 @@ impl Default for RpcServerArgs {
             builder_disallow: Default::default(),
             rpc_send_raw_transaction_sync_timeout:
                 constants::RPC_DEFAULT_SEND_RAW_TX_SYNC_TIMEOUT_SECS,
+            rpc_enable_tx_conditional: false,
+            rpc_validation_reports: None,
         }
     }
 }
@@ -1,10 +1,14 @@
 //! Transaction pool arguments
 
 use crate::cli::config::RethTransactionPoolConfig;
-use alloy_eips::eip1559::{ETHEREUM_BLOCK_GAS_LIMIT_30M, MIN_PROTOCOL_BASE_FEE};
+use alloy_eips::{
+    eip1559::{ETHEREUM_BLOCK_GAS_LIMIT_30M, MIN_PROTOCOL_BASE_FEE},
+    merge::EPOCH_SLOTS,
+};
 use alloy_primitives::Address;
 use clap::Args;
 use reth_cli_util::parse_duration_from_secs_or_ms;
+use reth_prune_types::PruneMode;
 use reth_transaction_pool::{
     blobstore::disk::DEFAULT_MAX_CACHED_BLOBS,
     maintain::MAX_QUEUED_TRANSACTION_LIFETIME,
@@ -136,6 +140,13 @@ pub struct TxPoolArgs {
     /// Max batch size for transaction pool insertions
     #[arg(long = "txpool.max-batch-size", default_value_t = 1)]
     pub max_batch_size: usize,
+
+    /// Number of epochs to retain the blob sidecars of included transactions for.
+    ///
+    /// If set, the sidecars of blob transactions are copied to the database once they are
+    /// included and served by `reth_getBlobSidecars` until they are pruned.
+    #[arg(long = "txpool.blob-retention-epochs", value_name = "EPOCHS")]
+    pub blob_retention_epochs: Option<u64>,
 }
 
 impl TxPoolArgs {
@@ -154,6 +165,14 @@ impl TxPoolArgs {
         self.minimal_protocol_basefee = protocol_base_fee;
         self
     }
+
+    /// Returns the [`PruneMode`] for retained blob sidecars, if blob retention is enabled.
+    pub const fn blob_retention_prune_mode(&self) -> Option<PruneMode> {
+        match self.blob_retention_epochs {
+            Some(epochs) => Some(PruneMode::Distance(epochs.saturating_mul(EPOCH_SLOTS))),
+            None => None,
+        }
+    }
 }
 
 impl Default for TxPoolArgs {
@@ -188,6 +207,7 @@ impl Default for TxPoolArgs {
             transactions_backup_path: None,
             disable_transactions_backup: false,
             max_batch_size: 1,
+            blob_retention_epochs: None,
         }
     }
 }
@@ -289,4 +309,19 @@ mod tests {
 
         assert!(result.is_err(), "Expected an error for invalid duration");
     }
+
+    #[test]
+    fn txpool_parse_blob_retention_epochs() {
+        let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.blob_retention_prune_mode(), None);
+
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.blob-retention-epochs",
+            "100",
+        ])
+        .args;
+        assert_eq!(args.blob_retention_epochs, Some(100));
+        assert_eq!(args.blob_retention_prune_mode(), Some(PruneMode::Distance(3200)));
+    }
 }
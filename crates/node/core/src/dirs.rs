@@ -315,6 +315,13 @@ impl<D> ChainPath<D> {
         self.data_dir().join("known-peers.json")
     }
 
+    /// Returns the path to the file with the bans and peer reputations for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/peer-state.json`
+    pub fn peer_state(&self) -> PathBuf {
+        self.data_dir().join("peer-state.json")
+    }
+
     /// Returns the path to the blobstore directory for this chain where blobs of unfinalized
     /// transactions are stored.
     ///
@@ -330,6 +337,13 @@ impl<D> ChainPath<D> {
         self.data_dir().join("txpool-transactions-backup.rlp")
     }
 
+    /// Returns the path to the snapshot of the execution cache that is restored on startup.
+    ///
+    /// `<DIR>/<CHAIN_ID>/execution-cache.bin`
+    pub fn execution_cache_snapshot(&self) -> PathBuf {
+        self.data_dir().join("execution-cache.bin")
+    }
+
     /// Returns the path to the config file for this chain.
     ///
     /// `<DIR>/<CHAIN_ID>/reth.toml`
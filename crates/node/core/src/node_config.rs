@@ -40,6 +40,9 @@ pub use reth_engine_primitives::{
 /// Default size of cross-block cache in megabytes.
 pub const DEFAULT_CROSS_BLOCK_CACHE_SIZE_MB: u64 = 4 * 1024;
 
+/// Default maximum size of the execution cache snapshot in megabytes.
+pub const DEFAULT_EXECUTION_CACHE_SNAPSHOT_SIZE_MB: u64 = 256;
+
 /// This includes all necessary configuration to launch the node.
 /// The individual configuration options can be overwritten before launching the node.
 ///
@@ -21,7 +21,7 @@ use std::{
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use tokio::time::Interval;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 
 /// Interval of reporting node state.
 const INFO_MESSAGE_INTERVAL: Duration = Duration::from_secs(25);
@@ -283,6 +283,42 @@ impl NodeState {
             ConsensusEngineEvent::BlockReceived(num_hash) => {
                 info!(number=num_hash.number, hash=?num_hash.hash, "Received block from consensus engine");
             }
+            ConsensusEngineEvent::BlockValidated(report) => {
+                debug!(
+                    number=report.number,
+                    hash=?report.hash,
+                    total=?report.total_duration,
+                    execution=?report.execution_duration,
+                    speculative_execution=?report.speculative_execution_duration,
+                    post_execution_validation=?report.post_execution_validation_duration,
+                    state_root=?report.state_root_duration,
+                    multiproof_wait=?report.multiproof_wait_duration,
+                    sparse_trie_root=?report.sparse_trie_root_duration,
+                    state_root_source=?report.state_root_source,
+                    cache_hit_rate=?report.cache_hit_rate(),
+                    persistence_in_progress=report.persistence_in_progress,
+                    blocks_pending_persistence=report.blocks_pending_persistence,
+                    "Block validation report"
+                );
+            }
+            ConsensusEngineEvent::CanonicalChainReorged(reorg) => {
+                info!(
+                    depth=reorg.depth(),
+                    fork_block=?reorg.fork_block,
+                    old_head=?reorg.old.last(),
+                    new_head=?reorg.new_head(),
+                    "Canonical chain reorged"
+                );
+            }
+            ConsensusEngineEvent::DeepReorgRejected(reorg) => {
+                error!(
+                    depth=reorg.depth(),
+                    fork_block=?reorg.fork_block,
+                    old_head=?reorg.old.last(),
+                    new_head=?reorg.new_head(),
+                    "Refused reorg deeper than the maximum reorg depth"
+                );
+            }
         }
     }
 
@@ -3,7 +3,7 @@
 use crate::{MessageValidationKind, PayloadAttributes};
 use alloc::vec::Vec;
 use alloy_eips::{eip1898::BlockWithParent, eip4895::Withdrawal, eip7685::Requests, BlockNumHash};
-use alloy_primitives::B256;
+use alloy_primitives::{Bytes, B256};
 use alloy_rpc_types_engine::ExecutionData;
 use core::fmt::Debug;
 use serde::{de::DeserializeOwned, Serialize};
@@ -50,6 +50,15 @@ pub trait ExecutionPayload:
 
     /// Returns the total gas consumed by all transactions in this block.
     fn gas_used(&self) -> u64;
+
+    /// Returns the RLP encoded block-level access list (EIP-7928) carried by this payload.
+    ///
+    /// Returns `None` if the payload doesn't carry an access list, which is the case for all
+    /// payload types that don't override this, in which case the access list is derived when
+    /// executing the block in parallel.
+    fn block_access_list(&self) -> Option<&Bytes> {
+        None
+    }
 }
 
 impl ExecutionPayload for ExecutionData {
@@ -1,6 +1,6 @@
 use crate::segments::{
-    AccountHistory, Bodies, MerkleChangeSets, Segment, SenderRecovery, StorageHistory,
-    TransactionLookup, UserReceipts,
+    AccountHistory, BlobSidecars, Bodies, MerkleChangeSets, Segment, SenderRecovery,
+    StorageHistory, TransactionLookup, UserReceipts,
 };
 use alloy_eips::eip2718::Encodable2718;
 use reth_db_api::{table::Value, transaction::DbTxMut};
@@ -67,6 +67,7 @@ where
             account_history,
             storage_history,
             bodies_history,
+            blob_sidecars,
             merkle_changesets,
             receipts_log_filter: (),
         } = prune_modes;
@@ -86,6 +87,8 @@ where
             .segment_opt(transaction_lookup.map(TransactionLookup::new))
             // Sender recovery
             .segment_opt(sender_recovery.map(SenderRecovery::new))
+            // Blob sidecars
+            .segment_opt(blob_sidecars.map(BlobSidecars::new))
     }
 }
 
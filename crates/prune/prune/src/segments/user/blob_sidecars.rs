@@ -0,0 +1,188 @@
+use crate::{
+    db_ext::DbTxPruneExt,
+    segments::{PruneInput, Segment},
+    PrunerError,
+};
+use alloy_primitives::B256;
+use reth_db_api::{models::BlockNumberVersionedHash, tables, transaction::DbTxMut};
+use reth_provider::{errors::provider::ProviderResult, DBProvider, PruneCheckpointWriter};
+use reth_prune_types::{
+    PruneCheckpoint, PruneMode, PrunePurpose, PruneSegment, SegmentOutput, SegmentOutputCheckpoint,
+};
+use tracing::{instrument, trace};
+
+#[derive(Debug)]
+pub struct BlobSidecars {
+    mode: PruneMode,
+}
+
+impl BlobSidecars {
+    pub const fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<Provider> Segment<Provider> for BlobSidecars
+where
+    Provider: DBProvider<Tx: DbTxMut> + PruneCheckpointWriter,
+{
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::BlobSidecars
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::User
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        let Some(block_range) = input.get_next_block_range() else {
+            trace!(target: "pruner", "No blob sidecars to prune");
+            return Ok(SegmentOutput::done())
+        };
+
+        let block_range_end = *block_range.end();
+        let mut limiter = input.limiter;
+
+        let range_start: BlockNumberVersionedHash = (*block_range.start(), B256::ZERO).into();
+        let range_end: BlockNumberVersionedHash = (block_range_end + 1, B256::ZERO).into();
+
+        let mut last_pruned_block = None;
+        let (pruned, done) = provider.tx_ref().prune_table_with_range::<tables::BlobSidecars>(
+            range_start..range_end,
+            &mut limiter,
+            |_| false,
+            |(BlockNumberVersionedHash((block_number, _)), _)| {
+                last_pruned_block = Some(block_number);
+            },
+        )?;
+
+        trace!(target: "pruner", %pruned, %done, "Pruned blob sidecars");
+
+        let last_pruned_block = if done {
+            block_range_end
+        } else {
+            // If there are more blob sidecars to prune, set the checkpoint block number to
+            // previous, so we could finish pruning its blob sidecars on the next run.
+            last_pruned_block.map_or(block_range_end, |block_number| block_number.saturating_sub(1))
+        };
+
+        let progress = limiter.progress(done);
+
+        Ok(SegmentOutput {
+            progress,
+            pruned,
+            checkpoint: Some(SegmentOutputCheckpoint {
+                block_number: Some(last_pruned_block),
+                tx_number: None,
+            }),
+        })
+    }
+
+    fn save_checkpoint(
+        &self,
+        provider: &Provider,
+        checkpoint: PruneCheckpoint,
+    ) -> ProviderResult<()> {
+        provider.save_prune_checkpoint(PruneSegment::BlobSidecars, checkpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{BlobSidecars, PruneInput, PruneLimiter, Segment, SegmentOutput};
+    use alloy_primitives::{BlockNumber, B256};
+    use assert_matches::assert_matches;
+    use reth_db_api::{models::StoredBlobSidecar, tables};
+    use reth_provider::{
+        BlobSidecarWriter, DBProvider, DatabaseProviderFactory, PruneCheckpointReader,
+    };
+    use reth_prune_types::{
+        PruneCheckpoint, PruneInterruptReason, PruneMode, PruneProgress, PruneSegment,
+    };
+    use reth_stages::test_utils::TestStageDB;
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+
+        let provider = db.factory.database_provider_rw().unwrap();
+        for block_number in 1..=10 {
+            let sidecars = (0..2u8)
+                .map(|i| (B256::repeat_byte(i + 1), StoredBlobSidecar::default()))
+                .collect();
+            provider.insert_blob_sidecars(block_number, sidecars).unwrap();
+        }
+        provider.commit().expect("commit");
+
+        assert_eq!(db.table::<tables::BlobSidecars>().unwrap().len(), 20);
+
+        let test_prune = |to_block: BlockNumber,
+                          expected_result: (PruneProgress, usize),
+                          expected_checkpoint: BlockNumber,
+                          expected_remaining: usize| {
+            let prune_mode = PruneMode::Before(to_block);
+            let segment = BlobSidecars::new(prune_mode);
+            let input = PruneInput {
+                previous_checkpoint: db
+                    .factory
+                    .provider()
+                    .unwrap()
+                    .get_prune_checkpoint(PruneSegment::BlobSidecars)
+                    .unwrap(),
+                to_block,
+                limiter: PruneLimiter::default().set_deleted_entries_limit(5),
+            };
+
+            let provider = db.factory.database_provider_rw().unwrap();
+            let result = segment.prune(&provider, input).unwrap();
+
+            assert_matches!(
+                result,
+                SegmentOutput {progress, pruned, checkpoint: Some(_)}
+                    if (progress, pruned) == expected_result
+            );
+
+            segment
+                .save_checkpoint(
+                    &provider,
+                    result.checkpoint.unwrap().as_prune_checkpoint(prune_mode),
+                )
+                .unwrap();
+            provider.commit().expect("commit");
+
+            assert_eq!(db.table::<tables::BlobSidecars>().unwrap().len(), expected_remaining);
+            assert_eq!(
+                db.factory
+                    .provider()
+                    .unwrap()
+                    .get_prune_checkpoint(PruneSegment::BlobSidecars)
+                    .unwrap(),
+                Some(PruneCheckpoint {
+                    block_number: Some(expected_checkpoint),
+                    tx_number: None,
+                    prune_mode
+                })
+            );
+        };
+
+        // Block 3 is only partially pruned, so the checkpoint points at block 2.
+        test_prune(
+            4,
+            (PruneProgress::HasMoreData(PruneInterruptReason::DeletedEntriesLimitReached), 5),
+            2,
+            15,
+        );
+        test_prune(4, (PruneProgress::Finished, 3), 4, 12);
+        test_prune(
+            10,
+            (PruneProgress::HasMoreData(PruneInterruptReason::DeletedEntriesLimitReached), 5),
+            6,
+            7,
+        );
+    }
+}
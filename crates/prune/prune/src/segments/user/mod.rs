@@ -1,4 +1,5 @@
 mod account_history;
+mod blob_sidecars;
 mod bodies;
 mod history;
 mod merkle_change_sets;
@@ -8,6 +9,7 @@ mod storage_history;
 mod transaction_lookup;
 
 pub use account_history::AccountHistory;
+pub use blob_sidecars::BlobSidecars;
 pub use bodies::Bodies;
 pub use merkle_change_sets::MerkleChangeSets;
 pub use receipts::Receipts;
@@ -41,6 +41,8 @@ pub enum PruneSegment {
     MerkleChangeSets,
     /// Prune segment responsible for bodies (transactions in static files).
     Bodies,
+    /// Prune segment responsible for the retained blob sidecars in the `BlobSidecars` table.
+    BlobSidecars,
 }
 
 #[cfg(test)]
@@ -63,7 +65,7 @@ impl PruneSegment {
     /// Returns minimum number of blocks to keep in the database for this segment.
     pub const fn min_blocks(&self, purpose: PrunePurpose) -> u64 {
         match self {
-            Self::SenderRecovery | Self::TransactionLookup => 0,
+            Self::SenderRecovery | Self::TransactionLookup | Self::BlobSidecars => 0,
             Self::Receipts if purpose.is_static_file() => 0,
             Self::ContractLogs |
             Self::AccountHistory |
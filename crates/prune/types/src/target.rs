@@ -89,6 +89,9 @@ pub struct PruneModes {
         )
     )]
     pub bodies_history: Option<PruneMode>,
+    /// Blob sidecars retention configuration for the `BlobSidecars` table.
+    #[cfg_attr(any(test, feature = "serde"), serde(skip_serializing_if = "Option::is_none"))]
+    pub blob_sidecars: Option<PruneMode>,
     /// Merkle Changesets pruning configuration for `AccountsTrieChangeSets` and
     /// `StoragesTrieChangeSets`.
     #[cfg_attr(
@@ -114,6 +117,7 @@ impl Default for PruneModes {
             account_history: None,
             storage_history: None,
             bodies_history: None,
+            blob_sidecars: None,
             merkle_changesets: default_merkle_changesets_mode(),
             #[expect(deprecated)]
             receipts_log_filter: (),
@@ -131,6 +135,7 @@ impl PruneModes {
             account_history: Some(PruneMode::Full),
             storage_history: Some(PruneMode::Full),
             bodies_history: Some(PruneMode::Full),
+            blob_sidecars: Some(PruneMode::Full),
             merkle_changesets: PruneMode::Full,
             #[expect(deprecated)]
             receipts_log_filter: (),
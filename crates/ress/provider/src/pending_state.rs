@@ -124,7 +124,10 @@ pub async fn maintain_pending_state<P>(
             // ignore
             ConsensusEngineEvent::CanonicalChainCommitted(_, _) |
             ConsensusEngineEvent::BlockReceived(_) |
-            ConsensusEngineEvent::LiveSyncProgress(_) => (),
+            ConsensusEngineEvent::LiveSyncProgress(_) |
+            ConsensusEngineEvent::BlockValidated(_) |
+            ConsensusEngineEvent::CanonicalChainReorged(_) |
+            ConsensusEngineEvent::DeepReorgRejected(_) => (),
         }
     }
 }
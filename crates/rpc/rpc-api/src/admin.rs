@@ -1,5 +1,7 @@
+use alloy_primitives::B256;
 use alloy_rpc_types_admin::{NodeInfo, PeerInfo};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_net_banlist::{BanEntry, BanTarget};
 use reth_network_peers::{AnyNode, NodeRecord};
 
 /// Admin namespace rpc interface that gives access to several non-standard RPC methods.
@@ -27,6 +29,23 @@ pub trait AdminApi {
     #[method(name = "removeTrustedPeer")]
     fn remove_trusted_peer(&self, record: AnyNode) -> RpcResult<bool>;
 
+    /// Returns the active bans of peer ids, IP addresses and IP ranges.
+    #[method(name = "listBans")]
+    async fn list_bans(&self) -> RpcResult<Vec<BanEntry>>;
+
+    /// Bans a peer id, an IP address or an IP range in CIDR notation, for the given number of
+    /// seconds or permanently.
+    ///
+    /// Connected peers that match the ban are disconnected and matching nodes are no longer
+    /// discovered. Single IP addresses that are not globally routable can't be banned and are
+    /// rejected with an error.
+    #[method(name = "addBan")]
+    fn add_ban(&self, target: BanTarget, duration: Option<u64>) -> RpcResult<bool>;
+
+    /// Lifts the ban of a peer id, an IP address or an IP range.
+    #[method(name = "removeBan")]
+    fn remove_ban(&self, target: BanTarget) -> RpcResult<bool>;
+
     /// The peers administrative property can be queried for all the information known about the
     /// connected remote nodes at the networking granularity. These include general information
     /// about the nodes themselves as participants of the devp2p P2P overlay protocol, as well as
@@ -51,3 +70,17 @@ pub trait AdminApi {
     #[method(name = "clearTxpool")]
     async fn clear_txpool(&self) -> RpcResult<u64>;
 }
+
+/// Admin namespace rpc interface for approving reorgs that exceed the configured maximum reorg
+/// depth of the engine.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait AdminReorgApi {
+    /// Approves the next reorg whose new chain contains the given block, even if it exceeds the
+    /// maximum reorg depth.
+    ///
+    /// If the last refused reorg contains the block, it is applied right away. Returns false if
+    /// the engine is not running.
+    #[method(name = "approveReorg")]
+    fn approve_reorg(&self, head: B256) -> RpcResult<bool>;
+}
@@ -1,6 +1,8 @@
 use alloy_eips::BlockId;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, Bytes, FixedBytes, TxHash, B256, U256};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_engine_primitives::BlockValidationReport;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 // Required for the subscription attribute below
@@ -17,6 +19,18 @@ pub trait RethApi {
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
 
+    /// Returns the retained blob sidecars of the given block.
+    ///
+    /// If `versioned_hashes` is set, only the sidecars of the given versioned hashes are returned.
+    /// Blob sidecars are only available if blob retention is enabled and the block has not been
+    /// pruned yet.
+    #[method(name = "getBlobSidecars")]
+    async fn reth_get_blob_sidecars(
+        &self,
+        block_id: BlockId,
+        versioned_hashes: Option<Vec<B256>>,
+    ) -> RpcResult<Vec<RethBlobSidecar>>;
+
     /// Subscribe to json `ChainNotifications`
     #[subscription(
         name = "subscribeChainNotifications",
@@ -25,3 +39,41 @@ pub trait RethApi {
     )]
     async fn reth_subscribe_chain_notifications(&self) -> jsonrpsee::core::SubscriptionResult;
 }
+
+/// An extension to the `reth_` namespace that exposes the validation reports of recently validated
+/// blocks.
+///
+/// This is separate from the regular `reth_` api, because the reports are collected from the events
+/// of the consensus engine rather than read from the database.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "reth"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "reth"))]
+pub trait RethValidationReportsApi {
+    /// Returns the timing breakdowns of the most recently validated blocks, oldest first.
+    ///
+    /// If `count` is set, only the `count` most recent reports are returned.
+    #[method(name = "getValidationReports")]
+    async fn reth_get_validation_reports(
+        &self,
+        count: Option<usize>,
+    ) -> RpcResult<Vec<BlockValidationReport>>;
+}
+
+/// A single retained blob, as returned by `reth_getBlobSidecars`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RethBlobSidecar {
+    /// Number of the block that included the blob.
+    #[serde(with = "alloy_serde::quantity")]
+    pub block_number: u64,
+    /// Hash of the transaction that carried the blob.
+    pub transaction_hash: TxHash,
+    /// The versioned hash of the blob.
+    pub versioned_hash: B256,
+    /// The KZG commitment of the blob.
+    pub commitment: FixedBytes<48>,
+    /// The KZG proofs of the blob: the blob proof for EIP-4844 sidecars and the cell proofs for
+    /// EIP-7594 sidecars.
+    pub proofs: Vec<FixedBytes<48>>,
+    /// The blob.
+    pub blob: Bytes,
+}
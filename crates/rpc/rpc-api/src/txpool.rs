@@ -1,7 +1,8 @@
 use alloy_json_rpc::RpcObject;
-use alloy_primitives::Address;
+use alloy_primitives::{Address, TxHash};
 use alloy_rpc_types_txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
 
 /// Txpool rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "txpool"))]
@@ -34,4 +35,123 @@ pub trait TxPoolApi<T: RpcObject> {
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
     #[method(name = "content")]
     async fn txpool_content(&self) -> RpcResult<TxpoolContent<T>>;
+
+    /// Returns the nonce gap analysis for the given sender: the nonces missing between the
+    /// on-chain nonce and the highest pooled nonce, and for each pooled transaction the subpool it
+    /// resides in and why it is not pending.
+    ///
+    /// This is a reth specific extension of the `txpool` namespace.
+    #[method(name = "nonceGaps")]
+    async fn txpool_nonce_gaps(&self, from: Address) -> RpcResult<TxpoolNonceGaps>;
+
+    /// Returns the most recent replacements of the transaction sent by `from` with the given
+    /// nonce, newest first.
+    ///
+    /// At most `limit` entries are returned, defaults to all tracked replacements.
+    ///
+    /// This is a reth specific extension of the `txpool` namespace.
+    #[method(name = "replacements")]
+    async fn txpool_replacements(
+        &self,
+        from: Address,
+        nonce: u64,
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<TxpoolReplacement>>;
+
+    /// Returns the first `limit` transactions in the order the payload builder would currently
+    /// pick them from the pool for the next block.
+    ///
+    /// This is a reth specific extension of the `txpool` namespace.
+    #[method(name = "bestTransactions")]
+    async fn txpool_best_transactions(&self, limit: usize)
+        -> RpcResult<Vec<TxpoolBestTransaction>>;
+}
+
+/// Response type of `txpool_nonceGaps`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolNonceGaps {
+    /// The sender of the transactions.
+    pub sender: Address,
+    /// The on-chain nonce of the sender as tracked by the pool, if known.
+    #[serde(default, with = "alloy_serde::quantity::opt")]
+    pub state_nonce: Option<u64>,
+    /// The first nonce that is missing, if the sender's transactions are nonce gapped.
+    #[serde(default, with = "alloy_serde::quantity::opt")]
+    pub first_missing_nonce: Option<u64>,
+    /// All missing nonce ranges, in ascending order.
+    pub gaps: Vec<TxpoolNonceGap>,
+    /// All pooled transactions of the sender, ordered by nonce.
+    pub transactions: Vec<TxpoolTransactionStatus>,
+}
+
+/// A range of missing nonces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolNonceGap {
+    /// First missing nonce.
+    #[serde(with = "alloy_serde::quantity")]
+    pub from: u64,
+    /// Last missing nonce, inclusive.
+    #[serde(with = "alloy_serde::quantity")]
+    pub to: u64,
+}
+
+/// The state of a single pooled transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolTransactionStatus {
+    /// Hash of the transaction.
+    pub hash: TxHash,
+    /// Nonce of the transaction.
+    #[serde(with = "alloy_serde::quantity")]
+    pub nonce: u64,
+    /// The subpool the transaction resides in: `pending`, `queued`, `basefee` or `blob`.
+    pub subpool: String,
+    /// Why the transaction is not pending, if it isn't.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Response type of `txpool_replacements`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolReplacement {
+    /// Hash of the transaction that was replaced.
+    pub replaced: TxHash,
+    /// Hash of the transaction that replaced it.
+    pub replacement: TxHash,
+    /// `maxFeePerGas` of the replaced transaction.
+    #[serde(with = "alloy_serde::quantity")]
+    pub replaced_max_fee_per_gas: u128,
+    /// `maxFeePerGas` of the replacement transaction.
+    #[serde(with = "alloy_serde::quantity")]
+    pub replacement_max_fee_per_gas: u128,
+    /// Change of the `maxFeePerGas`.
+    pub max_fee_per_gas_delta: i128,
+    /// Change of the `maxPriorityFeePerGas`, if both transactions have one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas_delta: Option<i128>,
+    /// Unix timestamp in seconds at which the replacement happened.
+    #[serde(with = "alloy_serde::quantity")]
+    pub timestamp: u64,
+}
+
+/// Response type of `txpool_bestTransactions`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolBestTransaction {
+    /// Hash of the transaction.
+    pub hash: TxHash,
+    /// Sender of the transaction.
+    pub sender: Address,
+    /// Nonce of the transaction.
+    #[serde(with = "alloy_serde::quantity")]
+    pub nonce: u64,
+    /// Gas limit of the transaction.
+    #[serde(with = "alloy_serde::quantity")]
+    pub gas_limit: u64,
+    /// The tip per gas the transaction pays at the pending block's base fee.
+    #[serde(default, with = "alloy_serde::quantity::opt")]
+    pub effective_tip_per_gas: Option<u128>,
 }
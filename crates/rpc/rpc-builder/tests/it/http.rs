@@ -145,6 +145,9 @@ where
     AdminApiClient::remove_peer(client, node.into()).await.unwrap();
     AdminApiClient::add_trusted_peer(client, node.into()).await.unwrap();
     AdminApiClient::remove_trusted_peer(client, node.into()).await.unwrap();
+    AdminApiClient::add_ban(client, "10.3.0.0/16".parse().unwrap(), Some(60)).await.unwrap();
+    AdminApiClient::remove_ban(client, node.id.into()).await.unwrap();
+    AdminApiClient::list_bans(client).await.unwrap();
     AdminApiClient::node_info(client).await.unwrap();
 }
 
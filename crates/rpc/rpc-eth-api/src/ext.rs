@@ -4,7 +4,7 @@ use alloy_primitives::{Bytes, B256};
 use alloy_rpc_types_eth::erc4337::TransactionConditional;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
-/// Extension trait for `eth_` namespace for L2s.
+/// Extension trait for `eth_` namespace for L2s and pools that accept conditional transactions.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "eth"))]
 #[cfg_attr(feature = "client", rpc(server, client, namespace = "eth"))]
 pub trait L2EthApiExt {
@@ -1,6 +1,7 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use alloy_genesis::ChainConfig;
+use alloy_primitives::B256;
 use alloy_rpc_types_admin::{
     EthInfo, EthPeerInfo, EthProtocolInfo, NodeInfo, PeerInfo, PeerNetworkInfo, PeerProtocolInfo,
     Ports, ProtocolInfo,
@@ -8,11 +9,13 @@ use alloy_rpc_types_admin::{
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_chainspec::{EthChainSpec, EthereumHardfork, EthereumHardforks, ForkCondition};
-use reth_network_api::{NetworkInfo, Peers};
+use reth_engine_primitives::ConsensusEngineHandle;
+use reth_network_api::{BanEntry, BanTarget, NetworkInfo, Peers};
 use reth_network_peers::{id2pk, AnyNode, NodeRecord};
 use reth_network_types::PeerKind;
-use reth_rpc_api::AdminApiServer;
-use reth_rpc_server_types::ToRpcResult;
+use reth_node_api::PayloadTypes;
+use reth_rpc_api::{AdminApiServer, AdminReorgApiServer};
+use reth_rpc_server_types::{result::invalid_params_rpc_err, ToRpcResult};
 use reth_transaction_pool::TransactionPool;
 use revm_primitives::keccak256;
 
@@ -69,6 +72,28 @@ where
         Ok(true)
     }
 
+    /// Handler for `admin_listBans`
+    async fn list_bans(&self) -> RpcResult<Vec<BanEntry>> {
+        self.network.get_bans().await.to_rpc_result()
+    }
+
+    /// Handler for `admin_addBan`
+    fn add_ban(&self, target: BanTarget, duration: Option<u64>) -> RpcResult<bool> {
+        if !target.is_bannable() {
+            return Err(invalid_params_rpc_err(format!(
+                "{target} is not a globally routable address and can't be banned"
+            )))
+        }
+        self.network.ban(target, duration.map(Duration::from_secs));
+        Ok(true)
+    }
+
+    /// Handler for `admin_removeBan`
+    fn remove_ban(&self, target: BanTarget) -> RpcResult<bool> {
+        self.network.unban(target);
+        Ok(true)
+    }
+
     /// Handler for `admin_peers`
     async fn peers(&self) -> RpcResult<Vec<PeerInfo>> {
         let peers = self.network.get_all_peers().await.to_rpc_result()?;
@@ -91,7 +116,15 @@ where
                 protocols: PeerProtocolInfo {
                     eth: Some(EthPeerInfo::Info(EthInfo { version: peer.status.version as u64 })),
                     snap: None,
-                    other: Default::default(),
+                    // the quality of the data the peer served, see `PeerScoreStats`, and the
+                    // bytes exchanged with the peer, see `PeerBandwidthStats`
+                    other: [
+                        ("score", serde_json::to_value(peer.score)),
+                        ("bandwidth", serde_json::to_value(peer.bandwidth)),
+                    ]
+                    .into_iter()
+                    .filter_map(|(key, value)| Some((key.to_string(), value.ok()?)))
+                    .collect(),
                 },
             })
         }
@@ -200,3 +233,30 @@ impl<N, ChainSpec, Pool> std::fmt::Debug for AdminApi<N, ChainSpec, Pool> {
         f.debug_struct("AdminApi").finish_non_exhaustive()
     }
 }
+
+/// `admin` API implementation for approving reorgs that exceed the maximum reorg depth of the
+/// engine.
+pub struct AdminReorgApi<Payload: PayloadTypes> {
+    /// Handle to the consensus engine
+    engine: ConsensusEngineHandle<Payload>,
+}
+
+impl<Payload: PayloadTypes> AdminReorgApi<Payload> {
+    /// Creates a new instance of `AdminReorgApi`.
+    pub const fn new(engine: ConsensusEngineHandle<Payload>) -> Self {
+        Self { engine }
+    }
+}
+
+impl<Payload: PayloadTypes> AdminReorgApiServer for AdminReorgApi<Payload> {
+    /// Handler for `admin_approveReorg`
+    fn approve_reorg(&self, head: B256) -> RpcResult<bool> {
+        Ok(self.engine.approve_reorg(head))
+    }
+}
+
+impl<Payload: PayloadTypes> std::fmt::Debug for AdminReorgApi<Payload> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminReorgApi").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,193 @@
+//! `eth_sendRawTransactionConditional` implementation for Ethereum pools.
+
+use alloy_consensus::BlockHeader;
+use alloy_eips::{BlockId, BlockNumberOrTag};
+use alloy_primitives::{Bytes, StorageKey, B256, U256};
+use alloy_rpc_types_eth::erc4337::{AccountStorage, TransactionConditional};
+use jsonrpsee::core::RpcResult;
+use jsonrpsee_types::error::{ErrorObject, INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE};
+use reth_rpc_eth_api::L2EthApiExtServer;
+use reth_rpc_eth_types::{utils::recover_raw_transaction, EthApiError};
+use reth_storage_api::{BlockReaderIdExt, StateProviderFactory};
+use reth_transaction_pool::{
+    conditional::MaybeConditionalTransaction, AddedTransactionOutcome, PoolTransaction,
+    TransactionOrigin, TransactionPool,
+};
+use std::{fmt::Display, sync::Arc};
+use tokio::sync::Semaphore;
+
+/// Maximum execution cost of a conditional.
+const MAX_CONDITIONAL_EXECUTION_COST: u64 = 5000;
+
+/// Maximum number of conditionals whose known accounts are validated concurrently.
+const MAX_CONCURRENT_CONDITIONAL_VALIDATIONS: usize = 3;
+
+/// `Eth` conditional transaction API implementation.
+///
+/// Transactions submitted through this API are added to the pool as
+/// [`TransactionOrigin::Private`], because peers would drop the attached conditional. The
+/// conditional is re-checked on every new canonical block and by the payload builder.
+#[derive(Clone, Debug)]
+pub struct EthConditional<Pool, Provider> {
+    inner: Arc<EthConditionalInner<Pool, Provider>>,
+}
+
+impl<Pool, Provider> EthConditional<Pool, Provider>
+where
+    Provider: BlockReaderIdExt + StateProviderFactory + Clone + 'static,
+{
+    /// Creates a new [`EthConditional`].
+    pub fn new(pool: Pool, provider: Provider) -> Self {
+        let inner = Arc::new(EthConditionalInner {
+            pool,
+            provider,
+            validation_semaphore: Semaphore::new(MAX_CONCURRENT_CONDITIONAL_VALIDATIONS),
+        });
+        Self { inner }
+    }
+
+    /// Validates the conditional's `known accounts` settings against the latest state.
+    async fn validate_known_accounts(
+        &self,
+        condition: &TransactionConditional,
+    ) -> Result<(), EthConditionalError> {
+        if condition.known_accounts.is_empty() {
+            return Ok(());
+        }
+
+        let _permit = self
+            .inner
+            .validation_semaphore
+            .acquire()
+            .await
+            .map_err(EthConditionalError::internal)?;
+
+        let state = self
+            .inner
+            .provider
+            .state_by_block_number_or_tag(BlockNumberOrTag::Latest)
+            .map_err(EthConditionalError::internal)?;
+
+        for (address, storage) in &condition.known_accounts {
+            match storage {
+                AccountStorage::Slots(slots) => {
+                    for (slot, expected_value) in slots {
+                        let current = state
+                            .storage(*address, StorageKey::from(*slot))
+                            .map_err(EthConditionalError::internal)?
+                            .unwrap_or_default();
+
+                        if current != U256::from_be_bytes(**expected_value) {
+                            return Err(EthConditionalError::StorageValueMismatch);
+                        }
+                    }
+                }
+                AccountStorage::RootHash(expected_root) => {
+                    let actual_root = state
+                        .storage_root(*address, Default::default())
+                        .map_err(EthConditionalError::internal)?;
+
+                    if *expected_root != actual_root {
+                        return Err(EthConditionalError::StorageRootMismatch);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<Pool, Provider> L2EthApiExtServer for EthConditional<Pool, Provider>
+where
+    Provider: BlockReaderIdExt + StateProviderFactory + Clone + 'static,
+    Pool: TransactionPool<Transaction: MaybeConditionalTransaction> + 'static,
+{
+    async fn send_raw_transaction_conditional(
+        &self,
+        bytes: Bytes,
+        condition: TransactionConditional,
+    ) -> RpcResult<B256> {
+        if condition.cost() > MAX_CONDITIONAL_EXECUTION_COST {
+            return Err(EthConditionalError::ConditionalCostExceeded.into());
+        }
+
+        let recovered_tx = recover_raw_transaction(&bytes)?;
+        let tx = <Pool as TransactionPool>::Transaction::from_pooled(recovered_tx);
+
+        let header_not_found =
+            || EthApiError::HeaderNotFound(BlockId::Number(BlockNumberOrTag::Latest));
+        let header = self
+            .inner
+            .provider
+            .latest_header()
+            .map_err(|_| header_not_found())?
+            .ok_or_else(header_not_found)?;
+
+        // Ensure that the condition can still be met by checking the max bounds
+        if condition.has_exceeded_block_number(header.number()) ||
+            condition.has_exceeded_timestamp(header.timestamp())
+        {
+            return Err(EthConditionalError::InvalidCondition.into());
+        }
+
+        self.validate_known_accounts(&condition).await?;
+
+        let AddedTransactionOutcome { hash, .. } = self
+            .inner
+            .pool
+            .add_transaction(TransactionOrigin::Private, tx.with_conditional(condition))
+            .await
+            .map_err(|e| EthApiError::PoolError(e.into()))?;
+
+        Ok(hash)
+    }
+}
+
+#[derive(Debug)]
+struct EthConditionalInner<Pool, Provider> {
+    /// The transaction pool of the node.
+    pool: Pool,
+    /// The provider type used to interact with the node.
+    provider: Provider,
+    /// The semaphore used to limit the number of concurrent conditional validations.
+    validation_semaphore: Semaphore,
+}
+
+/// [`EthConditional`] specific errors.
+#[derive(Debug, thiserror::Error)]
+pub enum EthConditionalError {
+    /// Transaction conditional cost exceeded maximum allowed
+    #[error("conditional cost exceeded maximum allowed")]
+    ConditionalCostExceeded,
+    /// Invalid conditional parameters
+    #[error("invalid conditional parameters")]
+    InvalidCondition,
+    /// Internal error
+    #[error("internal error: {0}")]
+    Internal(String),
+    /// Thrown if the conditional's storage value doesn't match the latest state's.
+    #[error("storage value mismatch")]
+    StorageValueMismatch,
+    /// Thrown when the conditional's storage root doesn't match the latest state's root.
+    #[error("storage root mismatch")]
+    StorageRootMismatch,
+}
+
+impl EthConditionalError {
+    /// Creates an internal error variant
+    pub fn internal<E: Display>(err: E) -> Self {
+        Self::Internal(err.to_string())
+    }
+}
+
+impl From<EthConditionalError> for ErrorObject<'static> {
+    fn from(err: EthConditionalError) -> Self {
+        let code = match &err {
+            EthConditionalError::Internal(_) => INTERNAL_ERROR_CODE,
+            _ => INVALID_PARAMS_CODE,
+        };
+        ErrorObject::owned(code, err.to_string(), None::<String>)
+    }
+}
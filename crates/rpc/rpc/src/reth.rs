@@ -1,18 +1,26 @@
-use std::{collections::HashMap, future::Future, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    sync::Arc,
+};
 
 use alloy_eips::BlockId;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, B256, U256};
 use async_trait::async_trait;
-use futures::StreamExt;
+use futures::{Stream, StreamExt};
 use jsonrpsee::{core::RpcResult, PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink};
 use jsonrpsee_types::ErrorObject;
+use parking_lot::RwLock;
 use reth_chain_state::{CanonStateNotificationStream, CanonStateSubscriptions};
+use reth_engine_primitives::{BlockValidationReport, ConsensusEngineEvent};
 use reth_errors::RethResult;
 use reth_primitives_traits::NodePrimitives;
-use reth_rpc_api::RethApiServer;
+use reth_rpc_api::{RethApiServer, RethBlobSidecar, RethValidationReportsApiServer};
 use reth_rpc_eth_types::{EthApiError, EthResult};
 use reth_rpc_server_types::result::internal_rpc_err;
-use reth_storage_api::{BlockReaderIdExt, ChangeSetReader, StateProviderFactory};
+use reth_storage_api::{
+    BlobSidecarReader, BlockReaderIdExt, ChangeSetReader, StateProviderFactory,
+};
 use reth_tasks::TaskSpawner;
 use tokio::sync::oneshot;
 
@@ -40,7 +48,8 @@ impl<Provider> RethApi<Provider> {
 
 impl<Provider> RethApi<Provider>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider:
+        BlockReaderIdExt + ChangeSetReader + BlobSidecarReader + StateProviderFactory + 'static,
 {
     /// Executes the future on a new blocking task.
     async fn on_blocking_task<C, F, R>(&self, c: C) -> EthResult<R>
@@ -88,6 +97,56 @@ where
         )?;
         Ok(hash_map)
     }
+
+    /// Returns the retained blob sidecars of a particular block, optionally filtered by versioned
+    /// hash.
+    pub async fn blob_sidecars(
+        &self,
+        block_id: BlockId,
+        versioned_hashes: Option<Vec<B256>>,
+    ) -> EthResult<Vec<RethBlobSidecar>> {
+        self.on_blocking_task(
+            |this| async move { this.try_blob_sidecars(block_id, versioned_hashes) },
+        )
+        .await
+    }
+
+    fn try_blob_sidecars(
+        &self,
+        block_id: BlockId,
+        versioned_hashes: Option<Vec<B256>>,
+    ) -> EthResult<Vec<RethBlobSidecar>> {
+        let Some(block_number) = self.provider().block_number_for_id(block_id)? else {
+            return Err(EthApiError::HeaderNotFound(block_id))
+        };
+
+        let sidecars = match versioned_hashes {
+            Some(versioned_hashes) => {
+                let mut sidecars = Vec::with_capacity(versioned_hashes.len());
+                for versioned_hash in versioned_hashes {
+                    if let Some(sidecar) =
+                        self.provider().blob_sidecar(block_number, versioned_hash)?
+                    {
+                        sidecars.push((versioned_hash, sidecar));
+                    }
+                }
+                sidecars
+            }
+            None => self.provider().blob_sidecars(block_number)?,
+        };
+
+        Ok(sidecars
+            .into_iter()
+            .map(|(versioned_hash, sidecar)| RethBlobSidecar {
+                block_number,
+                transaction_hash: sidecar.tx_hash,
+                versioned_hash,
+                commitment: sidecar.commitment,
+                proofs: sidecar.proofs,
+                blob: sidecar.blob,
+            })
+            .collect())
+    }
 }
 
 #[async_trait]
@@ -95,6 +154,7 @@ impl<Provider> RethApiServer for RethApi<Provider>
 where
     Provider: BlockReaderIdExt
         + ChangeSetReader
+        + BlobSidecarReader
         + StateProviderFactory
         + CanonStateSubscriptions
         + 'static,
@@ -107,6 +167,15 @@ where
         Ok(Self::balance_changes_in_block(self, block_id).await?)
     }
 
+    /// Handler for `reth_getBlobSidecars`
+    async fn reth_get_blob_sidecars(
+        &self,
+        block_id: BlockId,
+        versioned_hashes: Option<Vec<B256>>,
+    ) -> RpcResult<Vec<RethBlobSidecar>> {
+        Ok(Self::blob_sidecars(self, block_id, versioned_hashes).await?)
+    }
+
     /// Handler for `reth_subscribeChainNotifications`
     async fn reth_subscribe_chain_notifications(
         &self,
@@ -170,3 +239,94 @@ struct RethApiInner<Provider> {
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
 }
+
+/// `reth_getValidationReports` implementation.
+///
+/// Keeps the validation reports of the most recently validated blocks, as emitted by the consensus
+/// engine.
+#[derive(Debug, Clone)]
+pub struct ValidationReportsApi {
+    /// The retained reports, oldest first.
+    reports: Arc<RwLock<VecDeque<BlockValidationReport>>>,
+    /// The maximum number of retained reports.
+    capacity: usize,
+}
+
+impl ValidationReportsApi {
+    /// Creates a new instance that retains at most `capacity` reports.
+    pub fn new(capacity: usize) -> Self {
+        Self { reports: Arc::new(RwLock::new(VecDeque::with_capacity(capacity))), capacity }
+    }
+
+    /// Retains the given report, evicting the oldest report if the capacity is reached.
+    pub fn insert(&self, report: BlockValidationReport) {
+        if self.capacity == 0 {
+            return
+        }
+
+        let mut reports = self.reports.write();
+        if reports.len() == self.capacity {
+            reports.pop_front();
+        }
+        reports.push_back(report);
+    }
+
+    /// Returns the `count` most recent reports, or all retained reports if `count` is not set,
+    /// oldest first.
+    pub fn reports(&self, count: Option<usize>) -> Vec<BlockValidationReport> {
+        let reports = self.reports.read();
+        let skip = count.map_or(0, |count| reports.len().saturating_sub(count));
+        reports.iter().skip(skip).cloned().collect()
+    }
+
+    /// Spawns a task that retains the validation reports of the given consensus engine events.
+    pub fn spawn_listener<N, St>(&self, mut events: St, task_spawner: &dyn TaskSpawner)
+    where
+        N: NodePrimitives,
+        St: Stream<Item = ConsensusEngineEvent<N>> + Send + Unpin + 'static,
+    {
+        let this = self.clone();
+        task_spawner.spawn(Box::pin(async move {
+            while let Some(event) = events.next().await {
+                if let ConsensusEngineEvent::BlockValidated(report) = event {
+                    this.insert(*report);
+                }
+            }
+        }));
+    }
+}
+
+#[async_trait]
+impl RethValidationReportsApiServer for ValidationReportsApi {
+    /// Handler for `reth_getValidationReports`
+    async fn reth_get_validation_reports(
+        &self,
+        count: Option<usize>,
+    ) -> RpcResult<Vec<BlockValidationReport>> {
+        Ok(self.reports(count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(number: u64) -> BlockValidationReport {
+        BlockValidationReport { number, ..Default::default() }
+    }
+
+    #[test]
+    fn validation_reports_evict_oldest() {
+        let api = ValidationReportsApi::new(3);
+        for number in 1..=5 {
+            api.insert(report(number));
+        }
+
+        let numbers = |reports: Vec<BlockValidationReport>| {
+            reports.into_iter().map(|report| report.number).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(api.reports(None)), vec![3, 4, 5]);
+        assert_eq!(numbers(api.reports(Some(2))), vec![4, 5]);
+        assert_eq!(numbers(api.reports(Some(10))), vec![3, 4, 5]);
+    }
+}
@@ -1,5 +1,5 @@
 use core::fmt;
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, time::UNIX_EPOCH};
 
 use alloy_consensus::Transaction;
 use alloy_primitives::Address;
@@ -9,11 +9,14 @@ use alloy_rpc_types_txpool::{
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_primitives_traits::NodePrimitives;
-use reth_rpc_api::TxPoolApiServer;
+use reth_rpc_api::{
+    TxPoolApiServer, TxpoolBestTransaction, TxpoolNonceGap, TxpoolNonceGaps, TxpoolReplacement,
+    TxpoolTransactionStatus,
+};
 use reth_rpc_convert::{RpcConvert, RpcTypes};
 use reth_rpc_eth_api::RpcTransaction;
 use reth_transaction_pool::{
-    AllPoolTransactions, PoolConsensusTx, PoolTransaction, TransactionPool,
+    AllPoolTransactions, PoolConsensusTx, PoolTransaction, QueuedReason, SubPool, TransactionPool,
 };
 use tracing::trace;
 
@@ -147,6 +150,105 @@ where
         trace!(target: "rpc::eth", "Serving txpool_content");
         Ok(self.content().map_err(Into::into)?)
     }
+
+    /// Handler for `txpool_nonceGaps`
+    async fn txpool_nonce_gaps(&self, from: Address) -> RpcResult<TxpoolNonceGaps> {
+        trace!(target: "rpc::eth", ?from, "Serving txpool_nonceGaps");
+        let sender = self.pool.get_sender_transactions(from);
+
+        Ok(TxpoolNonceGaps {
+            sender: sender.sender,
+            state_nonce: sender.state_nonce,
+            first_missing_nonce: sender.first_missing_nonce(),
+            gaps: sender
+                .nonce_gaps
+                .iter()
+                .map(|gap| TxpoolNonceGap { from: gap.start, to: gap.end - 1 })
+                .collect(),
+            transactions: sender
+                .transactions
+                .into_iter()
+                .map(|tx| TxpoolTransactionStatus {
+                    hash: *tx.transaction.hash(),
+                    nonce: tx.transaction.nonce(),
+                    subpool: subpool_name(tx.subpool).to_string(),
+                    reason: tx.queued_reason.as_ref().map(|r| queued_reason_name(r).to_string()),
+                })
+                .collect(),
+        })
+    }
+
+    /// Handler for `txpool_replacements`
+    async fn txpool_replacements(
+        &self,
+        from: Address,
+        nonce: u64,
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<TxpoolReplacement>> {
+        trace!(target: "rpc::eth", ?from, nonce, ?limit, "Serving txpool_replacements");
+        Ok(self
+            .pool
+            .get_transaction_replacements(from, nonce, limit.unwrap_or(usize::MAX))
+            .into_iter()
+            .map(|replacement| TxpoolReplacement {
+                replaced: replacement.replaced,
+                replacement: replacement.replacement,
+                replaced_max_fee_per_gas: replacement.replaced_max_fee_per_gas,
+                replacement_max_fee_per_gas: replacement.replacement_max_fee_per_gas,
+                max_fee_per_gas_delta: replacement.max_fee_per_gas_delta(),
+                max_priority_fee_per_gas_delta: replacement.max_priority_fee_per_gas_delta(),
+                timestamp: replacement
+                    .timestamp
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            })
+            .collect())
+    }
+
+    /// Handler for `txpool_bestTransactions`
+    async fn txpool_best_transactions(
+        &self,
+        limit: usize,
+    ) -> RpcResult<Vec<TxpoolBestTransaction>> {
+        trace!(target: "rpc::eth", limit, "Serving txpool_bestTransactions");
+        let base_fee = self.pool.block_info().pending_basefee;
+
+        Ok(self
+            .pool
+            .best_transactions()
+            .take(limit)
+            .map(|tx| TxpoolBestTransaction {
+                hash: *tx.hash(),
+                sender: tx.sender(),
+                nonce: tx.nonce(),
+                gas_limit: tx.gas_limit(),
+                effective_tip_per_gas: tx.effective_tip_per_gas(base_fee),
+            })
+            .collect())
+    }
+}
+
+/// Returns the name of the subpool as exposed over RPC.
+const fn subpool_name(subpool: SubPool) -> &'static str {
+    match subpool {
+        SubPool::Pending => "pending",
+        SubPool::Queued => "queued",
+        SubPool::BaseFee => "basefee",
+        SubPool::Blob => "blob",
+    }
+}
+
+/// Returns the reason a transaction is not pending as exposed over RPC.
+const fn queued_reason_name(reason: &QueuedReason) -> &'static str {
+    match reason {
+        QueuedReason::NonceGap => "nonceGap",
+        QueuedReason::ParkedAncestors => "parkedAncestors",
+        QueuedReason::InsufficientBalance => "insufficientBalance",
+        QueuedReason::TooMuchGas => "tooMuchGas",
+        QueuedReason::InsufficientBaseFee => "insufficientBaseFee",
+        QueuedReason::InsufficientBlobFee => "insufficientBlobFee",
+    }
 }
 
 impl<Pool, Eth> fmt::Debug for TxPoolApi<Pool, Eth> {
@@ -1,7 +1,12 @@
 //! Block related models and types.
 
+use crate::{
+    impl_fixed_arbitrary,
+    table::{Decode, Encode},
+    DatabaseError,
+};
 use alloy_consensus::Header;
-use alloy_primitives::B256;
+use alloy_primitives::{BlockNumber, B256};
 use reth_codecs::{add_arbitrary_tests, Compact};
 use serde::{Deserialize, Serialize};
 
@@ -37,11 +42,78 @@ impl<H: Compact> Compact for StoredBlockOmmers<H> {
 /// Hash of the block header.
 pub type HeaderHash = B256;
 
+/// [`BlockNumber`] concatenated with the versioned hash of a blob.
+///
+/// Since it's used as a key, it isn't compressed when encoding it.
+#[derive(
+    Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Ord, PartialOrd, Hash,
+)]
+pub struct BlockNumberVersionedHash(pub (BlockNumber, B256));
+
+impl BlockNumberVersionedHash {
+    /// Returns the block number.
+    pub const fn block_number(&self) -> BlockNumber {
+        self.0 .0
+    }
+
+    /// Returns the versioned hash.
+    pub const fn versioned_hash(&self) -> B256 {
+        self.0 .1
+    }
+}
+
+impl From<(BlockNumber, B256)> for BlockNumberVersionedHash {
+    fn from(tpl: (BlockNumber, B256)) -> Self {
+        Self(tpl)
+    }
+}
+
+impl Encode for BlockNumberVersionedHash {
+    type Encoded = [u8; 40];
+
+    fn encode(self) -> Self::Encoded {
+        let mut buf = [0u8; 40];
+        buf[..8].copy_from_slice(&self.block_number().to_be_bytes());
+        buf[8..].copy_from_slice(self.versioned_hash().as_slice());
+        buf
+    }
+}
+
+impl Decode for BlockNumberVersionedHash {
+    fn decode(value: &[u8]) -> Result<Self, DatabaseError> {
+        let num = u64::from_be_bytes(
+            value.get(..8).and_then(|num| num.try_into().ok()).ok_or(DatabaseError::Decode)?,
+        );
+        let hash = value.get(8..40).map(B256::from_slice).ok_or(DatabaseError::Decode)?;
+        Ok(Self((num, hash)))
+    }
+}
+
+impl_fixed_arbitrary!((BlockNumberVersionedHash, 40));
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::table::{Compress, Decompress};
 
+    #[test]
+    fn test_block_number_versioned_hash() {
+        let num = 1u64;
+        let hash = B256::random();
+        let key = BlockNumberVersionedHash((num, hash));
+
+        let mut bytes = [0u8; 40];
+        bytes[..8].copy_from_slice(&num.to_be_bytes());
+        bytes[8..].copy_from_slice(hash.as_slice());
+
+        let encoded = Encode::encode(key);
+        assert_eq!(encoded, bytes);
+
+        let decoded: BlockNumberVersionedHash = Decode::decode(&encoded).unwrap();
+        assert_eq!(decoded, key);
+        assert!(BlockNumberVersionedHash::decode(&encoded[..39]).is_err());
+    }
+
     #[test]
     fn test_ommer() {
         let mut ommer = StoredBlockOmmers::default();
@@ -29,8 +29,8 @@ pub use blocks::*;
 pub use integer_list::IntegerList;
 pub use metadata::*;
 pub use reth_db_models::{
-    AccountBeforeTx, ClientVersion, StaticFileBlockWithdrawals, StoredBlockBodyIndices,
-    StoredBlockWithdrawals,
+    AccountBeforeTx, ClientVersion, StaticFileBlockWithdrawals, StoredBlobSidecar,
+    StoredBlockBodyIndices, StoredBlockWithdrawals,
 };
 pub use sharded_key::ShardedKey;
 
@@ -231,6 +231,7 @@ impl_compression_for_compact!(
     StoredBlockOmmers<H>,
     StoredBlockWithdrawals,
     StaticFileBlockWithdrawals,
+    StoredBlobSidecar,
     Bytecode,
     AccountBeforeTx,
     TransactionSigned,
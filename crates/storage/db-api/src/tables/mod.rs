@@ -19,10 +19,10 @@ pub use raw::{RawDupSort, RawKey, RawTable, RawValue, TableRawRow};
 use crate::{
     models::{
         accounts::BlockNumberAddress,
-        blocks::{HeaderHash, StoredBlockOmmers},
+        blocks::{BlockNumberVersionedHash, HeaderHash, StoredBlockOmmers},
         storage_sharded_key::StorageShardedKey,
         AccountBeforeTx, BlockNumberHashedAddress, ClientVersion, CompactU256, IntegerList,
-        ShardedKey, StoredBlockBodyIndices, StoredBlockWithdrawals,
+        ShardedKey, StoredBlobSidecar, StoredBlockBodyIndices, StoredBlockWithdrawals,
     },
     table::{Decode, DupSort, Encode, Table, TableInfo},
 };
@@ -547,6 +547,14 @@ tables! {
         type Key = String;
         type Value = Vec<u8>;
     }
+
+    /// Stores the blob sidecars of canonical blob transactions by block number and versioned hash.
+    ///
+    /// Only populated if blob retention is enabled, entries are removed by the pruner.
+    table BlobSidecars {
+        type Key = BlockNumberVersionedHash;
+        type Value = StoredBlobSidecar;
+    }
 }
 
 /// Keys for the `ChainState` table.
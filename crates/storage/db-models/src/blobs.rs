@@ -0,0 +1,25 @@
+use alloc::vec::Vec;
+use alloy_primitives::{Bytes, FixedBytes, TxHash};
+
+/// The storage representation of a single retained blob.
+///
+/// Blobs are stored per versioned hash, so a blob transaction with multiple blobs is stored as
+/// multiple entries that share the same transaction hash.
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[cfg_attr(any(test, feature = "reth-codec"), derive(reth_codecs::Compact))]
+#[cfg_attr(any(test, feature = "reth-codec"), reth_codecs::add_arbitrary_tests(compact))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StoredBlobSidecar {
+    /// Hash of the transaction that carried the blob.
+    pub tx_hash: TxHash,
+    /// The KZG commitment of the blob.
+    pub commitment: FixedBytes<48>,
+    /// The KZG proofs of the blob.
+    ///
+    /// This is the single blob proof for EIP-4844 sidecars and the cell proofs for EIP-7594
+    /// sidecars.
+    pub proofs: Vec<FixedBytes<48>>,
+    /// The blob.
+    pub blob: Bytes,
+}
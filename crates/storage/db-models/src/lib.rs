@@ -19,6 +19,10 @@ pub use accounts::AccountBeforeTx;
 pub mod blocks;
 pub use blocks::{StaticFileBlockWithdrawals, StoredBlockBodyIndices, StoredBlockWithdrawals};
 
+/// Blobs
+pub mod blobs;
+pub use blobs::StoredBlobSidecar;
+
 /// Client Version
 pub mod client_version;
 pub use client_version::ClientVersion;
@@ -17,13 +17,17 @@ use reth_chain_state::{
     MemoryOverlayStateProvider,
 };
 use reth_chainspec::ChainInfo;
-use reth_db_api::models::{AccountBeforeTx, BlockNumberAddress, StoredBlockBodyIndices};
+use reth_db_api::models::{
+    AccountBeforeTx, BlockNumberAddress, StoredBlobSidecar, StoredBlockBodyIndices,
+};
 use reth_execution_types::ExecutionOutcome;
 use reth_node_types::{BlockTy, HeaderTy, NodeTypesWithDB, ReceiptTy, TxTy};
 use reth_primitives_traits::{Account, RecoveredBlock, SealedHeader, StorageEntry};
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
-use reth_storage_api::{BlockBodyIndicesProvider, NodePrimitivesProvider, StorageChangeSetReader};
+use reth_storage_api::{
+    BlobSidecarReader, BlockBodyIndicesProvider, NodePrimitivesProvider, StorageChangeSetReader,
+};
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{updates::TrieUpdatesSorted, HashedPostState, KeccakKeyHasher};
 use revm_database::BundleState;
@@ -447,6 +451,21 @@ impl<N: ProviderNodeTypes> BlockBodyIndicesProvider for BlockchainProvider<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> BlobSidecarReader for BlockchainProvider<N> {
+    fn blob_sidecars(&self, number: BlockNumber) -> ProviderResult<Vec<(B256, StoredBlobSidecar)>> {
+        // retained blob sidecars are only stored in the database
+        self.database.blob_sidecars(number)
+    }
+
+    fn blob_sidecar(
+        &self,
+        number: BlockNumber,
+        versioned_hash: B256,
+    ) -> ProviderResult<Option<StoredBlobSidecar>> {
+        self.database.blob_sidecar(number, versioned_hash)
+    }
+}
+
 impl<N: ProviderNodeTypes> StageCheckpointReader for BlockchainProvider<N> {
     fn get_stage_checkpoint(&self, id: StageId) -> ProviderResult<Option<StageCheckpoint>> {
         self.consistent_provider()?.get_stage_checkpoint(id)
@@ -40,7 +40,8 @@ use reth_db_api::{
     database::Database,
     models::{
         sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
-        BlockNumberHashedAddress, ShardedKey, StorageSettings, StoredBlockBodyIndices,
+        BlockNumberHashedAddress, BlockNumberVersionedHash, ShardedKey, StorageSettings,
+        StoredBlobSidecar, StoredBlockBodyIndices,
     },
     table::Table,
     tables,
@@ -58,9 +59,9 @@ use reth_prune_types::{
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_static_file_types::StaticFileSegment;
 use reth_storage_api::{
-    BlockBodyIndicesProvider, BlockBodyReader, MetadataProvider, MetadataWriter,
-    NodePrimitivesProvider, StateProvider, StorageChangeSetReader, StorageSettingsCache,
-    TryIntoHistoricalStateProvider,
+    BlobSidecarReader, BlobSidecarWriter, BlockBodyIndicesProvider, BlockBodyReader,
+    MetadataProvider, MetadataWriter, NodePrimitivesProvider, StateProvider,
+    StorageChangeSetReader, StorageSettingsCache, TryIntoHistoricalStateProvider,
 };
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
@@ -3129,6 +3130,53 @@ impl<TX: DbTx + 'static, N: NodeTypes + 'static> DBProvider for DatabaseProvider
     }
 }
 
+impl<TX: DbTx, N: NodeTypes> BlobSidecarReader for DatabaseProvider<TX, N> {
+    fn blob_sidecars(&self, number: BlockNumber) -> ProviderResult<Vec<(B256, StoredBlobSidecar)>> {
+        let range = BlockNumberVersionedHash::from((number, B256::ZERO))..=
+            BlockNumberVersionedHash::from((number, B256::repeat_byte(0xff)));
+        self.tx
+            .cursor_read::<tables::BlobSidecars>()?
+            .walk_range(range)?
+            .map(|entry| {
+                entry.map(|(key, sidecar)| (key.versioned_hash(), sidecar)).map_err(Into::into)
+            })
+            .collect()
+    }
+
+    fn blob_sidecar(
+        &self,
+        number: BlockNumber,
+        versioned_hash: B256,
+    ) -> ProviderResult<Option<StoredBlobSidecar>> {
+        Ok(self.tx.get::<tables::BlobSidecars>((number, versioned_hash).into())?)
+    }
+}
+
+impl<TX: DbTxMut, N: NodeTypes> BlobSidecarWriter for DatabaseProvider<TX, N> {
+    fn insert_blob_sidecars(
+        &self,
+        number: BlockNumber,
+        sidecars: Vec<(B256, StoredBlobSidecar)>,
+    ) -> ProviderResult<()> {
+        for (versioned_hash, sidecar) in sidecars {
+            self.tx.put::<tables::BlobSidecars>((number, versioned_hash).into(), sidecar)?;
+        }
+        Ok(())
+    }
+
+    fn remove_blob_sidecars_from(&self, number: BlockNumber) -> ProviderResult<usize> {
+        let mut removed = 0;
+        let mut cursor = self.tx.cursor_write::<tables::BlobSidecars>()?;
+        let mut walker =
+            cursor.walk_range(BlockNumberVersionedHash::from((number, B256::ZERO))..)?;
+        while walker.next().transpose()?.is_some() {
+            walker.delete_current()?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+}
+
 impl<TX: DbTx, N: NodeTypes> MetadataProvider for DatabaseProvider<TX, N> {
     fn get_metadata(&self, key: &str) -> ProviderResult<Option<Vec<u8>>> {
         self.tx.get::<tables::Metadata>(key.to_string()).map_err(Into::into)
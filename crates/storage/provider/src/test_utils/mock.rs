@@ -21,7 +21,7 @@ use reth_chainspec::{ChainInfo, EthChainSpec};
 use reth_db::transaction::DbTx;
 use reth_db_api::{
     mock::{DatabaseMock, TxMock},
-    models::{AccountBeforeTx, StoredBlockBodyIndices},
+    models::{AccountBeforeTx, StoredBlobSidecar, StoredBlockBodyIndices},
 };
 use reth_ethereum_primitives::EthPrimitives;
 use reth_execution_types::ExecutionOutcome;
@@ -32,9 +32,9 @@ use reth_primitives_traits::{
 use reth_prune_types::{PruneCheckpoint, PruneModes, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_api::{
-    BlockBodyIndicesProvider, BytecodeReader, DBProvider, DatabaseProviderFactory,
-    HashedPostStateProvider, NodePrimitivesProvider, StageCheckpointReader, StateProofProvider,
-    StorageRootProvider, TrieReader,
+    BlobSidecarReader, BlockBodyIndicesProvider, BytecodeReader, DBProvider,
+    DatabaseProviderFactory, HashedPostStateProvider, NodePrimitivesProvider,
+    StageCheckpointReader, StateProofProvider, StorageRootProvider, TrieReader,
 };
 use reth_storage_errors::provider::{ConsistentViewError, ProviderError, ProviderResult};
 use reth_trie::{
@@ -991,6 +991,25 @@ impl<T: NodePrimitives, ChainSpec: Send + Sync> ChangeSetReader for MockEthProvi
     }
 }
 
+impl<T: NodePrimitives, ChainSpec: Send + Sync> BlobSidecarReader
+    for MockEthProvider<T, ChainSpec>
+{
+    fn blob_sidecars(
+        &self,
+        _number: BlockNumber,
+    ) -> ProviderResult<Vec<(B256, StoredBlobSidecar)>> {
+        Ok(Vec::default())
+    }
+
+    fn blob_sidecar(
+        &self,
+        _number: BlockNumber,
+        _versioned_hash: B256,
+    ) -> ProviderResult<Option<StoredBlobSidecar>> {
+        Ok(None)
+    }
+}
+
 impl<T: NodePrimitives, ChainSpec: Send + Sync> StateReader for MockEthProvider<T, ChainSpec> {
     type Receipt = T::Receipt;
 
@@ -1,9 +1,10 @@
 //! Helper provider traits to encapsulate all provider traits for simplicity.
 
 use crate::{
-    AccountReader, BlockReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader,
-    DatabaseProviderFactory, HashedPostStateProvider, PruneCheckpointReader, StageCheckpointReader,
-    StateProviderFactory, StateReader, StaticFileProviderFactory, TrieReader,
+    AccountReader, BlobSidecarReader, BlobSidecarWriter, BlockReader, BlockReaderIdExt,
+    ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory, HashedPostStateProvider,
+    PruneCheckpointReader, StageCheckpointReader, StateProviderFactory, StateReader,
+    StaticFileProviderFactory, TrieReader,
 };
 use reth_chain_state::{CanonStateSubscriptions, ForkChoiceSubscriptions};
 use reth_node_types::{BlockTy, HeaderTy, NodeTypesWithDB, ReceiptTy, TxTy};
@@ -15,6 +16,7 @@ pub trait FullProvider<N: NodeTypesWithDB>:
     DatabaseProviderFactory<
         DB = N::DB,
         Provider: BlockReader + TrieReader + StageCheckpointReader + PruneCheckpointReader,
+        ProviderRW: BlobSidecarWriter,
     > + NodePrimitivesProvider<Primitives = N::Primitives>
     + StaticFileProviderFactory<Primitives = N::Primitives>
     + BlockReaderIdExt<
@@ -28,6 +30,7 @@ pub trait FullProvider<N: NodeTypesWithDB>:
     + HashedPostStateProvider
     + ChainSpecProvider<ChainSpec = N::ChainSpec>
     + ChangeSetReader
+    + BlobSidecarReader
     + CanonStateSubscriptions
     + ForkChoiceSubscriptions<Header = HeaderTy<N>>
     + StageCheckpointReader
@@ -42,6 +45,7 @@ impl<T, N: NodeTypesWithDB> FullProvider<N> for T where
     T: DatabaseProviderFactory<
             DB = N::DB,
             Provider: BlockReader + TrieReader + StageCheckpointReader + PruneCheckpointReader,
+            ProviderRW: BlobSidecarWriter,
         > + NodePrimitivesProvider<Primitives = N::Primitives>
         + StaticFileProviderFactory<Primitives = N::Primitives>
         + BlockReaderIdExt<
@@ -55,6 +59,7 @@ impl<T, N: NodeTypesWithDB> FullProvider<N> for T where
         + HashedPostStateProvider
         + ChainSpecProvider<ChainSpec = N::ChainSpec>
         + ChangeSetReader
+        + BlobSidecarReader
         + CanonStateSubscriptions
         + ForkChoiceSubscriptions<Header = HeaderTy<N>>
         + StageCheckpointReader
@@ -0,0 +1,36 @@
+use alloc::vec::Vec;
+use alloy_primitives::{BlockNumber, B256};
+use reth_db_models::StoredBlobSidecar;
+use reth_storage_errors::provider::ProviderResult;
+
+/// Client trait for fetching retained blob sidecars.
+///
+/// Blob sidecars are only retained if blob retention is enabled, and only until they are pruned.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait BlobSidecarReader: Send + Sync {
+    /// Returns all retained blob sidecars of the given block with their versioned hashes, ordered
+    /// by versioned hash.
+    fn blob_sidecars(&self, number: BlockNumber) -> ProviderResult<Vec<(B256, StoredBlobSidecar)>>;
+
+    /// Returns the retained blob sidecar with the given versioned hash in the given block.
+    fn blob_sidecar(
+        &self,
+        number: BlockNumber,
+        versioned_hash: B256,
+    ) -> ProviderResult<Option<StoredBlobSidecar>>;
+}
+
+/// Blob sidecar writer
+pub trait BlobSidecarWriter: Send + Sync {
+    /// Inserts the blob sidecars of the given block, keyed by versioned hash.
+    fn insert_blob_sidecars(
+        &self,
+        number: BlockNumber,
+        sidecars: Vec<(B256, StoredBlobSidecar)>,
+    ) -> ProviderResult<()>;
+
+    /// Removes the blob sidecars of all blocks starting at the given block, inclusive.
+    ///
+    /// Returns the number of removed blob sidecars.
+    fn remove_blob_sidecars_from(&self, number: BlockNumber) -> ProviderResult<usize>;
+}
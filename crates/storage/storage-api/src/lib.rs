@@ -85,6 +85,9 @@ pub use primitives::*;
 mod block_indices;
 pub use block_indices::*;
 
+mod blob_sidecars;
+pub use blob_sidecars::*;
+
 mod block_writer;
 pub use block_writer::*;
 
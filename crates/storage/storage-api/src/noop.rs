@@ -1,8 +1,8 @@
 //! Various noop implementations for traits.
 
 use crate::{
-    AccountReader, BlockBodyIndicesProvider, BlockHashReader, BlockIdReader, BlockNumReader,
-    BlockReader, BlockReaderIdExt, BlockSource, BytecodeReader, ChangeSetReader,
+    AccountReader, BlobSidecarReader, BlockBodyIndicesProvider, BlockHashReader, BlockIdReader,
+    BlockNumReader, BlockReader, BlockReaderIdExt, BlockSource, BytecodeReader, ChangeSetReader,
     HashedPostStateProvider, HeaderProvider, NodePrimitivesProvider, PruneCheckpointReader,
     ReceiptProvider, ReceiptProviderIdExt, StageCheckpointReader, StateProofProvider,
     StateProvider, StateProviderBox, StateProviderFactory, StateReader, StateRootProvider,
@@ -25,7 +25,7 @@ use core::{
 use reth_chainspec::{ChainInfo, ChainSpecProvider, EthChainSpec, MAINNET};
 #[cfg(feature = "db-api")]
 use reth_db_api::mock::{DatabaseMock, TxMock};
-use reth_db_models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db_models::{AccountBeforeTx, StoredBlobSidecar, StoredBlockBodyIndices};
 use reth_ethereum_primitives::EthPrimitives;
 use reth_execution_types::ExecutionOutcome;
 use reth_primitives_traits::{Account, Bytecode, NodePrimitives, RecoveredBlock, SealedHeader};
@@ -612,6 +612,23 @@ impl<C: Send + Sync, N: Send + Sync> BlockBodyIndicesProvider for NoopProvider<C
     }
 }
 
+impl<C: Send + Sync, N: Send + Sync> BlobSidecarReader for NoopProvider<C, N> {
+    fn blob_sidecars(
+        &self,
+        _number: BlockNumber,
+    ) -> ProviderResult<Vec<(B256, StoredBlobSidecar)>> {
+        Ok(Vec::new())
+    }
+
+    fn blob_sidecar(
+        &self,
+        _number: BlockNumber,
+        _versioned_hash: B256,
+    ) -> ProviderResult<Option<StoredBlobSidecar>> {
+        Ok(None)
+    }
+}
+
 #[cfg(feature = "db-api")]
 impl<ChainSpec: Send + Sync, N: NodePrimitives> DBProvider for NoopProvider<ChainSpec, N> {
     type Tx = TxMock;
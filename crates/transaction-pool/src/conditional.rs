@@ -0,0 +1,91 @@
+//! Additional support for pooled transactions with [`TransactionConditional`]
+
+use crate::error::PoolTransactionError;
+use alloy_consensus::conditional::BlockConditionalAttributes;
+use alloy_rpc_types_eth::erc4337::TransactionConditional;
+use std::any::Any;
+
+/// Helper trait that allows attaching a [`TransactionConditional`].
+pub trait MaybeConditionalTransaction {
+    /// Attach a [`TransactionConditional`].
+    fn set_conditional(&mut self, conditional: TransactionConditional);
+
+    /// Get attached [`TransactionConditional`] if any.
+    fn conditional(&self) -> Option<&TransactionConditional>;
+
+    /// Check if the conditional has exceeded the block attributes.
+    fn has_exceeded_block_attributes(&self, block_attr: &BlockConditionalAttributes) -> bool {
+        self.conditional().map(|tc| tc.has_exceeded_block_attributes(block_attr)).unwrap_or(false)
+    }
+
+    /// Check if the block attributes are within the bounds of the conditional.
+    ///
+    /// Returns `true` if no conditional is attached.
+    fn matches_block_attributes(&self, block_attr: &BlockConditionalAttributes) -> bool {
+        self.conditional().map(|tc| tc.matches_block_attributes(block_attr)).unwrap_or(true)
+    }
+
+    /// Helper that sets the conditional and returns the instance again
+    fn with_conditional(mut self, conditional: TransactionConditional) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_conditional(conditional);
+        self
+    }
+}
+
+/// Error returned if a transaction's [`TransactionConditional`] is not met by a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("transaction conditional not met by block {number} with timestamp {timestamp}")]
+pub struct ConditionalNotMet {
+    /// Number of the block the conditional was checked against.
+    pub number: u64,
+    /// Timestamp of the block the conditional was checked against.
+    pub timestamp: u64,
+}
+
+impl From<&BlockConditionalAttributes> for ConditionalNotMet {
+    fn from(block_attr: &BlockConditionalAttributes) -> Self {
+        Self { number: block_attr.number, timestamp: block_attr.timestamp }
+    }
+}
+
+impl PoolTransactionError for ConditionalNotMet {
+    fn is_bad_transaction(&self) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TransactionGenerator;
+
+    #[test]
+    fn eth_pooled_transaction_conditional() {
+        let mut tx_gen = TransactionGenerator::new(rand::rng());
+        let block = |number, timestamp| BlockConditionalAttributes { number, timestamp };
+
+        let tx = tx_gen.gen_eip1559_pooled();
+        assert!(tx.conditional().is_none());
+        assert!(tx.matches_block_attributes(&block(1, 1)));
+        assert!(!tx.has_exceeded_block_attributes(&block(u64::MAX, u64::MAX)));
+
+        let tx = tx_gen.gen_eip1559_pooled().with_conditional(TransactionConditional {
+            block_number_min: Some(10),
+            block_number_max: Some(20),
+            timestamp_max: Some(1000),
+            ..Default::default()
+        });
+        assert!(!tx.matches_block_attributes(&block(9, 100)));
+        assert!(!tx.has_exceeded_block_attributes(&block(9, 100)));
+        assert!(tx.matches_block_attributes(&block(15, 100)));
+        assert!(tx.has_exceeded_block_attributes(&block(21, 100)));
+        assert!(tx.has_exceeded_block_attributes(&block(15, 1001)));
+    }
+}
@@ -284,8 +284,9 @@ pub use crate::{
     ordering::{CoinbaseTipOrdering, Priority, TransactionOrdering},
     pool::{
         blob_tx_priority, fee_delta, state::SubPool, AddedTransactionOutcome,
-        AllTransactionsEvents, FullTransactionEvent, NewTransactionEvent, TransactionEvent,
-        TransactionEvents, TransactionListenerKind,
+        AllTransactionsEvents, FullTransactionEvent, NewTransactionEvent, QueuedReason,
+        SenderPooledTransaction, SenderTransactions, TransactionEvent, TransactionEvents,
+        TransactionListenerKind, TransactionReplacement,
     },
     traits::*,
     validate::{
@@ -318,6 +319,7 @@ pub mod validate;
 
 pub mod batcher;
 pub mod blobstore;
+pub mod conditional;
 mod config;
 pub mod identifier;
 mod ordering;
@@ -722,6 +724,19 @@ where
         self.inner().get_pool_data().all().get(&transaction_id).map(|tx| tx.transaction.clone())
     }
 
+    fn get_sender_transactions(&self, sender: Address) -> SenderTransactions<Self::Transaction> {
+        self.pool.get_sender_transactions(sender)
+    }
+
+    fn get_transaction_replacements(
+        &self,
+        sender: Address,
+        nonce: u64,
+        limit: usize,
+    ) -> Vec<TransactionReplacement> {
+        self.pool.get_transaction_replacements(sender, nonce, limit)
+    }
+
     fn get_transactions_by_origin(
         &self,
         origin: TransactionOrigin,
@@ -2,13 +2,16 @@
 
 use crate::{
     blobstore::{BlobSidecarConverter, BlobStoreCanonTracker, BlobStoreUpdates},
+    conditional::MaybeConditionalTransaction,
     error::PoolError,
-    metrics::MaintainPoolMetrics,
+    metrics::{MaintainPoolConditionalMetrics, MaintainPoolMetrics},
     traits::{CanonicalStateUpdate, EthPoolTransaction, TransactionPool, TransactionPoolExt},
     AllPoolTransactions, BlobTransactionSidecarVariant, BlockInfo, PoolTransaction, PoolUpdateKind,
     TransactionOrigin,
 };
-use alloy_consensus::{transaction::TxHashRef, BlockHeader, Typed2718};
+use alloy_consensus::{
+    conditional::BlockConditionalAttributes, transaction::TxHashRef, BlockHeader, Typed2718,
+};
 use alloy_eips::{BlockNumberOrTag, Decodable2718, Encodable2718};
 use alloy_primitives::{Address, BlockHash, BlockNumber, Bytes};
 use alloy_rlp::Encodable;
@@ -583,6 +586,61 @@ pub async fn maintain_transaction_pool<N, Client, P, St, Tasks>(
     }
 }
 
+/// Returns a spawnable future for maintaining the state of the conditional txs in the transaction
+/// pool.
+pub fn maintain_transaction_pool_conditional_future<N, Pool, St>(
+    pool: Pool,
+    events: St,
+) -> BoxFuture<'static, ()>
+where
+    N: NodePrimitives,
+    Pool: TransactionPool + 'static,
+    Pool::Transaction: MaybeConditionalTransaction,
+    St: Stream<Item = CanonStateNotification<N>> + Send + Unpin + 'static,
+{
+    async move {
+        maintain_transaction_pool_conditional(pool, events).await;
+    }
+    .boxed()
+}
+
+/// Maintains the state of the conditional tx in the transaction pool by handling new blocks and
+/// reorgs.
+///
+/// This listens for new canonical blocks and removes all transactions whose
+/// [`TransactionConditional`](alloy_rpc_types_eth::erc4337::TransactionConditional) can no longer
+/// be satisfied by the new canonical tip. Only committed blocks are handled, transactions are
+/// evaluated against the tip of a reorg once the next block is committed.
+pub async fn maintain_transaction_pool_conditional<N, Pool, St>(pool: Pool, mut events: St)
+where
+    N: NodePrimitives,
+    Pool: TransactionPool,
+    Pool::Transaction: MaybeConditionalTransaction,
+    St: Stream<Item = CanonStateNotification<N>> + Send + Unpin + 'static,
+{
+    let metrics = MaintainPoolConditionalMetrics::default();
+    loop {
+        let Some(event) = events.next().await else { break };
+        if let CanonStateNotification::Commit { new } = event {
+            let block_attr = BlockConditionalAttributes {
+                number: new.tip().number(),
+                timestamp: new.tip().timestamp(),
+            };
+            let mut to_remove = Vec::new();
+            for tx in &pool.pooled_transactions() {
+                if tx.transaction.has_exceeded_block_attributes(&block_attr) {
+                    to_remove.push(*tx.hash());
+                }
+            }
+            if !to_remove.is_empty() {
+                let removed = pool.remove_transactions(to_remove);
+                trace!(target: "txpool", count = removed.len(), "removed conditional transactions");
+                metrics.inc_removed_tx_conditional(removed.len());
+            }
+        }
+    }
+}
+
 struct FinalizedBlockTracker {
     last_finalized_block: Option<BlockNumber>,
 }
@@ -845,13 +903,19 @@ pub async fn backup_local_transactions_task<P>(
 mod tests {
     use super::*;
     use crate::{
-        blobstore::InMemoryBlobStore, validate::EthTransactionValidatorBuilder,
+        blobstore::InMemoryBlobStore,
+        test_utils::{OkValidator, TransactionGenerator},
+        validate::EthTransactionValidatorBuilder,
         CoinbaseTipOrdering, EthPooledTransaction, Pool, TransactionOrigin,
     };
+    use alloy_consensus::Header;
     use alloy_eips::eip2718::Decodable2718;
-    use alloy_primitives::{hex, U256};
-    use reth_ethereum_primitives::PooledTransactionVariant;
+    use alloy_primitives::{hex, B256, U256};
+    use alloy_rpc_types_eth::erc4337::TransactionConditional;
+    use reth_ethereum_primitives::{EthPrimitives, PooledTransactionVariant};
+    use reth_execution_types::Chain;
     use reth_fs_util as fs;
+    use reth_primitives_traits::{RecoveredBlock, SealedBlock};
     use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
     use reth_tasks::TaskManager;
 
@@ -955,4 +1019,69 @@ mod tests {
         assert_eq!(tracker.update(None), None);
         assert_eq!(tracker.last_finalized_block, None);
     }
+
+    #[tokio::test]
+    async fn test_maintain_conditional_evicts_exceeded_transactions() {
+        let pool = Pool::new(
+            OkValidator::<EthPooledTransaction>::default(),
+            CoinbaseTipOrdering::default(),
+            InMemoryBlobStore::default(),
+            Default::default(),
+        );
+        let mut tx_gen = TransactionGenerator::new(rand::rng());
+        let mut add_transaction = async |conditional: Option<TransactionConditional>| {
+            let mut tx = tx_gen.gen_eip1559_pooled();
+            if let Some(conditional) = conditional {
+                tx.set_conditional(conditional);
+            }
+            pool.add_transaction(TransactionOrigin::External, tx).await.unwrap().hash
+        };
+
+        let plain = add_transaction(None).await;
+        let live = add_transaction(Some(TransactionConditional {
+            block_number_max: Some(20),
+            ..Default::default()
+        }))
+        .await;
+        let expired_number = add_transaction(Some(TransactionConditional {
+            block_number_max: Some(5),
+            ..Default::default()
+        }))
+        .await;
+        let expired_timestamp = add_transaction(Some(TransactionConditional {
+            timestamp_max: Some(50),
+            ..Default::default()
+        }))
+        .await;
+        assert_eq!(pool.len(), 4);
+
+        let tip = RecoveredBlock::new_sealed(
+            SealedBlock::from_sealed_parts(
+                SealedHeader::new(
+                    Header { number: 10, timestamp: 100, ..Default::default() },
+                    B256::random(),
+                ),
+                Default::default(),
+            ),
+            Default::default(),
+        );
+        let chain = Arc::new(Chain::<EthPrimitives>::new([tip], Default::default(), None));
+
+        // reorgs don't evict transactions
+        let events = futures::stream::iter([CanonStateNotification::Reorg {
+            old: Arc::new(Chain::default()),
+            new: chain.clone(),
+        }]);
+        maintain_transaction_pool_conditional(pool.clone(), events).await;
+        assert_eq!(pool.len(), 4);
+
+        let events = futures::stream::iter([CanonStateNotification::Commit { new: chain }]);
+        maintain_transaction_pool_conditional(pool.clone(), events).await;
+
+        assert_eq!(pool.len(), 2);
+        assert!(pool.contains(&plain));
+        assert!(pool.contains(&live));
+        assert!(!pool.contains(&expired_number));
+        assert!(!pool.contains(&expired_timestamp));
+    }
 }
@@ -115,6 +115,22 @@ impl MaintainPoolMetrics {
     }
 }
 
+/// Conditional transaction pool maintenance metrics
+#[derive(Metrics)]
+#[metrics(scope = "transaction_pool")]
+pub struct MaintainPoolConditionalMetrics {
+    /// Counter indicating the number of conditional transactions removed from
+    /// the pool because of exceeded block attributes.
+    pub(crate) removed_tx_conditional: Counter,
+}
+
+impl MaintainPoolConditionalMetrics {
+    #[inline]
+    pub(crate) fn inc_removed_tx_conditional(&self, count: usize) {
+        self.removed_tx_conditional.increment(count as u64);
+    }
+}
+
 /// All Transactions metrics
 #[derive(Metrics)]
 #[metrics(scope = "transaction_pool")]
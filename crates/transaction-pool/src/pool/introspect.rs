@@ -0,0 +1,207 @@
+//! Types for inspecting the internal state of the pool.
+//!
+//! These are not required for normal pool operation, but expose information such as nonce gaps
+//! and replacement history that is otherwise only visible inside [`TxPool`](super::txpool::TxPool).
+
+use crate::{
+    identifier::TransactionId,
+    pool::{state::SubPool, QueuedReason},
+    PoolTransaction, ValidPoolTransaction,
+};
+use alloy_primitives::{Address, TxHash};
+use schnellru::{ByLength, LruMap};
+use std::{collections::VecDeque, ops::Range, sync::Arc, time::SystemTime};
+
+/// Maximum number of `(sender, nonce)` pairs for which replacements are tracked.
+pub const MAX_TRACKED_REPLACEMENT_SLOTS: u32 = 10_000;
+
+/// Maximum number of replacements that are kept per `(sender, nonce)` pair.
+pub const MAX_REPLACEMENTS_PER_SLOT: usize = 16;
+
+/// All transactions of a single sender that are currently in the pool, together with the reason
+/// each of them resides in its subpool.
+#[derive(Debug, Clone)]
+pub struct SenderTransactions<T: PoolTransaction> {
+    /// The sender of the transactions.
+    pub sender: Address,
+    /// The on-chain nonce of the sender as currently tracked by the pool.
+    ///
+    /// This is `None` if the pool has no state for this sender.
+    pub state_nonce: Option<u64>,
+    /// All nonce ranges that are missing between the on-chain nonce and the highest nonce of the
+    /// sender in the pool, in ascending order.
+    pub nonce_gaps: Vec<Range<u64>>,
+    /// All pooled transactions of the sender, ordered by nonce.
+    pub transactions: Vec<SenderPooledTransaction<T>>,
+}
+
+impl<T: PoolTransaction> SenderTransactions<T> {
+    /// Returns the first nonce that is missing, if the sender's transactions are nonce gapped.
+    pub fn first_missing_nonce(&self) -> Option<u64> {
+        self.nonce_gaps.first().map(|gap| gap.start)
+    }
+
+    /// Computes the missing nonce ranges for the given ordered nonces, starting at the state nonce.
+    pub(crate) fn nonce_gaps(
+        state_nonce: Option<u64>,
+        nonces: impl IntoIterator<Item = u64>,
+    ) -> Vec<Range<u64>> {
+        let mut gaps = Vec::new();
+        let mut next_nonce = state_nonce;
+        for nonce in nonces {
+            if let Some(expected) = next_nonce &&
+                nonce > expected
+            {
+                gaps.push(expected..nonce);
+            }
+            next_nonce = nonce.checked_add(1);
+        }
+        gaps
+    }
+}
+
+/// A pooled transaction of a sender and the subpool it currently resides in.
+#[derive(Debug, Clone)]
+pub struct SenderPooledTransaction<T: PoolTransaction> {
+    /// The pooled transaction.
+    pub transaction: Arc<ValidPoolTransaction<T>>,
+    /// The subpool that currently contains the transaction.
+    pub subpool: SubPool,
+    /// Why the transaction is not pending, if it isn't.
+    pub queued_reason: Option<QueuedReason>,
+}
+
+/// A record of a transaction that was replaced by another transaction with the same sender and
+/// nonce.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionReplacement {
+    /// Hash of the transaction that was replaced.
+    pub replaced: TxHash,
+    /// Hash of the transaction that replaced it.
+    pub replacement: TxHash,
+    /// `max_fee_per_gas` of the replaced transaction.
+    pub replaced_max_fee_per_gas: u128,
+    /// `max_fee_per_gas` of the replacement transaction.
+    pub replacement_max_fee_per_gas: u128,
+    /// `max_priority_fee_per_gas` of the replaced transaction, if any.
+    pub replaced_max_priority_fee_per_gas: Option<u128>,
+    /// `max_priority_fee_per_gas` of the replacement transaction, if any.
+    pub replacement_max_priority_fee_per_gas: Option<u128>,
+    /// When the replacement happened.
+    pub timestamp: SystemTime,
+}
+
+impl TransactionReplacement {
+    /// Creates a new record for the given replaced and replacement transactions.
+    pub fn new<T: PoolTransaction>(
+        replaced: &ValidPoolTransaction<T>,
+        replacement: &ValidPoolTransaction<T>,
+    ) -> Self {
+        Self {
+            replaced: *replaced.hash(),
+            replacement: *replacement.hash(),
+            replaced_max_fee_per_gas: replaced.max_fee_per_gas(),
+            replacement_max_fee_per_gas: replacement.max_fee_per_gas(),
+            replaced_max_priority_fee_per_gas: replaced.transaction.max_priority_fee_per_gas(),
+            replacement_max_priority_fee_per_gas: replacement
+                .transaction
+                .max_priority_fee_per_gas(),
+            timestamp: SystemTime::now(),
+        }
+    }
+
+    /// Returns by how much the `max_fee_per_gas` was changed by the replacement.
+    pub const fn max_fee_per_gas_delta(&self) -> i128 {
+        self.replacement_max_fee_per_gas as i128 - self.replaced_max_fee_per_gas as i128
+    }
+
+    /// Returns by how much the `max_priority_fee_per_gas` was changed by the replacement.
+    ///
+    /// Returns `None` if either transaction has no priority fee.
+    pub fn max_priority_fee_per_gas_delta(&self) -> Option<i128> {
+        let replaced = self.replaced_max_priority_fee_per_gas?;
+        let replacement = self.replacement_max_priority_fee_per_gas?;
+        Some(replacement as i128 - replaced as i128)
+    }
+}
+
+/// Bounded history of replacements, keyed by [`TransactionId`].
+///
+/// The history is kept independently of the transactions themselves, so replacements remain
+/// available after the final transaction was mined or evicted, until the slot is pushed out by
+/// newer ones.
+#[derive(Debug)]
+pub(crate) struct ReplacementHistory {
+    replacements: LruMap<TransactionId, VecDeque<TransactionReplacement>, ByLength>,
+}
+
+impl ReplacementHistory {
+    /// Records a new replacement for the given transaction id.
+    pub(crate) fn insert(&mut self, id: TransactionId, replacement: TransactionReplacement) {
+        let Some(entries) = self.replacements.get_or_insert(id, VecDeque::new) else { return };
+        if entries.len() == MAX_REPLACEMENTS_PER_SLOT {
+            entries.pop_front();
+        }
+        entries.push_back(replacement);
+    }
+
+    /// Returns up to `limit` of the most recent replacements for the given transaction id, newest
+    /// first.
+    pub(crate) fn get(&self, id: &TransactionId, limit: usize) -> Vec<TransactionReplacement> {
+        self.replacements
+            .peek(id)
+            .map(|entries| entries.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for ReplacementHistory {
+    fn default() -> Self {
+        Self { replacements: LruMap::new(ByLength::new(MAX_TRACKED_REPLACEMENT_SLOTS)) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        identifier::SenderId,
+        test_utils::{MockTransaction, MockTransactionFactory},
+    };
+
+    #[test]
+    fn computes_nonce_gaps() {
+        let gaps = SenderTransactions::<MockTransaction>::nonce_gaps(Some(2), [4, 5, 8]);
+        assert_eq!(gaps, vec![2..4, 6..8]);
+
+        let gaps = SenderTransactions::<MockTransaction>::nonce_gaps(Some(4), [4, 5, 6]);
+        assert!(gaps.is_empty());
+
+        let gaps = SenderTransactions::<MockTransaction>::nonce_gaps(None, [4, 6]);
+        assert_eq!(gaps, vec![5..6]);
+
+        // no nonce can follow the maximum nonce
+        let gaps = SenderTransactions::<MockTransaction>::nonce_gaps(Some(u64::MAX), [u64::MAX]);
+        assert!(gaps.is_empty());
+    }
+
+    #[test]
+    fn bounded_replacement_history() {
+        let mut f = MockTransactionFactory::default();
+        let mut history = ReplacementHistory::default();
+        let id = TransactionId::new(SenderId::from(1), 0);
+
+        let mut prev = f.validated(MockTransaction::eip1559());
+        for _ in 0..MAX_REPLACEMENTS_PER_SLOT + 2 {
+            let next = f.validated(prev.transaction.clone().rng_hash().inc_price());
+            history.insert(id, TransactionReplacement::new(&prev, &next));
+            prev = next;
+        }
+
+        let all = history.get(&id, usize::MAX);
+        assert_eq!(all.len(), MAX_REPLACEMENTS_PER_SLOT);
+        assert_eq!(all[0].replacement, *prev.hash());
+        assert!(all.iter().all(|r| r.max_fee_per_gas_delta() > 0));
+        assert_eq!(history.get(&id, 3).len(), 3);
+    }
+}
@@ -104,6 +104,10 @@ mod events;
 pub use best::{BestTransactionFilter, BestTransactionsWithPrioritizedSenders};
 pub use blob::{blob_tx_priority, fee_delta, BlobOrd, BlobTransactions};
 pub use events::{FullTransactionEvent, NewTransactionEvent, TransactionEvent};
+pub use introspect::{
+    SenderPooledTransaction, SenderTransactions, TransactionReplacement, MAX_REPLACEMENTS_PER_SLOT,
+    MAX_TRACKED_REPLACEMENT_SLOTS,
+};
 pub use listener::{AllTransactionsEvents, TransactionEvents, TransactionListenerKind};
 pub use parked::{BasefeeOrd, ParkedOrd, ParkedPool, QueuedOrd};
 pub use pending::PendingPool;
@@ -111,6 +115,7 @@ use reth_primitives_traits::Block;
 
 mod best;
 mod blob;
+mod introspect;
 mod listener;
 mod parked;
 pub mod pending;
@@ -927,6 +932,24 @@ where
         )
     }
 
+    /// Returns all transactions of the address together with their subpool and nonce gaps.
+    pub fn get_sender_transactions(&self, sender: Address) -> SenderTransactions<T::Transaction> {
+        let sender_id = self.get_sender_id(sender);
+        self.get_pool_data().sender_transactions(sender_id, sender)
+    }
+
+    /// Returns up to `limit` of the most recent replacements of the address' transaction with the
+    /// given nonce, newest first.
+    pub fn get_transaction_replacements(
+        &self,
+        sender: Address,
+        nonce: u64,
+        limit: usize,
+    ) -> Vec<TransactionReplacement> {
+        let transaction_id = TransactionId::new(self.get_sender_id(sender), nonce);
+        self.get_pool_data().replacements(&transaction_id, limit)
+    }
+
     /// Returns the transaction given a [`TransactionId`]
     pub fn get_transaction_by_transaction_id(
         &self,
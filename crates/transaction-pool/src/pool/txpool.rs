@@ -11,6 +11,9 @@ use crate::{
     pool::{
         best::BestTransactions,
         blob::BlobTransactions,
+        introspect::{
+            ReplacementHistory, SenderPooledTransaction, SenderTransactions, TransactionReplacement,
+        },
         parked::{BasefeeOrd, ParkedPool, QueuedOrd},
         pending::PendingPool,
         state::{SubPool, TxState},
@@ -118,6 +121,8 @@ pub struct TxPool<T: TransactionOrdering> {
     metrics: TxPoolMetrics,
     /// The last update kind that was applied to the pool.
     latest_update_kind: Option<PoolUpdateKind>,
+    /// Recent replacements of transactions, tracked for introspection.
+    replacements: ReplacementHistory,
 }
 
 // === impl TxPool ===
@@ -138,6 +143,7 @@ impl<T: TransactionOrdering> TxPool<T> {
             config,
             metrics: Default::default(),
             latest_update_kind: None,
+            replacements: Default::default(),
         }
     }
 
@@ -184,6 +190,41 @@ impl<T: TransactionOrdering> TxPool<T> {
         last_consecutive_tx.map(|tx| Arc::clone(&tx.transaction))
     }
 
+    /// Returns all transactions of the given sender, including the subpool each of them resides
+    /// in and the nonce gaps relative to the sender's on-chain nonce.
+    pub(crate) fn sender_transactions(
+        &self,
+        sender_id: SenderId,
+        sender: Address,
+    ) -> SenderTransactions<T::Transaction> {
+        let state_nonce = self.sender_info.get(&sender_id).map(|info| info.state_nonce);
+        let transactions = self
+            .all()
+            .txs_iter(sender_id)
+            .map(|(_, tx)| SenderPooledTransaction {
+                transaction: Arc::clone(&tx.transaction),
+                subpool: tx.subpool,
+                queued_reason: tx.state.determine_queued_reason(tx.subpool),
+            })
+            .collect::<Vec<_>>();
+        let nonce_gaps = SenderTransactions::<T::Transaction>::nonce_gaps(
+            state_nonce,
+            transactions.iter().map(|tx| tx.transaction.nonce()),
+        );
+
+        SenderTransactions { sender, state_nonce, nonce_gaps, transactions }
+    }
+
+    /// Returns up to `limit` of the most recent replacements of the transaction with the given id,
+    /// newest first.
+    pub(crate) fn replacements(
+        &self,
+        id: &TransactionId,
+        limit: usize,
+    ) -> Vec<TransactionReplacement> {
+        self.replacements.get(id, limit)
+    }
+
     /// Returns access to the [`AllTransactions`] container.
     pub(crate) const fn all(&self) -> &AllTransactions<T::Transaction> {
         &self.all_transactions
@@ -761,6 +802,12 @@ impl<T: TransactionOrdering> TxPool<T> {
                 let UpdateOutcome { promoted, discarded } = self.process_updates(updates);
 
                 let replaced = replaced_tx.map(|(tx, _)| tx);
+                if let Some(replaced) = &replaced {
+                    self.replacements.insert(
+                        *transaction.id(),
+                        TransactionReplacement::new(replaced, &transaction),
+                    );
+                }
 
                 // This transaction was moved to the pending pool.
                 let res = if move_to.is_pending() {
@@ -2257,6 +2304,7 @@ impl SenderInfo {
 mod tests {
     use super::*;
     use crate::{
+        pool::QueuedReason,
         test_utils::{MockOrdering, MockTransaction, MockTransactionFactory, MockTransactionSet},
         traits::TransactionOrigin,
         SubPoolLimit,
@@ -2982,6 +3030,48 @@ mod tests {
         size.assert_invariants();
     }
 
+    #[test]
+    fn insert_replace_records_history() {
+        let on_chain_balance = U256::MAX;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::mock();
+
+        let tx = MockTransaction::eip1559().inc_price().inc_limit();
+        let first = f.validated(tx.clone());
+        pool.add_transaction(first.clone(), on_chain_balance, on_chain_nonce, None).unwrap();
+        let replacement = f.validated(tx.rng_hash().inc_price_by(100));
+        pool.add_transaction(replacement.clone(), on_chain_balance, on_chain_nonce, None).unwrap();
+
+        let history = pool.replacements(replacement.id(), 10);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].replaced, *first.hash());
+        assert_eq!(history[0].replacement, *replacement.hash());
+        assert_eq!(history[0].max_fee_per_gas_delta(), 100);
+    }
+
+    #[test]
+    fn sender_transactions_nonce_gaps() {
+        let on_chain_balance = U256::MAX;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::mock();
+
+        let tx = MockTransaction::eip1559().inc_price().inc_limit();
+        let first = f.validated(tx.clone());
+        let gapped = f.validated(tx.next().next());
+        pool.add_transaction(first.clone(), on_chain_balance, on_chain_nonce, None).unwrap();
+        pool.add_transaction(gapped.clone(), on_chain_balance, on_chain_nonce, None).unwrap();
+
+        let sender = pool.sender_transactions(first.sender_id(), first.sender());
+        assert_eq!(sender.state_nonce, Some(on_chain_nonce));
+        assert_eq!(sender.first_missing_nonce(), Some(1));
+        assert_eq!(sender.transactions.len(), 2);
+        assert_eq!(sender.transactions[0].subpool, SubPool::Pending);
+        assert_eq!(sender.transactions[1].subpool, SubPool::Queued);
+        assert_eq!(sender.transactions[1].queued_reason, Some(QueuedReason::NonceGap));
+    }
+
     #[test]
     fn insert_replace_underpriced() {
         let on_chain_balance = U256::ZERO;
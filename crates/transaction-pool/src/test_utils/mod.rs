@@ -14,6 +14,9 @@ mod pool;
 mod okvalidator;
 pub use okvalidator::*;
 
+mod replay;
+pub use replay::*;
+
 /// A [Pool] used for testing
 pub type TestPool =
     Pool<MockTransactionValidator<MockTransaction>, MockOrdering, InMemoryBlobStore>;
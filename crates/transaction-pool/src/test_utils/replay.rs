@@ -0,0 +1,613 @@
+//! Replay of recorded mempool traces.
+//!
+//! A trace is a stream of [`ReplayEvent`]s: raw transactions as they were received from peers and
+//! the transactions included by every new block. [`PoolReplay`] feeds a trace into a [`Pool`] that
+//! uses the [`ReplayValidator`] and the recorded timestamps as its clock, and collects a
+//! [`ReplayReport`] that can be used to compare [`PoolConfig`]s against realistic load.
+//!
+//! # Time
+//!
+//! The replay keeps its own clock that advances with the timestamps of the trace, the eviction of
+//! stale queued transactions configured by [`MaintainPoolConfig`] runs on this clock.
+//!
+//! The pool itself still stamps every transaction with the wall clock
+//! ([`ValidPoolTransaction::timestamp`](crate::ValidPoolTransaction::timestamp)), so any behaviour
+//! that reads that timestamp directly is not reproduced by a replay.
+
+use crate::{
+    blobstore::InMemoryBlobStore,
+    error::InvalidPoolTransactionError,
+    maintain::MaintainPoolConfig,
+    pool::FullTransactionEvent,
+    validate::{TransactionValidationOutcome, TransactionValidator, ValidTransaction},
+    AllTransactionsEvents, CanonicalStateUpdate, CoinbaseTipOrdering, EthPoolTransaction,
+    EthPooledTransaction, PeerId, Pool, PoolConfig, PoolSize, PoolTransaction, PoolUpdateKind,
+    TransactionOrigin, TransactionPool, TransactionPoolExt,
+};
+use alloy_consensus::{BlockBody, Header};
+use alloy_eips::eip2718::Decodable2718;
+use alloy_primitives::{
+    map::{AddressMap, HashMap},
+    Address, Bytes, TxHash, U256,
+};
+use parking_lot::RwLock;
+use reth_ethereum_primitives::{Block, PooledTransactionVariant};
+use reth_execution_types::ChangedAccount;
+use reth_primitives_traits::{
+    transaction::error::InvalidTransactionError, SealedBlock, SignedTransaction,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, io::BufRead, sync::Arc};
+
+/// The [`Pool`] type driven by [`PoolReplay`].
+pub type ReplayPool =
+    Pool<ReplayValidator, CoinbaseTipOrdering<EthPooledTransaction>, InMemoryBlobStore>;
+
+/// A single event of a recorded mempool trace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ReplayEvent {
+    /// A transaction was received.
+    Transaction {
+        /// When the transaction was received, in milliseconds.
+        timestamp_ms: u64,
+        /// The EIP-2718 encoded pooled transaction.
+        raw: Bytes,
+        /// The peer the transaction was received from, `None` if it was submitted locally.
+        peer: Option<PeerId>,
+    },
+    /// A new block was added to the canonical chain.
+    Block {
+        /// When the block was received, in milliseconds.
+        timestamp_ms: u64,
+        /// Number of the block.
+        number: u64,
+        /// Gas limit of the block.
+        gas_limit: u64,
+        /// Base fee of the block following this block.
+        pending_base_fee: u64,
+        /// Blob fee of the block following this block.
+        pending_blob_fee: Option<u128>,
+        /// Hashes of all transactions included in the block.
+        transactions: Vec<TxHash>,
+    },
+}
+
+impl ReplayEvent {
+    /// Returns the timestamp of the event, in milliseconds.
+    pub const fn timestamp_ms(&self) -> u64 {
+        match self {
+            Self::Transaction { timestamp_ms, .. } | Self::Block { timestamp_ms, .. } => {
+                *timestamp_ms
+            }
+        }
+    }
+}
+
+/// Reads a trace in JSON lines format, one [`ReplayEvent`] per line.
+///
+/// Empty lines are skipped and the returned events are sorted by timestamp.
+pub fn read_trace(reader: impl BufRead) -> Result<Vec<ReplayEvent>, ReplayTraceError> {
+    let mut events = Vec::new();
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue
+        }
+        let event = serde_json::from_str(&line)
+            .map_err(|err| ReplayTraceError::Json { line: idx + 1, err })?;
+        events.push(event);
+    }
+    events.sort_by_key(ReplayEvent::timestamp_ms);
+    Ok(events)
+}
+
+/// Errors that can occur while reading a trace.
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayTraceError {
+    /// Failed to read the trace.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A line of the trace is not a valid [`ReplayEvent`].
+    #[error("invalid event on line {line}: {err}")]
+    Json {
+        /// The line number of the invalid event, starting at 1.
+        line: usize,
+        /// The decoding error.
+        err: serde_json::Error,
+    },
+}
+
+/// A [`TransactionValidator`] for replays that accepts every transaction.
+///
+/// Recorded traces don't include account state, so the nonce of a sender that was not seen before
+/// is assumed to be the nonce of its first transaction. The nonce is then advanced as the sender's
+/// transactions are included in blocks. Balances are assumed to be unlimited.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayValidator {
+    nonces: Arc<RwLock<AddressMap<u64>>>,
+}
+
+impl ReplayValidator {
+    /// Returns the nonce currently assumed for the given sender.
+    pub fn state_nonce(&self, sender: &Address) -> Option<u64> {
+        self.nonces.read().get(sender).copied()
+    }
+
+    /// Advances the nonce of the given sender, if it is higher than the current one.
+    fn advance_nonce(&self, sender: Address, nonce: u64) {
+        let mut nonces = self.nonces.write();
+        let current = nonces.entry(sender).or_default();
+        *current = (*current).max(nonce);
+    }
+}
+
+impl TransactionValidator for ReplayValidator {
+    type Transaction = EthPooledTransaction;
+
+    async fn validate_transaction(
+        &self,
+        origin: TransactionOrigin,
+        mut transaction: Self::Transaction,
+    ) -> TransactionValidationOutcome<Self::Transaction> {
+        let nonce = transaction.nonce();
+        let state_nonce = *self.nonces.write().entry(transaction.sender()).or_insert(nonce);
+        if nonce < state_nonce {
+            return TransactionValidationOutcome::Invalid(
+                transaction,
+                InvalidPoolTransactionError::Consensus(
+                    InvalidTransactionError::NonceNotConsistent { tx: nonce, state: state_nonce },
+                ),
+            )
+        }
+        let maybe_sidecar = transaction.take_blob().maybe_sidecar().cloned();
+        TransactionValidationOutcome::Valid {
+            balance: U256::MAX,
+            state_nonce,
+            bytecode_hash: None,
+            transaction: ValidTransaction::new(transaction, maybe_sidecar),
+            propagate: origin != TransactionOrigin::Private,
+            authorities: None,
+        }
+    }
+}
+
+/// Statistics collected while replaying a trace.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayReport {
+    /// Number of received transactions.
+    pub received: usize,
+    /// Number of received transactions per peer.
+    pub received_per_peer: HashMap<PeerId, usize>,
+    /// Number of received transactions that could not be decoded or recovered.
+    pub undecodable: usize,
+    /// Number of received transactions that were already in the pool.
+    pub duplicates: usize,
+    /// Number of transactions that were added to the pool.
+    pub accepted: usize,
+    /// Number of transactions that were rejected by the pool.
+    pub rejected: usize,
+    /// Number of transactions that were discarded from the pool, e.g. to enforce its size limits.
+    pub discarded: usize,
+    /// Number of transactions that were replaced by another transaction.
+    pub replaced: usize,
+    /// Number of transactions that were removed from the pool because they became invalid.
+    pub invalidated: usize,
+    /// Number of queued transactions that were evicted because they exceeded
+    /// [`MaintainPoolConfig::max_tx_lifetime`], these are also counted as discarded.
+    pub stale: usize,
+    /// Number of included transactions that were received before their inclusion.
+    pub included: usize,
+    /// Number of included transactions that were never received.
+    pub included_unseen: usize,
+    /// Time between receiving and the inclusion of every included transaction, in milliseconds.
+    pub inclusion_latencies_ms: Vec<u64>,
+    /// Highest number of transactions in the pool.
+    pub peak_transactions: usize,
+    /// Highest reported size of all transactions in the pool, in bytes.
+    pub peak_size_bytes: usize,
+    /// Size of the pool after the last event.
+    pub final_size: PoolSize,
+}
+
+impl ReplayReport {
+    /// Returns the inclusion latency at the given percentile in `0.0..=1.0`, in milliseconds.
+    ///
+    /// Returns `None` if no received transaction was included.
+    pub fn inclusion_latency_percentile(&self, percentile: f64) -> Option<u64> {
+        if self.inclusion_latencies_ms.is_empty() {
+            return None
+        }
+        let mut latencies = self.inclusion_latencies_ms.clone();
+        latencies.sort_unstable();
+        let idx = ((latencies.len() - 1) as f64 * percentile.clamp(0.0, 1.0)).round() as usize;
+        Some(latencies[idx])
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let latency = |percentile| {
+            self.inclusion_latency_percentile(percentile)
+                .map_or_else(|| "n/a".to_string(), |latency| format!("{latency}ms"))
+        };
+        writeln!(
+            f,
+            "received: {} from {} peers, undecodable: {}, duplicates: {}",
+            self.received,
+            self.received_per_peer.len(),
+            self.undecodable,
+            self.duplicates
+        )?;
+        writeln!(f, "accepted: {}, rejected: {}", self.accepted, self.rejected)?;
+        writeln!(
+            f,
+            "discarded: {}, replaced: {}, invalidated: {}, stale: {}",
+            self.discarded, self.replaced, self.invalidated, self.stale
+        )?;
+        writeln!(
+            f,
+            "included: {}, included unseen: {}, latency p50: {}, p90: {}, p99: {}",
+            self.included,
+            self.included_unseen,
+            latency(0.5),
+            latency(0.9),
+            latency(0.99)
+        )?;
+        write!(
+            f,
+            "peak transactions: {}, peak size: {} bytes, final transactions: {}",
+            self.peak_transactions, self.peak_size_bytes, self.final_size.total
+        )
+    }
+}
+
+/// A transaction that was received during the replay and not yet included.
+#[derive(Debug, Clone, Copy)]
+struct Arrival {
+    timestamp_ms: u64,
+    sender: Address,
+    nonce: u64,
+}
+
+/// Drives a [`ReplayPool`] with the events of a recorded trace.
+///
+/// All latencies are measured in trace time, and stale queued transactions are evicted on trace
+/// time like [`maintain_transaction_pool`](crate::maintain::maintain_transaction_pool) does on the
+/// wall clock.
+#[derive(Debug)]
+pub struct PoolReplay {
+    pool: ReplayPool,
+    validator: ReplayValidator,
+    events: AllTransactionsEvents<EthPooledTransaction>,
+    arrivals: HashMap<TxHash, Arrival>,
+    maintain_config: MaintainPoolConfig,
+    /// The current trace time, in milliseconds.
+    now_ms: Option<u64>,
+    /// The trace time of the next stale transaction eviction, in milliseconds.
+    next_stale_eviction_ms: u64,
+    report: ReplayReport,
+}
+
+impl PoolReplay {
+    /// Creates a new replay for a pool with the given config.
+    pub fn new(config: PoolConfig) -> Self {
+        let validator = ReplayValidator::default();
+        let pool = Pool::new(
+            validator.clone(),
+            CoinbaseTipOrdering::default(),
+            InMemoryBlobStore::default(),
+            config,
+        );
+        let events = pool.all_transactions_event_listener();
+        Self {
+            pool,
+            validator,
+            events,
+            arrivals: Default::default(),
+            maintain_config: Default::default(),
+            now_ms: None,
+            next_stale_eviction_ms: 0,
+            report: Default::default(),
+        }
+    }
+
+    /// Sets the maintenance config that is applied on trace time.
+    pub const fn with_maintain_config(mut self, maintain_config: MaintainPoolConfig) -> Self {
+        self.maintain_config = maintain_config;
+        self
+    }
+
+    /// Returns the pool that is driven by this replay.
+    pub const fn pool(&self) -> &ReplayPool {
+        &self.pool
+    }
+
+    /// Replays all given events and returns the report.
+    pub async fn replay(mut self, events: impl IntoIterator<Item = ReplayEvent>) -> ReplayReport {
+        for event in events {
+            self.apply(event).await;
+        }
+        self.finish()
+    }
+
+    /// Applies a single event to the pool.
+    pub async fn apply(&mut self, event: ReplayEvent) {
+        self.advance_clock(event.timestamp_ms());
+        match event {
+            ReplayEvent::Transaction { timestamp_ms, raw, peer } => {
+                self.on_transaction(timestamp_ms, &raw, peer).await
+            }
+            ReplayEvent::Block {
+                timestamp_ms,
+                number,
+                gas_limit,
+                pending_base_fee,
+                pending_blob_fee,
+                transactions,
+            } => self.on_block(
+                timestamp_ms,
+                number,
+                gas_limit,
+                pending_base_fee,
+                pending_blob_fee,
+                transactions,
+            ),
+        }
+        self.drain_events();
+        let size = self.pool.pool_size();
+        self.report.peak_transactions = self.report.peak_transactions.max(size.total);
+        self.report.peak_size_bytes = self
+            .report
+            .peak_size_bytes
+            .max(size.pending_size + size.basefee_size + size.queued_size + size.blob_size);
+    }
+
+    /// Returns the report of all events applied so far.
+    pub fn finish(mut self) -> ReplayReport {
+        self.drain_events();
+        self.report.final_size = self.pool.pool_size();
+        self.report
+    }
+
+    async fn on_transaction(&mut self, timestamp_ms: u64, raw: &[u8], peer: Option<PeerId>) {
+        self.report.received += 1;
+        if let Some(peer) = peer {
+            *self.report.received_per_peer.entry(peer).or_default() += 1;
+        }
+
+        let Some(recovered) = PooledTransactionVariant::decode_2718(&mut &raw[..])
+            .ok()
+            .and_then(|tx| tx.try_into_recovered().ok())
+        else {
+            self.report.undecodable += 1;
+            return
+        };
+        let transaction = EthPooledTransaction::from_pooled(recovered);
+        let hash = *transaction.hash();
+        if self.pool.get(&hash).is_some() {
+            self.report.duplicates += 1;
+            return
+        }
+
+        let arrival =
+            Arrival { timestamp_ms, sender: transaction.sender(), nonce: transaction.nonce() };
+        let origin =
+            if peer.is_some() { TransactionOrigin::External } else { TransactionOrigin::Local };
+        match self.pool.add_transaction(origin, transaction).await {
+            Ok(_) => {
+                self.report.accepted += 1;
+                self.arrivals.entry(hash).or_insert(arrival);
+            }
+            Err(_) => self.report.rejected += 1,
+        }
+    }
+
+    fn on_block(
+        &mut self,
+        timestamp_ms: u64,
+        number: u64,
+        gas_limit: u64,
+        pending_base_fee: u64,
+        pending_blob_fee: Option<u128>,
+        transactions: Vec<TxHash>,
+    ) {
+        let mut changed_accounts = AddressMap::<u64>::default();
+        for hash in &transactions {
+            let Some(arrival) = self.arrivals.remove(hash) else {
+                self.report.included_unseen += 1;
+                continue
+            };
+            self.report.included += 1;
+            self.report
+                .inclusion_latencies_ms
+                .push(timestamp_ms.saturating_sub(arrival.timestamp_ms));
+            let nonce = changed_accounts.entry(arrival.sender).or_default();
+            *nonce = (*nonce).max(arrival.nonce + 1);
+        }
+
+        for (sender, nonce) in &changed_accounts {
+            self.validator.advance_nonce(*sender, *nonce);
+        }
+
+        let header =
+            Header { number, gas_limit, timestamp: timestamp_ms / 1000, ..Default::default() };
+        let block = SealedBlock::<Block>::seal_parts(header, BlockBody::default());
+        self.pool.on_canonical_state_change(CanonicalStateUpdate {
+            new_tip: &block,
+            pending_block_base_fee: pending_base_fee,
+            pending_block_blob_fee: pending_blob_fee,
+            changed_accounts: changed_accounts
+                .into_iter()
+                .map(|(address, nonce)| ChangedAccount { address, nonce, balance: U256::MAX })
+                .collect(),
+            mined_transactions: transactions,
+            update_kind: PoolUpdateKind::Commit,
+        });
+    }
+
+    /// Advances the trace clock to the given timestamp and evicts stale transactions on every
+    /// elapsed [`MaintainPoolConfig::max_tx_lifetime`] interval.
+    fn advance_clock(&mut self, timestamp_ms: u64) {
+        let interval_ms = self.maintain_config.max_tx_lifetime.as_millis() as u64;
+        let now_ms = match self.now_ms {
+            Some(now_ms) => now_ms.max(timestamp_ms),
+            None => {
+                self.next_stale_eviction_ms = timestamp_ms.saturating_add(interval_ms);
+                timestamp_ms
+            }
+        };
+        self.now_ms = Some(now_ms);
+
+        if interval_ms == 0 || now_ms < self.next_stale_eviction_ms {
+            return
+        }
+        while self.next_stale_eviction_ms <= now_ms {
+            self.next_stale_eviction_ms += interval_ms;
+        }
+
+        let stale = self
+            .pool
+            .queued_transactions()
+            .into_iter()
+            .filter(|tx| {
+                (tx.origin.is_external() || self.maintain_config.no_local_exemptions) &&
+                    self.arrivals.get(tx.hash()).is_some_and(|arrival| {
+                        now_ms.saturating_sub(arrival.timestamp_ms) > interval_ms
+                    })
+            })
+            .map(|tx| *tx.hash())
+            .collect::<Vec<_>>();
+        for hash in &stale {
+            self.arrivals.remove(hash);
+        }
+        self.report.stale += self.pool.remove_transactions(stale).len();
+    }
+
+    fn drain_events(&mut self) {
+        while let Ok(event) = self.events.events.try_recv() {
+            match event {
+                FullTransactionEvent::Discarded(_) => self.report.discarded += 1,
+                FullTransactionEvent::Replaced { .. } => self.report.replaced += 1,
+                FullTransactionEvent::Invalid(_) => self.report.invalidated += 1,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TransactionBuilder;
+    use alloy_eips::{eip1559::MIN_PROTOCOL_BASE_FEE, eip2718::Encodable2718};
+    use alloy_primitives::B256;
+    use std::time::Duration;
+
+    fn raw_transaction(signer: B256, nonce: u64) -> (TxHash, Bytes) {
+        let tx = TransactionBuilder::default()
+            .signer(signer)
+            .nonce(nonce)
+            .gas_limit(21_000)
+            .max_fee_per_gas(MIN_PROTOCOL_BASE_FEE as u128 * 2)
+            .max_priority_fee_per_gas(1)
+            .into_eip1559();
+        (*tx.tx_hash(), tx.encoded_2718().into())
+    }
+
+    #[test]
+    fn parse_trace() {
+        let (_, raw) = raw_transaction(B256::random(), 0);
+        let events = vec![
+            ReplayEvent::Block {
+                timestamp_ms: 2_000,
+                number: 1,
+                gas_limit: 30_000_000,
+                pending_base_fee: MIN_PROTOCOL_BASE_FEE,
+                pending_blob_fee: None,
+                transactions: vec![],
+            },
+            ReplayEvent::Transaction { timestamp_ms: 1_000, raw, peer: Some(PeerId::random()) },
+        ];
+        let trace = events
+            .iter()
+            .map(|event| serde_json::to_string(event).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let parsed = read_trace(trace.as_bytes()).unwrap();
+        assert_eq!(parsed, events.into_iter().rev().collect::<Vec<_>>());
+        assert!(read_trace("{}".as_bytes()).is_err());
+    }
+
+    #[tokio::test]
+    async fn replay_trace() {
+        let signer = B256::random();
+        let peer = PeerId::random();
+        let (hash0, raw0) = raw_transaction(signer, 5);
+        let (hash1, raw1) = raw_transaction(signer, 6);
+        let (_, raw2) = raw_transaction(signer, 7);
+
+        let block = |timestamp_ms, number, transactions| ReplayEvent::Block {
+            timestamp_ms,
+            number,
+            gas_limit: 30_000_000,
+            pending_base_fee: MIN_PROTOCOL_BASE_FEE,
+            pending_blob_fee: None,
+            transactions,
+        };
+        let events = vec![
+            ReplayEvent::Transaction { timestamp_ms: 0, raw: raw0.clone(), peer: Some(peer) },
+            ReplayEvent::Transaction { timestamp_ms: 500, raw: raw0, peer: Some(peer) },
+            ReplayEvent::Transaction { timestamp_ms: 1_000, raw: raw1, peer: None },
+            ReplayEvent::Transaction {
+                timestamp_ms: 1_500,
+                raw: Bytes::from_static(&[1]),
+                peer: None,
+            },
+            block(12_000, 1, vec![hash0, hash1, TxHash::random()]),
+            ReplayEvent::Transaction { timestamp_ms: 13_000, raw: raw2, peer: Some(peer) },
+        ];
+
+        let report = PoolReplay::new(PoolConfig::default()).replay(events).await;
+        assert_eq!(report.received, 5);
+        assert_eq!(report.received_per_peer[&peer], 3);
+        assert_eq!(report.undecodable, 1);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(report.accepted, 3);
+        assert_eq!(report.included, 2);
+        assert_eq!(report.included_unseen, 1);
+        assert_eq!(report.inclusion_latencies_ms, vec![12_000, 11_000]);
+        assert_eq!(report.inclusion_latency_percentile(0.0), Some(11_000));
+        assert_eq!(report.inclusion_latency_percentile(1.0), Some(12_000));
+        assert_eq!(report.peak_transactions, 2);
+        assert_eq!(report.final_size.pending, 1);
+    }
+
+    #[tokio::test]
+    async fn replay_evicts_stale_transactions_on_trace_time() {
+        let signer = B256::random();
+        let peer = Some(PeerId::random());
+        let (pending, raw_pending) = raw_transaction(signer, 5);
+        // nonce gap, so this transaction is queued
+        let (queued, raw_queued) = raw_transaction(signer, 7);
+        let (_, raw_late) = raw_transaction(B256::random(), 0);
+
+        let maintain_config =
+            MaintainPoolConfig { max_tx_lifetime: Duration::from_secs(10), ..Default::default() };
+        let mut replay =
+            PoolReplay::new(PoolConfig::default()).with_maintain_config(maintain_config);
+        replay.apply(ReplayEvent::Transaction { timestamp_ms: 0, raw: raw_pending, peer }).await;
+        replay.apply(ReplayEvent::Transaction { timestamp_ms: 0, raw: raw_queued, peer }).await;
+        assert!(replay.pool().contains(&queued));
+
+        // the queued transaction is evicted once it exceeds its lifetime in trace time
+        replay.apply(ReplayEvent::Transaction { timestamp_ms: 25_000, raw: raw_late, peer }).await;
+        assert!(replay.pool().contains(&pending));
+        assert!(!replay.pool().contains(&queued));
+
+        let report = replay.finish();
+        assert_eq!(report.stale, 1);
+        assert_eq!(report.discarded, 1);
+    }
+}
@@ -52,10 +52,11 @@
 
 use crate::{
     blobstore::BlobStoreError,
+    conditional::MaybeConditionalTransaction,
     error::{InvalidPoolTransactionError, PoolError, PoolResult},
     pool::{
-        state::SubPool, BestTransactionFilter, NewTransactionEvent, TransactionEvents,
-        TransactionListenerKind,
+        state::SubPool, BestTransactionFilter, NewTransactionEvent, SenderTransactions,
+        TransactionEvents, TransactionListenerKind, TransactionReplacement,
     },
     validate::ValidPoolTransaction,
     AddedTransactionOutcome, AllTransactionsEvents,
@@ -71,6 +72,7 @@ use alloy_eips::{
     eip7702::SignedAuthorization,
 };
 use alloy_primitives::{Address, Bytes, TxHash, TxKind, B256, U256};
+use alloy_rpc_types_eth::erc4337::TransactionConditional;
 use futures_util::{ready, Stream};
 use reth_eth_wire_types::HandleMempoolData;
 use reth_ethereum_primitives::{PooledTransactionVariant, TransactionSigned};
@@ -550,6 +552,35 @@ pub trait TransactionPool: Clone + Debug + Send + Sync {
         nonce: u64,
     ) -> Option<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Returns all transactions sent by a given user, together with the subpool each transaction
+    /// currently resides in, the reason it is not pending and the nonce gaps relative to the
+    /// sender's on-chain nonce.
+    ///
+    /// By default, this returns no transactions.
+    fn get_sender_transactions(&self, sender: Address) -> SenderTransactions<Self::Transaction> {
+        SenderTransactions {
+            sender,
+            state_nonce: None,
+            nonce_gaps: Vec::new(),
+            transactions: Vec::new(),
+        }
+    }
+
+    /// Returns up to `limit` of the most recent replacements of the transaction sent by the given
+    /// user with the given nonce, newest first.
+    ///
+    /// Replacements are tracked in a bounded history and may outlive the transactions themselves.
+    ///
+    /// By default, this returns no replacements.
+    fn get_transaction_replacements(
+        &self,
+        _sender: Address,
+        _nonce: u64,
+        _limit: usize,
+    ) -> Vec<TransactionReplacement> {
+        Vec::new()
+    }
+
     /// Returns all transactions that where submitted with the given [`TransactionOrigin`]
     fn get_transactions_by_origin(
         &self,
@@ -1270,6 +1301,7 @@ pub trait EthPoolTransaction: PoolTransaction {
 /// - `cost`: Pre-calculated max cost (gas * price + value + blob costs)
 /// - `encoded_length`: Cached RLP encoding length for size limits
 /// - `blob_sidecar`: Blob data state (None/Missing/Present)
+/// - `conditional`: Optional [`TransactionConditional`] the transaction was submitted with
 ///
 /// This avoids recalculating these values repeatedly during pool operations.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1289,6 +1321,9 @@ pub struct EthPooledTransaction<T = TransactionSigned> {
 
     /// The blob side car for this transaction
     pub blob_sidecar: EthBlobTransactionSidecar,
+
+    /// Optional conditional attached to this transaction.
+    pub conditional: Option<Box<TransactionConditional>>,
 }
 
 impl<T: SignedTransaction> EthPooledTransaction<T> {
@@ -1317,7 +1352,7 @@ impl<T: SignedTransaction> EthPooledTransaction<T> {
             blob_sidecar = EthBlobTransactionSidecar::Missing;
         }
 
-        Self { transaction, cost, encoded_length, blob_sidecar }
+        Self { transaction, cost, encoded_length, blob_sidecar, conditional: None }
     }
 
     /// Return the reference to the underlying transaction.
@@ -1326,6 +1361,16 @@ impl<T: SignedTransaction> EthPooledTransaction<T> {
     }
 }
 
+impl<T> MaybeConditionalTransaction for EthPooledTransaction<T> {
+    fn set_conditional(&mut self, conditional: TransactionConditional) {
+        self.conditional = Some(Box::new(conditional))
+    }
+
+    fn conditional(&self) -> Option<&TransactionConditional> {
+        self.conditional.as_deref()
+    }
+}
+
 impl PoolTransaction for EthPooledTransaction {
     type TryFromConsensusError = ValueError<TransactionSigned>;
 
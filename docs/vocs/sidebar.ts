@@ -479,20 +479,8 @@ export const sidebar: SidebarItem[] = [
                         collapsed: true,
                         items: [
                             {
-                                text: "reth debug execution",
-                                link: "/cli/reth/debug/execution"
-                            },
-                            {
-                                text: "reth debug merkle",
-                                link: "/cli/reth/debug/merkle"
-                            },
-                            {
-                                text: "reth debug in-memory-merkle",
-                                link: "/cli/reth/debug/in-memory-merkle"
-                            },
-                            {
-                                text: "reth debug build-block",
-                                link: "/cli/reth/debug/build-block"
+                                text: "reth debug replay-engine",
+                                link: "/cli/reth/debug/replay-engine"
                             }
                         ]
                     },
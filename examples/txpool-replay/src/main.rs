@@ -0,0 +1,52 @@
+//! Example of how to replay a recorded mempool trace against a transaction pool configuration.
+//!
+//! Run with
+//!
+//! ```sh
+//! cargo run --release -p example-txpool-replay -- --trace trace.jsonl --txpool.pending-max-count 5000
+//! ```
+//!
+//! The trace is read in JSON lines format, one `ReplayEvent` per line. The pool is configured with
+//! the same `--txpool.*` arguments as the node, so the printed reports of different
+//! configurations can be compared.
+
+#![warn(unused_crate_dependencies)]
+
+use clap::Parser;
+use reth_ethereum::{
+    node::core::{args::TxPoolArgs, cli::config::RethTransactionPoolConfig},
+    pool::{
+        maintain::MaintainPoolConfig,
+        test_utils::{read_trace, PoolReplay},
+    },
+};
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+/// Replays a recorded mempool trace against a transaction pool.
+#[derive(Debug, Parser)]
+struct Args {
+    /// Path to the trace, in JSON lines format.
+    #[arg(long, value_name = "PATH")]
+    trace: PathBuf,
+
+    #[command(flatten)]
+    txpool: TxPoolArgs,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let args = Args::parse();
+    let events = read_trace(BufReader::new(File::open(&args.trace)?))?;
+
+    let pool_config = args.txpool.pool_config();
+    let maintain_config = MaintainPoolConfig {
+        max_tx_lifetime: pool_config.max_queued_lifetime,
+        no_local_exemptions: pool_config.local_transactions_config.no_exemptions,
+        ..Default::default()
+    };
+    let report =
+        PoolReplay::new(pool_config).with_maintain_config(maintain_config).replay(events).await;
+
+    println!("{report}");
+    Ok(())
+}